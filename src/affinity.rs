@@ -0,0 +1,153 @@
+//! CPU core sets for isolating the benchmark client and the datastore containers on one host.
+//!
+//! `--pin-cores` restricts every thread of the benchmark, the tokio workers and the blocking
+//! threadpool alike, to a set of cores, and `--container-cores` restricts the Docker containers
+//! to another, so the client and the datastore do not compete for the same cores. On machines
+//! with several sockets, `--numa-node` instead pins the benchmark to the cores of one NUMA node
+//! and binds its memory to that node, so embedded engines do not bounce across sockets.
+
+use anyhow::{Result, bail};
+use std::fmt::{Display, Formatter};
+
+/// A set of CPU cores, given as a comma-separated list of cores and ranges, such as `0-15` or `0,2,4-7`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CoreSet {
+	/// The set as given on the command line, which Docker's `--cpuset-cpus` accepts as is.
+	spec: String,
+	/// The cores in the set, in ascending order.
+	cores: Vec<usize>,
+}
+
+impl CoreSet {
+	/// Parses a comma-separated list of cores and inclusive core ranges.
+	pub(crate) fn parse(spec: &str) -> Result<Self, String> {
+		let mut cores = Vec::new();
+		for part in spec.split(',').map(str::trim) {
+			let core =
+				|v: &str| v.parse::<usize>().map_err(|_| format!("Invalid core `{v}` in `{spec}`"));
+			match part.split_once('-') {
+				Some((start, end)) => {
+					let (start, end) = (core(start)?, core(end)?);
+					if start > end {
+						return Err(format!("The core range `{part}` must not be descending"));
+					}
+					cores.extend(start..=end);
+				}
+				None => cores.push(core(part)?),
+			}
+		}
+		cores.sort_unstable();
+		cores.dedup();
+		Ok(Self {
+			spec: spec.to_string(),
+			cores,
+		})
+	}
+
+	/// The cores of a NUMA node, as listed by the kernel.
+	pub(crate) fn numa_node(node: u32) -> Result<Self> {
+		let path = format!("/sys/devices/system/node/node{node}/cpulist");
+		match std::fs::read_to_string(&path) {
+			Ok(list) => Self::parse(list.trim()).map_err(anyhow::Error::msg),
+			Err(e) => bail!("Failed to read the cores of NUMA node {node} from {path}: {e}"),
+		}
+	}
+
+	/// Whether any core is in both sets.
+	pub(crate) fn overlaps(&self, other: &CoreSet) -> bool {
+		self.cores.iter().any(|c| other.cores.binary_search(c).is_ok())
+	}
+}
+
+impl Display for CoreSet {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.spec)
+	}
+}
+
+/// Restricts the current thread, and every thread it starts from now on, to the given cores.
+#[cfg(target_os = "linux")]
+pub(crate) fn pin(cores: &CoreSet) -> Result<()> {
+	// SAFETY: the set is zeroed before use, and only cores below `CPU_SETSIZE` are added to it
+	unsafe {
+		let mut set: libc::cpu_set_t = std::mem::zeroed();
+		libc::CPU_ZERO(&mut set);
+		for &core in &cores.cores {
+			if core >= libc::CPU_SETSIZE as usize {
+				bail!(
+					"The core {core} is beyond the {} cores which can be pinned",
+					libc::CPU_SETSIZE
+				);
+			}
+			libc::CPU_SET(core, &mut set);
+		}
+		if libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &set) != 0 {
+			bail!("Failed to pin to the cores {cores}: {}", std::io::Error::last_os_error());
+		}
+	}
+	Ok(())
+}
+
+/// Restricts the current thread, and every thread it starts from now on, to the given cores.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn pin(_cores: &CoreSet) -> Result<()> {
+	bail!("Pinning the benchmark to cores is only supported on Linux")
+}
+
+/// Binds the memory the current thread, and every thread it starts from now on, allocates to a NUMA node.
+#[cfg(target_os = "linux")]
+pub(crate) fn bind_memory(node: u32) -> Result<()> {
+	// The memory policy which only allocates from the nodes in the mask
+	const MPOL_BIND: libc::c_long = 2;
+	// The mask of nodes, which is large enough for any node the kernel supports
+	let mut mask = [0u64; 16];
+	let Some(word) = mask.get_mut(node as usize / 64) else {
+		bail!("The NUMA node {node} is beyond the nodes which can be bound");
+	};
+	*word |= 1 << (node % 64);
+	// SAFETY: the mask outlives the call, and the maximum node given is within the mask
+	let res = unsafe {
+		libc::syscall(
+			libc::SYS_set_mempolicy,
+			MPOL_BIND,
+			mask.as_ptr(),
+			(mask.len() * 64) as libc::c_ulong,
+		)
+	};
+	if res != 0 {
+		bail!("Failed to bind memory to NUMA node {node}: {}", std::io::Error::last_os_error());
+	}
+	Ok(())
+}
+
+/// Binds the memory the current thread, and every thread it starts from now on, allocates to a NUMA node.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn bind_memory(_node: u32) -> Result<()> {
+	bail!("Binding the benchmark to a NUMA node is only supported on Linux")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_cores_and_ranges() {
+		let set = CoreSet::parse("4-7,0, 2,5").unwrap();
+		assert_eq!(set.cores, vec![0, 2, 4, 5, 6, 7]);
+		assert_eq!(set.to_string(), "4-7,0, 2,5");
+	}
+
+	#[test]
+	fn rejects_invalid_sets() {
+		assert!(CoreSet::parse("").is_err());
+		assert!(CoreSet::parse("7-4").is_err());
+		assert!(CoreSet::parse("a-b").is_err());
+	}
+
+	#[test]
+	fn detects_overlapping_sets() {
+		let client = CoreSet::parse("0-15").unwrap();
+		assert!(!client.overlaps(&CoreSet::parse("16-31").unwrap()));
+		assert!(client.overlaps(&CoreSet::parse("15-31").unwrap()));
+	}
+}
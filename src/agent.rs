@@ -0,0 +1,348 @@
+//! `crud-bench agent` and `crud-bench coordinate`: drive the load of one benchmark from several
+//! machines.
+//!
+//! One client machine cannot saturate a large datastore server, so the load can be generated by
+//! several agents instead. Each machine runs `crud-bench agent`, and `crud-bench coordinate` sends
+//! every agent the same benchmark options with its [`Shard`] of the samples. The agents run the
+//! phases in lockstep: each waits at the start of every phase until all the agents have reached
+//! it, so the phases overlap in time and their throughput adds up. Only the first agent sets up
+//! and tears down the datastore. The coordinator then collects the result of every agent, and
+//! merges their latency histograms.
+//!
+//! The coordinator and the agents exchange one JSON [`Message`] per line over TCP.
+
+use crate::compare::ResultFile;
+use crate::histograms::{self, HistogramsArgs};
+use crate::{Args, config, rerun};
+use anyhow::{Context, Result, bail};
+use clap::{CommandFactory, FromArgMatches};
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, CellAlignment, Color, ContentArrangement, Table};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Mutex, PoisonError};
+use tracing::{error, info};
+
+/// Command-line interface for `crud-bench agent`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct AgentArgs {
+	/// The address to wait for the coordinator on
+	#[arg(long, default_value = "0.0.0.0:7878")]
+	pub(crate) listen: String,
+}
+
+/// Command-line interface for `crud-bench coordinate`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct CoordinateArgs {
+	/// Comma-separated addresses of the agents which generate the load
+	#[arg(long, required = true, value_delimiter = ',')]
+	pub(crate) agents: Vec<String>,
+
+	/// Benchmark options, including `--database` and `--endpoint`, which every agent runs
+	#[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+	pub(crate) args: Vec<OsString>,
+}
+
+/// The share of the samples of every phase which one agent runs: every sample whose index
+/// leaves `index` when divided by `count`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Shard {
+	/// The position of the agent, from zero.
+	pub(crate) index: u32,
+	/// The number of agents.
+	pub(crate) count: u32,
+}
+
+impl Shard {
+	/// Parses a shard given as `index/count`, such as `0/4`.
+	pub(crate) fn parse(value: &str) -> Result<Self, String> {
+		let invalid =
+			|| format!("The shard `{value}` must be given as `index/count`, such as `0/4`");
+		let (index, count) = value.split_once('/').ok_or_else(invalid)?;
+		let index = index.trim().parse().map_err(|_| invalid())?;
+		let count = count.trim().parse().map_err(|_| invalid())?;
+		match index < count {
+			true => Ok(Self {
+				index,
+				count,
+			}),
+			false => Err(format!("The shard index must be below the shard count in `{value}`")),
+		}
+	}
+
+	/// Whether the sample is run by this shard.
+	pub(crate) fn owns(&self, sample: u32) -> bool {
+		sample % self.count == self.index
+	}
+
+	/// How many of the first `samples` samples this shard runs.
+	pub(crate) fn samples(&self, samples: u32) -> u32 {
+		samples / self.count + u32::from(samples % self.count > self.index)
+	}
+
+	/// Whether this is the shard which sets up and tears down the datastore.
+	pub(crate) fn is_first(&self) -> bool {
+		self.index == 0
+	}
+}
+
+impl Display for Shard {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}/{}", self.index, self.count)
+	}
+}
+
+/// A message between the coordinator and an agent.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Message {
+	/// The benchmark command line an agent runs, and its shard of the samples.
+	Job {
+		shard: Shard,
+		args: Vec<String>,
+	},
+	/// The agent reached the start of a phase.
+	Ready(String),
+	/// Every agent reached the phase, so it starts.
+	Go,
+	/// The result JSON of the agent's benchmark.
+	Result(Value),
+	/// The agent's benchmark failed.
+	Failed(String),
+}
+
+/// One end of the connection between the coordinator and an agent.
+struct Link {
+	reader: BufReader<TcpStream>,
+	writer: TcpStream,
+}
+
+impl Link {
+	fn new(stream: TcpStream) -> Result<Self> {
+		Ok(Self {
+			reader: BufReader::new(stream.try_clone()?),
+			writer: stream,
+		})
+	}
+
+	fn send(&mut self, message: &Message) -> Result<()> {
+		let mut line = serde_json::to_vec(message)?;
+		line.push(b'\n');
+		self.writer.write_all(&line)?;
+		Ok(())
+	}
+
+	fn receive(&mut self) -> Result<Message> {
+		let mut line = String::new();
+		if self.reader.read_line(&mut line)? == 0 {
+			bail!("The connection was closed");
+		}
+		Ok(serde_json::from_str(&line)?)
+	}
+}
+
+/// The connection of this agent to the coordinator, while it runs a job.
+static COORDINATOR: Mutex<Option<Link>> = Mutex::new(None);
+
+/// Waits until every agent has reached the start of the phase. Outside a coordinated run, the
+/// phase starts straight away.
+pub(crate) async fn barrier(phase: &str) -> Result<()> {
+	let phase = phase.to_string();
+	tokio::task::spawn_blocking(move || {
+		let mut coordinator = COORDINATOR.lock().unwrap_or_else(PoisonError::into_inner);
+		let Some(link) = coordinator.as_mut() else {
+			return Ok(());
+		};
+		link.send(&Message::Ready(phase.clone()))?;
+		match link.receive().context("Lost the connection to the coordinator")? {
+			Message::Go => Ok(()),
+			other => bail!("Expected the coordinator to start the {phase}, but received {other:?}"),
+		}
+	})
+	.await?
+}
+
+/// The result file an agent writes for its shard.
+fn result_file(shard: &Shard) -> String {
+	format!("result-agent-{}.json", shard.index)
+}
+
+/// Waits for coordinators, and runs the benchmark of each in turn.
+pub(crate) fn run(args: AgentArgs) -> Result<()> {
+	let listener = TcpListener::bind(&args.listen)
+		.with_context(|| format!("Failed to listen on {}", args.listen))?;
+	println!("Agent waiting for the coordinator on {}", args.listen);
+	for stream in listener.incoming() {
+		let stream = stream?;
+		info!(coordinator = %stream.peer_addr()?, "Coordinator connected");
+		if let Err(e) = serve(stream) {
+			error!("{e:#}");
+		}
+	}
+	Ok(())
+}
+
+/// Runs the job of one coordinator, and sends back its result.
+fn serve(stream: TcpStream) -> Result<()> {
+	let mut link = Link::new(stream)?;
+	let Message::Job {
+		shard,
+		mut args,
+	} = link.receive()?
+	else {
+		bail!("Expected a job from the coordinator");
+	};
+	println!("Running shard {shard} for the coordinator");
+	// Write the result of this shard apart from those of other runs
+	args.extend(["--shard".to_string(), shard.to_string()]);
+	args.extend(["--name".to_string(), format!("agent-{}", shard.index)]);
+	*COORDINATOR.lock().unwrap_or_else(PoisonError::into_inner) = Some(link);
+	let outcome = benchmark(args).and_then(|_| {
+		let text = std::fs::read_to_string(result_file(&shard))?;
+		Ok(serde_json::from_str(&text)?)
+	});
+	let Some(mut link) = COORDINATOR.lock().unwrap_or_else(PoisonError::into_inner).take() else {
+		bail!("Lost the connection to the coordinator");
+	};
+	match outcome {
+		Ok(result) => link.send(&Message::Result(result)),
+		Err(e) => link.send(&Message::Failed(format!("{e:#}"))),
+	}
+}
+
+/// Runs the benchmark of a job, reporting invalid options as an error rather than exiting.
+fn benchmark(args: Vec<String>) -> Result<()> {
+	let command_line = config::command_line_from(args.into_iter().map(OsString::from).collect())?;
+	let matches = Args::command().try_get_matches_from(command_line)?;
+	let mut args = Args::from_arg_matches(&matches)?;
+	args.run_options = rerun::run_options(&matches);
+	args.effective_options = rerun::effective_options(&matches);
+	crate::run(args)
+}
+
+/// Runs the benchmark on every agent, keeping their phases in step, then merges their results.
+pub(crate) fn coordinate(args: CoordinateArgs) -> Result<()> {
+	let count = args.agents.len() as u32;
+	let mut command_line = vec!["crud-bench".to_string()];
+	command_line.extend(args.args.iter().map(|a| a.to_string_lossy().into_owned()));
+	// Send every agent its shard of the benchmark
+	let mut links = Vec::with_capacity(args.agents.len());
+	for (index, agent) in (0..).zip(&args.agents) {
+		let stream = TcpStream::connect(agent)
+			.with_context(|| format!("Failed to connect to the agent on {agent}"))?;
+		let mut link = Link::new(stream)?;
+		link.send(&Message::Job {
+			shard: Shard {
+				index,
+				count,
+			},
+			args: command_line.clone(),
+		})?;
+		links.push(link);
+	}
+	println!("Running the benchmark on {count} agents");
+	// Start each phase once every agent has reached it, until they have all finished
+	let files = loop {
+		let mut messages = Vec::with_capacity(links.len());
+		for (link, agent) in links.iter_mut().zip(&args.agents) {
+			match link.receive().with_context(|| format!("Lost the agent on {agent}"))? {
+				Message::Failed(e) => bail!("The benchmark failed on the agent on {agent}: {e}"),
+				message => messages.push(message),
+			}
+		}
+		match &messages[..] {
+			[Message::Ready(phase), rest @ ..]
+				if rest.iter().all(|m| matches!(m, Message::Ready(p) if p == phase)) =>
+			{
+				info!(phase, "Phase starting on every agent");
+				for link in &mut links {
+					link.send(&Message::Go)?;
+				}
+			}
+			_ if messages.iter().all(|m| matches!(m, Message::Result(_))) => {
+				let mut files = Vec::with_capacity(messages.len());
+				for (index, message) in (0..).zip(messages) {
+					let Message::Result(result) = message else {
+						continue;
+					};
+					let file = result_file(&Shard {
+						index,
+						count,
+					});
+					std::fs::write(&file, serde_json::to_string_pretty(&result)?)?;
+					files.push(file);
+				}
+				break files;
+			}
+			_ => bail!("The agents diverged: {messages:?}"),
+		}
+	};
+	// Add up the throughput of the agents, and merge their latencies
+	println!("{}", throughput(&files)?);
+	histograms::run(HistogramsArgs {
+		files,
+		percentiles: vec![50.0, 90.0, 99.0, 99.9, 99.99],
+		merge: true,
+	})
+}
+
+/// Builds the table of the throughput of each operation row, summed over the agents.
+fn throughput(files: &[String]) -> Result<Table> {
+	let mut rows: Vec<(String, f64)> = Vec::new();
+	for path in files {
+		for (label, phase) in ResultFile::load(path)?.rows() {
+			let ops = phase.map(|p| p.ops).unwrap_or(0.0);
+			match rows.iter_mut().find(|(l, _)| *l == label) {
+				Some((_, total)) => *total += ops,
+				None => rows.push((label, ops)),
+			}
+		}
+	}
+	let mut table = Table::new();
+	table
+		.load_preset(UTF8_FULL)
+		.apply_modifier(UTF8_ROUND_CORNERS)
+		.set_content_arrangement(ContentArrangement::Dynamic)
+		.set_header(
+			["Test", "Total OPS"]
+				.into_iter()
+				.map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Blue)),
+		);
+	for (label, ops) in rows {
+		table.add_row([Cell::new(label), Cell::new(format!("{ops:.2}"))]);
+	}
+	if let Some(column) = table.column_mut(1) {
+		column.set_cell_alignment(CellAlignment::Right);
+	}
+	Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn shards_split_the_samples_between_them() {
+		let shards: Vec<Shard> = (0..3).map(|i| Shard::parse(&format!("{i}/3")).unwrap()).collect();
+		for sample in 0..10 {
+			assert_eq!(shards.iter().filter(|s| s.owns(sample)).count(), 1);
+		}
+		let samples: Vec<u32> = shards.iter().map(|s| s.samples(10)).collect();
+		assert_eq!(samples, vec![4, 3, 3]);
+		assert_eq!(shards[1].to_string(), "1/3");
+	}
+
+	#[test]
+	fn rejects_invalid_shards() {
+		assert!(Shard::parse("3/3").is_err());
+		assert!(Shard::parse("1").is_err());
+		assert!(Shard::parse("a/b").is_err());
+	}
+}
@@ -1,23 +1,25 @@
 #![cfg(feature = "arangodb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::dialect::ArangoDBDialect;
 use crate::docker::DockerParams;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
 use crate::memory::Config;
+use crate::security::Security;
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
 use anyhow::{Result, bail};
 use arangors::aql::AqlQuery;
 use arangors::client::reqwest::ReqwestClient;
 use arangors::document::Document;
 use arangors::document::options::InsertOptions;
 use arangors::document::options::RemoveOptions;
+use arangors::index::{Index as ArangoIndex, IndexSettings};
 use arangors::{Collection, Connection, Database, GenericConnection};
 use serde_json::{Value, json};
 use std::hint::black_box;
-use std::time::Duration;
 use tokio::sync::Mutex;
 
 pub const DEFAULT: &str = "http://127.0.0.1:8529";
@@ -35,7 +37,7 @@ pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 				// I/O threads handle network reads/writes; the default of 1
 				// causes the kernel SYN queue to fill under heavy parallel
 				// HTTP load, producing ETIMEDOUT errors on the client side.
-				let io_threads = num_cpus::get().clamp(2, 8);
+				let io_threads = options.container_cores().clamp(2, 8);
 				format!(
 					"--server.io-threads {io_threads} \
 					 --server.scheduler-queue-size 8192 \
@@ -59,20 +61,24 @@ pub(crate) struct ArangoDBClientProvider {
 	sync: bool,
 	key: KeyType,
 	url: String,
+	security: Security,
 }
 
 impl BenchmarkEngine<ArangoDBClient> for ArangoDBClientProvider {
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
+		// TLS is chosen by the `https://` scheme of the endpoint
+		options.security.without_tls_options("ArangoDB")?;
 		Ok(Self {
 			sync: options.sync,
 			key: kt,
 			url: options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned(),
+			security: options.security.clone(),
 		})
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<ArangoDBClient> {
-		let (conn, db, co) = create_arango_client(&self.url).await?;
+		let (conn, db, co) = create_arango_client(&self.url, &self.security).await?;
 		// Wrap the database/collection handles in `Mutex` per client. The
 		// lock isn't there to protect state — both handles are `Clone` and
 		// thread-safe — but to serialise the `-t` worker tasks per client
@@ -88,10 +94,6 @@ impl BenchmarkEngine<ArangoDBClient> for ArangoDBClientProvider {
 			collection: Mutex::new(co),
 		})
 	}
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		Some(Duration::from_secs(15))
-	}
 }
 
 pub(crate) struct ArangoDBClient {
@@ -104,10 +106,19 @@ pub(crate) struct ArangoDBClient {
 
 async fn create_arango_client(
 	url: &str,
+	security: &Security,
 ) -> Result<(GenericConnection<ReqwestClient>, Database<ReqwestClient>, Collection<ReqwestClient>)>
 {
 	// Create the connection to the database
-	let conn = Connection::establish_without_auth(url).await.unwrap();
+	// The Docker container runs without authentication
+	let conn = match security.has_credentials() {
+		true => {
+			let user = security.username("root");
+			let pass = security.password("");
+			Connection::establish_basic_auth(url, &user, &pass).await?
+		}
+		false => Connection::establish_without_auth(url).await.unwrap(),
+	};
 	// Create the benchmarking database
 	let db = match conn.create_database("crud-bench").await {
 		Err(_) => conn.db("crud-bench").await.unwrap(),
@@ -138,80 +149,114 @@ impl BenchmarkClient for ArangoDBClient {
 
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.create(key.to_string(), val).await,
 		}
 	}
 
 	async fn create_string(&self, key: String, val: BenchValue) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.create(key, val).await,
 		}
 	}
 
 	async fn read_u32(&self, key: u32) -> Result<BenchValue> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.read(key.to_string()).await,
 		}
 	}
 
 	async fn read_string(&self, key: String) -> Result<BenchValue> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.read(key).await,
 		}
 	}
 
 	async fn update_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.update(key.to_string(), val).await,
 		}
 	}
 
 	async fn update_string(&self, key: String, val: BenchValue) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.update(key, val).await,
 		}
 	}
 
 	async fn delete_u32(&self, key: u32) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.delete(key.to_string()).await,
 		}
 	}
 
 	async fn delete_string(&self, key: String) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.delete(key).await,
 		}
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
-			_ => self.scan(scan).await,
+			KeyType::String506 => bail!(BenchError::Unsupported),
+			_ => self.scan(scan, ctx).await,
 		}
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
-			_ => self.scan(scan).await,
+			KeyType::String506 => bail!(BenchError::Unsupported),
+			_ => self.scan(scan, ctx).await,
 		}
 	}
 
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// ArangoDB only skips documents missing the attribute (sparse), not arbitrary predicates
+		if spec.filter.is_some() {
+			bail!(BenchError::Unsupported);
+		}
+		// Array elements are indexed with the `[*]` expansion
+		let fields: Vec<String> = spec.fields.iter().map(|f| f.replace(".*", "[*]")).collect();
+		// Check if an index type is specified
+		let settings = match spec.index_type.as_deref() {
+			// Full-text indexes cover a single attribute
+			Some("fulltext") if fields.len() == 1 => IndexSettings::Fulltext {
+				min_length: 1,
+			},
+			Some(_) => bail!(BenchError::Unsupported),
+			None => IndexSettings::Persistent {
+				unique: spec.unique.unwrap_or(false),
+				sparse: false,
+				deduplicate: false,
+			},
+		};
+		// Define the index
+		let index =
+			ArangoIndex::builder().name(name.to_string()).fields(fields).settings(settings).build();
+		// Create the index, which is built before the request returns
+		self.database.lock().await.create_index("record", &index).await?;
+		// All ok
+		Ok(())
+	}
+
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		self.database.lock().await.delete_index(&format!("record/{name}")).await?;
+		Ok(())
+	}
+
 	async fn batch_create_u32(
 		&self,
 		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
 	) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => {
 				let pairs = key_vals.map(|(k, v)| (k.to_string(), v)).collect::<Vec<_>>();
 				self.batch_create_pairs(pairs).await
@@ -224,14 +269,14 @@ impl BenchmarkClient for ArangoDBClient {
 		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
 	) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.batch_create_pairs(key_vals.collect()).await,
 		}
 	}
 
 	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => {
 				let ks = keys.map(|k| k.to_string()).collect::<Vec<_>>();
 				self.batch_read_keys(ks).await
@@ -241,7 +286,7 @@ impl BenchmarkClient for ArangoDBClient {
 
 	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.batch_read_keys(keys.collect()).await,
 		}
 	}
@@ -251,7 +296,7 @@ impl BenchmarkClient for ArangoDBClient {
 		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
 	) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => {
 				let pairs = key_vals.map(|(k, v)| (k.to_string(), v)).collect::<Vec<_>>();
 				self.batch_update_pairs(pairs).await
@@ -264,14 +309,14 @@ impl BenchmarkClient for ArangoDBClient {
 		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
 	) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.batch_update_pairs(key_vals.collect()).await,
 		}
 	}
 
 	async fn batch_delete_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => {
 				let ks = keys.map(|k| k.to_string()).collect::<Vec<_>>();
 				self.batch_delete_keys(ks).await
@@ -281,7 +326,7 @@ impl BenchmarkClient for ArangoDBClient {
 
 	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
 		match self.keytype {
-			KeyType::String506 => bail!(NOT_SUPPORTED_ERROR),
+			KeyType::String506 => bail!(BenchError::Unsupported),
 			_ => self.batch_delete_keys(keys.collect()).await,
 		}
 	}
@@ -315,6 +360,9 @@ impl ArangoDBClient {
 			.build();
 		let res: Vec<Value> = self.database.lock().await.aql_query(aql).await?;
 		assert!(!res.is_empty());
+		for v in res {
+			materialize::consume(v, |v| Ok(v.into()))?;
+		}
 		Ok(())
 	}
 
@@ -394,7 +442,7 @@ impl ArangoDBClient {
 		Ok(())
 	}
 
-	async fn scan(&self, scan: &Scan) -> Result<usize> {
+	async fn scan(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
 		// Extract parameters
 		let l = match (scan.start, scan.limit) {
 			(Some(s), Some(l)) => format!("LIMIT {s}, {l}"),
@@ -404,11 +452,12 @@ impl ArangoDBClient {
 		};
 		let c = ArangoDBDialect::filter_clause(scan)?;
 		let o = ArangoDBDialect::sort_clause(scan)?;
+		let h = ArangoDBDialect::hint_clause(scan, ctx)?;
 		let p = scan.projection()?;
 		// Perform the relevant projection scan type
 		match p {
 			Projection::Id => {
-				let stm = format!("FOR r IN record {c} {o} {l} RETURN {{ _id: r._id }}");
+				let stm = format!("FOR r IN record {h} {c} {o} {l} RETURN {{ _id: r._id }}");
 				let res: Vec<Value> = { self.database.lock().await.aql_str(&stm).await.unwrap() };
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
@@ -422,7 +471,43 @@ impl ArangoDBClient {
 				Ok(count)
 			}
 			Projection::Full => {
-				let stm = format!("FOR r IN record {c} {o} {l} RETURN r");
+				let stm = format!("FOR r IN record {h} {c} {o} {l} RETURN r");
+				let res: Vec<Value> = { self.database.lock().await.aql_str(&stm).await.unwrap() };
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					materialize::consume(v, |v| Ok(v.into()))?;
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Field(path) => {
+				let stm = format!(
+					"FOR r IN record {h} {c} {o} {l} RETURN {{ _id: r._id, field: r.{path} }}"
+				);
+				let res: Vec<Value> = { self.database.lock().await.aql_str(&stm).await.unwrap() };
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					black_box(v);
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Fields(paths) => {
+				let f = paths
+					.iter()
+					.enumerate()
+					.map(|(i, p)| format!("field_{i}: r.{p}"))
+					.collect::<Vec<_>>()
+					.join(", ");
+				let stm = format!("FOR r IN record {h} {c} {o} {l} RETURN {{ _id: r._id, {f} }}");
 				let res: Vec<Value> = { self.database.lock().await.aql_str(&stm).await.unwrap() };
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
@@ -436,8 +521,9 @@ impl ArangoDBClient {
 				Ok(count)
 			}
 			Projection::Count => {
-				let stm =
-					format!("FOR r IN record {c} {l} COLLECT WITH COUNT INTO count RETURN count");
+				let stm = format!(
+					"FOR r IN record {h} {c} {l} COLLECT WITH COUNT INTO count RETURN count"
+				);
 				let res: Vec<Value> = { self.database.lock().await.aql_str(&stm).await.unwrap() };
 				let count = res.first().unwrap().as_i64().unwrap();
 				Ok(count as usize)
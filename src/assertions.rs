@@ -0,0 +1,315 @@
+//! SLA assertions declared in the benchmark definition, checked against the results of the run.
+//!
+//! The `assertions` array of the definition holds conditions such as `reads.p99 < 5ms` or
+//! `creates.ops > 50000`: a phase, one of its metrics, a comparison, and a bound. Latency bounds
+//! take a `ns`, `us`, `ms`, or `s` unit, while throughput bounds are plain operations per second.
+//! Each assertion is printed as passed or failed below the results, and the benchmark exits with
+//! an error when any fails, so a CI pipeline can gate a release without parsing the JSON results.
+
+use crate::result::{BenchmarkResult, OperationResult};
+use anyhow::{Result, bail, ensure};
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+/// A condition which a metric of one phase must meet, such as `reads.p99 < 5ms`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) struct Assertion {
+	/// The assertion as written in the definition
+	text: String,
+	/// The phase whose result is checked
+	phase: Phase,
+	/// The metric of the phase which is compared
+	metric: Metric,
+	/// How the metric is compared with the bound
+	comparison: Comparison,
+	/// The bound, in microseconds for latencies and operations per second for throughput
+	bound: f64,
+	/// The unit the bound was written in, which the measured value is shown in
+	unit: Unit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Phase {
+	Creates,
+	Reads,
+	RestrictedReads,
+	Updates,
+	PathUpdates,
+	Deletes,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Metric {
+	Ops,
+	Mean,
+	Min,
+	Max,
+	P99,
+	P95,
+	P75,
+	P50,
+	P25,
+	P01,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+	Less,
+	LessOrEqual,
+	Greater,
+	GreaterOrEqual,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Unit {
+	Ops,
+	Nanos,
+	Micros,
+	Millis,
+	Seconds,
+}
+
+impl Phase {
+	fn parse(name: &str) -> Result<Self> {
+		Ok(match name {
+			"creates" => Self::Creates,
+			"reads" => Self::Reads,
+			"restricted_reads" => Self::RestrictedReads,
+			"updates" => Self::Updates,
+			"path_updates" => Self::PathUpdates,
+			"deletes" => Self::Deletes,
+			_ => bail!(
+				"Unknown phase `{name}`, expected creates, reads, restricted_reads, updates, path_updates, or deletes"
+			),
+		})
+	}
+
+	/// The result of the phase, when it ran.
+	fn result(self, res: &BenchmarkResult) -> Option<&OperationResult> {
+		match self {
+			Self::Creates => res.creates.as_ref(),
+			Self::Reads => res.reads.as_ref(),
+			Self::RestrictedReads => res.restricted_reads.as_ref(),
+			Self::Updates => res.updates.as_ref(),
+			Self::PathUpdates => res.path_updates.as_ref(),
+			Self::Deletes => res.deletes.as_ref(),
+		}
+	}
+}
+
+impl Metric {
+	fn parse(name: &str) -> Result<Self> {
+		Ok(match name {
+			"ops" => Self::Ops,
+			"mean" => Self::Mean,
+			"min" => Self::Min,
+			"max" => Self::Max,
+			"p99" => Self::P99,
+			"p95" => Self::P95,
+			"p75" => Self::P75,
+			"p50" => Self::P50,
+			"p25" => Self::P25,
+			"p01" => Self::P01,
+			_ => bail!(
+				"Unknown metric `{name}`, expected ops, mean, min, max, p99, p95, p75, p50, p25, or p01"
+			),
+		})
+	}
+
+	/// The metric of a phase, in microseconds for latencies and operations per second for `ops`.
+	fn value(self, res: &OperationResult) -> f64 {
+		match self {
+			Self::Ops => res.ops(),
+			Self::Mean => res.mean(),
+			Self::Min => res.min() as f64,
+			Self::Max => res.max() as f64,
+			Self::P99 => res.q99() as f64,
+			Self::P95 => res.q95() as f64,
+			Self::P75 => res.q75() as f64,
+			Self::P50 => res.q50() as f64,
+			Self::P25 => res.q25() as f64,
+			Self::P01 => res.q01() as f64,
+		}
+	}
+}
+
+impl Comparison {
+	/// The comparison operators, with the two-character ones first so they are found whole.
+	const OPERATORS: [(&'static str, Self); 4] = [
+		("<=", Self::LessOrEqual),
+		(">=", Self::GreaterOrEqual),
+		("<", Self::Less),
+		(">", Self::Greater),
+	];
+
+	fn holds(self, value: f64, bound: f64) -> bool {
+		match self {
+			Self::Less => value < bound,
+			Self::LessOrEqual => value <= bound,
+			Self::Greater => value > bound,
+			Self::GreaterOrEqual => value >= bound,
+		}
+	}
+}
+
+impl Unit {
+	fn parse(suffix: &str) -> Result<Self> {
+		Ok(match suffix {
+			"" => Self::Ops,
+			"ns" => Self::Nanos,
+			"us" | "µs" => Self::Micros,
+			"ms" => Self::Millis,
+			"s" => Self::Seconds,
+			_ => bail!("Unknown unit `{suffix}`, expected ns, us, ms, or s"),
+		})
+	}
+
+	/// How many microseconds one of the unit is, or one for a plain number.
+	fn micros(self) -> f64 {
+		match self {
+			Self::Ops | Self::Micros => 1.0,
+			Self::Nanos => 0.001,
+			Self::Millis => 1_000.0,
+			Self::Seconds => 1_000_000.0,
+		}
+	}
+
+	fn suffix(self) -> &'static str {
+		match self {
+			Self::Ops => " ops/s",
+			Self::Nanos => "ns",
+			Self::Micros => "µs",
+			Self::Millis => "ms",
+			Self::Seconds => "s",
+		}
+	}
+}
+
+impl TryFrom<String> for Assertion {
+	type Error = anyhow::Error;
+
+	fn try_from(text: String) -> Result<Self> {
+		Self::parse(&text)
+	}
+}
+
+impl Assertion {
+	/// Parses an assertion such as `reads.p99 < 5ms` or `creates.ops >= 50000`.
+	fn parse(text: &str) -> Result<Self> {
+		let Some((at, symbol, comparison)) = Comparison::OPERATORS
+			.iter()
+			.find_map(|&(symbol, comparison)| Some((text.find(symbol)?, symbol, comparison)))
+		else {
+			bail!("The assertion `{text}` must compare with <, <=, >, or >=");
+		};
+		let (path, bound) = (text[..at].trim(), text[at + symbol.len()..].trim());
+		let Some((phase, metric)) = path.split_once('.') else {
+			bail!("The assertion `{text}` must name a `<phase>.<metric>`, such as `reads.p99`");
+		};
+		let (phase, metric) = (Phase::parse(phase)?, Metric::parse(metric)?);
+		let split = bound.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(bound.len());
+		let (number, unit) = bound.split_at(split);
+		let number: f64 = match number.parse() {
+			Ok(number) => number,
+			Err(_) => bail!("The assertion `{text}` must end in a number, such as `5ms`"),
+		};
+		let unit = Unit::parse(unit.trim())?;
+		match metric {
+			Metric::Ops => ensure!(unit == Unit::Ops, "The throughput in `{text}` takes no unit"),
+			_ => {
+				ensure!(unit != Unit::Ops, "The latency in `{text}` needs a ns, us, ms, or s unit")
+			}
+		}
+		Ok(Self {
+			text: text.trim().to_string(),
+			phase,
+			metric,
+			comparison,
+			bound: number * unit.micros(),
+			unit,
+		})
+	}
+
+	/// The measured value of the metric, in the unit of its bound, when the phase ran.
+	fn measured(&self, res: &BenchmarkResult) -> Option<f64> {
+		self.phase.result(res).map(|r| self.metric.value(r))
+	}
+
+	/// Whether the measured value meets the bound. A phase which did not run fails.
+	fn passes(&self, measured: Option<f64>) -> bool {
+		measured.is_some_and(|v| self.comparison.holds(v, self.bound))
+	}
+}
+
+impl Display for Assertion {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.text)
+	}
+}
+
+/// Prints whether each assertion passed, returning how many failed.
+pub(crate) fn check(assertions: &[Assertion], res: &BenchmarkResult) -> usize {
+	let mut failed = 0;
+	for assertion in assertions {
+		let measured = assertion.measured(res);
+		let passed = assertion.passes(measured);
+		let status = if passed {
+			"✅ PASS"
+		} else {
+			failed += 1;
+			"❌ FAIL"
+		};
+		match measured {
+			Some(v) => println!(
+				"{status} {assertion} (measured {:.2}{})",
+				v / assertion.unit.micros(),
+				assertion.unit.suffix()
+			),
+			None => println!("{status} {assertion} (the phase did not run)"),
+		}
+	}
+	failed
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_latency_and_throughput_bounds() -> Result<()> {
+		let p99 = Assertion::parse("reads.p99 < 5ms")?;
+		assert_eq!(
+			(p99.phase, p99.metric, p99.comparison),
+			(Phase::Reads, Metric::P99, Comparison::Less)
+		);
+		assert_eq!(p99.bound, 5_000.0);
+		let ops = Assertion::parse("creates.ops >= 50000")?;
+		assert_eq!(
+			(ops.metric, ops.comparison, ops.bound),
+			(Metric::Ops, Comparison::GreaterOrEqual, 50_000.0)
+		);
+		assert_eq!(Assertion::parse("updates.mean<=250us")?.bound, 250.0);
+		assert_eq!(Assertion::parse("deletes.max > 1.5s")?.bound, 1_500_000.0);
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_malformed_assertions() {
+		assert!(Assertion::parse("reads.p99 = 5ms").is_err());
+		assert!(Assertion::parse("reads < 5ms").is_err());
+		assert!(Assertion::parse("scans.p99 < 5ms").is_err());
+		assert!(Assertion::parse("reads.p90 < 5ms").is_err());
+		assert!(Assertion::parse("reads.p99 < 5").is_err());
+		assert!(Assertion::parse("creates.ops > 50000ms").is_err());
+	}
+
+	#[test]
+	fn phases_which_did_not_run_fail() -> Result<()> {
+		let assertion = Assertion::parse("reads.p99 < 5ms")?;
+		assert!(assertion.passes(Some(4_999.0)));
+		assert!(!assertion.passes(Some(5_000.0)));
+		assert!(!assertion.passes(None));
+		Ok(())
+	}
+}
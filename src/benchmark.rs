@@ -1,40 +1,63 @@
-//! Orchestrates benchmark phases (CRUD, scans, batches) against a [`crate::engine::BenchmarkEngine`].
+//! Orchestrates benchmark phases (CRUD, scans, batches, time series) against a [`crate::engine::BenchmarkEngine`].
 //!
 //! Spawns concurrent clients/threads, records latency histograms, and aggregates
 //! [`crate::result::OperationResult`] values for reporting.
 
+use crate::agent::{self, Shard};
+use crate::cgroup::Cgroup;
+use crate::checkpoint::Checkpoint;
 use crate::dialect::Dialect;
-use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
-use crate::keyprovider::KeyProvider;
+use crate::docker::Container;
+use crate::engine::{BenchmarkClient, BenchmarkEngine, LiveStream, PageStart, ScanContext};
+use crate::error::{BenchError, ErrorCounts, ErrorTimeline, ErrorWindow};
+use crate::keyprovider::{GeneratedKeys, KeyProvider};
+use crate::latencies::{LatencySink, RawLatencies};
+use crate::profiling::PhaseProfile;
 use crate::result::{
-	BenchmarkMetadata, BenchmarkResult, OperationMetric, OperationResult, ScanResult, ScanRun,
-	ScanWorkload, writes_ratio_percent,
+	Backup, BenchmarkMetadata, BenchmarkResult, CACHE_HIT_SPEEDUP, Compaction, DiskFootprint,
+	GrowthStage, LatencySplit, LiveQueries, OperationMetric, OperationResult, PageMode, PageWalk,
+	Queueing, Relations, ReplicationLag, ScanConsistency, ScanResult, ScanRun, ScanWorkload,
+	SecondaryReads, SoakWindow, WorkerLatency, batch_row_label, scan_run_row_label,
+	writes_ratio_percent,
 };
+use crate::retry::{Retries, RetryPolicy};
+use crate::security::Security;
+use crate::serverprofile::ServerProfiler;
 use crate::system::SystemInfo;
 use crate::terminal::BenchUi;
+use crate::timeout::AdaptiveTimeout;
+use crate::timeseries::{TimeSeries, TimeSeriesQuery};
 use crate::util::format_duration;
-use crate::valueprovider::ValueProvider;
-use crate::workloads;
-use crate::{Args, BatchOperation, Batches, Index, Scan, ScanWithWrites, Scans};
+use crate::value::BenchValue;
+use crate::valueprovider::{ValueProvider, unique_value};
+use crate::{
+	Args, BatchOperation, Batches, DeleteStrategy, Index, Maintenance, Phase, Scan, ScanWithWrites,
+	Scans,
+};
+use crate::{soak, verify, workloads};
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use bytesize::ByteSize;
+use futures::channel::oneshot;
 use futures::future::try_join_all;
+use futures::{FutureExt, StreamExt};
 use hdrhistogram::Histogram;
 use indicatif::ProgressBar;
-use log::{debug, info};
+use rand::prelude::SmallRng;
+use rand::{RngExt, SeedableRng};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use tokio::task;
 use tokio::time::Instant;
+use tracing::{Instrument, debug, error, error_span, info, warn};
 
+use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::time::{Duration, SystemTime};
-
-/// Maximum wait when polling until the first datastore client connects.
-const TIMEOUT: Duration = Duration::from_secs(60);
-
-/// Error string returned by adapters to mark an operation as unsupported (skipped, not fatal).
-pub(crate) const NOT_SUPPORTED_ERROR: &str = "NotSupported";
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Shared benchmark settings and UI, built from CLI [`crate::Args`].
 pub(crate) struct Benchmark {
@@ -42,85 +65,428 @@ pub(crate) struct Benchmark {
 	pub(crate) privileged: bool,
 	/// The container image to use
 	pub(crate) image: Option<String>,
+	/// The platform every container runs for, rather than the host's
+	pub(crate) docker_platform: Option<String>,
+	/// The CPU cores each container is limited to
+	pub(crate) container_cpus: Option<f64>,
+	/// The memory (bytes) each container is limited to
+	pub(crate) container_memory: Option<u64>,
+	/// The cores each container is pinned to
+	pub(crate) container_cores: Option<String>,
 	/// The server endpoint to connect to
 	pub(crate) endpoint: Option<String>,
+	/// The credentials and TLS settings to connect to the endpoint with
+	pub(crate) security: Security,
+	/// The multi-container topology the datastore is run as
+	pub(crate) topology: Option<String>,
+	/// The tc/netem rules emulating the network between the clients and containers
+	pub(crate) netem: Option<String>,
 	/// The number of clients to spawn
 	pub(crate) clients: u32,
 	/// The number of threads to spawn
 	pub(crate) threads: u32,
 	/// The number of samples to run
 	pub(crate) samples: u32,
+	/// The number of records bulk loaded beyond the samples before the creates, untimed
+	pub(crate) preload: u32,
+	/// Number of records the reads and updates select from
+	pub(crate) keyspace: u32,
+	/// Whether the records kept by an earlier run are reused, without clearing the datastore
+	pub(crate) no_setup: bool,
+	/// Whether the records are kept after the run, without deleting them or cleaning up
+	pub(crate) keep_data: bool,
+	/// The phases to run, or every phase when empty
+	pub(crate) phases: Vec<Phase>,
+	/// The bulk deletions measured after the deletes
+	pub(crate) delete_strategies: Vec<DeleteStrategy>,
+	/// The number of records removed by each range delete
+	pub(crate) delete_range_size: u32,
+	/// The ids of the scans to run again after the deletes
+	pub(crate) tombstone_scans: Vec<String>,
+	/// The points between the phases to run the datastore maintenance at
+	pub(crate) maintenance: Vec<Maintenance>,
+	/// The number of records in the table during the scans
+	pub(crate) scan_records: Option<u32>,
+	/// The numbers of records to grow the table through, repeating the reads and scans at each
+	pub(crate) growth_sizes: Vec<u32>,
+	/// The number of records in each page when walking the whole table
+	pub(crate) page_size: Option<u32>,
 	/// Pid to monitor
 	pub(crate) pid: Option<u32>,
+	/// The cgroups of the datastore containers, monitored instead of the pid when readable
+	pub(crate) cgroups: Vec<Cgroup>,
+	/// Whether the datastore runs in a container started by crud-bench, whose size is measured
+	pub(crate) containerised: bool,
 	/// Whether to ensure data is synced
 	pub(crate) sync: bool,
 	/// Whether to enable disk persistence
 	pub(crate) persisted: bool,
+	/// Whether Redis-family clients pipeline the commands of their threads
+	pub(crate) pipelined: bool,
+	/// Whether SQL clients prepare their CRUD statements once, rather than on every call
+	pub(crate) prepared: bool,
+	/// Whether embedded key-value datastores emulate secondary indexes on integer fields
+	pub(crate) emulate_indexes: bool,
 	/// Whether to enable optimised configurations
 	pub(crate) optimised: bool,
 	/// Per-operation timeout
 	pub(crate) operation_timeout: Duration,
+	/// Multiple of the warmup p99 latency each phase's timeout tightens to (0 disables)
+	pub(crate) timeout_multiplier: u32,
+	/// Maximum wait when polling until the first datastore client connects
+	pub(crate) connect_timeout: Duration,
+	/// Dotted field path to update in place after the full updates
+	pub(crate) update_path: Option<String>,
+	/// Numbers of writes between sync barriers, one synced update phase each
+	pub(crate) sync_intervals: Vec<u32>,
+	/// Whether to repeat the reads through a restricted role
+	pub(crate) restricted_reads: bool,
+	/// Number of reads by the `unique` field, without and then with an index on it
+	pub(crate) secondary_reads: Option<u32>,
+	/// Number of records each record is linked to, when relations are benchmarked
+	pub(crate) relations: Option<u32>,
+	/// Number of live queries open while the events are written
+	pub(crate) live_queries: Option<u32>,
+	/// Whether to measure how long writes take to be visible on a replica
+	pub(crate) replication_lag: bool,
+	/// Whether to back up and restore the datastore after the updates
+	pub(crate) backup: bool,
+	/// Stream of every sample's latency, when `--raw-latencies` is set
+	pub(crate) raw_latencies: Option<RawLatencies>,
+	/// Number of reads to sample with engine-reported execution times
+	pub(crate) server_timings: Option<u32>,
+	/// Whether to repeat filtered scans with perturbed predicates
+	pub(crate) detect_scan_cache: bool,
+	/// Whether to run the scans against a snapshot of the table
+	pub(crate) isolate_scans: bool,
+	/// Whether to check the ids returned by repeated scans are stable
+	pub(crate) check_scan_consistency: bool,
+	/// Number of updates to run alongside each scan index build
+	pub(crate) index_build_writes: Option<u32>,
+	/// How long to loop the mixed reads and updates, when `--soak` is set
+	pub(crate) soak: Option<Duration>,
+	/// Length of each soak window
+	pub(crate) soak_interval: Duration,
+	/// Whether to record the latency of every client and thread in each phase
+	pub(crate) worker_histograms: bool,
+	/// Path, without the extension, of the soak windows flushed while the soak runs
+	pub(crate) soak_output: String,
+	/// Whether to read back every record after the creates and updates, checking its value
+	pub(crate) verify: bool,
+	/// Results of the completed phases, when `--checkpoint` is set
+	pub(crate) checkpoint: Option<Checkpoint>,
+	/// The shard of the samples this agent runs, in a coordinated run
+	pub(crate) shard: Option<Shard>,
 	/// Terminal UI (tables, progress bars, phase markers).
 	pub(crate) bench_ui: BenchUi,
+	/// How the operations which fail with a transient error are retried, from the benchmark TOML
+	pub(crate) retries: Retries,
+	/// Count the failed operations of each phase and carry on, rather than failing the phase
+	pub(crate) continue_on_error: bool,
+	/// Samples of the reads, spread across or cycling through the keyspace when not its size
+	pub(crate) read_samples: u32,
+	/// Samples of the updates, spread across or cycling through the keyspace when not its size
+	pub(crate) update_samples: u32,
+	/// Mean arrivals per second of the samples of the measured phases, run open-loop when set
+	pub(crate) arrival_rate: Option<f64>,
+	/// Seeds the gaps between the open-loop arrivals with `--seed`, so they are the same in every run
+	pub(crate) seed: Option<u64>,
+	/// The perf recordings of the datastore container, when `--profile-server` is set
+	pub(crate) server_profiler: Option<ServerProfiler>,
 	/// Grep-friendly `… starting` / `Benchmark starting` lines for profiling scripts
 	pub(crate) emit_phase_markers: bool,
 }
 
 impl Benchmark {
 	/// Builds runtime settings from parsed CLI arguments (including env-driven phase markers).
-	pub(crate) fn new(args: &Args) -> Self {
+	pub(crate) fn new(args: &Args) -> Result<Self> {
 		let emit_phase_markers = args.emit_phase_markers
 			|| matches!(
 				std::env::var("CRUD_BENCH_EMIT_PHASE_MARKERS").as_deref(),
 				Ok("1" | "true" | "yes" | "on")
 			);
-		Self {
+		Ok(Self {
 			privileged: args.privileged,
 			image: args.image.to_owned(),
+			docker_platform: args.docker_platform.to_owned(),
+			container_cpus: args.container_cpus,
+			container_memory: args.container_memory.map(|m| m.as_u64()),
+			container_cores: args.container_cores.as_ref().map(ToString::to_string),
 			endpoint: args.endpoint.to_owned(),
+			security: Security {
+				username: args.username.to_owned(),
+				password: args.password.to_owned(),
+				tls_ca: args.tls_ca.to_owned(),
+				tls_insecure: args.tls_insecure,
+			},
+			topology: args.topology.to_owned(),
+			netem: args.netem.to_owned(),
 			clients: args.clients,
 			threads: args.threads,
 			samples: args.samples,
+			preload: args.preloaded(),
+			keyspace: args.keyspace.unwrap_or(args.samples),
+			no_setup: args.no_setup,
+			keep_data: args.keep_data,
+			phases: args.phases.to_owned(),
+			delete_strategies: args.delete_strategies.to_owned(),
+			delete_range_size: args.delete_range_size,
+			tombstone_scans: args.tombstone_scans.to_owned(),
+			maintenance: args.maintenance.to_owned(),
+			scan_records: args.scan_records,
+			growth_sizes: args.growth_sizes.to_owned(),
+			page_size: args.page_size,
 			sync: args.sync,
 			pid: args.pid,
+			cgroups: vec![],
+			containerised: false,
 			persisted: args.persisted,
+			pipelined: args.pipelined,
+			prepared: !args.unprepared,
+			emulate_indexes: args.emulate_indexes,
 			optimised: args.optimised,
 			operation_timeout: Duration::from_secs(args.operation_timeout),
+			timeout_multiplier: args.timeout_multiplier,
+			connect_timeout: Duration::from_secs(args.connect_timeout),
+			update_path: args.update_path.to_owned(),
+			sync_intervals: args.sync_intervals.to_owned(),
+			restricted_reads: args.restricted_reads,
+			secondary_reads: args.secondary_reads,
+			relations: args.relations,
+			live_queries: args.live_queries,
+			replication_lag: args.replication_lag,
+			backup: args.backup,
+			raw_latencies: args.raw_latencies.as_deref().map(RawLatencies::create).transpose()?,
+			server_timings: args.server_timings,
+			detect_scan_cache: args.detect_scan_cache,
+			isolate_scans: args.isolate_scans,
+			check_scan_consistency: args.check_scan_consistency,
+			index_build_writes: args.index_build_writes,
+			soak: args.soak,
+			soak_interval: Duration::from_secs(args.soak_interval * 60),
+			worker_histograms: args.worker_histograms,
+			soak_output: match &args.name {
+				Some(name) => format!("result-{name}-soak"),
+				None => "result-soak".to_string(),
+			},
+			verify: args.verify,
+			checkpoint: None,
+			shard: args.shard,
 			bench_ui: BenchUi::new(args.color),
+			retries: Retries::default(),
+			continue_on_error: args.continue_on_error,
+			arrival_rate: args.arrival_rate,
+			seed: args.seed,
+			read_samples: args.samples,
+			update_samples: args.samples,
+			server_profiler: None,
 			emit_phase_markers,
+		})
+	}
+
+	/// The CPU cores the datastore container can use, which the server threads are sized to.
+	pub(crate) fn container_cores(&self) -> usize {
+		match self.container_cpus {
+			Some(cpus) => (cpus.ceil() as usize).max(1),
+			None => num_cpus::get(),
+		}
+	}
+
+	/// Whether the phase metrics sample the datastore apart from the clients, rather than
+	/// crud-bench itself, such as an embedded engine.
+	fn monitors_datastore(&self) -> bool {
+		self.pid.is_some() || !self.cgroups.is_empty()
+	}
+
+	/// The CPU the datastore can use, as a percentage of every host core.
+	fn datastore_capacity(&self) -> f32 {
+		(self.container_cores() as f32 / num_cpus::get() as f32 * 100.0).min(100.0)
+	}
+
+	/// The records in the table after the creates: the samples, then the preloaded records.
+	fn base_records(&self) -> u32 {
+		self.samples + self.preload
+	}
+
+	/// Whether the phase was chosen with `--phases`, which runs every phase when it is not set.
+	fn runs(&self, phase: Phase) -> bool {
+		self.phases.is_empty() || self.phases.contains(&phase)
+	}
+
+	/// Whether the phase completed before an interrupted run which is being resumed.
+	fn completed(&self, phase: &str) -> bool {
+		self.checkpoint.as_ref().is_some_and(|c| c.contains(phase))
+	}
+
+	/// Runs a phase and records its results in the checkpoint, or restores the results
+	/// instead when the phase completed before an interrupted run which is being resumed.
+	async fn checkpointed<T, F>(&self, phase: &str, run: F) -> Result<T>
+	where
+		T: Serialize + DeserializeOwned,
+		F: Future<Output = Result<T>>,
+	{
+		let Some(checkpoint) = &self.checkpoint else {
+			return run.await;
+		};
+		if let Some(result) = checkpoint.restore(phase)? {
+			self.bench_ui.println_muted(&format!("Restored the {phase} from the checkpoint"));
+			return Ok(result);
 		}
+		let result = run.await?;
+		checkpoint.save(phase, &result)?;
+		Ok(result)
 	}
 
-	/// When `COMPACTION` is set in the environment, run the engine-specific
-	/// compaction hook and print elapsed time (same style as phase lines).
-	async fn maybe_compact_datastore<C, E>(&self, engine: &E) -> Result<()>
+	/// When the point was chosen with `--maintenance`, or `COMPACTION` is set in the environment,
+	/// run the engine-specific maintenance hook and print elapsed time (same style as phase lines),
+	/// with the space it reclaimed for datastores which report their size. The table scan of the
+	/// probe scan, when there is one, runs before and after, to measure the throughput it buys.
+	async fn maybe_compact_datastore<C, D, E>(
+		&self,
+		engine: &E,
+		clients: &[Arc<C>],
+		point: Maintenance,
+		probe: Option<&Scan>,
+		(kp, vp): (KeyProvider, &ValueProvider),
+	) -> Result<Option<Compaction>>
 	where
 		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
 		E: BenchmarkEngine<C> + Send + Sync,
 	{
-		if std::env::var("COMPACTION").is_ok() {
-			if self.emit_phase_markers {
-				self.bench_ui.println_plain("Compaction starting");
+		if std::env::var("COMPACTION").is_err() && !self.maintenance.contains(&point) {
+			return Ok(None);
+		}
+		// The records in the table change between the phases, so the cardinality is not checked
+		let probe = probe.map(|scan| {
+			let samples = scan.samples.map(|s| s as u32).unwrap_or(self.samples);
+			let operation = BenchmarkOperation::Scan(scan.unchecked(), ScanContext::WithoutIndex);
+			(scan.select_clients(clients), operation, samples)
+		});
+		let before = match &probe {
+			Some((clients, operation, samples)) => {
+				self.bench_ui.println_muted("Scanning before the maintenance");
+				self.run_operation::<C, D>(clients, operation.clone(), kp, vp.clone(), *samples)
+					.await?
 			}
-			let t = Instant::now();
-			self.wait_for_client(engine).await?.compact().await?;
-			self.bench_ui.println_took_head("Compaction", &format_duration(t.elapsed()));
+			None => None,
+		};
+		if self.emit_phase_markers {
+			self.bench_ui.println_plain("Compaction starting");
 		}
-		Ok(())
+		let client = self.wait_for_client(engine).await?;
+		let size_before = client.disk_size();
+		let t = Instant::now();
+		client.compact().await?;
+		let elapsed = t.elapsed();
+		let size_after = client.disk_size();
+		let after = match &probe {
+			Some((clients, operation, samples)) => {
+				self.bench_ui.println_muted("Scanning after the maintenance");
+				self.run_operation::<C, D>(clients, operation.clone(), kp, vp.clone(), *samples)
+					.await?
+			}
+			None => None,
+		};
+		let compaction = Compaction {
+			after: point.after().to_string(),
+			elapsed,
+			size_before,
+			size_after,
+			throughput_change: before
+				.as_ref()
+				.zip(after.as_ref())
+				.and_then(|(b, a)| throughput_change(b, a)),
+		};
+		let took = match compaction.reclaimed() {
+			Some(reclaimed) if reclaimed >= 0 => format!(
+				"{}, reclaimed {}",
+				format_duration(compaction.elapsed),
+				ByteSize(reclaimed as u64)
+			),
+			_ => format_duration(compaction.elapsed),
+		};
+		self.bench_ui.println_took_head("Compaction", &took);
+		if let Some(change) = compaction.throughput_change {
+			self.bench_ui.println_muted(&format!("Scan after the maintenance: {change:+.2}% OPS"));
+		}
+		Ok(Some(compaction))
+	}
+
+	/// Measures the size on disk of the datastore after a phase which loaded `logical` bytes, for
+	/// datastores which report their size and those in a container started by crud-bench.
+	fn measure_footprint<C>(
+		&self,
+		client: &C,
+		after: &str,
+		logical: u64,
+		result: Option<&OperationResult>,
+	) -> Option<DiskFootprint>
+	where
+		C: BenchmarkClient + Send + Sync,
+	{
+		let size = client.disk_size().or_else(|| match self.containerised {
+			true => Container::disk_size(),
+			false => None,
+		})?;
+		// Without a pid or cgroups, the writes of crud-bench itself are measured
+		let monitored = !self.containerised || self.pid.is_some() || !self.cgroups.is_empty();
+		let footprint = DiskFootprint {
+			after: after.to_string(),
+			size,
+			written: result.filter(|_| monitored).map(|r| r.disk_usage().total_written_bytes),
+			logical,
+		};
+		self.bench_ui.println_muted(&footprint.to_string());
+		Some(footprint)
 	}
 
 	#[allow(clippy::too_many_arguments)]
 	/// Run the benchmark for the desired benchmark engine
-	pub(crate) async fn run<C, D, E>(
+	pub(crate) fn run<C, D, E>(
 		&self,
 		engine: E,
 		kp: KeyProvider,
-		mut vp: ValueProvider,
+		vp: ValueProvider,
 		scans: Scans,
 		batches: Batches,
+		time_series: Option<TimeSeries>,
 		database: Option<String>,
 		system: Option<SystemInfo>,
 		metadata: Option<BenchmarkMetadata>,
+	) -> Pin<Box<impl Future<Output = Result<BenchmarkResult>>>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+		E: BenchmarkEngine<C> + Send + Sync,
+	{
+		// The state of every phase is kept on the heap, as it is too large for the stack
+		Box::pin(self.run_phases::<C, D, E>(
+			engine,
+			kp,
+			vp,
+			scans,
+			batches,
+			time_series,
+			database,
+			system,
+			metadata,
+		))
+	}
+
+	#[allow(clippy::too_many_arguments)]
+	/// Run each phase of the benchmark in turn
+	async fn run_phases<C, D, E>(
+		&self,
+		engine: E,
+		kp: KeyProvider,
+		mut vp: ValueProvider,
+		scans: Scans,
+		batches: Batches,
+		time_series: Option<TimeSeries>,
+		database: Option<String>,
+		system: Option<SystemInfo>,
+		mut metadata: Option<BenchmarkMetadata>,
 	) -> Result<BenchmarkResult>
 	where
 		C: BenchmarkClient + Send + Sync,
@@ -129,20 +495,84 @@ impl Benchmark {
 	{
 		// Generate a value sample for the report
 		let sample = vp.generate_value();
+		// Check every scan to run again after the deletes exists
+		if let Some(id) = self.tombstone_scans.iter().find(|id| !scans.iter().any(|s| &s.id == *id))
+		{
+			bail!("There is no scan with the id '{id}' given to --tombstone-scans");
+		}
+		// Leave out the scans and batches which were not chosen with `--phases`
+		let scans = match self.runs(Phase::Scan) {
+			true => scans,
+			false => vec![],
+		};
+		let batches = match self.runs(Phase::Batch) {
+			true => batches,
+			false => vec![],
+		};
 		// Setup the datastore
 		self.bench_ui
 			.println_muted(&format!("Setting up the datastore with {} clients", self.clients));
-		// Setup the datastore
-		self.wait_for_client(&engine).await?.startup().await?;
+		// Setup the datastore, once for all the agents of a coordinated run, unless it is reused
+		if self.no_setup {
+			self.bench_ui.println_muted("Reusing the records of an earlier run, without setup");
+		} else if self.shard.is_none_or(|s| s.is_first()) {
+			self.wait_for_client(&engine).await?.startup().await?;
+		}
+		agent::barrier("setup").await?;
 		// Setup the clients
 		let clients = self.setup_clients(&engine).await?;
+		// Record the datastore version, so the results can be attributed to a build
+		if let Some(metadata) = metadata.as_mut() {
+			metadata.version = match clients[0].version().await {
+				Ok(version) => Some(version),
+				Err(e) if BenchError::is_unsupported(&e) => None,
+				Err(e) => {
+					warn!("Failed to detect the datastore version: {e:#}");
+					None
+				}
+			};
+		}
 		// Start the benchmark (optional line for log-based profiling)
 		if self.emit_phase_markers {
 			self.bench_ui.println_plain("Benchmark starting");
 		}
-		// Run the "creates" benchmark
-		let creates = self
-			.run_operation::<C, D>(
+		// Restart an interrupted run after the phases it completed
+		if let Some(checkpoint) = self.checkpoint.as_ref().filter(|c| c.len() > 0) {
+			self.bench_ui.println_muted(&format!(
+				"Resuming after {} completed phases from the checkpoint",
+				checkpoint.len()
+			));
+		}
+		// The datastore starts out empty, so restored or unchosen creates are loaded for later phases
+		let reload = !self.no_setup
+			&& (self.completed("creates") || !self.runs(Phase::Create))
+			&& !self.completed("deletes");
+		// Bulk load the records beyond the samples, before the measured creates
+		if self.preload > 0 && !self.no_setup && !self.completed("deletes") {
+			self.run_preload::<C, D>(&clients, kp, vp.clone()).await?;
+		}
+		// The compactions run between the phases, measured with the first scan which runs
+		let mut compactions = Vec::new();
+		let probe = scans.iter().find(|s| !s.skip);
+		// The disk footprint after each phase which loads records
+		let mut footprints = Vec::new();
+		// Run the "creates" benchmark, unless the records are already loaded
+		let creates = match self.no_setup || !self.runs(Phase::Create) {
+			true => None,
+			false => {
+				let run = self.run_operation::<C, D>(
+					&clients,
+					BenchmarkOperation::Create,
+					kp,
+					vp.clone(),
+					self.samples,
+				);
+				self.checkpointed("creates", run).await?
+			}
+		};
+		if reload {
+			self.bench_ui.println_muted("Loading the records of the unmeasured creates, untimed");
+			self.run_operation::<C, D>(
 				&clients,
 				BenchmarkOperation::Create,
 				kp,
@@ -150,35 +580,218 @@ impl Benchmark {
 				self.samples,
 			)
 			.await?;
+		}
+		// Measure the disk footprint of the created records
+		let logical = self.base_records() as u64 * record_size(&kp, &vp);
+		let client = clients[0].as_ref();
+		footprints.extend(self.measure_footprint(client, "creates", logical, creates.as_ref()));
 		// Compact the datastore
-		self.maybe_compact_datastore::<C, E>(&engine).await?;
+		let run = self.maybe_compact_datastore::<C, D, E>(
+			&engine,
+			&clients,
+			Maintenance::Create,
+			probe,
+			(kp, &vp),
+		);
+		compactions.extend(run.await?);
 		// Run the "reads" benchmark
-		let reads = self
-			.run_operation::<C, D>(&clients, BenchmarkOperation::Read, kp, vp.clone(), self.samples)
-			.await?;
+		let reads = match self.runs(Phase::Read) {
+			false => None,
+			true => {
+				let operation = BenchmarkOperation::reads(self.read_samples, self.keyspace);
+				let run = self.run_operation::<C, D>(
+					&clients,
+					operation,
+					kp,
+					vp.clone(),
+					self.read_samples,
+				);
+				self.checkpointed("reads", run).await?
+			}
+		};
+		// Run the "restricted reads" benchmark
+		let restricted_reads = match self.restricted_reads {
+			true => {
+				let run = self.run_restricted_reads::<C, D, E>(&engine, kp, vp.clone(), &reads);
+				self.checkpointed("restricted reads", run).await?
+			}
+			false => None,
+		};
+		// Run the "secondary reads" benchmarks
+		let secondary_reads = match self.secondary_reads {
+			Some(samples) => {
+				let run = self.run_secondary_reads::<C, D>(&clients, kp, vp.clone(), samples);
+				self.checkpointed("secondary reads", run).await?
+			}
+			None => None,
+		};
+		// Run the "relations" benchmarks
+		let relations = match self.relations {
+			Some(fanout) => {
+				let run = self.run_relations::<C, D>(&clients, kp, vp.clone(), fanout);
+				self.checkpointed("relations", run).await?
+			}
+			None => None,
+		};
+		// Run the "live queries" benchmark
+		let live_queries = match self.live_queries {
+			Some(subscribers) => {
+				let run = self.run_live_queries::<C, D, E>(
+					&engine,
+					&clients,
+					kp,
+					vp.clone(),
+					subscribers,
+				);
+				self.checkpointed("live queries", run).await?
+			}
+			None => None,
+		};
+		// Run the "replication lag" benchmark
+		let replication_lag = match self.replication_lag {
+			true => {
+				let run = self.run_replication_lag::<C, D, E>(&engine, &clients, kp, vp.clone());
+				self.checkpointed("replication lag", run).await?
+			}
+			false => None,
+		};
+		// Sample reads with engine-reported execution times
+		let server_timings = match self.server_timings {
+			Some(samples) => {
+				let run = self.run_server_timings(clients[0].as_ref(), kp, samples);
+				self.checkpointed("server timings", run).await?
+			}
+			None => None,
+		};
+		// Check every record holds the value it was created with, which a reused record may not
+		if !self.no_setup {
+			self.verify::<C, D>(&clients, "creates", kp, vp.clone()).await?;
+		}
 		// Compact the datastore
-		self.maybe_compact_datastore::<C, E>(&engine).await?;
-		// Run the "reads" benchmark
-		let updates = self
-			.run_operation::<C, D>(
-				&clients,
-				BenchmarkOperation::Update,
-				kp,
-				vp.clone(),
-				self.samples,
-			)
-			.await?;
+		let run = self.maybe_compact_datastore::<C, D, E>(
+			&engine,
+			&clients,
+			Maintenance::Read,
+			probe,
+			(kp, &vp),
+		);
+		compactions.extend(run.await?);
+		// The updates write values which differ from those created
+		let updated = vp.round(UPDATE_ROUND);
+		// Run the "updates" benchmark
+		let updates = match self.runs(Phase::Update) {
+			false => None,
+			true => {
+				let operation = BenchmarkOperation::updates(self.update_samples, self.keyspace);
+				let run = self.run_operation::<C, D>(
+					&clients,
+					operation,
+					kp,
+					updated.clone(),
+					self.update_samples,
+				);
+				self.checkpointed("updates", run).await?
+			}
+		};
+		// Run the "path updates" benchmark
+		let path_updates = match self.update_path.as_ref().filter(|_| self.runs(Phase::Update)) {
+			Some(path) => {
+				let run = async {
+					let result = self
+						.run_operation::<C, D>(
+							&clients,
+							BenchmarkOperation::UpdatePath(path.clone()),
+							kp,
+							updated.clone(),
+							self.samples,
+						)
+						.await?;
+					// Output the throughput compared to the full updates
+					self.println_throughput_change(
+						"Path updates",
+						"full updates",
+						&updates,
+						&result,
+					);
+					Ok(result)
+				};
+				self.checkpointed("path updates", run).await?
+			}
+			None => None,
+		};
+		// Run the "synced updates" benchmarks
+		let synced_updates = match self.runs(Phase::Update) {
+			false => vec![],
+			true => {
+				let run = self.run_synced_updates::<C, D>(&clients, kp, updated.clone(), &updates);
+				self.checkpointed("synced updates", run).await?
+			}
+		};
+		// Check every record holds its updated value, unless the restored updates were not rerun
+		if self.runs(Phase::Update) && !self.completed("updates") {
+			self.verify::<C, D>(&clients, "updates", kp, updated).await?;
+		}
 		// Compact the datastore
-		self.maybe_compact_datastore::<C, E>(&engine).await?;
+		let run = self.maybe_compact_datastore::<C, D, E>(
+			&engine,
+			&clients,
+			Maintenance::Update,
+			probe,
+			(kp, &vp),
+		);
+		compactions.extend(run.await?);
+		// Back up the datastore, and restore the backup into a fresh database
+		let backup = match self.backup {
+			true => {
+				let run = self.run_backup(clients[0].as_ref());
+				self.checkpointed("backup", run).await?
+			}
+			false => None,
+		};
+		// Loop the mixed reads and updates for the soak duration
+		let soak = match self.soak {
+			Some(duration) => {
+				let run = self.run_soak::<C, D>(&clients, kp, vp.round(UPDATE_ROUND), duration);
+				self.checkpointed("soak", run).await?
+			}
+			None => vec![],
+		};
+		// Load the records beyond the samples which only the scans use, unless every scan was restored
+		let pending_scans = scans.iter().any(|s| !s.skip && !self.completed(&scan_phase(s)));
+		let scan_records = match pending_scans {
+			false => 0,
+			true => {
+				let base = self.base_records();
+				self.scan_records.unwrap_or(base).saturating_sub(base)
+			}
+		};
+		if scan_records > 0 {
+			let operation = BenchmarkOperation::CreateScanRecords(self.base_records());
+			self.run_operation::<C, D>(&clients, operation, kp, vp.clone(), scan_records).await?;
+		}
+		// Isolate the index churn of the scans on a copy of the table
+		let isolated = match self.isolate_scans && pending_scans {
+			true => self.snapshot_table(clients[0].as_ref()).await?,
+			false => false,
+		};
+		// The scans and batches skipped by the spec overrides, marked in the results
+		let mut skipped = Vec::new();
 		// Run the "scan" benchmarks
 		let mut scan_results = Vec::with_capacity(scans.len());
 		let mut prev_spec_group: Option<u32> = None;
 		let mut prev_run_key: Option<(u32, String)> = None;
-		for scan in scans {
+		for scan in &scans {
 			// New section in the TOML/config → new heading in the CLI output
 			if prev_spec_group != Some(scan.spec_group) {
 				self.bench_ui.section_header(&format!("Scan · {}", scan.id));
 				prev_spec_group = Some(scan.spec_group);
+				if scan.clients.is_some() || scan.threads.is_some() {
+					self.bench_ui.println_muted(&format!(
+						"Running with {} clients and {} threads per client",
+						scan.select_clients(&clients).len(),
+						scan.threads.unwrap_or(self.threads),
+					));
+				}
 			}
 			// Multi-run entries (`runs` array): print a sub-line when the run name changes
 			let run_key = (scan.spec_group, scan.name.clone());
@@ -191,77 +804,319 @@ impl Benchmark {
 			let id = scan.id.clone();
 			let name = scan.name.clone();
 			let samples = scan.samples.map(|s| s as u32).unwrap_or(self.samples);
-			let write_specs = scan.with_writes.as_slice();
-			let w = write_specs.len();
-			let index_spec = scan.with_index.as_ref().filter(|i| !i.skip);
-
-			let result = if let Some(index_spec) = index_spec {
-				// Indexed scan: heap legs → build index → indexed legs → drop index
-				let mut runs = Vec::with_capacity(2 + 2 * w);
-				// Table-scan / heap query (no physical index)
-				let without_index = self
+			// Keep an empty row for a scan skipped for this database
+			if scan.skip {
+				self.bench_ui.println_muted("Skipped for this database by the benchmark TOML");
+				let run = ScanRun {
+					workload: ScanWorkload::Read,
+					indexed: false,
+					hinted: false,
+					result: None,
+				};
+				skipped.push(scan_run_row_label(&id, &name, samples, &run));
+				scan_results.push(ScanResult {
+					id,
+					name,
+					samples,
+					index_build: None,
+					index_remove: None,
+					idle_writes: None,
+					build_writes: None,
+					runs: vec![run],
+					consistency: None,
+				});
+				continue;
+			}
+			let run = self.run_scan::<C, D>(&clients, scan, kp, vp.clone());
+			let result = self.checkpointed(&scan_phase(scan), run).await?;
+			scan_results.push(result);
+		}
+		// Restore the table as it was before the scans
+		if isolated {
+			let time = Instant::now();
+			clients[0].restore_table().await?;
+			self.bench_ui.println_took_head("Restore table", &format_duration(time.elapsed()));
+		}
+		// Remove the records which only the scans use
+		if scan_records > 0 {
+			let operation = BenchmarkOperation::DeleteScanRecords(self.base_records());
+			self.run_operation::<C, D>(&clients, operation, kp, vp.clone(), scan_records).await?;
+		}
+		// Run the "pagination" benchmarks
+		let pagination = match self.page_size {
+			Some(page_size) => {
+				let run = self.run_pagination(clients[0].as_ref(), kp, page_size);
+				self.checkpointed("pagination", run).await?
+			}
+			None => vec![],
+		};
+		// Run the "growth" benchmarks
+		let run = self.run_growth::<C, D>(&clients, &scans, kp, vp.clone());
+		let growth = self.checkpointed("growth", run).await?;
+		// Compact the datastore
+		let run = self.maybe_compact_datastore::<C, D, E>(
+			&engine,
+			&clients,
+			Maintenance::Scan,
+			probe,
+			(kp, &vp),
+		);
+		compactions.extend(run.await?);
+		// Run the "deletes" benchmark, unless the records are kept
+		let deletes = match self.keep_data || !self.runs(Phase::Delete) {
+			true => None,
+			false => {
+				self.bench_ui.section_header("Delete");
+				let run = self.run_operation::<C, D>(
+					&clients,
+					BenchmarkOperation::Delete,
+					kp,
+					vp.clone(),
+					self.samples,
+				);
+				self.checkpointed("deletes", run).await?
+			}
+		};
+		// Run the chosen scans again over the deleted records, before a compaction removes them
+		if deletes.is_some() && !self.tombstone_scans.is_empty() {
+			let run =
+				self.run_tombstone_scans::<C, D>(&clients, &scans, &scan_results, kp, vp.clone());
+			for (index, run) in self.checkpointed("tombstone scans", run).await? {
+				scan_results[index].runs.push(run);
+			}
+		}
+		// Run the "delete strategies" benchmarks, unless the records are kept
+		let delete_strategies = match deletes.is_some() && !self.delete_strategies.is_empty() {
+			false => vec![],
+			true => {
+				let run = self.run_delete_strategies::<C, D>(&clients, kp, vp.clone());
+				self.checkpointed("delete strategies", run).await?
+			}
+		};
+		// Compact the datastore
+		let run = self.maybe_compact_datastore::<C, D, E>(
+			&engine,
+			&clients,
+			Maintenance::Delete,
+			probe,
+			(kp, &vp),
+		);
+		compactions.extend(run.await?);
+		// The batches create and delete records in the table, which the kept records would clash with
+		if self.keep_data && !batches.is_empty() {
+			self.bench_ui.println_muted("Skipping the batches, as the records are kept");
+		} else if !batches.is_empty() {
+			self.bench_ui.section_header("Batches");
+		}
+		// Keep an empty row for each batch case skipped for this database
+		for batch in batches.iter().filter(|b| b.skip) {
+			let samples = batch.samples.map(|s| s as u32).unwrap_or(self.samples);
+			skipped.push(batch_row_label(&batch.name, samples, batch.batch_size));
+		}
+		// Run the "batch" benchmarks
+		let batch_results = match batches.is_empty() || self.keep_data {
+			true => vec![],
+			false => {
+				let run =
+					self.run_batches::<C, D>(&clients, batches, kp, vp.clone(), &mut footprints);
+				self.checkpointed("batches", run).await?
+			}
+		};
+		// Run the "time series" benchmarks
+		let time_series = match time_series {
+			Some(series) => {
+				let run = self.run_time_series::<C, D>(&clients, series, kp, vp.clone());
+				self.checkpointed("time series", run).await?
+			}
+			None => vec![],
+		};
+		// Mark the benchmark as complete
+		if self.emit_phase_markers {
+			self.bench_ui.println_plain("Benchmark complete");
+		}
+		// Shut down the datastore, once every agent of a coordinated run has finished
+		agent::barrier("shutdown").await?;
+		if self.shard.is_none_or(|s| s.is_first()) {
+			let client = self.wait_for_client(&engine).await?;
+			match self.keep_data {
+				// The kept records are synced to disk, rather than removed with the datastore
+				true => match client.sync().await {
+					Err(e) if !BenchError::is_unsupported(&e) => return Err(e),
+					_ => self.bench_ui.println_muted("Keeping the records for the next run"),
+				},
+				false => client.shutdown().await?,
+			}
+		}
+		// Return the benchmark results
+		Ok(BenchmarkResult {
+			database,
+			system,
+			metadata,
+			workload: None,
+			config: None,
+			flamegraphs: Vec::new(),
+			server_flamegraphs: Vec::new(),
+			creates,
+			reads,
+			restricted_reads,
+			secondary_reads,
+			relations,
+			live_queries,
+			replication_lag,
+			server_timings,
+			resources: None,
+			updates,
+			path_updates,
+			synced_updates,
+			soak,
+			scans: scan_results,
+			pagination,
+			growth,
+			batches: batch_results,
+			time_series,
+			skipped,
+			compactions,
+			backup,
+			footprints,
+			deletes,
+			delete_strategies,
+			sample,
+		})
+	}
+
+	/// Runs the table-scan legs of a scan, then builds its index and runs the indexed legs.
+	async fn run_scan<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		scan: &Scan,
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<ScanResult>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		let id = scan.id.clone();
+		let name = scan.name.clone();
+		let samples = scan.samples.map(|s| s as u32).unwrap_or(self.samples);
+		let scan_clients = scan.select_clients(clients);
+		let write_specs = scan.with_writes.as_slice();
+		let w = write_specs.len();
+		let index_spec = scan.with_index.as_ref().filter(|i| !i.skip);
+		// Check the returned ids while no writes occur
+		let consistency = match self.check_scan_consistency {
+			true => self.run_scan_consistency(clients[0].as_ref(), scan).await?,
+			false => None,
+		};
+
+		let result = if let Some(index_spec) = index_spec {
+			// Indexed scan: heap legs → build index → indexed legs → drop index
+			let mut runs = Vec::with_capacity(2 + 2 * w);
+			// Table-scan / heap query (no physical index)
+			let without_index = self
+				.run_operation::<C, D>(
+					scan_clients,
+					BenchmarkOperation::Scan(scan.clone(), ScanContext::WithoutIndex),
+					kp,
+					vp.clone(),
+					samples,
+				)
+				.await?;
+			// Same query with a predicate varied per sample, to expose result caching
+			let perturbed = self
+				.run_perturbed_scan::<C, D>(
+					scan_clients,
+					scan,
+					ScanContext::WithoutIndex,
+					(kp, vp.clone(), samples),
+					&without_index,
+				)
+				.await?;
+			runs.push(ScanRun {
+				workload: ScanWorkload::Read,
+				indexed: false,
+				hinted: false,
+				result: without_index,
+			});
+			runs.extend(perturbed);
+			// Optional mixed read+write legs on the heap path (one per `with_writes` entry)
+			for spec in write_specs {
+				let mixed_without_index = self
 					.run_operation::<C, D>(
-						&clients,
-						BenchmarkOperation::Scan(scan.clone(), ScanContext::WithoutIndex),
+						scan_clients,
+						BenchmarkOperation::ScanWithWrites(
+							scan.clone(),
+							ScanContext::WithoutIndex,
+							spec.clone(),
+						),
 						kp,
 						vp.clone(),
 						samples,
 					)
 					.await?;
 				runs.push(ScanRun {
-					workload: ScanWorkload::Read,
+					workload: ScanWorkload::ReadWrite {
+						write_ratio_percent: writes_ratio_percent(spec),
+					},
 					indexed: false,
-					result: without_index,
+					hinted: false,
+					result: mixed_without_index,
 				});
-				// Optional mixed read+write legs on the heap path (one per `with_writes` entry)
-				for spec in write_specs {
-					let mixed_without_index = self
-						.run_operation::<C, D>(
-							&clients,
-							BenchmarkOperation::ScanWithWrites(
-								scan.clone(),
-								ScanContext::WithoutIndex,
-								spec.clone(),
-							),
-							kp,
-							vp.clone(),
-							samples,
-						)
-						.await?;
-					runs.push(ScanRun {
-						workload: ScanWorkload::ReadWrite {
-							write_ratio_percent: writes_ratio_percent(spec),
-						},
-						indexed: false,
-						result: mixed_without_index,
-					});
-				}
-				// BuildIndex uses a single client to avoid races on DDL
-				let index_build = self
-					.run_operation::<C, D>(
-						&clients[..1],
-						BenchmarkOperation::BuildIndex(index_spec.clone(), id.clone()),
-						kp,
-						vp.clone(),
-						1,
-					)
-					.await?;
-				let (with_index, index_remove, indexed_write_results) = if index_build.is_some() {
+			}
+			// Build the index, alongside the `--index-build-writes` updates when set
+			let (index_build, idle_writes, build_writes) = self
+				.run_index_build::<C, D>(clients, scan_clients, index_spec, &id, kp, &vp)
+				.await?;
+			let indexed = index_build.is_some();
+			let (with_index, with_hint, with_perturbed, index_remove, indexed_write_results) =
+				if indexed {
 					// Same query shape using the new index
 					let with_index = self
 						.run_operation::<C, D>(
-							&clients,
+							scan_clients,
 							BenchmarkOperation::Scan(scan.clone(), ScanContext::WithIndex),
 							kp,
 							vp.clone(),
 							samples,
 						)
 						.await?;
+					// Same query again with the index forced, next to the planner-chosen leg
+					let with_hint = match scan.hint {
+						Some(_) => {
+							let result = self
+								.run_operation::<C, D>(
+									scan_clients,
+									BenchmarkOperation::Scan(scan.clone(), ScanContext::WithHint),
+									kp,
+									vp.clone(),
+									samples,
+								)
+								.await?;
+							self.println_throughput_change(
+								"Hinted scan",
+								"planner-chosen scan",
+								&with_index,
+								&result,
+							);
+							result
+						}
+						None => None,
+					};
+					// Same query with a predicate varied per sample, to expose result caching
+					let with_perturbed = self
+						.run_perturbed_scan::<C, D>(
+							scan_clients,
+							scan,
+							ScanContext::WithIndex,
+							(kp, vp.clone(), samples),
+							&with_index,
+						)
+						.await?;
 					let mut iw = Vec::with_capacity(w);
 					for spec in write_specs {
 						iw.push(
 							self.run_operation::<C, D>(
-								&clients,
+								scan_clients,
 								BenchmarkOperation::ScanWithWrites(
 									scan.clone(),
 									ScanContext::WithIndex,
@@ -277,206 +1132,1312 @@ impl Benchmark {
 					let index_remove = self
 						.run_operation::<C, D>(
 							&clients[..1],
-							BenchmarkOperation::RemoveIndex(id.clone()),
+							BenchmarkOperation::RemoveIndex(index_spec.clone(), id.clone()),
 							kp,
 							vp.clone(),
 							1,
 						)
 						.await?;
-					(with_index, index_remove, iw)
+					(with_index, with_hint, with_perturbed, index_remove, iw)
 				} else {
 					// BuildIndex unsupported or skipped → no indexed timings to merge
-					(None, None, Vec::new())
+					(None, None, None, None, Vec::new())
 				};
-				if index_build.is_some() {
+			if indexed {
+				runs.push(ScanRun {
+					workload: ScanWorkload::Read,
+					indexed: true,
+					hinted: false,
+					result: with_index,
+				});
+				if scan.hint.is_some() {
 					runs.push(ScanRun {
 						workload: ScanWorkload::Read,
 						indexed: true,
-						result: with_index,
+						hinted: true,
+						result: with_hint,
 					});
-					for (spec, r) in write_specs.iter().zip(indexed_write_results) {
-						runs.push(ScanRun {
-							workload: ScanWorkload::ReadWrite {
-								write_ratio_percent: writes_ratio_percent(spec),
-							},
-							indexed: true,
-							result: r,
-						});
-					}
-				} else {
-					// Still emit indexed rows so CSV/HTML rows align; cells show "-" when result is None
+				}
+				runs.extend(with_perturbed);
+				for (spec, r) in write_specs.iter().zip(indexed_write_results) {
 					runs.push(ScanRun {
-						workload: ScanWorkload::Read,
+						workload: ScanWorkload::ReadWrite {
+							write_ratio_percent: writes_ratio_percent(spec),
+						},
 						indexed: true,
-						result: None,
+						hinted: false,
+						result: r,
 					});
-					for spec in write_specs {
-						runs.push(ScanRun {
-							workload: ScanWorkload::ReadWrite {
-								write_ratio_percent: writes_ratio_percent(spec),
-							},
-							indexed: true,
-							result: None,
-						});
-					}
-				}
-				ScanResult {
-					id: id.clone(),
-					name,
-					samples,
-					index_build,
-					index_remove,
-					runs,
 				}
 			} else {
-				// No index spec (or index skipped): only heap scan + optional write-mix legs
-				let mut runs = Vec::with_capacity(1 + w);
-				let without_index = self
-					.run_operation::<C, D>(
-						&clients,
-						BenchmarkOperation::Scan(scan.clone(), ScanContext::WithoutIndex),
-						kp,
-						vp.clone(),
-						samples,
-					)
-					.await?;
+				// Still emit indexed rows so CSV/HTML rows align; cells show "-" when result is None
 				runs.push(ScanRun {
 					workload: ScanWorkload::Read,
-					indexed: false,
-					result: without_index,
+					indexed: true,
+					hinted: false,
+					result: None,
 				});
-				for spec in write_specs {
-					let mixed_without_index = self
-						.run_operation::<C, D>(
-							&clients,
-							BenchmarkOperation::ScanWithWrites(
-								scan.clone(),
-								ScanContext::WithoutIndex,
-								spec.clone(),
-							),
-							kp,
-							vp.clone(),
-							samples,
-						)
-						.await?;
+				if scan.hint.is_some() {
 					runs.push(ScanRun {
-						workload: ScanWorkload::ReadWrite {
-							write_ratio_percent: writes_ratio_percent(spec),
+						workload: ScanWorkload::Read,
+						indexed: true,
+						hinted: true,
+						result: None,
+					});
+				}
+				if self.detect_scan_cache && scan.has_condition() {
+					runs.push(ScanRun {
+						workload: ScanWorkload::Perturbed,
+						indexed: true,
+						hinted: false,
+						result: None,
+					});
+				}
+				for spec in write_specs {
+					runs.push(ScanRun {
+						workload: ScanWorkload::ReadWrite {
+							write_ratio_percent: writes_ratio_percent(spec),
 						},
-						indexed: false,
-						result: mixed_without_index,
+						indexed: true,
+						hinted: false,
+						result: None,
 					});
 				}
-				ScanResult {
-					id: id.clone(),
-					name,
+			}
+			ScanResult {
+				id: id.clone(),
+				name,
+				samples,
+				index_build,
+				index_remove,
+				idle_writes,
+				build_writes,
+				runs,
+				consistency,
+			}
+		} else {
+			// No index spec (or index skipped): only heap scan + optional write-mix legs
+			let mut runs = Vec::with_capacity(1 + w);
+			let without_index = self
+				.run_operation::<C, D>(
+					scan_clients,
+					BenchmarkOperation::Scan(scan.clone(), ScanContext::WithoutIndex),
+					kp,
+					vp.clone(),
 					samples,
-					index_build: None,
-					index_remove: None,
-					runs,
+				)
+				.await?;
+			// Same query with a predicate varied per sample, to expose result caching
+			let perturbed = self
+				.run_perturbed_scan::<C, D>(
+					scan_clients,
+					scan,
+					ScanContext::WithoutIndex,
+					(kp, vp.clone(), samples),
+					&without_index,
+				)
+				.await?;
+			runs.push(ScanRun {
+				workload: ScanWorkload::Read,
+				indexed: false,
+				hinted: false,
+				result: without_index,
+			});
+			runs.extend(perturbed);
+			for spec in write_specs {
+				let mixed_without_index = self
+					.run_operation::<C, D>(
+						scan_clients,
+						BenchmarkOperation::ScanWithWrites(
+							scan.clone(),
+							ScanContext::WithoutIndex,
+							spec.clone(),
+						),
+						kp,
+						vp.clone(),
+						samples,
+					)
+					.await?;
+				runs.push(ScanRun {
+					workload: ScanWorkload::ReadWrite {
+						write_ratio_percent: writes_ratio_percent(spec),
+					},
+					indexed: false,
+					hinted: false,
+					result: mixed_without_index,
+				});
+			}
+			ScanResult {
+				id: id.clone(),
+				name,
+				samples,
+				index_build: None,
+				index_remove: None,
+				idle_writes: None,
+				build_writes: None,
+				runs,
+				consistency,
+			}
+		};
+		Ok(result)
+	}
+
+	/// Runs each batch case, leaving the row empty when it is skipped or unsupported.
+	async fn run_batches<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		batches: Batches,
+		kp: KeyProvider,
+		vp: ValueProvider,
+		footprints: &mut Vec<DiskFootprint>,
+	) -> Result<Vec<(String, u32, usize, Option<OperationResult>)>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		// Create the table for the batches with keys generated by the datastore
+		let generated = match batches.iter().any(|b| !b.skip && b.operation.is_generated()) {
+			true => self.setup_generated_keys(clients[0].as_ref()).await?,
+			false => None,
+		};
+		// Run the "batch" benchmarks
+		let mut batch_results = Vec::with_capacity(batches.len());
+		for batch in batches {
+			// Get the name of the batch operation
+			let name = batch.name.clone();
+			let groups = batch.batch_size;
+			let samples = batch.samples.map(|s| s as u32).unwrap_or(self.samples);
+			// Keep an empty row for a batch case skipped for this database
+			if batch.skip {
+				self.bench_ui.println_muted(&format!(
+					"{name} skipped for this database by the benchmark TOML"
+				));
+				batch_results.push((name, samples, groups, None));
+				continue;
+			}
+			// Determine the batch operation type
+			let operation = match (&batch.operation, &generated) {
+				(crate::BatchOperationType::Create, _) => {
+					BenchmarkOperation::BatchCreate(batch.clone())
+				}
+				(crate::BatchOperationType::Read, _) => {
+					BenchmarkOperation::BatchRead(batch.clone())
+				}
+				(crate::BatchOperationType::Update, _) => {
+					BenchmarkOperation::BatchUpdate(batch.clone())
+				}
+				(crate::BatchOperationType::Delete, _) => {
+					BenchmarkOperation::BatchDelete(batch.clone())
+				}
+				(crate::BatchOperationType::CreateGenerated, Some(keys)) => {
+					BenchmarkOperation::BatchCreateGenerated(batch.clone(), keys.clone())
+				}
+				(crate::BatchOperationType::ReadGenerated, Some(keys)) => {
+					BenchmarkOperation::BatchReadGenerated(batch.clone(), keys.clone())
+				}
+				// Generated keys are not supported, so leave the row empty
+				(_, None) => {
+					batch_results.push((name, samples, groups, None));
+					continue;
+				}
+			};
+			// Run bulk loads and interactive batches at their own concurrency and rate
+			let batch_clients = batch.select_clients(clients);
+			if batch.clients.is_some() || batch.threads.is_some() || batch.rate.is_some() {
+				let threads = batch.threads.unwrap_or(self.threads);
+				let rate = match batch.rate {
+					Some(rate) => format!(", at most {rate} batches per second"),
+					None => String::new(),
+				};
+				self.bench_ui.println_muted(&format!(
+					"Running {name} with {} clients and {threads} threads per client{rate}",
+					batch_clients.len(),
+				));
+			}
+			// Execute the batch benchmark
+			let duration = self
+				.run_operation::<C, D>(batch_clients, operation, kp, vp.clone(), samples)
+				.await?;
+			// Measure the disk footprint of the records the batches created
+			if batch.operation == crate::BatchOperationType::Create {
+				let label = batch_row_label(&name, samples, groups);
+				let logical = samples as u64 * groups as u64 * record_size(&kp, &vp);
+				let client = clients[0].as_ref();
+				footprints.extend(self.measure_footprint(
+					client,
+					&label,
+					logical,
+					duration.as_ref(),
+				));
+			}
+			// Store the batch benchmark result
+			batch_results.push((name, samples, groups, duration));
+		}
+		Ok(batch_results)
+	}
+
+	/// Polls [`BenchmarkEngine::ready`] until the datastore accepts a client, or the connect timeout elapses.
+	async fn wait_for_client<C, E>(&self, engine: &E) -> Result<C>
+	where
+		C: BenchmarkClient + Send + Sync,
+		E: BenchmarkEngine<C> + Send + Sync,
+	{
+		let start = Instant::now();
+		// Probe often while the datastore starts, backing off up to a couple of seconds
+		let mut delay = Duration::from_millis(100);
+		loop {
+			// Connect once the datastore reports it is ready
+			let client = match engine.ready().await {
+				Ok(()) => engine.create_client().await,
+				Err(e) => Err(e),
+			};
+			match client {
+				Ok(c) => {
+					debug!("The datastore was ready after {:?}", start.elapsed());
+					return Ok(c);
+				}
+				Err(e) if start.elapsed() >= self.connect_timeout => {
+					// Show why a container started by crud-bench did not become ready
+					if self.containerised
+						&& let Some(logs) = Container::tail_logs(50)
+					{
+						error!("The last lines of the container logs:\n{logs}");
+					}
+					return Err(e.context(format!(
+						"The datastore was not ready after {}s, raise `--connect-timeout` if it starts slowly",
+						self.connect_timeout.as_secs()
+					)));
+				}
+				Err(e) => debug!("The datastore is not ready: {e:#}"),
+			}
+			tokio::time::sleep(delay).await;
+			delay = (delay * 2).min(Duration::from_secs(2));
+		}
+	}
+
+	/// Creates one async connection per logical client; returns shared handles for workers.
+	async fn setup_clients<C, E>(&self, engine: &E) -> Result<Vec<Arc<C>>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		E: BenchmarkEngine<C> + Send + Sync,
+	{
+		// Create a set of client connections
+		let mut clients = Vec::with_capacity(self.clients as usize);
+		// Create the desired number of connections
+		for i in 0..self.clients {
+			// Log some information
+			info!("Creating client {}", i + 1);
+			// Create a new client connection
+			clients.push(engine.create_client());
+		}
+		// Wait for all the clients to connect
+		Ok(try_join_all(clients).await?.into_iter().map(Arc::new).collect())
+	}
+
+	/// Bulk loads the `--preload` records beyond the samples through batch creates, or through
+	/// single creates when the datastore does not support batches, outside the results.
+	async fn run_preload<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<()>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		self.bench_ui.println_muted(&format!("Preloading {} records, untimed", self.preload));
+		let batches = self.preload.div_ceil(PRELOAD_BATCH_SIZE);
+		let operation =
+			BenchmarkOperation::Preload(self.samples, self.preload, Some(PRELOAD_BATCH_SIZE));
+		let loaded =
+			self.run_operation::<C, D>(clients, operation, kp, vp.clone(), batches).await?;
+		if loaded.is_none() {
+			self.bench_ui.println_muted("Batch creates are not supported, preloading one by one");
+			let operation = BenchmarkOperation::Preload(self.samples, self.preload, None);
+			self.run_operation::<C, D>(clients, operation, kp, vp, self.preload).await?;
+		}
+		Ok(())
+	}
+
+	/// Reads back every record when `--verify` is set, regenerating the value the
+	/// `phase` wrote to it, and fails the run when the datastore returns another value.
+	async fn verify<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		phase: &str,
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<()>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		if !self.verify {
+			return Ok(());
+		}
+		let operation = BenchmarkOperation::Verify(phase.to_string());
+		self.run_operation::<C, D>(clients, operation, kp, vp, self.samples).await?;
+		Ok(())
+	}
+
+	/// Repeats the read phase through restricted clients, where row-level security or
+	/// document permissions are checked on every read, and prints the throughput overhead.
+	async fn run_restricted_reads<C, D, E>(
+		&self,
+		engine: &E,
+		kp: KeyProvider,
+		vp: ValueProvider,
+		reads: &Option<OperationResult>,
+	) -> Result<Option<OperationResult>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+		E: BenchmarkEngine<C> + Send + Sync,
+	{
+		// Create the restricted role and permissions, then connect as that role
+		let clients = match self.setup_restricted_clients(engine).await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Restricted reads are not supported, skipping");
+				return Ok(None);
+			}
+			clients => clients?,
+		};
+		// Run the reads through the restricted clients
+		let result = self
+			.run_operation::<C, D>(
+				&clients,
+				BenchmarkOperation::RestrictedRead,
+				kp,
+				vp,
+				self.samples,
+			)
+			.await?;
+		// Output the throughput compared to the unrestricted reads
+		self.println_throughput_change("Restricted reads", "unrestricted reads", reads, &result);
+		Ok(result)
+	}
+
+	/// Reads records by the `unique` field of the value template with no index on
+	/// the field, then through an index built on it, which is dropped afterwards.
+	async fn run_secondary_reads<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+		samples: u32,
+	) -> Result<Option<SecondaryReads>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		let field = vp.unique_field().context("Missing unique field")?.to_string();
+		self.bench_ui.section_header(&format!("Secondary reads · {field}"));
+		// Reads which have to scan the table, or a primary index
+		let operation = BenchmarkOperation::SecondaryRead(
+			field.clone(),
+			self.samples,
+			ScanContext::WithoutIndex,
+		);
+		let without_index =
+			self.run_operation::<C, D>(clients, operation, kp, vp.clone(), samples).await?;
+		// BuildIndex uses a single client to avoid races on DDL
+		let name = format!("{field}_secondary");
+		let spec = Index {
+			skip: false,
+			fields: vec![field.clone()],
+			unique: Some(true),
+			index_type: None,
+			separate: false,
+			filter: None,
+		};
+		let operation = BenchmarkOperation::BuildIndex(spec.clone(), name.clone());
+		let index_build =
+			self.run_operation::<C, D>(&clients[..1], operation, kp, vp.clone(), 1).await?;
+		let (with_index, index_remove) = match index_build {
+			Some(_) => {
+				// Same reads through the new index
+				let operation = BenchmarkOperation::SecondaryRead(
+					field.clone(),
+					self.samples,
+					ScanContext::WithIndex,
+				);
+				let with_index =
+					self.run_operation::<C, D>(clients, operation, kp, vp.clone(), samples).await?;
+				self.println_throughput_change(
+					"Indexed secondary reads",
+					"unindexed secondary reads",
+					&without_index,
+					&with_index,
+				);
+				let operation = BenchmarkOperation::RemoveIndex(spec, name);
+				let index_remove =
+					self.run_operation::<C, D>(&clients[..1], operation, kp, vp, 1).await?;
+				(with_index, index_remove)
+			}
+			None => (None, None),
+		};
+		Ok(Some(SecondaryReads {
+			field,
+			samples,
+			without_index,
+			index_build,
+			with_index,
+			index_remove,
+		}))
+	}
+
+	/// Links every record to `fanout` other records, through graph edges or an edge
+	/// table, then reads the records linked from each record by following the links.
+	async fn run_relations<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+		fanout: u32,
+	) -> Result<Option<Relations>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		self.bench_ui.section_header(&format!("Relations · {fanout} per record"));
+		// Create the table for the edges before timing anything
+		let (relate, traverse) = match clients[0].setup_relations().await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Relations are not supported, skipping");
+				(None, None)
+			}
+			Err(e) => return Err(e),
+			Ok(_) => {
+				let operation = BenchmarkOperation::Relate(fanout, self.samples);
+				let relate = self
+					.run_operation::<C, D>(clients, operation, kp, vp.clone(), self.samples)
+					.await?;
+				let operation = BenchmarkOperation::Traverse(fanout);
+				let traverse =
+					self.run_operation::<C, D>(clients, operation, kp, vp, self.samples).await?;
+				(relate, traverse)
+			}
+		};
+		Ok(Some(Relations {
+			fanout,
+			samples: self.samples,
+			relate,
+			traverse,
+		}))
+	}
+
+	/// Opens `subscribers` live queries, then writes a stream of events, measuring
+	/// how long after each event was written every live query is notified of it.
+	async fn run_live_queries<C, D, E>(
+		&self,
+		engine: &E,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+		subscribers: u32,
+	) -> Result<Option<LiveQueries>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+		E: BenchmarkEngine<C> + Send + Sync,
+	{
+		self.bench_ui.section_header(&format!("Live queries · {subscribers} subscribers"));
+		// Open the live queries before any event is written
+		let streams = match self.open_live_queries(engine, clients[0].as_ref(), subscribers).await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Live queries are not supported, skipping");
+				return Ok(None);
+			}
+			streams => streams?,
+		};
+		// Each live query listens until it has been notified of every
+		// event, or until it is closed once the writes have finished
+		let expected = self.samples as u64;
+		let (close, closed) = oneshot::channel::<()>();
+		let closed = closed.shared();
+		let listeners: Vec<_> = streams
+			.into_iter()
+			.map(|stream| {
+				let mut stream = stream.take_until(closed.clone());
+				task::spawn(async move {
+					let mut histogram = Histogram::<u64>::new(3)?;
+					while histogram.len() < expected
+						&& let Some(sent) = stream.next().await
+					{
+						histogram.record(unix_micros().saturating_sub(sent?))?;
+					}
+					Ok::<_, anyhow::Error>(histogram)
+				})
+			})
+			.collect();
+		// Write the events while the live queries are open
+		let operation = BenchmarkOperation::AppendLiveEvent;
+		let writes = self.run_operation::<C, D>(clients, operation, kp, vp, self.samples).await?;
+		// Give the notifications still in flight a moment to arrive
+		task::spawn(async move {
+			tokio::time::sleep(LIVE_QUERY_GRACE).await;
+			let _ = close.send(());
+		});
+		let mut latency = Histogram::<u64>::new(3)?;
+		for listener in listeners {
+			latency.add(listener.await??)?;
+		}
+		let result = LiveQueries::new(subscribers, writes, expected * subscribers as u64, &latency);
+		self.bench_ui.println_muted(&result.to_string());
+		Ok(Some(result))
+	}
+
+	/// Backs the datastore up with its native tool, then restores the backup into a fresh
+	/// database, timing both, and removes the backup and the restored database afterwards.
+	async fn run_backup<C>(&self, client: &C) -> Result<Option<Backup>>
+	where
+		C: BenchmarkClient + Send + Sync,
+	{
+		self.bench_ui.section_header("Backup and restore");
+		let time = Instant::now();
+		let size = match client.backup().await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Backups are not supported, skipping");
+				return Ok(None);
+			}
+			size => size?,
+		};
+		let backup = time.elapsed();
+		let took = match size {
+			Some(size) => format!("{}, {}", format_duration(backup), ByteSize(size)),
+			None => format_duration(backup),
+		};
+		self.bench_ui.println_took_head("Backup", &took);
+		let time = Instant::now();
+		let restore = match client.restore().await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Restores are not supported, skipping");
+				Ok(None)
+			}
+			Err(e) => Err(e),
+			Ok(()) => Ok(Some(time.elapsed())),
+		};
+		// The backup is removed even when the restore failed
+		client.remove_backup().await?;
+		let restore = restore?;
+		if let Some(restore) = restore {
+			self.bench_ui.println_took_head("Restore", &format_duration(restore));
+		}
+		Ok(Some(Backup {
+			backup,
+			size,
+			restore,
+		}))
+	}
+
+	/// Writes to the primary while a replica is polled for the writes, measuring how long
+	/// after each write was made it became visible on the replica.
+	async fn run_replication_lag<C, D, E>(
+		&self,
+		engine: &E,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<Option<ReplicationLag>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+		E: BenchmarkEngine<C> + Send + Sync,
+	{
+		self.bench_ui.section_header("Replication lag");
+		// Connect to the replica, and create the table before any write is made
+		let setup = async {
+			let replica = engine.create_replica_client().await?;
+			clients[0].setup_replication_lag().await?;
+			Ok::<_, anyhow::Error>(replica)
+		};
+		let replica = match setup.await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Reading from a replica is not supported, skipping");
+				return Ok(None);
+			}
+			replica => replica?,
+		};
+		// The replica is polled until every write is visible on
+		// it, or until a while after the writes have finished
+		let expected = self.samples as u64;
+		let written = AtomicBool::new(false);
+		let poll = async {
+			let mut lag = Histogram::<u64>::new(3)?;
+			// The writes below `from` have all been seen, and those in `seen` beyond it
+			let mut from = 0;
+			let mut seen = HashSet::new();
+			let mut deadline = None;
+			while lag.len() < expected {
+				if written.load(Ordering::Relaxed) {
+					let deadline =
+						*deadline.get_or_insert_with(|| Instant::now() + REPLICATION_GRACE);
+					if Instant::now() >= deadline {
+						break;
+					}
+				}
+				let rows = replica.read_replicated(from).await?;
+				let now = unix_micros();
+				for (id, sent) in rows {
+					if seen.insert(id) {
+						lag.record(now.saturating_sub(sent))?;
+					}
+				}
+				while seen.remove(&from) {
+					from += 1;
+				}
+				// Leave the replica time to apply more writes before reading it again
+				tokio::time::sleep(REPLICATION_POLL).await;
+			}
+			Ok::<_, anyhow::Error>(lag)
+		};
+		// Make the writes while the replica is polled
+		let write = async {
+			let operation = BenchmarkOperation::AppendReplicated;
+			let writes = self.run_operation::<C, D>(clients, operation, kp, vp, self.samples).await;
+			written.store(true, Ordering::Relaxed);
+			writes
+		};
+		let (writes, lag) = futures::join!(write, poll);
+		let result = ReplicationLag::new(writes?, expected, &lag?);
+		self.bench_ui.println_muted(&result.to_string());
+		Ok(Some(result))
+	}
+
+	/// Creates the table for the events, and opens `subscribers` live queries on it.
+	async fn open_live_queries<C, E>(
+		&self,
+		engine: &E,
+		client: &C,
+		subscribers: u32,
+	) -> Result<Vec<LiveStream>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		E: BenchmarkEngine<C> + Send + Sync,
+	{
+		client.setup_live_queries().await?;
+		let mut streams = Vec::with_capacity(subscribers as usize);
+		for _ in 0..subscribers {
+			streams.push(engine.subscribe().await?);
+		}
+		Ok(streams)
+	}
+
+	/// Repeats the updates once per `--sync-intervals` entry, syncing the datastore after
+	/// every Nth write, and prints the throughput of each interval against the plain updates.
+	async fn run_synced_updates<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+		updates: &Option<OperationResult>,
+	) -> Result<Vec<(u32, Option<OperationResult>)>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		if self.sync_intervals.is_empty() {
+			return Ok(vec![]);
+		}
+		// Check the datastore exposes an explicit sync before timing anything
+		let supported = match clients[0].sync().await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Sync barriers are not supported, skipping");
+				false
+			}
+			Err(e) => return Err(e),
+			Ok(_) => true,
+		};
+		let mut results = Vec::with_capacity(self.sync_intervals.len());
+		for &interval in &self.sync_intervals {
+			let result = match supported {
+				true => {
+					let result = self
+						.run_operation::<C, D>(
+							clients,
+							BenchmarkOperation::SyncedUpdate(interval),
+							kp,
+							vp.clone(),
+							self.samples,
+						)
+						.await?;
+					// Output the throughput compared to the unsynced updates
+					let name = format!("Sync every {interval}");
+					self.println_throughput_change(&name, "updates", updates, &result);
+					result
+				}
+				false => None,
+			};
+			results.push((interval, result));
+		}
+		Ok(results)
+	}
+
+	/// Runs the table scan of each `--tombstone-scans` entry again after the deletes, while the
+	/// deleted records are still tombstones or dead tuples, returning each leg with the index of
+	/// its scan result, and printing how its throughput changed from the scan before the deletes.
+	async fn run_tombstone_scans<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		scans: &[Scan],
+		scan_results: &[ScanResult],
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<Vec<(usize, ScanRun)>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		self.bench_ui.section_header("Scans after deletes");
+		let mut runs = Vec::with_capacity(self.tombstone_scans.len());
+		for (index, scan) in scans.iter().enumerate() {
+			if scan.skip || !self.tombstone_scans.contains(&scan.id) {
+				continue;
+			}
+			self.bench_ui.println_scan_run(&scan.name);
+			let samples = scan.samples.map(|s| s as u32).unwrap_or(self.samples);
+			let operation = BenchmarkOperation::Scan(scan.unchecked(), ScanContext::WithoutIndex);
+			let result = self
+				.run_operation::<C, D>(
+					scan.select_clients(clients),
+					operation,
+					kp,
+					vp.clone(),
+					samples,
+				)
+				.await?;
+			// Compare with the table scan before the deletes
+			let before = scan_results[index]
+				.runs
+				.iter()
+				.find(|r| matches!(r.workload, ScanWorkload::Read) && !r.indexed)
+				.and_then(|r| r.result.as_ref());
+			if let Some(change) =
+				before.zip(result.as_ref()).and_then(|(b, r)| throughput_change(b, r))
+			{
+				self.bench_ui.println_muted(&format!(
+					"Scan after deletes: {change:+.2}% OPS vs the scan before"
+				));
+			}
+			runs.push((
+				index,
+				ScanRun {
+					workload: ScanWorkload::AfterDeletes,
+					indexed: false,
+					hinted: false,
+					result,
+				},
+			));
+		}
+		Ok(runs)
+	}
+
+	/// Loads the records again, untimed, before each `--delete-strategies` entry, then deletes
+	/// them all at once, or in contiguous key ranges, with the bulk deletion of the datastore.
+	async fn run_delete_strategies<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<Vec<(String, Option<OperationResult>)>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		let mut results = Vec::with_capacity(self.delete_strategies.len());
+		for strategy in &self.delete_strategies {
+			let size = self.delete_range_size;
+			let (name, operation, deleters, samples) = match strategy {
+				DeleteStrategy::Truncate => {
+					("truncate".to_string(), BenchmarkOperation::Truncate, &clients[..1], 1)
+				}
+				DeleteStrategy::Range => {
+					let operation = BenchmarkOperation::DeleteRange(size, self.samples);
+					(format!("ranges of {size}"), operation, clients, self.samples.div_ceil(size))
+				}
+			};
+			// Random keys are spread over the key space, so no range holds only these records
+			let ordered = matches!(
+				kp,
+				KeyProvider::OrderedInteger(_)
+					| KeyProvider::OrderedString(_)
+					| KeyProvider::OrderedUuid(_)
+			);
+			if *strategy == DeleteStrategy::Range && !ordered {
+				self.bench_ui.println_muted("Range deletes need ordered keys, skipping");
+				results.push((name, None));
+				continue;
+			}
+			self.bench_ui.println_muted(&format!("Loading the records again, untimed, for {name}"));
+			let create = BenchmarkOperation::Create;
+			self.run_operation::<C, D>(clients, create, kp, vp.clone(), self.samples).await?;
+			let result =
+				self.run_operation::<C, D>(deleters, operation, kp, vp.clone(), samples).await?;
+			// The records are left behind by an unsupported deletion, so are deleted one by one
+			if result.is_none() {
+				let operation = BenchmarkOperation::Delete;
+				self.run_operation::<C, D>(clients, operation, kp, vp.clone(), self.samples)
+					.await?;
+			}
+			results.push((name, result));
+		}
+		Ok(results)
+	}
+
+	/// Walks the whole table in pages of `page_size` records, with `OFFSET` and then keyset
+	/// pagination, printing how much slower the last pages were than the first in each walk.
+	async fn run_pagination<C>(
+		&self,
+		client: &C,
+		kp: KeyProvider,
+		page_size: u32,
+	) -> Result<Vec<PageWalk>>
+	where
+		C: BenchmarkClient + Send + Sync,
+	{
+		self.bench_ui.section_header(&format!("Pagination · pages of {page_size}"));
+		let pages = self.base_records().div_ceil(page_size);
+		let mut walks = Vec::with_capacity(2);
+		for mode in [PageMode::Offset, PageMode::Keyset] {
+			let limit = page_size as usize;
+			let walk = match kp {
+				KeyProvider::OrderedInteger(_) | KeyProvider::UnorderedInteger(_) => {
+					let fetch = |s| client.scan_page_u32(s, limit);
+					self.walk_pages(mode, pages, page_size, fetch).await?
+				}
+				KeyProvider::OrderedString(_)
+				| KeyProvider::UnorderedString(_)
+				| KeyProvider::OrderedUuid(_)
+				| KeyProvider::UnorderedUuid(_) => {
+					let fetch = |s| client.scan_page_string(s, limit);
+					self.walk_pages(mode, pages, page_size, fetch).await?
+				}
+			};
+			if let Some(slowdown) = walk.slowdown() {
+				self.bench_ui.println_muted(&format!(
+					"Pagination · {mode}: last pages took {slowdown:.2}x as long as the first"
+				));
+			}
+			walks.push(walk);
+		}
+		Ok(walks)
+	}
+
+	/// Fetches `pages` pages one after another through `fetch`, timing each page, and leaves
+	/// the result empty when the datastore does not support paginated walks.
+	async fn walk_pages<K, F, R>(
+		&self,
+		mode: PageMode,
+		pages: u32,
+		page_size: u32,
+		fetch: F,
+	) -> Result<PageWalk>
+	where
+		F: Fn(PageStart<K>) -> R,
+		R: Future<Output = Result<Option<K>>>,
+	{
+		let mut walk = PageWalk {
+			mode,
+			page_size,
+			pages,
+			tenths: vec![],
+			result: None,
+		};
+		let metric = OperationMetric::new(self.pid, &self.cgroups, pages);
+		let mut histogram = Histogram::<u64>::new(3)?;
+		let mut latencies = Vec::with_capacity(pages as usize);
+		let mut cursor = None;
+		for page in 0..pages {
+			let start = match mode {
+				PageMode::Offset => PageStart::Offset(page as usize * page_size as usize),
+				PageMode::Keyset => PageStart::After(cursor.take()),
+			};
+			let time = Instant::now();
+			let last = match fetch(start).await {
+				Err(e) if BenchError::is_unsupported(&e) => {
+					self.bench_ui.println_muted("Paginated walks are not supported, skipping");
+					return Ok(walk);
+				}
+				last => last?,
+			};
+			let micros = time.elapsed().as_micros() as u64;
+			histogram.record(micros)?;
+			latencies.push(micros);
+			// Every page holds records until the walk reaches the end of the table
+			cursor = Some(last.with_context(|| format!("Page {page} of {pages} was empty"))?);
+		}
+		let result = OperationResult::new(metric, histogram, Default::default());
+		self.bench_ui.println_took_head(&format!("Page :: {mode}"), &result.total_time());
+		walk.tenths = PageWalk::tenths(&latencies);
+		walk.result = Some(result);
+		Ok(walk)
+	}
+
+	/// Grows the table through each of `--growth-sizes` in turn, loading only the records beyond
+	/// the previous size, and repeats the reads and table scans at every size.
+	async fn run_growth<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		scans: &[Scan],
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<Vec<GrowthStage>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		if self.growth_sizes.is_empty() {
+			return Ok(vec![]);
+		}
+		let mut stages = Vec::with_capacity(self.growth_sizes.len());
+		let mut records = self.base_records();
+		for &size in &self.growth_sizes {
+			self.bench_ui.section_header(&format!("Growth · {size} records"));
+			// Load the records beyond the previous stage
+			let operation = BenchmarkOperation::GrowRecords(records, size);
+			self.run_operation::<C, D>(clients, operation, kp, vp.clone(), size - records).await?;
+			records = size;
+			// Read keys spread evenly across all the records
+			let operation = BenchmarkOperation::ReadSpread(size);
+			let reads = self
+				.run_operation::<C, D>(clients, operation, kp, vp.clone(), self.samples)
+				.await?;
+			// Repeat the table scans, without building their indexes
+			let mut scan_results = Vec::with_capacity(scans.len());
+			for scan in scans.iter().filter(|s| !s.skip) {
+				let samples = scan.samples.map(|s| s as u32).unwrap_or(self.samples);
+				let operation = BenchmarkOperation::Scan(scan.clone(), ScanContext::WithoutIndex);
+				let clients = scan.select_clients(clients);
+				let result =
+					self.run_operation::<C, D>(clients, operation, kp, vp.clone(), samples).await?;
+				scan_results.push((format!("{} · {}", scan.id, scan.name), result));
+			}
+			stages.push(GrowthStage {
+				records: size,
+				reads,
+				scans: scan_results,
+			});
+		}
+		// Remove the grown records, so the later phases run on the samples alone
+		let base = self.base_records();
+		let operation = BenchmarkOperation::ShrinkRecords(base);
+		self.run_operation::<C, D>(clients, operation, kp, vp, records - base).await?;
+		Ok(stages)
+	}
+
+	/// Appends the time-series rows, then runs each configured query over windows of them,
+	/// returning one row per phase, left empty when the datastore does not support it.
+	async fn run_time_series<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		series: TimeSeries,
+		kp: KeyProvider,
+		vp: ValueProvider,
+	) -> Result<Vec<(String, u32, Option<OperationResult>)>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		self.bench_ui.section_header("Time series");
+		let rows = series.rows.unwrap_or(self.samples);
+		let mut results = Vec::with_capacity(1 + series.queries.len());
+		// Create the table before timing anything
+		let supported = match clients[0].setup_time_series().await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Time series are not supported, skipping");
+				false
+			}
+			Err(e) => return Err(e),
+			Ok(_) => true,
+		};
+		let series = Arc::new(series);
+		let append = match supported {
+			true => {
+				let operation = BenchmarkOperation::AppendTimeSeries(series.clone());
+				self.run_operation::<C, D>(clients, operation, kp, vp.clone(), rows).await?
+			}
+			false => None,
+		};
+		results.push(("append".to_string(), rows, append));
+		// Wait for the appended rows to become visible to queries
+		if supported {
+			let time = Instant::now();
+			clients[0].time_series_ready(rows).await?;
+			self.bench_ui.println_took_head("Time series ready", &format_duration(time.elapsed()));
+		}
+		for query in &series.queries {
+			let samples = query.samples.unwrap_or(self.samples);
+			let result = match supported {
+				true => {
+					let operation =
+						BenchmarkOperation::QueryTimeSeries(series.clone(), query.clone(), rows);
+					self.run_operation::<C, D>(clients, operation, kp, vp.clone(), samples).await?
+				}
+				false => None,
+			};
+			results.push((query.name.clone(), samples, result));
+		}
+		Ok(results)
+	}
+
+	/// Reads a sampled subset of keys sequentially, recording both the client latency
+	/// and the execution time reported by the engine for each read.
+	async fn run_server_timings<C>(
+		&self,
+		client: &C,
+		mut kp: KeyProvider,
+		samples: u32,
+	) -> Result<Option<LatencySplit>>
+	where
+		C: BenchmarkClient + Send + Sync,
+	{
+		let mut client_histogram = Histogram::<u64>::new(3)?;
+		let mut server_histogram = Histogram::<u64>::new(3)?;
+		// Spread the sampled reads evenly across the created keys
+		let step = (self.samples / samples.max(1)).max(1) as usize;
+		for n in (0..self.samples).step_by(step).take(samples as usize) {
+			let time = Instant::now();
+			let server = match client.read_server_time(n, &mut kp).await {
+				Err(e) if BenchError::is_unsupported(&e) => {
+					self.bench_ui.println_muted("Server timings are not supported, skipping");
+					return Ok(None);
+				}
+				server => server?,
+			};
+			client_histogram.record(time.elapsed().as_micros() as u64)?;
+			server_histogram.record(server.as_micros() as u64)?;
+		}
+		let split = LatencySplit::new(&client_histogram, &server_histogram);
+		self.bench_ui.println_muted(&split.to_string());
+		Ok(Some(split))
+	}
+
+	/// Copies the table aside before the scan phases, returning whether the datastore supports it.
+	async fn snapshot_table<C>(&self, client: &C) -> Result<bool>
+	where
+		C: BenchmarkClient + Send + Sync,
+	{
+		let time = Instant::now();
+		match client.snapshot_table().await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui
+					.println_muted("Table snapshots are not supported, scanning the live table");
+				Ok(false)
+			}
+			Err(e) => Err(e),
+			Ok(_) => {
+				self.bench_ui.println_took_head("Snapshot table", &format_duration(time.elapsed()));
+				Ok(true)
+			}
+		}
+	}
+
+	/// Repeats a scan, and fetches the page which follows it, comparing the returned ids
+	/// for duplicates and instability, or returns `None` when the datastore does not support it.
+	async fn run_scan_consistency<C>(
+		&self,
+		client: &C,
+		scan: &Scan,
+	) -> Result<Option<ScanConsistency>>
+	where
+		C: BenchmarkClient + Send + Sync,
+	{
+		let mut scans = Vec::with_capacity(SCAN_CONSISTENCY_REPEATS);
+		for _ in 0..SCAN_CONSISTENCY_REPEATS {
+			match client.scan_ids(scan).await {
+				Err(e) if BenchError::is_unsupported(&e) => {
+					self.bench_ui
+						.println_muted("Scan consistency checks are not supported, skipping");
+					return Ok(None);
 				}
-			};
-			scan_results.push(result);
+				ids => scans.push(ids?),
+			}
 		}
-		// Compact the datastore
-		self.maybe_compact_datastore::<C, E>(&engine).await?;
-		self.bench_ui.section_header("Delete");
-		// Run the "deletes" benchmark
-		let deletes = self
-			.run_operation::<C, D>(
-				&clients,
-				BenchmarkOperation::Delete,
-				kp,
-				vp.clone(),
-				self.samples,
-			)
-			.await?;
-		// Compact the datastore
-		self.maybe_compact_datastore::<C, E>(&engine).await?;
-		if !batches.is_empty() {
-			self.bench_ui.section_header("Batches");
+		let next_page = match scan.next_page() {
+			Some(page) => Some(client.scan_ids(&page).await?),
+			None => None,
+		};
+		let consistency = ScanConsistency::check(&scans, next_page.as_deref());
+		// Make inconsistent scans stand out from the muted notes
+		let line = format!("Scan ids: {consistency}");
+		match consistency.is_consistent() {
+			true => self.bench_ui.println_muted(&line),
+			false => self.bench_ui.println_plain(&line),
 		}
-		// Run the "batch" benchmarks
-		let mut batch_results = Vec::with_capacity(batches.len());
-		for batch in batches {
-			// Get the name of the batch operation
-			let name = batch.name.clone();
-			let groups = batch.batch_size;
-			let samples = batch.samples.map(|s| s as u32).unwrap_or(self.samples);
-			// Determine the batch operation type
-			let operation = match batch.operation {
-				crate::BatchOperationType::Create => BenchmarkOperation::BatchCreate(batch.clone()),
-				crate::BatchOperationType::Read => BenchmarkOperation::BatchRead(batch.clone()),
-				crate::BatchOperationType::Update => BenchmarkOperation::BatchUpdate(batch.clone()),
-				crate::BatchOperationType::Delete => BenchmarkOperation::BatchDelete(batch.clone()),
+		Ok(Some(consistency))
+	}
+
+	/// Creates the table for records with keys generated by the datastore, returning
+	/// the store for the keys it generates, or `None` when the datastore does not support it.
+	async fn setup_generated_keys<C>(&self, client: &C) -> Result<Option<Arc<GeneratedKeys>>>
+	where
+		C: BenchmarkClient + Send + Sync,
+	{
+		match client.setup_generated_keys().await {
+			Err(e) if BenchError::is_unsupported(&e) => {
+				self.bench_ui.println_muted("Generated keys are not supported, skipping");
+				Ok(None)
+			}
+			Err(e) => Err(e),
+			Ok(_) => Ok(Some(Arc::new(GeneratedKeys::default()))),
+		}
+	}
+
+	/// Repeats a read scan leg with its predicate perturbed per sample, when `--detect-scan-cache`
+	/// is set and the scan has a predicate, and reports how much faster the identical leg was.
+	async fn run_perturbed_scan<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		scan: &Scan,
+		ctx: ScanContext,
+		(kp, vp, samples): (KeyProvider, ValueProvider, u32),
+		identical: &Option<OperationResult>,
+	) -> Result<Option<ScanRun>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		if !self.detect_scan_cache || !scan.has_condition() {
+			return Ok(None);
+		}
+		let operation = BenchmarkOperation::ScanPerturbed(scan.clone(), ctx);
+		let result = self.run_operation::<C, D>(clients, operation, kp, vp, samples).await?;
+		let speedup = identical
+			.as_ref()
+			.zip(result.as_ref())
+			.and_then(|(i, p)| (p.ops() > 0.0).then(|| i.ops() / p.ops()));
+		if let Some(speedup) = speedup {
+			let verdict = match speedup >= CACHE_HIT_SPEEDUP {
+				true => ", mostly cache hits",
+				false => "",
 			};
-			// Execute the batch benchmark
-			let duration =
-				self.run_operation::<C, D>(&clients, operation, kp, vp.clone(), samples).await?;
-			// Store the batch benchmark result
-			batch_results.push((name, samples, groups, duration));
+			self.bench_ui.println_muted(&format!(
+				"Identical scan: {speedup:.2}× perturbed scan OPS{verdict}"
+			));
 		}
-		// Mark the benchmark as complete
-		if self.emit_phase_markers {
-			self.bench_ui.println_plain("Benchmark complete");
+		Ok(Some(ScanRun {
+			workload: ScanWorkload::Perturbed,
+			indexed: ctx == ScanContext::WithIndex,
+			hinted: false,
+			result,
+		}))
+	}
+
+	/// Prints the OPS change of a phase variant relative to its baseline phase, when both ran.
+	fn println_throughput_change(
+		&self,
+		name: &str,
+		baseline_name: &str,
+		baseline: &Option<OperationResult>,
+		result: &Option<OperationResult>,
+	) {
+		if let Some(change) =
+			baseline.as_ref().zip(result.as_ref()).and_then(|(b, r)| throughput_change(b, r))
+		{
+			self.bench_ui.println_muted(&format!("{name}: {change:+.2}% OPS vs {baseline_name}"));
 		}
-		// Shut down the datastore
-		self.wait_for_client(&engine).await?.shutdown().await?;
-		// Return the benchmark results
-		Ok(BenchmarkResult {
-			database,
-			system,
-			metadata,
-			creates,
-			reads,
-			updates,
-			scans: scan_results,
-			batches: batch_results,
-			deletes,
-			sample,
-		})
 	}
 
-	/// Polls until [`BenchmarkEngine::create_client`] succeeds or [`TIMEOUT`] elapses.
-	async fn wait_for_client<C, E>(&self, engine: &E) -> Result<C>
+	/// Builds the index of a scan. With `--index-build-writes`, the updates first run on the
+	/// idle table and then again while the index builds, returning both with the build.
+	async fn run_index_build<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		scan_clients: &[Arc<C>],
+		spec: &Index,
+		id: &str,
+		kp: KeyProvider,
+		vp: &ValueProvider,
+	) -> Result<(Option<OperationResult>, Option<OperationResult>, Option<OperationResult>)>
 	where
 		C: BenchmarkClient + Send + Sync,
-		E: BenchmarkEngine<C> + Send + Sync,
+		D: Dialect,
 	{
-		// Get the current system time
-		let time = SystemTime::now();
-		// Get the timeout for the engine
-		let wait = engine.wait_timeout();
-		// Check the elapsed time
-		while time.elapsed()? < TIMEOUT {
-			// Wait for a small amount of time
-			if let Some(wait) = wait {
-				tokio::time::sleep(wait).await
-			};
-			// Attempt to create a client connection
-			match engine.create_client().await {
-				Err(e) => debug!("Received error: {e}"),
-				Ok(c) => return Ok(c),
+		// BuildIndex uses a single client to avoid races on DDL
+		let build = BenchmarkOperation::BuildIndex(spec.clone(), id.to_string());
+		let Some(writes) = self.index_build_writes else {
+			let index_build =
+				self.run_operation::<C, D>(&clients[..1], build, kp, vp.clone(), 1).await?;
+			return Ok((index_build, None, None));
+		};
+		// Rewrite the values of the updates phase, so the scans read unchanged records
+		let updated = vp.round(UPDATE_ROUND);
+		let writes = writes.min(self.samples);
+		let operation = BenchmarkOperation::Update;
+		let idle_writes = self
+			.run_operation::<C, D>(scan_clients, operation, kp, updated.clone(), writes)
+			.await?;
+		let operation = BenchmarkOperation::UpdateDuringBuild;
+		let (index_build, build_writes) = tokio::try_join!(
+			self.run_operation::<C, D>(&clients[..1], build, kp, vp.clone(), 1),
+			self.run_operation::<C, D>(scan_clients, operation, kp, updated, writes),
+		)?;
+		// Updates during an unsupported build are not comparable
+		if index_build.is_none() {
+			return Ok((None, None, None));
+		}
+		if let Some(change) = idle_writes
+			.as_ref()
+			.zip(build_writes.as_ref())
+			.and_then(|(idle, build)| latency_change(idle, build))
+		{
+			let line =
+				format!("Updates during index build: {change:+.2}% p99 latency vs idle table");
+			self.bench_ui.println_muted(&line);
+		}
+		Ok((index_build, idle_writes, build_writes))
+	}
+
+	/// Loops the mixed reads and updates for `duration`, one phase per soak window, writing the
+	/// windows completed so far after each one.
+	async fn run_soak<C, D>(
+		&self,
+		clients: &[Arc<C>],
+		kp: KeyProvider,
+		vp: ValueProvider,
+		duration: Duration,
+	) -> Result<Vec<SoakWindow>>
+	where
+		C: BenchmarkClient + Send + Sync,
+		D: Dialect,
+	{
+		self.bench_ui.section_header("Soak");
+		let start = Instant::now();
+		let end = start + duration;
+		let mut windows = Vec::new();
+		while Instant::now() < end {
+			// The last window stops with the soak
+			let deadline = (Instant::now() + self.soak_interval).min(end);
+			let operation = BenchmarkOperation::Soak(deadline, self.samples);
+			// The window runs until its deadline, so its samples are only bounded by that
+			let result =
+				self.run_operation::<C, D>(clients, operation, kp, vp.clone(), u32::MAX).await?;
+			let skipped = result.is_none();
+			windows.push(SoakWindow {
+				elapsed: start.elapsed().as_secs(),
+				result,
+			});
+			soak::flush(&self.soak_output, &windows)?;
+			// Reads and updates are unsupported, so every window would be skipped
+			if skipped {
+				break;
 			}
 		}
-		bail!("Can't create the client")
+		self.bench_ui.println_muted(&format!("Soak windows saved to: {}.html", self.soak_output));
+		Ok(windows)
 	}
 
-	/// Creates one async connection per logical client; returns shared handles for workers.
-	async fn setup_clients<C, E>(&self, engine: &E) -> Result<Vec<Arc<C>>>
+	/// Sets up the restricted role, then opens one restricted connection per logical client.
+	async fn setup_restricted_clients<C, E>(&self, engine: &E) -> Result<Vec<Arc<C>>>
 	where
 		C: BenchmarkClient + Send + Sync,
 		E: BenchmarkEngine<C> + Send + Sync,
 	{
-		// Create a set of client connections
-		let mut clients = Vec::with_capacity(self.clients as usize);
-		// Create the desired number of connections
-		for i in 0..self.clients {
-			// Log some information
-			info!("Creating client {}", i + 1);
-			// Create a new client connection
-			clients.push(engine.create_client());
-		}
+		// Configure the role and permissions as the privileged user
+		self.wait_for_client(engine).await?.setup_restricted_reads().await?;
+		// Create the desired number of restricted connections
+		let clients = (0..self.clients).map(|_| engine.create_restricted_client());
 		// Wait for all the clients to connect
 		Ok(try_join_all(clients).await?.into_iter().map(Arc::new).collect())
 	}
@@ -498,60 +2459,143 @@ impl Benchmark {
 		if self.emit_phase_markers {
 			self.bench_ui.println_plain(&format!("{operation} starting"));
 		}
-		let progress =
-			self.bench_ui.progress_bar(samples as u64, &progress_short_label(&operation));
+		// Only the index build running alongside these updates shows a progress bar
+		let progress = match operation {
+			// A soak window runs until its deadline rather than for its samples
+			BenchmarkOperation::UpdateDuringBuild | BenchmarkOperation::Soak(_, _) => None,
+			_ => self.bench_ui.progress_bar(samples as u64, &progress_short_label(&operation)),
+		};
+		// Scans and batches may run with fewer or more tasks per client than the other phases
+		let (threads, rate) = operation.overrides();
+		let threads = threads.unwrap_or(self.threads);
 		// Get the total concurrent futures
-		let total = (self.clients * self.threads) as usize;
+		let total = clients.len() * threads as usize;
 		// Whether we have experienced an error
 		let error = Arc::new(AtomicBool::new(false));
 		// Wether the test should be skipped
 		let skip = Arc::new(AtomicBool::new(false));
+		// The failed operations, per error category
+		let errors = Arc::new(ErrorCounts::default());
+		// The retried operations, per error category
+		let retried = Arc::new(ErrorCounts::default());
+		// How the failed operations of this phase are retried
+		let policy = self.retries.policy(operation.retry_phase());
+		// The completed and failed samples per second, when failures do not fail the phase
+		let timeline = self.continue_on_error.then(|| Arc::new(ErrorTimeline::new()));
 		// The total records processed so far
 		let current = Arc::new(AtomicU32::new(0));
 		// The total records processed so far
 		let complete = Arc::new(AtomicU32::new(0));
 		// Store the futures in a vector
 		let mut futures = Vec::with_capacity(total);
+		// The client and thread of each future
+		let mut worker_ids = Vec::with_capacity(total);
+		// Start the phase together with the other agents of a coordinated run, apart from the
+		// updates which run alongside an index build, which start with the build
+		if !matches!(operation, BenchmarkOperation::UpdateDuringBuild) {
+			agent::barrier(&operation.to_string()).await?;
+		}
+		// Spread the samples of a rate-limited phase evenly over time, or let them arrive at random
+		// at the arrival rate whether or not the earlier samples completed
+		let pacing = match (rate, self.arrival_rate) {
+			(Some(rate), _) => Some(Pacing::Even {
+				start: Instant::now(),
+				interval: Duration::from_secs_f64(1.0 / rate),
+			}),
+			(None, Some(rate)) if operation.open_loop() => {
+				Some(Pacing::Open(Arc::new(Arrivals::new(rate, self.seed)?)))
+			}
+			_ => None,
+		};
+		// Snapshot the profile, to write a flamegraph of this phase alone
+		let profile = PhaseProfile::start();
+		let recording = self.server_profiler.as_ref().and_then(ServerProfiler::start);
 		// Measure the starting time
-		let metric = OperationMetric::new(self.pid, samples);
+		let mut metric = OperationMetric::new(self.pid, &self.cgroups, samples);
+		// Tighten the operation timeout once the first samples have completed
+		let timeout = Arc::new(AdaptiveTimeout::new(
+			self.operation_timeout,
+			self.timeout_multiplier,
+			samples,
+		));
+		// Name the samples of this operation in the raw latency stream
+		let label: Arc<str> = match &operation {
+			BenchmarkOperation::Scan(scan, _)
+			| BenchmarkOperation::ScanPerturbed(scan, _)
+			| BenchmarkOperation::ScanWithWrites(scan, _, _) => {
+				format!("{operation} · {} · {}", scan.id, scan.name).into()
+			}
+			_ => operation.to_string().into(),
+		};
+		// Tag the log events of every worker with this phase
+		let phase = error_span!("phase", phase = %label, samples);
+		phase.in_scope(|| info!("Phase starting"));
 		// Loop over the clients
-		for (client, _) in clients.iter().cloned().zip(1..) {
+		for (client, client_id) in clients.iter().cloned().zip(1..) {
 			// Loop over the threads
-			for _ in 0..self.threads {
+			for thread_id in 1..=threads {
 				let error = error.clone();
 				let skip = skip.clone();
+				let errors = errors.clone();
+				let retried = retried.clone();
+				let policy = policy.clone();
+				let timeline = timeline.clone();
+				let pacing = pacing.clone();
 				let current = current.clone();
 				let complete = complete.clone();
 				let client = client.clone();
 				let progress = progress.clone();
-				let vp = vp.clone();
+				// Each worker generates its own sequence of seeded values
+				let vp = vp.worker(((client_id - 1) * threads + thread_id) as u64);
 				let operation = operation.clone();
-				let operation_timeout = self.operation_timeout;
-				futures.push(task::spawn(async move {
-					match Self::operation_loop::<C, D>(
-						client,
-						samples,
-						&error,
-						&current,
-						&complete,
-						operation,
-						operation_timeout,
-						(kp, vp, progress),
-					)
-					.await
-					{
-						Err(e) if e.to_string().eq(NOT_SUPPORTED_ERROR) => {
-							skip.store(true, Ordering::Relaxed);
-							Ok(None)
-						}
-						Err(e) => {
-							eprintln!("{e}");
-							error.store(true, Ordering::Relaxed);
-							Err(e)
+				let timeout = timeout.clone();
+				let shard = self.shard;
+				let latencies = self
+					.raw_latencies
+					.as_ref()
+					.map(|raw| raw.sink(label.clone(), client_id, thread_id));
+				let worker =
+					error_span!(parent: &phase, "worker", client = client_id, thread = thread_id);
+				worker_ids.push((client_id, thread_id));
+				futures.push(task::spawn(
+					async move {
+						match Self::operation_loop::<C, D>(
+							client.clone(),
+							samples,
+							&error,
+							&current,
+							&complete,
+							operation,
+							&timeout,
+							WorkerState {
+								kp,
+								vp,
+								progress,
+								latencies,
+								pacing,
+								shard,
+							},
+							(&policy, &retried, timeline.as_deref().map(|t| (&*errors, t))),
+						)
+						.await
+						{
+							Err(e) if BenchError::is_unsupported(&e) => {
+								skip.store(true, Ordering::Relaxed);
+								Ok(None)
+							}
+							Err(e) => {
+								let category =
+									BenchError::of(&e).or_else(|| client.classify_error(&e));
+								errors.record(category);
+								error!(category = category.map(BenchError::name), "{e:#}");
+								error.store(true, Ordering::Relaxed);
+								Err(e)
+							}
+							Ok(h) => Ok(Some(h)),
 						}
-						Ok(h) => Ok(Some(h)),
 					}
-				}));
+					.instrument(worker),
+				));
 			}
 		}
 		// Wait for all the threads to complete
@@ -562,11 +2606,20 @@ impl Benchmark {
 			pb.set_position(samples as u64);
 			pb.finish_and_clear();
 		}
+		// Summarise the failed operations of the phase per error category
+		if error.load(Ordering::Relaxed) {
+			let failed = errors.snapshot();
+			phase.in_scope(|| error!(errors = %failed, "Phase failed"));
+		}
+		let mut workers = Vec::new();
 		match join {
 			Ok(results) => {
 				// Merge per-worker HDR histograms into one distribution for this phase
-				for res in results {
+				for ((client, thread), res) in worker_ids.into_iter().zip(results) {
 					if let Some(histogram) = res? {
+						if self.worker_histograms {
+							workers.push(WorkerLatency::new(client, thread, &histogram));
+						}
 						global_histogram.add(histogram)?;
 					}
 				}
@@ -578,15 +2631,40 @@ impl Benchmark {
 			}
 		};
 		if error.load(Ordering::Relaxed) {
-			bail!("Task failure");
+			bail!("Task failure ({})", errors.snapshot());
+		}
+		// An agent of a coordinated run only ran its shard of the samples
+		if let Some(shard) = self.shard {
+			metric.completed(shard.samples(samples));
+		}
+		// A soak window completed as many samples as it ran before its deadline
+		if let BenchmarkOperation::Soak(_, _) = operation {
+			metric.completed(global_histogram.len().min(u32::MAX as u64) as u32);
+		}
+		// The failed samples of a phase which continued past them did not complete
+		if timeline.is_some() && !matches!(operation, BenchmarkOperation::Soak(_, _)) {
+			let failed = errors.snapshot().total().min(u32::MAX as u64) as u32;
+			let ran = self.shard.map_or(samples, |shard| shard.samples(samples));
+			metric.completed(ran.saturating_sub(failed));
 		}
 		// Histogram + sysinfo snapshots → OperationResult; then print phase timing line
-		let result = OperationResult::new(metric, global_histogram);
+		let mut result = OperationResult::new(metric, global_histogram, errors.snapshot())
+			.with_workers(workers)
+			.with_retries(retried.snapshot())
+			.with_error_rate(timeline.map(|t| t.windows()).unwrap_or_default())
+			.with_queueing(match &pacing {
+				Some(Pacing::Open(arrivals)) => Some(arrivals.queueing()),
+				_ => None,
+			});
 		let took = result.total_time();
 		match &operation {
 			BenchmarkOperation::Scan(_, ctx) => {
 				self.bench_ui.println_took_scan(scan_context_slug(*ctx), None, &took);
 			}
+			BenchmarkOperation::ScanPerturbed(_, ctx) => {
+				let ctx = format!("{} (perturbed)", scan_context_slug(*ctx));
+				self.bench_ui.println_took_scan(&ctx, None, &took);
+			}
 			BenchmarkOperation::ScanWithWrites(_, ctx, spec) => {
 				self.bench_ui.println_took_scan(
 					scan_context_slug(*ctx),
@@ -601,8 +2679,68 @@ impl Benchmark {
 		}
 		// Shall we skip the operation? (operation not supported)
 		if skip.load(Ordering::Relaxed) {
+			phase.in_scope(|| info!("Phase skipped as it is not supported"));
 			return Ok(None);
 		}
+		// Write the flamegraphs of the phase, when it is profiled
+		if let Some(profile) = profile {
+			profile.finish(&label);
+		}
+		if let (Some(profiler), Some(recording)) = (&self.server_profiler, recording) {
+			profiler.finish(recording, &label);
+		}
+		// Show how long the arrivals of an open-loop phase waited for a worker
+		if let Some(queueing) = result.queueing() {
+			self.bench_ui.println_muted(&format!(
+				"Queueing at {:.0} ops/s: mean {:.2} ms, p50 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+				queueing.rate,
+				queueing.mean / 1000.0,
+				queueing.q50 as f64 / 1000.0,
+				queueing.q99 as f64 / 1000.0,
+				queueing.max as f64 / 1000.0,
+			));
+		}
+		// Show how many operations failed without failing the phase
+		if let Some(peak) = result.error_rate().iter().map(ErrorWindow::rate).reduce(f64::max) {
+			let failed = result.errors().total();
+			if failed > 0 {
+				let total = failed + result.error_rate().iter().map(|w| w.ops).sum::<u64>();
+				self.bench_ui.println_muted(&format!(
+					"Errors: {failed} of {total} ({:.2}%, peak {:.2}%/s): {}",
+					failed as f64 * 100.0 / total as f64,
+					peak * 100.0,
+					result.errors(),
+				));
+			}
+		}
+		// Show how many operations only succeeded once retried
+		if !result.retries().is_empty() {
+			self.bench_ui.println_muted(&format!("Retries: {}", result.retries()));
+		}
+		// Show the imbalance between the slowest and fastest workers
+		if let Some((slowest, fastest)) = result.worker_spread() {
+			self.bench_ui.println_muted(&format!(
+				"Worker p99: slowest {:.2} ms (client {}, thread {}), fastest {:.2} ms (client {}, thread {})",
+				slowest.q99 as f64 / 1000.0,
+				slowest.client,
+				slowest.thread,
+				fastest.q99 as f64 / 1000.0,
+				fastest.client,
+				fastest.thread,
+			));
+		}
+		// Warn when the clients, rather than the datastore, limited the phase
+		if self.monitors_datastore() && result.flag_client_bound(self.datastore_capacity()) {
+			let backpressure = result.backpressure();
+			self.bench_ui.println_warning(&format!(
+				"{operation} was client-bound: the client runtime was {:.0}% busy with {:.1} tasks queued on average, while the datastore used {:.1}% CPU. Raise --workers or run the clients on another host.",
+				backpressure.utilisation * 100.0,
+				backpressure.queue_avg,
+				result.cpu_avg(),
+			));
+			phase.in_scope(|| warn!("Phase was client-bound"));
+		}
+		phase.in_scope(|| info!(took = %took, "Phase complete"));
 		// Everything ok
 		Ok(Some(result))
 	}
@@ -616,14 +2754,30 @@ impl Benchmark {
 		current: &AtomicU32,
 		complete: &AtomicU32,
 		operation: BenchmarkOperation,
-		operation_timeout: Duration,
-		(mut kp, mut vp, progress): (KeyProvider, ValueProvider, Option<Arc<ProgressBar>>),
+		timeout: &AdaptiveTimeout,
+		WorkerState {
+			mut kp,
+			mut vp,
+			progress,
+			latencies,
+			pacing,
+			shard,
+		}: WorkerState,
+		(policy, retried, failures): (
+			&RetryPolicy,
+			&ErrorCounts,
+			Option<(&ErrorCounts, &ErrorTimeline)>,
+		),
 	) -> Result<Histogram<u64>>
 	where
 		C: BenchmarkClient,
 		D: Dialect,
 	{
 		let mut histogram = Histogram::new(3)?;
+		// The samples which completed and failed in each second, merged into the phase at the end
+		let mut windows = Vec::new();
+		// The time each open-loop sample queued for this worker, merged into the phase at the end
+		let mut queueing = Histogram::<u64>::new(3)?;
 		// Check if we have encountered an error
 		while !error.load(Ordering::Relaxed) {
 			// Get the current sample number
@@ -633,6 +2787,34 @@ impl Benchmark {
 				// We are done
 				break;
 			}
+			// Leave the samples of the other shards to the other agents
+			if shard.is_some_and(|s| !s.owns(sample)) {
+				continue;
+			}
+			// Has the soak window ended
+			if let BenchmarkOperation::Soak(deadline, _) = &operation
+				&& Instant::now() >= *deadline
+			{
+				break;
+			}
+			// Wait for the slot of this sample in a rate-limited phase
+			let arrival = match &pacing {
+				Some(pacing) => pacing.wait(sample).await,
+				None => None,
+			};
+			// Vary the predicate outside the timed section
+			let perturbed = match &operation {
+				BenchmarkOperation::ScanPerturbed(s, _) => Some(s.perturbed(sample)),
+				_ => None,
+			};
+			// Pick the queried time window, and the results it holds, outside the timed section
+			let window = match &operation {
+				BenchmarkOperation::QueryTimeSeries(series, query, rows) => {
+					let window = series.window(query, *rows, sample);
+					Some((window, series.expected(&window, *rows)))
+				}
+				_ => None,
+			};
 			// Perform the benchmark operation under a per-iteration
 			// timeout. A stuck `await` inside the underlying SDK
 			// (e.g. a WebSocket reply that never lands because the
@@ -641,60 +2823,269 @@ impl Benchmark {
 			// parking the worker task forever; `try_join_all` then
 			// short-circuits with the operation name in the error
 			// chain rather than hanging in `block_on`.
+			let limit = timeout.current();
+			let start = latencies.as_ref().map(|_| SystemTime::now());
 			let time = Instant::now();
-			tokio::time::timeout(operation_timeout, async {
-				match &operation {
-					BenchmarkOperation::Create => {
-						let value = vp.generate_value();
-						client.create(sample, value, &mut kp).await
-					}
-					BenchmarkOperation::Read => client.read(sample, &mut kp).await.map(|_| ()),
-					BenchmarkOperation::Update => {
-						let value = vp.generate_value();
-						client.update(sample, value, &mut kp).await
-					}
-					BenchmarkOperation::Scan(s, ctx) => client.scan(s, &kp, *ctx).await,
-					BenchmarkOperation::ScanWithWrites(scan, ctx, spec) => {
-						workloads::run_scan_with_writes(
-							&*client, scan, *ctx, spec, sample, samples, &mut kp,
-						)
-						.await
-					}
-					BenchmarkOperation::BuildIndex(spec, name) => {
-						client.build_index(spec, name.as_str()).await
-					}
-					BenchmarkOperation::RemoveIndex(name) => client.drop_index(name.as_str()).await,
-					BenchmarkOperation::Delete => client.delete(sample, &mut kp).await,
-					BenchmarkOperation::BatchCreate(batch_op) => {
-						client.batch_create(sample, batch_op, &mut kp, &mut vp).await
-					}
-					BenchmarkOperation::BatchRead(batch_op) => {
-						client.batch_read(sample, batch_op, &mut kp).await
-					}
-					BenchmarkOperation::BatchUpdate(batch_op) => {
-						client.batch_update(sample, batch_op, &mut kp, &mut vp).await
+			// The latency of a retried sample covers every attempt, and the backoff between them
+			let mut attempt = 0;
+			let failure = loop {
+				let res = tokio::time::timeout(limit, async {
+					match &operation {
+						BenchmarkOperation::Create => {
+							let value = vp.generate_value_for(sample);
+							client.create(sample, value, &mut kp).await
+						}
+						BenchmarkOperation::Read | BenchmarkOperation::RestrictedRead => {
+							client.read(sample, &mut kp).await.map(|_| ())
+						}
+						BenchmarkOperation::SecondaryRead(field, records, _) => {
+							// Spread the reads evenly across all the records
+							let n = sample as u64 * *records as u64 / samples as u64;
+							let value = unique_value(n as u32);
+							let count = client.read_secondary(field, value.clone()).await?;
+							ensure!(
+								count == 1,
+								"Expected 1 record with {field} = {value}, found {count}"
+							);
+							Ok(())
+						}
+						BenchmarkOperation::AppendLiveEvent => {
+							client.append_live_event(sample, unix_micros()).await
+						}
+						BenchmarkOperation::AppendReplicated => {
+							client.append_replicated(sample, unix_micros()).await
+						}
+						BenchmarkOperation::Relate(fanout, records) => {
+							let targets = relation_targets(sample, *fanout, *records);
+							client.relate(sample, &targets, &mut kp).await
+						}
+						BenchmarkOperation::Traverse(fanout) => {
+							let count = client.traverse(sample, &mut kp).await?;
+							ensure!(
+								count == *fanout as usize,
+								"Expected {fanout} records linked from record {sample}, found {count}"
+							);
+							Ok(())
+						}
+						BenchmarkOperation::Verify(_) => {
+							let expected = vp.generate_value_for(sample);
+							let actual: BenchValue = client.read(sample, &mut kp).await?.into();
+							match verify::mismatch(&expected, &actual) {
+								Some(diff) => {
+									bail!("Record {sample} was read back changed: {diff}")
+								}
+								None => Ok(()),
+							}
+						}
+						BenchmarkOperation::ReadSpread(records) => {
+							let n = spread(sample, samples, *records);
+							client.read(n, &mut kp).await.map(|_| ())
+						}
+						BenchmarkOperation::UpdateSpread(records) => {
+							// Each record is always written the same value, however often
+							let n = spread(sample, samples, *records);
+							let value = vp.generate_value_for(n);
+							client.update(n, value, &mut kp).await
+						}
+						BenchmarkOperation::Update | BenchmarkOperation::UpdateDuringBuild => {
+							let value = vp.generate_value_for(sample);
+							client.update(sample, value, &mut kp).await
+						}
+						BenchmarkOperation::SyncedUpdate(interval) => {
+							let value = vp.generate_value_for(sample);
+							client.update(sample, value, &mut kp).await?;
+							// Every Nth write across all workers waits for a sync
+							match (sample + 1) % interval {
+								0 => client.sync().await,
+								_ => Ok(()),
+							}
+						}
+						BenchmarkOperation::UpdatePath(path) => {
+							let value = vp.generate_value_for(sample);
+							let field =
+								value.get_path(path).cloned().context("Missing update path")?;
+							client.update_path(sample, path, field, &mut kp).await
+						}
+						BenchmarkOperation::Scan(s, ctx) => client.scan(s, &kp, *ctx).await,
+						BenchmarkOperation::ScanPerturbed(s, ctx) => {
+							client.scan(perturbed.as_ref().unwrap_or(s), &kp, *ctx).await
+						}
+						BenchmarkOperation::ScanWithWrites(scan, ctx, spec) => {
+							workloads::run_scan_with_writes(
+								&*client, scan, *ctx, spec, sample, samples, &mut kp,
+							)
+							.await
+						}
+						BenchmarkOperation::BuildIndex(spec, name) => {
+							for (spec, name) in spec.physical(name) {
+								client.build_index(&spec, name.as_str()).await?;
+							}
+							Ok(())
+						}
+						BenchmarkOperation::RemoveIndex(spec, name) => {
+							for (_, name) in spec.physical(name) {
+								client.drop_index(name.as_str()).await?;
+							}
+							Ok(())
+						}
+						BenchmarkOperation::Soak(_, records) => {
+							// Alternate reads and updates, cycling through the records
+							let key = sample % records;
+							match sample % 2 {
+								0 => client.read(key, &mut kp).await.map(|_| ()),
+								_ => client.update(key, vp.generate_value_for(key), &mut kp).await,
+							}
+						}
+						BenchmarkOperation::Delete => client.delete(sample, &mut kp).await,
+						BenchmarkOperation::Truncate => client.truncate().await,
+						BenchmarkOperation::DeleteRange(size, records) => {
+							// The last range holds the remaining records
+							let start = sample.saturating_mul(*size);
+							let end = start.saturating_add(*size).min(*records);
+							client.delete_range(start, end, &mut kp).await
+						}
+						BenchmarkOperation::Preload(offset, records, Some(batch)) => {
+							// The last batch holds the remaining records
+							let loaded = sample * batch;
+							let count = (*batch).min(records - loaded) as usize;
+							client.preload(offset + loaded, count, &mut kp, &mut vp).await
+						}
+						BenchmarkOperation::Preload(offset, _, None)
+						| BenchmarkOperation::CreateScanRecords(offset)
+						| BenchmarkOperation::GrowRecords(offset, _) => {
+							let value = vp.generate_value_for(offset + sample);
+							client.create(offset + sample, value, &mut kp).await
+						}
+						BenchmarkOperation::DeleteScanRecords(offset)
+						| BenchmarkOperation::ShrinkRecords(offset) => client.delete(offset + sample, &mut kp).await,
+						BenchmarkOperation::BatchCreate(batch_op) => {
+							client.batch_create(sample, batch_op, &mut kp, &mut vp).await
+						}
+						BenchmarkOperation::BatchRead(batch_op) => {
+							client.batch_read(sample, batch_op, &mut kp).await
+						}
+						BenchmarkOperation::BatchUpdate(batch_op) => {
+							client.batch_update(sample, batch_op, &mut kp, &mut vp).await
+						}
+						BenchmarkOperation::BatchDelete(batch_op) => {
+							client.batch_delete(sample, batch_op, &mut kp).await
+						}
+						BenchmarkOperation::BatchCreateGenerated(batch_op, generated) => {
+							let vals =
+								(0..batch_op.batch_size).map(|_| vp.generate_value()).collect();
+							let keys = client.batch_create_generated(vals).await?;
+							generated.record(keys);
+							Ok(())
+						}
+						BenchmarkOperation::BatchReadGenerated(batch_op, generated) => {
+							let keys = generated.batch(sample, batch_op.batch_size)?;
+							client.batch_read_generated(keys).await
+						}
+						BenchmarkOperation::AppendTimeSeries(series) => {
+							client.append_time_series(series.row(sample)).await
+						}
+						BenchmarkOperation::QueryTimeSeries(_, query, _) => {
+							let (window, expected) = window.context("Missing time window")?;
+							let count = client.query_time_series(window).await?;
+							ensure!(
+								count == expected,
+								"Expected {expected} results from time series query '{}', got {count}",
+								query.name
+							);
+							Ok(())
+						}
 					}
-					BenchmarkOperation::BatchDelete(batch_op) => {
-						client.batch_delete(sample, batch_op, &mut kp).await
+				})
+				.await
+				.map_err(|_| {
+					anyhow!(BenchError::Timeout).context(format!(
+						"{operation} did not complete within {}",
+						timeout.describe(limit)
+					))
+				})
+				.and_then(|res| res);
+				match res {
+					Ok(()) => break None,
+					Err(e) => {
+						let category = BenchError::of(&e).or_else(|| client.classify_error(&e));
+						let Some(backoff) = policy.backoff(attempt, category) else {
+							break Some((e, category));
+						};
+						debug!(attempt = attempt + 1, "Retrying after {backoff:?}: {e:#}");
+						retried.record(category);
+						attempt += 1;
+						tokio::time::sleep(backoff).await;
 					}
 				}
-			})
-			.await
-			.with_context(|| {
-				format!("{operation} did not complete within {operation_timeout:?}")
-			})??;
+			};
 			// Get the completed sample number
 			let sample = complete.fetch_add(1, Ordering::Relaxed);
 			if let Some(pb) = &progress {
 				let done = ((sample + 1).min(samples)) as u64;
 				pb.set_position(done);
 			}
-			histogram.record(time.elapsed().as_micros() as u64)?;
+			// Count the failure and carry on with `--continue-on-error`, or fail the phase
+			if let Some((e, category)) = failure {
+				match failures {
+					Some((errors, timeline)) if !BenchError::is_unsupported(&e) => {
+						errors.record(category);
+						debug!(category = category.map(BenchError::name), "{e:#}");
+						timeline.tally(&mut windows, true);
+						continue;
+					}
+					_ => return Err(e),
+				}
+			}
+			if let Some((_, timeline)) = failures {
+				timeline.tally(&mut windows, false);
+			}
+			let elapsed = time.elapsed();
+			timeout.observe(elapsed);
+			// An open-loop sample is measured from its arrival, including the time it queued
+			let elapsed = match arrival {
+				Some(arrival) => {
+					let queued = time.saturating_duration_since(arrival);
+					queueing.record(queued.as_micros() as u64)?;
+					elapsed + queued
+				}
+				None => elapsed,
+			};
+			histogram.record(elapsed.as_micros() as u64)?;
+			if let (Some(latencies), Some(start)) = (&latencies, start) {
+				latencies.record(start, elapsed);
+			}
+		}
+		if let Some((_, timeline)) = failures {
+			timeline.merge(&windows);
+		}
+		if let Some(Pacing::Open(arrivals)) = &pacing {
+			arrivals.merge(&queueing)?;
 		}
 		Ok(histogram)
 	}
 }
 
+/// Number of times each scan is repeated by `--check-scan-consistency`.
+const SCAN_CONSISTENCY_REPEATS: usize = 3;
+
+/// The [`ValueProvider::round`] of the values written by the updates.
+const UPDATE_ROUND: u64 = 1;
+
+/// Number of records in each batch create of the `--preload` phase.
+const PRELOAD_BATCH_SIZE: u32 = 1000;
+
+/// How long the live queries wait for notifications still in flight after the writes finish.
+const LIVE_QUERY_GRACE: Duration = Duration::from_secs(5);
+
+/// How long to keep polling the replica for writes after they have all been made
+const REPLICATION_GRACE: Duration = Duration::from_secs(5);
+
+/// How long to wait between two reads of the writes made visible on the replica.
+const REPLICATION_POLL: Duration = Duration::from_millis(1);
+
+/// Number of generated values averaged to estimate the logical size of a record.
+const RECORD_SIZE_SAMPLES: u32 = 100;
+
 /// Single logical workload dispatched to [`BenchmarkClient`] (CRUD, scan, index, or batch).
 #[derive(Clone, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -703,18 +3094,61 @@ pub(crate) enum BenchmarkOperation {
 	Create,
 	/// Read by key.
 	Read,
+	/// Read by key through a restricted role with permission checks.
+	RestrictedRead,
+	/// Read by key, checking the value written by the named phase was returned.
+	Verify(String),
+	/// Read by a `unique` field, spread across the given number of records.
+	SecondaryRead(String, u32, ScanContext),
+	/// Link each record to the given number of others, out of the given number of records.
+	Relate(u32, u32),
+	/// Read the given number of records linked from each record.
+	Traverse(u32),
+	/// Write an event, notifying every open live query.
+	AppendLiveEvent,
+	/// Write to the primary, with the time it was written, while a replica is polled.
+	AppendReplicated,
 	/// Update existing keys.
 	Update,
+	/// Update keys spread evenly across, or cycling through, the given number of records.
+	UpdateSpread(u32),
+	/// Update existing keys while an index builds, without a progress bar of its own.
+	UpdateDuringBuild,
+	/// Alternate reads and updates over the given number of records until the deadline.
+	Soak(Instant, u32),
+	/// Update a single field, at a dotted path, of existing keys.
+	UpdatePath(String),
+	/// Update existing keys, syncing the datastore after every Nth write.
+	SyncedUpdate(u32),
 	/// Table or indexed query for a [`Scan`] and [`ScanContext`].
 	Scan(Scan, ScanContext),
+	/// [`BenchmarkOperation::Scan`] with the predicate perturbed for every sample.
+	ScanPerturbed(Scan, ScanContext),
 	/// Scan plus mixed writes according to [`ScanWithWrites`].
 	ScanWithWrites(Scan, ScanContext, ScanWithWrites),
 	/// Create backing index for the given analyzer/index id.
 	BuildIndex(Index, String),
-	/// Drop index by stable scan id.
-	RemoveIndex(String),
+	/// Drop the index created by [`BenchmarkOperation::BuildIndex`] for the same spec and id.
+	RemoveIndex(Index, String),
 	/// Delete by key.
 	Delete,
+	/// Delete every record at once.
+	Truncate,
+	/// Delete the records in contiguous key ranges of the given size, out of the given number.
+	DeleteRange(u32, u32),
+	/// Insert the given number of keys from an offset before the creates, outside the
+	/// results, in batches of the given size, or one by one when there is no size.
+	Preload(u32, u32, Option<u32>),
+	/// Insert the keys from an offset which only the scans read, outside the results.
+	CreateScanRecords(u32),
+	/// Delete the keys from an offset which only the scans read, outside the results.
+	DeleteScanRecords(u32),
+	/// Insert the keys from an offset, growing the table to the given number of records.
+	GrowRecords(u32, u32),
+	/// Read keys spread evenly across, or cycling through, the given number of records.
+	ReadSpread(u32),
+	/// Delete the keys from an offset which the growth stages inserted.
+	ShrinkRecords(u32),
 	/// Batch insert configured by [`BatchOperation`].
 	BatchCreate(BatchOperation),
 	/// Batch read by keys from [`BatchOperation`].
@@ -723,6 +3157,252 @@ pub(crate) enum BenchmarkOperation {
 	BatchUpdate(BatchOperation),
 	/// Batch delete configured by [`BatchOperation`].
 	BatchDelete(BatchOperation),
+	/// Batch insert with keys generated by the datastore, recorded in [`GeneratedKeys`].
+	BatchCreateGenerated(BatchOperation, Arc<GeneratedKeys>),
+	/// Batch read by keys recorded in [`GeneratedKeys`].
+	BatchReadGenerated(BatchOperation, Arc<GeneratedKeys>),
+	/// Append the timestamped rows of a [`TimeSeries`].
+	AppendTimeSeries(Arc<TimeSeries>),
+	/// Query windows of the given number of appended [`TimeSeries`] rows.
+	QueryTimeSeries(Arc<TimeSeries>, TimeSeriesQuery, u32),
+}
+
+impl BenchmarkOperation {
+	/// The reads of `samples` records, spread across or cycling through the keyspace when its
+	/// size differs.
+	fn reads(samples: u32, keyspace: u32) -> Self {
+		match samples == keyspace {
+			true => Self::Read,
+			false => Self::ReadSpread(keyspace),
+		}
+	}
+
+	/// The updates of `samples` records, spread across or cycling through the keyspace when its
+	/// size differs.
+	fn updates(samples: u32, keyspace: u32) -> Self {
+		match samples == keyspace {
+			true => Self::Update,
+			false => Self::UpdateSpread(keyspace),
+		}
+	}
+
+	/// The operation type whose retry policy applies, or none for the operations never retried,
+	/// such as index builds, which may have taken effect before they failed.
+	fn retry_phase(&self) -> Option<Phase> {
+		match self {
+			Self::Create
+			| Self::Preload(..)
+			| Self::CreateScanRecords(_)
+			| Self::GrowRecords(..)
+			| Self::AppendTimeSeries(_) => Some(Phase::Create),
+			Self::Read
+			| Self::RestrictedRead
+			| Self::Verify(_)
+			| Self::SecondaryRead(..)
+			| Self::Traverse(_)
+			| Self::ReadSpread(_)
+			| Self::QueryTimeSeries(..) => Some(Phase::Read),
+			Self::Update
+			| Self::UpdateSpread(_)
+			| Self::UpdateDuringBuild
+			| Self::Soak(..)
+			| Self::UpdatePath(_)
+			| Self::SyncedUpdate(_) => Some(Phase::Update),
+			Self::Scan(..) | Self::ScanPerturbed(..) | Self::ScanWithWrites(..) => {
+				Some(Phase::Scan)
+			}
+			Self::Delete
+			| Self::Truncate
+			| Self::DeleteRange(..)
+			| Self::DeleteScanRecords(_)
+			| Self::ShrinkRecords(_) => Some(Phase::Delete),
+			Self::BatchCreate(_)
+			| Self::BatchRead(_)
+			| Self::BatchUpdate(_)
+			| Self::BatchDelete(_)
+			| Self::BatchCreateGenerated(..)
+			| Self::BatchReadGenerated(..) => Some(Phase::Batch),
+			Self::Relate(..)
+			| Self::AppendLiveEvent
+			| Self::AppendReplicated
+			| Self::BuildIndex(..)
+			| Self::RemoveIndex(..) => None,
+		}
+	}
+
+	/// Whether the samples arrive at the `--arrival-rate`, which only the measured single-record
+	/// and scan phases do, rather than the setup phases and the single statements.
+	fn open_loop(&self) -> bool {
+		matches!(
+			self,
+			Self::Create
+				| Self::Read | Self::RestrictedRead
+				| Self::SecondaryRead(..)
+				| Self::Traverse(_)
+				| Self::ReadSpread(_)
+				| Self::Update
+				| Self::UpdateSpread(_)
+				| Self::UpdatePath(_)
+				| Self::SyncedUpdate(_)
+				| Self::Scan(..)
+				| Self::ScanPerturbed(..)
+				| Self::ScanWithWrites(..)
+				| Self::Delete
+				| Self::QueryTimeSeries(..)
+		)
+	}
+
+	/// The tasks per client and samples per second a scan or batch overrides for its phase.
+	fn overrides(&self) -> (Option<u32>, Option<f64>) {
+		match self {
+			Self::Scan(scan, _)
+			| Self::ScanPerturbed(scan, _)
+			| Self::ScanWithWrites(scan, _, _) => (scan.threads, None),
+			Self::BatchCreate(batch)
+			| Self::BatchRead(batch)
+			| Self::BatchUpdate(batch)
+			| Self::BatchDelete(batch)
+			| Self::BatchCreateGenerated(batch, _)
+			| Self::BatchReadGenerated(batch, _) => (batch.threads, batch.rate),
+			_ => (None, None),
+		}
+	}
+}
+
+/// What each worker of a phase owns: its providers, and its share of the phase-wide reporting.
+struct WorkerState {
+	/// The keys of the samples
+	kp: KeyProvider,
+	/// The values of the samples
+	vp: ValueProvider,
+	/// The progress bar of the phase, when shown
+	progress: Option<Arc<ProgressBar>>,
+	/// Where the raw latencies of this worker are streamed, with `--raw-latencies`
+	latencies: Option<LatencySink>,
+	/// When each sample may start, in a paced phase
+	pacing: Option<Pacing>,
+	/// The samples this agent runs, in a coordinated run
+	shard: Option<Shard>,
+}
+
+/// The spacing of the samples of a paced phase, shared by all its workers.
+#[derive(Clone)]
+enum Pacing {
+	/// Even spacing at the rate a scan or batch sets, measured from when each sample starts.
+	Even {
+		/// When the phase started.
+		start: Instant,
+		/// Time between consecutive samples.
+		interval: Duration,
+	},
+	/// Random arrivals at the `--arrival-rate`, measured from when each sample arrives.
+	Open(Arc<Arrivals>),
+}
+
+impl Pacing {
+	/// Sleeps until the scheduled start of the given sample, returning when an open-loop sample
+	/// arrived, which may be well before a worker was free to start it.
+	async fn wait(&self, sample: u32) -> Option<Instant> {
+		match self {
+			Self::Even {
+				start,
+				interval,
+			} => {
+				tokio::time::sleep_until(*start + *interval * sample).await;
+				None
+			}
+			Self::Open(arrivals) => {
+				let arrival = arrivals.next();
+				tokio::time::sleep_until(arrival).await;
+				Some(arrival)
+			}
+		}
+	}
+}
+
+/// The Poisson arrivals of an open-loop phase, and how long they queued for a free worker.
+struct Arrivals {
+	/// When the phase started.
+	start: Instant,
+	/// Mean arrivals per second.
+	rate: f64,
+	/// The offset of the next arrival from the start, and the generator of the gaps between them.
+	next: std::sync::Mutex<(Duration, SmallRng)>,
+	/// Time queued (µs) by the samples of every worker which has finished.
+	queueing: std::sync::Mutex<Histogram<u64>>,
+}
+
+impl Arrivals {
+	fn new(rate: f64, seed: Option<u64>) -> Result<Self> {
+		let rng = match seed {
+			Some(seed) => SmallRng::seed_from_u64(seed),
+			None => rand::make_rng(),
+		};
+		Ok(Self {
+			start: Instant::now(),
+			rate,
+			next: std::sync::Mutex::new((Duration::ZERO, rng)),
+			queueing: std::sync::Mutex::new(Histogram::new(3)?),
+		})
+	}
+
+	/// Claims the next arrival, with exponentially distributed gaps between the arrivals.
+	fn next(&self) -> Instant {
+		let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+		let (offset, rng) = &mut *next;
+		let arrival = self.start + *offset;
+		// The uniform sample lies in [0, 1), so the logarithm is of (0, 1] and finite
+		let gap = -(1.0 - rng.random::<f64>()).ln() / self.rate;
+		*offset += Duration::from_secs_f64(gap);
+		arrival
+	}
+
+	/// Adds the times queued by the samples of a worker which has finished.
+	fn merge(&self, queueing: &Histogram<u64>) -> Result<()> {
+		self.queueing.lock().unwrap_or_else(|e| e.into_inner()).add(queueing)?;
+		Ok(())
+	}
+
+	/// Summarises the times queued by the samples of every worker.
+	fn queueing(&self) -> Queueing {
+		Queueing::new(self.rate, &self.queueing.lock().unwrap_or_else(|e| e.into_inner()))
+	}
+}
+
+/// The record of a sample in a phase which runs a different number of samples than there are
+/// records: spread evenly across the records when there are fewer samples, or cycling through
+/// them when there are more.
+fn spread(sample: u32, samples: u32, records: u32) -> u32 {
+	match samples > records {
+		true => sample % records,
+		false => (sample as u64 * records as u64 / samples as u64) as u32,
+	}
+}
+
+/// The checkpoint phase of a scan, unique across the expanded scans.
+fn scan_phase(scan: &Scan) -> String {
+	format!("scan {} · {} · {}", scan.spec_group, scan.id, scan.name)
+}
+
+/// The estimated logical bytes of a record: its key and its encoded value, averaged over a sample.
+fn record_size(kp: &KeyProvider, vp: &ValueProvider) -> u64 {
+	let mut vp = vp.clone();
+	let values: usize = (0..RECORD_SIZE_SAMPLES)
+		.filter_map(|n| vp.generate_value_for(n).encode().ok())
+		.map(|value| value.len())
+		.sum();
+	(kp.key_size() + values / RECORD_SIZE_SAMPLES as usize) as u64
+}
+
+/// Relative OPS change of a phase variant, in percent of the baseline phase.
+fn throughput_change(baseline: &OperationResult, result: &OperationResult) -> Option<f64> {
+	(baseline.ops() > 0.0).then(|| (result.ops() - baseline.ops()) / baseline.ops() * 100.0)
+}
+
+/// Relative p99 latency change of a phase variant, in percent of the baseline phase.
+fn latency_change(baseline: &OperationResult, result: &OperationResult) -> Option<f64> {
+	let baseline_q99 = baseline.q99() as f64;
+	(baseline_q99 > 0.0).then(|| (result.q99() as f64 - baseline_q99) / baseline_q99 * 100.0)
 }
 
 /// Short slug for UI labels: heap scan vs index-backed scan.
@@ -730,6 +3410,7 @@ fn scan_context_slug(ctx: ScanContext) -> &'static str {
 	match ctx {
 		ScanContext::WithoutIndex => "no-index",
 		ScanContext::WithIndex => "indexed",
+		ScanContext::WithHint => "hinted",
 	}
 }
 
@@ -739,9 +3420,21 @@ impl Display for BenchmarkOperation {
 		match self {
 			Self::Create => write!(f, "Create"),
 			Self::Read => write!(f, "Read"),
+			Self::RestrictedRead => write!(f, "Read (restricted)"),
+			Self::Verify(phase) => write!(f, "Verify :: {phase}"),
+			Self::SecondaryRead(field, _, ctx) => {
+				write!(f, "Read :: by {field}, {}", scan_context_slug(*ctx))
+			}
+			Self::Relate(fanout, _) => write!(f, "Relate :: {fanout} per record"),
+			Self::Traverse(fanout) => write!(f, "Traverse :: {fanout} per record"),
+			Self::AppendLiveEvent => write!(f, "Create :: live events"),
+			Self::AppendReplicated => write!(f, "Create :: replicated writes"),
 			Self::Scan(_, ctx) => {
 				write!(f, "Scan :: {}", scan_context_slug(*ctx))
 			}
+			Self::ScanPerturbed(_, ctx) => {
+				write!(f, "Scan :: {} (perturbed)", scan_context_slug(*ctx))
+			}
 			Self::ScanWithWrites(_, ctx, spec) => {
 				write!(
 					f,
@@ -751,27 +3444,58 @@ impl Display for BenchmarkOperation {
 				)
 			}
 			Self::BuildIndex(_, _) => write!(f, "BuildIndex"),
-			Self::RemoveIndex(_) => write!(f, "RemoveIndex"),
+			Self::RemoveIndex(_, _) => write!(f, "RemoveIndex"),
 			Self::Update => write!(f, "Update"),
+			Self::UpdateSpread(records) => write!(f, "Update :: {records} records"),
+			Self::UpdateDuringBuild => write!(f, "Update :: during index build"),
+			Self::Soak(_, _) => write!(f, "Soak"),
+			Self::UpdatePath(path) => write!(f, "Update :: {path}"),
+			Self::SyncedUpdate(interval) => write!(f, "Update :: sync every {interval}"),
 			Self::Delete => write!(f, "Delete"),
+			Self::Truncate => write!(f, "Delete :: truncate"),
+			Self::DeleteRange(size, _) => write!(f, "Delete :: ranges of {size}"),
+			Self::Preload(_, records, _) => write!(f, "Create :: preload {records} records"),
+			Self::CreateScanRecords(_) => write!(f, "Create :: scan records"),
+			Self::DeleteScanRecords(_) => write!(f, "Delete :: scan records"),
+			Self::GrowRecords(_, records) => write!(f, "Create :: grow to {records} records"),
+			Self::ReadSpread(records) => write!(f, "Read :: {records} records"),
+			Self::ShrinkRecords(_) => write!(f, "Delete :: grown records"),
 			Self::BatchCreate(b) => write!(f, "BatchCreate::{}", b.name),
 			Self::BatchRead(b) => write!(f, "BatchRead::{}", b.name),
 			Self::BatchUpdate(b) => write!(f, "BatchUpdate::{}", b.name),
 			Self::BatchDelete(b) => write!(f, "BatchDelete::{}", b.name),
+			Self::BatchCreateGenerated(b, _) => write!(f, "BatchCreateGenerated::{}", b.name),
+			Self::BatchReadGenerated(b, _) => write!(f, "BatchReadGenerated::{}", b.name),
+			Self::AppendTimeSeries(_) => write!(f, "TimeSeries::append"),
+			Self::QueryTimeSeries(_, q, _) => write!(f, "TimeSeries::{}", q.name),
 		}
 	}
 }
 
+/// The current time, in microseconds since the Unix epoch.
+fn unix_micros() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64
+}
+
+/// The records which record `n` is linked to, spread evenly across the other `records` records.
+fn relation_targets(n: u32, fanout: u32, records: u32) -> Vec<u32> {
+	let stride = (records - 1) / fanout;
+	(0..fanout).map(|i| ((n as u64 + 1 + (i * stride) as u64) % records as u64) as u32).collect()
+}
+
 /// Truncated label for the indicatif progress bar (scan/batch variants).
 fn progress_short_label(operation: &BenchmarkOperation) -> String {
 	const MAX: usize = 72;
 	let s = match operation {
 		BenchmarkOperation::Scan(_, ctx) => scan_context_slug(*ctx).to_string(),
+		BenchmarkOperation::ScanPerturbed(_, ctx) => {
+			format!("{}, perturbed", scan_context_slug(*ctx))
+		}
 		BenchmarkOperation::ScanWithWrites(_, ctx, spec) => {
 			format!("{}, writes {}%", scan_context_slug(*ctx), writes_ratio_percent(spec))
 		}
 		BenchmarkOperation::BuildIndex(_, _) => "BuildIndex".to_string(),
-		BenchmarkOperation::RemoveIndex(_) => "RemoveIndex".to_string(),
+		BenchmarkOperation::RemoveIndex(_, _) => "RemoveIndex".to_string(),
 		_ => operation.to_string(),
 	};
 	if s.len() > MAX {
@@ -780,3 +3504,33 @@ fn progress_short_label(operation: &BenchmarkOperation) -> String {
 		s
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reads_and_updates_spread_across_the_keyspace() {
+		assert!(matches!(BenchmarkOperation::reads(100, 100), BenchmarkOperation::Read));
+		assert!(matches!(BenchmarkOperation::updates(100, 100), BenchmarkOperation::Update));
+		assert!(matches!(
+			BenchmarkOperation::reads(100, 1000),
+			BenchmarkOperation::ReadSpread(1000)
+		));
+		assert!(matches!(
+			BenchmarkOperation::updates(100, 30),
+			BenchmarkOperation::UpdateSpread(30)
+		));
+		// A keyspace larger than the samples is spread over evenly, one record per sample
+		let keys: Vec<u32> = (0..100).map(|s| spread(s, 100, 1000)).collect();
+		assert_eq!(keys[..3], [0, 10, 20]);
+		assert_eq!(keys[99], 990);
+		assert!(keys.windows(2).all(|w| w[0] < w[1]));
+		// A keyspace smaller than the samples is cycled through, each record in turn
+		let keys: Vec<u32> = (0..100).map(|s| spread(s, 100, 30)).collect();
+		assert_eq!(keys[..3], [0, 1, 2]);
+		assert_eq!(keys[30], 0);
+		assert_eq!(keys[99], 9);
+		assert!(keys.iter().all(|&k| k < 30));
+	}
+}
@@ -0,0 +1,114 @@
+//! Resource usage of the datastore containers, read from their cgroup v2 files.
+//!
+//! A cgroup covers every process of a container, so servers which fork a process per connection
+//! (Postgres) or spawn helper processes are measured as a whole, unlike the single process given
+//! with `--pid`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The cgroup v2 directory of a container.
+#[derive(Clone, Debug)]
+pub(crate) struct Cgroup(PathBuf);
+
+/// The counters and current memory of one or more cgroups.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct CgroupStats {
+	/// CPU time used since the cgroup was created (µs).
+	pub(crate) cpu_usec: u64,
+	/// Resident memory: anonymous pages plus mapped file pages (bytes).
+	pub(crate) memory: u64,
+	/// Bytes read from block devices since the cgroup was created.
+	pub(crate) read_bytes: u64,
+	/// Bytes written to block devices since the cgroup was created.
+	pub(crate) written_bytes: u64,
+}
+
+impl Cgroup {
+	/// Finds the cgroup of a container from its full id, under the systemd or cgroupfs driver.
+	pub(crate) fn find(id: &str) -> Option<Self> {
+		[
+			format!("/sys/fs/cgroup/system.slice/docker-{id}.scope"),
+			format!("/sys/fs/cgroup/docker/{id}"),
+		]
+		.into_iter()
+		.map(PathBuf::from)
+		.find(|path| path.join("cpu.stat").exists())
+		.map(Self)
+	}
+
+	/// Reads the current counters, if the cgroup still exists.
+	fn stats(&self) -> Option<CgroupStats> {
+		let read = |file: &str| std::fs::read_to_string(self.0.join(file)).ok();
+		let cpu = read("cpu.stat")?;
+		let memory = read("memory.stat")?;
+		// The io controller is not enabled on every host
+		let io = read("io.stat").unwrap_or_default();
+		Some(CgroupStats {
+			cpu_usec: field(&cpu, "usage_usec"),
+			memory: field(&memory, "anon") + field(&memory, "file_mapped"),
+			read_bytes: io_total(&io, "rbytes"),
+			written_bytes: io_total(&io, "wbytes"),
+		})
+	}
+}
+
+impl CgroupStats {
+	/// The summed counters of every cgroup, or `None` once none of them exist.
+	pub(crate) fn collect(cgroups: &[Cgroup]) -> Option<Self> {
+		cgroups.iter().filter_map(Cgroup::stats).reduce(|total, stats| Self {
+			cpu_usec: total.cpu_usec + stats.cpu_usec,
+			memory: total.memory + stats.memory,
+			read_bytes: total.read_bytes + stats.read_bytes,
+			written_bytes: total.written_bytes + stats.written_bytes,
+		})
+	}
+
+	/// CPU usage since an earlier snapshot, as a percentage of every host core.
+	pub(crate) fn cpu_since(&self, earlier: &Self, elapsed: Duration) -> f32 {
+		let used = self.cpu_usec.saturating_sub(earlier.cpu_usec) as f64;
+		let available = elapsed.as_micros() as f64 * num_cpus::get() as f64;
+		(used / available.max(1.0) * 100.0) as f32
+	}
+}
+
+/// The value of a `key value` line of a cgroup stat file.
+fn field(text: &str, key: &str) -> u64 {
+	text.lines()
+		.find_map(|line| line.strip_prefix(key)?.strip_prefix(' ')?.trim().parse().ok())
+		.unwrap_or(0)
+}
+
+/// The sum of a `key=value` counter over the device lines of `io.stat`.
+fn io_total(text: &str, key: &str) -> u64 {
+	text.split_whitespace()
+		.filter_map(|pair| pair.strip_prefix(key)?.strip_prefix('=')?.parse::<u64>().ok())
+		.sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stat_files_are_summed_by_key() {
+		let memory = "anon 1024\nfile 4096\nfile_mapped 512\nanon_thp 0\n";
+		assert_eq!(field(memory, "anon"), 1024);
+		assert_eq!(field(memory, "file_mapped"), 512);
+		assert_eq!(field(memory, "shmem"), 0);
+		let io = "259:0 rbytes=100 wbytes=200 rios=1 wios=2 dbytes=0 dios=0\n\
+			8:0 rbytes=10 wbytes=20 rios=1 wios=1 dbytes=0 dios=0\n";
+		assert_eq!(io_total(io, "rbytes"), 110);
+		assert_eq!(io_total(io, "wbytes"), 220);
+	}
+
+	#[test]
+	fn cpu_is_a_share_of_every_host_core() {
+		let earlier = CgroupStats::default();
+		let later = CgroupStats {
+			cpu_usec: 1_000_000 * num_cpus::get() as u64 / 2,
+			..earlier
+		};
+		assert_eq!(later.cpu_since(&earlier, Duration::from_secs(1)), 50.0);
+	}
+}
@@ -1,23 +1,8 @@
-use crate::result::{BenchmarkResult, OperationResult};
+use crate::result::{BenchmarkResult, OperationResult, SoakWindow};
 use serde_json::{Map, Value, json};
 
-/// Generate an HTML file with interactive charts for a single benchmark result
-pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> String {
-	let system_info_html = generate_system_info(result);
-
-	format!(
-		r##"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>CRUD Benchmark Results - {database_name}</title>
-    <link rel="preconnect" href="https://fonts.googleapis.com">
-    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
-    <link href="https://fonts.googleapis.com/css2?family=Inter:opsz,wght@14..32,100..900&family=JetBrains+Mono:wght@100..800&display=swap" rel="stylesheet">
-    <script src="https://cdn.jsdelivr.net/npm/apexcharts@3.45.0/dist/apexcharts.min.js"></script>
-    <style>
-        :root {{
+/// Shared stylesheet for the generated HTML pages (Spectron `--sd-*` tokens).
+pub(crate) const PAGE_STYLES: &str = r#"        :root {
             --sd-bg: #0e0c14;
             --sd-surface: #16141f;
             --sd-surface-2: #1a1825;
@@ -31,9 +16,9 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
             --sd-passion: #651ddd;
             --sd-line: #3d3650;
             --sd-success: #34d399;
-        }}
-        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
-        body {{
+        }
+        * { margin: 0; padding: 0; box-sizing: border-box; }
+        body {
             font-family: "Inter", system-ui, sans-serif;
             font-size: 16px;
             line-height: 1.6;
@@ -46,16 +31,16 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
             -webkit-font-smoothing: antialiased;
             min-height: 100vh;
             padding: 24px 16px 64px;
-        }}
-        .container {{
+        }
+        .container {
             max-width: 1400px;
             margin: 0 auto;
             padding: 40px 32px 48px;
-        }}
-        @media (max-width: 640px) {{
-            .container {{ padding: 24px 16px 32px; }}
-        }}
-        .eyebrow {{
+        }
+        @media (max-width: 640px) {
+            .container { padding: 24px 16px 32px; }
+        }
+        .eyebrow {
             font-family: "JetBrains Mono", ui-monospace, monospace;
             font-size: 11px;
             letter-spacing: 0.2em;
@@ -65,67 +50,67 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
             display: flex;
             align-items: center;
             gap: 12px;
-        }}
-        .eyebrow::before {{
+        }
+        .eyebrow::before {
             content: "";
             width: 24px;
             height: 1px;
             background: var(--sd-accent);
             display: inline-block;
-        }}
-        h1 {{
+        }
+        h1 {
             font-size: clamp(2rem, 4vw, 3.25rem);
             font-weight: 700;
             letter-spacing: -0.03em;
             line-height: 1.05;
             margin-bottom: 16px;
             color: #ffffff;
-        }}
-        h1 .grad {{
+        }
+        h1 .grad {
             background: linear-gradient(135deg, var(--sd-energy), var(--sd-passion));
             -webkit-background-clip: text;
             background-clip: text;
             -webkit-text-fill-color: transparent;
-        }}
-        .subtitle {{
+        }
+        .subtitle {
             color: var(--sd-text-muted);
             font-size: 1.125rem;
             line-height: 1.55;
             font-style: italic;
             margin-bottom: 40px;
             max-width: 720px;
-        }}
-        .chart-grid {{
+        }
+        .chart-grid {
             display: grid;
             grid-template-columns: repeat(auto-fit, minmax(600px, 1fr));
             gap: 28px;
             margin-bottom: 28px;
-        }}
-        @media (max-width: 700px) {{
-            .chart-grid {{ grid-template-columns: 1fr; }}
-        }}
-        .chart-container {{
+        }
+        @media (max-width: 700px) {
+            .chart-grid { grid-template-columns: 1fr; }
+        }
+        .chart-container {
             background:
                 linear-gradient(var(--sd-surface), var(--sd-surface)) padding-box,
                 linear-gradient(135deg, rgba(124, 92, 252, 0.18), rgba(124, 92, 252, 0.04)) border-box;
             border: 1px solid transparent;
             border-radius: 12px;
             padding: 22px 26px;
-        }}
-        .chart-title {{
+        }
+        .chart-title {
             font-size: 1.375rem;
             font-weight: 600;
             letter-spacing: -0.02em;
             margin-bottom: 14px;
             color: #ffffff;
-        }}
-        .stats-grid {{
+        }
+        .stats-grid {
             display: grid;
             grid-template-columns: repeat(auto-fit, minmax(200px, 1fr));
             gap: 16px;
             margin-bottom: 36px;
-        }}
-        .stat-card {{
+        }
+        .stat-card {
             background:
                 linear-gradient(var(--sd-surface), var(--sd-surface)) padding-box,
                 linear-gradient(135deg, rgba(124, 92, 252, 0.22), rgba(124, 92, 252, 0.05)) border-box;
@@ -135,38 +120,38 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
             text-align: center;
             border-top: 3px solid var(--sd-accent);
             color: var(--sd-text);
-        }}
-        .stat-card.blue {{ border-top-color: #7c5cfc; }}
-        .stat-card.green {{ border-top-color: #34d399; }}
-        .stat-card.orange {{ border-top-color: #d255fe; }}
-        .stat-card.red {{ border-top-color: #c471f5; }}
-        .stat-label {{
+        }
+        .stat-card.blue { border-top-color: #7c5cfc; }
+        .stat-card.green { border-top-color: #34d399; }
+        .stat-card.orange { border-top-color: #d255fe; }
+        .stat-card.red { border-top-color: #c471f5; }
+        .stat-label {
             font-family: "JetBrains Mono", ui-monospace, monospace;
             font-size: 10px;
             letter-spacing: 0.14em;
             text-transform: uppercase;
             color: var(--sd-text-dim);
             margin-bottom: 8px;
-        }}
-        .stat-value {{
+        }
+        .stat-value {
             font-size: 1.85rem;
             font-weight: 700;
             letter-spacing: -0.02em;
             color: #ffffff;
-        }}
-        .stat-unit {{
+        }
+        .stat-unit {
             font-size: 0.65rem;
             font-weight: 500;
             color: var(--sd-text-muted);
             margin-left: 2px;
-        }}
-        .chart {{
+        }
+        .chart {
             max-height: 400px;
-        }}
-        .full-width {{
+        }
+        .full-width {
             grid-column: 1 / -1;
-        }}
-        .system-info {{
+        }
+        .system-info {
             background:
                 linear-gradient(var(--sd-surface), var(--sd-surface)) padding-box,
                 linear-gradient(135deg, rgba(124, 92, 252, 0.18), rgba(124, 92, 252, 0.04)) border-box;
@@ -174,80 +159,80 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
             border-radius: 12px;
             padding: 24px 26px;
             margin-bottom: 36px;
-        }}
-        .system-info-title {{
+        }
+        .system-info-title {
             font-size: 1.25rem;
             font-weight: 600;
             letter-spacing: -0.02em;
             margin-bottom: 18px;
             color: #ffffff;
-        }}
-        .system-info-grid {{
+        }
+        .system-info-grid {
             display: grid;
             grid-template-columns: repeat(auto-fit, minmax(300px, 1fr));
             gap: 12px;
-        }}
-        .system-info-item {{
+        }
+        .system-info-item {
             display: flex;
             align-items: center;
             padding: 12px 14px;
             background: var(--sd-surface-2);
             border-radius: 8px;
             border: 1px solid var(--sd-surface-border);
-        }}
-        .system-info-label {{
+        }
+        .system-info-label {
             font-weight: 600;
             color: var(--sd-text-muted);
             margin-right: 8px;
             min-width: 120px;
             font-size: 0.9rem;
-        }}
-        .system-info-value {{
+        }
+        .system-info-value {
             color: var(--sd-text);
             font-family: "JetBrains Mono", ui-monospace, monospace;
             font-size: 0.875rem;
-        }}
-        .apexcharts-tooltip-box {{
+        }
+        .apexcharts-tooltip-box {
             padding: 10px 12px;
             background: var(--sd-surface) !important;
             border: 1px solid var(--sd-surface-border) !important;
             border-radius: 8px;
             box-shadow: 0 8px 24px rgba(0, 0, 0, 0.45);
             color: var(--sd-text);
-        }}
-        .apexcharts-tooltip-box > div {{
+        }
+        .apexcharts-tooltip-box > div {
             padding: 3px 0;
             color: var(--sd-text-muted);
-        }}
-        .apexcharts-tooltip-box strong {{
+        }
+        .apexcharts-tooltip-box strong {
             color: var(--sd-text);
-        }}
-        .chart-subtitle {{
+        }
+        .chart-subtitle {
             color: var(--sd-text-muted);
             font-size: 0.95em;
             line-height: 1.45;
             margin: -6px 0 14px 0;
             max-width: 920px;
-        }}
-        .scan-percentile-table-wrap {{
+        }
+        .scan-percentile-table-wrap {
             overflow-x: auto;
             max-width: 100%;
             margin-top: 4px;
-        }}
-        .scan-percentile-table {{
+        }
+        .scan-percentile-table {
             width: 100%;
             border-collapse: collapse;
             font-size: 12px;
             color: var(--sd-text-muted);
-        }}
-        .scan-percentile-table caption {{
+        }
+        .scan-percentile-table caption {
             caption-side: top;
             text-align: left;
             font-size: 0.85em;
             color: var(--sd-text-muted);
             padding-bottom: 8px;
-        }}
-        .scan-percentile-table th {{
+        }
+        .scan-percentile-table th {
             font-weight: 600;
             background: var(--sd-surface-2);
             color: var(--sd-text);
@@ -256,82 +241,100 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
             font-size: 10px;
             letter-spacing: 0.06em;
             text-transform: uppercase;
-        }}
-        .scan-percentile-table th, .scan-percentile-table td {{
+        }
+        .scan-percentile-table th, .scan-percentile-table td {
             border: 1px solid var(--sd-surface-border);
             padding: 8px 10px;
-        }}
-        .scan-percentile-table td.num {{
+        }
+        .scan-percentile-table td.num {
             font-variant-numeric: tabular-nums;
             text-align: right;
             font-family: "JetBrains Mono", ui-monospace, monospace;
             color: var(--sd-text);
-        }}
-        .scan-percentile-table td.scan-name {{
+        }
+        .scan-percentile-table td.scan-name {
             max-width: 28em;
             overflow: hidden;
             text-overflow: ellipsis;
             white-space: nowrap;
             text-align: left;
             color: var(--sd-text);
-        }}
-        .scan-percentile-table td.spark {{
+        }
+        .scan-percentile-table td.spark {
             text-align: center;
             vertical-align: middle;
             min-width: 130px;
             background: rgba(22, 20, 31, 0.5);
-        }}
+        }
         .scan-percentile-table th.scan-dist-col,
-        .scan-percentile-table td.scan-dist {{
+        .scan-percentile-table td.scan-dist {
             border-left: 1px solid var(--sd-line);
             text-align: center;
             vertical-align: middle;
             min-width: 96px;
             background: rgba(22, 20, 31, 0.35);
-        }}
-        .scan-percentile-table tbody tr:nth-child(even) {{
+        }
+        .scan-percentile-table tbody tr:nth-child(even) {
             background: rgba(26, 24, 37, 0.45);
-        }}
-        .scan-sparkline {{
+        }
+        .scan-sparkline {
             display: block;
             margin: 0 auto;
             flex-shrink: 0;
-        }}
-        .scan-mini-dist {{
+        }
+        .scan-mini-dist {
             display: block;
             margin: 0 auto;
             flex-shrink: 0;
-        }}
-        .chart-container .apexcharts-svg .apexcharts-background {{
+        }
+        .chart-container .apexcharts-svg .apexcharts-background {
             fill: var(--sd-surface) !important;
-        }}
-        .chart-container line.apexcharts-gridline {{
+        }
+        .chart-container line.apexcharts-gridline {
             stroke: var(--sd-surface-border) !important;
             opacity: 0.75;
-        }}
-        .chart-container .apexcharts-grid-borders line {{
+        }
+        .chart-container .apexcharts-grid-borders line {
             stroke: var(--sd-surface-border) !important;
             opacity: 0.8;
-        }}
-        .chart-container .apexcharts-yaxis line {{
+        }
+        .chart-container .apexcharts-yaxis line {
             stroke: var(--sd-surface-border) !important;
             opacity: 0.95;
-        }}
-        .chart-container .apexcharts-xaxis line {{
+        }
+        .chart-container .apexcharts-xaxis line {
             stroke: var(--sd-surface-border) !important;
             opacity: 0.95;
-        }}
-        .chart-container .apexcharts-boxPlot-series path.apexcharts-boxPlot-area {{
+        }
+        .chart-container .apexcharts-boxPlot-series path.apexcharts-boxPlot-area {
             stroke-width: 2.25px !important;
             stroke-linecap: round;
             stroke-linejoin: round;
-        }}
-        .chart-container #scanLatencyChart path.apexcharts-boxPlot-area {{
+        }
+        .chart-container #scanLatencyChart path.apexcharts-boxPlot-area {
             stroke: #faf7ff !important;
-        }}
-        .chart-container #batchLatencyChart path.apexcharts-boxPlot-area {{
+        }
+        .chart-container #batchLatencyChart path.apexcharts-boxPlot-area {
             stroke: #fff6fb !important;
-        }}
+        }"#;
+
+/// Generate an HTML file with interactive charts for a single benchmark result
+pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> String {
+	let system_info_html = generate_system_info(result);
+
+	format!(
+		r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>CRUD Benchmark Results - {database_name}</title>
+    <link rel="preconnect" href="https://fonts.googleapis.com">
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+    <link href="https://fonts.googleapis.com/css2?family=Inter:opsz,wght@14..32,100..900&family=JetBrains+Mono:wght@100..800&display=swap" rel="stylesheet">
+    <script src="https://cdn.jsdelivr.net/npm/apexcharts@3.45.0/dist/apexcharts.min.js"></script>
+    <style>
+{styles}
     </style>
 </head>
 <body>
@@ -382,6 +385,21 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
                 {scan_percentile_table}
             </div>
 
+            <div class="chart-container full-width">
+                <div class="chart-title">Page latency through the table</div>
+                <div id="paginationChart"></div>
+            </div>
+
+            <div class="chart-container full-width">
+                <div class="chart-title">Latency vs dataset size</div>
+                <div id="growthChart"></div>
+            </div>
+
+            <div class="chart-container full-width">
+                <div class="chart-title">Throughput, latency, and memory over the soak</div>
+                <div id="soakChart"></div>
+            </div>
+
             <div class="chart-container">
                 <div class="chart-title">Batch throughput</div>
                 <div id="batchThroughputChart"></div>
@@ -396,6 +414,7 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
                 <div class="chart-title">Batch percentile comparison</div>
                 <div id="batchPercentileChart"></div>
             </div>
+{flamegraphs}
         </div>
     </div>
 
@@ -405,10 +424,129 @@ pub(crate) fn generate_html(result: &BenchmarkResult, database_name: &str) -> St
 </body>
 </html>"##,
 		database_name = database_name,
+		styles = PAGE_STYLES,
 		system_info = system_info_html,
 		stats_cards = generate_stat_cards(result),
 		scan_percentile_table = generate_scan_percentile_table_html(&scan_chart_rows(result)),
-		chart_scripts = generate_chart_scripts(result)
+		flamegraphs = generate_flamegraph_links(result),
+		chart_scripts = generate_chart_scripts(result) + &soak_chart_script(&result.soak)
+	)
+}
+
+/// A page charting the soak windows completed so far, reloading itself to follow a running soak.
+pub(crate) fn generate_soak_html(windows: &[SoakWindow]) -> String {
+	format!(
+		r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta http-equiv="refresh" content="60">
+    <title>CRUD Benchmark Soak</title>
+    <link rel="preconnect" href="https://fonts.googleapis.com">
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+    <link href="https://fonts.googleapis.com/css2?family=Inter:opsz,wght@14..32,100..900&family=JetBrains+Mono:wght@100..800&display=swap" rel="stylesheet">
+    <script src="https://cdn.jsdelivr.net/npm/apexcharts@3.45.0/dist/apexcharts.min.js"></script>
+    <style>
+{styles}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="eyebrow">CRUD Benchmark</div>
+        <h1>Soak <span class="grad">Progress</span></h1>
+        <div class="subtitle">{windows} windows completed</div>
+
+        <div class="chart-grid">
+            <div class="chart-container full-width">
+                <div class="chart-title">Throughput, latency, and memory over the soak</div>
+                <div id="soakChart"></div>
+            </div>
+        </div>
+    </div>
+
+    <script>
+        {chart_script}
+    </script>
+</body>
+</html>"##,
+		styles = PAGE_STYLES,
+		windows = windows.len(),
+		chart_script = soak_chart_script(windows),
+	)
+}
+
+/// The script rendering the throughput, p99 latency, and memory of each soak window into
+/// `#soakChart`, with gaps where a window was skipped.
+fn soak_chart_script(windows: &[SoakWindow]) -> String {
+	let categories: Vec<String> =
+		windows.iter().map(|w| format!("\"{}\"", html_escape(&w.row_label()))).collect();
+	let series = |value: fn(&OperationResult) -> f64| -> String {
+		let data: Vec<String> = windows
+			.iter()
+			.map(|w| match &w.result {
+				Some(r) => format!("{:.2}", value(r)),
+				None => "null".to_string(),
+			})
+			.collect();
+		format!("[{}]", data.join(", "))
+	};
+	format!(
+		r##"
+// Throughput, latency, and memory over the soak
+if (document.querySelector("#soakChart")) {{
+    new ApexCharts(document.querySelector("#soakChart"), {{
+        series: [
+            {{ name: 'OPS', data: {ops} }},
+            {{ name: 'p99 (ms)', data: {p99} }},
+            {{ name: 'Memory (MiB)', data: {memory} }}
+        ],
+        chart: {{
+            type: 'line',
+            height: 350,
+            fontFamily: '"Inter", system-ui, sans-serif',
+            foreColor: '#9990ab',
+            background: '#16141f',
+            toolbar: {{ show: false }},
+            animations: {{ enabled: false }}
+        }},
+        theme: {{ mode: 'dark' }},
+        grid: {{
+            borderColor: 'rgba(111, 121, 136, 0.2)',
+            strokeDashArray: 4
+        }},
+        stroke: {{
+            width: 3,
+            curve: 'straight'
+        }},
+        markers: {{ size: 4 }},
+        colors: ['#7c5cfc', '#d255fe', '#34d399'],
+        dataLabels: {{ enabled: false }},
+        legend: {{
+            position: 'top',
+            horizontalAlign: 'left',
+            labels: {{ colors: '#e8e4f0' }}
+        }},
+        xaxis: {{
+            categories: [{categories}],
+            title: {{ text: 'Elapsed' }}
+        }},
+        yaxis: [
+            {{ title: {{ text: 'OPS' }}, labels: {{ formatter: function(val) {{ return val === null ? '-' : val.toFixed(0); }} }} }},
+            {{ opposite: true, title: {{ text: 'p99 (ms)' }}, labels: {{ formatter: function(val) {{ return val === null ? '-' : val.toFixed(3); }} }} }},
+            {{ opposite: true, title: {{ text: 'Memory (MiB)' }}, labels: {{ formatter: function(val) {{ return val === null ? '-' : val.toFixed(0); }} }} }}
+        ],
+        tooltip: {{
+            shared: true,
+            intersect: false
+        }}
+    }}).render();
+}}
+"##,
+		ops = series(|r| r.ops()),
+		p99 = series(|r| r.q99() as f64 / 1000.0),
+		memory = series(|r| r.used_memory() as f64 / (1024.0 * 1024.0)),
+		categories = categories.join(", "),
 	)
 }
 
@@ -539,7 +677,7 @@ fn generate_stat_cards(result: &BenchmarkResult) -> String {
 	cards
 }
 
-fn html_escape(s: &str) -> String {
+pub(crate) fn html_escape(s: &str) -> String {
 	s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
 }
 
@@ -644,6 +782,37 @@ fn scan_distribution_title_attr(r: &OperationResult) -> String {
 	html_escape(&s)
 }
 
+/// Links to the flamegraph of each phase, of crud-bench and of the datastore container, when
+/// the run was profiled.
+fn generate_flamegraph_links(result: &BenchmarkResult) -> String {
+	[
+		("Flamegraph per phase", &result.flamegraphs),
+		("Server flamegraph per phase", &result.server_flamegraphs),
+	]
+	.into_iter()
+	.filter(|(_, flamegraphs)| !flamegraphs.is_empty())
+	.map(|(title, flamegraphs)| {
+		let links: String = flamegraphs
+			.iter()
+			.map(|f| {
+				format!(
+					r#"<li><a href="{}" target="_blank">{}</a></li>"#,
+					html_escape(&f.path),
+					html_escape(&f.phase)
+				)
+			})
+			.collect();
+		format!(
+			r#"            <div class="chart-container full-width">
+                <div class="chart-title">{title}</div>
+                <ul>{links}</ul>
+            </div>
+"#
+		)
+	})
+	.collect()
+}
+
 fn generate_scan_percentile_table_html(rows: &[(String, &OperationResult)]) -> String {
 	if rows.is_empty() {
 		return r#"<p class="chart-subtitle">No scan benchmarks in this result.</p>"#.to_string();
@@ -1358,6 +1527,116 @@ var batchPercentileChart = new ApexCharts(document.querySelector("#batchPercenti
     }}
 }});
 batchPercentileChart.render();
+
+// Page Latency Through the Table Chart
+var paginationChart = new ApexCharts(document.querySelector("#paginationChart"), {{
+    series: {pagination_series},
+    chart: {{
+        type: 'line',
+        height: 350,
+        fontFamily: '"Inter", system-ui, sans-serif',
+        foreColor: SD.muted,
+        background: SD.surface,
+        toolbar: {{ show: false }}
+    }},
+    theme: {{ mode: 'dark' }},
+    grid: {{
+        borderColor: SD.surfaceBorder,
+        strokeDashArray: 4
+    }},
+    stroke: {{
+        width: 3,
+        curve: 'straight'
+    }},
+    markers: {{ size: 4 }},
+    colors: ['#d255fe', '#7c5cfc', '#c471f5', '#651ddd', '#a78bfa', '#9b7dfb', '#e879f9', '#c084fc'],
+    dataLabels: {{ enabled: false }},
+    legend: {{
+        position: 'top',
+        horizontalAlign: 'left',
+        labels: {{ colors: SD.text }},
+        markers: {{
+            strokeColors: SD.surface
+        }}
+    }},
+    xaxis: {{
+        categories: {pagination_categories},
+        title: {{ text: 'Position in the walk' }}
+    }},
+    yaxis: {{
+        title: {{ text: 'Latency (microseconds)' }},
+        labels: {{
+            formatter: function(val) {{
+                return formatNumber(val);
+            }}
+        }}
+    }},
+    tooltip: {{
+        shared: true,
+        intersect: false,
+        y: {{
+            formatter: function(val) {{
+                return val === null ? '-' : (val / 1000).toFixed(3) + ' ms';
+            }}
+        }}
+    }}
+}});
+paginationChart.render();
+
+// Latency vs Dataset Size Chart
+var growthChart = new ApexCharts(document.querySelector("#growthChart"), {{
+    series: {growth_series},
+    chart: {{
+        type: 'line',
+        height: 350,
+        fontFamily: '"Inter", system-ui, sans-serif',
+        foreColor: SD.muted,
+        background: SD.surface,
+        toolbar: {{ show: false }}
+    }},
+    theme: {{ mode: 'dark' }},
+    grid: {{
+        borderColor: SD.surfaceBorder,
+        strokeDashArray: 4
+    }},
+    stroke: {{
+        width: 3,
+        curve: 'straight'
+    }},
+    markers: {{ size: 4 }},
+    colors: ['#d255fe', '#7c5cfc', '#c471f5', '#651ddd', '#a78bfa', '#9b7dfb', '#e879f9', '#c084fc'],
+    dataLabels: {{ enabled: false }},
+    legend: {{
+        position: 'top',
+        horizontalAlign: 'left',
+        labels: {{ colors: SD.text }},
+        markers: {{
+            strokeColors: SD.surface
+        }}
+    }},
+    xaxis: {{
+        categories: {growth_categories},
+        title: {{ text: 'Records' }}
+    }},
+    yaxis: {{
+        title: {{ text: 'Latency (microseconds)' }},
+        labels: {{
+            formatter: function(val) {{
+                return formatNumber(val);
+            }}
+        }}
+    }},
+    tooltip: {{
+        shared: true,
+        intersect: false,
+        y: {{
+            formatter: function(val) {{
+                return val === null ? '-' : (val / 1000).toFixed(3) + ' ms';
+            }}
+        }}
+    }}
+}});
+growthChart.render();
 "##,
 		ops_labels = get_ops_labels(result),
 		ops_data = get_ops_data(result),
@@ -1378,6 +1657,10 @@ batchPercentileChart.render();
 		batch_boxplot_data = get_batch_boxplot_data(result),
 		batch_ops_lookup = get_batch_ops_lookup(result),
 		batch_percentile_series = get_batch_percentile_series(result),
+		pagination_categories = get_pagination_categories(result),
+		pagination_series = get_pagination_series(result),
+		growth_categories = get_growth_categories(result),
+		growth_series = get_growth_series(result),
 	)
 }
 
@@ -1540,7 +1823,7 @@ fn scan_chart_rows(result: &BenchmarkResult) -> Vec<(String, &OperationResult)>
 	v
 }
 
-fn scan_chart_bar_height(row_count: usize) -> u32 {
+pub(crate) fn scan_chart_bar_height(row_count: usize) -> u32 {
 	const ROW_PX: u32 = 23;
 	const PAD: u32 = 120;
 	const MIN: u32 = 320;
@@ -1644,3 +1927,73 @@ fn get_batch_percentile_series(result: &BenchmarkResult) -> String {
 
 	format!("[{}]", series.join(", "))
 }
+
+/// The tenths of the longest paginated walk, as the share of the table walked through.
+fn get_pagination_categories(result: &BenchmarkResult) -> String {
+	let tenths = result.pagination.iter().map(|w| w.tenths.len()).max().unwrap_or(0);
+	let labels: Vec<String> = (0..tenths)
+		.map(|i| format!("\"{}–{}%\"", i * 100 / tenths, (i + 1) * 100 / tenths))
+		.collect();
+	format!("[{}]", labels.join(", "))
+}
+
+/// The mean page latency in each tenth of the walk, one series per pagination mode.
+fn get_pagination_series(result: &BenchmarkResult) -> String {
+	let series: Vec<String> = result
+		.pagination
+		.iter()
+		.filter(|w| !w.tenths.is_empty())
+		.map(|w| {
+			let data: Vec<String> = w.tenths.iter().map(u64::to_string).collect();
+			format!(
+				r##"{{
+                        name: '{}',
+                        data: [{}]
+                    }}"##,
+				w.mode,
+				data.join(", ")
+			)
+		})
+		.collect();
+	format!("[{}]", series.join(", "))
+}
+
+fn get_growth_categories(result: &BenchmarkResult) -> String {
+	let labels: Vec<String> = result
+		.growth
+		.iter()
+		.map(|stage| format!("\"{}\"", format_number_with_commas(stage.records as f64)))
+		.collect();
+	format!("[{}]", labels.join(", "))
+}
+
+/// The p50 and p99 latency of each phase across the growth stages,
+/// with gaps where a phase was skipped at a stage.
+fn get_growth_series(result: &BenchmarkResult) -> String {
+	let Some(first) = result.growth.first() else {
+		return "[]".to_string();
+	};
+	let mut series = vec![];
+	for (index, (phase, _)) in first.phases().iter().enumerate() {
+		for (label, quantile) in [("p50", 0), ("p99", 1)] {
+			let data: Vec<String> = result
+				.growth
+				.iter()
+				.map(|stage| match stage.phases().get(index).and_then(|(_, r)| *r) {
+					Some(r) if quantile == 0 => r.q50().to_string(),
+					Some(r) => r.q99().to_string(),
+					None => "null".to_string(),
+				})
+				.collect();
+			series.push(format!(
+				r##"{{
+                        name: '{} {label}',
+                        data: [{}]
+                    }}"##,
+				html_escape(phase).replace('\'', "\\'"),
+				data.join(", ")
+			));
+		}
+	}
+	format!("[{}]", series.join(", "))
+}
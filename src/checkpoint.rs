@@ -0,0 +1,154 @@
+//! Records the results of each completed phase to a checkpoint file (`--checkpoint`).
+//!
+//! A long run which crashes can then be restarted with `--resume`, restoring the phases the
+//! checkpoint holds instead of running them again. A phase interrupted part way through is
+//! run again from its start, so the checkpoint is only ever written between phases.
+
+use crate::result::BenchmarkMetadata;
+use anyhow::{Context, Result, bail};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// The contents of the checkpoint file.
+#[derive(Default, Serialize, Deserialize)]
+struct CheckpointFile {
+	/// The options of the run, which a resumed run must match.
+	run: Value,
+	/// The results of each completed phase, keyed by phase.
+	phases: BTreeMap<String, Value>,
+}
+
+/// The completed phases of a run, written to disk after every phase.
+pub(crate) struct Checkpoint {
+	/// The checkpoint file.
+	path: PathBuf,
+	/// The phases completed so far.
+	file: Mutex<CheckpointFile>,
+}
+
+impl Checkpoint {
+	/// Opens the checkpoint of a run, restoring its completed phases when resuming.
+	pub(crate) fn open(path: &str, resume: bool, metadata: &BenchmarkMetadata) -> Result<Self> {
+		let run = serde_json::to_value(metadata)?;
+		let path = PathBuf::from(path);
+		let file = match resume && path.exists() {
+			true => {
+				let text = std::fs::read_to_string(&path)
+					.with_context(|| format!("Failed to read checkpoint '{}'", path.display()))?;
+				let file: CheckpointFile = serde_json::from_str(&text)
+					.with_context(|| format!("Failed to parse checkpoint '{}'", path.display()))?;
+				if file.run != run {
+					bail!(
+						"The checkpoint '{}' was written by a run with different options: {}",
+						path.display(),
+						file.run
+					);
+				}
+				file
+			}
+			false => CheckpointFile {
+				run,
+				phases: BTreeMap::new(),
+			},
+		};
+		Ok(Self {
+			path,
+			file: Mutex::new(file),
+		})
+	}
+
+	/// The number of phases restored from the checkpoint file.
+	pub(crate) fn len(&self) -> usize {
+		self.file.lock().unwrap_or_else(|e| e.into_inner()).phases.len()
+	}
+
+	/// Whether the phase completed before the run was interrupted.
+	pub(crate) fn contains(&self, phase: &str) -> bool {
+		self.file.lock().unwrap_or_else(|e| e.into_inner()).phases.contains_key(phase)
+	}
+
+	/// The results of a completed phase, if it completed before the run was interrupted.
+	pub(crate) fn restore<T: DeserializeOwned>(&self, phase: &str) -> Result<Option<T>> {
+		let file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+		file.phases
+			.get(phase)
+			.map(|result| T::deserialize(result))
+			.transpose()
+			.with_context(|| format!("Failed to restore the {phase} phase from the checkpoint"))
+	}
+
+	/// Records the results of a completed phase, replacing the file so it is never half written.
+	pub(crate) fn save<T: Serialize>(&self, phase: &str, result: &T) -> Result<()> {
+		let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+		file.phases.insert(phase.to_string(), serde_json::to_value(result)?);
+		let tmp = self.path.with_extension("tmp");
+		std::fs::write(&tmp, serde_json::to_vec_pretty(&*file)?)
+			.with_context(|| format!("Failed to write checkpoint '{}'", tmp.display()))?;
+		std::fs::rename(&tmp, &self.path)
+			.with_context(|| format!("Failed to write checkpoint '{}'", self.path.display()))?;
+		Ok(())
+	}
+
+	/// Removes the checkpoint file once the results of the whole run are written.
+	pub(crate) fn remove(&self) -> Result<()> {
+		match std::fs::remove_file(&self.path) {
+			Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+			_ => Ok(()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn metadata(samples: u32) -> BenchmarkMetadata {
+		BenchmarkMetadata {
+			samples,
+			clients: 1,
+			threads: 1,
+			key_type: "Integer".to_string(),
+			random: false,
+			sync: false,
+			persisted: false,
+			pipelined: false,
+			prepared: true,
+			emulate_indexes: false,
+			materialize: "Full".to_string(),
+			optimised: false,
+			cost_per_hour: None,
+			preload: None,
+			scan_records: None,
+			seed: None,
+			topology: None,
+			netem: None,
+			server_options: BTreeMap::new(),
+			version: None,
+			image: None,
+			image_digest: None,
+		}
+	}
+
+	#[test]
+	fn resume_restores_completed_phases_of_the_same_run() {
+		let path = std::env::temp_dir().join(format!("crud-bench-{}.json", std::process::id()));
+		let path = path.to_str().unwrap();
+		let checkpoint = Checkpoint::open(path, false, &metadata(100)).unwrap();
+		checkpoint.save("synced_updates", &vec![(10u32, None::<u64>)]).unwrap();
+		// A fresh run starts over, ignoring the existing file
+		assert_eq!(Checkpoint::open(path, false, &metadata(100)).unwrap().len(), 0);
+		let resumed = Checkpoint::open(path, true, &metadata(100)).unwrap();
+		assert!(resumed.contains("synced_updates"));
+		let restored: Option<Vec<(u32, Option<u64>)>> = resumed.restore("synced_updates").unwrap();
+		assert_eq!(restored, Some(vec![(10, None)]));
+		assert_eq!(resumed.restore::<u64>("creates").unwrap(), None);
+		// A run with different options cannot resume it
+		assert!(Checkpoint::open(path, true, &metadata(200)).is_err());
+		resumed.remove().unwrap();
+		assert!(!std::path::Path::new(path).exists());
+	}
+}
@@ -0,0 +1,540 @@
+//! `crud-bench compare`: diff two or more `result*.json` files and flag throughput regressions.
+//!
+//! The first file is the baseline; every later file is compared against it row by row, using the
+//! same row labels as the CLI table and CSV output. The command exits with an error when any
+//! operation's OPS drops by more than `--threshold` percent, so nightly jobs can gate on it.
+
+use crate::result::{
+	PageMode, ScanWorkload, ServerResources, batch_row_label, cost_per_million_ops,
+	delete_strategy_row_label, growth_row_label, live_query_row_label, ops_per_gb,
+	page_walk_row_label, relation_row_label, scan_leg_row_label, secondary_read_row_label,
+	soak_row_label, synced_update_row_label, time_series_row_label,
+};
+use anyhow::{Context, Result, bail};
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, CellAlignment, Color, ContentArrangement, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Command-line interface for `crud-bench compare`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct CompareArgs {
+	/// Result JSON files to compare; the first file is the baseline
+	#[arg(required = true, num_args = 2..)]
+	pub(crate) files: Vec<String>,
+
+	/// Maximum allowed throughput drop per operation, in percent
+	#[arg(long, default_value = "5.0")]
+	pub(crate) threshold: f64,
+}
+
+/// The subset of a serialised [`crate::result::BenchmarkResult`] needed for comparisons.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ResultFile {
+	/// Display name of the datastore under test.
+	pub(crate) database: Option<String>,
+	/// Host snapshot, when collected.
+	pub(crate) system: Option<HostInfo>,
+	/// CLI parameters snapshot, when recorded.
+	pub(crate) metadata: Option<RunMetadata>,
+	/// Server resources that throughput is normalised against.
+	#[serde(default)]
+	pub(crate) resources: Option<ServerResources>,
+	/// Single-record insert phase.
+	creates: Option<PhaseResult>,
+	/// Single-record read phase.
+	reads: Option<PhaseResult>,
+	/// Single-record read phase through a restricted role.
+	restricted_reads: Option<PhaseResult>,
+	/// Reads by the `unique` field, with and without an index.
+	#[serde(default)]
+	secondary_reads: Option<SecondaryReadsEntry>,
+	/// Linking the records to each other and following the links.
+	#[serde(default)]
+	relations: Option<RelationsEntry>,
+	/// Writes notifying open live queries.
+	#[serde(default)]
+	live_queries: Option<LiveQueriesEntry>,
+	/// Writes to the primary polled for on a replica.
+	#[serde(default)]
+	replication_lag: Option<ReplicationLagEntry>,
+	/// Single-record update phase.
+	updates: Option<PhaseResult>,
+	/// Single-record update phase of one field at a dotted path.
+	path_updates: Option<PhaseResult>,
+	/// `(writes_per_sync, result_or_skip)`, one per sync interval.
+	#[serde(default)]
+	synced_updates: Vec<(u32, Option<PhaseResult>)>,
+	/// The windows of a soak run.
+	#[serde(default)]
+	soak: Vec<SoakEntry>,
+	/// Single-record delete phase.
+	deletes: Option<PhaseResult>,
+	/// `(strategy, result_or_skip)`, one per bulk deletion.
+	#[serde(default)]
+	delete_strategies: Vec<(String, Option<PhaseResult>)>,
+	/// One entry per configured scan id.
+	#[serde(default)]
+	scans: Vec<ScanEntry>,
+	/// The offset then keyset walks over the whole table.
+	#[serde(default)]
+	pagination: Vec<PageWalkEntry>,
+	/// One entry per dataset size of a growth sweep.
+	#[serde(default)]
+	growth: Vec<GrowthEntry>,
+	/// `(batch_case_name, timed_iterations, records_per_batch, result_or_skip)`.
+	#[serde(default)]
+	batches: Vec<(String, u32, usize, Option<PhaseResult>)>,
+	/// `(phase_name, timed_iterations, result_or_skip)`, the append phase then each query.
+	#[serde(default)]
+	time_series: Vec<(String, u32, Option<PhaseResult>)>,
+}
+
+/// The subset of a serialised [`crate::system::SystemInfo`] describing the benchmark hardware.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct HostInfo {
+	pub(crate) os_name: String,
+	pub(crate) cpu_arch: String,
+	pub(crate) cpu_cores: usize,
+	pub(crate) cpu_physical_cores: usize,
+	/// Total memory in bytes.
+	pub(crate) total_memory: u64,
+}
+
+/// The subset of a serialised [`crate::result::BenchmarkMetadata`] describing the run parameters.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct RunMetadata {
+	pub(crate) samples: u32,
+	pub(crate) clients: u32,
+	pub(crate) threads: u32,
+	pub(crate) key_type: String,
+	pub(crate) random: bool,
+	/// Hourly instance and storage price, when costs are reported.
+	pub(crate) cost_per_hour: Option<f64>,
+	/// Version reported by the datastore, when it was detected.
+	pub(crate) version: Option<String>,
+}
+
+/// Throughput and latency percentiles (µs) of one timed phase.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct PhaseResult {
+	/// Operations per second for the phase.
+	pub(crate) ops: f64,
+	pub(crate) mean: f64,
+	pub(crate) min: u64,
+	pub(crate) q01: u64,
+	pub(crate) q25: u64,
+	pub(crate) q50: u64,
+	pub(crate) q75: u64,
+	pub(crate) q95: u64,
+	pub(crate) q99: u64,
+	pub(crate) max: u64,
+	/// The complete latency histogram, when the file stores it.
+	pub(crate) histogram: Option<String>,
+	/// Mean resident memory across polls (bytes).
+	pub(crate) memory_avg: u64,
+	/// Resident memory at the end of the phase (bytes).
+	pub(crate) used_memory: u64,
+}
+
+impl PhaseResult {
+	/// Operations per second for each GB of mean resident memory, if memory was sampled.
+	pub(crate) fn ops_per_gb(&self) -> Option<f64> {
+		ops_per_gb(self.ops, self.memory_avg, self.used_memory)
+	}
+
+	/// Price of one million operations at the given hourly price.
+	pub(crate) fn cost_per_million_ops(&self, cost_per_hour: f64) -> Option<f64> {
+		cost_per_million_ops(self.ops, cost_per_hour)
+	}
+}
+
+/// One scan entry with its timed legs and optional index phases.
+#[derive(Debug, Deserialize)]
+struct ScanEntry {
+	id: String,
+	name: String,
+	samples: u32,
+	index_build: Option<PhaseResult>,
+	index_remove: Option<PhaseResult>,
+	#[serde(default)]
+	idle_writes: Option<PhaseResult>,
+	#[serde(default)]
+	build_writes: Option<PhaseResult>,
+	runs: Vec<ScanRunEntry>,
+}
+
+/// One timed scan leg.
+#[derive(Debug, Deserialize)]
+struct ScanRunEntry {
+	workload: ScanWorkload,
+	indexed: bool,
+	#[serde(default)]
+	hinted: bool,
+	#[serde(default)]
+	result: Option<PhaseResult>,
+}
+
+/// Reads by the `unique` field, before and after it is indexed.
+#[derive(Debug, Deserialize)]
+struct SecondaryReadsEntry {
+	field: String,
+	without_index: Option<PhaseResult>,
+	index_build: Option<PhaseResult>,
+	with_index: Option<PhaseResult>,
+	index_remove: Option<PhaseResult>,
+}
+
+/// Records linked to each other, then read by following the links.
+#[derive(Debug, Deserialize)]
+struct RelationsEntry {
+	fanout: u32,
+	relate: Option<PhaseResult>,
+	traverse: Option<PhaseResult>,
+}
+
+/// Writes notifying open live queries.
+#[derive(Debug, Deserialize)]
+struct LiveQueriesEntry {
+	subscribers: u32,
+	writes: Option<PhaseResult>,
+}
+
+/// Writes to the primary polled for on a replica.
+#[derive(Debug, Deserialize)]
+struct ReplicationLagEntry {
+	writes: Option<PhaseResult>,
+}
+
+/// One walk over the whole table, a page at a time.
+#[derive(Debug, Deserialize)]
+struct PageWalkEntry {
+	mode: PageMode,
+	page_size: u32,
+	pages: u32,
+	result: Option<PhaseResult>,
+}
+
+/// One window of a soak run.
+#[derive(Debug, Deserialize)]
+struct SoakEntry {
+	elapsed: u64,
+	result: Option<PhaseResult>,
+}
+
+/// The reads and table scans repeated at one dataset size.
+#[derive(Debug, Deserialize)]
+struct GrowthEntry {
+	records: u32,
+	reads: Option<PhaseResult>,
+	#[serde(default)]
+	scans: Vec<(String, Option<PhaseResult>)>,
+}
+
+impl ResultFile {
+	/// Loads and parses a result JSON file.
+	pub(crate) fn load(path: &str) -> Result<Self> {
+		let text = std::fs::read_to_string(path)
+			.with_context(|| format!("Failed to read result file '{path}'"))?;
+		serde_json::from_str(&text).with_context(|| format!("Failed to parse result file '{path}'"))
+	}
+
+	/// Hourly price recorded with the run, when costs are reported.
+	pub(crate) fn cost_per_hour(&self) -> Option<f64> {
+		self.metadata.as_ref().and_then(|m| m.cost_per_hour)
+	}
+
+	/// Row labels and phase results in CLI table order (`None` when the phase was skipped).
+	pub(crate) fn rows(&self) -> Vec<(String, Option<&PhaseResult>)> {
+		let phase = Option::<PhaseResult>::as_ref;
+		let mut rows = vec![
+			("[C]reate".to_string(), phase(&self.creates)),
+			("[R]ead".to_string(), phase(&self.reads)),
+			("[R]ead (restricted)".to_string(), phase(&self.restricted_reads)),
+		];
+		rows.retain(|(_, v)| v.is_some());
+		if let Some(s) = &self.secondary_reads {
+			let field = &s.field;
+			rows.extend([
+				(secondary_read_row_label(field, "without index"), phase(&s.without_index)),
+				(format!("[I]ndex · {field} · build"), phase(&s.index_build)),
+				(secondary_read_row_label(field, "with index"), phase(&s.with_index)),
+				(format!("[R]emoveIndex · {field}"), phase(&s.index_remove)),
+			]);
+		}
+		if let Some(r) = &self.relations {
+			rows.extend([
+				(relation_row_label(r.fanout, "relate"), phase(&r.relate)),
+				(relation_row_label(r.fanout, "traverse"), phase(&r.traverse)),
+			]);
+		}
+		if let Some(l) = &self.live_queries {
+			rows.push((live_query_row_label(l.subscribers), phase(&l.writes)));
+		}
+		if let Some(r) = &self.replication_lag {
+			rows.push(("[C]reate (replicated)".to_string(), phase(&r.writes)));
+		}
+		let updates = [
+			("[U]pdate".to_string(), phase(&self.updates)),
+			("[U]pdate (path)".to_string(), phase(&self.path_updates)),
+		];
+		rows.extend(updates.into_iter().filter(|(_, v)| v.is_some()));
+		for (interval, result) in &self.synced_updates {
+			rows.push((synced_update_row_label(*interval), phase(result)));
+		}
+		for window in &self.soak {
+			rows.push((soak_row_label(window.elapsed), phase(&window.result)));
+		}
+		if self.deletes.is_some() {
+			rows.push(("[D]elete".to_string(), phase(&self.deletes)));
+		}
+		for (strategy, result) in &self.delete_strategies {
+			rows.push((delete_strategy_row_label(strategy), phase(result)));
+		}
+		for scan in &self.scans {
+			let leg = |r| scan.leg_row(r);
+			let indexed = scan.runs.iter().any(|r| r.indexed);
+			rows.extend(scan.runs.iter().filter(|r| !r.indexed).map(leg));
+			if indexed {
+				rows.push((format!("[I]ndex · {} · build", scan.id), phase(&scan.index_build)));
+			}
+			if scan.idle_writes.is_some() {
+				let label = format!("[I]ndex · {} · updates idle", scan.id);
+				rows.push((label, phase(&scan.idle_writes)));
+			}
+			if scan.build_writes.is_some() {
+				let label = format!("[I]ndex · {} · updates during build", scan.id);
+				rows.push((label, phase(&scan.build_writes)));
+			}
+			rows.extend(scan.runs.iter().filter(|r| r.indexed).map(leg));
+			if indexed {
+				rows.push((format!("[R]emoveIndex · {}", scan.id), phase(&scan.index_remove)));
+			}
+		}
+		for walk in &self.pagination {
+			let label = page_walk_row_label(walk.mode, walk.pages, walk.page_size);
+			rows.push((label, phase(&walk.result)));
+		}
+		for stage in &self.growth {
+			rows.push((growth_row_label(stage.records, "read"), phase(&stage.reads)));
+			for (name, result) in &stage.scans {
+				rows.push((
+					growth_row_label(stage.records, &format!("scan · {name}")),
+					phase(result),
+				));
+			}
+		}
+		for (name, samples, groups, result) in &self.batches {
+			rows.push((batch_row_label(name, *samples, *groups), phase(result)));
+		}
+		for (name, samples, result) in &self.time_series {
+			rows.push((time_series_row_label(name, *samples), phase(result)));
+		}
+		rows
+	}
+}
+
+impl ScanEntry {
+	/// Row label and result of one timed scan leg.
+	fn leg_row<'a>(&self, run: &'a ScanRunEntry) -> (String, Option<&'a PhaseResult>) {
+		let label = scan_leg_row_label(
+			&self.id,
+			&self.name,
+			self.samples,
+			&run.workload,
+			run.indexed,
+			run.hinted,
+		);
+		(label, run.result.as_ref())
+	}
+}
+
+/// Relative throughput change in percent, or `None` when there is no usable baseline.
+fn change_percent(baseline: f64, candidate: f64) -> Option<f64> {
+	(baseline > 0.0).then(|| (candidate - baseline) / baseline * 100.0)
+}
+
+/// Whether a change breaches the allowed regression threshold.
+fn is_regression(change: f64, threshold: f64) -> bool {
+	change < -threshold.abs()
+}
+
+/// Column title for a result file: file name plus database, when recorded.
+pub(crate) fn column_title(path: &str, result: &ResultFile) -> String {
+	let file = Path::new(path).file_name().and_then(|f| f.to_str()).unwrap_or(path);
+	match &result.database {
+		Some(db) => format!("{file}\n({db})"),
+		None => file.to_string(),
+	}
+}
+
+/// Runs the comparison, prints the table, and fails when any operation regressed.
+pub(crate) fn run(args: CompareArgs) -> Result<()> {
+	let results =
+		args.files.iter().map(|f| ResultFile::load(f)).collect::<Result<Vec<ResultFile>>>()?;
+	let ops = |r: &ResultFile| -> Vec<(String, Option<f64>)> {
+		r.rows().into_iter().map(|(label, phase)| (label, phase.map(|p| p.ops))).collect()
+	};
+	let baseline = ops(&results[0]);
+	let candidates: Vec<HashMap<String, Option<f64>>> =
+		results[1..].iter().map(|r| ops(r).into_iter().collect()).collect();
+	// Build the table header: baseline OPS, then OPS and delta per candidate
+	let mut table = Table::new();
+	table
+		.load_preset(UTF8_FULL)
+		.apply_modifier(UTF8_ROUND_CORNERS)
+		.set_content_arrangement(ContentArrangement::Dynamic);
+	let mut headers = vec!["Test".to_string(), column_title(&args.files[0], &results[0])];
+	for (path, result) in args.files[1..].iter().zip(&results[1..]) {
+		headers.push(column_title(path, result));
+		headers.push("Δ OPS".to_string());
+	}
+	table.set_header(
+		headers.into_iter().map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Blue)),
+	);
+	// Compare every baseline row against each candidate
+	let mut regressions = Vec::new();
+	for (label, base) in &baseline {
+		let mut row = vec![Cell::new(label), ops_cell(*base)];
+		for (candidate, path) in candidates.iter().zip(&args.files[1..]) {
+			let ops = candidate.get(label).copied().flatten();
+			row.push(ops_cell(ops));
+			match base.zip(ops).and_then(|(b, o)| change_percent(b, o)) {
+				Some(change) => {
+					let cell = Cell::new(format!("{change:+.2}%"));
+					if is_regression(change, args.threshold) {
+						regressions.push(format!("{label} ({path}): {change:+.2}%"));
+						row.push(cell.fg(Color::Red).add_attribute(Attribute::Bold));
+					} else if change > args.threshold.abs() {
+						row.push(cell.fg(Color::Green));
+					} else {
+						row.push(cell);
+					}
+				}
+				None => row.push(Cell::new("-")),
+			}
+		}
+		table.add_row(row);
+	}
+	// Right align the numeric columns
+	for i in 1..table.column_count() {
+		if let Some(column) = table.column_mut(i) {
+			column.set_cell_alignment(CellAlignment::Right);
+		}
+	}
+	println!("{table}");
+	// Fail with a non-zero exit code when any throughput regressed
+	if !regressions.is_empty() {
+		for r in &regressions {
+			eprintln!("Regression: {r}");
+		}
+		bail!(
+			"{} operation(s) regressed by more than {}% throughput",
+			regressions.len(),
+			args.threshold.abs()
+		);
+	}
+	println!("No throughput regressions beyond {}%", args.threshold.abs());
+	Ok(())
+}
+
+/// Formats an OPS value, or `-` when the phase was skipped or missing.
+fn ops_cell(ops: Option<f64>) -> Cell {
+	match ops {
+		Some(v) => Cell::new(format!("{v:.2}")),
+		None => Cell::new("-"),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{ResultFile, change_percent, is_regression};
+
+	const RESULT: &str = r#"{
+		"database": "DashMap",
+		"creates": {"ops": 1000.0},
+		"reads": {"ops": 2000.0},
+		"secondary_reads": {
+			"field": "sk",
+			"samples": 10,
+			"without_index": {"ops": 20.0},
+			"index_build": null,
+			"with_index": {"ops": 1500.0},
+			"index_remove": null
+		},
+		"relations": {"fanout": 3, "samples": 10, "relate": {"ops": 300.0}, "traverse": null},
+		"updates": null,
+		"synced_updates": [[10, {"ops": 400.0}], [100, null]],
+		"deletes": {"ops": 500.0},
+		"delete_strategies": [["truncate", {"ops": 2.0}], ["ranges of 1000", null]],
+		"scans": [{
+			"id": "limit",
+			"name": "select(id) limit(100)",
+			"samples": 10,
+			"index_build": {"ops": 1.0},
+			"index_remove": null,
+			"runs": [
+				{"workload": {"kind": "read"}, "indexed": false, "result": {"ops": 100.0}},
+				{"workload": {"kind": "read_write", "write_ratio_percent": 10}, "indexed": true}
+			]
+		}],
+		"pagination": [
+			{"mode": "offset", "page_size": 100, "pages": 10, "tenths": [], "result": {"ops": 40.0}},
+			{"mode": "keyset", "page_size": 100, "pages": 10, "tenths": [], "result": null}
+		],
+		"growth": [{"records": 1000, "reads": {"ops": 800.0}, "scans": [["limit · select(id) limit(100)", null]]}],
+		"batches": [["batch_test", 10, 5, {"ops": 50.0}]],
+		"time_series": [["append", 100, {"ops": 900.0}], ["last_minute", 10, null]]
+	}"#;
+
+	#[test]
+	fn rows_follow_table_order() {
+		let result: ResultFile = serde_json::from_str(RESULT).unwrap();
+		let labels: Vec<String> = result.rows().into_iter().map(|(l, _)| l).collect();
+		assert_eq!(
+			labels,
+			vec![
+				"[C]reate",
+				"[R]ead",
+				"[R]ead · by sk · without index",
+				"[I]ndex · sk · build",
+				"[R]ead · by sk · with index",
+				"[R]emoveIndex · sk",
+				"[L]ink · 3 per record · relate",
+				"[L]ink · 3 per record · traverse",
+				"[U]pdate (sync every 10)",
+				"[U]pdate (sync every 100)",
+				"[D]elete",
+				"[D]elete (truncate)",
+				"[D]elete (ranges of 1000)",
+				"[S]can · limit · select(id) limit(100) - reads - no-index (10)",
+				"[I]ndex · limit · build",
+				"[S]can · limit · select(id) limit(100) - reads+writes (10%) - indexed (10)",
+				"[R]emoveIndex · limit",
+				"[P]age · offset · 10 pages of 100",
+				"[P]age · keyset · 10 pages of 100",
+				"[G]rowth · 1000 records · read",
+				"[G]rowth · 1000 records · scan · limit · select(id) limit(100)",
+				"[B]atch::batch_test (10 batches of 5)",
+				"[T]ime series::append (100)",
+				"[T]ime series::last_minute (10)",
+			]
+		);
+	}
+
+	#[test]
+	fn regression_threshold() {
+		let change = change_percent(1000.0, 900.0).unwrap();
+		assert!((change + 10.0).abs() < 1e-9);
+		assert!(is_regression(change, 5.0));
+		assert!(!is_regression(change, 10.0));
+		assert!(!is_regression(change_percent(1000.0, 1200.0).unwrap(), 5.0));
+		assert!(change_percent(0.0, 10.0).is_none());
+	}
+}
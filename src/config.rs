@@ -1,9 +1,28 @@
-//! Single-file benchmark workload definition (`config/bench.toml`).
+//! Single-file benchmark definition (`config/bench.toml`), in TOML or, with a `.yaml` or `.yml`
+//! extension, YAML.
+//!
+//! The `[run]` table holds command-line options, which are passed to the CLI parser ahead of the
+//! real arguments, so explicit flags override the file. Before deserializing the workload,
+//! `{{name}}` placeholders in the `[[scans]]`, `[[batches]]`, and `[time_series]` entries are
+//! replaced from the `[params]` table (plus the built-in `samples`), and each `[[scans]]` or
+//! `[[batches]]` entry with a `matrix` table is expanded into one entry per combination of its
+//! values. An entry's `overrides.<database>` table is then merged into it when benchmarking that
+//! database, so one engine can adjust or `skip` a scenario.
 
-use crate::{BatchOperation, ScanSpec};
-use anyhow::{Context, Result};
-use serde::Deserialize;
-use serde_json::Value;
+use crate::assertions::Assertion;
+use crate::retry::Retries;
+use crate::timeseries::TimeSeries;
+use crate::{Args, BatchOperation, Batches, ScanSpec, Scans};
+use anyhow::{Context, Result, bail, ensure};
+use clap::CommandFactory;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use toml::Table;
+
+/// Benchmark definition read when neither `--config` nor `CRUD_BENCH_CONFIG` is set.
+pub(crate) const DEFAULT_CONFIG: &str = "config/bench.toml";
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct BenchToml {
@@ -11,11 +30,447 @@ pub(crate) struct BenchToml {
 	pub(crate) scans: Vec<ScanSpec>,
 	#[serde(default)]
 	pub(crate) batches: Vec<BatchOperation>,
+	pub(crate) time_series: Option<TimeSeries>,
+	pub(crate) value: Value,
+	/// Conditions on the results, such as `reads.p99 < 5ms`, which fail the run when not met.
+	#[serde(default)]
+	pub(crate) assertions: Vec<Assertion>,
+	/// How the operations which fail with a transient error are retried.
+	#[serde(default)]
+	pub(crate) retries: Retries,
+	/// The samples of the reads and updates, when they differ from `--samples`.
+	#[serde(default)]
+	pub(crate) samples: PhaseSamples,
+	/// The definition with its templates expanded and overrides applied, recorded in the results.
+	#[serde(skip)]
+	pub(crate) definition: Value,
+}
+
+/// The configuration a run resolved to, recorded in its results, so that a published result is
+/// reproducible from the file alone rather than from the command line which produced it.
+#[derive(Debug, Serialize)]
+pub(crate) struct EffectiveConfig {
+	/// Every command-line option with the value it took, including the defaults.
+	pub(crate) options: Map<String, Value>,
+	/// How the record keys were generated.
+	pub(crate) key: KeySettings,
+	/// The value template the records were generated from.
 	pub(crate) value: Value,
+	/// The scans as they ran, with their runs and matrices expanded and the skipped indexes marked.
+	pub(crate) scans: Scans,
+	/// The batches as they ran.
+	pub(crate) batches: Batches,
+	/// The time-series workload, when it ran.
+	pub(crate) time_series: Option<TimeSeries>,
+	/// How the failed operations were retried.
+	pub(crate) retries: Retries,
+	/// The samples of the reads and updates, when they differed from `--samples`.
+	pub(crate) samples: PhaseSamples,
 }
 
-pub(crate) fn load_bench_toml(path: &str) -> Result<BenchToml> {
+/// The `[samples]` table: the samples of the reads and updates, which may run more or fewer
+/// samples than the records created by `--samples`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PhaseSamples {
+	/// Samples of the reads
+	pub(crate) read: Option<u32>,
+	/// Samples of the updates
+	pub(crate) update: Option<u32>,
+}
+
+/// How the record keys and values were generated.
+#[derive(Debug, Serialize)]
+pub(crate) struct KeySettings {
+	/// Stringified [`crate::KeyType`].
+	pub(crate) key_type: String,
+	/// Whether the keys were generated in random order.
+	pub(crate) random: bool,
+	/// Seed the values were generated from, including one picked at random for `--verify`.
+	pub(crate) seed: Option<u64>,
+}
+
+pub(crate) fn load_bench_toml(path: &str, samples: u32, database: &str) -> Result<BenchToml> {
 	let text = std::fs::read_to_string(path)
 		.with_context(|| format!("Failed to read config file '{path}'"))?;
-	toml::from_str(&text).with_context(|| format!("Failed to parse benchmark TOML '{path}'"))
+	parse_bench_toml(path, &text, samples, database)
+		.with_context(|| format!("Failed to parse benchmark definition '{path}'"))
+}
+
+/// The process arguments with the `[run]` options of the benchmark definition inserted before
+/// the real ones, so that flags given on the command line take precedence over the file.
+pub(crate) fn command_line() -> Result<Vec<OsString>> {
+	command_line_from(std::env::args_os().collect())
+}
+
+/// The given arguments with the `[run]` options of their benchmark definition inserted.
+pub(crate) fn command_line_from(mut args: Vec<OsString>) -> Result<Vec<OsString>> {
+	// The commands run in place of a benchmark take none of its options
+	let command = args.get(1).and_then(|a| a.to_str());
+	if command.is_some_and(|c| Args::command().find_subcommand(c).is_some()) {
+		return Ok(args);
+	}
+	let path = config_path(&args);
+	let text = match std::fs::read_to_string(&path) {
+		Ok(text) => text,
+		// A missing file is reported once the workload is loaded, after `--help` had its chance
+		Err(e) if e.kind() == ErrorKind::NotFound => return Ok(args),
+		Err(e) => return Err(e).with_context(|| format!("Failed to read config file '{path}'")),
+	};
+	let run = match parse_document(&path, &text)?.remove("run") {
+		Some(toml::Value::Table(run)) => run,
+		Some(_) => bail!("`run` in '{path}' must be a table of command-line options"),
+		None => return Ok(args),
+	};
+	let flags = run_flags(run).with_context(|| format!("Invalid `run` options in '{path}'"))?;
+	let at = args.len().min(1);
+	args.splice(at..at, flags.into_iter().map(OsString::from));
+	Ok(args)
+}
+
+/// The `--config` argument, falling back to `CRUD_BENCH_CONFIG` and then the default path.
+fn config_path(args: &[OsString]) -> String {
+	let mut args = args.iter().skip(1).filter_map(|a| a.to_str());
+	while let Some(arg) = args.next() {
+		if arg == "--config"
+			&& let Some(path) = args.next()
+		{
+			return path.to_string();
+		}
+		if let Some(path) = arg.strip_prefix("--config=") {
+			return path.to_string();
+		}
+	}
+	std::env::var("CRUD_BENCH_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG.to_string())
+}
+
+/// Turns `key = value` options into `--key=value` flags, with `true` as a bare switch, `false`
+/// omitted, and arrays joined by commas.
+fn run_flags(run: Table) -> Result<Vec<String>> {
+	let mut flags = Vec::with_capacity(run.len());
+	for (key, value) in run {
+		ensure!(key != "config", "`config` cannot be set from the benchmark definition");
+		let flag = format!("--{}", key.replace('_', "-"));
+		match value {
+			toml::Value::Boolean(true) => flags.push(flag),
+			toml::Value::Boolean(false) => {}
+			toml::Value::Array(values) => {
+				let values = values.iter().map(|v| scalar(&key, v)).collect::<Result<Vec<_>>>()?;
+				flags.push(format!("{flag}={}", values.join(",")));
+			}
+			value => flags.push(format!("{flag}={}", scalar(&key, &value)?)),
+		}
+	}
+	Ok(flags)
+}
+
+/// The command-line text of a string or number option value.
+fn scalar(key: &str, value: &toml::Value) -> Result<String> {
+	match value {
+		toml::Value::String(s) => Ok(s.clone()),
+		toml::Value::Integer(i) => Ok(i.to_string()),
+		toml::Value::Float(f) => Ok(f.to_string()),
+		_ => bail!("`{key}` must be a string, number, boolean, or array of them"),
+	}
+}
+
+/// Parses the definition as YAML for a `.yaml` or `.yml` path, and as TOML otherwise.
+fn parse_document(path: &str, text: &str) -> Result<Table> {
+	match path.ends_with(".yaml") || path.ends_with(".yml") {
+		true => Ok(serde_yaml::from_str(text)?),
+		false => Ok(toml::from_str(text)?),
+	}
+}
+
+/// Parses the specification text, expanding templates with `samples` as the built-in parameter
+/// and applying the overrides for `database` (its `--database` value, e.g. `mongodb`).
+fn parse_bench_toml(path: &str, text: &str, samples: u32, database: &str) -> Result<BenchToml> {
+	let mut doc = parse_document(path, text)?;
+	// The run options were already applied to the command line
+	doc.remove("run");
+	expand_templates(&mut doc, samples)?;
+	apply_overrides(&mut doc, database)?;
+	let definition = serde_json::to_value(&doc)?;
+	Ok(BenchToml {
+		definition,
+		..toml::Value::Table(doc).try_into()?
+	})
+}
+
+/// Removes the `[params]` table and expands the placeholders and matrices of the workload entries.
+fn expand_templates(doc: &mut Table, samples: u32) -> Result<()> {
+	let mut params = match doc.remove("params") {
+		Some(toml::Value::Table(params)) => params,
+		Some(_) => bail!("`params` must be a table"),
+		None => Table::new(),
+	};
+	// The record count is available to every entry unless overridden
+	params.entry("samples").or_insert(toml::Value::Integer(samples.into()));
+	for key in ["scans", "batches"] {
+		let Some(entries) = doc.get_mut(key) else {
+			continue;
+		};
+		let toml::Value::Array(entries) = entries else {
+			bail!("`{key}` must be an array of tables");
+		};
+		*entries = expand_entries(std::mem::take(entries), &params)
+			.with_context(|| format!("Failed to expand `{key}`"))?;
+	}
+	if let Some(time_series) = doc.get_mut("time_series") {
+		substitute(time_series, &params).context("Failed to expand `time_series`")?;
+	}
+	Ok(())
+}
+
+/// Removes the `overrides` table of every scan and batch, merging in the one for `database`.
+fn apply_overrides(doc: &mut Table, database: &str) -> Result<()> {
+	for key in ["scans", "batches"] {
+		let entries = doc.get_mut(key).and_then(toml::Value::as_array_mut).into_iter().flatten();
+		for entry in entries.filter_map(toml::Value::as_table_mut) {
+			let overrides = match entry.remove("overrides") {
+				Some(toml::Value::Table(overrides)) => overrides,
+				Some(_) => bail!("`overrides` in `{key}` must be a table of database tables"),
+				None => continue,
+			};
+			match overrides.get(database) {
+				Some(toml::Value::Table(changes)) => merge(entry, changes.clone()),
+				Some(_) => bail!("`overrides.{database}` in `{key}` must be a table"),
+				None => {}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Merges `changes` into `table`, recursing into nested tables and replacing any other value.
+fn merge(table: &mut Table, changes: Table) {
+	for (key, value) in changes {
+		match (table.get_mut(&key), value) {
+			(Some(toml::Value::Table(nested)), toml::Value::Table(changes)) => {
+				merge(nested, changes)
+			}
+			(_, value) => {
+				table.insert(key, value);
+			}
+		}
+	}
+}
+
+/// Expands each entry once per combination of its `matrix` values, or once without a matrix.
+fn expand_entries(entries: Vec<toml::Value>, params: &Table) -> Result<Vec<toml::Value>> {
+	let mut expanded = Vec::with_capacity(entries.len());
+	for mut entry in entries {
+		let matrix = match entry.as_table_mut().and_then(|t| t.remove("matrix")) {
+			Some(toml::Value::Table(matrix)) => matrix,
+			Some(_) => bail!("`matrix` must be a table of value arrays"),
+			None => Table::new(),
+		};
+		for combination in combinations(&matrix)? {
+			let mut scope = params.clone();
+			scope.extend(combination);
+			let mut instance = entry.clone();
+			substitute(&mut instance, &scope)?;
+			expanded.push(instance);
+		}
+	}
+	Ok(expanded)
+}
+
+/// Every combination of the matrix values, in key order with the last key varying fastest.
+fn combinations(matrix: &Table) -> Result<Vec<Table>> {
+	let mut combinations = vec![Table::new()];
+	for (key, values) in matrix {
+		let toml::Value::Array(values) = values else {
+			bail!("Matrix key `{key}` must be an array of values");
+		};
+		ensure!(!values.is_empty(), "Matrix key `{key}` must list at least one value");
+		combinations = combinations
+			.into_iter()
+			.flat_map(|combination| {
+				values.iter().map(move |value| {
+					let mut combination = combination.clone();
+					combination.insert(key.clone(), value.clone());
+					combination
+				})
+			})
+			.collect();
+	}
+	Ok(combinations)
+}
+
+/// Replaces the `{{name}}` placeholders in every string of `value` from `scope`.
+///
+/// A string holding a single placeholder takes the parameter value as-is, so `"{{limit}}"`
+/// becomes an integer; otherwise parameters are interpolated into the surrounding text.
+fn substitute(value: &mut toml::Value, scope: &Table) -> Result<()> {
+	match value {
+		toml::Value::String(text) => {
+			if let Some(name) = lone_placeholder(text) {
+				*value = lookup(scope, name)?.clone();
+				return Ok(());
+			}
+			let mut output = String::with_capacity(text.len());
+			let mut rest = text.as_str();
+			while let Some(open) = rest.find("{{") {
+				let Some(close) = rest[open..].find("}}") else {
+					bail!("Unclosed placeholder in `{text}`");
+				};
+				output.push_str(&rest[..open]);
+				let name = rest[open + 2..open + close].trim();
+				match lookup(scope, name)? {
+					toml::Value::String(s) => output.push_str(s),
+					v @ (toml::Value::Integer(_)
+					| toml::Value::Float(_)
+					| toml::Value::Boolean(_)) => output.push_str(&v.to_string()),
+					_ => bail!("Template parameter `{name}` cannot be interpolated into `{text}`"),
+				}
+				rest = &rest[open + close + 2..];
+			}
+			output.push_str(rest);
+			*text = output;
+		}
+		toml::Value::Array(values) => {
+			for value in values {
+				substitute(value, scope)?;
+			}
+		}
+		toml::Value::Table(table) => {
+			for (_, value) in table.iter_mut() {
+				substitute(value, scope)?;
+			}
+		}
+		_ => {}
+	}
+	Ok(())
+}
+
+/// The parameter name when `text` is exactly one placeholder.
+fn lone_placeholder(text: &str) -> Option<&str> {
+	let name = text.strip_prefix("{{")?.strip_suffix("}}")?;
+	(!name.contains("{{") && !name.contains("}}")).then(|| name.trim())
+}
+
+/// Resolves a parameter name, with dotted segments selecting fields of table values.
+fn lookup<'a>(scope: &'a Table, name: &str) -> Result<&'a toml::Value> {
+	let mut segments = name.split('.');
+	let mut value = segments.next().and_then(|first| scope.get(first));
+	for segment in segments {
+		value = value.and_then(|v| v.get(segment));
+	}
+	value.with_context(|| format!("Unknown template parameter `{name}`"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const VALUE: &str = "[value]\ntext = \"string:50\"\n";
+
+	#[test]
+	fn matrix_expands_in_key_order() -> Result<()> {
+		let text = format!(
+			r#"{VALUE}
+[params]
+iterations = 50
+
+[[batches]]
+name = "batch_{{{{operation.label}}}}_{{{{batch_size}}}}"
+operation = "{{{{operation.kind}}}}"
+batch_size = "{{{{batch_size}}}}"
+samples = "{{{{iterations}}}}"
+
+[batches.matrix]
+batch_size = [10, 100]
+operation = [{{ kind = "CREATE", label = "create" }}, {{ kind = "DELETE", label = "delete" }}]
+"#
+		);
+		let toml = parse_bench_toml("bench.toml", &text, 1000, "map")?;
+		let names: Vec<_> = toml.batches.iter().map(|b| b.name.as_str()).collect();
+		assert_eq!(
+			names,
+			["batch_create_10", "batch_delete_10", "batch_create_100", "batch_delete_100"]
+		);
+		assert_eq!(toml.batches[3].batch_size, 100);
+		assert_eq!(toml.batches[3].samples, Some(50));
+		Ok(())
+	}
+
+	#[test]
+	fn placeholders_resolve_samples_and_reject_unknown_names() -> Result<()> {
+		let text = format!(
+			"{VALUE}\n[[scans]]\nid = \"all\"\nname = \"count() of {{{{samples}}}}\"\nexpect = \"{{{{samples}}}}\"\n"
+		);
+		let mut doc: Table = toml::from_str(&text)?;
+		expand_templates(&mut doc, 500)?;
+		let scan = &doc["scans"][0];
+		assert_eq!(scan["name"].as_str(), Some("count() of 500"));
+		assert_eq!(scan["expect"].as_integer(), Some(500));
+		let text = format!("{VALUE}\n[[scans]]\nid = \"{{{{missing}}}}\"\n");
+		let err = parse_bench_toml("bench.toml", &text, 500, "map").unwrap_err();
+		assert!(format!("{err:#}").contains("Unknown template parameter `missing`"));
+		Ok(())
+	}
+
+	#[test]
+	fn run_options_become_flags() -> Result<()> {
+		let run: Table = toml::from_str(
+			"database = \"postgres\"\nsamples = 5000\nrandom = true\nsync = false\ngrowth_sizes = [10000, 20000]\n",
+		)?;
+		let mut flags = run_flags(run)?;
+		flags.sort();
+		assert_eq!(
+			flags,
+			["--database=postgres", "--growth-sizes=10000,20000", "--random", "--samples=5000"]
+		);
+		let run: Table = toml::from_str("config = \"other.toml\"")?;
+		assert!(run_flags(run).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn phase_samples_override_the_reads_and_updates() -> Result<()> {
+		let text = format!("{VALUE}\n[samples]\nread = 100000\n");
+		let bench = parse_bench_toml("bench.toml", &text, 500, "map")?;
+		assert_eq!(bench.samples.read, Some(100000));
+		assert_eq!(bench.samples.update, None);
+		let text = format!("{VALUE}\n[samples]\ndelete = 10\n");
+		assert!(parse_bench_toml("bench.toml", &text, 500, "map").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn yaml_definitions_parse_like_toml() -> Result<()> {
+		let text = "run:\n  samples: 100\nvalue:\n  text: string:50\nbatches:\n  - name: create\n    operation: CREATE\n    batch_size: 10\n";
+		let bench = parse_bench_toml("bench.yaml", text, 100, "map")?;
+		assert_eq!(bench.batches[0].batch_size, 10);
+		assert_eq!(bench.value["text"], "string:50");
+		Ok(())
+	}
+
+	#[test]
+	fn overrides_merge_for_the_matching_database() -> Result<()> {
+		let text = format!(
+			r#"{VALUE}
+[[scans]]
+id = "eq"
+name = "select(*) where(integer = integer)"
+condition = {{ sql = "integer = 21", mongodb = {{ integer = 21 }} }}
+overrides.mongodb.condition.mongodb = {{ integer = {{ "$eq" = 21 }} }}
+overrides.sqlite.skip = true
+"#
+		);
+		let parse = |database| -> Result<Table> {
+			let mut doc: Table = toml::from_str(&text)?;
+			apply_overrides(&mut doc, database)?;
+			Ok(doc)
+		};
+		let mongodb = parse("mongodb")?;
+		let condition = &mongodb["scans"][0]["condition"];
+		assert_eq!(condition["sql"].as_str(), Some("integer = 21"));
+		assert_eq!(condition["mongodb"]["integer"]["$eq"].as_integer(), Some(21));
+		assert!(mongodb["scans"][0].get("overrides").is_none());
+		assert_eq!(parse("sqlite")?["scans"][0]["skip"].as_bool(), Some(true));
+		assert!(parse("postgres")?["scans"][0].get("skip").is_none());
+		Ok(())
+	}
 }
@@ -4,17 +4,19 @@ use crate::Scan;
 use crate::benchmark::Benchmark;
 use crate::dialect::{
 	AnsiSqlDialect, ArangoDBDialect, DefaultDialect, MariaDBDialect, MongoDBDialect, MySqlDialect,
-	Neo4jDialect, SurrealDBDialect,
+	Neo4jDialect, OpenSearchDialect, SurrealDBDialect,
 };
-use crate::docker::{Container, DockerParams};
+use crate::docker::{Container, DockerParams, DockerTopology};
 use crate::dry::DryClientProvider;
 use crate::engine::BenchmarkEngine;
 use crate::keyprovider::KeyProvider;
 use crate::map::MapClientProvider;
 use crate::result::BenchmarkResult;
+use crate::timeseries::TimeSeries;
 use crate::valueprovider::ValueProvider;
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::ValueEnum;
+use std::collections::BTreeMap;
 
 #[derive(ValueEnum, Debug, Clone, Copy)]
 pub(crate) enum Database {
@@ -40,8 +42,12 @@ pub(crate) enum Database {
 	Mysql,
 	#[cfg(feature = "neo4j")]
 	Neo4j,
+	#[cfg(feature = "opensearch")]
+	Opensearch,
 	#[cfg(feature = "postgres")]
 	Postgres,
+	#[cfg(feature = "questdb")]
+	Questdb,
 	#[cfg(feature = "redb")]
 	Redb,
 	#[cfg(feature = "redis")]
@@ -70,6 +76,18 @@ pub(crate) enum Database {
 	/// `-e "ws://host1:8000;ws://host2:8000;ws://host3:8000"`
 	#[cfg(feature = "surrealdb")]
 	Surrealds,
+	/// SurrealDB over its HTTP endpoint rather than a WebSocket.
+	///
+	/// Runs the same workload as `surrealdb`, against the same Docker container or an
+	/// `http://` / `https://` endpoint, so the protocol overhead can be compared.
+	#[cfg(feature = "surrealdb")]
+	SurrealdbHttp,
+	#[cfg(feature = "tidb")]
+	Tidb,
+	#[cfg(feature = "timescaledb")]
+	Timescaledb,
+	#[cfg(feature = "yugabytedb")]
+	Yugabytedb,
 }
 
 impl Database {
@@ -77,13 +95,53 @@ impl Database {
 	pub(crate) fn wants_docker(&self, endpoint: &Option<String>) -> bool {
 		match self {
 			#[cfg(feature = "surrealdb")]
-			Database::Surrealdb => crate::surrealdb::wants_docker(endpoint.as_deref()),
+			Database::Surrealdb | Database::SurrealdbHttp => {
+				crate::surrealdb::wants_docker(endpoint.as_deref())
+			}
 			_ => endpoint.is_none(),
 		}
 	}
 
-	/// Start the Docker container if necessary
-	pub(crate) fn start_docker(&self, options: &Benchmark) -> Option<Container> {
+	/// The server flags the Docker container is started with, for datastores which expose them
+	pub(crate) fn server_options(&self, options: &Benchmark) -> BTreeMap<String, String> {
+		match self {
+			#[cfg(feature = "dragonfly")]
+			Self::Dragonfly => crate::dragonfly::server_options(options),
+			#[cfg(feature = "keydb")]
+			Self::Keydb => crate::keydb::server_options(options),
+			#[cfg(feature = "neo4j")]
+			Self::Neo4j => crate::neo4j::server_options(options),
+			#[allow(unreachable_patterns)]
+			_ => BTreeMap::new(),
+		}
+	}
+
+	/// The containers a multi-container `--topology` runs, for datastores which support it
+	#[allow(unused_variables)]
+	fn docker_topology(
+		&self,
+		params: DockerParams,
+		topology: &str,
+		options: &Benchmark,
+	) -> Result<DockerTopology> {
+		match self {
+			#[cfg(feature = "mongodb")]
+			Self::Mongodb => crate::mongodb::topology(params, topology, options),
+			#[cfg(feature = "postgres")]
+			Self::Postgres => crate::postgres::topology(params, topology),
+			#[cfg(feature = "redis")]
+			Self::Redis => crate::redis::topology(params, topology, options),
+			#[cfg(feature = "scylladb")]
+			Self::Scylladb => crate::scylladb::topology(params, topology, options),
+			#[cfg(feature = "surrealdb")]
+			Self::Surrealdb | Self::SurrealdbHttp => crate::surrealdb::topology(params, topology, options),
+			#[allow(unreachable_patterns)]
+			_ => bail!("{self:?} does not support multi-container topologies"),
+		}
+	}
+
+	/// Start the Docker container, and any supporting containers, if necessary
+	pub(crate) fn start_docker(&self, options: &Benchmark) -> Result<Option<Container>> {
 		// Get any pre-defined Docker configuration
 		let params: DockerParams = match self {
 			#[cfg(feature = "arangodb")]
@@ -100,23 +158,58 @@ impl Database {
 			Self::Mysql => crate::mysql::docker(options),
 			#[cfg(feature = "neo4j")]
 			Self::Neo4j => crate::neo4j::docker(options),
+			#[cfg(feature = "opensearch")]
+			Self::Opensearch => crate::opensearch::docker(options),
 			#[cfg(feature = "postgres")]
 			Self::Postgres => crate::postgres::docker(options),
+			#[cfg(feature = "questdb")]
+			Self::Questdb => crate::questdb::docker(options),
 			#[cfg(feature = "redis")]
 			Self::Redis => crate::redis::docker(options),
 			#[cfg(feature = "scylladb")]
 			Self::Scylladb => crate::scylladb::docker(options),
 			#[cfg(feature = "surrealdb")]
-			Self::Surrealdb => crate::surrealdb::docker(options),
+			Self::Surrealdb | Self::SurrealdbHttp => crate::surrealdb::docker(options),
+			#[cfg(feature = "tidb")]
+			Self::Tidb => crate::tidb::docker(options),
+			#[cfg(feature = "timescaledb")]
+			Self::Timescaledb => crate::timescaledb::docker(options),
+			#[cfg(feature = "yugabytedb")]
+			Self::Yugabytedb => crate::yugabytedb::docker(options),
 			#[allow(unreachable_patterns)]
-			_ => return None,
+			_ => match &options.topology {
+				Some(_) => bail!("{self:?} does not support multi-container topologies"),
+				None => return Ok(None),
+			},
+		};
+		// Check if a custom image has been specified, for every platform or only this one
+		let platform = Container::platform(options);
+		let image = match &options.image {
+			Some(image) => image.clone(),
+			None => self.platform_image(&platform).unwrap_or(params.image.to_string()),
+		};
+		// Arrange the containers of the chosen topology
+		let topology = match &options.topology {
+			Some(topology) => self.docker_topology(params, topology, options)?,
+			None => params.into(),
 		};
-		// Check if a custom image has been specified
-		let image = options.image.clone().unwrap_or(params.image.to_string());
-		// Start the specified container with arguments
-		let container = Container::start(image, &params.pre_args, &params.post_args, options);
+		// Fail before starting anything when an image has no build for the platform
+		Container::check_platform(&image, &platform)?;
+		for service in &topology.services {
+			Container::check_platform(service.params.image, &platform)?;
+		}
+		// Start the specified containers with arguments
+		let container = Container::start(image, &topology, options);
 		// Return the container reference
-		Some(container)
+		Ok(Some(container))
+	}
+
+	/// The image set for this database on the platform, such as `linux/arm64`, with an environment
+	/// variable named after both, such as `CRUD_BENCH_KEYDB_IMAGE_ARM64`.
+	fn platform_image(&self, platform: &str) -> Option<String> {
+		let name = self.to_possible_value()?.get_name().replace('-', "_").to_uppercase();
+		let arch = platform.split('/').nth(1).unwrap_or(platform).to_uppercase();
+		std::env::var(format!("CRUD_BENCH_{name}_IMAGE_{arch}")).ok()
 	}
 
 	#[allow(clippy::too_many_arguments)]
@@ -129,6 +222,7 @@ impl Database {
 		vp: ValueProvider,
 		scans: Vec<Scan>,
 		batches: Vec<BatchOperation>,
+		time_series: Option<TimeSeries>,
 		database: Option<String>,
 		system: Option<crate::system::SystemInfo>,
 		metadata: Option<crate::result::BenchmarkMetadata>,
@@ -142,6 +236,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database,
 						system,
 						metadata,
@@ -158,6 +253,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -178,6 +274,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -194,6 +291,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -210,6 +308,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -225,6 +324,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -240,6 +340,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -254,6 +355,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -270,6 +372,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -286,6 +389,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -302,6 +406,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -318,6 +423,28 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
+						database.clone(),
+						system.clone(),
+						metadata.clone(),
+					)
+					.await
+			}
+			#[cfg(feature = "opensearch")]
+			Database::Opensearch => {
+				benchmark
+					.run::<_, OpenSearchDialect, _>(
+						crate::opensearch::OpenSearchClientProvider::setup(
+							kt,
+							vp.columns(),
+							benchmark,
+						)
+						.await?,
+						kp,
+						vp,
+						scans,
+						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -334,6 +461,24 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
+						database.clone(),
+						system.clone(),
+						metadata.clone(),
+					)
+					.await
+			}
+			#[cfg(feature = "questdb")]
+			Database::Questdb => {
+				benchmark
+					.run::<_, AnsiSqlDialect, _>(
+						crate::questdb::QuestDBClientProvider::setup(kt, vp.columns(), benchmark)
+							.await?,
+						kp,
+						vp,
+						scans,
+						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -349,6 +494,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -365,6 +511,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -381,6 +528,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -397,6 +545,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -413,6 +562,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -429,6 +579,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -449,6 +600,28 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
+						database.clone(),
+						system.clone(),
+						metadata.clone(),
+					)
+					.await
+			}
+			#[cfg(feature = "surrealdb")]
+			Database::SurrealdbHttp => {
+				benchmark
+					.run::<_, SurrealDBDialect, _>(
+						crate::surrealdb::SurrealDBHttpClientProvider::setup(
+							kt,
+							vp.columns(),
+							benchmark,
+						)
+						.await?,
+						kp,
+						vp,
+						scans,
+						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -469,6 +642,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -489,6 +663,7 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -510,6 +685,65 @@ impl Database {
 						vp,
 						scans,
 						batches,
+						time_series,
+						database.clone(),
+						system.clone(),
+						metadata.clone(),
+					)
+					.await
+			}
+			#[cfg(feature = "tidb")]
+			Database::Tidb => {
+				benchmark
+					.run::<_, MySqlDialect, _>(
+						crate::tidb::TidbClientProvider::setup(kt, vp.columns(), benchmark).await?,
+						kp,
+						vp,
+						scans,
+						batches,
+						time_series,
+						database.clone(),
+						system.clone(),
+						metadata.clone(),
+					)
+					.await
+			}
+			#[cfg(feature = "timescaledb")]
+			Database::Timescaledb => {
+				benchmark
+					.run::<_, AnsiSqlDialect, _>(
+						crate::timescaledb::TimescaleDBClientProvider::setup(
+							kt,
+							vp.columns(),
+							benchmark,
+						)
+						.await?,
+						kp,
+						vp,
+						scans,
+						batches,
+						time_series,
+						database.clone(),
+						system.clone(),
+						metadata.clone(),
+					)
+					.await
+			}
+			#[cfg(feature = "yugabytedb")]
+			Database::Yugabytedb => {
+				benchmark
+					.run::<_, AnsiSqlDialect, _>(
+						crate::yugabytedb::YugabyteDBClientProvider::setup(
+							kt,
+							vp.columns(),
+							benchmark,
+						)
+						.await?,
+						kp,
+						vp,
+						scans,
+						batches,
+						time_series,
 						database.clone(),
 						system.clone(),
 						metadata.clone(),
@@ -543,10 +777,14 @@ impl Database {
 			Database::Mysql => "MySQL",
 			#[cfg(feature = "postgres")]
 			Database::Postgres => "PostgreSQL",
+			#[cfg(feature = "questdb")]
+			Database::Questdb => "QuestDB",
 			#[cfg(feature = "sqlite")]
 			Database::Sqlite => "SQLite",
 			#[cfg(feature = "neo4j")]
 			Database::Neo4j => "Neo4j",
+			#[cfg(feature = "opensearch")]
+			Database::Opensearch => "OpenSearch",
 			#[cfg(feature = "arangodb")]
 			Database::Arangodb => "ArangoDB",
 			#[cfg(feature = "scylladb")]
@@ -563,6 +801,14 @@ impl Database {
 			Database::Surrealmx => "SurrealMX",
 			#[cfg(feature = "surrealdb")]
 			Database::Surrealdb => "SurrealDB",
+			#[cfg(feature = "surrealdb")]
+			Database::SurrealdbHttp => "SurrealDB (HTTP)",
+			#[cfg(feature = "tidb")]
+			Database::Tidb => "TiDB",
+			#[cfg(feature = "timescaledb")]
+			Database::Timescaledb => "TimescaleDB",
+			#[cfg(feature = "yugabytedb")]
+			Database::Yugabytedb => "YugabyteDB",
 			#[allow(unreachable_patterns)]
 			_ => "Unknown",
 		}
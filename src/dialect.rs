@@ -1,9 +1,14 @@
 use crate::Scan;
-use crate::benchmark::NOT_SUPPORTED_ERROR;
+use crate::engine::ScanContext;
+use crate::error::BenchError;
 use crate::valueprovider::{ColumnType, Columns};
 use anyhow::{Result, bail};
 #[cfg(feature = "mongodb")]
 use mongodb::bson::{Document, doc, to_document};
+#[cfg(feature = "mongodb")]
+use mongodb::options::Hint;
+#[cfg(feature = "opensearch")]
+use serde_json::{Value, json};
 
 /// Per-database-family helpers for SQL identifier escaping and projection /
 /// scan-clause assembly. Datetime / UUID formatting used to live here, but the
@@ -72,7 +77,7 @@ impl AnsiSqlDialect {
 			if let Some(ref c) = c.sql {
 				return Ok(format!("WHERE {c}"));
 			} else {
-				bail!(NOT_SUPPORTED_ERROR);
+				bail!(BenchError::Unsupported);
 			}
 		}
 		Ok(String::new())
@@ -84,7 +89,7 @@ impl AnsiSqlDialect {
 			None => Ok(String::new()),
 			Some(o) => match &o.sql {
 				Some(s) if !s.is_empty() => Ok(format!("ORDER BY {s}")),
-				_ => bail!(NOT_SUPPORTED_ERROR),
+				_ => bail!(BenchError::Unsupported),
 			},
 		}
 	}
@@ -106,11 +111,47 @@ impl PostgresDialect {
 			if let Some(ref frag) = c.sql {
 				return Ok(format!("WHERE {frag}"));
 			}
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		Ok(String::new())
 	}
 
+	/// `WHERE` clause of a partial index, preferring `where.postgres`, then generic `where.sql`.
+	pub fn index_filter_clause(spec: &crate::Index) -> Result<String> {
+		if let Some(ref c) = spec.filter {
+			if let Some(ref frag) = c.postgres {
+				return Ok(format!("WHERE {frag}"));
+			}
+			if let Some(ref frag) = c.sql {
+				return Ok(format!("WHERE {frag}"));
+			}
+			bail!(BenchError::Unsupported);
+		}
+		Ok(String::new())
+	}
+
+	/// Leading `pg_hint_plan` comment for the hinted [S]can leg
+	pub fn hint_clause(scan: &Scan, ctx: ScanContext) -> Result<String> {
+		if ctx != ScanContext::WithHint {
+			return Ok(String::new());
+		}
+		match scan.hint.as_ref().and_then(|h| h.postgres.as_ref()) {
+			Some(h) if !h.is_empty() => Ok(format!("/*+ {h} */ ")),
+			_ => bail!(BenchError::Unsupported),
+		}
+	}
+
+	/// Selects a dotted field path as text, walking nested JSONB columns with `#>>`.
+	pub(crate) fn field_path(path: &str) -> String {
+		match path.split_once('.') {
+			Some((column, nested)) => {
+				let column = AnsiSqlDialect::escape_field(column.to_string());
+				format!("{column} #>> '{{{}}}'", nested.replace('.', ","))
+			}
+			None => format!("{}::text", AnsiSqlDialect::escape_field(path.to_string())),
+		}
+	}
+
 	/// Bench `tags.*` denotes a JSON array index; PostgreSQL has no Surreal-style path — index the JSONB column (same idea as SQLite).
 	pub(crate) fn btree_index_key_list(columns: &Columns, spec: &crate::Index) -> String {
 		spec.fields
@@ -127,6 +168,40 @@ impl PostgresDialect {
 			.collect::<Vec<_>>()
 			.join(", ")
 	}
+
+	/// The key list of a GIN, GiST, BRIN, or hash index, and the extension providing the B-tree
+	/// operator classes its scalar columns need. Only GIN indexes JSONB columns and `tags.*`
+	/// arrays, and hash indexes cover a single column.
+	pub(crate) fn method_index_key_list(
+		columns: &Columns,
+		spec: &crate::Index,
+		method: &str,
+	) -> Result<(String, Option<&'static str>)> {
+		if method == "hash" && spec.fields.len() != 1 {
+			bail!(BenchError::Unsupported);
+		}
+		let mut scalar = false;
+		let mut keys = Vec::with_capacity(spec.fields.len());
+		for field in &spec.fields {
+			let base = field.strip_suffix(".*").unwrap_or(field);
+			let json = columns
+				.0
+				.iter()
+				.any(|(n, t)| n == base && matches!(t, ColumnType::Array | ColumnType::Object));
+			match (json, method) {
+				(true, "gin") => {}
+				(true, _) => bail!(BenchError::Unsupported),
+				(false, _) => scalar = true,
+			}
+			keys.push(AnsiSqlDialect::escape_field(base.to_string()));
+		}
+		let extension = match (method, scalar) {
+			("gin", true) => Some("btree_gin"),
+			("gist", _) => Some("btree_gist"),
+			_ => None,
+		};
+		Ok((keys.join(", "), extension))
+	}
 }
 
 // --------------------------------------------------
@@ -145,11 +220,47 @@ impl SqliteDialect {
 			if let Some(ref frag) = c.sql {
 				return Ok(format!("WHERE {frag}"));
 			}
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		Ok(String::new())
 	}
 
+	/// `WHERE` clause of a partial index, preferring `where.sqlite`, then generic `where.sql`.
+	pub fn index_filter_clause(spec: &crate::Index) -> Result<String> {
+		if let Some(ref c) = spec.filter {
+			if let Some(ref frag) = c.sqlite {
+				return Ok(format!("WHERE {frag}"));
+			}
+			if let Some(ref frag) = c.sql {
+				return Ok(format!("WHERE {frag}"));
+			}
+			bail!(BenchError::Unsupported);
+		}
+		Ok(String::new())
+	}
+
+	/// `INDEXED BY` clause for the hinted [S]can leg
+	pub fn hint_clause(scan: &Scan, ctx: ScanContext) -> Result<String> {
+		if ctx != ScanContext::WithHint {
+			return Ok(String::new());
+		}
+		match scan.hint.as_ref().and_then(|h| h.sqlite.as_ref()) {
+			Some(h) if !h.is_empty() => Ok(h.clone()),
+			_ => bail!(BenchError::Unsupported),
+		}
+	}
+
+	/// Selects a dotted field path, walking nested JSON columns with `json_extract`.
+	pub(crate) fn field_path(path: &str) -> String {
+		match path.split_once('.') {
+			Some((column, nested)) => {
+				let column = AnsiSqlDialect::escape_field(column.to_string());
+				format!("json_extract({column}, '$.{nested}')")
+			}
+			None => AnsiSqlDialect::escape_field(path.to_string()),
+		}
+	}
+
 	/// Bench `tags.*` denotes a JSON array index; SQLite has no multi-valued btree — index the stored JSON column instead.
 	pub(crate) fn btree_index_key_list(columns: &Columns, spec: &crate::Index) -> String {
 		spec.fields
@@ -214,7 +325,7 @@ impl MySqlDialect {
 			if let Some(ref c) = c.mysql {
 				return Ok(format!("WHERE {c}"));
 			} else {
-				bail!(NOT_SUPPORTED_ERROR);
+				bail!(BenchError::Unsupported);
 			}
 		}
 		Ok(String::new())
@@ -226,11 +337,33 @@ impl MySqlDialect {
 			None => Ok(String::new()),
 			Some(o) => match &o.mysql {
 				Some(s) if !s.is_empty() => Ok(format!("ORDER BY {s}")),
-				_ => bail!(NOT_SUPPORTED_ERROR),
+				_ => bail!(BenchError::Unsupported),
 			},
 		}
 	}
 
+	/// Index hint following the table name for the hinted [S]can leg
+	pub fn hint_clause(scan: &Scan, ctx: ScanContext) -> Result<String> {
+		if ctx != ScanContext::WithHint {
+			return Ok(String::new());
+		}
+		match scan.hint.as_ref().and_then(|h| h.mysql.as_ref()) {
+			Some(h) if !h.is_empty() => Ok(h.clone()),
+			_ => bail!(BenchError::Unsupported),
+		}
+	}
+
+	/// Selects a dotted field path, walking nested JSON columns with `JSON_EXTRACT`.
+	pub(crate) fn field_path(path: &str) -> String {
+		match path.split_once('.') {
+			Some((column, nested)) => {
+				let column = Self::escape_field(column.to_string());
+				format!("JSON_EXTRACT({column}, '$.{nested}')")
+			}
+			None => Self::escape_field(path.to_string()),
+		}
+	}
+
 	/// InnoDB btree indexes require a prefix length on [`ColumnType::String`] (`TEXT`) columns.
 	///
 	/// Bench specs may use Surreal-style `tags.*` on JSON array columns; map those to a MySQL 8
@@ -278,56 +411,6 @@ impl Dialect for MariaDBDialect {
 }
 
 impl MariaDBDialect {
-	/// Escaped identifiers for each non-id column
-	pub(crate) fn escaped_columns(columns: &Columns) -> Vec<String> {
-		columns.0.iter().map(|(name, _)| Self::escape_field(name.clone())).collect()
-	}
-
-	/// Constructs the `(column list, placeholder list)` for a `INSERT` statement
-	pub(crate) fn create_clause(columns: &Columns) -> (String, String) {
-		let column_list = columns
-			.0
-			.iter()
-			.map(|(name, _)| Self::escape_field(name.clone()))
-			.collect::<Vec<String>>()
-			.join(", ");
-		let placeholders = (0..columns.0.len()).map(|_| "?").collect::<Vec<_>>().join(", ");
-		(column_list, placeholders)
-	}
-
-	/// Constructs the `SET` clause for a `UPDATE` statement.
-	pub(crate) fn update_clause(columns: &Columns) -> String {
-		columns
-			.0
-			.iter()
-			.map(|(name, _)| format!("{} = ?", Self::escape_field(name.clone())))
-			.collect::<Vec<_>>()
-			.join(", ")
-	}
-
-	/// Constructs the WHERE clause for [S]can tests
-	pub fn filter_clause(scan: &Scan) -> Result<String> {
-		if let Some(ref c) = scan.condition {
-			if let Some(ref c) = c.mysql {
-				return Ok(format!("WHERE {c}"));
-			} else {
-				bail!(NOT_SUPPORTED_ERROR);
-			}
-		}
-		Ok(String::new())
-	}
-
-	/// Constructs the ORDER BY clause for [S]can tests
-	pub fn order_by_clause(scan: &Scan) -> Result<String> {
-		match &scan.order_by {
-			None => Ok(String::new()),
-			Some(o) => match &o.mysql {
-				Some(s) if !s.is_empty() => Ok(format!("ORDER BY {s}")),
-				_ => bail!(NOT_SUPPORTED_ERROR),
-			},
-		}
-	}
-
 	/// B-tree index key list; unlike MySQL 8 `CAST(… AS … ARRAY)` multi-valued indexes, MariaDB uses a functional `CAST` to `CHAR` for `tags.*` JSON wildcard specs.
 	pub(crate) fn btree_index_key_list(columns: &Columns, spec: &crate::Index) -> String {
 		spec.fields
@@ -380,7 +463,7 @@ impl Neo4jDialect {
 					return Ok(format!("WHERE {c}"));
 				}
 			} else {
-				bail!(NOT_SUPPORTED_ERROR);
+				bail!(BenchError::Unsupported);
 			}
 		}
 		Ok(String::new())
@@ -392,10 +475,15 @@ impl Neo4jDialect {
 			None => Ok(String::new()),
 			Some(o) => match &o.neo4j {
 				Some(s) if !s.is_empty() => Ok(format!("ORDER BY {s}")),
-				_ => bail!(NOT_SUPPORTED_ERROR),
+				_ => bail!(BenchError::Unsupported),
 			},
 		}
 	}
+
+	/// Nested objects are flattened into `_`-joined node properties on insert.
+	pub(crate) fn field_path(path: &str) -> String {
+		path.replace('.', "_")
+	}
 }
 
 // --------------------------------------------------
@@ -413,7 +501,7 @@ impl SurrealDBDialect {
 			if let Some(ref c) = c.surrealdb {
 				return Ok(format!("WHERE {c}"));
 			} else {
-				bail!(NOT_SUPPORTED_ERROR);
+				bail!(BenchError::Unsupported);
 			}
 		}
 		Ok(String::new())
@@ -425,10 +513,21 @@ impl SurrealDBDialect {
 			None => Ok(String::new()),
 			Some(o) => match &o.surrealdb {
 				Some(s) if !s.is_empty() => Ok(format!("ORDER BY {s}")),
-				_ => bail!(NOT_SUPPORTED_ERROR),
+				_ => bail!(BenchError::Unsupported),
 			},
 		}
 	}
+
+	/// `WITH INDEX` clause for the hinted [S]can leg
+	pub fn hint_clause(scan: &Scan, ctx: ScanContext) -> Result<String> {
+		if ctx != ScanContext::WithHint {
+			return Ok(String::new());
+		}
+		match scan.hint.as_ref().and_then(|h| h.surrealdb.as_ref()) {
+			Some(h) if !h.is_empty() => Ok(h.clone()),
+			_ => bail!(BenchError::Unsupported),
+		}
+	}
 }
 
 // --------------------------------------------------
@@ -446,7 +545,7 @@ impl ArangoDBDialect {
 			if let Some(ref c) = c.arangodb {
 				return Ok(format!("FILTER {c}"));
 			} else {
-				bail!(NOT_SUPPORTED_ERROR);
+				bail!(BenchError::Unsupported);
 			}
 		}
 		Ok(String::new())
@@ -458,10 +557,21 @@ impl ArangoDBDialect {
 			None => Ok(String::new()),
 			Some(o) => match &o.arangodb {
 				Some(s) if !s.is_empty() => Ok(format!("SORT {s}")),
-				_ => bail!(NOT_SUPPORTED_ERROR),
+				_ => bail!(BenchError::Unsupported),
 			},
 		}
 	}
+
+	/// Constructs the `FOR` loop OPTIONS for the hinted [S]can leg
+	pub fn hint_clause(scan: &Scan, ctx: ScanContext) -> Result<String> {
+		if ctx != ScanContext::WithHint {
+			return Ok(String::new());
+		}
+		match scan.hint.as_ref().and_then(|h| h.arangodb.as_ref()) {
+			Some(h) if !h.is_empty() => Ok(format!("OPTIONS {h}")),
+			_ => bail!(BenchError::Unsupported),
+		}
+	}
 }
 
 // --------------------------------------------------
@@ -481,20 +591,67 @@ impl MongoDBDialect {
 			if let Some(ref c) = c.mongodb {
 				return Ok(to_document(c)?);
 			} else {
-				bail!(NOT_SUPPORTED_ERROR);
+				bail!(BenchError::Unsupported);
 			}
 		}
 		Ok(doc! {})
 	}
 
+	/// Constructs the `partialFilterExpression` of a partial index
+	pub fn partial_filter(spec: &crate::Index) -> Result<Option<Document>> {
+		match &spec.filter {
+			None => Ok(None),
+			Some(c) => match &c.mongodb {
+				Some(v) => Ok(Some(to_document(v)?)),
+				_ => bail!(BenchError::Unsupported),
+			},
+		}
+	}
+
 	/// Constructs the ORDER BY clause for [S]can tests
 	pub fn sort_document(scan: &Scan) -> Result<Option<Document>> {
 		match &scan.order_by {
 			None => Ok(None),
 			Some(o) => match &o.mongodb {
 				Some(v) => Ok(Some(to_document(v)?)),
-				_ => bail!(NOT_SUPPORTED_ERROR),
+				_ => bail!(BenchError::Unsupported),
 			},
 		}
 	}
+
+	/// Index name or key pattern for the hinted [S]can leg
+	pub fn hint(scan: &Scan, ctx: ScanContext) -> Result<Option<Hint>> {
+		if ctx != ScanContext::WithHint {
+			return Ok(None);
+		}
+		match scan.hint.as_ref().and_then(|h| h.mongodb.as_ref()) {
+			Some(serde_json::Value::String(name)) => Ok(Some(Hint::Name(name.clone()))),
+			Some(keys) => Ok(Some(Hint::Keys(to_document(keys)?))),
+			None => bail!(BenchError::Unsupported),
+		}
+	}
+}
+
+// --------------------------------------------------
+// OpenSearch
+// --------------------------------------------------
+
+pub(crate) struct OpenSearchDialect();
+
+#[cfg(feature = "opensearch")]
+impl Dialect for OpenSearchDialect {}
+
+#[cfg(feature = "opensearch")]
+impl OpenSearchDialect {
+	/// Constructs the query clause for [S]can tests
+	pub fn query(scan: &Scan) -> Result<Value> {
+		if let Some(ref c) = scan.condition {
+			if let Some(ref c) = c.opensearch {
+				return Ok(c.clone());
+			} else {
+				bail!(BenchError::Unsupported);
+			}
+		}
+		Ok(json!({ "match_all": {} }))
+	}
 }
@@ -1,26 +1,78 @@
 use crate::benchmark::Benchmark;
-use log::{debug, error, info};
+use crate::cgroup::Cgroup;
+use crate::error::BenchError;
+use anyhow::anyhow;
 use std::fmt;
 use std::process::{Command, exit};
 use std::time::Duration;
+use tracing::{debug, error, info};
 
 const RETRIES: i32 = 10;
 
 const TIMEOUT: Duration = Duration::from_secs(6);
 
+/// The name of the datastore container, which the clients connect to
+const CONTAINER: &str = "crud-bench";
+
+/// The image which applies the `--netem` rules, as it ships with `tc`
+const NETEM_IMAGE: &str = "nicolaka/netshoot";
+
 pub(crate) struct DockerParams {
 	pub(crate) image: &'static str,
 	pub(crate) pre_args: String,
 	pub(crate) post_args: String,
 }
 
+/// A supporting container of a [`DockerTopology`], like a service in a compose file
+pub(crate) struct DockerService {
+	/// The container is named `crud-bench-{name}`
+	pub(crate) name: &'static str,
+	/// The image and arguments the container is run with
+	pub(crate) params: DockerParams,
+	/// Whether the service starts before the datastore container, rather than after it
+	pub(crate) before: bool,
+}
+
+/// The datastore container together with the supporting containers it runs with,
+/// such as the other members of a replica set, a connection pooler, or a storage layer
+pub(crate) struct DockerTopology {
+	/// The datastore container, which the clients connect to
+	pub(crate) main: DockerParams,
+	/// The supporting containers, started in order around the datastore container
+	pub(crate) services: Vec<DockerService>,
+	/// Commands run in the datastore container once every container has started,
+	/// retried until they succeed, such as initiating a replica set
+	pub(crate) setup: Vec<String>,
+}
+
+impl From<DockerParams> for DockerTopology {
+	fn from(main: DockerParams) -> Self {
+		Self {
+			main,
+			services: vec![],
+			setup: vec![],
+		}
+	}
+}
+
 pub(crate) struct Container {
 	image: String,
+	/// The names of the supporting containers, in the order they started
+	services: Vec<String>,
+	/// Whether the `--netem` rules were applied, and must be removed
+	netem: bool,
 }
 
 impl Drop for Container {
 	fn drop(&mut self) {
+		if self.netem {
+			info!("Removing the network emulation");
+			let _ = Self::execute(Self::netem("del dev lo root"));
+		}
 		let _ = Self::stop();
+		for name in self.services.iter().rev() {
+			let _ = Self::stop_container(name);
+		}
 	}
 }
 
@@ -30,38 +82,170 @@ impl Container {
 		&self.image
 	}
 
-	/// Start the Docker container
-	pub(crate) fn start(image: String, pre: &str, post: &str, options: &Benchmark) -> Self {
+	/// Get the digest of the Docker image, or its id when it was built locally without one
+	pub(crate) fn digest(&self) -> Option<String> {
+		let format = "{{if .RepoDigests}}{{index .RepoDigests 0}}{{else}}{{.Id}}{{end}}";
+		let args = ["image", "inspect", "--format", format, &self.image];
+		Self::execute(Arguments::new(args)).ok().filter(|d| !d.is_empty())
+	}
+
+	/// Get the host pid of the main process of the datastore container
+	pub(crate) fn pid(&self) -> Option<u32> {
+		let args = ["container", "inspect", "--format", "{{.State.Pid}}", CONTAINER];
+		Self::execute(Arguments::new(args)).ok()?.parse().ok().filter(|pid| *pid > 0)
+	}
+
+	/// The platform the containers run for, as `os/architecture`, such as `linux/arm64`
+	pub(crate) fn platform(options: &Benchmark) -> String {
+		match &options.docker_platform {
+			Some(platform) => platform.clone(),
+			None => match std::env::consts::ARCH {
+				"x86_64" => "linux/amd64".to_string(),
+				"aarch64" => "linux/arm64".to_string(),
+				arch => format!("linux/{arch}"),
+			},
+		}
+	}
+
+	/// Fail when the registry lists the platforms of `image`, and the platform is not one of them.
+	/// Images built locally, or which the registry cannot be asked about, are left to `docker run`.
+	pub(crate) fn check_platform(image: &str, platform: &str) -> anyhow::Result<()> {
+		let args = ["manifest", "inspect", image];
+		let Ok(output) = Self::execute(Arguments::new(args)) else {
+			return Ok(());
+		};
+		let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&output) else {
+			return Ok(());
+		};
+		// A single-platform image has no list of manifests to check
+		let Some(manifests) = manifest["manifests"].as_array() else {
+			return Ok(());
+		};
+		let (os, arch) = platform.split_once('/').unwrap_or(("linux", platform));
+		let arch = arch.split('/').next().unwrap_or(arch);
+		let available: Vec<String> = manifests
+			.iter()
+			.filter_map(|m| {
+				let os = m["platform"]["os"].as_str()?;
+				let arch = m["platform"]["architecture"].as_str()?;
+				Some(format!("{os}/{arch}"))
+			})
+			.filter(|p| p != "unknown/unknown")
+			.collect();
+		if !available.is_empty() && !available.contains(&format!("{os}/{arch}")) {
+			anyhow::bail!(
+				"The image '{image}' has no {platform} build (only {}), set another image with `--image` or `CRUD_BENCH_<DATABASE>_IMAGE_<ARCH>`",
+				available.join(", ")
+			);
+		}
+		Ok(())
+	}
+
+	/// Start the datastore container from `image`, and any supporting containers around it
+	pub(crate) fn start(image: String, topology: &DockerTopology, options: &Benchmark) -> Self {
+		let mut container = Self {
+			image,
+			services: vec![],
+			netem: false,
+		};
+		let start_services = |before: bool, container: &mut Self| {
+			for service in topology.services.iter().filter(|s| s.before == before) {
+				let name = format!("{CONTAINER}-{}", service.name);
+				let DockerParams {
+					image,
+					pre_args,
+					post_args,
+				} = &service.params;
+				Self::run(&name, image, pre_args, post_args, false, options);
+				container.services.push(name);
+			}
+		};
+		start_services(true, &mut container);
+		let main = &topology.main;
+		Self::run(CONTAINER, &container.image, &main.pre_args, &main.post_args, true, options);
+		start_services(false, &mut container);
+		// Run the setup commands once the containers accept them
+		for command in &topology.setup {
+			Self::retry(|| {
+				let mut args = Arguments::new(["exec", CONTAINER]);
+				args.append(command);
+				args
+			});
+		}
+		// Emulate the network on the loopback interface, which the host network shares
+		if let Some(rules) = &options.netem {
+			info!("Emulating the network with '{rules}'");
+			Self::retry(|| Self::netem(&format!("replace dev lo root netem {rules}")));
+			container.netem = true;
+		}
+		container
+	}
+
+	/// The arguments running `tc qdisc` with the given arguments on the host network
+	fn netem(qdisc: &str) -> Arguments {
+		let mut args = Arguments::new(["run", "--rm", "--net", "host", "--cap-add", "NET_ADMIN"]);
+		args.add([NETEM_IMAGE, "tc", "qdisc"]);
+		args.append(qdisc);
+		args
+	}
+
+	/// Run a container, with the custom `DOCKER_PRE_ARGS` and `DOCKER_POST_ARGS` when `custom`
+	fn run(name: &str, image: &str, pre: &str, post: &str, custom: bool, options: &Benchmark) {
 		// Output debug information to the logs
-		info!("Starting Docker image '{image}'");
-		// Attempt to start Docker 10 times
-		for i in 1..=RETRIES {
+		info!("Starting Docker image '{image}' as '{name}'");
+		Self::retry(|| {
 			// Configure the Docker command arguments
 			let mut args = Arguments::new(["run"]);
 			// Configure the default pre arguments
 			args.append(pre);
 			// Configure any custom pre arguments
-			if let Ok(v) = std::env::var("DOCKER_PRE_ARGS") {
+			if custom && let Ok(v) = std::env::var("DOCKER_PRE_ARGS") {
 				args.append(&v);
 			}
 			// Run in privileged mode if specified
 			if options.privileged {
 				args.add(["--privileged"]);
 			}
+			// Limit the container resources if specified
+			if let Some(cpus) = options.container_cpus {
+				args.add(["--cpus".to_string(), cpus.to_string()]);
+			}
+			// Pin the container to a set of cores if specified
+			if let Some(cores) = &options.container_cores {
+				args.add(["--cpuset-cpus".to_string(), cores.clone()]);
+			}
+			// Limit the container memory, without letting it swap, if specified
+			if let Some(memory) = options.container_memory {
+				args.add(["--memory".to_string(), memory.to_string()]);
+				args.add(["--memory-swap".to_string(), memory.to_string()]);
+			}
 			// Configure the Docker container options
 			args.add(["--rm"]);
 			args.add(["--quiet"]);
 			args.add(["--pull", "always"]);
-			args.add(["--name", "crud-bench"]);
+			// Run the image for another platform if specified
+			if let Some(platform) = &options.docker_platform {
+				args.add(["--platform", platform]);
+			}
+			args.add(["--name", name]);
 			args.add(["--net", "host"]);
-			args.add(["-d", &image]);
+			args.add(["-d", image]);
 			// Configure the default post arguments
 			args.append(post);
 			// Configure any custom post arguments
-			if let Ok(v) = std::env::var("DOCKER_POST_ARGS") {
+			if custom && let Ok(v) = std::env::var("DOCKER_POST_ARGS") {
 				args.append(&v);
 			}
-			// Execute the Docker run command
+			args
+		});
+	}
+
+	/// Execute a Docker command, retrying it 10 times before exiting
+	fn retry(command: impl Fn() -> Arguments) {
+		// Attempt to run the command 10 times
+		for i in 1..=RETRIES {
+			let args = command();
+			// Execute the Docker command
 			match Self::execute(args.clone()) {
 				// The command executed successfully
 				Ok(_) => break,
@@ -82,19 +266,78 @@ impl Container {
 				},
 			}
 		}
-		// Return the container name
-		Self {
-			image,
-		}
 	}
 
 	/// Stop the Docker container
 	pub(crate) fn stop() -> Result<String, String> {
-		info!("Stopping Docker container 'crud-bench'");
-		let args = ["container", "stop", "--time", "300", "crud-bench"];
+		Self::stop_container(CONTAINER)
+	}
+
+	/// Stop a Docker container by name
+	fn stop_container(name: &str) -> Result<String, String> {
+		info!("Stopping Docker container '{name}'");
+		let args = ["container", "stop", "--time", "300", name];
 		Self::execute(Arguments::new(args))
 	}
 
+	/// Get the cgroups of the datastore container and its supporting containers, which can be read
+	pub(crate) fn cgroups(&self) -> Vec<Cgroup> {
+		std::iter::once(CONTAINER)
+			.chain(self.services.iter().map(String::as_str))
+			.filter_map(|name| {
+				let args = ["container", "inspect", "--format", "{{.Id}}", name];
+				Cgroup::find(&Self::execute(Arguments::new(args)).ok()?)
+			})
+			.collect()
+	}
+
+	/// Get the CPU (cores) and memory (bytes) limits of the container, if it is running
+	pub(crate) fn limits(&self) -> Option<(f64, Option<u64>)> {
+		let format = "{{.HostConfig.NanoCpus}} {{.HostConfig.Memory}}";
+		let args = ["container", "inspect", "--format", format, "crud-bench"];
+		let output = Self::execute(Arguments::new(args)).ok()?;
+		let (cpus, memory) = output.split_once(' ')?;
+		// Docker reports zero for limits which were not set
+		let cores = cpus.parse::<u64>().ok()? as f64 / 1_000_000_000.0;
+		let memory = memory.parse::<u64>().ok().filter(|m| *m > 0);
+		Some((cores, memory))
+	}
+
+	/// Get the bytes the datastore container occupies on disk, in its writable layer and volumes
+	pub(crate) fn disk_size() -> Option<u64> {
+		let format = "{{.SizeRw}}{{range .Mounts}} {{.Destination}}{{end}}";
+		let args = ["container", "inspect", "--size", "--format", format, CONTAINER];
+		let output = Self::execute(Arguments::new(args)).ok()?;
+		let mut fields = output.split(' ');
+		let layer = fields.next()?.parse::<u64>().ok()?;
+		let mounts: Vec<&str> = fields.collect();
+		if mounts.is_empty() {
+			return Some(layer);
+		}
+		// Measure the volumes inside the container, as their host paths may not be readable
+		let mut args = Arguments::new(["exec", CONTAINER, "du", "-sk"]);
+		args.add(mounts);
+		let output = Self::execute(args).ok()?;
+		let kib: u64 = output
+			.lines()
+			.filter_map(|line| line.split_whitespace().next()?.parse::<u64>().ok())
+			.sum();
+		Some(layer + kib * 1024)
+	}
+
+	/// Run a command in the datastore container, such as a native backup tool, failing as
+	/// unsupported when crud-bench did not start the container, or it has no such tool
+	pub(crate) fn exec(command: &str) -> anyhow::Result<String> {
+		let mut args = Arguments::new(["exec", CONTAINER]);
+		args.append(command);
+		Self::execute(args).map_err(|e| {
+			match e.contains("No such container") || e.contains("executable file not found") {
+				true => BenchError::Unsupported.into(),
+				false => anyhow!("Failed to run `{command}` in the container: {e}"),
+			}
+		})
+	}
+
 	/// Output the container logs
 	pub(crate) fn logs() -> Result<String, String> {
 		info!("Logging Docker container 'crud-bench'");
@@ -102,6 +345,16 @@ impl Container {
 		Self::execute(Arguments::new(args))
 	}
 
+	/// The last lines the datastore container logged, on both its output and error streams
+	pub(crate) fn tail_logs(lines: usize) -> Option<String> {
+		let tail = lines.to_string();
+		let args = ["container", "logs", "--tail", &tail, CONTAINER];
+		let output = Command::new("docker").args(args).output().ok()?;
+		let mut logs = String::from_utf8_lossy(&output.stdout).into_owned();
+		logs.push_str(&String::from_utf8_lossy(&output.stderr));
+		Some(logs.trim().to_string()).filter(|l| !l.is_empty())
+	}
+
 	fn execute(args: Arguments) -> Result<String, String> {
 		// Output debug information to the logs
 		println!("Running command: `docker {args}`");
@@ -151,8 +404,20 @@ impl Arguments {
 		}
 	}
 
+	/// Adds space-separated arguments, keeping single-quoted text (which may be empty) as one.
 	fn append(&mut self, args: &str) {
-		let split: Vec<&str> = args.split(' ').filter(|a| !a.is_empty()).collect();
-		self.add(split);
+		let mut arg: Option<String> = None;
+		let mut quoted = false;
+		for c in args.chars() {
+			match c {
+				'\'' => {
+					quoted = !quoted;
+					arg.get_or_insert_default();
+				}
+				' ' if !quoted => self.0.extend(arg.take()),
+				c => arg.get_or_insert_default().push(c),
+			}
+		}
+		self.0.extend(arg);
 	}
 }
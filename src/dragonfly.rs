@@ -1,44 +1,72 @@
 #![cfg(feature = "dragonfly")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::docker::DockerParams;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::util::resp::{Multiplexed, RespConnection};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, anyhow, bail};
-use futures::StreamExt;
-use redis::aio::MultiplexedConnection;
-use redis::{AsyncCommands, Client, ScanOptions};
+use crate::{Benchmark, KeyType, Scan};
+use anyhow::Result;
+use redis::AsyncCommands;
+use std::collections::BTreeMap;
 use std::hint::black_box;
 use tokio::sync::Mutex;
 
 pub const DEFAULT: &str = "redis://:root@127.0.0.1:6379/";
 
-pub(crate) fn docker(_: &Benchmark) -> DockerParams {
+/// Server flags set from the environment, so the multi-threading and snapshot behaviour can be
+/// examined under controlled configurations rather than the image defaults.
+const SERVER_OPTIONS: [(&str, &str); 3] = [
+	// Number of shard threads, one per core by default
+	("proactor_threads", "CRUD_BENCH_DRAGONFLY_THREADS"),
+	// Cron schedule of background snapshots, none by default
+	("snapshot_cron", "CRUD_BENCH_DRAGONFLY_SNAPSHOT_CRON"),
+	// `emulated` to serve the cluster protocol from a single node
+	("cluster_mode", "CRUD_BENCH_DRAGONFLY_CLUSTER_MODE"),
+];
+
+/// The server flags the container is started with, recorded in the benchmark metadata.
+pub(crate) fn server_options(_: &Benchmark) -> BTreeMap<String, String> {
+	SERVER_OPTIONS
+		.iter()
+		.filter_map(|(flag, var)| Some((flag.to_string(), std::env::var(var).ok()?)))
+		.collect()
+}
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	let flags = server_options(options)
+		.iter()
+		.map(|(flag, value)| format!(" --{flag}='{value}'"))
+		.collect::<String>();
 	DockerParams {
 		image: "docker.dragonflydb.io/dragonflydb/dragonfly",
 		pre_args: "-p 127.0.0.1:6379:6379 --ulimit memlock=-1".to_string(),
-		post_args: "--requirepass root".to_string(),
+		post_args: format!("--requirepass root{flags}"),
 	}
 }
 
 pub(crate) struct DragonflyClientProvider {
 	url: String,
+	pipelined: bool,
 }
 
 impl BenchmarkEngine<DragonflyClient> for DragonflyClientProvider {
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
+		// TLS is chosen by the `rediss://` scheme of the endpoint
+		options.security.without_tls_options("Dragonfly")?;
 		Ok(Self {
-			url: options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned(),
+			url: options
+				.security
+				.url_with_credentials(options.endpoint.as_deref().unwrap_or(DEFAULT)),
+			pipelined: options.pipelined,
 		})
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<DragonflyClient> {
-		let client = Client::open(self.url.as_str())?;
-		let conn_record = Mutex::new(client.get_multiplexed_async_connection().await?);
-		let conn_iter = Mutex::new(client.get_multiplexed_async_connection().await?);
+		let conn_record = RespConnection::new(Multiplexed::node(&self.url).await?, self.pipelined);
+		let conn_iter = Mutex::new(Multiplexed::node(&self.url).await?);
 		Ok(DragonflyClient {
 			conn_record,
 			conn_iter,
@@ -47,14 +75,38 @@ impl BenchmarkEngine<DragonflyClient> for DragonflyClientProvider {
 }
 
 pub(crate) struct DragonflyClient {
-	conn_iter: Mutex<MultiplexedConnection>,
-	conn_record: Mutex<MultiplexedConnection>,
+	conn_iter: Mutex<Multiplexed>,
+	conn_record: RespConnection,
 }
 
 impl BenchmarkClient for DragonflyClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		self.conn_record.server_version().await
+	}
+
+	fn classify_error(&self, err: &anyhow::Error) -> Option<BenchError> {
+		use redis::ErrorKind;
+		let err = err.chain().find_map(|e| e.downcast_ref::<redis::RedisError>())?;
+		if err.is_timeout() {
+			return Some(BenchError::Timeout);
+		}
+		if err.is_io_error() || err.is_connection_dropped() {
+			return Some(BenchError::Transient);
+		}
+		match err.kind() {
+			// A transaction aborted by a watched key
+			ErrorKind::ExecAbortError => Some(BenchError::Conflict),
+			ErrorKind::TryAgain
+			| ErrorKind::ClusterDown
+			| ErrorKind::MasterDown
+			| ErrorKind::BusyLoadingError => Some(BenchError::Transient),
+			_ => None,
+		}
+	}
+
 	#[allow(dependency_on_unit_never_type_fallback)]
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		let val = val.encode()?;
@@ -112,81 +164,33 @@ impl BenchmarkClient for DragonflyClient {
 	}
 
 	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+		self.conn_record.scan(&self.conn_iter, scan).await
 	}
 
 	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+		self.conn_record.scan(&self.conn_iter, scan).await
 	}
 
 	async fn batch_create_u32(
 		&self,
 		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
 	) -> Result<()> {
-		// Build the SET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for (k, v) in key_vals {
-			pipe.cmd("SET").arg(k).arg(v.encode()?).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_set(key_vals).await
 	}
 
 	async fn batch_create_string(
 		&self,
 		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
 	) -> Result<()> {
-		// Build the SET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for (k, v) in key_vals {
-			pipe.cmd("SET").arg(k).arg(v.encode()?).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_set(key_vals).await
 	}
 
 	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
-		// Build the GET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		let mut count = 0usize;
-		for k in keys {
-			pipe.cmd("GET").arg(k);
-			count += 1;
-		}
-		// Execute the pipeline and inspect the responses
-		let vals: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
-		assert_eq!(vals.len(), count);
-		for v in vals {
-			let v = v.ok_or_else(|| anyhow!("missing key"))?;
-			assert!(!v.is_empty());
-			black_box(v);
-		}
-		Ok(())
+		self.conn_record.batch_get(keys).await
 	}
 
 	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
-		// Build the GET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		let mut count = 0usize;
-		for k in keys {
-			pipe.cmd("GET").arg(k);
-			count += 1;
-		}
-		// Execute the pipeline and inspect the responses
-		let vals: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
-		assert_eq!(vals.len(), count);
-		for v in vals {
-			let v = v.ok_or_else(|| anyhow!("missing key"))?;
-			assert!(!v.is_empty());
-			black_box(v);
-		}
-		Ok(())
+		self.conn_record.batch_get(keys).await
 	}
 
 	async fn batch_update_u32(
@@ -206,86 +210,10 @@ impl BenchmarkClient for DragonflyClient {
 	}
 
 	async fn batch_delete_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
-		// Build the DEL pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for k in keys {
-			pipe.cmd("DEL").arg(k).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_del(keys).await
 	}
 
 	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
-		// Build the DEL pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for k in keys {
-			pipe.cmd("DEL").arg(k).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
-	}
-}
-
-impl DragonflyClient {
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Conditional scans are not supported
-		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
-		}
-		// Extract parameters
-		let s = scan.start.unwrap_or(0);
-		let l = scan.limit.unwrap_or(usize::MAX);
-		let p = scan.projection()?;
-		// Get the two connection types
-		let mut conn_iter = self.conn_iter.lock().await;
-		let mut conn_record = self.conn_record.lock().await;
-		// Configure the scan options for improve iteration
-		let opts = ScanOptions::default().with_count(5000);
-		// Create an iterator starting at the beginning
-		let mut iter = conn_iter.scan_options::<String>(opts).await?.skip(s);
-		// Perform the relevant projection scan type
-		match p {
-			Projection::Id => {
-				// We use a for loop to iterate over the results, while
-				// calling black_box internally. This is necessary as
-				// an iterator with `filter_map` or `map` is optimised
-				// out by the compiler when calling `count` at the end.
-				let mut count = 0;
-				for _ in 0..l {
-					if let Some(k) = iter.next().await {
-						black_box(k);
-						count += 1;
-					} else {
-						break;
-					}
-				}
-				Ok(count)
-			}
-			Projection::Full => {
-				// We use a for loop to iterate over the results, while
-				// calling black_box internally. This is necessary as
-				// an iterator with `filter_map` or `map` is optimised
-				// out by the compiler when calling `count` at the end.
-				let mut count = 0;
-				while let Some(k) = iter.next().await {
-					let v: Vec<u8> = conn_record.get(k).await?;
-					black_box(v);
-					count += 1;
-					if count >= l {
-						break;
-					}
-				}
-				Ok(count)
-			}
-			Projection::Count => match scan.limit {
-				// Full count queries are too slow
-				None => bail!(NOT_SUPPORTED_ERROR),
-				Some(l) => Ok(iter.take(l).count().await),
-			},
-		}
+		self.conn_record.batch_del(keys).await
 	}
 }
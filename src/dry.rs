@@ -4,15 +4,10 @@ use crate::valueprovider::Columns;
 use crate::{Benchmark, KeyType, Scan};
 use anyhow::Result;
 use std::hint::black_box;
-use std::time::Duration;
 
 pub(crate) struct DryClientProvider {}
 
 impl BenchmarkEngine<DryClient> for DryClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, _options: &Benchmark) -> Result<Self> {
 		Ok(Self {})
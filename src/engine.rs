@@ -1,13 +1,20 @@
 use crate::Benchmark;
-use crate::benchmark::NOT_SUPPORTED_ERROR;
-use crate::keyprovider::{IntegerKeyProvider, KeyProvider, StringKeyProvider};
+use crate::error::BenchError;
+use crate::keyprovider::{GeneratedKey, IntegerKeyProvider, KeyProvider, StringKeyProvider};
+use crate::timeseries::{TimeSeriesRow, TimeWindow};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
 use crate::{BatchOperation, Index, KeyType, Scan};
 use anyhow::{Result, bail};
+use futures::Stream;
 use std::future::Future;
+use std::pin::Pin;
 use std::time::Duration;
 
+/// The events a live query is notified of, as the time each event was
+/// written, in microseconds since the Unix epoch
+pub(crate) type LiveStream = Pin<Box<dyn Stream<Item = Result<u64>> + Send>>;
+
 /// Indicates whether a scan is running with or without an index
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScanContext {
@@ -15,6 +22,27 @@ pub enum ScanContext {
 	WithoutIndex,
 	/// Scan is running with an index
 	WithIndex,
+	/// Scan is running with an index forced through the scan's `hint`
+	WithHint,
+}
+
+/// Where one page of a walk over the whole table starts, in key order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PageStart<K> {
+	/// Skip this many records before the page (`OFFSET` pagination)
+	Offset(usize),
+	/// Start after this key (keyset pagination), or at the first record when `None`
+	After(Option<K>),
+}
+
+impl<K> PageStart<K> {
+	/// Converts the key of a keyset page start into the native key type of a datastore
+	pub(crate) fn map<T>(self, f: impl FnOnce(K) -> T) -> PageStart<T> {
+		match self {
+			Self::Offset(s) => PageStart::Offset(s),
+			Self::After(k) => PageStart::After(k.map(f)),
+		}
+	}
 }
 
 /// A trait for a database benchmark implementation
@@ -27,9 +55,25 @@ where
 	async fn setup(kt: KeyType, columns: Columns, endpoint: &Benchmark) -> Result<Self>;
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<C>;
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		Some(Duration::from_secs(5))
+	/// Creates a client connected as the restricted role
+	/// set up by [`BenchmarkClient::setup_restricted_reads`]
+	async fn create_restricted_client(&self) -> Result<C> {
+		bail!(BenchError::Unsupported)
+	}
+	/// Opens a live query, on a connection of its own, notified of every
+	/// event written by [`BenchmarkClient::append_live_event`]
+	async fn subscribe(&self) -> Result<LiveStream> {
+		bail!(BenchError::Unsupported)
+	}
+	/// Creates a client reading from a replica of the datastore, which
+	/// is written to by [`BenchmarkClient::append_replicated`]
+	async fn create_replica_client(&self) -> Result<C> {
+		bail!(BenchError::Unsupported)
+	}
+	/// Checks the datastore accepts requests, polled with a short backoff
+	/// until it succeeds before the clients connect
+	async fn ready(&self) -> Result<()> {
+		self.create_client().await.map(drop)
 	}
 }
 
@@ -57,6 +101,107 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		Ok(())
 	}
 
+	/// Back up the store with its native tool, returning
+	/// the size of the backup in bytes, when it is known
+	async fn backup(&self) -> Result<Option<u64>> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Restore the backup made by [`BenchmarkClient::backup`]
+	/// into a fresh database, beside the one benchmarked
+	async fn restore(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Remove the backup, and the database it was restored into
+	async fn remove_backup(&self) -> Result<()> {
+		Ok(())
+	}
+
+	/// The bytes the store occupies on disk, for reporting the
+	/// space a compaction reclaims and the disk footprint
+	fn disk_size(&self) -> Option<u64> {
+		None
+	}
+
+	/// The version of the datastore server, or the crate
+	/// version of an embedded engine, recorded in the results
+	async fn version(&self) -> Result<String> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Create a restricted role which can only read
+	/// the records through row-level permission checks
+	async fn setup_restricted_reads(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Copy the records, with their primary key, into a
+	/// snapshot table before the index scan phases
+	async fn snapshot_table(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Replace the records table, and any index churn on
+	/// it, with the snapshot taken before the scan phases
+	async fn restore_table(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Create the table holding the records created
+	/// with keys generated by the datastore
+	async fn setup_generated_keys(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Create the table holding the timestamped rows
+	/// appended by the time-series workload
+	async fn setup_time_series(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Create the table holding the events written
+	/// while the live queries are open
+	async fn setup_live_queries(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Create the table holding the writes whose
+	/// replication to a replica is measured
+	async fn setup_replication_lag(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Read the writes from the given id onwards, each with the time
+	/// it was written, in microseconds since the Unix epoch
+	async fn read_replicated(&self, _from: u32) -> Result<Vec<(u32, u64)>> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Create the table holding the edges which
+	/// link the records to each other
+	async fn setup_relations(&self) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Wait until the given number of appended time-series
+	/// rows are visible to queries, for eventually visible ingestion
+	async fn time_series_ready(&self, _rows: u32) -> Result<()> {
+		Ok(())
+	}
+
+	/// Flush and sync all writes acknowledged so far to durable
+	/// storage, as an explicit barrier between groups of writes
+	fn sync(&self) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
+	/// Map a driver error returned by this client onto
+	/// the shared error categories, when it is recognised
+	fn classify_error(&self, _err: &anyhow::Error) -> Option<BenchError> {
+		None
+	}
+
 	/// Create a single entry with the current client
 	fn create(
 		&self,
@@ -70,6 +215,8 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 				KeyProvider::UnorderedInteger(p) => self.create_u32(p.key(n), val).await,
 				KeyProvider::OrderedString(p) => self.create_string(p.key(n), val).await,
 				KeyProvider::UnorderedString(p) => self.create_string(p.key(n), val).await,
+				KeyProvider::OrderedUuid(p) => self.create_string(p.key(n), val).await,
+				KeyProvider::UnorderedUuid(p) => self.create_string(p.key(n), val).await,
 			}
 		}
 	}
@@ -86,6 +233,71 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 				KeyProvider::UnorderedInteger(p) => self.read_u32(p.key(n)).await,
 				KeyProvider::OrderedString(p) => self.read_string(p.key(n)).await,
 				KeyProvider::UnorderedString(p) => self.read_string(p.key(n)).await,
+				KeyProvider::OrderedUuid(p) => self.read_string(p.key(n)).await,
+				KeyProvider::UnorderedUuid(p) => self.read_string(p.key(n)).await,
+			}
+		}
+	}
+
+	/// Read a single entry, returning the execution time reported by the engine
+	async fn read_server_time(&self, n: u32, kp: &mut KeyProvider) -> Result<Duration> {
+		match kp {
+			KeyProvider::OrderedInteger(p) => self.read_server_time_u32(p.key(n)).await,
+			KeyProvider::UnorderedInteger(p) => self.read_server_time_u32(p.key(n)).await,
+			KeyProvider::OrderedString(p) => self.read_server_time_string(p.key(n)).await,
+			KeyProvider::UnorderedString(p) => self.read_server_time_string(p.key(n)).await,
+			KeyProvider::OrderedUuid(p) => self.read_server_time_string(p.key(n)).await,
+			KeyProvider::UnorderedUuid(p) => self.read_server_time_string(p.key(n)).await,
+		}
+	}
+
+	/// Link a single entry to each of the `targets` entries
+	fn relate(
+		&self,
+		n: u32,
+		targets: &[u32],
+		kp: &mut KeyProvider,
+	) -> impl Future<Output = Result<()>> + Send {
+		async move {
+			match kp {
+				KeyProvider::OrderedInteger(p) => {
+					let targets = targets.iter().map(|&t| p.key(t)).collect();
+					self.relate_u32(p.key(n), targets).await
+				}
+				KeyProvider::UnorderedInteger(p) => {
+					let targets = targets.iter().map(|&t| p.key(t)).collect();
+					self.relate_u32(p.key(n), targets).await
+				}
+				KeyProvider::OrderedString(p) => {
+					let targets = targets.iter().map(|&t| p.key(t)).collect();
+					self.relate_string(p.key(n), targets).await
+				}
+				KeyProvider::UnorderedString(p) => {
+					let targets = targets.iter().map(|&t| p.key(t)).collect();
+					self.relate_string(p.key(n), targets).await
+				}
+				KeyProvider::OrderedUuid(p) => {
+					let targets = targets.iter().map(|&t| p.key(t)).collect();
+					self.relate_string(p.key(n), targets).await
+				}
+				KeyProvider::UnorderedUuid(p) => {
+					let targets = targets.iter().map(|&t| p.key(t)).collect();
+					self.relate_string(p.key(n), targets).await
+				}
+			}
+		}
+	}
+
+	/// Read the entries linked from a single entry, returning the number found
+	fn traverse(&self, n: u32, kp: &mut KeyProvider) -> impl Future<Output = Result<usize>> + Send {
+		async move {
+			match kp {
+				KeyProvider::OrderedInteger(p) => self.traverse_u32(p.key(n)).await,
+				KeyProvider::UnorderedInteger(p) => self.traverse_u32(p.key(n)).await,
+				KeyProvider::OrderedString(p) => self.traverse_string(p.key(n)).await,
+				KeyProvider::UnorderedString(p) => self.traverse_string(p.key(n)).await,
+				KeyProvider::OrderedUuid(p) => self.traverse_string(p.key(n)).await,
+				KeyProvider::UnorderedUuid(p) => self.traverse_string(p.key(n)).await,
 			}
 		}
 	}
@@ -103,6 +315,30 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 				KeyProvider::UnorderedInteger(p) => self.update_u32(p.key(n), val).await,
 				KeyProvider::OrderedString(p) => self.update_string(p.key(n), val).await,
 				KeyProvider::UnorderedString(p) => self.update_string(p.key(n), val).await,
+				KeyProvider::OrderedUuid(p) => self.update_string(p.key(n), val).await,
+				KeyProvider::UnorderedUuid(p) => self.update_string(p.key(n), val).await,
+			}
+		}
+	}
+
+	/// Update a single field, at a dotted path, of an entry with the current client
+	fn update_path(
+		&self,
+		n: u32,
+		path: &str,
+		val: BenchValue,
+		kp: &mut KeyProvider,
+	) -> impl Future<Output = Result<()>> + Send {
+		async move {
+			match kp {
+				KeyProvider::OrderedInteger(p) => self.update_path_u32(p.key(n), path, val).await,
+				KeyProvider::UnorderedInteger(p) => self.update_path_u32(p.key(n), path, val).await,
+				KeyProvider::OrderedString(p) => self.update_path_string(p.key(n), path, val).await,
+				KeyProvider::UnorderedString(p) => {
+					self.update_path_string(p.key(n), path, val).await
+				}
+				KeyProvider::OrderedUuid(p) => self.update_path_string(p.key(n), path, val).await,
+				KeyProvider::UnorderedUuid(p) => self.update_path_string(p.key(n), path, val).await,
 			}
 		}
 	}
@@ -115,11 +351,43 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 				KeyProvider::UnorderedInteger(p) => self.delete_u32(p.key(n)).await,
 				KeyProvider::OrderedString(p) => self.delete_string(p.key(n)).await,
 				KeyProvider::UnorderedString(p) => self.delete_string(p.key(n)).await,
+				KeyProvider::OrderedUuid(p) => self.delete_string(p.key(n)).await,
+				KeyProvider::UnorderedUuid(p) => self.delete_string(p.key(n)).await,
 			}
 		}
 	}
 
-	/// Scan a range of entries with the current client
+	/// Delete the entries from sample `start`, up to but excluding sample `end`, at once
+	fn delete_range(
+		&self,
+		start: u32,
+		end: u32,
+		kp: &mut KeyProvider,
+	) -> impl Future<Output = Result<()>> + Send {
+		async move {
+			match kp {
+				KeyProvider::OrderedInteger(p) => {
+					self.delete_range_u32(p.key(start), p.key(end)).await
+				}
+				KeyProvider::OrderedString(p) => {
+					self.delete_range_string(p.key(start), p.key(end)).await
+				}
+				KeyProvider::OrderedUuid(p) => {
+					self.delete_range_string(p.key(start), p.key(end)).await
+				}
+				// Unordered keys are spread over the key space, so no range holds only these
+				KeyProvider::UnorderedInteger(_)
+				| KeyProvider::UnorderedString(_)
+				| KeyProvider::UnorderedUuid(_) => {
+					bail!(BenchError::Unsupported)
+				}
+			}
+		}
+	}
+
+	/// Scan a range of entries with the current client. The key-value stores
+	/// hold opaque values, so they return whole documents for every projection
+	/// other than `Id` and `Count`.
 	fn scan(
 		&self,
 		scan: &Scan,
@@ -131,9 +399,10 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 				KeyProvider::OrderedInteger(_) | KeyProvider::UnorderedInteger(_) => {
 					self.scan_u32(scan, ctx).await?
 				}
-				KeyProvider::OrderedString(_) | KeyProvider::UnorderedString(_) => {
-					self.scan_string(scan, ctx).await?
-				}
+				KeyProvider::OrderedString(_)
+				| KeyProvider::UnorderedString(_)
+				| KeyProvider::OrderedUuid(_)
+				| KeyProvider::UnorderedUuid(_) => self.scan_string(scan, ctx).await?,
 			};
 			if let Some(expect) = scan.expect {
 				assert_eq!(
@@ -162,6 +431,40 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 	/// Read a single entry with a string id
 	fn read_string(&self, key: String) -> impl Future<Output = Result<Self::ReadRow>> + Send;
 
+	/// Read a single entry with a numeric id, returning the engine-reported execution time
+	async fn read_server_time_u32(&self, _key: u32) -> Result<Duration> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Read a single entry with a string id, returning the engine-reported execution time
+	async fn read_server_time_string(&self, _key: String) -> Result<Duration> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Link an entry with a numeric id to the entries with the `targets` ids
+	fn relate_u32(&self, _key: u32, _targets: Vec<u32>) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
+	/// Link an entry with a string id to the entries with the `targets` ids
+	fn relate_string(
+		&self,
+		_key: String,
+		_targets: Vec<String>,
+	) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
+	/// Read the entries linked from an entry with a numeric id, returning the number found
+	fn traverse_u32(&self, _key: u32) -> impl Future<Output = Result<usize>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
+	/// Read the entries linked from an entry with a string id, returning the number found
+	fn traverse_string(&self, _key: String) -> impl Future<Output = Result<usize>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
 	/// Update a single entry with a numeric id
 	fn update_u32(&self, key: u32, val: BenchValue) -> impl Future<Output = Result<()>> + Send;
 
@@ -172,19 +475,58 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		val: BenchValue,
 	) -> impl Future<Output = Result<()>> + Send;
 
+	/// Update a single field, at a dotted path, of an entry with a numeric id
+	fn update_path_u32(
+		&self,
+		_key: u32,
+		_path: &str,
+		_val: BenchValue,
+	) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
+	/// Update a single field, at a dotted path, of an entry with a string id
+	fn update_path_string(
+		&self,
+		_key: String,
+		_path: &str,
+		_val: BenchValue,
+	) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
 	/// Delete a single entry with a numeric id
 	fn delete_u32(&self, key: u32) -> impl Future<Output = Result<()>> + Send;
 
 	/// Delete a single entry with a string id
 	fn delete_string(&self, key: String) -> impl Future<Output = Result<()>> + Send;
 
+	/// Delete the entries with numeric ids from `from`, up to but excluding `to`
+	fn delete_range_u32(&self, _from: u32, _to: u32) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
+	/// Delete the entries with string ids from `from`, up to but excluding `to`
+	fn delete_range_string(
+		&self,
+		_from: String,
+		_to: String,
+	) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
+	/// Delete every entry at once, through the bulk deletion of the datastore
+	fn truncate(&self) -> impl Future<Output = Result<()>> + Send {
+		async { bail!(BenchError::Unsupported) }
+	}
+
 	/// Scan a range of entries with numeric ids
 	fn scan_u32(
 		&self,
 		_scan: &Scan,
 		_ctx: ScanContext,
 	) -> impl Future<Output = Result<usize>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Scan a range of entries with string ids
@@ -193,17 +535,48 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		_scan: &Scan,
 		_ctx: ScanContext,
 	) -> impl Future<Output = Result<usize>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Return the ids of the records matched by a scan, in the order
+	/// the datastore returned them, regardless of the scan projection
+	async fn scan_ids(&self, _scan: &Scan) -> Result<Vec<String>> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Fetch a page of `limit` full records with integer ids, ordered by id,
+	/// returning the id of the last record, or `None` when the page is empty
+	async fn scan_page_u32(&self, _start: PageStart<u32>, _limit: usize) -> Result<Option<u32>> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Fetch a page of `limit` full records with string ids, ordered by id,
+	/// returning the id of the last record, or `None` when the page is empty
+	async fn scan_page_string(
+		&self,
+		_start: PageStart<String>,
+		_limit: usize,
+	) -> Result<Option<String>> {
+		bail!(BenchError::Unsupported)
+	}
+
+	/// Read the records whose `field` holds `value`, returning the number found
+	fn read_secondary(
+		&self,
+		_field: &str,
+		_value: String,
+	) -> impl Future<Output = Result<usize>> + Send {
+		async { bail!(BenchError::Unsupported) }
 	}
 
 	/// Build an index on specified fields
 	fn build_index(&self, _spec: &Index, _name: &str) -> impl Future<Output = Result<()>> + Send {
-		async { bail!(NOT_SUPPORTED_ERROR) }
+		async { bail!(BenchError::Unsupported) }
 	}
 
 	/// Drop an index by name
 	fn drop_index(&self, _name: &str) -> impl Future<Output = Result<()>> + Send {
-		async { bail!(NOT_SUPPORTED_ERROR) }
+		async { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch create operation
@@ -218,18 +591,83 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 			match kp {
 				KeyProvider::OrderedInteger(p) => {
 					let pairs_iter = generate_integer_key_values_iter(n, batch_op, p, vp);
-					self.batch_create_u32(pairs_iter).await
+					match batch_op.load {
+						true => self.batch_load_u32(pairs_iter).await,
+						false => self.batch_create_u32(pairs_iter).await,
+					}
 				}
 				KeyProvider::UnorderedInteger(p) => {
 					let pairs_iter = generate_integer_key_values_iter(n, batch_op, p, vp);
-					self.batch_create_u32(pairs_iter).await
+					match batch_op.load {
+						true => self.batch_load_u32(pairs_iter).await,
+						false => self.batch_create_u32(pairs_iter).await,
+					}
 				}
 				KeyProvider::OrderedString(p) => {
 					let pairs_iter = generate_string_key_values_iter(n, batch_op, p, vp);
-					self.batch_create_string(pairs_iter).await
+					match batch_op.load {
+						true => self.batch_load_string(pairs_iter).await,
+						false => self.batch_create_string(pairs_iter).await,
+					}
 				}
 				KeyProvider::UnorderedString(p) => {
 					let pairs_iter = generate_string_key_values_iter(n, batch_op, p, vp);
+					match batch_op.load {
+						true => self.batch_load_string(pairs_iter).await,
+						false => self.batch_create_string(pairs_iter).await,
+					}
+				}
+				KeyProvider::OrderedUuid(p) => {
+					let pairs_iter = generate_string_key_values_iter(n, batch_op, p, vp);
+					match batch_op.load {
+						true => self.batch_load_string(pairs_iter).await,
+						false => self.batch_create_string(pairs_iter).await,
+					}
+				}
+				KeyProvider::UnorderedUuid(p) => {
+					let pairs_iter = generate_string_key_values_iter(n, batch_op, p, vp);
+					match batch_op.load {
+						true => self.batch_load_string(pairs_iter).await,
+						false => self.batch_create_string(pairs_iter).await,
+					}
+				}
+			}
+		}
+	}
+
+	/// Create the `count` entries from sample `start` in a single batch,
+	/// through the bulk load path of [`Self::batch_create_u32`]
+	fn preload(
+		&self,
+		start: u32,
+		count: usize,
+		kp: &mut KeyProvider,
+		vp: &mut crate::valueprovider::ValueProvider,
+	) -> impl Future<Output = Result<()>> + Send {
+		async move {
+			match kp {
+				KeyProvider::OrderedInteger(p) => {
+					let pairs_iter = preload_integer_key_values_iter(start, count, p, vp);
+					self.batch_create_u32(pairs_iter).await
+				}
+				KeyProvider::UnorderedInteger(p) => {
+					let pairs_iter = preload_integer_key_values_iter(start, count, p, vp);
+					self.batch_create_u32(pairs_iter).await
+				}
+				KeyProvider::OrderedString(p) => {
+					let pairs_iter = preload_string_key_values_iter(start, count, p, vp);
+					self.batch_create_string(pairs_iter).await
+				}
+				KeyProvider::UnorderedString(p) => {
+					let pairs_iter = preload_string_key_values_iter(start, count, p, vp);
+					self.batch_create_string(pairs_iter).await
+				}
+				KeyProvider::OrderedUuid(p) => {
+					let pairs_iter = preload_string_key_values_iter(start, count, p, vp);
+					self.batch_create_string(pairs_iter).await
+				}
+				KeyProvider::UnorderedUuid(p) => {
+					let pairs_iter = preload_string_key_values_iter(start, count, p, vp);
 					self.batch_create_string(pairs_iter).await
 				}
 			}
@@ -261,6 +699,14 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 					let keys_iter = generate_string_keys_iter(n, batch_op, p);
 					self.batch_read_string(keys_iter).await
 				}
+				KeyProvider::OrderedUuid(p) => {
+					let keys_iter = generate_string_keys_iter(n, batch_op, p);
+					self.batch_read_string(keys_iter).await
+				}
+				KeyProvider::UnorderedUuid(p) => {
+					let keys_iter = generate_string_keys_iter(n, batch_op, p);
+					self.batch_read_string(keys_iter).await
+				}
 			}
 		}
 	}
@@ -291,6 +737,14 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 					let pairs_iter = generate_string_key_values_iter(n, batch_op, p, vp);
 					self.batch_update_string(pairs_iter).await
 				}
+				KeyProvider::OrderedUuid(p) => {
+					let pairs_iter = generate_string_key_values_iter(n, batch_op, p, vp);
+					self.batch_update_string(pairs_iter).await
+				}
+				KeyProvider::UnorderedUuid(p) => {
+					let pairs_iter = generate_string_key_values_iter(n, batch_op, p, vp);
+					self.batch_update_string(pairs_iter).await
+				}
 			}
 		}
 	}
@@ -320,6 +774,14 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 					let keys_iter = generate_string_keys_iter(n, batch_op, p);
 					self.batch_delete_string(keys_iter).await
 				}
+				KeyProvider::OrderedUuid(p) => {
+					let keys_iter = generate_string_keys_iter(n, batch_op, p);
+					self.batch_delete_string(keys_iter).await
+				}
+				KeyProvider::UnorderedUuid(p) => {
+					let keys_iter = generate_string_keys_iter(n, batch_op, p);
+					self.batch_delete_string(keys_iter).await
+				}
 			}
 		}
 	}
@@ -329,7 +791,7 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_key_value_pairs: impl Iterator<Item = (u32, BenchValue)> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch create operation with string keys
@@ -337,7 +799,23 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_key_value_pairs: impl Iterator<Item = (String, BenchValue)> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Perform a bulk load of a batch with numeric keys
+	fn batch_load_u32(
+		&self,
+		_key_value_pairs: impl Iterator<Item = (u32, BenchValue)> + Send,
+	) -> impl Future<Output = Result<()>> + Send {
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Perform a bulk load of a batch with string keys
+	fn batch_load_string(
+		&self,
+		_key_value_pairs: impl Iterator<Item = (String, BenchValue)> + Send,
+	) -> impl Future<Output = Result<()>> + Send {
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch read operation with numeric keys
@@ -345,7 +823,7 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_keys: impl Iterator<Item = u32> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch read operation with string keys
@@ -353,7 +831,7 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_keys: impl Iterator<Item = String> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch update operation with numeric keys
@@ -361,7 +839,7 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_key_value_pairs: impl Iterator<Item = (u32, BenchValue)> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch update operation with string keys
@@ -369,7 +847,7 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_key_value_pairs: impl Iterator<Item = (String, BenchValue)> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch delete operation with numeric keys
@@ -377,7 +855,7 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_keys: impl Iterator<Item = u32> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
 	}
 
 	/// Perform a batch delete operation with string keys
@@ -385,13 +863,53 @@ pub(crate) trait BenchmarkClient: Sync + Send + 'static {
 		&self,
 		_keys: impl Iterator<Item = String> + Send,
 	) -> impl Future<Output = Result<()>> + Send {
-		async move { bail!(NOT_SUPPORTED_ERROR) }
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Append a single timestamped row to the time series
+	fn append_time_series(&self, _row: TimeSeriesRow) -> impl Future<Output = Result<()>> + Send {
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Query the time series within a window, returning the number of
+	/// rows, or the number of non-empty buckets when downsampling
+	fn query_time_series(&self, _window: TimeWindow) -> impl Future<Output = Result<usize>> + Send {
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Write an event, notifying every open live query, with the
+	/// time it was written, in microseconds since the Unix epoch
+	fn append_live_event(&self, _seq: u32, _sent: u64) -> impl Future<Output = Result<()>> + Send {
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Write to the primary, with the time it was
+	/// written, in microseconds since the Unix epoch
+	fn append_replicated(&self, _id: u32, _sent: u64) -> impl Future<Output = Result<()>> + Send {
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Perform a batch create operation, letting the datastore
+	/// generate the keys, and returning them in insertion order
+	fn batch_create_generated(
+		&self,
+		_vals: Vec<BenchValue>,
+	) -> impl Future<Output = Result<Vec<GeneratedKey>>> + Send {
+		async move { bail!(BenchError::Unsupported) }
+	}
+
+	/// Perform a batch read operation with keys generated by the datastore
+	fn batch_read_generated(
+		&self,
+		_keys: Vec<GeneratedKey>,
+	) -> impl Future<Output = Result<()>> + Send {
+		async move { bail!(BenchError::Unsupported) }
 	}
 }
 
 /// Iterator for generating integer keys lazily
 struct IntegerKeysIter<'a> {
-	n: u32,
+	start: u32,
 	batch_size: usize,
 	current: usize,
 	kp: &'a mut dyn IntegerKeyProvider,
@@ -402,7 +920,7 @@ impl<'a> Iterator for IntegerKeysIter<'a> {
 
 	fn next(&mut self) -> Option<Self::Item> {
 		if self.current < self.batch_size {
-			let sample_idx = self.n * self.batch_size as u32 + self.current as u32;
+			let sample_idx = self.start + self.current as u32;
 			let key = self.kp.key(sample_idx);
 			self.current += 1;
 			Some(key)
@@ -421,7 +939,7 @@ impl<'a> ExactSizeIterator for IntegerKeysIter<'a> {}
 
 /// Iterator for generating string keys lazily
 struct StringKeysIter<'a> {
-	n: u32,
+	start: u32,
 	batch_size: usize,
 	current: usize,
 	kp: &'a mut dyn StringKeyProvider,
@@ -432,7 +950,7 @@ impl<'a> Iterator for StringKeysIter<'a> {
 
 	fn next(&mut self) -> Option<Self::Item> {
 		if self.current < self.batch_size {
-			let sample_idx = self.n * self.batch_size as u32 + self.current as u32;
+			let sample_idx = self.start + self.current as u32;
 			let key = self.kp.key(sample_idx);
 			self.current += 1;
 			Some(key)
@@ -451,7 +969,7 @@ impl<'a> ExactSizeIterator for StringKeysIter<'a> {}
 
 /// Iterator for generating integer key-value pairs lazily
 struct IntegerKeyValuesIter<'a> {
-	n: u32,
+	start: u32,
 	batch_size: usize,
 	current: usize,
 	kp: &'a mut dyn IntegerKeyProvider,
@@ -463,9 +981,9 @@ impl<'a> Iterator for IntegerKeyValuesIter<'a> {
 
 	fn next(&mut self) -> Option<Self::Item> {
 		if self.current < self.batch_size {
-			let sample_idx = self.n * self.batch_size as u32 + self.current as u32;
+			let sample_idx = self.start + self.current as u32;
 			let key = self.kp.key(sample_idx);
-			let value = self.vp.generate_value();
+			let value = self.vp.generate_value_for(sample_idx);
 			self.current += 1;
 			Some((key, value))
 		} else {
@@ -483,7 +1001,7 @@ impl<'a> ExactSizeIterator for IntegerKeyValuesIter<'a> {}
 
 /// Iterator for generating string key-value pairs lazily
 struct StringKeyValuesIter<'a> {
-	n: u32,
+	start: u32,
 	batch_size: usize,
 	current: usize,
 	kp: &'a mut dyn StringKeyProvider,
@@ -495,9 +1013,9 @@ impl<'a> Iterator for StringKeyValuesIter<'a> {
 
 	fn next(&mut self) -> Option<Self::Item> {
 		if self.current < self.batch_size {
-			let sample_idx = self.n * self.batch_size as u32 + self.current as u32;
+			let sample_idx = self.start + self.current as u32;
 			let key = self.kp.key(sample_idx);
-			let value = self.vp.generate_value();
+			let value = self.vp.generate_value_for(sample_idx);
 			self.current += 1;
 			Some((key, value))
 		} else {
@@ -520,7 +1038,7 @@ fn generate_integer_keys_iter<'a>(
 	kp: &'a mut dyn IntegerKeyProvider,
 ) -> IntegerKeysIter<'a> {
 	IntegerKeysIter {
-		n,
+		start: n * batch_op.batch_size as u32,
 		batch_size: batch_op.batch_size,
 		current: 0,
 		kp,
@@ -534,7 +1052,7 @@ fn generate_string_keys_iter<'a>(
 	kp: &'a mut dyn StringKeyProvider,
 ) -> StringKeysIter<'a> {
 	StringKeysIter {
-		n,
+		start: n * batch_op.batch_size as u32,
 		batch_size: batch_op.batch_size,
 		current: 0,
 		kp,
@@ -549,7 +1067,7 @@ fn generate_integer_key_values_iter<'a>(
 	vp: &'a mut crate::valueprovider::ValueProvider,
 ) -> IntegerKeyValuesIter<'a> {
 	IntegerKeyValuesIter {
-		n,
+		start: n * batch_op.batch_size as u32,
 		batch_size: batch_op.batch_size,
 		current: 0,
 		kp,
@@ -565,10 +1083,42 @@ fn generate_string_key_values_iter<'a>(
 	vp: &'a mut crate::valueprovider::ValueProvider,
 ) -> StringKeyValuesIter<'a> {
 	StringKeyValuesIter {
-		n,
+		start: n * batch_op.batch_size as u32,
 		batch_size: batch_op.batch_size,
 		current: 0,
 		kp,
 		vp,
 	}
 }
+
+/// Helper function to create an iterator for the integer key-value pairs of a preload batch
+fn preload_integer_key_values_iter<'a>(
+	start: u32,
+	count: usize,
+	kp: &'a mut dyn IntegerKeyProvider,
+	vp: &'a mut crate::valueprovider::ValueProvider,
+) -> IntegerKeyValuesIter<'a> {
+	IntegerKeyValuesIter {
+		start,
+		batch_size: count,
+		current: 0,
+		kp,
+		vp,
+	}
+}
+
+/// Helper function to create an iterator for the string key-value pairs of a preload batch
+fn preload_string_key_values_iter<'a>(
+	start: u32,
+	count: usize,
+	kp: &'a mut dyn StringKeyProvider,
+	vp: &'a mut crate::valueprovider::ValueProvider,
+) -> StringKeyValuesIter<'a> {
+	StringKeyValuesIter {
+		start,
+		batch_size: count,
+		current: 0,
+		kp,
+		vp,
+	}
+}
@@ -0,0 +1,276 @@
+//! Shared error categories which adapters map their driver errors into.
+//!
+//! Adapters return [`BenchError::Unsupported`] directly for operations they do not implement,
+//! and categorise driver errors through [`crate::engine::BenchmarkClient::classify_error`],
+//! so failures are counted per category in the results rather than lost in error strings.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// The category of a failed benchmark operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum BenchError {
+	/// The operation did not complete within the phase timeout.
+	Timeout,
+	/// The operation conflicted with a concurrent transaction, or deadlocked.
+	Conflict,
+	/// The record, or the index or table it is read from, does not exist.
+	NotFound,
+	/// A unique, foreign key, or other constraint rejected the write.
+	ConstraintViolation,
+	/// The datastore was temporarily unavailable, and the operation can be retried.
+	Transient,
+	/// The operation is not supported by the datastore, so the phase is skipped.
+	Unsupported,
+}
+
+impl BenchError {
+	/// The category of an error, when one is attached anywhere in its chain.
+	pub(crate) fn of(err: &anyhow::Error) -> Option<Self> {
+		err.chain().find_map(|e| e.downcast_ref::<Self>().copied())
+	}
+
+	/// Whether an error marks an operation as unsupported, skipping the phase.
+	pub(crate) fn is_unsupported(err: &anyhow::Error) -> bool {
+		Self::of(err) == Some(Self::Unsupported)
+	}
+
+	/// The key of this category in the result files.
+	pub(crate) fn name(self) -> &'static str {
+		match self {
+			Self::Timeout => "timeout",
+			Self::Conflict => "conflict",
+			Self::NotFound => "not_found",
+			Self::ConstraintViolation => "constraint_violation",
+			Self::Transient => "transient",
+			Self::Unsupported => "unsupported",
+		}
+	}
+
+	/// Every category, in the order they are declared.
+	const ALL: [Self; 6] = [
+		Self::Timeout,
+		Self::Conflict,
+		Self::NotFound,
+		Self::ConstraintViolation,
+		Self::Transient,
+		Self::Unsupported,
+	];
+}
+
+impl Display for BenchError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Timeout => write!(f, "Timeout"),
+			Self::Conflict => write!(f, "Conflict"),
+			Self::NotFound => write!(f, "NotFound"),
+			Self::ConstraintViolation => write!(f, "ConstraintViolation"),
+			Self::Transient => write!(f, "Transient"),
+			Self::Unsupported => write!(f, "Unsupported"),
+		}
+	}
+}
+
+impl std::error::Error for BenchError {}
+
+/// Failed operations of one phase, counted per category.
+#[derive(Default)]
+pub(crate) struct ErrorCounts(Mutex<BTreeMap<&'static str, u64>>);
+
+impl ErrorCounts {
+	/// Counts a failed operation, under `other` when it could not be categorised.
+	pub(crate) fn record(&self, category: Option<BenchError>) {
+		let name = category.map_or("other", BenchError::name);
+		let mut counts = self.0.lock().unwrap_or_else(|e| e.into_inner());
+		*counts.entry(name).or_default() += 1;
+	}
+
+	/// The counts recorded so far, keyed by category name.
+	pub(crate) fn snapshot(&self) -> ErrorSummary {
+		ErrorSummary(self.0.lock().unwrap_or_else(|e| e.into_inner()).clone())
+	}
+}
+
+/// Per-category error counts of a phase, as stored in the results.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub(crate) struct ErrorSummary(BTreeMap<&'static str, u64>);
+
+impl ErrorSummary {
+	/// Whether no operation failed.
+	pub(crate) fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// The failed operations across every category.
+	pub(crate) fn total(&self) -> u64 {
+		self.0.values().sum()
+	}
+}
+
+impl<'de> Deserialize<'de> for ErrorSummary {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		// The keys are interned as the static category names they were written from
+		let counts = BTreeMap::<String, u64>::deserialize(deserializer)?;
+		let mut summary = BTreeMap::new();
+		for (name, count) in counts {
+			let name = match BenchError::ALL.iter().map(|e| e.name()).find(|n| *n == name) {
+				Some(name) => name,
+				None if name == "other" => "other",
+				None => {
+					return Err(serde::de::Error::custom(format!(
+						"unknown error category `{name}`"
+					)));
+				}
+			};
+			summary.insert(name, count);
+		}
+		Ok(ErrorSummary(summary))
+	}
+}
+
+impl Display for ErrorSummary {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for (i, (name, count)) in self.0.iter().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+			write!(f, "{count} {name}")?;
+		}
+		Ok(())
+	}
+}
+
+/// The samples of a phase which completed and failed within one second, with `--continue-on-error`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ErrorWindow {
+	/// The samples which completed
+	pub(crate) ops: u64,
+	/// The samples which failed
+	pub(crate) errors: u64,
+}
+
+impl ErrorWindow {
+	/// The share of the samples which failed, between 0 and 1.
+	pub(crate) fn rate(&self) -> f64 {
+		match self.ops + self.errors {
+			0 => 0.0,
+			total => self.errors as f64 / total as f64,
+		}
+	}
+}
+
+/// The completed and failed samples of a phase which continues past its failures, per second.
+pub(crate) struct ErrorTimeline {
+	/// When the phase started
+	start: Instant,
+	/// The samples of each second since the start, merged from every worker
+	windows: Mutex<Vec<ErrorWindow>>,
+}
+
+impl ErrorTimeline {
+	pub(crate) fn new() -> Self {
+		Self {
+			start: Instant::now(),
+			windows: Mutex::new(Vec::new()),
+		}
+	}
+
+	/// Counts a completed or failed sample in the current second of a worker's own windows.
+	pub(crate) fn tally(&self, windows: &mut Vec<ErrorWindow>, failed: bool) {
+		let second = self.start.elapsed().as_secs() as usize;
+		if windows.len() <= second {
+			windows.resize(second + 1, ErrorWindow::default());
+		}
+		match failed {
+			true => windows[second].errors += 1,
+			false => windows[second].ops += 1,
+		}
+	}
+
+	/// Adds the windows of a worker which has finished.
+	pub(crate) fn merge(&self, windows: &[ErrorWindow]) {
+		let mut merged = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+		if merged.len() < windows.len() {
+			merged.resize(windows.len(), ErrorWindow::default());
+		}
+		for (merged, window) in merged.iter_mut().zip(windows) {
+			merged.ops += window.ops;
+			merged.errors += window.errors;
+		}
+	}
+
+	/// The windows of every second of the phase.
+	pub(crate) fn windows(&self) -> Vec<ErrorWindow> {
+		self.windows.lock().unwrap_or_else(|e| e.into_inner()).clone()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::{Context, anyhow};
+
+	#[test]
+	fn category_is_found_through_context() {
+		let err = anyhow!(BenchError::Unsupported).context("Scan failed");
+		assert!(BenchError::is_unsupported(&err));
+		let err: anyhow::Error = Err::<(), _>(BenchError::Timeout)
+			.context("Read did not complete within 30s")
+			.unwrap_err();
+		assert_eq!(BenchError::of(&err), Some(BenchError::Timeout));
+		assert_eq!(BenchError::of(&anyhow!("Unsupported")), None);
+	}
+
+	#[test]
+	fn counts_are_summarised_per_category() {
+		let counts = ErrorCounts::default();
+		assert!(counts.snapshot().is_empty());
+		counts.record(Some(BenchError::Timeout));
+		counts.record(None);
+		counts.record(Some(BenchError::Timeout));
+		assert_eq!(counts.snapshot().to_string(), "1 other, 2 timeout");
+	}
+
+	#[test]
+	fn timelines_merge_the_windows_of_every_worker() {
+		let timeline = ErrorTimeline::new();
+		let mut first = Vec::new();
+		timeline.tally(&mut first, false);
+		timeline.tally(&mut first, true);
+		timeline.tally(&mut first, false);
+		let second = vec![
+			ErrorWindow::default(),
+			ErrorWindow {
+				ops: 3,
+				errors: 1,
+			},
+		];
+		timeline.merge(&first);
+		timeline.merge(&second);
+		let windows = timeline.windows();
+		assert_eq!(windows.len(), 2);
+		assert_eq!(
+			windows[0],
+			ErrorWindow {
+				ops: 2,
+				errors: 1
+			}
+		);
+		assert_eq!(windows[1].rate(), 0.25);
+	}
+
+	#[test]
+	fn summary_roundtrips_through_json() {
+		let counts = ErrorCounts::default();
+		counts.record(Some(BenchError::ConstraintViolation));
+		counts.record(None);
+		let json = serde_json::to_string(&counts.snapshot()).unwrap();
+		let summary: ErrorSummary = serde_json::from_str(&json).unwrap();
+		assert_eq!(summary, counts.snapshot());
+		assert!(serde_json::from_str::<ErrorSummary>(r#"{"lost":1}"#).is_err());
+	}
+}
@@ -0,0 +1,217 @@
+//! `crud-bench export`: flatten `result*.json` files into the normalized rows of the published
+//! benchmark dashboards.
+//!
+//! Every completed phase of every file becomes one row, keyed by database and scenario (the same
+//! row labels as the CLI and CSV tables), alongside the run parameters and host hardware. Rows from
+//! any number of runs share one schema, so results from other hardware can be appended to the
+//! published dataset as they are.
+
+use crate::compare::ResultFile;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::path::Path;
+
+/// The version of the exported row schema, bumped whenever a column changes meaning.
+const SCHEMA_VERSION: u32 = 1;
+
+/// Command-line interface for `crud-bench export`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct ExportArgs {
+	/// Result JSON files to export, all into one dataset
+	#[arg(required = true, num_args = 1..)]
+	pub(crate) files: Vec<String>,
+
+	/// Path of the exported dataset
+	#[arg(short, long, default_value = "dashboard.csv")]
+	pub(crate) output: String,
+
+	/// Format of the exported dataset
+	#[arg(long, value_enum, default_value_t)]
+	pub(crate) format: ExportFormat,
+}
+
+/// File format of an exported dataset.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub(crate) enum ExportFormat {
+	/// One header line, then one line per row.
+	#[default]
+	Csv,
+	/// One JSON array of row objects.
+	Json,
+}
+
+/// One normalized dashboard row: a single scenario of a single database run.
+#[derive(Debug, Serialize)]
+struct DashboardRow<'a> {
+	/// [`SCHEMA_VERSION`] of the row.
+	schema_version: u32,
+	/// Display name of the datastore, or the file name when it is not recorded.
+	database: &'a str,
+	/// File name of the result file the row was exported from.
+	source: &'a str,
+	/// Row label, as in the CLI and CSV tables.
+	scenario: String,
+	/// One of `crud`, `scan`, `index`, or `batch`.
+	category: &'static str,
+	key_type: Option<&'a str>,
+	samples: Option<u32>,
+	clients: Option<u32>,
+	threads: Option<u32>,
+	random: Option<bool>,
+	os_name: Option<&'a str>,
+	cpu_arch: Option<&'a str>,
+	cpu_cores: Option<usize>,
+	cpu_physical_cores: Option<usize>,
+	/// Total host memory in bytes.
+	total_memory: Option<u64>,
+	/// Operations per second.
+	ops: f64,
+	mean_us: f64,
+	min_us: u64,
+	q50_us: u64,
+	q95_us: u64,
+	q99_us: u64,
+	max_us: u64,
+}
+
+/// The dashboard category of a row label.
+pub(crate) fn category(scenario: &str) -> &'static str {
+	if scenario.starts_with("[S]can") {
+		"scan"
+	} else if scenario.starts_with("[I]ndex") || scenario.starts_with("[R]emoveIndex") {
+		"index"
+	} else if scenario.starts_with("[B]atch") {
+		"batch"
+	} else {
+		"crud"
+	}
+}
+
+/// The rows of one result file, skipping the phases which did not run.
+fn dashboard_rows<'a>(source: &'a str, result: &'a ResultFile) -> Vec<DashboardRow<'a>> {
+	let database = result.database.as_deref().unwrap_or(source);
+	let metadata = result.metadata.as_ref();
+	let system = result.system.as_ref();
+	result
+		.rows()
+		.into_iter()
+		.filter_map(|(scenario, phase)| phase.map(|p| (scenario, p)))
+		.map(|(scenario, p)| DashboardRow {
+			schema_version: SCHEMA_VERSION,
+			database,
+			source,
+			category: category(&scenario),
+			scenario,
+			key_type: metadata.map(|m| m.key_type.as_str()),
+			samples: metadata.map(|m| m.samples),
+			clients: metadata.map(|m| m.clients),
+			threads: metadata.map(|m| m.threads),
+			random: metadata.map(|m| m.random),
+			os_name: system.map(|s| s.os_name.as_str()),
+			cpu_arch: system.map(|s| s.cpu_arch.as_str()),
+			cpu_cores: system.map(|s| s.cpu_cores),
+			cpu_physical_cores: system.map(|s| s.cpu_physical_cores),
+			total_memory: system.map(|s| s.total_memory),
+			ops: p.ops,
+			mean_us: p.mean,
+			min_us: p.min,
+			q50_us: p.q50,
+			q95_us: p.q95,
+			q99_us: p.q99,
+			max_us: p.max,
+		})
+		.collect()
+}
+
+/// Writes the rows to `path` in the chosen format.
+fn write_rows(rows: &[DashboardRow], path: &str, format: ExportFormat) -> Result<()> {
+	match format {
+		ExportFormat::Csv => {
+			let mut w = csv::Writer::from_path(path)?;
+			for row in rows {
+				w.serialize(row)?;
+			}
+			w.flush()?;
+		}
+		ExportFormat::Json => std::fs::write(path, serde_json::to_string_pretty(rows)?)?,
+	}
+	Ok(())
+}
+
+/// Loads the result files and writes their rows into one dataset.
+pub(crate) fn run(args: ExportArgs) -> Result<()> {
+	let results =
+		args.files.iter().map(|f| ResultFile::load(f)).collect::<Result<Vec<ResultFile>>>()?;
+	let sources: Vec<&str> = args
+		.files
+		.iter()
+		.map(|path| Path::new(path).file_name().and_then(|f| f.to_str()).unwrap_or(path))
+		.collect();
+	let rows: Vec<DashboardRow> =
+		sources.iter().zip(&results).flat_map(|(source, r)| dashboard_rows(source, r)).collect();
+	write_rows(&rows, &args.output, args.format)
+		.with_context(|| format!("Failed to write dataset '{}'", args.output))?;
+	println!(
+		"📊 Exported {} rows from {} result files to: {}",
+		rows.len(),
+		results.len(),
+		args.output
+	);
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::{ResultFile, category, dashboard_rows};
+
+	const RESULT: &str = r#"{
+		"database": "DashMap",
+		"system": {"os_name": "Linux", "cpu_arch": "x86_64", "cpu_cores": 16, "total_memory": 1024},
+		"metadata": {"samples": 100, "clients": 2, "threads": 4, "key_type": "Integer", "random": true},
+		"creates": {"ops": 1000.0, "q99": 12},
+		"reads": {"ops": 2000.0},
+		"updates": null,
+		"scans": [{
+			"id": "limit",
+			"name": "select(id) limit(100)",
+			"samples": 10,
+			"index_build": null,
+			"index_remove": null,
+			"runs": [{"workload": {"kind": "read"}, "indexed": false, "result": null}]
+		}],
+		"batches": [["batch_test", 10, 5, {"ops": 50.0}]]
+	}"#;
+
+	#[test]
+	fn rows_skip_phases_which_did_not_run() {
+		let result: ResultFile = serde_json::from_str(RESULT).unwrap();
+		let rows = dashboard_rows("result.json", &result);
+		let scenarios: Vec<&str> = rows.iter().map(|r| r.scenario.as_str()).collect();
+		assert_eq!(scenarios, vec!["[C]reate", "[R]ead", "[B]atch::batch_test (10 batches of 5)"]);
+		let create = &rows[0];
+		assert_eq!(
+			(create.database, create.source, create.category),
+			("DashMap", "result.json", "crud")
+		);
+		assert_eq!((create.ops, create.q99_us), (1000.0, 12));
+		assert_eq!(
+			(create.key_type, create.clients, create.threads),
+			(Some("Integer"), Some(2), Some(4))
+		);
+		assert_eq!((create.cpu_arch, create.cpu_cores), (Some("x86_64"), Some(16)));
+		assert_eq!(rows[2].category, "batch");
+	}
+
+	#[test]
+	fn rows_without_run_details_fall_back_to_the_file_name() {
+		let result: ResultFile = serde_json::from_str(r#"{"reads": {"ops": 1.0}}"#).unwrap();
+		let rows = dashboard_rows("result-old.json", &result);
+		assert_eq!(rows.len(), 1);
+		assert_eq!(rows[0].database, "result-old.json");
+		assert_eq!((rows[0].samples, rows[0].os_name), (None, None));
+		assert_eq!(category("[I]ndex · limit · build"), "index");
+		assert_eq!(category("[S]can · limit · select(id) limit(100)"), "scan");
+	}
+}
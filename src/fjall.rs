@@ -1,19 +1,20 @@
 #![cfg(feature = "fjall")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::materialize;
 use crate::memory::Config as MemoryConfig;
+use crate::util::kvindex::{KvIndexes, Matches};
+use crate::util::{crate_version, dir_size};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, bail};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
+use anyhow::Result;
 use fjall::{
 	KeyspaceCreateOptions, KvSeparationOptions, OptimisticTxDatabase, OptimisticTxKeyspace,
 	PersistMode, Readable, config::BlockSizePolicy,
 };
 use std::hint::black_box;
 use std::sync::Arc;
-use std::time::Duration;
 
 const DATABASE_DIR: &str = "fjall";
 
@@ -30,18 +31,18 @@ fn calculate_fjall_memory() -> u64 {
 pub(crate) struct FjallClientProvider {
 	db: Arc<OptimisticTxDatabase>,
 	keyspace: Arc<OptimisticTxKeyspace>,
+	index: Arc<OptimisticTxKeyspace>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
 impl BenchmarkEngine<FjallClient> for FjallClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Cleanup the data directory
-		std::fs::remove_dir_all(DATABASE_DIR).ok();
+		// Cleanup the data directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_dir_all(DATABASE_DIR).ok();
+		}
 		// Calculate memory allocation
 		let memory = calculate_fjall_memory();
 		// Configure and create the database
@@ -70,10 +71,14 @@ impl BenchmarkEngine<FjallClient> for FjallClientProvider {
 			.with_kv_separation(Some(blob_opts));
 		// Create a default data keyspace
 		let keyspace = db.keyspace("default", || keyspace_opts)?;
+		// Create a keyspace for the entries of the emulated secondary indexes
+		let index = db.keyspace("index", KeyspaceCreateOptions::default)?;
 		// Create the store
 		Ok(Self {
 			db: Arc::new(db),
 			keyspace: Arc::new(keyspace),
+			index: Arc::new(index),
+			indexes: Arc::new(KvIndexes::new(options.emulate_indexes, &[])),
 			sync: options.sync,
 		})
 	}
@@ -82,6 +87,8 @@ impl BenchmarkEngine<FjallClient> for FjallClientProvider {
 		Ok(FjallClient {
 			db: self.db.clone(),
 			keyspace: self.keyspace.clone(),
+			index: self.index.clone(),
+			indexes: self.indexes.clone(),
 			sync: self.sync,
 		})
 	}
@@ -90,6 +97,8 @@ impl BenchmarkEngine<FjallClient> for FjallClientProvider {
 pub(crate) struct FjallClient {
 	db: Arc<OptimisticTxDatabase>,
 	keyspace: Arc<OptimisticTxKeyspace>,
+	index: Arc<OptimisticTxKeyspace>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
@@ -97,6 +106,10 @@ impl BenchmarkClient for FjallClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("fjall")
+	}
+
 	async fn shutdown(&self) -> Result<()> {
 		// Cleanup the data directory
 		std::fs::remove_dir_all(DATABASE_DIR).ok();
@@ -104,6 +117,17 @@ impl BenchmarkClient for FjallClient {
 		Ok(())
 	}
 
+	fn disk_size(&self) -> Option<u64> {
+		dir_size(DATABASE_DIR)
+	}
+
+	async fn sync(&self) -> Result<()> {
+		// Flush and sync the journal to storage
+		self.db.persist(PersistMode::SyncAll)?;
+		// Ok
+		Ok(())
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create_bytes(&key.to_ne_bytes(), val).await
 	}
@@ -136,12 +160,63 @@ impl BenchmarkClient for FjallClient {
 		self.delete_bytes(&key.into_bytes()).await
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// Writes maintain the index from now on
+		let index = self.indexes.create(spec, name)?;
+		// Collect the entries of the existing records
+		let mut entries = Vec::new();
+		for kv in self.db.read_tx().iter(&*self.keyspace) {
+			let (key, val) = kv.into_inner()?;
+			if let Some(entry) = index.entry(&key, &BenchValue::decode(&val)?) {
+				entries.push(entry);
+			}
+		}
+		// Set the transaction durability
+		let durability = if self.sync {
+			Some(PersistMode::SyncData)
+		} else {
+			Some(PersistMode::Buffer)
+		};
+		// Create a new transaction
+		let mut txn = self.db.write_tx()?.durability(durability);
+		// Write the entries
+		for entry in entries {
+			txn.insert(&*self.index, entry, &b""[..]);
+		}
+		txn.commit()??;
+		Ok(())
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		// Writes stop maintaining the index
+		let index = self.indexes.remove(name)?;
+		// Collect the entries of the index
+		let mut entries = Vec::new();
+		for kv in self.db.read_tx().range(&*self.index, index.start().to_vec()..index.end()) {
+			entries.push(kv.key()?);
+		}
+		// Set the transaction durability
+		let durability = if self.sync {
+			Some(PersistMode::SyncData)
+		} else {
+			Some(PersistMode::Buffer)
+		};
+		// Create a new transaction
+		let mut txn = self.db.write_tx()?.durability(durability);
+		// Delete the entries
+		for entry in entries {
+			txn.remove(&*self.index, entry);
+		}
+		txn.commit()??;
+		Ok(())
 	}
 
 	async fn batch_create_u32(
@@ -213,6 +288,8 @@ impl FjallClient {
 	async fn create_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
 		// Serialise the value
 		let val = val.encode()?;
+		// Derive any index entries
+		let entries = self.indexes.entries(key, &val)?;
 		// Set the transaction durability
 		let durability = if self.sync {
 			Some(PersistMode::SyncData)
@@ -223,6 +300,9 @@ impl FjallClient {
 		let mut txn = self.db.write_tx()?.durability(durability);
 		// Process the data
 		txn.insert(&*self.keyspace, key, val);
+		for entry in entries {
+			txn.insert(&*self.index, entry, &b""[..]);
+		}
 		txn.commit()??;
 		Ok(())
 	}
@@ -243,6 +323,8 @@ impl FjallClient {
 	async fn update_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
 		// Serialise the value
 		let val = val.encode()?;
+		// Derive any index entries
+		let entries = self.indexes.entries(key, &val)?;
 		// Set the transaction durability
 		let durability = if self.sync {
 			Some(PersistMode::SyncData)
@@ -253,6 +335,9 @@ impl FjallClient {
 		let mut txn = self.db.write_tx()?.durability(durability);
 		// Process the data
 		txn.insert(&*self.keyspace, key, val);
+		for entry in entries {
+			txn.insert(&*self.index, entry, &b""[..]);
+		}
 		txn.commit()??;
 		Ok(())
 	}
@@ -287,6 +372,9 @@ impl FjallClient {
 		// Process the data
 		for result in key_vals {
 			let (key, val) = result?;
+			for entry in self.indexes.entries(&key, &val)? {
+				txn.insert(&*self.index, entry, &b""[..]);
+			}
 			txn.insert(&*self.keyspace, &key, val);
 		}
 		// Commit the batch
@@ -303,10 +391,8 @@ impl FjallClient {
 			let res = txn.get(&*self.keyspace, &key)?;
 			// Check the value exists
 			assert!(res.is_some());
-			// Deserialise the value
-			let val = BenchValue::decode(res.unwrap().as_ref())?;
-			// Use the value
-			black_box(val);
+			// Materialise the value
+			materialize::consume(res.unwrap(), |v| BenchValue::decode(v.as_ref()))?;
 		}
 		// All ok
 		Ok(())
@@ -327,6 +413,9 @@ impl FjallClient {
 		// Process the data
 		for result in key_vals {
 			let (key, val) = result?;
+			for entry in self.indexes.entries(&key, &val)? {
+				txn.insert(&*self.index, entry, &b""[..]);
+			}
 			txn.insert(&*self.keyspace, &key, val);
 		}
 		// Commit the batch
@@ -352,10 +441,10 @@ impl FjallClient {
 		Ok(())
 	}
 
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Contional scans are not supported
+	async fn scan_bytes(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Conditional scans filter the records
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			return self.scan_filtered(scan, ctx).await;
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -379,7 +468,7 @@ impl FjallClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// Create an iterator starting at the beginning
 				let iter = txn.iter(&*self.keyspace);
 				// calling black_box internally. This is necessary as
@@ -387,7 +476,7 @@ impl FjallClient {
 				// out by the compiler when calling `count` at the end.
 				let mut count = 0;
 				for kv in iter.skip(s).take(l) {
-					black_box(kv.value()?);
+					materialize::consume(kv.value()?, |v| BenchValue::decode(&v))?;
 					count += 1;
 				}
 				Ok(count)
@@ -401,4 +490,43 @@ impl FjallClient {
 			}
 		}
 	}
+
+	async fn scan_filtered(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Only conditions on a single integer field are supported
+		let filter = self.indexes.filter(scan, ctx)?;
+		let mut matches = Matches::new(scan)?;
+		// Create a new transaction
+		let txn = self.db.read_tx();
+		match self.indexes.find(&filter, ctx) {
+			// Look up the records of each range of index entries
+			Some(index) => {
+				'ranges: for range in filter.ranges() {
+					let (beg, end) = index.range(*range);
+					for kv in txn.range(&*self.index, beg..end) {
+						let entry = kv.key()?;
+						let (value, key) = index.split(&entry);
+						// Skip the entries left behind by updates and deletes
+						let Some(val) = txn.get(&*self.keyspace, key)? else {
+							continue;
+						};
+						let record = BenchValue::decode(&val)?;
+						if index.holds(value, &record) && !matches.push(key, record) {
+							break 'ranges;
+						}
+					}
+				}
+			}
+			// Decode and filter every record
+			None => {
+				for kv in txn.iter(&*self.keyspace) {
+					let (key, val) = kv.into_inner()?;
+					let record = BenchValue::decode(&val)?;
+					if filter.matches(&record) && !matches.push(&key, record) {
+						break;
+					}
+				}
+			}
+		}
+		Ok(matches.count())
+	}
 }
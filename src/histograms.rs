@@ -0,0 +1,188 @@
+//! `crud-bench histograms`: re-render the latency percentiles of `result*.json` files from the
+//! complete histograms they store.
+//!
+//! Every timed phase stores its latency histogram in the V2 deflate encoding of HdrHistogram, as
+//! base64, the same form as the lines of an HdrHistogram interval log. Any percentile can then be
+//! recomputed after the run, and the histograms of several runs merged into one.
+
+use crate::compare::{ResultFile, column_title};
+use anyhow::{Context, Result, ensure};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, CellAlignment, Color, ContentArrangement, Table};
+use hdrhistogram::Histogram;
+use hdrhistogram::serialization::{Deserializer, Serializer, V2DeflateSerializer};
+
+/// Command-line interface for `crud-bench histograms`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct HistogramsArgs {
+	/// Result JSON files whose stored histograms are rendered, one table per file
+	#[arg(required = true, num_args = 1..)]
+	pub(crate) files: Vec<String>,
+
+	/// Comma-separated latency percentiles to render
+	#[arg(long, value_delimiter = ',', default_value = "50,90,99,99.9,99.99")]
+	pub(crate) percentiles: Vec<f64>,
+
+	/// Merge the histograms of the same operation across the files into one table
+	#[arg(long)]
+	pub(crate) merge: bool,
+}
+
+/// Encodes a latency histogram as base64 of its V2 deflate encoding, or `None` when it could not
+/// be encoded.
+pub(crate) fn encode(histogram: &Histogram<u64>) -> Option<String> {
+	let mut encoded = Vec::new();
+	V2DeflateSerializer::new().serialize(histogram, &mut encoded).ok()?;
+	Some(STANDARD.encode(encoded))
+}
+
+/// Decodes a latency histogram written by [`encode`].
+pub(crate) fn decode(encoded: &str) -> Result<Histogram<u64>> {
+	let bytes = STANDARD.decode(encoded).context("The histogram is not valid base64")?;
+	Deserializer::new().deserialize(&mut &bytes[..]).context("The histogram could not be decoded")
+}
+
+/// The decoded histogram of each operation row of a result file, in CLI table order (`None` when
+/// the phase was skipped, or the file predates stored histograms).
+fn histograms(result: &ResultFile) -> Result<Vec<(String, Option<Histogram<u64>>)>> {
+	result
+		.rows()
+		.into_iter()
+		.map(|(label, phase)| {
+			let histogram = phase
+				.and_then(|p| p.histogram.as_deref())
+				.map(decode)
+				.transpose()
+				.with_context(|| format!("Invalid histogram for {label}"))?;
+			Ok((label, histogram))
+		})
+		.collect()
+}
+
+/// Adds the histograms of each row to those of the row with the same label, appending the rows
+/// which are not there yet.
+fn merge(
+	merged: &mut Vec<(String, Option<Histogram<u64>>)>,
+	rows: Vec<(String, Option<Histogram<u64>>)>,
+) -> Result<()> {
+	for (label, histogram) in rows {
+		match merged.iter_mut().find(|(l, _)| *l == label) {
+			Some((_, Some(total))) => {
+				if let Some(histogram) = histogram {
+					total.add(histogram).with_context(|| format!("Failed to merge {label}"))?;
+				}
+			}
+			Some((_, total)) => *total = histogram,
+			None => merged.push((label, histogram)),
+		}
+	}
+	Ok(())
+}
+
+/// The column title of a percentile, e.g. `p99.9`.
+fn percentile_title(percentile: f64) -> String {
+	format!("p{percentile}")
+}
+
+/// Builds the table of the samples, rendered percentiles, and maximum of each operation row.
+fn table(rows: &[(String, Option<Histogram<u64>>)], percentiles: &[f64]) -> Table {
+	let mut table = Table::new();
+	table
+		.load_preset(UTF8_FULL)
+		.apply_modifier(UTF8_ROUND_CORNERS)
+		.set_content_arrangement(ContentArrangement::Dynamic);
+	let mut headers = vec!["Test".to_string(), "Samples".to_string()];
+	headers.extend(percentiles.iter().map(|&p| percentile_title(p)));
+	headers.push("Max".to_string());
+	table.set_header(
+		headers.into_iter().map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Blue)),
+	);
+	for (label, histogram) in rows {
+		let mut row = vec![Cell::new(label)];
+		match histogram {
+			Some(histogram) => {
+				let ms = |v: u64| Cell::new(format!("{:.2} ms", v as f64 / 1000.0));
+				row.push(Cell::new(histogram.len()));
+				row.extend(percentiles.iter().map(|&p| ms(histogram.value_at_percentile(p))));
+				row.push(ms(histogram.max()));
+			}
+			None => row.extend((0..percentiles.len() + 2).map(|_| Cell::new("-"))),
+		}
+		table.add_row(row);
+	}
+	// Right align the numeric columns
+	for i in 1..table.column_count() {
+		if let Some(column) = table.column_mut(i) {
+			column.set_cell_alignment(CellAlignment::Right);
+		}
+	}
+	table
+}
+
+/// Prints the percentiles of each file, or of all the files merged, from their stored histograms.
+pub(crate) fn run(args: HistogramsArgs) -> Result<()> {
+	ensure!(
+		args.percentiles.iter().all(|p| (0.0..=100.0).contains(p)),
+		"The percentiles must be between 0 and 100"
+	);
+	let mut merged = Vec::new();
+	for path in &args.files {
+		let result = ResultFile::load(path)?;
+		let rows = histograms(&result).with_context(|| format!("In result file '{path}'"))?;
+		if args.merge {
+			merge(&mut merged, rows)?;
+		} else {
+			println!("{}", column_title(path, &result).replace('\n', " "));
+			println!("{}", table(&rows, &args.percentiles));
+		}
+	}
+	if args.merge {
+		println!("Merged from {} result files", args.files.len());
+		println!("{}", table(&merged, &args.percentiles));
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::{decode, encode, merge, percentile_title};
+	use hdrhistogram::Histogram;
+
+	fn histogram(values: impl IntoIterator<Item = u64>) -> Histogram<u64> {
+		let mut histogram = Histogram::<u64>::new(3).unwrap();
+		for v in values {
+			histogram.record(v).unwrap();
+		}
+		histogram
+	}
+
+	#[test]
+	fn histograms_round_trip_through_base64() {
+		let original = histogram(1..=10_000);
+		let decoded = decode(&encode(&original).unwrap()).unwrap();
+		assert_eq!(decoded.len(), original.len());
+		for p in [50.0, 99.0, 99.9, 99.99] {
+			assert_eq!(decoded.value_at_percentile(p), original.value_at_percentile(p));
+		}
+		assert!(decode("not base64!").is_err());
+	}
+
+	#[test]
+	fn merged_rows_add_their_samples() {
+		let mut merged = vec![("[R]ead".to_string(), Some(histogram([1000, 2000])))];
+		let rows =
+			vec![("[R]ead".to_string(), Some(histogram([3000]))), ("[U]pdate".to_string(), None)];
+		merge(&mut merged, rows).unwrap();
+		assert_eq!(merged.len(), 2);
+		let read = merged[0].1.as_ref().unwrap();
+		assert_eq!(read.len(), 3);
+		assert!(read.equivalent(read.max(), 3000));
+		assert!(merged[1].1.is_none());
+		assert_eq!(percentile_title(99.9), "p99.9");
+		assert_eq!(percentile_title(50.0), "p50");
+	}
+}
@@ -1,41 +1,64 @@
 #![cfg(feature = "keydb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::docker::DockerParams;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::util::resp::{Multiplexed, RespConnection};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, anyhow, bail};
-use futures::StreamExt;
-use redis::aio::MultiplexedConnection;
-use redis::{AsyncCommands, Client, ScanOptions};
+use crate::{Benchmark, KeyType, Scan};
+use anyhow::Result;
+use redis::AsyncCommands;
+use std::collections::BTreeMap;
 use std::hint::black_box;
 use tokio::sync::Mutex;
 
 pub const DEFAULT: &str = "redis://:root@127.0.0.1:6379/";
 
-pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+/// The server flags the container is started with, recorded in the benchmark metadata.
+///
+/// The thread count, snapshot cadence, and multi-master mode can be set from the environment, so
+/// KeyDB's multi-threading can be examined under controlled configurations.
+pub(crate) fn server_options(options: &Benchmark) -> BTreeMap<String, String> {
+	let mut flags = BTreeMap::new();
 	// KeyDB's defining feature is multi-threading; without `--server-threads`
-	// it behaves like single-threaded Redis. Pin to the number of host CPU
-	// cores (capped at the practical KeyDB recommendation of 8). With
+	// it behaves like single-threaded Redis. Pin to the number of CPU cores
+	// of the container (capped at the practical KeyDB recommendation of 8). With
 	// `appendfsync always` (i.e. --sync) multiple I/O threads each take the
 	// AOF lock and fsync independently, which defeats the per-iteration
 	// fsync batching and is dramatically slower than single-threaded — so
 	// we drop back to one thread in that case.
-	let threading = if options.sync {
-		"--server-threads 1".to_string()
-	} else {
-		let n = num_cpus::get().clamp(2, 8);
-		format!("--server-threads {n} --server-thread-affinity true")
+	let threads = match std::env::var("CRUD_BENCH_KEYDB_THREADS") {
+		Ok(threads) => threads,
+		Err(_) if options.sync => "1".to_string(),
+		Err(_) => options.container_cores().clamp(2, 8).to_string(),
 	};
-	// Persistence: AOF on/off + sync flush. When persisted=true we also
-	// disable RDB explicitly so the default snapshot schedule doesn't
-	// contend with AOF writes during the benchmark.
+	if threads != "1" {
+		flags.insert("server-thread-affinity".to_string(), "true".to_string());
+	}
+	flags.insert("server-threads".to_string(), threads);
+	// RDB snapshots as `<seconds> <changes>` pairs, disabled by default so the
+	// snapshot schedule doesn't contend with the benchmark (or AOF) writes.
+	let save = std::env::var("CRUD_BENCH_KEYDB_SAVE").unwrap_or_default();
+	flags.insert("save".to_string(), save);
+	// Multi-master mode, which takes the replica code paths even on one node
+	if std::env::var("CRUD_BENCH_KEYDB_MULTI_MASTER").is_ok_and(|v| v == "true") {
+		flags.insert("multi-master".to_string(), "yes".to_string());
+		flags.insert("active-replica".to_string(), "yes".to_string());
+	}
+	flags
+}
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	let flags = server_options(options)
+		.iter()
+		.map(|(flag, value)| format!(" --{flag} '{value}'"))
+		.collect::<String>();
+	// Persistence: AOF on/off + sync flush.
 	let persistence = match (options.persisted, options.sync) {
-		(false, _) => "--appendonly no --save ''".to_string(),
-		(true, false) => "--appendonly yes --appendfsync everysec --save ''".to_string(),
-		(true, true) => "--appendonly yes --appendfsync always --save ''".to_string(),
+		(false, _) => "--appendonly no",
+		(true, false) => "--appendonly yes --appendfsync everysec",
+		(true, true) => "--appendonly yes --appendfsync always",
 	};
 	// Memory cap (optimised only) — without one the container OOM-kills
 	// rather than evicting; this keeps comparisons deterministic.
@@ -49,26 +72,31 @@ pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 	DockerParams {
 		image: "eqalpha/keydb",
 		pre_args: "-p 127.0.0.1:6379:6379".to_string(),
-		post_args: format!("keydb-server --requirepass root {threading} {persistence} {memory}"),
+		post_args: format!("keydb-server --requirepass root{flags} {persistence} {memory}"),
 	}
 }
 
 pub(crate) struct KeydbClientProvider {
 	url: String,
+	pipelined: bool,
 }
 
 impl BenchmarkEngine<KeydbClient> for KeydbClientProvider {
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
+		// TLS is chosen by the `rediss://` scheme of the endpoint
+		options.security.without_tls_options("KeyDB")?;
 		Ok(KeydbClientProvider {
-			url: options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned(),
+			url: options
+				.security
+				.url_with_credentials(options.endpoint.as_deref().unwrap_or(DEFAULT)),
+			pipelined: options.pipelined,
 		})
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<KeydbClient> {
-		let client = Client::open(self.url.as_str())?;
-		let conn_record = Mutex::new(client.get_multiplexed_async_connection().await?);
-		let conn_iter = Mutex::new(client.get_multiplexed_async_connection().await?);
+		let conn_record = RespConnection::new(Multiplexed::node(&self.url).await?, self.pipelined);
+		let conn_iter = Mutex::new(Multiplexed::node(&self.url).await?);
 		Ok(KeydbClient {
 			conn_record,
 			conn_iter,
@@ -77,14 +105,38 @@ impl BenchmarkEngine<KeydbClient> for KeydbClientProvider {
 }
 
 pub(crate) struct KeydbClient {
-	conn_record: Mutex<MultiplexedConnection>,
-	conn_iter: Mutex<MultiplexedConnection>,
+	conn_record: RespConnection,
+	conn_iter: Mutex<Multiplexed>,
 }
 
 impl BenchmarkClient for KeydbClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		self.conn_record.server_version().await
+	}
+
+	fn classify_error(&self, err: &anyhow::Error) -> Option<BenchError> {
+		use redis::ErrorKind;
+		let err = err.chain().find_map(|e| e.downcast_ref::<redis::RedisError>())?;
+		if err.is_timeout() {
+			return Some(BenchError::Timeout);
+		}
+		if err.is_io_error() || err.is_connection_dropped() {
+			return Some(BenchError::Transient);
+		}
+		match err.kind() {
+			// A transaction aborted by a watched key
+			ErrorKind::ExecAbortError => Some(BenchError::Conflict),
+			ErrorKind::TryAgain
+			| ErrorKind::ClusterDown
+			| ErrorKind::MasterDown
+			| ErrorKind::BusyLoadingError => Some(BenchError::Transient),
+			_ => None,
+		}
+	}
+
 	#[allow(dependency_on_unit_never_type_fallback)]
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		let val = val.encode()?;
@@ -142,81 +194,33 @@ impl BenchmarkClient for KeydbClient {
 	}
 
 	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+		self.conn_record.scan(&self.conn_iter, scan).await
 	}
 
 	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+		self.conn_record.scan(&self.conn_iter, scan).await
 	}
 
 	async fn batch_create_u32(
 		&self,
 		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
 	) -> Result<()> {
-		// Build the SET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for (k, v) in key_vals {
-			pipe.cmd("SET").arg(k).arg(v.encode()?).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_set(key_vals).await
 	}
 
 	async fn batch_create_string(
 		&self,
 		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
 	) -> Result<()> {
-		// Build the SET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for (k, v) in key_vals {
-			pipe.cmd("SET").arg(k).arg(v.encode()?).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_set(key_vals).await
 	}
 
 	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
-		// Build the GET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		let mut count = 0usize;
-		for k in keys {
-			pipe.cmd("GET").arg(k);
-			count += 1;
-		}
-		// Execute the pipeline and inspect the responses
-		let vals: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
-		assert_eq!(vals.len(), count);
-		for v in vals {
-			let v = v.ok_or_else(|| anyhow!("missing key"))?;
-			assert!(!v.is_empty());
-			black_box(v);
-		}
-		Ok(())
+		self.conn_record.batch_get(keys).await
 	}
 
 	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
-		// Build the GET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		let mut count = 0usize;
-		for k in keys {
-			pipe.cmd("GET").arg(k);
-			count += 1;
-		}
-		// Execute the pipeline and inspect the responses
-		let vals: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
-		assert_eq!(vals.len(), count);
-		for v in vals {
-			let v = v.ok_or_else(|| anyhow!("missing key"))?;
-			assert!(!v.is_empty());
-			black_box(v);
-		}
-		Ok(())
+		self.conn_record.batch_get(keys).await
 	}
 
 	async fn batch_update_u32(
@@ -236,86 +240,10 @@ impl BenchmarkClient for KeydbClient {
 	}
 
 	async fn batch_delete_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
-		// Build the DEL pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for k in keys {
-			pipe.cmd("DEL").arg(k).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_del(keys).await
 	}
 
 	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
-		// Build the DEL pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for k in keys {
-			pipe.cmd("DEL").arg(k).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
-	}
-}
-
-impl KeydbClient {
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Conditional scans are not supported
-		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
-		}
-		// Extract parameters
-		let s = scan.start.unwrap_or(0);
-		let l = scan.limit.unwrap_or(usize::MAX);
-		let p = scan.projection()?;
-		// Get the two connection types
-		let mut conn_iter = self.conn_iter.lock().await;
-		let mut conn_record = self.conn_record.lock().await;
-		// Configure the scan options for improve iteration
-		let opts = ScanOptions::default().with_count(5000);
-		// Create an iterator starting at the beginning
-		let mut iter = conn_iter.scan_options::<String>(opts).await?.skip(s);
-		// Perform the relevant projection scan type
-		match p {
-			Projection::Id => {
-				// We use a for loop to iterate over the results, while
-				// calling black_box internally. This is necessary as
-				// an iterator with `filter_map` or `map` is optimised
-				// out by the compiler when calling `count` at the end.
-				let mut count = 0;
-				for _ in 0..l {
-					if let Some(k) = iter.next().await {
-						black_box(k);
-						count += 1;
-					} else {
-						break;
-					}
-				}
-				Ok(count)
-			}
-			Projection::Full => {
-				// We use a for loop to iterate over the results, while
-				// calling black_box internally. This is necessary as
-				// an iterator with `filter_map` or `map` is optimised
-				// out by the compiler when calling `count` at the end.
-				let mut count = 0;
-				while let Some(k) = iter.next().await {
-					let v: Vec<u8> = conn_record.get(k).await?;
-					black_box(v);
-					count += 1;
-					if count >= l {
-						break;
-					}
-				}
-				Ok(count)
-			}
-			Projection::Count => match scan.limit {
-				// Full count queries are too slow
-				None => bail!(NOT_SUPPORTED_ERROR),
-				Some(l) => Ok(iter.take(l).count().await),
-			},
-		}
+		self.conn_record.batch_del(keys).await
 	}
 }
@@ -1,5 +1,8 @@
 use crate::KeyType;
+use anyhow::{Result, bail};
+use std::sync::RwLock;
 use twox_hash::XxHash64;
+use uuid::{Builder, Uuid};
 
 #[derive(Clone, Copy)]
 pub(crate) enum KeyProvider {
@@ -7,6 +10,8 @@ pub(crate) enum KeyProvider {
 	UnorderedInteger(UnorderedInteger),
 	OrderedString(OrderedString),
 	UnorderedString(UnorderedString),
+	OrderedUuid(OrderedUuid),
+	UnorderedUuid(UnorderedUuid),
 }
 
 impl KeyProvider {
@@ -48,8 +53,24 @@ impl KeyProvider {
 				}
 			}
 			KeyType::Uuid => {
-				todo!()
+				if random {
+					Self::UnorderedUuid(UnorderedUuid::new(UuidVersion::V7))
+				} else {
+					Self::OrderedUuid(OrderedUuid::default())
+				}
 			}
+			// Random UUIDs are unordered whichever order they are generated in
+			KeyType::UuidV4 => Self::UnorderedUuid(UnorderedUuid::new(UuidVersion::V4)),
+		}
+	}
+
+	/// The bytes of each key, as stored by the key-value datastores.
+	pub(crate) fn key_size(&self) -> usize {
+		match self {
+			Self::OrderedInteger(_) | Self::UnorderedInteger(_) => 4,
+			Self::OrderedString(s) => 10 + 16 * s.0,
+			Self::UnorderedString(s) => 10 + 16 * s.0,
+			Self::OrderedUuid(_) | Self::UnorderedUuid(_) => 36,
 		}
 	}
 }
@@ -155,9 +176,106 @@ impl StringKeyProvider for UnorderedString {
 	}
 }
 
+/// The first timestamp of the version 7 UUID keys, 2024-01-01T00:00:00Z, in milliseconds.
+const UUID_EPOCH_MILLIS: u64 = 1_704_067_200_000;
+
+/// The version of the UUID keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum UuidVersion {
+	/// Random bytes
+	V4,
+	/// A millisecond timestamp followed by random bytes
+	V7,
+}
+
+/// A UUID derived from the sample, with its timestamp a millisecond after that of the previous
+/// sample for version 7, so the same sample always has the same key.
+fn uuid(n: u32, timestamp: u32, version: UuidVersion) -> Uuid {
+	let high = XxHash64::oneshot(0, &n.to_be_bytes()).to_be_bytes();
+	let low = XxHash64::oneshot(1, &n.to_be_bytes()).to_be_bytes();
+	let mut bytes = [0u8; 16];
+	bytes[..8].copy_from_slice(&high);
+	bytes[8..].copy_from_slice(&low);
+	match version {
+		UuidVersion::V4 => Builder::from_random_bytes(bytes).into_uuid(),
+		UuidVersion::V7 => {
+			let millis = UUID_EPOCH_MILLIS + timestamp as u64;
+			let random: [u8; 10] = bytes[..10].try_into().expect("10 of the 16 bytes");
+			Builder::from_unix_timestamp_millis(millis, &random).into_uuid()
+		}
+	}
+}
+
+/// Version 7 UUID keys, ordered as the samples are.
+#[derive(Default, Clone, Copy)]
+pub(crate) struct OrderedUuid();
+
+impl StringKeyProvider for OrderedUuid {
+	fn key(&mut self, n: u32) -> String {
+		uuid(n, n, UuidVersion::V7).hyphenated().to_string()
+	}
+}
+
+/// Version 4 UUID keys, or version 7 UUID keys with their timestamps shuffled across the samples.
+#[derive(Clone, Copy)]
+pub(crate) struct UnorderedUuid(UuidVersion);
+
+impl UnorderedUuid {
+	fn new(version: UuidVersion) -> Self {
+		Self(version)
+	}
+}
+
+impl StringKeyProvider for UnorderedUuid {
+	fn key(&mut self, n: u32) -> String {
+		let timestamp = UnorderedInteger::feistel_transform(n);
+		uuid(n, timestamp, self.0).hyphenated().to_string()
+	}
+}
+
+/// A key assigned by the datastore when a record was created.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum GeneratedKey {
+	/// An auto-increment or identity column value
+	Integer(i64),
+	/// An ObjectId, random record id, or other textual key
+	String(String),
+}
+
+/// The keys returned by `CREATE_GENERATED` batches, read back by `READ_GENERATED` batches.
+#[derive(Debug, Default)]
+pub(crate) struct GeneratedKeys(RwLock<Vec<GeneratedKey>>);
+
+impl GeneratedKeys {
+	/// Records the keys returned by one batch create.
+	pub(crate) fn record(&self, keys: Vec<GeneratedKey>) {
+		self.0.write().unwrap_or_else(|e| e.into_inner()).extend(keys);
+	}
+
+	/// The keys read by batch `n`, cycling over all the keys recorded so far.
+	pub(crate) fn batch(&self, n: u32, batch_size: usize) -> Result<Vec<GeneratedKey>> {
+		let keys = self.0.read().unwrap_or_else(|e| e.into_inner());
+		// Each batch reads distinct keys, as duplicates would return fewer rows
+		if keys.len() < batch_size {
+			bail!(
+				"Only {} generated keys to read in batches of {batch_size}, \
+				 a larger CREATE_GENERATED batch must run first",
+				keys.len()
+			);
+		}
+		let start = n as usize * batch_size;
+		Ok((start..start + batch_size).map(|i| keys[i % keys.len()].clone()).collect())
+	}
+}
+
 #[cfg(test)]
 mod test {
-	use crate::keyprovider::{OrderedString, StringKeyProvider, UnorderedString};
+	use crate::KeyType;
+	use crate::keyprovider::{
+		GeneratedKey, GeneratedKeys, KeyProvider, OrderedString, OrderedUuid, StringKeyProvider,
+		UnorderedString, UnorderedUuid, UuidVersion,
+	};
+	use uuid::Uuid;
 
 	#[test]
 	fn ordered_string_26() {
@@ -240,4 +358,49 @@ mod test {
 			"d79235c904e704c6c379c25fea98cd11b4d0f71900f91df2ecc87c25d7fff4b03be1bd13590485d31bc0feb2815d5c908f5a4633b8a9d5d6ec1c074d5d64ab296c6495f784f8294ac42b828a9c4ef45d3decc0a8dff00062adfb547fea6132f38afda36acf629cc15413acfe35a50fecbec285e9ee42b13691088df6c3740c87c3d003e3addf1888a582ac5cb408feec138fe9a43c9fda574006e770bb0b5e84edcbeecc6f723960ed7d02591a7b2487bb317f83bfd95e44a69d957deb6b10e22d895a375acfa54143137feeb53921625bc9d582166477e562454fecc90f130662338c070bd709c27d8478abaa825dc69bc3aa89dc7ce0760012345678"
 		);
 	}
+
+	#[test]
+	fn key_size_matches_the_generated_keys() {
+		assert_eq!(KeyProvider::new(KeyType::Integer, true).key_size(), 4);
+		assert_eq!(KeyProvider::new(KeyType::String26, false).key_size(), 26);
+		assert_eq!(KeyProvider::new(KeyType::String90, true).key_size(), 90);
+		assert_eq!(KeyProvider::new(KeyType::String250, false).key_size(), 250);
+		assert_eq!(KeyProvider::new(KeyType::String506, true).key_size(), 506);
+		assert_eq!(KeyProvider::new(KeyType::Uuid, false).key_size(), 36);
+		assert_eq!(KeyProvider::new(KeyType::UuidV4, false).key_size(), 36);
+	}
+
+	#[test]
+	fn ordered_uuids_sort_as_the_samples() {
+		let mut o = OrderedUuid::default();
+		let keys: Vec<String> = (0..1000).map(|n| o.key(n)).collect();
+		assert!(keys.windows(2).all(|w| w[0] < w[1]));
+		let uuid = Uuid::parse_str(&keys[0]).unwrap();
+		assert_eq!(uuid.get_version_num(), 7);
+		assert_eq!(keys[0].len(), 36);
+		assert_eq!(o.key(0), keys[0]);
+	}
+
+	#[test]
+	fn unordered_uuids_are_distinct() {
+		for (version, number) in [(UuidVersion::V7, 7), (UuidVersion::V4, 4)] {
+			let mut o = UnorderedUuid::new(version);
+			let mut keys: Vec<String> = (0..1000).map(|n| o.key(n)).collect();
+			assert!(!keys.windows(2).all(|w| w[0] < w[1]));
+			assert_eq!(Uuid::parse_str(&keys[0]).unwrap().get_version_num(), number);
+			keys.sort();
+			keys.dedup();
+			assert_eq!(keys.len(), 1000);
+		}
+	}
+
+	#[test]
+	fn generated_keys_cycle_over_recorded_keys() {
+		let keys = GeneratedKeys::default();
+		assert!(keys.batch(0, 2).is_err());
+		keys.record((1..=3).map(GeneratedKey::Integer).collect());
+		assert!(keys.batch(0, 4).is_err());
+		assert_eq!(keys.batch(0, 2).unwrap(), [GeneratedKey::Integer(1), GeneratedKey::Integer(2)]);
+		assert_eq!(keys.batch(1, 2).unwrap(), [GeneratedKey::Integer(3), GeneratedKey::Integer(1)]);
+	}
 }
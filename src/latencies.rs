@@ -0,0 +1,108 @@
+//! Streams the latency of every individual sample to a JSON Lines file (`--raw-latencies`).
+//!
+//! Workers only push records onto a channel; a dedicated thread serialises them, so enabling
+//! the stream adds no file I/O to the timed benchmark loop.
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Serialize, Serializer};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
+use std::sync::mpsc::{Sender, channel};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// One line in the raw latency file.
+#[derive(Serialize)]
+struct LatencyRecord {
+	/// Phase name, e.g. `Create` or `Scan :: indexed · limit · select(id) limit(100)`.
+	#[serde(serialize_with = "serialize_operation")]
+	operation: Arc<str>,
+	/// 1-based client index.
+	client: u32,
+	/// 1-based thread index within the client.
+	thread: u32,
+	/// Wall-clock start of the sample, in microseconds since the Unix epoch.
+	timestamp_us: u64,
+	/// Sample latency in microseconds.
+	latency_us: u64,
+}
+
+/// Serialises the shared operation name as a plain string.
+fn serialize_operation<S>(operation: &Arc<str>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	serializer.serialize_str(operation)
+}
+
+/// Background writer for the raw latency file.
+pub(crate) struct RawLatencies {
+	/// Queue feeding the writer thread.
+	sender: Sender<LatencyRecord>,
+	/// Writer thread, returning any I/O error once the queue closes.
+	writer: JoinHandle<Result<()>>,
+}
+
+impl RawLatencies {
+	/// Creates the output file and starts the writer thread.
+	pub(crate) fn create(path: &str) -> Result<Self> {
+		let file = File::create(path)
+			.with_context(|| format!("Failed to create raw latency file '{path}'"))?;
+		let (sender, receiver) = channel::<LatencyRecord>();
+		let writer = std::thread::Builder::new().name("crud-bench-latencies".to_string()).spawn(
+			move || {
+				let mut out = BufWriter::new(file);
+				for record in receiver {
+					serde_json::to_writer(&mut out, &record)?;
+					out.write_all(b"\n")?;
+				}
+				out.flush()?;
+				Ok(())
+			},
+		)?;
+		Ok(Self {
+			sender,
+			writer,
+		})
+	}
+
+	/// Returns a handle recording samples for one worker of an operation.
+	pub(crate) fn sink(&self, operation: Arc<str>, client: u32, thread: u32) -> LatencySink {
+		LatencySink {
+			sender: self.sender.clone(),
+			operation,
+			client,
+			thread,
+		}
+	}
+
+	/// Closes the queue and waits until every record is written.
+	pub(crate) fn finish(self) -> Result<()> {
+		drop(self.sender);
+		self.writer.join().map_err(|_| anyhow!("Raw latency writer panicked"))?
+	}
+}
+
+/// Per-worker handle queuing samples for [`RawLatencies`].
+pub(crate) struct LatencySink {
+	sender: Sender<LatencyRecord>,
+	operation: Arc<str>,
+	client: u32,
+	thread: u32,
+}
+
+impl LatencySink {
+	/// Queues one sample which started at `start` and took `latency`.
+	pub(crate) fn record(&self, start: SystemTime, latency: Duration) {
+		let timestamp = start.duration_since(UNIX_EPOCH).unwrap_or_default();
+		// A closed queue means the writer failed, which is reported by `finish`
+		let _ = self.sender.send(LatencyRecord {
+			operation: self.operation.clone(),
+			client: self.client,
+			thread: self.thread,
+			timestamp_us: timestamp.as_micros() as u64,
+			latency_us: latency.as_micros() as u64,
+		});
+	}
+}
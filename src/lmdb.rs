@@ -1,18 +1,20 @@
 #![cfg(feature = "lmdb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::materialize;
+use crate::util::kvindex::{KvIndexes, Matches};
+use crate::util::{crate_version, dir_size};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, bail};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
+use anyhow::Result;
 use heed::types::Bytes;
 use heed::{Database, EnvOpenOptions};
 use heed::{Env, EnvFlags, WithoutTls};
 use std::hint::black_box;
+use std::ops::Bound;
 use std::sync::Arc;
 use std::sync::LazyLock;
-use std::time::Duration;
 
 const DATABASE_DIR: &str = "lmdb";
 
@@ -24,17 +26,18 @@ static DATABASE_SIZE: LazyLock<usize> = LazyLock::new(|| {
 		.unwrap_or(DEFAULT_SIZE)
 });
 
-pub(crate) struct LmDBClientProvider(Arc<(Env<WithoutTls>, Database<Bytes, Bytes>)>);
+/// The environment, the records, and the entries of the emulated secondary indexes
+type Store = (Env<WithoutTls>, Database<Bytes, Bytes>, Database<Bytes, Bytes>);
+
+pub(crate) struct LmDBClientProvider(Arc<Store>, Arc<KvIndexes>);
 
 impl BenchmarkEngine<LmDBClient> for LmDBClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Cleanup the data directory
-		std::fs::remove_dir_all(DATABASE_DIR).ok();
+		// Cleanup the data directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_dir_all(DATABASE_DIR).ok();
+		}
 		// Recreate the database directory
 		std::fs::create_dir(DATABASE_DIR)?;
 		// Configure flags based on options
@@ -50,8 +53,8 @@ impl BenchmarkEngine<LmDBClient> for LmDBClientProvider {
 				.read_txn_without_tls()
 				// Configure database flags
 				.flags(flags)
-				// We only use one db for benchmarks
-				.max_dbs(1)
+				// One db for the records, and one for index entries
+				.max_dbs(2)
 				// Optimize for expected concurrent readers
 				.max_readers(126)
 				// Set the database size
@@ -59,32 +62,42 @@ impl BenchmarkEngine<LmDBClient> for LmDBClientProvider {
 				// Open the database
 				.open(DATABASE_DIR)
 		}?;
-		// Create the database
-		let db = {
+		// Create the databases
+		let (db, index) = {
 			// Open a new transaction
 			let mut txn = env.write_txn()?;
-			// Initiate the database
-			env.create_database::<Bytes, Bytes>(&mut txn, None)?
+			// Initiate the databases
+			let db = env.create_database::<Bytes, Bytes>(&mut txn, None)?;
+			let index = env.create_database::<Bytes, Bytes>(&mut txn, Some("index"))?;
+			txn.commit()?;
+			(db, index)
 		};
 		// Create the store
-		Ok(Self(Arc::new((env, db))))
+		let indexes = KvIndexes::new(options.emulate_indexes, &[]);
+		Ok(Self(Arc::new((env, db, index)), Arc::new(indexes)))
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<LmDBClient> {
 		Ok(LmDBClient {
 			db: self.0.clone(),
+			indexes: self.1.clone(),
 		})
 	}
 }
 
 pub(crate) struct LmDBClient {
-	db: Arc<(Env<WithoutTls>, Database<Bytes, Bytes>)>,
+	db: Arc<Store>,
+	indexes: Arc<KvIndexes>,
 }
 
 impl BenchmarkClient for LmDBClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("heed")
+	}
+
 	async fn shutdown(&self) -> Result<()> {
 		// Cleanup the data directory
 		std::fs::remove_dir_all(DATABASE_DIR).ok();
@@ -92,6 +105,17 @@ impl BenchmarkClient for LmDBClient {
 		Ok(())
 	}
 
+	fn disk_size(&self) -> Option<u64> {
+		dir_size(DATABASE_DIR)
+	}
+
+	async fn sync(&self) -> Result<()> {
+		// Flush the buffers of the environment to disk
+		self.db.0.force_sync()?;
+		// Ok
+		Ok(())
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create_bytes(&key.to_ne_bytes(), val).await
 	}
@@ -124,12 +148,46 @@ impl BenchmarkClient for LmDBClient {
 		self.delete_bytes(&key.into_bytes()).await
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// Writes maintain the index from now on
+		let index = self.indexes.create(spec, name)?;
+		// Create a new transaction
+		let mut txn = self.db.0.write_txn()?;
+		// Collect the entries of the existing records
+		let mut entries = Vec::new();
+		for v in self.db.1.iter(&txn)? {
+			let (key, val) = v?;
+			if let Some(entry) = index.entry(key, &BenchValue::decode(val)?) {
+				entries.push(entry);
+			}
+		}
+		// Write the entries
+		for entry in entries {
+			self.db.2.put(&mut txn, &entry, &[])?;
+		}
+		txn.commit()?;
+		Ok(())
+	}
+
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		// Writes stop maintaining the index
+		let index = self.indexes.remove(name)?;
+		// Create a new transaction
+		let mut txn = self.db.0.write_txn()?;
+		// Delete the entries of the index
+		let end = index.end();
+		let range = (Bound::Included(index.start()), Bound::Excluded(end.as_slice()));
+		self.db.2.delete_range(&mut txn, &range)?;
+		txn.commit()?;
+		Ok(())
 	}
 
 	async fn batch_create_u32(
@@ -205,6 +263,9 @@ impl LmDBClient {
 		let mut txn = self.db.0.write_txn()?;
 		// Process the data
 		self.db.1.put(&mut txn, key, val.as_ref())?;
+		for entry in self.indexes.entries(key, &val)? {
+			self.db.2.put(&mut txn, &entry, &[])?;
+		}
 		txn.commit()?;
 		Ok(())
 	}
@@ -229,6 +290,9 @@ impl LmDBClient {
 		let mut txn = self.db.0.write_txn()?;
 		// Process the data
 		self.db.1.put(&mut txn, key, &val)?;
+		for entry in self.indexes.entries(key, &val)? {
+			self.db.2.put(&mut txn, &entry, &[])?;
+		}
 		txn.commit()?;
 		Ok(())
 	}
@@ -252,6 +316,9 @@ impl LmDBClient {
 		for result in key_vals {
 			let (key, val) = result?;
 			self.db.1.put(&mut txn, &key, &val)?;
+			for entry in self.indexes.entries(&key, &val)? {
+				self.db.2.put(&mut txn, &entry, &[])?;
+			}
 		}
 		// Commit the batch
 		txn.commit()?;
@@ -267,10 +334,8 @@ impl LmDBClient {
 			let res: Option<_> = self.db.1.get(&txn, &key)?;
 			// Check the value exists
 			assert!(res.is_some());
-			// Deserialise the value
-			let val = BenchValue::decode(res.unwrap())?;
-			// Use the value
-			black_box(val);
+			// Materialise the value
+			materialize::consume(res.unwrap(), BenchValue::decode)?;
 		}
 		// All ok
 		Ok(())
@@ -286,6 +351,9 @@ impl LmDBClient {
 		for result in key_vals {
 			let (key, val) = result?;
 			self.db.1.put(&mut txn, &key, &val)?;
+			for entry in self.indexes.entries(&key, &val)? {
+				self.db.2.put(&mut txn, &entry, &[])?;
+			}
 		}
 		// Commit the batch
 		txn.commit()?;
@@ -304,10 +372,10 @@ impl LmDBClient {
 		Ok(())
 	}
 
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Contional scans are not supported
+	async fn scan_bytes(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Conditional scans filter the records
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			return self.scan_filtered(scan, ctx).await;
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -331,14 +399,14 @@ impl LmDBClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
 				// an iterator with `filter_map` or `map` is optimised
 				// out by the compiler when calling `count` at the end.
 				let mut count = 0;
 				for v in iter.skip(s).take(l) {
-					black_box(v.unwrap().1);
+					materialize::consume(v.unwrap().1, BenchValue::decode)?;
 					count += 1;
 				}
 				Ok(count)
@@ -351,4 +419,44 @@ impl LmDBClient {
 			}
 		}
 	}
+
+	async fn scan_filtered(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Only conditions on a single integer field are supported
+		let filter = self.indexes.filter(scan, ctx)?;
+		let mut matches = Matches::new(scan)?;
+		// Create a new transaction
+		let txn = self.db.0.read_txn()?;
+		match self.indexes.find(&filter, ctx) {
+			// Look up the records of each range of index entries
+			Some(index) => {
+				'ranges: for range in filter.ranges() {
+					let (beg, end) = index.range(*range);
+					let range = (Bound::Included(beg.as_slice()), Bound::Excluded(end.as_slice()));
+					for v in self.db.2.range(&txn, &range)? {
+						let (entry, _) = v?;
+						let (value, key) = index.split(entry);
+						// Skip the entries left behind by updates and deletes
+						let Some(val) = self.db.1.get(&txn, key)? else {
+							continue;
+						};
+						let record = BenchValue::decode(val)?;
+						if index.holds(value, &record) && !matches.push(key, record) {
+							break 'ranges;
+						}
+					}
+				}
+			}
+			// Decode and filter every record
+			None => {
+				for v in self.db.1.iter(&txn)? {
+					let (key, val) = v?;
+					let record = BenchValue::decode(val)?;
+					if filter.matches(&record) && !matches.push(key, record) {
+						break;
+					}
+				}
+			}
+		}
+		Ok(matches.count())
+	}
 }
@@ -0,0 +1,49 @@
+//! Diagnostic logging through `tracing`, filtered with `RUST_LOG`.
+//!
+//! Events are printed to stderr (errors only, unless `RUST_LOG` says otherwise) and, with
+//! `--log-file`, also written as JSON lines carrying the enclosing spans: the `benchmark`
+//! span (`database`), the `phase` span (`phase`), and the `worker` span (`client`, `thread`).
+
+use anyhow::{Context, Result, anyhow};
+use std::fs::File;
+use std::io::IsTerminal;
+use std::sync::Mutex;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, fmt};
+
+/// Installs the global subscriber, adding a JSON log file when `log_file` is set.
+pub(crate) fn init(log_file: Option<&str>) -> Result<()> {
+	// Only errors reach the terminal by default, as with `env_logger`
+	let stderr = fmt::layer()
+		.with_writer(std::io::stderr)
+		.with_ansi(std::io::stderr().is_terminal())
+		.with_filter(filter(LevelFilter::ERROR));
+	// The log file records the run at the info level by default
+	let file = match log_file {
+		Some(path) => {
+			let file = File::create(path)
+				.with_context(|| format!("Failed to create log file '{path}'"))?;
+			let layer = fmt::layer()
+				.json()
+				.with_current_span(true)
+				.with_span_list(true)
+				.with_thread_names(true)
+				.with_writer(Mutex::new(file))
+				.with_filter(filter(LevelFilter::INFO));
+			Some(layer)
+		}
+		None => None,
+	};
+	tracing_subscriber::registry()
+		.with(stderr)
+		.with(file)
+		.try_init()
+		.map_err(|e| anyhow!("Failed to initialise logging: {e}"))
+}
+
+/// Filters events with `RUST_LOG`, falling back to `level` when it is unset.
+fn filter(level: LevelFilter) -> EnvFilter {
+	EnvFilter::builder().with_default_directive(level.into()).from_env_lossy()
+}
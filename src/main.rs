@@ -2,40 +2,74 @@
 //!
 //! Datastore implementations live in sibling modules; workload loading uses [`crate::config`].
 
+use crate::affinity::CoreSet;
+use crate::agent::Shard;
 use crate::benchmark::Benchmark;
+use crate::checkpoint::Checkpoint;
 use crate::config::load_bench_toml;
 use crate::database::Database;
 use crate::keyprovider::KeyProvider;
+use crate::materialize::Materialize;
+use crate::ramp::Ramp;
+use crate::serverprofile::{ServerProfile, ServerProfiler};
 use crate::terminal::ColorChoice;
 use crate::valueprovider::ValueProvider;
 use anyhow::{Result, bail};
-use clap::{Parser, ValueEnum};
+use bytesize::ByteSize;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use docker::Container;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::ffi::OsString;
 use std::fs::File;
 use std::io::{IsTerminal, Write};
+use std::time::Duration;
 use tokio::runtime;
+use tracing::Instrument;
 
 // Benchmark modules
+mod affinity;
+mod agent;
 mod allocator;
+mod assertions;
 mod benchmark;
+mod cgroup;
 mod chart;
+mod checkpoint;
+mod compare;
 mod config;
 mod database;
 mod dialect;
 mod docker;
 mod engine;
+mod error;
+mod export;
+mod histograms;
 mod keyprovider;
+mod latencies;
+mod logging;
+mod materialize;
+mod matrix;
 mod memory;
 mod profiling;
+mod ramp;
+mod report;
+mod rerun;
 mod result;
+mod results;
+mod retry;
+mod security;
+mod serverprofile;
+mod soak;
 mod storage;
 mod system;
 mod terminal;
+mod timeout;
+mod timeseries;
 mod util;
 mod value;
 mod valueprovider;
+mod verify;
 mod workloads;
 
 // Datastore modules
@@ -50,8 +84,11 @@ mod mariadb;
 mod mdbx;
 mod mongodb;
 mod mysql;
+mod mysql_common;
 mod neo4j;
+mod opensearch;
 mod postgres;
+mod questdb;
 mod redb;
 mod redis;
 mod rocksdb;
@@ -62,11 +99,24 @@ mod surrealdb;
 mod surrealds;
 mod surrealkv;
 mod surrealmx;
+mod tidb;
+mod timescaledb;
+mod yugabytedb;
 
-/// Command-line interface for a single benchmark run.
+/// Command-line interface for a single benchmark run, or one of the commands run instead.
 #[derive(Parser, Debug)]
-#[command(term_width = 0)]
+#[command(
+	term_width = 0,
+	args_override_self = true,
+	args_conflicts_with_subcommands = true,
+	subcommand_negates_reqs = true
+)]
 pub(crate) struct Args {
+	/// Process existing result files, or drive a benchmark from several machines, instead of
+	/// running a benchmark
+	#[command(subcommand)]
+	pub(crate) command: Option<Command>,
+
 	/// An optional name for the test, used as a suffix for the JSON result file name
 	#[arg(short, long)]
 	pub(crate) name: Option<String>,
@@ -83,10 +133,58 @@ pub(crate) struct Args {
 	#[arg(short, long)]
 	pub(crate) privileged: bool,
 
+	/// Run every Docker container for this platform, e.g. linux/arm64, rather than the host's
+	#[arg(long, env = "DOCKER_PLATFORM")]
+	pub(crate) docker_platform: Option<String>,
+
+	/// Limit the CPU cores of each Docker container, e.g. 4 or 2.5
+	#[arg(long)]
+	pub(crate) container_cpus: Option<f64>,
+
+	/// Limit the memory of each Docker container, e.g. 16GiB
+	#[arg(long)]
+	pub(crate) container_memory: Option<ByteSize>,
+
+	/// Pin each Docker container to a set of cores, e.g. 16-31
+	#[arg(long, value_parser = CoreSet::parse)]
+	pub(crate) container_cores: Option<CoreSet>,
+
+	/// Pin the benchmark runtime and threadpool to a set of cores, e.g. 0-15
+	#[arg(long, value_parser = CoreSet::parse)]
+	pub(crate) pin_cores: Option<CoreSet>,
+
+	/// Pin the benchmark to the cores and memory of a NUMA node
+	#[arg(long, conflicts_with = "pin_cores")]
+	pub(crate) numa_node: Option<u32>,
+
 	/// Specify a custom endpoint to connect to
 	#[arg(short, long)]
 	pub(crate) endpoint: Option<String>,
 
+	/// Connect to the endpoint as this user, rather than the datastore's default
+	#[arg(long, env = "CRUD_BENCH_USERNAME", requires = "endpoint")]
+	pub(crate) username: Option<String>,
+
+	/// Connect to the endpoint with this password, rather than the datastore's default
+	#[arg(long, env = "CRUD_BENCH_PASSWORD", hide_env_values = true, requires = "endpoint")]
+	pub(crate) password: Option<String>,
+
+	/// Connect to the endpoint over TLS, trusting the certificate authority in this PEM file
+	#[arg(long, requires = "endpoint")]
+	pub(crate) tls_ca: Option<String>,
+
+	/// Connect to the endpoint over TLS, accepting any server certificate
+	#[arg(long, default_value = "false", requires = "endpoint")]
+	pub(crate) tls_insecure: bool,
+
+	/// Run the datastore as a multi-container topology, such as a replica set or cluster
+	#[arg(long)]
+	pub(crate) topology: Option<String>,
+
+	/// Emulate the network to the containers with tc/netem rules, e.g. "delay 2ms loss 0.1%"
+	#[arg(long)]
+	pub(crate) netem: Option<String>,
+
 	/// Maximum number of blocking threads (default is the number of CPU cores)
 	#[arg(short, long, default_value=num_cpus::get().to_string(), value_parser=clap::value_parser!(u32).range(1..))]
 	pub(crate) blocking: u32,
@@ -99,6 +197,10 @@ pub(crate) struct Args {
 	#[arg(short, long, default_value = "1", value_parser=clap::value_parser!(u32).range(1..))]
 	pub(crate) clients: u32,
 
+	/// Repeat the benchmark at each of these clients, as `clients=1,2,4,8`, charting throughput and latency against them
+	#[arg(long, value_parser = Ramp::parse, conflicts_with = "resume")]
+	pub(crate) ramp: Option<Ramp>,
+
 	/// Number of concurrent threads per client
 	#[arg(short, long, default_value = "1", value_parser=clap::value_parser!(u32).range(1..))]
 	pub(crate) threads: u32,
@@ -107,10 +209,62 @@ pub(crate) struct Args {
 	#[arg(short, long, value_parser=clap::value_parser!(u32).range(1..))]
 	pub(crate) samples: u32,
 
+	/// Number of records bulk loaded beyond the samples before the measured creates, untimed
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) preload: Option<u32>,
+
+	/// Number of records the reads and updates select from, preloading any beyond the samples
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) keyspace: Option<u32>,
+
+	/// Reuse the records kept by an earlier `--keep-data` run, skipping the setup which clears the datastore and the creates
+	#[arg(long, default_value = "false")]
+	pub(crate) no_setup: bool,
+
+	/// Keep the records after the run, skipping the deletes and the cleanup of the datastore
+	#[arg(long, default_value = "false")]
+	pub(crate) keep_data: bool,
+
+	/// Comma-separated phases to run, from create, read, update, scan, delete, and batch (default is every phase)
+	#[arg(long, value_delimiter = ',')]
+	pub(crate) phases: Vec<Phase>,
+
+	/// Comma-separated bulk deletions to measure after the deletes, each on the records loaded again
+	#[arg(long, value_delimiter = ',')]
+	pub(crate) delete_strategies: Vec<DeleteStrategy>,
+
+	/// Number of records removed by each range delete
+	#[arg(long, default_value = "1000", value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) delete_range_size: u32,
+
+	/// Comma-separated ids of scans to run again after the deletes, measuring the cost of the deleted records
+	#[arg(long, value_delimiter = ',')]
+	pub(crate) tombstone_scans: Vec<String>,
+
+	/// Comma-separated points to run the datastore maintenance (VACUUM ANALYZE, OPTIMIZE TABLE, or a compaction) at, timing it and the scan throughput it buys
+	#[arg(long, value_delimiter = ',')]
+	pub(crate) maintenance: Vec<Maintenance>,
+
+	/// Number of records in the table during the scans, loaded beyond the samples and removed afterwards
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) scan_records: Option<u32>,
+
+	/// Grow the table through these comma-separated record counts, repeating the reads and table scans at each size
+	#[arg(long, value_delimiter = ',', value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) growth_sizes: Vec<u32>,
+
+	/// Walk the whole table in pages of this many records, with OFFSET and then keyset pagination
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) page_size: Option<u32>,
+
 	/// Generate the keys in a pseudo-randomized order
 	#[arg(short, long)]
 	pub(crate) random: bool,
 
+	/// Seed the generated values, so that runs with the same seed write byte-identical payloads
+	#[arg(long)]
+	pub(crate) seed: Option<u64>,
+
 	/// Whether to ensure data is synced and durable
 	#[arg(long, default_value = "false")]
 	pub(crate) sync: bool,
@@ -119,10 +273,34 @@ pub(crate) struct Args {
 	#[arg(long, env = "CRUD_BENCH_OPERATION_TIMEOUT", default_value = "1800", value_parser=clap::value_parser!(u64).range(1..))]
 	pub(crate) operation_timeout: u64,
 
+	/// Tighten each phase's operation timeout to this multiple of its warmup p99 latency (0 disables)
+	#[arg(long, env = "CRUD_BENCH_TIMEOUT_MULTIPLIER", default_value = "100")]
+	pub(crate) timeout_multiplier: u32,
+
+	/// Seconds to wait for the datastore to accept the first client connection
+	#[arg(long, env = "CRUD_BENCH_CONNECT_TIMEOUT", default_value = "60", value_parser=clap::value_parser!(u64).range(1..))]
+	pub(crate) connect_timeout: u64,
+
 	/// Whether to enable disk persistence for Redis-family databases
 	#[arg(long, default_value = "false")]
 	pub(crate) persisted: bool,
 
+	/// Pipeline the commands of concurrent threads, and send batches as one MSET/MGET/DEL, for Redis-family databases
+	#[arg(long, default_value = "false")]
+	pub(crate) pipelined: bool,
+
+	/// Prepare each CRUD statement again on every call, instead of once per client, for Postgres and MySQL-family databases
+	#[arg(long, default_value = "false")]
+	pub(crate) unprepared: bool,
+
+	/// Run scans on single integer field conditions with emulated secondary indexes, for embedded key-value databases
+	#[arg(long, default_value = "false")]
+	pub(crate) emulate_indexes: bool,
+
+	/// How much of each record returned by scans and batch reads to materialise on the client
+	#[arg(long, value_enum, default_value_t = Materialize::Full)]
+	pub(crate) materialize: Materialize,
+
 	/// Use optimised database configurations instead of defaults
 	#[arg(long, default_value = "false")]
 	pub(crate) optimised: bool,
@@ -151,8 +329,8 @@ pub(crate) struct Args {
 	#[arg(long, env = "CRUD_BENCH_STORAGE_ENDPOINT", default_value = "ws://localhost:8000")]
 	pub(crate) storage_endpoint: String,
 
-	/// Path to the benchmark configuration file
-	#[arg(long, env = "CRUD_BENCH_CONFIG", default_value = "config/bench.toml")]
+	/// Path to the benchmark configuration file (TOML, or YAML with a `.yaml` extension), whose `[run]` table sets default options
+	#[arg(long, env = "CRUD_BENCH_CONFIG", default_value = config::DEFAULT_CONFIG)]
 	pub(crate) config: String,
 
 	/// Skip all scan benchmarks
@@ -163,13 +341,186 @@ pub(crate) struct Args {
 	#[arg(long, default_value = "false")]
 	pub(crate) skip_batches: bool,
 
+	/// Skip the time-series benchmarks
+	#[arg(long, default_value = "false")]
+	pub(crate) skip_time_series: bool,
+
 	/// Skip index operations, but still table scan queries
 	#[arg(long, default_value = "false")]
 	pub(crate) skip_indexes: bool,
 
+	/// Also update a single field at this dotted path (e.g. `geography.code`), compared against full updates
+	#[arg(long)]
+	pub(crate) update_path: Option<String>,
+
+	/// Repeat the updates with a sync barrier after every Nth write, once per comma-separated interval
+	#[arg(long, value_delimiter = ',', value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) sync_intervals: Vec<u32>,
+
+	/// Repeat the read benchmark through a restricted role with row-level security enabled
+	#[arg(long, default_value = "false")]
+	pub(crate) restricted_reads: bool,
+
+	/// Read this many records by the `unique` field of the value template, without and then with an index on it
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) secondary_reads: Option<u32>,
+
+	/// Link every record to this many others, then read the records linked from each, through graph edges or joins
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) relations: Option<u32>,
+
+	/// Open this many live queries, then measure how long each is notified of a stream of writes after it
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) live_queries: Option<u32>,
+
+	/// Write to the primary while polling a replica, measuring how long each write takes to be visible on it
+	#[arg(long, default_value = "false")]
+	pub(crate) replication_lag: bool,
+
+	/// Back up the datastore with its native tool after the updates, then restore it into a fresh database, timing both
+	#[arg(long, default_value = "false")]
+	pub(crate) backup: bool,
+
+	/// Stream every sample's latency to this file as JSON Lines
+	#[arg(long)]
+	pub(crate) raw_latencies: Option<String>,
+
+	/// Write diagnostic logs, with database, phase, client, and thread context, to this file as JSON Lines
+	#[arg(long)]
+	pub(crate) log_file: Option<String>,
+
+	/// Sample this many reads with engine-reported execution times, splitting client and server latency
+	#[arg(long)]
+	pub(crate) server_timings: Option<u32>,
+
+	/// Hourly instance and storage price, to report the cost per million operations
+	#[arg(long)]
+	pub(crate) cost_per_hour: Option<f64>,
+
+	/// Repeat filtered scans with a predicate perturbed per sample, to detect result caching
+	#[arg(long, default_value_t = false)]
+	pub(crate) detect_scan_cache: bool,
+
+	/// Run the scans against a snapshot of the table, restored before the delete and batch phases
+	#[arg(long, default_value_t = false)]
+	pub(crate) isolate_scans: bool,
+
+	/// Repeat each scan, and its next page, to check the returned ids are stable and unique
+	#[arg(long, default_value_t = false)]
+	pub(crate) check_scan_consistency: bool,
+
+	/// Run this many updates alongside each scan index build, after the same updates on the idle table
+	#[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+	pub(crate) index_build_writes: Option<u32>,
+
+	/// Loop reads and updates for this long after the updates (e.g. `30m`, `6h`), flushing each window
+	#[arg(long, value_parser = soak::parse_duration)]
+	pub(crate) soak: Option<Duration>,
+
+	/// Minutes in each soak window, written to the soak results as it completes
+	#[arg(long, default_value = "10", value_parser=clap::value_parser!(u64).range(1..))]
+	pub(crate) soak_interval: u64,
+
+	/// Record the latency histogram of every client and thread in each phase, to diagnose imbalance
+	#[arg(long, default_value_t = false)]
+	pub(crate) worker_histograms: bool,
+
+	/// Read back every record after the creates and updates, failing on any value not as written
+	#[arg(long, default_value_t = false)]
+	pub(crate) verify: bool,
+
+	/// Run the measured phases open-loop, with samples arriving at random at this mean rate per second
+	#[arg(long)]
+	pub(crate) arrival_rate: Option<f64>,
+
+	/// Count each failed operation and carry on, rather than failing the phase at the first failure
+	#[arg(long, default_value_t = false)]
+	pub(crate) continue_on_error: bool,
+
+	/// Record the datastore container with `perf` during each phase, rendering one flamegraph per phase
+	#[arg(long, value_enum)]
+	pub(crate) profile_server: Option<ServerProfile>,
+
 	/// Emit debug phase markers for log-based tooling
 	#[arg(long, default_value_t = false)]
 	pub(crate) emit_phase_markers: bool,
+
+	/// Record the results of each completed phase to this file, removed once the results are written
+	#[arg(long)]
+	pub(crate) checkpoint: Option<String>,
+
+	/// Resume an interrupted run, restoring the phases completed in the `--checkpoint` file
+	#[arg(long, default_value_t = false, requires = "checkpoint")]
+	pub(crate) resume: bool,
+
+	/// The shard of the samples this agent runs, as `index/count`, set by `crud-bench coordinate`
+	#[arg(long, hide = true, value_parser = Shard::parse)]
+	pub(crate) shard: Option<Shard>,
+
+	/// The options which shape the workload, recorded in the results for `crud-bench rerun`
+	#[arg(skip)]
+	pub(crate) run_options: serde_json::Map<String, Value>,
+
+	/// Every option with the value it took, recorded in the results
+	#[arg(skip)]
+	pub(crate) effective_options: serde_json::Map<String, Value>,
+}
+
+impl Args {
+	/// The records bulk loaded beyond the samples: the `--preload`, or enough to fill the
+	/// `--keyspace` when it is larger.
+	pub(crate) fn preloaded(&self) -> u32 {
+		let keyspace = self.keyspace.map_or(0, |k| k.saturating_sub(self.samples));
+		self.preload.unwrap_or(0).max(keyspace)
+	}
+}
+
+/// The commands run in place of a benchmark.
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+	/// Diff two or more result files and flag throughput regressions
+	Compare(compare::CompareArgs),
+	/// Merge two or more result files into one comparative HTML report
+	Report(report::ReportArgs),
+	/// Re-render the latency percentiles of result files from their stored histograms
+	Histograms(histograms::HistogramsArgs),
+	/// Flatten result files into the rows of the published dashboard dataset
+	Export(export::ExportArgs),
+	/// Replay the workload recorded in a result file against another datastore
+	Rerun(rerun::RerunArgs),
+	/// Wait for a coordinator, and generate the load of its benchmark
+	Agent(agent::AgentArgs),
+	/// Run a benchmark with its load generated by several agents
+	Coordinate(agent::CoordinateArgs),
+	/// Run the same workload against every SurrealDB storage engine and protocol
+	Matrix(matrix::MatrixArgs),
+	/// Read back the runs stored in SurrealDB with `--store-results`
+	Results(results::ResultsArgs),
+}
+
+impl Command {
+	/// Runs the command, initialising logging for all but the replayed benchmark, which
+	/// initialises it from its own options.
+	fn run(self) -> Result<()> {
+		if !matches!(self, Self::Rerun(_)) {
+			logging::init(None)?;
+		}
+		match self {
+			Self::Compare(args) => compare::run(args),
+			Self::Report(args) => report::run(args),
+			Self::Histograms(args) => histograms::run(args),
+			Self::Export(args) => export::run(args),
+			Self::Rerun(args) => {
+				// Keep the written benchmark definition until the benchmark finishes
+				let (_definition, command_line) = args.command_line()?;
+				benchmark(command_line)
+			}
+			Self::Agent(args) => agent::run(args),
+			Self::Coordinate(args) => agent::coordinate(args),
+			Self::Matrix(args) => matrix::run(args),
+			Self::Results(args) => results::run(args),
+		}
+	}
 }
 
 /// Primary key shape and size for generated record ids.
@@ -185,8 +536,69 @@ pub(crate) enum KeyType {
 	String250,
 	/// 506 ascii bytes
 	String506,
-	/// UUID type 7
+	/// UUID version 7, ordered by its timestamp
 	Uuid,
+	/// UUID version 4, random
+	UuidV4,
+}
+
+/// A phase of the benchmark which can be chosen with `--phases`.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Phase {
+	/// The creates, whose records are otherwise loaded untimed
+	Create,
+	/// The reads
+	Read,
+	/// The updates, path updates, and synced updates
+	Update,
+	/// The scans defined in the benchmark TOML
+	Scan,
+	/// The deletes
+	Delete,
+	/// The batches defined in the benchmark TOML
+	Batch,
+}
+
+/// A bulk deletion measured with `--delete-strategies`, besides the single-record deletes.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DeleteStrategy {
+	/// Delete every record at once, such as with TRUNCATE or a RocksDB range tombstone
+	Truncate,
+	/// Delete the records in contiguous key ranges of `--delete-range-size`
+	Range,
+}
+
+/// A point between the phases to run the datastore maintenance at, with `--maintenance`.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Maintenance {
+	/// After the creates
+	#[value(name = "after-create")]
+	Create,
+	/// After the reads
+	#[value(name = "after-read")]
+	Read,
+	/// After the updates
+	#[value(name = "after-update")]
+	Update,
+	/// After the scans
+	#[value(name = "after-scan")]
+	Scan,
+	/// After the deletes
+	#[value(name = "after-delete")]
+	Delete,
+}
+
+impl Maintenance {
+	/// The phases the maintenance runs after, as listed in the results.
+	pub(crate) fn after(self) -> &'static str {
+		match self {
+			Self::Create => "creates",
+			Self::Read => "reads",
+			Self::Update => "updates",
+			Self::Scan => "scans",
+			Self::Delete => "deletes",
+		}
+	}
 }
 
 /// Expanded scan benchmarks ready to execute (one [`Scan`] per row after multi-run expansion).
@@ -200,8 +612,12 @@ pub(crate) type Batches = Vec<BatchOperation>;
 pub(crate) struct ScanRun {
 	/// Label for this run in results and CLI output.
 	name: String,
-	/// `ID`, `FULL`, or `COUNT`; overrides the parent [`ScanSpec`] `projection` when set.
+	/// `ID`, `FULL`, `COUNT`, `FIELD`, or `FIELDS`; overrides the parent [`ScanSpec`] `projection`.
 	projection: Option<String>,
+	/// Dotted path selected by the `FIELD` projection; overrides the parent [`ScanSpec`] `field`.
+	field: Option<String>,
+	/// Paths selected by the `FIELDS` projection; overrides the parent [`ScanSpec`] `fields`.
+	fields: Option<Vec<String>>,
 }
 
 /// Deserialized scan file entry: either a single [`Scan`] (`name`) or several (`runs`), never both.
@@ -215,6 +631,10 @@ pub(crate) struct ScanSpec {
 	runs: Option<Vec<ScanRun>>,
 	/// Overrides the global sample count for this scan when set.
 	samples: Option<usize>,
+	/// Runs this scan on the first N clients instead of all `--clients` when set.
+	clients: Option<u32>,
+	/// Runs this scan with N tasks per client instead of `--threads` when set.
+	threads: Option<u32>,
 	/// Per-dialect filter fragments (`WHERE`); omit for unrestricted/table scans.
 	condition: Option<Condition>,
 	/// Per-dialect `ORDER BY` fragments; omit for unordered scans.
@@ -225,13 +645,22 @@ pub(crate) struct ScanSpec {
 	limit: Option<usize>,
 	/// Expected row count for validation when set.
 	expect: Option<usize>,
-	/// `ID`, `FULL`, or `COUNT`; applies to single-run entries or as default for [`ScanRun`] rows.
+	/// `ID`, `FULL`, `COUNT`, `FIELD`, or `FIELDS`; applies to single-run entries or as default for [`ScanRun`] rows.
 	projection: Option<String>,
+	/// Dotted path (e.g. `geography.code`) selected by the `FIELD` projection.
+	field: Option<String>,
+	/// Dotted paths (e.g. `["text", "number"]`) selected by the `FIELDS` projection.
+	fields: Option<Vec<String>>,
 	/// Index definition for indexed-scan legs; omit for heap/table scans only.
 	with_index: Option<Index>,
+	/// Per-dialect index hints; adds a hinted leg next to the planner-chosen indexed leg.
+	hint: Option<IndexHint>,
 	/// Mixed read/write legs after each scan sample; omitted in config deserializes as empty (read-only).
 	#[serde(default)]
 	with_writes: Vec<ScanWithWrites>,
+	/// Skips the scan, keeping an empty row in the results, usually set through `overrides`.
+	#[serde(default)]
+	skip: bool,
 }
 
 impl ScanSpec {
@@ -242,19 +671,39 @@ impl ScanSpec {
 			name,
 			runs,
 			samples,
+			clients,
+			threads,
 			condition,
 			order_by,
 			start,
 			limit,
 			expect,
 			projection,
+			field,
+			fields,
 			with_index,
+			hint,
 			with_writes,
+			skip,
 		} = self;
 
 		if id.trim().is_empty() {
 			bail!("each scan entry must have a non-empty `id`");
 		}
+		if clients == Some(0) || threads == Some(0) {
+			bail!("scan `{id}` must run with at least one client and one thread");
+		}
+		if hint.is_some() && with_index.is_none() {
+			bail!("scan `{id}` specifies a `hint` but no `with_index` for it to force");
+		}
+		if let Some(index) = with_index.as_ref().filter(|i| i.separate) {
+			if index.index_type.is_some() {
+				bail!("scan `{id}` cannot build `separate` indexes with an `index_type`");
+			}
+			if hint.is_some() {
+				bail!("scan `{id}` cannot `hint` at `separate` indexes, as a hint names one index");
+			}
+		}
 
 		match (name, runs) {
 			(Some(_), Some(_)) => {
@@ -276,39 +725,55 @@ impl ScanSpec {
 					multi_run_spec: false,
 					name: n,
 					samples,
+					clients,
+					threads,
 					condition,
 					order_by,
 					start,
 					limit,
 					expect,
 					projection,
+					field,
+					fields,
 					with_index,
+					hint,
 					with_writes,
+					skip,
 				}])
 			}
 			(None, Some(runs)) => {
 				let multi_run_spec = runs.len() > 1;
 				let default_projection = projection.clone();
+				let default_field = field.clone();
+				let default_fields = fields.clone();
 				let mut out = Vec::with_capacity(runs.len());
 				for run in runs {
 					if run.name.is_empty() {
 						bail!("each entry in `runs` must have a non-empty `name`");
 					}
 					let run_projection = run.projection.or_else(|| default_projection.clone());
+					let run_field = run.field.or_else(|| default_field.clone());
+					let run_fields = run.fields.or_else(|| default_fields.clone());
 					out.push(Scan {
 						id: id.clone(),
 						spec_group,
 						multi_run_spec,
 						name: run.name,
 						samples,
+						clients,
+						threads,
 						condition: condition.clone(),
 						order_by: order_by.clone(),
 						start,
 						limit,
 						expect,
 						projection: run_projection,
+						field: run_field,
+						fields: run_fields,
 						with_index: with_index.clone(),
+						hint: hint.clone(),
 						with_writes: with_writes.clone(),
+						skip,
 					});
 				}
 				Ok(out)
@@ -326,6 +791,26 @@ fn expand_scan_specs(specs: Vec<ScanSpec>) -> Result<Scans> {
 	Ok(scans)
 }
 
+/// The first `n` of the connected clients when a phase overrides its clients, or all of them.
+fn first_clients<T>(clients: &[T], n: Option<u32>) -> &[T] {
+	match n {
+		Some(n) => &clients[..clients.len().min(n as usize)],
+		None => clients,
+	}
+}
+
+/// Scan `clients` overrides select from the connected clients, so cannot exceed `--clients`.
+fn validate_scan_concurrency(scans: &[Scan], clients: u32) -> Result<()> {
+	for scan in scans {
+		if let Some(n) = scan.clients
+			&& n > clients
+		{
+			bail!("scan `{}` runs with {n} clients, but only {clients} are connected", scan.id);
+		}
+	}
+	Ok(())
+}
+
 /// Every scan with a non-skipped `with_index` must supply a non-empty `id` for datastore index names.
 fn validate_scan_index_ids(scans: &[Scan]) -> Result<()> {
 	for scan in scans {
@@ -348,8 +833,41 @@ pub(crate) struct Index {
 	pub(crate) fields: Vec<String>,
 	/// Whether the index enforces uniqueness when supported by the backend.
 	pub(crate) unique: Option<bool>,
-	/// Backend-specific hint, e.g. `"fulltext"`.
+	/// Backend-specific hint, e.g. `"fulltext"`, or the `"gin"`, `"gist"`, `"brin"`, or `"hash"` access method.
 	pub(crate) index_type: Option<String>,
+	/// When true, build one single-field index per entry in `fields` instead of a composite index.
+	#[serde(default)]
+	pub(crate) separate: bool,
+	/// Per-dialect predicate restricting the index to matching records (a partial index).
+	#[serde(default, rename = "where")]
+	pub(crate) filter: Option<Condition>,
+}
+
+impl Index {
+	/// The Postgres access method named by the index type, for GIN, GiST, BRIN, and hash indexes.
+	pub(crate) fn method(&self) -> Option<&str> {
+		self.index_type.as_deref().filter(|t| matches!(*t, "gin" | "gist" | "brin" | "hash"))
+	}
+
+	/// The physical indexes this spec creates under `name`, as `(spec, name)` pairs.
+	pub(crate) fn physical(&self, name: &str) -> Vec<(Index, String)> {
+		match self.separate {
+			false => vec![(self.clone(), name.to_string())],
+			true => self
+				.fields
+				.iter()
+				.enumerate()
+				.map(|(i, field)| {
+					let spec = Index {
+						fields: vec![field.clone()],
+						separate: false,
+						..self.clone()
+					};
+					(spec, format!("{name}_{i}"))
+				})
+				.collect(),
+		}
+	}
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -367,6 +885,12 @@ pub(crate) struct Scan {
 	name: String,
 	/// Sample count for this scan; falls back to CLI `--samples` when unset.
 	samples: Option<usize>,
+	/// Clients running this scan; falls back to all CLI `--clients` when unset.
+	#[serde(default)]
+	pub(crate) clients: Option<u32>,
+	/// Tasks per client running this scan; falls back to CLI `--threads` when unset.
+	#[serde(default)]
+	pub(crate) threads: Option<u32>,
 	/// Filter predicates per dialect; omit for full scans.
 	condition: Option<Condition>,
 	/// Optional `ORDER BY` per datastore (omit for unordered scans).
@@ -378,13 +902,23 @@ pub(crate) struct Scan {
 	limit: Option<usize>,
 	/// Asserted cardinality when set.
 	expect: Option<usize>,
-	/// Result shape: `ID`, `FULL`, or `COUNT`.
+	/// Result shape: `ID`, `FULL`, `COUNT`, `FIELD`, or `FIELDS`.
 	projection: Option<String>,
+	/// Dotted path selected when the projection is `FIELD`.
+	field: Option<String>,
+	/// Dotted paths selected when the projection is `FIELDS`.
+	fields: Option<Vec<String>>,
 	/// Optional index specification for indexed scan legs (`skip`, `fields`, etc.).
 	with_index: Option<Index>,
+	/// Optional per-dialect index hints for a forced-plan leg after the indexed leg.
+	#[serde(default)]
+	hint: Option<IndexHint>,
 	/// Read+write workloads (ratio / mode / operation); omit or use `[]` for read-only scans.
 	#[serde(default)]
 	pub(crate) with_writes: Vec<ScanWithWrites>,
+	/// Whether the benchmark TOML skips this scan for the database under test.
+	#[serde(default)]
+	pub(crate) skip: bool,
 }
 
 /// Mixed read/write scan leg: scan samples plus paired updates that touch indexed columns while
@@ -438,16 +972,63 @@ impl Scan {
 		Ok(id)
 	}
 
+	/// The clients running this scan: the first `clients` of them when overridden, or all of them.
+	pub(crate) fn select_clients<'a, T>(&self, clients: &'a [T]) -> &'a [T] {
+		first_clients(clients, self.clients)
+	}
+
 	/// Returns the scan projection type for adapter code.
 	fn projection(&self) -> Result<Projection> {
 		match self.projection.as_deref() {
 			Some("ID") => Ok(Projection::Id),
 			Some("FULL") => Ok(Projection::Full),
 			Some("COUNT") => Ok(Projection::Count),
+			Some("FIELD") => match self.field.as_deref() {
+				Some(f) if !f.is_empty() => Ok(Projection::Field(f.to_string())),
+				_ => bail!("The FIELD projection requires a non-empty `field` path"),
+			},
+			Some("FIELDS") => match self.fields.as_deref() {
+				Some(f) if !f.is_empty() && f.iter().all(|p| !p.is_empty()) => {
+					Ok(Projection::Fields(f.to_vec()))
+				}
+				_ => bail!("The FIELDS projection requires a non-empty list of `fields` paths"),
+			},
 			Some(o) => bail!(format!("Unsupported projection: {}", o)),
 			_ => Ok(Projection::Full),
 		}
 	}
+
+	/// Whether the scan has a predicate which [`Scan::perturbed`] can vary.
+	pub(crate) fn has_condition(&self) -> bool {
+		self.condition.is_some()
+	}
+
+	/// Copy of this scan whose predicate differs for every `nonce` but matches the same rows,
+	/// so engines cannot answer it from a result cache keyed on the query text.
+	pub(crate) fn perturbed(&self, nonce: u32) -> Scan {
+		let fulltext = self.with_index.as_ref().and_then(|i| i.index_type.as_deref());
+		let mut scan = self.clone();
+		if let Some(condition) = scan.condition.as_mut() {
+			condition.perturb(nonce, fulltext == Some("fulltext"));
+		}
+		scan
+	}
+
+	/// Copy of this scan without its asserted cardinality, for a table holding other records.
+	pub(crate) fn unchecked(&self) -> Scan {
+		Scan {
+			expect: None,
+			..self.clone()
+		}
+	}
+
+	/// Copy of this scan returning the page which follows it, when the scan has a limit.
+	pub(crate) fn next_page(&self) -> Option<Scan> {
+		let limit = self.limit?;
+		let mut scan = self.clone();
+		scan.start = Some(self.start.unwrap_or(0) + limit);
+		Some(scan)
+	}
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -460,6 +1041,10 @@ pub(crate) enum Projection {
 	Full,
 	/// `COUNT` aggregate only.
 	Count,
+	/// A single (possibly nested) field, addressed by a dotted path.
+	Field(String),
+	/// A subset of (possibly nested) fields, each addressed by a dotted path.
+	Fields(Vec<String>),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -481,6 +1066,44 @@ pub(crate) struct Condition {
 	arangodb: Option<String>,
 	/// SurrealQL predicate fragment.
 	surrealdb: Option<String>,
+	/// OpenSearch query DSL clause (`serde_json::Value`).
+	opensearch: Option<Value>,
+}
+
+impl Condition {
+	/// ANDs a constant tautology containing `nonce` onto every dialect's predicate.
+	fn perturb(&mut self, nonce: u32, fulltext: bool) {
+		let tautology = |condition: &mut Option<String>, eq: &str| {
+			if let Some(c) = condition {
+				*c = format!("({c}) AND {nonce} {eq} {nonce}");
+			}
+		};
+		for condition in [
+			&mut self.sql,
+			&mut self.postgres,
+			&mut self.sqlite,
+			&mut self.mysql,
+			&mut self.surrealdb,
+		] {
+			tautology(condition, "=");
+		}
+		tautology(&mut self.arangodb, "==");
+		// A Neo4j full-text condition is a procedure call, not a predicate
+		match fulltext {
+			true => self.neo4j = None,
+			false => tautology(&mut self.neo4j, "="),
+		}
+		if let Some(filter) = self.mongodb.take() {
+			let tautology = serde_json::json!({ "$expr": { "$eq": [nonce, nonce] } });
+			self.mongodb = Some(serde_json::json!({ "$and": [filter, tautology] }));
+		}
+		if let Some(query) = self.opensearch.take() {
+			let tautology =
+				serde_json::json!({ "ids": { "values": [format!("perturbed-{nonce}")] } });
+			self.opensearch =
+				Some(serde_json::json!({ "bool": { "must": [query], "must_not": [tautology] } }));
+		}
+	}
 }
 
 /// Per-dialect `ORDER BY` fragments for scans (same shape as [`Condition`]).
@@ -500,6 +1123,23 @@ pub(crate) struct OrderByClause {
 	pub(crate) surrealdb: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Per-dialect index hints forcing the `with_index` index (same shape as [`Condition`]).
+pub(crate) struct IndexHint {
+	/// `pg_hint_plan` hint text, sent as a leading `/*+ ... */` comment.
+	pub(crate) postgres: Option<String>,
+	/// SQLite `INDEXED BY` clause.
+	pub(crate) sqlite: Option<String>,
+	/// MySQL / MariaDB index hint, e.g. `FORCE INDEX (name)`.
+	pub(crate) mysql: Option<String>,
+	/// MongoDB hint: an index name string or a key pattern document.
+	pub(crate) mongodb: Option<Value>,
+	/// SurrealQL `WITH INDEX` clause.
+	pub(crate) surrealdb: Option<String>,
+	/// ArangoDB `FOR` loop options, e.g. `{ indexHint: "name", forceIndexHint: true }`.
+	pub(crate) arangodb: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 /// One batch throughput case (create/read/update/delete many rows per iteration).
 pub(crate) struct BatchOperation {
@@ -511,6 +1151,21 @@ pub(crate) struct BatchOperation {
 	pub(crate) batch_size: usize,
 	/// Timed iterations for this batch case; backend may default when unset.
 	pub(crate) samples: Option<usize>,
+	/// Runs this batch case on the first N clients instead of all `--clients` when set.
+	#[serde(default)]
+	pub(crate) clients: Option<u32>,
+	/// Runs this batch case with N tasks per client instead of `--threads` when set.
+	#[serde(default)]
+	pub(crate) threads: Option<u32>,
+	/// Caps the batches per second across all tasks when set, pacing them evenly.
+	#[serde(default)]
+	pub(crate) rate: Option<f64>,
+	/// Creates through the datastore's bulk load path (`COPY`, `LOAD DATA`) instead of an insert.
+	#[serde(default)]
+	pub(crate) load: bool,
+	/// Whether the benchmark TOML skips this batch case for the database under test.
+	#[serde(default)]
+	pub(crate) skip: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -525,16 +1180,99 @@ pub(crate) enum BatchOperationType {
 	Update,
 	/// Delete many.
 	Delete,
+	/// Insert many, with keys generated by the datastore.
+	#[serde(rename = "CREATE_GENERATED")]
+	CreateGenerated,
+	/// Read many by the keys returned from `CREATE_GENERATED` batches.
+	#[serde(rename = "READ_GENERATED")]
+	ReadGenerated,
+}
+
+impl BatchOperation {
+	/// The clients running this batch case: the first `clients` of them when overridden, or all.
+	pub(crate) fn select_clients<'a, T>(&self, clients: &'a [T]) -> &'a [T] {
+		first_clients(clients, self.clients)
+	}
+
+	/// Checks the concurrency, rate, and bulk load overrides against the `clients` connected.
+	fn validate(&self, clients: u32) -> Result<()> {
+		let name = &self.name;
+		if self.clients == Some(0) || self.threads == Some(0) {
+			bail!("batch `{name}` must run with at least one client and one thread");
+		}
+		if let Some(n) = self.clients
+			&& n > clients
+		{
+			bail!("batch `{name}` runs with {n} clients, but only {clients} are connected");
+		}
+		if let Some(rate) = self.rate
+			&& !(rate.is_finite() && rate > 0.0)
+		{
+			bail!("batch `{name}` must have a positive `rate`, not {rate}");
+		}
+		if self.load && self.operation != BatchOperationType::Create {
+			bail!("batch `{name}` can only bulk load with the `CREATE` operation");
+		}
+		Ok(())
+	}
+}
+
+impl BatchOperationType {
+	/// Whether the batch uses keys generated by the datastore.
+	pub(crate) fn is_generated(&self) -> bool {
+		matches!(self, Self::CreateGenerated | Self::ReadGenerated)
+	}
 }
 
-/// CLI entry: init logging, parse [`Args`], dispatch to [`run`].
+/// CLI entry: parse [`Args`], and run the benchmark with [`run`] or the [`Command`] given instead.
 fn main() -> Result<()> {
+	// Parse the command line arguments, after the options of the benchmark definition
+	benchmark(config::command_line()?)
+}
+
+/// Parses the benchmark command line, initialises logging, and runs the benchmark, or the command
+/// given instead.
+fn benchmark(command_line: Vec<OsString>) -> Result<()> {
+	let matches = Args::command().get_matches_from(command_line);
+	// Process existing result files instead of running a benchmark
+	if matches.subcommand().is_some() {
+		let command = Command::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+		return command.run();
+	}
+	let args = parse_args(&matches);
 	// Initialise the logger
-	env_logger::init();
-	// Parse the command line arguments
-	let args = Args::parse();
+	logging::init(args.log_file.as_deref())?;
 	// Run the benchmark
-	run(args)
+	let Some(ramp) = args.ramp.clone() else {
+		return run(args);
+	};
+	// Repeat the benchmark at each step of the ramp, then chart the steps together
+	let mut files = Vec::with_capacity(ramp.clients.len());
+	for (step, &clients) in ramp.clients.iter().enumerate() {
+		let mut step_args = parse_args(&matches);
+		let name = ramp.step_name(args.name.as_deref(), clients);
+		println!("Running {name} ({} of {})", step + 1, ramp.clients.len());
+		// Each step is recorded as the run it would be without the ramp
+		for options in [&mut step_args.run_options, &mut step_args.effective_options] {
+			options.remove("ramp");
+			options.insert("clients".to_string(), Value::String(clients.to_string()));
+		}
+		step_args.effective_options.insert("name".to_string(), Value::String(name.clone()));
+		step_args.clients = clients;
+		step_args.name = Some(name.clone());
+		step_args.ramp = None;
+		run(step_args)?;
+		files.push(format!("result-{name}.json"));
+	}
+	ramp::report(&ramp.clients, &files, &ramp.report_path(args.name.as_deref()))
+}
+
+/// Parses the benchmark options, recording the options which were given for the results.
+fn parse_args(matches: &ArgMatches) -> Args {
+	let mut args = Args::from_arg_matches(matches).unwrap_or_else(|e| e.exit());
+	args.run_options = rerun::run_options(matches);
+	args.effective_options = rerun::effective_options(matches);
+	args
 }
 
 /// Runs the full benchmark lifecycle: Docker (if needed), runtime setup, workload load, datastore run, artifacts.
@@ -543,16 +1281,77 @@ fn run(args: Args) -> Result<()> {
 	if std::env::var("PROFILE").is_ok() {
 		profiling::initialise();
 	}
+	// Check the container limits can be given to Docker
+	if let Some(cpus) = args.container_cpus
+		&& (!cpus.is_finite() || cpus <= 0.0)
+	{
+		bail!("The container CPUs ({cpus}) must be greater than zero");
+	}
+	// Check the open-loop arrivals can be scheduled
+	if let Some(rate) = args.arrival_rate
+		&& (!rate.is_finite() || rate <= 0.0)
+	{
+		bail!("The arrival rate ({rate}) must be greater than zero");
+	}
+	// Check the benchmark and the containers are isolated onto different cores
+	if let (Some(client), Some(container)) = (&args.pin_cores, &args.container_cores)
+		&& client.overlaps(container)
+	{
+		bail!("The pinned cores ({client}) overlap the container cores ({container})");
+	}
+	// Set how much of each scanned record the clients materialise
+	materialize::set(args.materialize);
 	// Prepare the benchmark
-	let mut benchmark = Benchmark::new(&args);
+	let mut benchmark = Benchmark::new(&args)?;
 	// Check if we should spawn a Docker container
 	let container = if args.database.wants_docker(&args.endpoint) {
+		// Size the server caches to the container rather than the host
+		if let Some(memory) = args.container_memory {
+			memory::limit(memory.as_u64());
+		}
 		// Start the Docker container
-		args.database.start_docker(&benchmark)
+		args.database.start_docker(&benchmark)?
+	} else if let Some(topology) = &args.topology {
+		// A topology is only started by crud-bench, not connected to
+		bail!("The `{topology}` topology cannot be used with a custom endpoint");
 	} else {
 		// No Docker container needed
 		None
 	};
+	// A container started by crud-bench is removed with its data when the run ends
+	if (args.no_setup || args.keep_data) && container.is_some() {
+		bail!(
+			"The `--no-setup` and `--keep-data` options need a datastore which outlives the run, such as an embedded datastore or a custom endpoint"
+		);
+	}
+	// The network is only emulated for the containers crud-bench starts
+	if args.netem.is_some() && container.is_none() {
+		bail!("The `--netem` rules can only be applied to a container started by crud-bench");
+	}
+	// Measure the size on disk of the container started by crud-bench
+	benchmark.containerised = container.is_some();
+	// Attach perf to the container started by crud-bench
+	if let Some(profile) = args.profile_server {
+		benchmark.server_profiler = Some(ServerProfiler::attach(profile, container.as_ref())?);
+	}
+	// Monitor every process of the containers, unless a process was given
+	if args.pid.is_none()
+		&& let Some(container) = &container
+	{
+		benchmark.cgroups = container.cgroups();
+		if benchmark.cgroups.is_empty() {
+			tracing::warn!("The container cgroups could not be read, so crud-bench is monitored");
+		}
+	}
+	// Pin the benchmark to its cores, which every runtime and threadpool thread inherits
+	if let Some(cores) = &args.pin_cores {
+		affinity::pin(cores)?;
+	}
+	// Pin the benchmark to a NUMA node, so its threads and memory stay on one socket
+	if let Some(node) = args.numa_node {
+		affinity::pin(&CoreSet::numa_node(node)?)?;
+		affinity::bind_memory(node)?;
+	}
 	// Setup the asynchronous runtime
 	let runtime = runtime::Builder::new_multi_thread()
 		.thread_stack_size(2 * 1024 * 1024) // Set stack size to 5MiB
@@ -567,7 +1366,7 @@ fn run(args: Args) -> Result<()> {
 		.thread_stack_size(5 * 1024 * 1024) // Set stack size to 5MiB
 		.worker_threads(args.blocking as usize) // Set the number of worker threads
 		.thread_name("crud-bench-threadpool") // Set the name of the threadpool threads
-		.thread_per_core(true) // Try to set a thread per core
+		.thread_per_core(args.pin_cores.is_none() && args.numa_node.is_none()) // Try to set a thread per core, unless pinned
 		.build()
 		.build_global();
 	// Display formatting
@@ -585,20 +1384,121 @@ fn run(args: Args) -> Result<()> {
 		random: args.random,
 		sync: args.sync,
 		persisted: args.persisted,
+		pipelined: args.pipelined,
+		prepared: !args.unprepared,
+		emulate_indexes: args.emulate_indexes,
+		materialize: format!("{:?}", args.materialize),
 		optimised: args.optimised,
+		cost_per_hour: args.cost_per_hour,
+		preload: Some(args.preloaded()).filter(|&p| p > 0),
+		scan_records: args.scan_records,
+		seed: args.seed,
+		topology: args.topology.clone(),
+		netem: args.netem.clone(),
+		server_options: match container.is_some() {
+			true => args.database.server_options(&benchmark),
+			false => Default::default(),
+		},
+		// The server version is detected once the clients connect
+		version: None,
+		image: container.as_ref().map(|c| c.image().to_string()),
+		image_digest: container.as_ref().and_then(Container::digest),
 	};
+	// Record the completed phases, restoring those of an interrupted run
+	if let Some(path) = &args.checkpoint {
+		benchmark.checkpoint = Some(Checkpoint::open(path, args.resume, &metadata)?);
+	}
 	// Get database display name
 	let name = args.database.name().to_string();
 	// Build the key provider
 	let kp = KeyProvider::new(args.key, args.random);
-	let bench_toml = load_bench_toml(&args.config)?;
+	// Apply the spec overrides keyed by the `--database` value
+	let database = args.database.to_possible_value().map(|v| v.get_name().to_string());
+	let bench_toml = load_bench_toml(&args.config, args.samples, &database.unwrap_or_default())?;
 	let value_json = serde_json::to_string(&bench_toml.value)?;
-	let vp = ValueProvider::new(&value_json)?;
+	let mut vp = ValueProvider::new(&value_json)?;
+	// Check there are stored values to read back
+	if args.verify && matches!(args.database, Database::Dry) {
+		bail!("The dry database does not store the values which `--verify` reads back");
+	}
+	// Derive each record's value from a seed, so it can be generated again
+	let seed = args.seed.or_else(|| args.verify.then(rand::random));
+	if let Some(seed) = seed {
+		vp = vp.seeded(seed);
+	}
+	// Check the partial update path exists in the value template
+	if let Some(path) = &args.update_path
+		&& vp.clone().generate_value().get_path(path).is_none()
+	{
+		bail!("The update path `{path}` does not exist in the value template");
+	}
+	// Check there is a unique field to read the records by
+	if args.secondary_reads.is_some() && vp.unique_field().is_none() {
+		bail!("The secondary reads need a top-level `unique` field in the value template");
+	}
+	// Check every record can be linked to distinct records other than itself
+	if let Some(fanout) = args.relations
+		&& fanout >= args.samples
+	{
+		bail!("The relations must link each record to fewer than {} records", args.samples);
+	}
+	// The records in the table after the creates, including those preloaded
+	let Some(records) = args.samples.checked_add(args.preloaded()) else {
+		bail!("The samples and preloaded records must not exceed {} records", u32::MAX);
+	};
+	// Check the scan dataset holds at least the created samples
+	if let Some(scan_records) = args.scan_records
+		&& scan_records < records
+	{
+		bail!("The scan records ({scan_records}) must not be fewer than the records ({records})");
+	}
+	// Check the dataset only grows from one stage to the next
+	let mut previous = records;
+	for &size in &args.growth_sizes {
+		if size <= previous {
+			bail!("The growth sizes must increase from the records ({records}), found {size}");
+		}
+		previous = size;
+	}
+	// Check the walk over the table has more than one page
+	if let Some(size) = args.page_size
+		&& size >= args.samples
+	{
+		bail!("The page size ({size}) must be smaller than the samples ({})", args.samples);
+	}
 	let mut batches = bench_toml.batches;
 	if args.skip_batches {
 		batches.clear();
 	}
+	for batch in &batches {
+		batch.validate(args.clients)?;
+	}
+	let time_series = bench_toml.time_series.filter(|_| !args.skip_time_series);
+	let assertions = bench_toml.assertions;
+	bench_toml.retries.validate()?;
+	benchmark.retries = bench_toml.retries.clone();
+	// The reads and updates may run more or fewer samples than there are records
+	let phase_samples = bench_toml.samples;
+	if phase_samples.read == Some(0) || phase_samples.update == Some(0) {
+		bail!("The samples of the reads and updates must be greater than zero");
+	}
+	// The updates are only checked when every created record is updated
+	let keyspace = args.keyspace.unwrap_or(args.samples);
+	let updates = phase_samples.update.unwrap_or(args.samples);
+	if args.verify && (keyspace < args.samples || updates < keyspace) {
+		bail!(
+			"The updates ({updates}) must cover the keyspace ({keyspace}), and the keyspace the samples ({}), with `--verify`",
+			args.samples
+		);
+	}
+	benchmark.read_samples = phase_samples.read.unwrap_or(args.samples);
+	benchmark.update_samples = phase_samples.update.unwrap_or(args.samples);
 	let mut scans: Scans = expand_scan_specs(bench_toml.scans)?;
+	// Record the workload, so it can be replayed against another datastore
+	let workload = rerun::Workload {
+		run: args.run_options.clone(),
+		definition: bench_toml.definition,
+	};
 	if args.skip_scans {
 		scans.clear();
 	} else {
@@ -610,23 +1510,53 @@ fn run(args: Args) -> Result<()> {
 			}
 		}
 		validate_scan_index_ids(&scans)?;
+		validate_scan_concurrency(&scans, args.clients)?;
+	}
+	// Record the configuration the run resolved to, so the results are reproducible on their own
+	let config = config::EffectiveConfig {
+		options: args.effective_options.clone(),
+		key: config::KeySettings {
+			key_type: format!("{:?}", args.key),
+			random: args.random,
+			seed,
+		},
+		value: bench_toml.value,
+		scans: scans.clone(),
+		batches: batches.clone(),
+		time_series: time_series.clone(),
+		retries: bench_toml.retries,
+		samples: phase_samples,
+	};
+	// Run the benchmark, tagging every log event with the database. The
+	// spans use the error level, so that errors keep their context when
+	// the terminal only shows errors.
+	let span = tracing::error_span!("benchmark", database = %name);
+	let res = runtime.block_on(
+		async {
+			args.database
+				.run(
+					&mut benchmark,
+					args.key,
+					kp,
+					vp,
+					scans,
+					batches,
+					time_series,
+					Some(name.clone()),
+					Some(system),
+					Some(metadata),
+				)
+				.await
+		}
+		.instrument(span),
+	);
+	// Flush the raw latency stream, even when the benchmark failed
+	if let (Some(latencies), Some(path)) = (benchmark.raw_latencies.take(), &args.raw_latencies) {
+		match latencies.finish() {
+			Ok(_) => println!("⏱️ Raw latencies saved to: {path}"),
+			Err(e) => eprintln!("⚠️ Failed to write raw latencies: {e:#}"),
+		}
 	}
-	// Run the benchmark
-	let res = runtime.block_on(async {
-		args.database
-			.run(
-				&mut benchmark,
-				args.key,
-				kp,
-				vp,
-				scans,
-				batches,
-				Some(name.clone()),
-				Some(system),
-				Some(metadata),
-			)
-			.await
-	});
 	// Check if we should profile
 	if std::env::var("PROFILE").is_ok() {
 		profiling::process();
@@ -634,7 +1564,16 @@ fn run(args: Args) -> Result<()> {
 	// Output the results
 	match res {
 		// Output the results
-		Ok(res) => {
+		Ok(mut res) => {
+			// Normalise throughput against the resources given to the server
+			res.resources =
+				Some(result::ServerResources::new(container.as_ref().and_then(Container::limits)));
+			res.workload = Some(workload);
+			res.config = Some(config);
+			res.flamegraphs = profiling::flamegraphs();
+			if let Some(profiler) = &benchmark.server_profiler {
+				res.server_flamegraphs = profiler.flamegraphs();
+			}
 			println!("--------------------------------------------------");
 			match container.as_ref().map(Container::image) {
 				Some(v) => {
@@ -659,9 +1598,16 @@ fn run(args: Args) -> Result<()> {
 				args.key,
 				args.random,
 			);
+			if let Some(version) = res.metadata.as_ref().and_then(|m| m.version.as_ref()) {
+				println!("Version: {version}");
+			}
 			println!("--------------------------------------------------");
 			println!("{res}");
 			println!("--------------------------------------------------");
+			let failed = assertions::check(&assertions, &res);
+			if !assertions.is_empty() {
+				println!("--------------------------------------------------");
+			}
 			if args.show_sample {
 				println!("Value sample: {:#}", res.sample.to_json());
 				println!("--------------------------------------------------");
@@ -696,6 +1642,11 @@ fn run(args: Args) -> Result<()> {
 			res.to_html_charts(&result_html_name, &name)?;
 			println!("📊 Interactive charts saved to: {}", result_html_name);
 
+			// The merged results are written, so the run no longer needs resuming
+			if let Some(checkpoint) = &benchmark.checkpoint {
+				checkpoint.remove()?;
+			}
+
 			// Store results in SurrealDB if requested
 			if args.store_results {
 				match runtime.block_on(async {
@@ -709,6 +1660,11 @@ fn run(args: Args) -> Result<()> {
 				}
 			}
 
+			// Fail the run once its results are saved, so CI can gate on the assertions
+			if failed > 0 {
+				bail!("{failed} of {} assertions failed", assertions.len());
+			}
+
 			Ok(())
 		}
 		// Output the errors
@@ -736,29 +1692,64 @@ fn run(args: Args) -> Result<()> {
 #[cfg(test)]
 /// Unit and integration-style tests for scan expansion and CLI wiring.
 mod test {
+	use crate::materialize::Materialize;
 	use crate::terminal::ColorChoice;
 	use crate::{Args, Database, KeyType, run};
 	use anyhow::Result;
+	use clap::Parser;
 	use serial_test::serial;
 
 	fn test(database: Database, key: KeyType, random: bool) -> Result<()> {
 		run(Args {
+			command: None,
 			image: None,
 			name: None,
 			database,
 			privileged: false,
+			docker_platform: None,
+			container_cpus: None,
+			container_memory: None,
+			container_cores: None,
+			pin_cores: None,
+			numa_node: None,
 			endpoint: None,
+			username: None,
+			password: None,
+			tls_ca: None,
+			tls_insecure: false,
+			topology: None,
+			netem: None,
 			blocking: 5,
 			workers: 5,
 			clients: 2,
+			ramp: None,
 			threads: 2,
 			samples: 10000,
+			preload: None,
+			keyspace: None,
+			no_setup: false,
+			keep_data: false,
+			phases: vec![],
+			delete_strategies: vec![],
+			delete_range_size: 1000,
+			tombstone_scans: vec![],
+			maintenance: vec![],
+			scan_records: None,
+			growth_sizes: vec![],
+			page_size: None,
 			sync: false,
 			operation_timeout: 300,
+			timeout_multiplier: 100,
+			connect_timeout: 60,
 			persisted: false,
+			pipelined: false,
+			unprepared: false,
+			emulate_indexes: false,
+			materialize: Materialize::Full,
 			optimised: false,
 			color: ColorChoice::Never,
 			random,
+			seed: None,
 			key,
 			config: format!("{}/config/test.toml", env!("CARGO_MANIFEST_DIR")),
 			show_sample: false,
@@ -767,8 +1758,37 @@ mod test {
 			storage_endpoint: "ws://localhost:8000".to_string(),
 			skip_scans: false,
 			skip_batches: false,
+			skip_time_series: false,
 			skip_indexes: false,
+			update_path: None,
+			sync_intervals: vec![],
+			restricted_reads: false,
+			secondary_reads: None,
+			relations: None,
+			live_queries: None,
+			replication_lag: false,
+			backup: false,
+			raw_latencies: None,
+			log_file: None,
+			server_timings: None,
+			cost_per_hour: None,
+			detect_scan_cache: false,
+			isolate_scans: false,
+			check_scan_consistency: false,
+			index_build_writes: None,
+			soak: None,
+			soak_interval: 10,
+			worker_histograms: false,
+			verify: false,
+			arrival_rate: None,
+			continue_on_error: false,
+			profile_server: None,
 			emit_phase_markers: false,
+			checkpoint: None,
+			resume: false,
+			shard: None,
+			run_options: Default::default(),
+			effective_options: Default::default(),
 		})
 	}
 
@@ -844,6 +1864,39 @@ mod test {
 		test(Database::Dry, KeyType::String506, true)
 	}
 
+	#[test]
+	#[serial]
+	fn test_uuid_ordered() -> Result<()> {
+		test(Database::Map, KeyType::Uuid, false)
+	}
+
+	#[test]
+	#[serial]
+	fn test_uuid_unordered() -> Result<()> {
+		test(Database::Map, KeyType::Uuid, true)
+	}
+
+	#[test]
+	#[serial]
+	fn test_uuid_v4() -> Result<()> {
+		test(Database::Map, KeyType::UuidV4, false)
+	}
+
+	#[test]
+	fn keyspace_preloads_the_records_beyond_the_samples() {
+		let args = |extra: &[&str]| {
+			let argv = ["crud-bench", "-d", "map", "-s", "100"].iter().chain(extra);
+			Args::try_parse_from(argv).unwrap()
+		};
+		assert_eq!(args(&[]).preloaded(), 0);
+		assert_eq!(args(&["--keyspace", "100"]).preloaded(), 0);
+		assert_eq!(args(&["--keyspace", "30"]).preloaded(), 0);
+		assert_eq!(args(&["--keyspace", "1000"]).preloaded(), 900);
+		assert_eq!(args(&["--keyspace", "1000", "--preload", "10"]).preloaded(), 900);
+		assert_eq!(args(&["--keyspace", "1000", "--preload", "2000"]).preloaded(), 2000);
+		assert_eq!(args(&["--preload", "50"]).preloaded(), 50);
+	}
+
 	#[test]
 	fn scan_spec_name_only() -> Result<()> {
 		let specs: Vec<super::ScanSpec> =
@@ -900,6 +1953,89 @@ mod test {
 		Ok(())
 	}
 
+	#[test]
+	fn scan_spec_field_projection() -> Result<()> {
+		let specs: Vec<super::ScanSpec> = serde_json::from_str(
+			r#"[{"id":"f","field":"a.b","runs":[{"name":"x","projection":"FIELD"},{"name":"y","projection":"FIELD","field":""}]}]"#,
+		)?;
+		let scans = super::expand_scan_specs(specs)?;
+		assert!(matches!(scans[0].projection()?, super::Projection::Field(f) if f == "a.b"));
+		assert!(scans[1].projection().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn scan_spec_fields_projection() -> Result<()> {
+		let specs: Vec<super::ScanSpec> = serde_json::from_str(
+			r#"[{"id":"f","fields":["text","number"],"runs":[{"name":"x","projection":"FIELDS"},{"name":"y","projection":"FIELDS","fields":[]}]}]"#,
+		)?;
+		let scans = super::expand_scan_specs(specs)?;
+		assert!(
+			matches!(scans[0].projection()?, super::Projection::Fields(f) if f == ["text", "number"])
+		);
+		assert!(scans[1].projection().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn scan_spec_separate_indexes() -> Result<()> {
+		let specs: Vec<super::ScanSpec> = serde_json::from_str(
+			r#"[{"id":"s","name":"x","with_index":{"fields":["a","b"],"separate":true}}]"#,
+		)?;
+		let scans = super::expand_scan_specs(specs)?;
+		let index = scans[0].with_index.as_ref().unwrap();
+		let physical = index.physical("s");
+		assert_eq!(physical.len(), 2);
+		assert_eq!(physical[1].0.fields, ["b"]);
+		assert_eq!(physical[1].1, "s_1");
+		let specs: Vec<super::ScanSpec> = serde_json::from_str(
+			r#"[{"id":"s","name":"x","with_index":{"fields":["a","b"],"separate":true},"hint":{"sqlite":"INDEXED BY s"}}]"#,
+		)?;
+		assert!(super::expand_scan_specs(specs).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn scan_spec_concurrency_overrides() -> Result<()> {
+		let specs: Vec<super::ScanSpec> = serde_json::from_str(
+			r#"[{"id":"c","clients":2,"threads":1,"runs":[{"name":"x"},{"name":"y"}]}]"#,
+		)?;
+		let scans = super::expand_scan_specs(specs)?;
+		assert_eq!((scans[1].clients, scans[1].threads), (Some(2), Some(1)));
+		assert!(super::validate_scan_concurrency(&scans, 2).is_ok());
+		assert!(super::validate_scan_concurrency(&scans, 1).is_err());
+		let specs: Vec<super::ScanSpec> =
+			serde_json::from_str(r#"[{"id":"c","name":"x","threads":0}]"#)?;
+		assert!(super::expand_scan_specs(specs).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn batch_concurrency_and_rate_overrides() -> Result<()> {
+		let batch: super::BatchOperation = serde_json::from_str(
+			r#"{"name":"load","operation":"CREATE","batch_size":1000,"clients":2,"threads":1,"rate":50.0}"#,
+		)?;
+		assert_eq!(batch.select_clients(&[1, 2, 3]), [1, 2]);
+		assert!(batch.validate(3).is_ok());
+		assert!(batch.validate(1).is_err());
+		let loaded = super::BatchOperation {
+			load: true,
+			..batch.clone()
+		};
+		assert!(loaded.validate(3).is_ok());
+		let loaded = super::BatchOperation {
+			operation: super::BatchOperationType::Read,
+			..loaded
+		};
+		assert!(loaded.validate(3).is_err());
+		let batch = super::BatchOperation {
+			rate: Some(0.0),
+			..batch
+		};
+		assert!(batch.validate(3).is_err());
+		Ok(())
+	}
+
 	#[test]
 	fn scan_spec_name_and_runs_rejected() {
 		let specs: Vec<super::ScanSpec> = serde_json::from_str(
@@ -925,6 +2061,42 @@ mod test {
 		assert!(super::expand_scan_specs(specs).is_err());
 	}
 
+	#[test]
+	fn scan_spec_hint_requires_with_index() {
+		let specs: Vec<super::ScanSpec> = serde_json::from_str(
+			r#"[{"id":"h","name":"n","samples":1,"hint":{"sqlite":"INDEXED BY h"}}]"#,
+		)
+		.unwrap();
+		assert!(super::expand_scan_specs(specs).is_err());
+	}
+
+	#[test]
+	fn scan_perturbed_condition_differs_per_nonce() {
+		let specs: Vec<super::ScanSpec> = serde_json::from_str(
+			r#"[{"id":"p","name":"n","samples":1,"condition":{"sql":"n = 1","mongodb":{"n":1},"opensearch":{"term":{"n":1}}}}]"#,
+		)
+		.unwrap();
+		let scans = super::expand_scan_specs(specs).unwrap();
+		let perturbed = scans[0].perturbed(7);
+		let condition = perturbed.condition.unwrap();
+		assert_eq!(condition.sql.as_deref(), Some("(n = 1) AND 7 = 7"));
+		assert_eq!(
+			condition.mongodb,
+			Some(serde_json::json!({ "$and": [{ "n": 1 }, { "$expr": { "$eq": [7, 7] } }] }))
+		);
+		assert_eq!(
+			condition.opensearch,
+			Some(serde_json::json!({
+				"bool": {
+					"must": [{ "term": { "n": 1 } }],
+					"must_not": [{ "ids": { "values": ["perturbed-7"] } }]
+				}
+			}))
+		);
+		// The original scan is left untouched
+		assert_eq!(scans[0].condition.as_ref().unwrap().sql.as_deref(), Some("n = 1"));
+	}
+
 	#[test]
 	fn scan_with_index_ok_when_id_present() {
 		let specs: Vec<super::ScanSpec> = serde_json::from_str(
@@ -1,14 +1,20 @@
-use crate::benchmark::NOT_SUPPORTED_ERROR;
-use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::engine::{BenchmarkClient, BenchmarkEngine, PageStart, ScanContext};
+use crate::error::BenchError;
+use crate::keyprovider::GeneratedKey;
+use crate::materialize;
+use crate::timeseries::{TimeSeriesRow, TimeWindow};
+use crate::util::crate_version;
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
 use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
+use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::hint::black_box;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub(crate) enum MapDatabase {
@@ -20,37 +26,55 @@ impl From<KeyType> for MapDatabase {
 	fn from(t: KeyType) -> Self {
 		match t {
 			KeyType::Integer => Self::Integer(DashMap::new().into()),
-			KeyType::String26 | KeyType::String90 | KeyType::String250 | KeyType::String506 => {
-				Self::String(DashMap::new().into())
-			}
-			KeyType::Uuid => todo!(),
+			KeyType::String26
+			| KeyType::String90
+			| KeyType::String250
+			| KeyType::String506
+			| KeyType::Uuid
+			| KeyType::UuidV4 => Self::String(DashMap::new().into()),
 		}
 	}
 }
 
-pub(crate) struct MapClientProvider(MapDatabase);
+/// The records with keys generated on insert, as random UUIDs
+type GeneratedMap = Arc<DashMap<String, BenchValue>>;
+
+/// The appended time-series rows, ordered by timestamp
+type SeriesMap = Arc<RwLock<BTreeMap<DateTime<Utc>, TimeSeriesRow>>>;
+
+pub(crate) struct MapClientProvider(MapDatabase, GeneratedMap, SeriesMap);
 
 impl BenchmarkEngine<MapClient> for MapClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(kt: KeyType, _columns: Columns, _options: &Benchmark) -> Result<Self> {
-		Ok(Self(kt.into()))
+		Ok(Self(kt.into(), GeneratedMap::default(), SeriesMap::default()))
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<MapClient> {
-		Ok(MapClient(self.0.clone()))
+		Ok(MapClient(self.0.clone(), self.1.clone(), self.2.clone()))
 	}
 }
 
-pub(crate) struct MapClient(MapDatabase);
+pub(crate) struct MapClient(MapDatabase, GeneratedMap, SeriesMap);
 
 impl BenchmarkClient for MapClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("dashmap")
+	}
+
+	async fn setup_generated_keys(&self) -> Result<()> {
+		self.1.clear();
+		Ok(())
+	}
+
+	async fn setup_time_series(&self) -> Result<()> {
+		self.2.write().map_err(|_| anyhow!("Poisoned time series lock"))?.clear();
+		Ok(())
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		if let MapDatabase::Integer(m) = &self.0 {
 			assert!(m.insert(key, val).is_none());
@@ -139,6 +163,41 @@ impl BenchmarkClient for MapClient {
 		}
 	}
 
+	async fn read_secondary(&self, field: &str, value: String) -> Result<usize> {
+		// There are no secondary indexes, so every record is checked
+		match &self.0 {
+			MapDatabase::Integer(m) => Ok(Self::read_secondary(m, field, value)),
+			MapDatabase::String(m) => Ok(Self::read_secondary(m, field, value)),
+		}
+	}
+
+	async fn scan_ids(&self, scan: &Scan) -> Result<Vec<String>> {
+		match &self.0 {
+			MapDatabase::Integer(m) => Self::scan_ids(m, scan),
+			MapDatabase::String(m) => Self::scan_ids(m, scan),
+		}
+	}
+
+	async fn scan_page_u32(&self, start: PageStart<u32>, limit: usize) -> Result<Option<u32>> {
+		if let MapDatabase::Integer(m) = &self.0 {
+			Ok(Self::scan_page(m, start, limit))
+		} else {
+			bail!("Invalid MapDatabase variant");
+		}
+	}
+
+	async fn scan_page_string(
+		&self,
+		start: PageStart<String>,
+		limit: usize,
+	) -> Result<Option<String>> {
+		if let MapDatabase::String(m) = &self.0 {
+			Ok(Self::scan_page(m, start, limit))
+		} else {
+			bail!("Invalid MapDatabase variant");
+		}
+	}
+
 	async fn batch_create_u32(
 		&self,
 		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
@@ -170,7 +229,9 @@ impl BenchmarkClient for MapClient {
 	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
 		if let MapDatabase::Integer(m) = &self.0 {
 			for key in keys {
-				assert!(m.get(&key).is_some());
+				let v = m.get(&key);
+				assert!(v.is_some());
+				materialize::consume(v.unwrap(), |v| Ok(v.value().clone()))?;
 			}
 		} else {
 			bail!("Invalid MapDatabase variant");
@@ -181,7 +242,9 @@ impl BenchmarkClient for MapClient {
 	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
 		if let MapDatabase::String(m) = &self.0 {
 			for key in keys {
-				assert!(m.get(&key).is_some());
+				let v = m.get(&key);
+				assert!(v.is_some());
+				materialize::consume(v.unwrap(), |v| Ok(v.value().clone()))?;
 			}
 		} else {
 			bail!("Invalid MapDatabase variant");
@@ -238,16 +301,127 @@ impl BenchmarkClient for MapClient {
 		}
 		Ok(())
 	}
+
+	async fn batch_create_generated(&self, vals: Vec<BenchValue>) -> Result<Vec<GeneratedKey>> {
+		let mut keys = Vec::with_capacity(vals.len());
+		for val in vals {
+			let key = Uuid::new_v4().to_string();
+			assert!(self.1.insert(key.clone(), val).is_none());
+			keys.push(GeneratedKey::String(key));
+		}
+		Ok(keys)
+	}
+
+	async fn batch_read_generated(&self, keys: Vec<GeneratedKey>) -> Result<()> {
+		for key in keys {
+			match key {
+				GeneratedKey::String(key) => assert!(self.1.get(&key).is_some()),
+				GeneratedKey::Integer(_) => bail!("Unexpected generated key {key:?}"),
+			}
+		}
+		Ok(())
+	}
+
+	async fn append_time_series(&self, row: TimeSeriesRow) -> Result<()> {
+		let mut series = self.2.write().map_err(|_| anyhow!("Poisoned time series lock"))?;
+		assert!(series.insert(row.ts, row).is_none());
+		Ok(())
+	}
+
+	async fn query_time_series(&self, window: TimeWindow) -> Result<usize> {
+		let series = self.2.read().map_err(|_| anyhow!("Poisoned time series lock"))?;
+		let rows = series.range(window.from..window.to);
+		match window.bucket {
+			None => {
+				let mut count = 0;
+				for v in rows {
+					black_box(v);
+					count += 1;
+				}
+				Ok(count)
+			}
+			Some(bucket) => {
+				// Rows are ordered, so each bucket is a contiguous run of rows
+				let bucket = bucket.max(1) as i64;
+				let mut buckets: Vec<(i64, f64, f64, f64, usize)> = Vec::new();
+				for (ts, row) in rows {
+					let b = ts.timestamp().div_euclid(bucket);
+					match buckets.last_mut() {
+						Some((last, sum, min, max, n)) if *last == b => {
+							*sum += row.value;
+							*min = min.min(row.value);
+							*max = max.max(row.value);
+							*n += 1;
+						}
+						_ => buckets.push((b, row.value, row.value, row.value, 1)),
+					}
+				}
+				black_box(&buckets);
+				Ok(buckets.len())
+			}
+		}
+	}
 }
 
 impl MapClient {
+	fn read_secondary<T>(m: &DashMap<T, BenchValue>, field: &str, value: String) -> usize
+	where
+		T: Eq + Hash,
+	{
+		let value = BenchValue::String(value);
+		let mut count = 0;
+		for v in m.iter().filter(|v| v.value().get_field(field) == Some(&value)) {
+			black_box(v);
+			count += 1;
+		}
+		count
+	}
+
+	fn scan_page<T>(m: &DashMap<T, BenchValue>, start: PageStart<T>, limit: usize) -> Option<T>
+	where
+		T: Eq + Hash + Ord + Clone,
+	{
+		let (offset, after) = match start {
+			PageStart::Offset(s) => (s, None),
+			PageStart::After(k) => (0, k),
+		};
+		// The map has no key order, so every page sorts the keys it could start from
+		let mut keys: Vec<T> = m
+			.iter()
+			.map(|v| v.key().clone())
+			.filter(|k| after.as_ref().is_none_or(|a| k > a))
+			.collect();
+		keys.sort_unstable();
+		let mut last = None;
+		for key in keys.into_iter().skip(offset).take(limit) {
+			black_box(m.get(&key));
+			last = Some(key);
+		}
+		last
+	}
+
+	fn scan_ids<T>(m: &DashMap<T, BenchValue>, scan: &Scan) -> Result<Vec<String>>
+	where
+		T: Eq + Hash + ToString,
+	{
+		// Contional scans are not supported
+		if scan.condition.is_some() {
+			bail!(BenchError::Unsupported);
+		}
+		// Extract parameters
+		let s = scan.start.unwrap_or(0);
+		let l = scan.limit.unwrap_or(usize::MAX);
+		// Return the keys in iteration order
+		Ok(m.iter().skip(s).take(l).map(|v| v.key().to_string()).collect())
+	}
+
 	async fn scan<T>(m: &DashMap<T, BenchValue>, scan: &Scan) -> Result<usize>
 	where
 		T: Eq + Hash,
 	{
 		// Contional scans are not supported
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -267,14 +441,14 @@ impl MapClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
 				// an iterator with `filter_map` or `map` is optimised
 				// out by the compiler when calling `count` at the end.
 				let mut count = 0;
 				for v in m.iter().skip(s).take(l) {
-					black_box(v);
+					materialize::consume(v, |v| Ok(v.value().clone()))?;
 					count += 1;
 				}
 				Ok(count)
@@ -0,0 +1,51 @@
+//! How much of each record returned by a scan or batch read the clients materialise.
+//!
+//! Drivers hand back records in very different shapes, from raw bytes to fully typed rows, so
+//! the work of consuming them varies between adapters. `--materialize` sets the same depth for
+//! every adapter: `full` decodes each record into a [`BenchValue`], `bytes` keeps the bytes or
+//! row the driver returned without decoding it, and `count` only counts the records.
+
+use crate::value::BenchValue;
+use anyhow::Result;
+use clap::ValueEnum;
+use std::hint::black_box;
+use std::sync::OnceLock;
+
+/// The depth records are materialised to, set by `--materialize`
+static MODE: OnceLock<Materialize> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Materialize {
+	/// Decode every record into a value
+	#[default]
+	Full,
+	/// Keep every record as the bytes or row the driver returned
+	Bytes,
+	/// Only count the records
+	Count,
+}
+
+/// Materialises the records of scans and batch reads to the given depth
+pub(crate) fn set(mode: Materialize) {
+	let _ = MODE.set(mode);
+}
+
+/// The depth records are materialised to
+pub(crate) fn mode() -> Materialize {
+	MODE.get().copied().unwrap_or_default()
+}
+
+/// Consumes one record returned by a scan or batch read, decoding it with `decode` only when
+/// full records are materialised
+pub(crate) fn consume<T>(raw: T, decode: impl FnOnce(T) -> Result<BenchValue>) -> Result<()> {
+	match mode() {
+		Materialize::Full => {
+			black_box(decode(raw)?);
+		}
+		Materialize::Bytes => {
+			black_box(raw);
+		}
+		Materialize::Count => {}
+	}
+	Ok(())
+}
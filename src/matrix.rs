@@ -0,0 +1,180 @@
+//! `crud-bench matrix`: run the same workload against every SurrealDB storage engine and protocol,
+//! and merge the results into one comparative report.
+//!
+//! Each cell of the matrix is one storage engine reached over one protocol: a Docker server over a
+//! WebSocket (`ws`) or its HTTP endpoint (`http`), or the engine embedded in process (`embedded`).
+//! The cells run back-to-back, each as its own benchmark with the options given after the matrix
+//! options, and write `result-surrealdb-<protocol>-<engine>.json`. The results are then merged as
+//! `crud-bench report` does.
+
+use crate::report::{self, ReportArgs};
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Command-line interface for `crud-bench matrix`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct MatrixArgs {
+	/// Comma-separated storage engines to run
+	#[arg(long, value_delimiter = ',', default_value = "memory,rocksdb,surrealkv")]
+	pub(crate) engines: Vec<Engine>,
+
+	/// Comma-separated protocols to reach each engine over
+	#[arg(long, value_delimiter = ',', default_value = "ws")]
+	pub(crate) protocols: Vec<Protocol>,
+
+	/// Path of the generated HTML report
+	#[arg(short, long, default_value = "surrealdb-matrix.html")]
+	pub(crate) output: String,
+
+	/// Benchmark options which every cell runs with, such as `--samples` and `--clients`
+	#[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+	pub(crate) args: Vec<OsString>,
+}
+
+/// A SurrealDB storage engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Engine {
+	Memory,
+	Rocksdb,
+	Surrealkv,
+}
+
+/// How the benchmark clients reach the storage engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Protocol {
+	/// A Docker server, over a WebSocket
+	Ws,
+	/// A Docker server, over its HTTP endpoint
+	Http,
+	/// The engine embedded in the benchmark process
+	Embedded,
+}
+
+impl Display for Engine {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Memory => write!(f, "memory"),
+			Self::Rocksdb => write!(f, "rocksdb"),
+			Self::Surrealkv => write!(f, "surrealkv"),
+		}
+	}
+}
+
+impl Display for Protocol {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Ws => write!(f, "ws"),
+			Self::Http => write!(f, "http"),
+			Self::Embedded => write!(f, "embedded"),
+		}
+	}
+}
+
+/// One engine reached over one protocol.
+struct Cell {
+	engine: Engine,
+	protocol: Protocol,
+}
+
+impl Cell {
+	/// The name of the cell's run, which names its result file.
+	fn name(&self) -> String {
+		format!("surrealdb-{}-{}", self.protocol, self.engine)
+	}
+
+	/// The `--database` the cell runs as.
+	fn database(&self) -> &'static str {
+		match self.protocol {
+			Protocol::Http => "surrealdb-http",
+			Protocol::Ws | Protocol::Embedded => "surrealdb",
+		}
+	}
+
+	/// The data directory of an embedded engine, removed after its run.
+	fn directory(&self) -> Option<PathBuf> {
+		match (self.protocol, self.engine) {
+			(Protocol::Embedded, Engine::Rocksdb | Engine::Surrealkv) => {
+				Some(std::env::temp_dir().join(format!("crud-bench-{}", self.name())))
+			}
+			_ => None,
+		}
+	}
+
+	/// The `--endpoint` the cell runs against.
+	fn endpoint(&self) -> String {
+		match (self.protocol, self.directory()) {
+			(Protocol::Ws | Protocol::Http, _) => format!("server:{}", self.engine),
+			(Protocol::Embedded, Some(path)) => format!("{}:{}", self.engine, path.display()),
+			(Protocol::Embedded, None) => "memory".to_string(),
+		}
+	}
+}
+
+/// Runs every cell of the matrix in turn, then merges their results into one report.
+pub(crate) fn run(args: MatrixArgs) -> Result<()> {
+	let exe = std::env::current_exe().context("Failed to find the crud-bench executable")?;
+	let cells: Vec<Cell> = args
+		.protocols
+		.iter()
+		.flat_map(|&protocol| {
+			args.engines.iter().map(move |&engine| Cell {
+				engine,
+				protocol,
+			})
+		})
+		.collect();
+	let mut files = Vec::with_capacity(cells.len());
+	for cell in &cells {
+		let name = cell.name();
+		println!("Running {name} ({} of {})", files.len() + 1, cells.len());
+		// The options of the cell are given last, so they take precedence
+		let status = Command::new(&exe)
+			.args(&args.args)
+			.args(["--database", cell.database()])
+			.args(["--endpoint", &cell.endpoint()])
+			.args(["--name", &name])
+			.status()
+			.with_context(|| format!("Failed to run {name}"))?;
+		if let Some(path) = cell.directory() {
+			let _ = std::fs::remove_dir_all(path);
+		}
+		if !status.success() {
+			bail!("The {name} benchmark failed ({status})");
+		}
+		files.push(format!("result-{name}.json"));
+	}
+	report::run(ReportArgs {
+		files,
+		output: args.output,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cells_choose_the_database_and_endpoint() {
+		let cell = |protocol, engine| Cell {
+			engine,
+			protocol,
+		};
+		let ws = cell(Protocol::Ws, Engine::Rocksdb);
+		assert_eq!((ws.database(), ws.endpoint()), ("surrealdb", "server:rocksdb".to_string()));
+		assert_eq!(ws.name(), "surrealdb-ws-rocksdb");
+		let http = cell(Protocol::Http, Engine::Memory);
+		assert_eq!(
+			(http.database(), http.endpoint()),
+			("surrealdb-http", "server:memory".to_string())
+		);
+		assert_eq!(cell(Protocol::Embedded, Engine::Memory).endpoint(), "memory");
+		let embedded = cell(Protocol::Embedded, Engine::Surrealkv);
+		assert!(embedded.endpoint().starts_with("surrealkv:"));
+		assert!(embedded.endpoint().ends_with("crud-bench-surrealdb-embedded-surrealkv"));
+	}
+}
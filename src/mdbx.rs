@@ -1,31 +1,34 @@
 #![cfg(feature = "mdbx")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::materialize;
+use crate::util::kvindex::{KvIndexes, Matches};
+use crate::util::{crate_version, dir_size};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, bail};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
+use anyhow::Result;
 use libmdbx::{
-	Database, DatabaseOptions, Mode, NoWriteMap, PageSize, ReadWriteOptions, SyncMode, WriteFlags,
+	Database, DatabaseOptions, Mode, NoWriteMap, PageSize, ReadWriteOptions, SyncMode, TableFlags,
+	WriteFlags,
 };
 use std::hint::black_box;
 use std::sync::Arc;
-use std::time::Duration;
 
 const DATABASE_DIR: &str = "mdbx";
 
-pub(crate) struct MDBXClientProvider(Arc<Database<NoWriteMap>>);
+/// The table holding the entries of the emulated secondary indexes
+const INDEX_TABLE: &str = "index";
+
+pub(crate) struct MDBXClientProvider(Arc<Database<NoWriteMap>>, Arc<KvIndexes>);
 
 impl BenchmarkEngine<MDBXClient> for MDBXClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Cleanup the data directory
-		std::fs::remove_dir_all(DATABASE_DIR).ok();
+		// Cleanup the data directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_dir_all(DATABASE_DIR).ok();
+		}
 		// Configure database options
 		let options = DatabaseOptions {
 			// Configure the read-write options
@@ -58,11 +61,13 @@ impl BenchmarkEngine<MDBXClient> for MDBXClientProvider {
 			coalesce: true,
 			// Optimize for expected concurrent readers
 			max_readers: Some(126),
-			// We only use one table for benchmarks
-			max_tables: Some(1),
+			// One table for the records, and one for index entries
+			max_tables: Some(2),
 			// Use defaults for transaction limits
 			..Default::default()
 		};
+		// Create the emulated secondary indexes
+		let indexes = KvIndexes::new(options.emulate_indexes, &[]);
 		// Create the database
 		let db = Database::open_with_options(DATABASE_DIR, options)?;
 		// Begin a new transaction
@@ -71,27 +76,36 @@ impl BenchmarkEngine<MDBXClient> for MDBXClientProvider {
 		let tb = tx.open_table(None)?;
 		// Prime the table for permaopen
 		tx.prime_for_permaopen(tb);
+		// Create and prime the index table
+		let tb = tx.create_table(Some(INDEX_TABLE), TableFlags::default())?;
+		tx.prime_for_permaopen(tb);
 		// Commit the transaction
 		tx.commit()?;
 		// Create the store
-		Ok(Self(Arc::new(db)))
+		Ok(Self(Arc::new(db), Arc::new(indexes)))
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<MDBXClient> {
 		Ok(MDBXClient {
 			db: self.0.clone(),
+			indexes: self.1.clone(),
 		})
 	}
 }
 
 pub(crate) struct MDBXClient {
 	db: Arc<Database<NoWriteMap>>,
+	indexes: Arc<KvIndexes>,
 }
 
 impl BenchmarkClient for MDBXClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("libmdbx")
+	}
+
 	async fn shutdown(&self) -> Result<()> {
 		// Cleanup the data directory
 		std::fs::remove_dir_all(DATABASE_DIR).ok();
@@ -99,6 +113,17 @@ impl BenchmarkClient for MDBXClient {
 		Ok(())
 	}
 
+	fn disk_size(&self) -> Option<u64> {
+		dir_size(DATABASE_DIR)
+	}
+
+	async fn sync(&self) -> Result<()> {
+		// Flush the buffers of the database to disk
+		self.db.sync(true)?;
+		// Ok
+		Ok(())
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create_bytes(&key.to_ne_bytes(), val).await
 	}
@@ -131,12 +156,61 @@ impl BenchmarkClient for MDBXClient {
 		self.delete_bytes(&key.into_bytes()).await
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// Writes maintain the index from now on
+		let index = self.indexes.create(spec, name)?;
+		// Create a new transaction
+		let txn = self.db.begin_rw_txn()?;
+		// Open the default and index tables
+		let table = txn.open_table(None)?;
+		let idx = txn.open_table(Some(INDEX_TABLE))?;
+		// Collect the entries of the existing records
+		let mut entries = Vec::new();
+		for v in txn.cursor(&table)?.iter_start::<Vec<u8>, Vec<u8>>() {
+			let (key, val) = v?;
+			if let Some(entry) = index.entry(&key, &BenchValue::decode(&val)?) {
+				entries.push(entry);
+			}
+		}
+		// Write the entries
+		for entry in entries {
+			txn.put(&idx, &entry, b"", WriteFlags::empty())?;
+		}
+		txn.commit()?;
+		Ok(())
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		// Writes stop maintaining the index
+		let index = self.indexes.remove(name)?;
+		// Create a new transaction
+		let txn = self.db.begin_rw_txn()?;
+		// Open the index table
+		let idx = txn.open_table(Some(INDEX_TABLE))?;
+		// Collect the entries of the index
+		let end = index.end();
+		let mut entries = Vec::new();
+		for v in txn.cursor(&idx)?.iter_from::<Vec<u8>, ()>(index.start()) {
+			let (entry, _) = v?;
+			if entry >= end {
+				break;
+			}
+			entries.push(entry);
+		}
+		// Delete the entries
+		for entry in entries {
+			txn.del(&idx, &entry, None)?;
+		}
+		txn.commit()?;
+		Ok(())
 	}
 
 	async fn batch_create_u32(
@@ -214,6 +288,14 @@ impl MDBXClient {
 		let table = txn.open_table(None)?;
 		// Process the data
 		txn.put(&table, key, &val, WriteFlags::empty())?;
+		let entries = self.indexes.entries(key, &val)?;
+		// Write any index entries
+		if !entries.is_empty() {
+			let idx = txn.open_table(Some(INDEX_TABLE))?;
+			for entry in entries {
+				txn.put(&idx, &entry, b"", WriteFlags::empty())?;
+			}
+		}
 		txn.commit()?;
 		Ok(())
 	}
@@ -242,6 +324,14 @@ impl MDBXClient {
 		let table = txn.open_table(None)?;
 		// Process the data
 		txn.put(&table, key, &val, WriteFlags::empty())?;
+		let entries = self.indexes.entries(key, &val)?;
+		// Write any index entries
+		if !entries.is_empty() {
+			let idx = txn.open_table(Some(INDEX_TABLE))?;
+			for entry in entries {
+				txn.put(&idx, &entry, b"", WriteFlags::empty())?;
+			}
+		}
 		txn.commit()?;
 		Ok(())
 	}
@@ -266,10 +356,19 @@ impl MDBXClient {
 		// Open the default table
 		let table = txn.open_table(None)?;
 		// Process the data
+		let mut entries = Vec::new();
 		for result in key_vals {
 			let (key, val) = result?;
+			entries.extend(self.indexes.entries(&key, &val)?);
 			txn.put(&table, &key, &val, WriteFlags::empty())?;
 		}
+		// Write any index entries
+		if !entries.is_empty() {
+			let idx = txn.open_table(Some(INDEX_TABLE))?;
+			for entry in entries {
+				txn.put(&idx, &entry, b"", WriteFlags::empty())?;
+			}
+		}
 		// Commit the batch
 		txn.commit()?;
 		Ok(())
@@ -286,10 +385,8 @@ impl MDBXClient {
 			let res: Option<Vec<u8>> = txn.get(&table, &key)?;
 			// Check the value exists
 			assert!(res.is_some());
-			// Deserialise the value
-			let val = BenchValue::decode(res.unwrap().as_ref())?;
-			// Use the value
-			black_box(val);
+			// Materialise the value
+			materialize::consume(res.unwrap(), |v| BenchValue::decode(v.as_ref()))?;
 		}
 		// All ok
 		Ok(())
@@ -304,10 +401,19 @@ impl MDBXClient {
 		// Open the default table
 		let table = txn.open_table(None)?;
 		// Process the data
+		let mut entries = Vec::new();
 		for result in key_vals {
 			let (key, val) = result?;
+			entries.extend(self.indexes.entries(&key, &val)?);
 			txn.put(&table, &key, &val, WriteFlags::empty())?;
 		}
+		// Write any index entries
+		if !entries.is_empty() {
+			let idx = txn.open_table(Some(INDEX_TABLE))?;
+			for entry in entries {
+				txn.put(&idx, &entry, b"", WriteFlags::empty())?;
+			}
+		}
 		// Commit the batch
 		txn.commit()?;
 		Ok(())
@@ -327,10 +433,10 @@ impl MDBXClient {
 		Ok(())
 	}
 
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Conditional scans are not supported
+	async fn scan_bytes(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Conditional scans filter the records
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			return self.scan_filtered(scan, ctx).await;
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -356,14 +462,14 @@ impl MDBXClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
 				// an iterator with `filter_map` or `map` is optimised
 				// out by the compiler when calling `count` at the end.
 				let mut count = 0;
 				for v in iter.skip(s).take(l) {
-					black_box(v.unwrap().1);
+					materialize::consume(v.unwrap().1, |v| BenchValue::decode(&v))?;
 					count += 1;
 				}
 				Ok(count)
@@ -376,4 +482,50 @@ impl MDBXClient {
 			}
 		}
 	}
+
+	async fn scan_filtered(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Only conditions on a single integer field are supported
+		let filter = self.indexes.filter(scan, ctx)?;
+		let mut matches = Matches::new(scan)?;
+		// Create a new transaction
+		let txn = self.db.begin_ro_txn()?;
+		// Open the default table
+		let table = txn.open_table(None)?;
+		match self.indexes.find(&filter, ctx) {
+			// Look up the records of each range of index entries
+			Some(index) => {
+				let idx = txn.open_table(Some(INDEX_TABLE))?;
+				let mut cursor = txn.cursor(&idx)?;
+				'ranges: for range in filter.ranges() {
+					let (beg, end) = index.range(*range);
+					for v in cursor.iter_from::<Vec<u8>, ()>(&beg) {
+						let (entry, _) = v?;
+						if entry >= end {
+							break;
+						}
+						let (value, key) = index.split(&entry);
+						// Skip the entries left behind by updates and deletes
+						let Some(val) = txn.get::<Vec<u8>>(&table, key)? else {
+							continue;
+						};
+						let record = BenchValue::decode(&val)?;
+						if index.holds(value, &record) && !matches.push(key, record) {
+							break 'ranges;
+						}
+					}
+				}
+			}
+			// Decode and filter every record
+			None => {
+				for v in txn.cursor(&table)?.iter_start::<Vec<u8>, Vec<u8>>() {
+					let (key, val) = v?;
+					let record = BenchValue::decode(&val)?;
+					if filter.matches(&record) && !matches.push(&key, record) {
+						break;
+					}
+				}
+			}
+		}
+		Ok(matches.count())
+	}
 }
@@ -1,5 +1,14 @@
+use std::sync::OnceLock;
 use sysinfo::System;
 
+/// The memory (bytes) the datastore container is limited to, set by `--container-memory`
+static LIMIT: OnceLock<u64> = OnceLock::new();
+
+/// Sizes the datastore caches to a container memory limit, rather than the host memory
+pub(crate) fn limit(bytes: u64) {
+	let _ = LIMIT.set(bytes);
+}
+
 /// System memory information for database optimization
 pub(crate) struct Config {
 	/// Recommended memory allocation for database cache/buffer pools in GB
@@ -11,8 +20,8 @@ impl Config {
 	pub fn new() -> Self {
 		// Load the system attributed
 		let system = System::new_all();
-		// Get the total system memory
-		let total_memory = system.total_memory();
+		// Get the total system memory, or the container memory limit
+		let total_memory = LIMIT.get().copied().unwrap_or_else(|| system.total_memory());
 		// Convert to GB for easier calculations
 		let total_gb = total_memory / (1024 * 1024 * 1024);
 		// Use ~75% of total memory for database cache
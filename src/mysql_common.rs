@@ -0,0 +1,1005 @@
+#![cfg(any(feature = "mysql", feature = "mariadb", feature = "tidb"))]
+
+//! Shared adapter for the datastores speaking the MySQL protocol.
+//!
+//! MySQL-family backends only differ in their Docker parameters and a few dialect quirks, so
+//! each one supplies its own `docker` function and a [`Flavour`], and reuses the client below.
+
+use crate::dialect::{Dialect, MySqlDialect};
+use crate::engine::{BenchmarkClient, BenchmarkEngine, PageStart, ScanContext};
+use crate::error::BenchError;
+use crate::keyprovider::GeneratedKey;
+use crate::materialize;
+use crate::security::Security;
+use crate::util::sql::bench_to_mysql_value;
+use crate::value::{BenchValue, parse_decimal, parse_uuid};
+use crate::valueprovider::{ColumnType, Columns};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
+use anyhow::{Result, anyhow, bail};
+use bytes::Bytes;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use futures::StreamExt;
+use mysql_async::consts;
+use mysql_async::prelude::Queryable;
+use mysql_async::prelude::{FromValue, ToValue};
+use mysql_async::{Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts, Row, SslOpts};
+use std::hint::black_box;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+/// The table holding the records with keys generated by the datastore
+const GENERATED_TABLE: &str = "record_generated";
+
+/// The differences between the MySQL-family datastores.
+pub(crate) trait Flavour: Send + Sync + 'static {
+	/// The endpoint connected to when none is specified
+	const DEFAULT: &'static str;
+	/// Whether `INSERT ... RETURNING` reports the keys generated for each row
+	const INSERT_RETURNING: bool;
+	/// Whether `FULLTEXT` indexes, and the `MATCH` queries using them, are supported
+	const FULLTEXT_INDEXES: bool;
+	/// The key list of a B-tree index, which differs in how JSON arrays are indexed
+	fn btree_index_key_list(columns: &Columns, spec: &Index) -> String;
+}
+
+/// The client provider for a MySQL-family datastore of flavour `F`.
+pub(crate) struct MysqlCommonClientProvider<F> {
+	kt: KeyType,
+	columns: Columns,
+	url: String,
+	/// Whether the CRUD statements are prepared once per connection
+	prepared: bool,
+	/// The connections in the pool of each client, one per thread
+	threads: usize,
+	/// The credentials and TLS settings given on the command line
+	security: Security,
+	flavour: PhantomData<F>,
+}
+
+impl<F: Flavour> BenchmarkEngine<MysqlCommonClient<F>> for MysqlCommonClientProvider<F> {
+	/// Initiates a new datastore benchmarking engine
+	async fn setup(kt: KeyType, columns: Columns, options: &Benchmark) -> Result<Self> {
+		// Get the custom endpoint if specified
+		let url = options.endpoint.as_deref().unwrap_or(F::DEFAULT).to_owned();
+		// Create the client provider
+		Ok(Self {
+			kt,
+			columns,
+			url,
+			prepared: options.prepared,
+			threads: options.threads as usize,
+			security: options.security.clone(),
+			flavour: PhantomData,
+		})
+	}
+	/// Creates a new client for this benchmarking engine, with a pool of
+	/// one connection per `-t` worker task, so that the tasks of a client
+	/// run their statements concurrently. The first connection does a real
+	/// TCP handshake, so this also serves as the readiness probe used by
+	/// `wait_for_client`.
+	async fn create_client(&self) -> Result<MysqlCommonClient<F>> {
+		let constraints = PoolConstraints::new(1, self.threads.max(1))
+			.ok_or_else(|| anyhow!("Invalid pool size {}", self.threads))?;
+		let opts = OptsBuilder::from_opts(Opts::from_url(&self.url)?)
+			.pool_opts(PoolOpts::default().with_constraints(constraints));
+		// The statement cache prepares each statement once per connection, and without it every
+		// statement is prepared and closed again on each call
+		let opts = match self.prepared {
+			true => opts,
+			false => opts.stmt_cache_size(0),
+		};
+		let opts = match &self.security.username {
+			Some(user) => opts.user(Some(user)),
+			None => opts,
+		};
+		let opts = match &self.security.password {
+			Some(password) => opts.pass(Some(password)),
+			None => opts,
+		};
+		// Any TLS option connects over TLS, whereas the endpoint asks for it with `require_ssl`
+		let opts = match self.security.has_tls() {
+			true => {
+				let ssl = SslOpts::default()
+					.with_root_certs(
+						self.security.tls_ca.iter().map(|p| PathBuf::from(p).into()).collect(),
+					)
+					.with_danger_accept_invalid_certs(self.security.tls_insecure);
+				opts.ssl_opts(Some(ssl))
+			}
+			false => opts,
+		};
+		let pool = Pool::new(opts);
+		pool.get_conn().await?;
+		Ok(MysqlCommonClient {
+			pool,
+			kt: self.kt,
+			columns: self.columns.clone(),
+			flavour: PhantomData,
+		})
+	}
+}
+
+/// A client connected to a MySQL-family datastore of flavour `F`.
+pub(crate) struct MysqlCommonClient<F> {
+	pool: Pool,
+	kt: KeyType,
+	columns: Columns,
+	flavour: PhantomData<F>,
+}
+
+/// The single-record operations, which run as prepared statements
+#[derive(Clone, Copy)]
+enum Crud {
+	Create,
+	Read,
+	Update,
+	Delete,
+}
+
+impl<F: Flavour> BenchmarkClient for MysqlCommonClient<F> {
+	// The return type when reading a row
+	type ReadRow = BenchValue;
+
+	async fn startup(&self) -> Result<()> {
+		let id_type = match self.kt {
+			KeyType::Integer => "SERIAL",
+			KeyType::String26 => "VARCHAR(26)",
+			KeyType::String90 => "VARCHAR(90)",
+			KeyType::String250 => "VARCHAR(250)",
+			KeyType::String506 => "VARCHAR(506)",
+			KeyType::Uuid | KeyType::UuidV4 => "CHAR(36)",
+		};
+		let fields = self
+			.columns
+			.0
+			.iter()
+			.map(|(n, t)| {
+				let n = MySqlDialect::escape_field(n.clone());
+				match t {
+					ColumnType::String => format!("{n} TEXT NOT NULL"),
+					ColumnType::Integer => format!("{n} INTEGER NOT NULL"),
+					ColumnType::Object => format!("{n} JSON NOT NULL"),
+					ColumnType::Array => format!("{n} JSON NOT NULL"),
+					ColumnType::Float => format!("{n} REAL NOT NULL"),
+					ColumnType::DateTime => format!("{n} TIMESTAMP NOT NULL"),
+					ColumnType::Uuid => format!("{n} CHAR(36) NOT NULL"),
+					ColumnType::Decimal => format!("{n} DECIMAL(38, 10) NOT NULL"),
+					ColumnType::Bool => format!("{n} BOOL NOT NULL"),
+					ColumnType::Bytes => format!("{n} VARBINARY(8192) NOT NULL"),
+				}
+			})
+			.collect::<Vec<String>>()
+			.join(", ");
+		let stm = format!(
+			"DROP TABLE IF EXISTS record; CREATE TABLE record ( id {id_type} PRIMARY KEY, {fields}) ENGINE=InnoDB;"
+		);
+		self.pool.get_conn().await?.query_drop(&stm).await?;
+		Ok(())
+	}
+
+	async fn shutdown(&self) -> Result<()> {
+		// Close the pooled connections, rather than dropping them
+		self.pool.clone().disconnect().await?;
+		Ok(())
+	}
+
+	async fn setup_generated_keys(&self) -> Result<()> {
+		// Copy the columns of the records table, replacing
+		// the key with an id assigned by auto-increment
+		let stm = format!(
+			"
+			DROP TABLE IF EXISTS {GENERATED_TABLE};
+			CREATE TABLE {GENERATED_TABLE} LIKE record;
+			ALTER TABLE {GENERATED_TABLE} MODIFY id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT;
+			"
+		);
+		self.pool.get_conn().await?.query_drop(&stm).await?;
+		Ok(())
+	}
+
+	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
+		self.create(key as u64, val).await
+	}
+
+	async fn create_string(&self, key: String, val: BenchValue) -> Result<()> {
+		self.create(key, val).await
+	}
+
+	async fn read_u32(&self, key: u32) -> Result<BenchValue> {
+		self.read(key as u64).await
+	}
+
+	async fn read_string(&self, key: String) -> Result<BenchValue> {
+		self.read(key).await
+	}
+
+	async fn read_secondary(&self, field: &str, value: String) -> Result<usize> {
+		let field = MySqlDialect::escape_field(field.to_string());
+		let stm = format!("SELECT * FROM record WHERE {field} = ?");
+		let res: Vec<Row> = self.pool.get_conn().await?.exec(stm, (value,)).await?;
+		let mut count = 0;
+		for row in res {
+			black_box(self.consume(row)?);
+			count += 1;
+		}
+		Ok(count)
+	}
+
+	async fn update_u32(&self, key: u32, val: BenchValue) -> Result<()> {
+		self.update(key as u64, val).await
+	}
+
+	async fn update_string(&self, key: String, val: BenchValue) -> Result<()> {
+		self.update(key, val).await
+	}
+
+	async fn delete_u32(&self, key: u32) -> Result<()> {
+		self.delete(key as u64).await
+	}
+
+	async fn delete_string(&self, key: String) -> Result<()> {
+		self.delete(key).await
+	}
+
+	async fn delete_range_u32(&self, from: u32, to: u32) -> Result<()> {
+		self.delete_range(from as u64, to as u64).await
+	}
+
+	async fn delete_range_string(&self, from: String, to: String) -> Result<()> {
+		self.delete_range(from, to).await
+	}
+
+	async fn truncate(&self) -> Result<()> {
+		self.pool.get_conn().await?.query_drop("TRUNCATE TABLE record").await?;
+		Ok(())
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// MySQL and MariaDB have no partial indexes
+		if spec.filter.is_some() {
+			bail!(BenchError::Unsupported);
+		}
+		// Get the unique flag
+		let unique = if spec.unique.unwrap_or(false) {
+			"UNIQUE"
+		} else {
+			""
+		}
+		.to_string();
+		// Check if an index type is specified
+		let stmt = match &spec.index_type {
+			Some(kind) if kind == "fulltext" => {
+				if !F::FULLTEXT_INDEXES {
+					bail!(BenchError::Unsupported);
+				}
+				let fields = spec
+					.fields
+					.iter()
+					.cloned()
+					.map(MySqlDialect::escape_field)
+					.collect::<Vec<_>>()
+					.join(", ");
+				format!("CREATE FULLTEXT INDEX {name} ON record ({fields})")
+			}
+			// GIN, GiST, and BRIN have no MySQL equivalent, while InnoDB builds hash indexes as B-trees
+			Some(_) if spec.method().is_some_and(|m| m != "hash") => {
+				bail!(BenchError::Unsupported)
+			}
+			Some(kind) => {
+				let fields = F::btree_index_key_list(&self.columns, spec);
+				format!("CREATE INDEX {name} USING {kind} ON record ({fields})")
+			}
+			None => {
+				let fields = F::btree_index_key_list(&self.columns, spec);
+				format!("CREATE {unique} INDEX {name} ON record ({fields})")
+			}
+		};
+		// Create the index
+		self.pool.get_conn().await?.query_drop(&stmt).await?;
+		// All ok
+		Ok(())
+	}
+
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		let stmt = format!("DROP INDEX {name} ON record");
+		self.pool.get_conn().await?.query_drop(&stmt).await?;
+		Ok(())
+	}
+
+	async fn version(&self) -> Result<String> {
+		let version: Option<String> =
+			self.pool.get_conn().await?.query_first("SELECT VERSION()").await?;
+		version.ok_or_else(|| anyhow!("The server did not report its version"))
+	}
+
+	async fn compact(&self) -> Result<()> {
+		// InnoDB rebuilds the table and its indexes, then refreshes the statistics
+		self.pool.get_conn().await?.query_drop("OPTIMIZE TABLE record").await?;
+		Ok(())
+	}
+
+	async fn snapshot_table(&self) -> Result<()> {
+		let stm = "DROP TABLE IF EXISTS record_snapshot; CREATE TABLE record_snapshot LIKE record; INSERT INTO record_snapshot SELECT * FROM record;";
+		self.pool.get_conn().await?.query_drop(stm).await?;
+		Ok(())
+	}
+
+	async fn restore_table(&self) -> Result<()> {
+		let stm = "DROP TABLE record; RENAME TABLE record_snapshot TO record;";
+		self.pool.get_conn().await?.query_drop(stm).await?;
+		Ok(())
+	}
+
+	fn classify_error(&self, err: &anyhow::Error) -> Option<BenchError> {
+		let err = err.chain().find_map(|e| e.downcast_ref::<mysql_async::Error>())?;
+		let mysql_async::Error::Server(err) = err else {
+			// Driver and IO errors mean the connection was lost
+			return err.is_fatal().then_some(BenchError::Transient);
+		};
+		match err.code {
+			// Deadlock found when trying to get lock
+			1213 => Some(BenchError::Conflict),
+			// Lock wait timeout, or maximum statement execution time exceeded
+			1205 | 3024 => Some(BenchError::Timeout),
+			// Unknown table, or unknown key when dropping an index
+			1051 | 1091 | 1146 => Some(BenchError::NotFound),
+			// Duplicate entry, foreign key, not null, or check constraint
+			1062 | 1451 | 1452 | 1048 | 3819 => Some(BenchError::ConstraintViolation),
+			// Too many connections, or server shutdown in progress
+			1040 | 1053 => Some(BenchError::Transient),
+			_ => None,
+		}
+	}
+
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan(scan, ctx).await
+	}
+
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan(scan, ctx).await
+	}
+
+	async fn scan_ids(&self, scan: &Scan) -> Result<Vec<String>> {
+		// A full-text index is required to run a MATCH query
+		if let Some(index) = &scan.with_index
+			&& let Some(kind) = &index.index_type
+			&& kind == "fulltext"
+		{
+			bail!(BenchError::Unsupported);
+		}
+		// Extract parameters
+		let s = scan.start.map(|s| format!("OFFSET {}", s)).unwrap_or_default();
+		let l = scan.limit.map(|s| format!("LIMIT {}", s)).unwrap_or_default();
+		let c = MySqlDialect::filter_clause(scan)?;
+		let o = MySqlDialect::order_by_clause(scan)?;
+		// Fetch the ids as text, whatever the key type
+		let stm = format!("SELECT CAST(id AS CHAR) FROM record {c} {o} {l} {s}");
+		Ok(self.pool.get_conn().await?.query(stm).await?)
+	}
+
+	async fn scan_page_u32(&self, start: PageStart<u32>, limit: usize) -> Result<Option<u32>> {
+		let last: Option<u64> = self.scan_page(start.map(|k| k as u64), limit).await?;
+		Ok(last.map(|k| k as u32))
+	}
+
+	async fn scan_page_string(
+		&self,
+		start: PageStart<String>,
+		limit: usize,
+	) -> Result<Option<String>> {
+		self.scan_page(start, limit).await
+	}
+
+	async fn batch_create_u32(
+		&self,
+		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_create(key_vals.map(|(k, v)| (k as u64, v)).collect()).await
+	}
+
+	async fn batch_create_string(
+		&self,
+		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_create(key_vals.collect()).await
+	}
+
+	async fn batch_load_u32(
+		&self,
+		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_load(key_vals.map(|(k, v)| (k as u64, v)).collect()).await
+	}
+
+	async fn batch_load_string(
+		&self,
+		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_load(key_vals.collect()).await
+	}
+
+	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
+		self.batch_read("record", keys.map(|k| k as u64).collect()).await
+	}
+
+	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
+		self.batch_read("record", keys.collect()).await
+	}
+
+	async fn batch_update_u32(
+		&self,
+		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_update(key_vals.map(|(k, v)| (k as u64, v)).collect()).await
+	}
+
+	async fn batch_update_string(
+		&self,
+		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_update(key_vals.collect()).await
+	}
+
+	async fn batch_delete_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
+		self.batch_delete(keys.map(|k| k as u64).collect()).await
+	}
+
+	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
+		self.batch_delete(keys.collect()).await
+	}
+
+	async fn batch_create_generated(&self, vals: Vec<BenchValue>) -> Result<Vec<GeneratedKey>> {
+		let (columns, placeholders) = MySqlDialect::create_clause(&self.columns);
+		let values =
+			(0..vals.len()).map(|_| format!("({placeholders})")).collect::<Vec<_>>().join(", ");
+		let stm = format!("INSERT INTO {GENERATED_TABLE} ({columns}) VALUES {values}");
+		let mut params: Vec<mysql_async::Value> = Vec::new();
+		for val in vals.iter() {
+			if let BenchValue::Object(map) = val {
+				for (name, column_type) in &self.columns.0 {
+					if let Some(v) = map.iter().find(|(k, _)| k == name).map(|(_, v)| v) {
+						params.push(bench_to_mysql_value(column_type, v)?);
+					}
+				}
+			}
+		}
+		if F::INSERT_RETURNING {
+			// The ids assigned to each row are returned directly
+			let stm = format!("{stm} RETURNING id");
+			let ids: Vec<u64> = self.pool.get_conn().await?.exec(stm, params).await?;
+			assert_eq!(ids.len(), vals.len());
+			return Ok(ids.into_iter().map(|id| GeneratedKey::Integer(id as i64)).collect());
+		}
+		let mut conn = self.pool.get_conn().await?;
+		let res = conn.exec_iter(stm, params).await?;
+		assert_eq!(res.affected_rows(), vals.len() as u64);
+		// Otherwise the rows of a multi-row insert with a known row count
+		// are assigned consecutive ids, and the id of the first
+		// row is reported as the last insert id
+		let first = res.last_insert_id().ok_or_else(|| anyhow!("Missing last insert id"))?;
+		Ok((first..first + vals.len() as u64).map(|id| GeneratedKey::Integer(id as i64)).collect())
+	}
+
+	async fn batch_read_generated(&self, keys: Vec<GeneratedKey>) -> Result<()> {
+		let ids = keys
+			.iter()
+			.map(|k| match k {
+				GeneratedKey::Integer(id) => Ok(*id as u64),
+				GeneratedKey::String(_) => Err(anyhow!("Unexpected generated key {k:?}")),
+			})
+			.collect::<Result<Vec<u64>>>()?;
+		self.batch_read(GENERATED_TABLE, ids).await
+	}
+}
+
+impl<F: Flavour> MysqlCommonClient<F> {
+	/// The SQL text of a single-record operation
+	fn crud_sql(&self, op: Crud) -> String {
+		match op {
+			Crud::Create => {
+				let (columns, placeholders) = MySqlDialect::create_clause(&self.columns);
+				format!("INSERT INTO record (id, {columns}) VALUES (?, {placeholders})")
+			}
+			Crud::Read => "SELECT * FROM record WHERE id=?".to_string(),
+			Crud::Update => {
+				let set = MySqlDialect::update_clause(&self.columns);
+				format!("UPDATE record SET {set} WHERE id=?")
+			}
+			Crud::Delete => "DELETE FROM record WHERE id=?".to_string(),
+		}
+	}
+
+	fn consume(&self, mut row: Row) -> Result<BenchValue> {
+		let mut val: Vec<(String, BenchValue)> = Vec::with_capacity(row.columns().len());
+		for (i, c) in row.columns().iter().enumerate() {
+			let name = c.name_str().to_string();
+			let column_type = self.columns.0.iter().find(|(n, _)| n == &name).map(|(_, t)| *t);
+			let bv = match c.column_type() {
+				consts::ColumnType::MYSQL_TYPE_TINY => {
+					let v: Option<bool> = row.take(i);
+					match v {
+						Some(b) => BenchValue::Bool(b),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_SHORT => {
+					let v: Option<bool> = row.take(i);
+					match v {
+						Some(b) => BenchValue::Bool(b),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_VARCHAR
+				| consts::ColumnType::MYSQL_TYPE_VAR_STRING
+				| consts::ColumnType::MYSQL_TYPE_STRING => {
+					let v: Option<String> = row.take(i);
+					match (v, column_type) {
+						(Some(s), Some(ColumnType::Uuid)) => BenchValue::Uuid(parse_uuid(&s)?),
+						(Some(s), _) => BenchValue::String(s),
+						(None, _) => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_LONG => {
+					let v: Option<i32> = row.take(i);
+					match v {
+						Some(i) => BenchValue::Int(i as i64),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_LONGLONG => {
+					let v: Option<i64> = row.take(i);
+					match v {
+						Some(i) => BenchValue::Int(i),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_FLOAT => {
+					let v: Option<f32> = row.take(i);
+					match v {
+						Some(f) => BenchValue::Float(f as f64),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_DOUBLE => {
+					let v: Option<f64> = row.take(i);
+					match v {
+						Some(f) => BenchValue::Float(f),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_DECIMAL
+				| consts::ColumnType::MYSQL_TYPE_NEWDECIMAL => {
+					let v: Option<String> = row.take(i);
+					match v {
+						Some(s) => BenchValue::Decimal(parse_decimal(&s)?),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_BLOB
+				| consts::ColumnType::MYSQL_TYPE_TINY_BLOB
+				| consts::ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+				| consts::ColumnType::MYSQL_TYPE_LONG_BLOB => {
+					// MySQL and MariaDB return both binary BLOB and TEXT columns
+					// with the MYSQL_TYPE_BLOB / *_BLOB variants. Disambiguate
+					// using the schema-declared `ColumnType`: bytes-typed
+					// columns stay as `BenchValue::Bytes`, anything else (TEXT,
+					// VARCHAR-as-text, etc.) is decoded as a UTF-8 string.
+					let v: Option<Vec<u8>> = row.take(i);
+					match (v, column_type) {
+						(Some(b), Some(ColumnType::Bytes)) => BenchValue::Bytes(b),
+						(Some(b), _) => match String::from_utf8(b) {
+							Ok(s) => BenchValue::String(s),
+							Err(e) => BenchValue::Bytes(e.into_bytes()),
+						},
+						(None, _) => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_JSON => {
+					let v: Option<serde_json::Value> = row.take(i);
+					match v {
+						Some(j) => BenchValue::from(&j),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_TIMESTAMP
+				| consts::ColumnType::MYSQL_TYPE_DATETIME => {
+					let v: Option<NaiveDateTime> = row.take(i);
+					match v {
+						Some(dt) => BenchValue::DateTime(Utc.from_utc_datetime(&dt)),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_DATE | consts::ColumnType::MYSQL_TYPE_NEWDATE => {
+					let v: Option<NaiveDate> = row.take(i);
+					match v {
+						Some(d) => BenchValue::String(d.to_string()),
+						None => BenchValue::Null,
+					}
+				}
+				consts::ColumnType::MYSQL_TYPE_TIME => {
+					let v: Option<NaiveTime> = row.take(i);
+					match v {
+						Some(t) => BenchValue::String(t.to_string()),
+						None => BenchValue::Null,
+					}
+				}
+				c => {
+					todo!("Not yet implemented {c:?}")
+				}
+			};
+			val.push((name, bv));
+		}
+		Ok(BenchValue::Object(val))
+	}
+
+	async fn create<T>(&self, key: T, val: BenchValue) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		let obj = val.into_object()?;
+		let mut params: Vec<mysql_async::Value> = vec![key.to_value()];
+		for (name, column_type) in &self.columns.0 {
+			let v = obj
+				.iter()
+				.find(|(k, _)| k == name)
+				.map(|(_, v)| v)
+				.ok_or_else(|| anyhow!("Missing value for column {name}"))?;
+			params.push(bench_to_mysql_value(column_type, v)?);
+		}
+		let mut conn = self.pool.get_conn().await?;
+		let _: Vec<Row> = conn.exec(self.crud_sql(Crud::Create), params).await?;
+		Ok(())
+	}
+
+	async fn read<T>(&self, key: T) -> Result<BenchValue>
+	where
+		T: ToValue + Sync,
+	{
+		let mut conn = self.pool.get_conn().await?;
+		let res: Vec<Row> = conn.exec(self.crud_sql(Crud::Read), (key.to_value(),)).await?;
+		drop(conn);
+		assert_eq!(res.len(), 1);
+		Ok(black_box(self.consume(res.into_iter().next().unwrap())?))
+	}
+
+	async fn scan_page<T>(&self, start: PageStart<T>, limit: usize) -> Result<Option<T>>
+	where
+		T: ToValue + FromValue + Sync,
+	{
+		let res: Vec<Row> = match start {
+			PageStart::Offset(s) => {
+				let stm = format!("SELECT * FROM record ORDER BY id LIMIT {limit} OFFSET {s}");
+				self.pool.get_conn().await?.query(stm).await?
+			}
+			PageStart::After(None) => {
+				let stm = format!("SELECT * FROM record ORDER BY id LIMIT {limit}");
+				self.pool.get_conn().await?.query(stm).await?
+			}
+			PageStart::After(Some(key)) => {
+				let stm = format!("SELECT * FROM record WHERE id > ? ORDER BY id LIMIT {limit}");
+				self.pool.get_conn().await?.exec(stm, (key.to_value(),)).await?
+			}
+		};
+		let last = res.last().and_then(|row| row.get("id"));
+		for row in res {
+			materialize::consume(row, |row| self.consume(row))?;
+		}
+		Ok(last)
+	}
+
+	async fn update<T>(&self, key: T, val: BenchValue) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		let obj = val.into_object()?;
+		let mut params: Vec<mysql_async::Value> = Vec::new();
+		for (name, column_type) in &self.columns.0 {
+			let v = obj
+				.iter()
+				.find(|(k, _)| k == name)
+				.map(|(_, v)| v)
+				.ok_or_else(|| anyhow!("Missing value for column {name}"))?;
+			params.push(bench_to_mysql_value(column_type, v)?);
+		}
+		params.push(key.to_value());
+		let mut conn = self.pool.get_conn().await?;
+		let _: Vec<Row> = conn.exec(self.crud_sql(Crud::Update), params).await?;
+		Ok(())
+	}
+
+	async fn delete<T>(&self, key: T) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		let mut conn = self.pool.get_conn().await?;
+		let _: Vec<Row> = conn.exec(self.crud_sql(Crud::Delete), (key.to_value(),)).await?;
+		Ok(())
+	}
+
+	async fn delete_range<T>(&self, from: T, to: T) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		let stm = "DELETE FROM record WHERE id >= ? AND id < ?";
+		self.pool.get_conn().await?.exec_drop(stm, (from.to_value(), to.to_value())).await?;
+		Ok(())
+	}
+
+	async fn scan(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// A full-text index is required to run a MATCH query
+		if ctx == ScanContext::WithoutIndex
+			&& let Some(index) = &scan.with_index
+			&& let Some(kind) = &index.index_type
+			&& kind == "fulltext"
+		{
+			bail!(BenchError::Unsupported);
+		}
+		// Extract parameters
+		let s = scan.start.map(|s| format!("OFFSET {}", s)).unwrap_or_default();
+		let l = scan.limit.map(|s| format!("LIMIT {}", s)).unwrap_or_default();
+		let c = MySqlDialect::filter_clause(scan)?;
+		let o = MySqlDialect::order_by_clause(scan)?;
+		let h = MySqlDialect::hint_clause(scan, ctx)?;
+		let p = scan.projection()?;
+		// Perform the relevant projection scan type
+		match p {
+			Projection::Id => {
+				let stm = format!("SELECT id FROM record {h} {c} {o} {l} {s}");
+				let res: Vec<Row> = self.pool.get_conn().await?.query(stm).await?;
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					black_box(self.consume(v).unwrap());
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Full => {
+				let stm = format!("SELECT * FROM record {h} {c} {o} {l} {s}");
+				let res: Vec<Row> = self.pool.get_conn().await?.query(stm).await?;
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					materialize::consume(v, |v| self.consume(v))?;
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Field(path) => {
+				let f = MySqlDialect::field_path(&path);
+				let stm = format!("SELECT id, {f} AS field FROM record {h} {c} {o} {l} {s}");
+				let res: Vec<Row> = self.pool.get_conn().await?.query(stm).await?;
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					black_box(self.consume(v).unwrap());
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Fields(paths) => {
+				let f = paths
+					.iter()
+					.map(|p| MySqlDialect::field_path(p))
+					.collect::<Vec<_>>()
+					.join(", ");
+				let stm = format!("SELECT id, {f} FROM record {h} {c} {o} {l} {s}");
+				let res: Vec<Row> = self.pool.get_conn().await?.query(stm).await?;
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					black_box(self.consume(v).unwrap());
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Count => {
+				let stm =
+					format!("SELECT COUNT(*) FROM (SELECT id FROM record {h} {c} {l} {s}) AS T");
+				let res: Vec<Row> = self.pool.get_conn().await?.query(stm).await?;
+				let count: i64 = res.first().unwrap().get(0).unwrap();
+				Ok(count as usize)
+			}
+		}
+	}
+
+	async fn batch_create<T>(&self, key_vals: Vec<(T, BenchValue)>) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		if key_vals.is_empty() {
+			return Ok(());
+		}
+		let (columns, placeholders) = MySqlDialect::create_clause(&self.columns);
+		let values = (0..key_vals.len())
+			.map(|_| format!("(?, {placeholders})"))
+			.collect::<Vec<_>>()
+			.join(", ");
+		let stm = format!("INSERT INTO record (id, {columns}) VALUES {values}");
+		let mut params: Vec<mysql_async::Value> = Vec::new();
+		for (key, val) in key_vals.iter() {
+			params.push(key.to_value());
+			if let BenchValue::Object(map) = val {
+				for (name, column_type) in &self.columns.0 {
+					if let Some(v) = map.iter().find(|(k, _)| k == name).map(|(_, v)| v) {
+						params.push(bench_to_mysql_value(column_type, v)?);
+					}
+				}
+			}
+		}
+		let mut conn = self.pool.get_conn().await?;
+		let res = conn.exec_iter(stm, params).await?;
+		assert_eq!(res.affected_rows(), key_vals.len() as u64);
+		Ok(())
+	}
+
+	async fn batch_load<T>(&self, key_vals: Vec<(T, BenchValue)>) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		if key_vals.is_empty() {
+			return Ok(());
+		}
+		// Encode the rows as tab-separated lines, in the default `LOAD DATA` format
+		let mut data = Vec::new();
+		for (key, val) in key_vals.iter() {
+			infile_field(&mut data, &key.to_value());
+			for (name, column_type) in &self.columns.0 {
+				let v = val
+					.get_field(name)
+					.ok_or_else(|| anyhow!("Missing value for column {name}"))?;
+				data.push(b'\t');
+				infile_field(&mut data, &bench_to_mysql_value(column_type, v)?);
+			}
+			data.push(b'\n');
+		}
+		let columns = self
+			.columns
+			.0
+			.iter()
+			.map(|(name, _)| MySqlDialect::escape_field(name.clone()))
+			.collect::<Vec<_>>()
+			.join(", ");
+		let stm = format!("LOAD DATA LOCAL INFILE 'batch' INTO TABLE record (id, {columns})");
+		let mut conn = self.pool.get_conn().await?;
+		// Stream the encoded rows in place of the file named in the statement
+		conn.set_infile_handler(async move {
+			Ok(futures::stream::once(async move { Ok(Bytes::from(data)) }).boxed())
+		});
+		conn.query_drop(stm).await?;
+		// Duplicate keys are skipped with a warning rather than failing the load
+		assert_eq!(conn.affected_rows(), key_vals.len() as u64);
+		Ok(())
+	}
+
+	async fn batch_read<T>(&self, table: &str, keys: Vec<T>) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		if keys.is_empty() {
+			return Ok(());
+		}
+		// Store the record ids
+		let params: Vec<mysql_async::Value> = keys.iter().map(|k| k.to_value()).collect();
+		// Build the IN clause
+		let ids = keys.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+		// Build and execute the SELECT statement
+		let stm = format!("SELECT * FROM {table} WHERE id IN ({ids})");
+		let res: Vec<Row> = self.pool.get_conn().await?.exec(stm, params).await?;
+		assert_eq!(res.len(), keys.len());
+		for row in res {
+			materialize::consume(row, |row| self.consume(row))?;
+		}
+		Ok(())
+	}
+
+	async fn batch_update<T>(&self, key_vals: Vec<(T, BenchValue)>) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		if key_vals.is_empty() {
+			return Ok(());
+		}
+		let columns = MySqlDialect::escaped_columns(&self.columns);
+		let set = columns
+			.iter()
+			.map(|col| {
+				let when_clauses =
+					(0..key_vals.len()).map(|_| "WHEN id = ? THEN ?").collect::<Vec<_>>().join(" ");
+				format!("{col} = CASE {when_clauses} ELSE {col} END")
+			})
+			.collect::<Vec<_>>()
+			.join(", ");
+		let ids = key_vals.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+		let stm = format!("UPDATE record SET {set} WHERE id IN ({ids})");
+		let mut params: Vec<mysql_async::Value> = Vec::new();
+		for (name, column_type) in &self.columns.0 {
+			for (key, val) in &key_vals {
+				params.push(key.to_value());
+				if let BenchValue::Object(map) = val
+					&& let Some(v) = map.iter().find(|(k, _)| k == name).map(|(_, v)| v)
+				{
+					params.push(bench_to_mysql_value(column_type, v)?);
+				}
+			}
+		}
+		for (key, _) in &key_vals {
+			params.push(key.to_value());
+		}
+		let mut conn = self.pool.get_conn().await?;
+		let res = conn.exec_iter(stm, params).await?;
+		assert_eq!(res.affected_rows(), key_vals.len() as u64);
+		Ok(())
+	}
+
+	async fn batch_delete<T>(&self, keys: Vec<T>) -> Result<()>
+	where
+		T: ToValue + Sync,
+	{
+		if keys.is_empty() {
+			return Ok(());
+		}
+		// Store the record ids
+		let params: Vec<mysql_async::Value> = keys.iter().map(|k| k.to_value()).collect();
+		// Build the IN clause
+		let ids = keys.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+		// Build and execute the DELETE statement
+		let stm = format!("DELETE FROM record WHERE id IN ({ids})");
+		let mut conn = self.pool.get_conn().await?;
+		let res = conn.exec_iter(stm, params).await?;
+		assert_eq!(res.affected_rows(), keys.len() as u64);
+		Ok(())
+	}
+}
+
+/// Appends a value to a `LOAD DATA` row, escaping the
+/// characters the default field and line format treats specially
+fn infile_field(data: &mut Vec<u8>, value: &mysql_async::Value) {
+	use mysql_async::Value;
+	match value {
+		Value::NULL => data.extend_from_slice(b"\\N"),
+		Value::Int(i) => data.extend_from_slice(i.to_string().as_bytes()),
+		Value::UInt(u) => data.extend_from_slice(u.to_string().as_bytes()),
+		Value::Float(f) => data.extend_from_slice(f.to_string().as_bytes()),
+		Value::Double(d) => data.extend_from_slice(d.to_string().as_bytes()),
+		Value::Date(y, mo, d, h, mi, s, us) => data.extend_from_slice(
+			format!("{y:04}-{mo:02}-{d:02} {h:02}:{mi:02}:{s:02}.{us:06}").as_bytes(),
+		),
+		Value::Time(neg, d, h, mi, s, us) => data.extend_from_slice(
+			format!(
+				"{}{}:{mi:02}:{s:02}.{us:06}",
+				if *neg {
+					"-"
+				} else {
+					""
+				},
+				d * 24 + *h as u32
+			)
+			.as_bytes(),
+		),
+		Value::Bytes(bytes) => {
+			for b in bytes {
+				match b {
+					b'\\' => data.extend_from_slice(b"\\\\"),
+					b'\t' => data.extend_from_slice(b"\\t"),
+					b'\n' => data.extend_from_slice(b"\\n"),
+					b'\r' => data.extend_from_slice(b"\\r"),
+					0 => data.extend_from_slice(b"\\0"),
+					b => data.push(*b),
+				}
+			}
+		}
+	}
+}
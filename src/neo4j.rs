@@ -1,9 +1,10 @@
 #![cfg(feature = "neo4j")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::dialect::Neo4jDialect;
 use crate::docker::DockerParams;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
 use crate::memory::Config;
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
@@ -18,6 +19,7 @@ use neo4rs::BoltType;
 use neo4rs::ConfigBuilder;
 use neo4rs::Graph;
 use neo4rs::query;
+use std::collections::BTreeMap;
 use std::hint::black_box;
 
 /// Key separator matching the legacy `flatten_json_object` neo4j config.
@@ -28,38 +30,71 @@ const ARRAY_INDEX_END: &str = "";
 
 pub const DEFAULT: &str = "127.0.0.1:7687";
 
-pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+/// The Cypher runtime used for scans, `parallel` requiring the Enterprise image
+const RUNTIME_VAR: &str = "CRUD_BENCH_NEO4J_RUNTIME";
+/// The number of parallel runtime workers, all cores by default
+const WORKERS_VAR: &str = "CRUD_BENCH_NEO4J_PARALLEL_WORKERS";
+
+/// The Cypher runtime requested for scans, if any
+fn runtime() -> Option<String> {
+	std::env::var(RUNTIME_VAR).ok().filter(|v| !v.is_empty()).map(|v| v.to_lowercase())
+}
+
+/// Whether scans run on the parallel runtime, which only ships with Neo4j Enterprise
+fn parallel() -> bool {
+	runtime().as_deref() == Some("parallel")
+}
+
+/// The server settings the container is started with, recorded in the benchmark metadata.
+pub(crate) fn server_options(options: &Benchmark) -> BTreeMap<String, String> {
+	let mut settings = BTreeMap::new();
 	// Per-tx fsync control is not exposed; checkpoint cadence is the closest knob.
-	let checkpoint = match options.sync {
-		true => "-e NEO4J_dbms_checkpoint_interval_time=1s -e NEO4J_dbms_checkpoint_interval_tx=1",
-		false => {
-			"-e NEO4J_dbms_checkpoint_interval_time=1s -e NEO4J_dbms_checkpoint_interval_tx=10000"
-		}
+	let interval = match options.sync {
+		true => "1",
+		false => "10000",
 	};
+	settings.insert("dbms.checkpoint.interval.time".to_string(), "1s".to_string());
+	settings.insert("dbms.checkpoint.interval.tx".to_string(), interval.to_string());
 	// JVM heap and pagecache default to a few hundred MB; on a heavy benchmark this
 	// triggers GC thrash long before disk I/O. When optimised, split the recommended
 	// cache budget between heap (1/3) and pagecache (2/3).
-	let memory = match options.optimised {
-		true => {
-			let cache_gb = Config::new().cache_gb.max(2);
-			let heap_gb = (cache_gb / 3).max(1);
-			let pagecache_gb = cache_gb - heap_gb;
-			format!(
-				"-e NEO4J_server_memory_heap_initial__size={heap_gb}g \
-				 -e NEO4J_server_memory_heap_max__size={heap_gb}g \
-				 -e NEO4J_server_memory_pagecache_size={pagecache_gb}g \
-				 -e NEO4J_db_memory_transaction_total_max=0 \
-				 -e NEO4J_db_memory_transaction_max=0"
-			)
-		}
-		false => String::new(),
+	if options.optimised {
+		let cache_gb = Config::new().cache_gb.max(2);
+		let heap_gb = (cache_gb / 3).max(1);
+		let pagecache_gb = cache_gb - heap_gb;
+		settings.insert("server.memory.heap.initial_size".to_string(), format!("{heap_gb}g"));
+		settings.insert("server.memory.heap.max_size".to_string(), format!("{heap_gb}g"));
+		settings.insert("server.memory.pagecache.size".to_string(), format!("{pagecache_gb}g"));
+		settings.insert("db.memory.transaction.total.max".to_string(), "0".to_string());
+		settings.insert("db.memory.transaction.max".to_string(), "0".to_string());
+	}
+	// The worker limit is an Enterprise setting, which Community rejects at startup
+	if parallel() {
+		let workers = std::env::var(WORKERS_VAR).unwrap_or_else(|_| "0".to_string());
+		settings.insert("server.cypher.parallel.worker_limit".to_string(), workers);
+	}
+	settings
+}
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	// Settings map to environment variables, with `_` escaped as `__` and `.` as `_`
+	let settings = server_options(options)
+		.iter()
+		.map(|(key, value)| {
+			let key = key.replace('_', "__").replace('.', "_");
+			format!(" -e NEO4J_{key}={value}")
+		})
+		.collect::<String>();
+	let (image, license) = match parallel() {
+		true => ("neo4j:enterprise", " -e NEO4J_ACCEPT_LICENSE_AGREEMENT=yes"),
+		false => ("neo4j", ""),
 	};
 	DockerParams {
-		image: "neo4j",
+		image,
 		pre_args: format!(
 			"--ulimit nofile=65536:65536 \
 			 -p 127.0.0.1:7474:7474 -p 127.0.0.1:7687:7687 \
-			 -e NEO4J_AUTH=none {checkpoint} {memory}"
+			 -e NEO4J_AUTH=none{license}{settings}"
 		),
 		post_args: "".to_string(),
 	}
@@ -67,6 +102,7 @@ pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 
 pub(crate) struct Neo4jClientProvider {
 	graph: Graph,
+	runtime: Option<String>,
 }
 
 impl BenchmarkEngine<Neo4jClient> for Neo4jClientProvider {
@@ -74,6 +110,8 @@ impl BenchmarkEngine<Neo4jClient> for Neo4jClientProvider {
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
 		// Get the custom endpoint if specified
 		let url = options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned();
+		// TLS is chosen by the `neo4j+s://` and `neo4j+ssc://` schemes of the endpoint
+		options.security.without_tls_options("Neo4j")?;
 		// Create a new client with a connection pool.
 		// The Neo4j client supports connection pooling
 		// and the recommended advice is to use a single
@@ -86,26 +124,46 @@ impl BenchmarkEngine<Neo4jClient> for Neo4jClientProvider {
 		let config = ConfigBuilder::default()
 			.uri(url)
 			.db("neo4j")
-			.user("neo4j")
-			.password("neo4j")
+			.user(options.security.username("neo4j"))
+			.password(options.security.password("neo4j"))
 			.fetch_size(500)
 			.max_connections(options.clients as usize)
 			.build()?;
 		// Create the client
 		Ok(Self {
 			graph: Graph::connect(config).await?,
+			runtime: runtime(),
 		})
 	}
+	/// Checks the server completes the Bolt handshake and runs a query, as connecting
+	/// the clients only clones the connection pool
+	async fn ready(&self) -> Result<()> {
+		self.graph.execute(query("RETURN 1")).await?.next().await?;
+		Ok(())
+	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<Neo4jClient> {
 		Ok(Neo4jClient {
 			graph: self.graph.clone(),
+			runtime: self.runtime.clone(),
 		})
 	}
 }
 
 pub(crate) struct Neo4jClient {
 	graph: Graph,
+	runtime: Option<String>,
+}
+
+impl Neo4jClient {
+	/// Prefixes a `MATCH` scan with the requested Cypher runtime. Full-text scans call a
+	/// procedure, which the parallel runtime rejects, so these keep the default runtime.
+	fn scan_statement(&self, stm: String) -> String {
+		match &self.runtime {
+			Some(runtime) => format!("CYPHER runtime={runtime} {stm}"),
+			None => stm,
+		}
+	}
 }
 
 impl BenchmarkClient for Neo4jClient {
@@ -118,6 +176,26 @@ impl BenchmarkClient for Neo4jClient {
 		Ok(())
 	}
 
+	fn classify_error(&self, err: &anyhow::Error) -> Option<BenchError> {
+		let err = err.chain().find_map(|e| e.downcast_ref::<neo4rs::Error>())?;
+		match err {
+			neo4rs::Error::IOError {
+				..
+			}
+			| neo4rs::Error::ConnectionError => Some(BenchError::Transient),
+			neo4rs::Error::Neo4j(e) => match e.code() {
+				"Neo.ClientError.Schema.ConstraintValidationFailed" => {
+					Some(BenchError::ConstraintViolation)
+				}
+				"Neo.TransientError.Transaction.DeadlockDetected" => Some(BenchError::Conflict),
+				"Neo.ClientError.Transaction.TransactionTimedOut" => Some(BenchError::Timeout),
+				_ if e.kind() == neo4rs::Neo4jErrorKind::Transient => Some(BenchError::Transient),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create(key.into(), val).await
 	}
@@ -134,6 +212,17 @@ impl BenchmarkClient for Neo4jClient {
 		self.read(key).await
 	}
 
+	async fn read_secondary(&self, field: &str, value: String) -> Result<usize> {
+		let stm = format!("MATCH (r:Record) WHERE r.{field} = $value RETURN r");
+		let mut res = self.graph.execute(query(&stm).param("value", value)).await?;
+		let mut count = 0;
+		while let Some(v) = res.next().await? {
+			black_box(v);
+			count += 1;
+		}
+		Ok(count)
+	}
+
 	async fn update_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.update(key, val).await
 	}
@@ -157,7 +246,11 @@ impl BenchmarkClient for Neo4jClient {
 		// or multi-valued indexes; Neo4j has no such facility, so bail and let
 		// the bench framework skip this scan.
 		if spec.fields.iter().any(|f| f.contains(".*")) {
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
+		}
+		// Postgres access methods and partial indexes have no Neo4j equivalent
+		if spec.method().is_some() || spec.filter.is_some() {
+			bail!(BenchError::Unsupported);
 		}
 		// Get the fields
 		let fields = spec.fields.iter().map(|f| format!("r.{f}")).collect::<Vec<_>>().join(", ");
@@ -166,6 +259,10 @@ impl BenchmarkClient for Neo4jClient {
 			Some(kind) if kind == "fulltext" => {
 				format!("CREATE FULLTEXT INDEX {name} FOR (r:Record) ON EACH [{fields}]")
 			}
+			// Unique indexes are created through a constraint of the same name
+			_ if spec.unique.unwrap_or(false) => {
+				format!("CREATE CONSTRAINT {name} FOR (r:Record) REQUIRE ({fields}) IS UNIQUE")
+			}
 			_ => {
 				format!("CREATE INDEX {name} FOR (r:Record) ON ({fields})")
 			}
@@ -181,6 +278,9 @@ impl BenchmarkClient for Neo4jClient {
 	}
 
 	async fn drop_index(&self, name: &str) -> Result<()> {
+		// The index behind a unique constraint is dropped with the constraint
+		let stmt = format!("DROP CONSTRAINT {name} IF EXISTS");
+		self.graph.execute(query(&stmt)).await?.next().await?;
 		let stmt = format!("DROP INDEX {name} IF EXISTS");
 		self.graph.execute(query(&stmt)).await?.next().await?;
 		Ok(())
@@ -501,7 +601,11 @@ impl Neo4jClient {
 			&& let Some(kind) = &index.index_type
 			&& kind == "fulltext"
 		{
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
+		}
+		// Index hints are not supported
+		if ctx == ScanContext::WithHint {
+			bail!(BenchError::Unsupported);
 		}
 		// Ordered full-text scans are not supported
 		if scan.order_by.is_some()
@@ -509,7 +613,7 @@ impl Neo4jClient {
 			&& let Some(kind) = &index.index_type
 			&& kind == "fulltext"
 		{
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		// Extract parameters
 		let s = scan.start.map(|s| format!("SKIP {s}")).unwrap_or_default();
@@ -532,7 +636,9 @@ impl Neo4jClient {
 					true => format!(
 						"CALL db.index.fulltext.queryNodes('{n}', '{c}') YIELD node as r WITH r {o} {s} {l} RETURN r.id"
 					),
-					false => format!("MATCH (r) {c} WITH r {o} {s} {l} RETURN r.id"),
+					false => {
+						self.scan_statement(format!("MATCH (r) {c} WITH r {o} {s} {l} RETURN r.id"))
+					}
 				};
 				let mut res = self.graph.execute(query(&stm)).await.unwrap();
 				let mut count = 0;
@@ -547,7 +653,51 @@ impl Neo4jClient {
 					true => format!(
 						"CALL db.index.fulltext.queryNodes('{n}', '{c}') YIELD node as r WITH r {o} {s} {l} RETURN r"
 					),
-					false => format!("MATCH (r) {c} WITH r {o} {s} {l} RETURN r"),
+					false => {
+						self.scan_statement(format!("MATCH (r) {c} WITH r {o} {s} {l} RETURN r"))
+					}
+				};
+				let mut res = self.graph.execute(query(&stm)).await.unwrap();
+				let mut count = 0;
+				while let Ok(Some(v)) = res.next().await {
+					materialize::consume(v, |v| {
+						bolt_map_payload_to_object(v.get::<BoltNode>("r")?.properties)
+					})?;
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Field(path) => {
+				let f = Neo4jDialect::field_path(&path);
+				let stm = match fts {
+					true => format!(
+						"CALL db.index.fulltext.queryNodes('{n}', '{c}') YIELD node as r WITH r {o} {s} {l} RETURN r.id, r.{f}"
+					),
+					false => self.scan_statement(format!(
+						"MATCH (r) {c} WITH r {o} {s} {l} RETURN r.id, r.{f}"
+					)),
+				};
+				let mut res = self.graph.execute(query(&stm)).await.unwrap();
+				let mut count = 0;
+				while let Ok(Some(v)) = res.next().await {
+					black_box(v);
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Fields(paths) => {
+				let f = paths
+					.iter()
+					.map(|p| format!("r.{}", Neo4jDialect::field_path(p)))
+					.collect::<Vec<_>>()
+					.join(", ");
+				let stm = match fts {
+					true => format!(
+						"CALL db.index.fulltext.queryNodes('{n}', '{c}') YIELD node as r WITH r {o} {s} {l} RETURN r.id, {f}"
+					),
+					false => self.scan_statement(format!(
+						"MATCH (r) {c} WITH r {o} {s} {l} RETURN r.id, {f}"
+					)),
 				};
 				let mut res = self.graph.execute(query(&stm)).await.unwrap();
 				let mut count = 0;
@@ -562,7 +712,9 @@ impl Neo4jClient {
 					true => format!(
 						"CALL db.index.fulltext.queryNodes('{n}', '{c}') YIELD node as r WITH r {s} {l} RETURN count(r) as count"
 					),
-					false => format!("MATCH (r) {c} WITH r {s} {l} RETURN count(r) as count"),
+					false => self.scan_statement(format!(
+						"MATCH (r) {c} WITH r {s} {l} RETURN count(r) as count"
+					)),
 				};
 				let mut res = self.graph.execute(query(&stm)).await.unwrap();
 				let count: i64 = res.next().await.unwrap().unwrap().get("count").unwrap();
@@ -611,7 +763,10 @@ impl Neo4jClient {
 		// Execute the Cypher query
 		let mut res = self.graph.execute(query(cypher).param("ids", bolt)).await?;
 		let mut n = 0;
-		while res.next().await?.is_some() {
+		while let Some(v) = res.next().await? {
+			materialize::consume(v, |v| {
+				bolt_map_payload_to_object(v.get::<BoltNode>("r")?.properties)
+			})?;
 			n += 1;
 		}
 		// Check the response
@@ -0,0 +1,388 @@
+#![cfg(feature = "opensearch")]
+
+use crate::dialect::OpenSearchDialect;
+use crate::docker::DockerParams;
+use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
+use crate::memory::Config;
+use crate::security::Security;
+use crate::value::BenchValue;
+use crate::valueprovider::Columns;
+use crate::{Benchmark, KeyType, Projection, Scan};
+use anyhow::{Result, anyhow, bail};
+use reqwest::{Certificate, Client, RequestBuilder, StatusCode};
+use serde_json::{Value, json};
+use std::hint::black_box;
+
+pub const DEFAULT: &str = "http://127.0.0.1:9200";
+
+/// The index holding the benchmark records
+const INDEX: &str = "record";
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	// The JVM heap defaults to 1GB, which is exhausted by the field data and
+	// segment buffers of a large index long before the disk is the bottleneck.
+	// When optimised, give the heap half of the recommended cache budget, and
+	// leave the rest to the page cache serving the Lucene segments. The heap
+	// stays below 32GB so that the JVM keeps using compressed pointers. Docker
+	// arguments are split on spaces, so only the maximum heap is raised here.
+	let memory = match options.optimised {
+		true => {
+			let heap_gb = (Config::new().cache_gb / 2).clamp(1, 31);
+			format!("-e OPENSEARCH_JAVA_OPTS=-Xmx{heap_gb}g")
+		}
+		false => String::new(),
+	};
+	DockerParams {
+		image: "opensearchproject/opensearch",
+		pre_args: format!(
+			"--ulimit nofile=65536:65536 --ulimit memlock=-1:-1 \
+			 -p 127.0.0.1:9200:9200 \
+			 -e discovery.type=single-node \
+			 -e DISABLE_SECURITY_PLUGIN=true \
+			 -e DISABLE_INSTALL_DEMO_CONFIG=true {memory}"
+		),
+		post_args: "".to_string(),
+	}
+}
+
+pub(crate) struct OpenSearchClientProvider {
+	sync: bool,
+	samples: u32,
+	url: String,
+	security: Security,
+}
+
+impl BenchmarkEngine<OpenSearchClient> for OpenSearchClientProvider {
+	/// Initiates a new datastore benchmarking engine
+	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
+		Ok(Self {
+			sync: options.sync,
+			samples: options.samples,
+			// Credentials in the URL are sent as basic authentication
+			url: options
+				.security
+				.url_with_credentials(options.endpoint.as_deref().unwrap_or(DEFAULT))
+				.trim_end_matches('/')
+				.to_owned(),
+			security: options.security.clone(),
+		})
+	}
+	/// Creates a new client for this benchmarking engine
+	async fn create_client(&self) -> Result<OpenSearchClient> {
+		let mut builder = Client::builder().danger_accept_invalid_certs(self.security.tls_insecure);
+		if let Some(pem) = self.security.ca_certificate()? {
+			builder = builder.add_root_certificate(Certificate::from_pem(&pem)?);
+		}
+		let client = builder.build()?;
+		// Ensure the node has started and is able to serve requests
+		client
+			.get(format!("{}/_cluster/health?wait_for_status=yellow", self.url))
+			.send()
+			.await?
+			.error_for_status()?;
+		Ok(OpenSearchClient {
+			sync: self.sync,
+			samples: self.samples,
+			url: self.url.clone(),
+			client,
+		})
+	}
+}
+
+pub(crate) struct OpenSearchClient {
+	sync: bool,
+	samples: u32,
+	url: String,
+	client: Client,
+}
+
+impl BenchmarkClient for OpenSearchClient {
+	// The return type when reading a row
+	type ReadRow = BenchValue;
+
+	async fn version(&self) -> Result<String> {
+		let info = self.send(self.client.get(&self.url)).await?;
+		match info["version"]["number"].as_str() {
+			Some(version) => Ok(version.to_string()),
+			None => bail!("OpenSearch did not report its version: {info}"),
+		}
+	}
+
+	async fn startup(&self) -> Result<()> {
+		// Ensure we drop the index first
+		let res = self.client.delete(format!("{}/{INDEX}", self.url)).send().await?;
+		if res.status() != StatusCode::NOT_FOUND {
+			res.error_for_status()?;
+		}
+		// Create the benchmark record index. Without sync, the
+		// translog is flushed in the background instead of being
+		// fsynced before every write request is acknowledged.
+		let durability = match self.sync {
+			true => "request",
+			false => "async",
+		};
+		let settings = json!({
+			"settings": {
+				"index": {
+					"number_of_shards": 1,
+					"number_of_replicas": 0,
+					"max_result_window": self.samples.max(1),
+					"translog": {
+						"durability": durability,
+					},
+				},
+			},
+		});
+		self.send(self.client.put(format!("{}/{INDEX}", self.url)).json(&settings)).await?;
+		// Everything ok
+		Ok(())
+	}
+
+	fn classify_error(&self, err: &anyhow::Error) -> Option<BenchError> {
+		let err = err.chain().find_map(|e| e.downcast_ref::<reqwest::Error>())?;
+		if err.is_timeout() {
+			return Some(BenchError::Timeout);
+		}
+		err.is_connect().then_some(BenchError::Transient)
+	}
+
+	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
+		self.create(key.to_string(), val).await
+	}
+
+	async fn create_string(&self, key: String, val: BenchValue) -> Result<()> {
+		self.create(key, val).await
+	}
+
+	async fn read_u32(&self, key: u32) -> Result<BenchValue> {
+		self.read(key.to_string()).await
+	}
+
+	async fn read_string(&self, key: String) -> Result<BenchValue> {
+		self.read(key).await
+	}
+
+	async fn update_u32(&self, key: u32, val: BenchValue) -> Result<()> {
+		self.update(key.to_string(), val).await
+	}
+
+	async fn update_string(&self, key: String, val: BenchValue) -> Result<()> {
+		self.update(key, val).await
+	}
+
+	async fn delete_u32(&self, key: u32) -> Result<()> {
+		self.delete(key.to_string()).await
+	}
+
+	async fn delete_string(&self, key: String) -> Result<()> {
+		self.delete(key).await
+	}
+
+	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
+		self.scan(scan).await
+	}
+
+	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
+		self.scan(scan).await
+	}
+
+	async fn batch_create_u32(
+		&self,
+		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_write("create", key_vals.map(|(k, v)| (k.to_string(), Some(v)))).await
+	}
+
+	async fn batch_create_string(
+		&self,
+		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_write("create", key_vals.map(|(k, v)| (k, Some(v)))).await
+	}
+
+	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
+		self.batch_read_keys(keys.map(|k| k.to_string()).collect()).await
+	}
+
+	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
+		self.batch_read_keys(keys.collect()).await
+	}
+
+	async fn batch_update_u32(
+		&self,
+		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_write("index", key_vals.map(|(k, v)| (k.to_string(), Some(v)))).await
+	}
+
+	async fn batch_update_string(
+		&self,
+		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
+	) -> Result<()> {
+		self.batch_write("index", key_vals.map(|(k, v)| (k, Some(v)))).await
+	}
+
+	async fn batch_delete_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
+		self.batch_write("delete", keys.map(|k| (k.to_string(), None))).await
+	}
+
+	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
+		self.batch_write("delete", keys.map(|k| (k, None))).await
+	}
+}
+
+impl OpenSearchClient {
+	/// Sends a request, returning the response body of a successful request
+	async fn send(&self, req: RequestBuilder) -> Result<Value> {
+		let res = req.send().await?;
+		let status = res.status();
+		let body: Value = res.json().await?;
+		if !status.is_success() {
+			let message = format!("OpenSearch request failed with status {status}: {body}");
+			let category = match status {
+				StatusCode::NOT_FOUND => BenchError::NotFound,
+				StatusCode::CONFLICT => BenchError::Conflict,
+				StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => BenchError::Timeout,
+				StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+					BenchError::Transient
+				}
+				_ => bail!(message),
+			};
+			return Err(anyhow!(category).context(message));
+		}
+		Ok(body)
+	}
+
+	/// The URL of a single document in the record index
+	fn doc_url(&self, key: &str) -> String {
+		format!("{}/{INDEX}/_doc/{key}", self.url)
+	}
+
+	async fn create(&self, key: String, val: BenchValue) -> Result<()> {
+		let url = format!("{}/{INDEX}/_create/{key}", self.url);
+		self.send(self.client.put(url).json(&val.to_json())).await?;
+		Ok(())
+	}
+
+	async fn read(&self, key: String) -> Result<BenchValue> {
+		let res = self.send(self.client.get(self.doc_url(&key))).await?;
+		assert_eq!(res["found"], Value::Bool(true));
+		assert_eq!(res["_id"].as_str(), Some(key.as_str()));
+		Ok(black_box(BenchValue::from(&res["_source"])))
+	}
+
+	async fn update(&self, key: String, val: BenchValue) -> Result<()> {
+		self.send(self.client.put(self.doc_url(&key)).json(&val.to_json())).await?;
+		Ok(())
+	}
+
+	async fn delete(&self, key: String) -> Result<()> {
+		self.send(self.client.delete(self.doc_url(&key))).await?;
+		Ok(())
+	}
+
+	/// Sends a `_bulk` request with one `action` per document
+	async fn batch_write(
+		&self,
+		action: &str,
+		docs: impl Iterator<Item = (String, Option<BenchValue>)>,
+	) -> Result<()> {
+		// The bulk API takes newline-delimited JSON, with
+		// each action line followed by its document source
+		let mut body = String::new();
+		for (key, val) in docs {
+			body.push_str(&json!({ action: { "_index": INDEX, "_id": key } }).to_string());
+			body.push('\n');
+			if let Some(val) = val {
+				body.push_str(&val.to_json().to_string());
+				body.push('\n');
+			}
+		}
+		if body.is_empty() {
+			return Ok(());
+		}
+		let req = self
+			.client
+			.post(format!("{}/_bulk", self.url))
+			.header("Content-Type", "application/x-ndjson")
+			.body(body);
+		let res = self.send(req).await?;
+		// A bulk request succeeds even when individual actions fail
+		if res["errors"] == Value::Bool(true) {
+			let item = res["items"]
+				.as_array()
+				.and_then(|items| items.iter().find(|i| i[action]["error"].is_object()));
+			bail!("OpenSearch bulk {action} failed: {}", item.unwrap_or(&Value::Null));
+		}
+		Ok(())
+	}
+
+	async fn batch_read_keys(&self, keys: Vec<String>) -> Result<()> {
+		if keys.is_empty() {
+			return Ok(());
+		}
+		let url = format!("{}/{INDEX}/_mget", self.url);
+		let res = self.send(self.client.post(url).json(&json!({ "ids": keys }))).await?;
+		let docs = res["docs"].as_array().ok_or_else(|| anyhow!("expected docs in _mget"))?;
+		assert!(docs.iter().any(|d| d["found"] == Value::Bool(true)));
+		for v in docs {
+			materialize::consume(v, |v| Ok(BenchValue::from(&v["_source"])))?;
+		}
+		Ok(())
+	}
+
+	async fn scan(&self, scan: &Scan) -> Result<usize> {
+		// Ordered scans are not specified for OpenSearch
+		if scan.order_by.is_some() {
+			bail!(BenchError::Unsupported);
+		}
+		// Extract parameters
+		let s = scan.start.unwrap_or(0);
+		let l = scan
+			.limit
+			.unwrap_or(self.samples as usize)
+			.min((self.samples as usize).saturating_sub(s));
+		let q = OpenSearchDialect::query(scan)?;
+		let p = scan.projection()?;
+		// Perform the relevant projection scan type
+		let source = match p {
+			Projection::Id => json!(false),
+			Projection::Full => json!(true),
+			Projection::Field(path) => json!([path]),
+			Projection::Fields(paths) => json!(paths),
+			Projection::Count => {
+				let url = format!("{}/{INDEX}/_count", self.url);
+				let res = self.send(self.client.post(url).json(&json!({ "query": q }))).await?;
+				let count = res["count"].as_u64().ok_or_else(|| anyhow!("expected a count"))?;
+				// The count API has no paging, so apply the offset and limit here
+				let count = (count as usize).saturating_sub(s);
+				return Ok(scan.limit.map_or(count, |l| count.min(l)));
+			}
+		};
+		let body = json!({
+			"query": q,
+			"from": s,
+			"size": l,
+			"_source": source,
+			"track_total_hits": false,
+		});
+		let url = format!("{}/{INDEX}/_search", self.url);
+		let res = self.send(self.client.post(url).json(&body)).await?;
+		let hits = match res["hits"]["hits"].as_array() {
+			Some(hits) => hits,
+			None => bail!("expected hits in _search"),
+		};
+		// We use a for loop to iterate over the results, while
+		// calling black_box internally. This is necessary as
+		// an iterator with `filter_map` or `map` is optimised
+		// out by the compiler when calling `count` at the end.
+		let mut count = 0;
+		for v in hits {
+			materialize::consume(v, |v| Ok(BenchValue::from(&v["_source"])))?;
+			count += 1;
+		}
+		Ok(count)
+	}
+}
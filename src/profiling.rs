@@ -1,11 +1,34 @@
-use pprof::ProfilerGuard;
-use pprof::ProfilerGuardBuilder;
+//! CPU profiling of the benchmark process, enabled by setting the `PROFILE` environment variable.
+//!
+//! One sampling profiler runs for the whole benchmark, and is written out at the end as
+//! `flamegraph.svg` and `profile.pb`. Each phase also snapshots the samples when it starts, and
+//! the samples taken while it ran are written out as a flamegraph of their own, such as
+//! `flamegraph-02-read.svg`, so one phase is not lost among the others.
+
 use pprof::protos::Message;
+use pprof::{Frames, ProfilerGuard, ProfilerGuardBuilder, Report};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::io::Write;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 
 static PROFILER: OnceLock<ProfilerGuard<'static>> = OnceLock::new();
 
+/// The flamegraphs written for the phases so far, in the order they ran.
+static FLAMEGRAPHS: Mutex<Vec<PhaseFlamegraph>> = Mutex::new(Vec::new());
+
+/// The flamegraph of one phase of the benchmark.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct PhaseFlamegraph {
+	/// The phase, as named in the results
+	pub(crate) phase: String,
+	/// The SVG file the flamegraph was written to
+	pub(crate) path: String,
+}
+
+/// The samples the profiler had taken when a phase started.
+pub(crate) struct PhaseProfile(HashMap<Frames, isize>);
+
 pub(crate) fn initialise() {
 	PROFILER.get_or_init(|| {
 		ProfilerGuardBuilder::default()
@@ -31,3 +54,67 @@ pub(crate) fn process() {
 		file.write_all(&content).unwrap();
 	}
 }
+
+/// The flamegraphs written for the phases of the benchmark.
+pub(crate) fn flamegraphs() -> Vec<PhaseFlamegraph> {
+	FLAMEGRAPHS.lock().map(|f| f.clone()).unwrap_or_default()
+}
+
+impl PhaseProfile {
+	/// Snapshots the samples taken so far, when the benchmark is being profiled.
+	pub(crate) fn start() -> Option<Self> {
+		let report = PROFILER.get()?.report().build().ok()?;
+		Some(Self(report.data))
+	}
+
+	/// Writes a flamegraph of the samples taken since the phase started.
+	pub(crate) fn finish(self, phase: &str) {
+		if let Err(e) = self.write(phase) {
+			eprintln!("⚠️ Failed to write the flamegraph of {phase}: {e:#}");
+		}
+	}
+
+	fn write(self, phase: &str) -> anyhow::Result<()> {
+		let Some(guard) = PROFILER.get() else {
+			return Ok(());
+		};
+		let report = guard.report().build()?;
+		// Keep only the samples taken while the phase ran
+		let data = report
+			.data
+			.into_iter()
+			.filter_map(|(frames, count)| {
+				let count = count - self.0.get(&frames).copied().unwrap_or(0);
+				(count > 0).then_some((frames, count))
+			})
+			.collect();
+		let report = Report {
+			data,
+			timing: report.timing,
+		};
+		let mut flamegraphs = FLAMEGRAPHS.lock().map_err(|_| anyhow::anyhow!("poisoned lock"))?;
+		let path = format!("flamegraph-{:02}-{}.svg", flamegraphs.len() + 1, slug(phase));
+		report.flamegraph(std::fs::File::create(&path)?)?;
+		flamegraphs.push(PhaseFlamegraph {
+			phase: phase.to_string(),
+			path,
+		});
+		Ok(())
+	}
+}
+
+/// A file name friendly form of a phase name, such as `scan-count-all` for `[S]can · count · all`.
+pub(crate) fn slug(phase: &str) -> String {
+	let slug = phase
+		.replace(['[', ']'], "")
+		.to_lowercase()
+		.split(|c: char| !c.is_ascii_alphanumeric())
+		.filter(|s| !s.is_empty())
+		.collect::<Vec<_>>()
+		.join("-");
+	if slug.is_empty() {
+		"phase".to_string()
+	} else {
+		slug
+	}
+}
@@ -0,0 +1,195 @@
+#![cfg(feature = "questdb")]
+
+use crate::docker::DockerParams;
+use crate::engine::{BenchmarkClient, BenchmarkEngine};
+use crate::error::BenchError;
+use crate::security::Security;
+use crate::timeseries::{TimeSeriesRow, TimeWindow};
+use crate::value::BenchValue;
+use crate::valueprovider::Columns;
+use crate::{Benchmark, KeyType};
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use std::hint::black_box;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio::time::{Instant, sleep};
+use tokio_postgres::config::Host;
+use tokio_postgres::{Client, NoTls};
+use tracing::error;
+
+pub const DEFAULT: &str = "host=127.0.0.1 port=8812 user=admin password=quest dbname=qdb";
+
+/// The port accepting the InfluxDB line protocol over TCP
+const ILP_PORT: u16 = 9009;
+
+/// The table holding the rows appended by the time-series workload
+const TIME_SERIES_TABLE: &str = "metric";
+
+/// The maximum wait for appended rows to become visible to queries
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	// Commits are only fsynced in the `sync` commit mode
+	let commit_mode = match options.sync {
+		true => "sync",
+		false => "nosync",
+	};
+	// Return Docker parameters
+	DockerParams {
+		image: "questdb/questdb",
+		pre_args: format!(
+			"--ulimit nofile=65536:65536 -p 127.0.0.1:8812:8812 -p 127.0.0.1:{ILP_PORT}:{ILP_PORT} \
+			-e QDB_CAIRO_COMMIT_MODE={commit_mode}"
+		),
+		post_args: "".to_string(),
+	}
+}
+
+pub(crate) struct QuestDBClientProvider(String, Security);
+
+impl BenchmarkEngine<QuestDBClient> for QuestDBClientProvider {
+	/// Initiates a new datastore benchmarking engine
+	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
+		options.security.without_tls_options("QuestDB")?;
+		// Get the custom endpoint if specified
+		let url = options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned();
+		// Create the client provider
+		Ok(Self(url, options.security.clone()))
+	}
+	/// Creates a new client for this benchmarking engine
+	async fn create_client(&self) -> Result<QuestDBClient> {
+		// Queries are sent over the Postgres wire protocol
+		let mut config: tokio_postgres::Config = self.0.parse()?;
+		if let Some(user) = &self.1.username {
+			config.user(user);
+		}
+		if let Some(password) = &self.1.password {
+			config.password(password);
+		}
+		let (client, connection) = config.connect(NoTls).await?;
+		// Log any errors when the connection is closed
+		tokio::spawn(async move {
+			if let Err(e) = connection.await {
+				error!("Connection error: {e}");
+			}
+		});
+		// Rows are appended over the line protocol, on the same host
+		let host = match config.get_hosts().first() {
+			Some(Host::Tcp(host)) => host.clone(),
+			_ => "127.0.0.1".to_string(),
+		};
+		let ilp = TcpStream::connect((host.as_str(), ILP_PORT)).await?;
+		ilp.set_nodelay(true)?;
+		// Create the client
+		Ok(QuestDBClient {
+			client,
+			ilp: Mutex::new(ilp),
+		})
+	}
+}
+
+/// QuestDB only benchmarks the time-series workload: rows are appended over
+/// the line protocol, which is not acknowledged, and queried over the Postgres
+/// wire protocol once the server has committed them. Keyed records are not supported.
+pub(crate) struct QuestDBClient {
+	client: Client,
+	ilp: Mutex<TcpStream>,
+}
+
+impl BenchmarkClient for QuestDBClient {
+	// The return type when reading a row
+	type ReadRow = BenchValue;
+
+	async fn setup_time_series(&self) -> Result<()> {
+		// The designated timestamp orders and partitions the rows, and
+		// rows appended over the line protocol are stored by its column
+		self.client.batch_execute(&format!("DROP TABLE IF EXISTS {TIME_SERIES_TABLE}")).await?;
+		let stm = format!(
+			"CREATE TABLE {TIME_SERIES_TABLE} (ts TIMESTAMP, sensor SYMBOL, value DOUBLE) \
+			TIMESTAMP(ts) PARTITION BY DAY WAL"
+		);
+		self.client.batch_execute(&stm).await?;
+		Ok(())
+	}
+
+	async fn time_series_ready(&self, rows: u32) -> Result<()> {
+		// Appended rows are committed and applied asynchronously
+		let stm = format!("SELECT count() FROM {TIME_SERIES_TABLE}");
+		let time = Instant::now();
+		loop {
+			let count: i64 = self.client.query_one(&stm, &[]).await?.get(0);
+			if count >= rows as i64 {
+				return Ok(());
+			}
+			if time.elapsed() > READY_TIMEOUT {
+				bail!("Only {count} of {rows} appended rows became visible");
+			}
+			sleep(Duration::from_millis(100)).await;
+		}
+	}
+
+	async fn append_time_series(&self, row: TimeSeriesRow) -> Result<()> {
+		let Some(ts) = row.ts.timestamp_nanos_opt() else {
+			bail!("Timestamp {} is out of range", row.ts);
+		};
+		let line = format!("{TIME_SERIES_TABLE},sensor={} value={} {ts}\n", row.sensor, row.value);
+		self.ilp.lock().await.write_all(line.as_bytes()).await?;
+		Ok(())
+	}
+
+	async fn query_time_series(&self, window: TimeWindow) -> Result<usize> {
+		let (from, to) = (literal(window.from), literal(window.to));
+		let stm = match window.bucket {
+			None => format!(
+				"SELECT ts, sensor, value FROM {TIME_SERIES_TABLE} WHERE ts >= {from} AND ts < {to}"
+			),
+			// Calendar alignment starts the buckets on multiples of the bucket since the epoch
+			Some(bucket) => format!(
+				"SELECT ts, avg(value), min(value), max(value) FROM {TIME_SERIES_TABLE} \
+				WHERE ts >= {from} AND ts < {to} SAMPLE BY {bucket}s ALIGN TO CALENDAR"
+			),
+		};
+		let res = self.client.query(&stm, &[]).await?;
+		Ok(black_box(res).len())
+	}
+
+	async fn create_u32(&self, _key: u32, _val: BenchValue) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	async fn create_string(&self, _key: String, _val: BenchValue) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	async fn read_u32(&self, _key: u32) -> Result<BenchValue> {
+		bail!(BenchError::Unsupported)
+	}
+
+	async fn read_string(&self, _key: String) -> Result<BenchValue> {
+		bail!(BenchError::Unsupported)
+	}
+
+	async fn update_u32(&self, _key: u32, _val: BenchValue) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	async fn update_string(&self, _key: String, _val: BenchValue) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	async fn delete_u32(&self, _key: u32) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+
+	async fn delete_string(&self, _key: String) -> Result<()> {
+		bail!(BenchError::Unsupported)
+	}
+}
+
+/// A timestamp literal with microsecond precision
+fn literal(ts: DateTime<Utc>) -> String {
+	format!("'{}'", ts.format("%Y-%m-%dT%H:%M:%S%.6fZ"))
+}
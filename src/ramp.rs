@@ -0,0 +1,222 @@
+//! `--ramp`: repeat the benchmark at increasing concurrency, and chart how the throughput and the
+//! latencies of each phase change with it.
+//!
+//! Each step of the ramp runs the whole workload as a benchmark of its own, with `--clients` set to
+//! the step and the name suffixed with it, writing `result-<name>-clients-<n>.json`. Once every step
+//! has run, their results are merged into `result-<name>-ramp.html`, which draws the throughput and
+//! the median and p99 latencies of each phase against the number of clients.
+
+use crate::chart::{PAGE_STYLES, html_escape};
+use crate::compare::{PhaseResult, ResultFile};
+use crate::report::RowGroup;
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+
+/// The concurrencies a ramp steps through, given as `clients=1,2,4,8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Ramp {
+	/// The `--clients` of each step, in increasing order
+	pub(crate) clients: Vec<u32>,
+}
+
+impl Ramp {
+	/// Parses `clients=` followed by the comma-separated clients of each step.
+	pub(crate) fn parse(value: &str) -> Result<Self, String> {
+		let invalid = || format!("The ramp `{value}` must be given as `clients=1,2,4,8`");
+		let (dimension, steps) = value.split_once('=').ok_or_else(invalid)?;
+		if dimension.trim() != "clients" {
+			return Err(format!("The ramp can only step through `clients`, not `{dimension}`"));
+		}
+		let clients = steps
+			.split(',')
+			.map(|s| s.trim().parse::<u32>().ok().filter(|&c| c > 0))
+			.collect::<Option<Vec<u32>>>()
+			.ok_or_else(invalid)?;
+		if clients.len() < 2 {
+			return Err(format!("The ramp `{value}` needs at least two steps"));
+		}
+		if clients.windows(2).any(|w| w[0] >= w[1]) {
+			return Err(format!("The clients of the ramp `{value}` must increase"));
+		}
+		Ok(Self {
+			clients,
+		})
+	}
+
+	/// The name of the run of one step, which names its result files.
+	pub(crate) fn step_name(&self, name: Option<&str>, clients: u32) -> String {
+		match name {
+			Some(name) => format!("{name}-clients-{clients}"),
+			None => format!("clients-{clients}"),
+		}
+	}
+
+	/// The file the report of the whole ramp is written to.
+	pub(crate) fn report_path(&self, name: Option<&str>) -> String {
+		match name {
+			Some(name) => format!("result-{name}-ramp.html"),
+			None => "result-ramp.html".to_string(),
+		}
+	}
+}
+
+/// Chart data: one line per phase, for the throughput and the median and p99 latencies.
+fn ramp_data(clients: &[u32], results: &[ResultFile]) -> Value {
+	let rows: Vec<_> = results.iter().map(ResultFile::rows).collect();
+	// Every phase which ran in any step, in first-seen order
+	let mut labels: Vec<&String> = Vec::new();
+	for (label, _) in rows.iter().flatten() {
+		if RowGroup::of(label).is_some() && !labels.contains(&label) {
+			labels.push(label);
+		}
+	}
+	let chart = |id: &str, title: &str, unit: &str, value: fn(&PhaseResult) -> f64| {
+		let series: Vec<Value> = labels
+			.iter()
+			.map(|label| {
+				let data: Vec<Option<f64>> = rows
+					.iter()
+					.map(|rows| {
+						rows.iter().find(|(l, _)| l == *label).and_then(|(_, r)| r.map(value))
+					})
+					.collect();
+				json!({ "name": label, "data": data })
+			})
+			.collect();
+		json!({ "id": id, "title": title, "unit": unit, "series": series })
+	};
+	json!({
+		"clients": clients,
+		"charts": [
+			chart("rampOpsChart", "Operations per second by clients", "ops/s", |r| r.ops),
+			chart("rampP50Chart", "Median latency by clients", "ms", |r| r.q50 as f64 / 1000.0),
+			chart("rampP99Chart", "P99 latency by clients", "ms", |r| r.q99 as f64 / 1000.0),
+		],
+	})
+}
+
+/// Generate the HTML report of a ramp, from the results of its steps.
+fn generate_ramp_html(clients: &[u32], results: &[ResultFile]) -> String {
+	let data = ramp_data(clients, results);
+	let containers = data["charts"]
+		.as_array()
+		.into_iter()
+		.flatten()
+		.map(|chart| {
+			format!(
+				r#"<div class="chart-container full-width">
+                <div class="chart-title">{}</div>
+                <div id="{}"></div>
+            </div>"#,
+				html_escape(chart["title"].as_str().unwrap_or_default()),
+				chart["id"].as_str().unwrap_or_default(),
+			)
+		})
+		.collect::<Vec<_>>()
+		.join("\n            ");
+	let database = results.iter().find_map(|r| r.database.as_deref()).unwrap_or("crud-bench");
+	let steps = clients.iter().map(u32::to_string).collect::<Vec<_>>().join(" · ");
+	format!(
+		r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>CRUD Benchmark Ramp</title>
+    <link rel="preconnect" href="https://fonts.googleapis.com">
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+    <link href="https://fonts.googleapis.com/css2?family=Inter:opsz,wght@14..32,100..900&family=JetBrains+Mono:wght@100..800&display=swap" rel="stylesheet">
+    <script src="https://cdn.jsdelivr.net/npm/apexcharts@3.45.0/dist/apexcharts.min.js"></script>
+    <style>
+{styles}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="eyebrow">CRUD Benchmark</div>
+        <h1>{database} <span class="grad">Ramp</span></h1>
+        <div class="subtitle">Clients: {steps}</div>
+
+        <div class="chart-grid">
+            {containers}
+        </div>
+    </div>
+
+    <script>
+var RAMP = {data};
+
+// Helper function to format numbers with commas
+function formatNumber(num, decimals) {{
+    var parts = num.toFixed(decimals || 0).split('.');
+    parts[0] = parts[0].replace(/\B(?=(\d{{3}})+(?!\d))/g, ",");
+    return parts.join('.');
+}}
+
+var COLORS = ['#7c5cfc', '#d255fe', '#34d399', '#c471f5', '#651ddd', '#f59e0b', '#38bdf8', '#f472b6'];
+
+// One line per phase, against the clients of each step
+RAMP.charts.forEach(function (chart) {{
+    var decimals = chart.unit === 'ms' ? 3 : 0;
+    new ApexCharts(document.getElementById(chart.id), {{
+        series: chart.series,
+        chart: {{
+            type: 'line',
+            height: 380,
+            fontFamily: '"Inter", system-ui, sans-serif',
+            foreColor: '#9990ab',
+            background: '#16141f',
+            toolbar: {{ show: false }}
+        }},
+        theme: {{ mode: 'dark' }},
+        grid: {{ borderColor: 'rgba(111, 121, 136, 0.2)', strokeDashArray: 4 }},
+        colors: COLORS,
+        dataLabels: {{ enabled: false }},
+        legend: {{ position: 'top', horizontalAlign: 'left', labels: {{ colors: '#e8e4f0' }} }},
+        stroke: {{ width: 3, curve: 'straight' }},
+        markers: {{ size: 4 }},
+        xaxis: {{ categories: RAMP.clients, title: {{ text: 'Clients' }} }},
+        yaxis: {{
+            title: {{ text: chart.unit }},
+            labels: {{ formatter: function (val) {{ return formatNumber(Number(val), decimals); }} }}
+        }},
+        tooltip: {{ shared: true, y: {{ formatter: function (val) {{ return val == null ? '-' : formatNumber(val, decimals) + ' ' + chart.unit; }} }} }}
+    }}).render();
+}});
+    </script>
+</body>
+</html>"##,
+		styles = PAGE_STYLES,
+		database = html_escape(database),
+		steps = steps,
+		containers = containers,
+		// Keep labels from closing the inline script element
+		data = data.to_string().replace("</", "<\\/"),
+	)
+}
+
+/// Loads the results of every step, and writes the report of the ramp.
+pub(crate) fn report(clients: &[u32], files: &[String], output: &str) -> Result<()> {
+	let results = files.iter().map(|f| ResultFile::load(f)).collect::<Result<Vec<ResultFile>>>()?;
+	let html = generate_ramp_html(clients, &results);
+	std::fs::write(output, html).with_context(|| format!("Failed to write report '{output}'"))?;
+	println!("📈 Ramp report saved to: {output}");
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ramps_step_through_increasing_clients() {
+		let ramp = Ramp::parse("clients=1, 2,4,32").unwrap();
+		assert_eq!(ramp.clients, vec![1, 2, 4, 32]);
+		assert_eq!(ramp.step_name(Some("surrealdb"), 4), "surrealdb-clients-4");
+		assert_eq!(ramp.report_path(None), "result-ramp.html");
+		assert!(Ramp::parse("threads=1,2").is_err());
+		assert!(Ramp::parse("clients=4,2").is_err());
+		assert!(Ramp::parse("clients=0,2").is_err());
+		assert!(Ramp::parse("clients=8").is_err());
+		assert!(Ramp::parse("1,2,4").is_err());
+	}
+}
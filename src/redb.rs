@@ -1,16 +1,17 @@
 #![cfg(feature = "redb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::materialize;
 use crate::memory::Config;
+use crate::util::crate_version;
+use crate::util::kvindex::{KvIndexes, Matches};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, bail};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
+use anyhow::Result;
 use redb::{Database, Durability, ReadableDatabase, ReadableTable, TableDefinition};
 use std::hint::black_box;
 use std::sync::Arc;
-use std::time::Duration;
 
 const DATABASE_DIR: &str = "redb";
 
@@ -24,20 +25,22 @@ fn calculate_redb_memory() -> u64 {
 
 const TABLE: TableDefinition<&[u8], Vec<u8>> = TableDefinition::new("test");
 
+/// The entries of the emulated secondary indexes, kept apart from the records
+const INDEX: TableDefinition<&[u8], ()> = TableDefinition::new("index");
+
 pub(crate) struct ReDBClientProvider {
 	db: Arc<Database>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
 impl BenchmarkEngine<ReDBClient> for ReDBClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Cleanup the data directory
-		std::fs::remove_file(DATABASE_DIR).ok();
+		// Cleanup the data directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_file(DATABASE_DIR).ok();
+		}
 		// Calculate memory allocation
 		let memory = calculate_redb_memory();
 		// Configure and create the database
@@ -49,6 +52,7 @@ impl BenchmarkEngine<ReDBClient> for ReDBClientProvider {
 		// Create the store
 		Ok(Self {
 			db: Arc::new(db),
+			indexes: Arc::new(KvIndexes::new(options.emulate_indexes, &[])),
 			sync: options.sync,
 		})
 	}
@@ -56,6 +60,7 @@ impl BenchmarkEngine<ReDBClient> for ReDBClientProvider {
 	async fn create_client(&self) -> Result<ReDBClient> {
 		Ok(ReDBClient {
 			db: self.db.clone(),
+			indexes: self.indexes.clone(),
 			sync: self.sync,
 		})
 	}
@@ -63,6 +68,7 @@ impl BenchmarkEngine<ReDBClient> for ReDBClientProvider {
 
 pub(crate) struct ReDBClient {
 	db: Arc<Database>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
@@ -70,6 +76,10 @@ impl BenchmarkClient for ReDBClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("redb")
+	}
+
 	async fn shutdown(&self) -> Result<()> {
 		// Cleanup the data directory
 		std::fs::remove_file(DATABASE_DIR).ok();
@@ -77,6 +87,26 @@ impl BenchmarkClient for ReDBClient {
 		Ok(())
 	}
 
+	fn disk_size(&self) -> Option<u64> {
+		// The database is a single file
+		std::fs::metadata(DATABASE_DIR).ok().map(|meta| meta.len())
+	}
+
+	async fn sync(&self) -> Result<()> {
+		// Clone the datastore
+		let db = self.db.clone();
+		// Execute on the blocking threadpool
+		affinitypool::spawn_local(move || -> Result<_> {
+			// Create a new transaction
+			let mut txn = db.begin_write()?;
+			// An immediate commit persists all earlier non-durable commits
+			txn.set_durability(Durability::Immediate)?;
+			txn.commit()?;
+			Ok(())
+		})
+		.await
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create_bytes(&key.to_ne_bytes(), val).await
 	}
@@ -109,12 +139,74 @@ impl BenchmarkClient for ReDBClient {
 		self.delete_bytes(&key.into_bytes()).await
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// Writes maintain the index from now on
+		let index = self.indexes.create(spec, name)?;
+		// Clone the datastore and sync flag
+		let db = self.db.clone();
+		let sync = self.sync;
+		// Execute on the blocking threadpool
+		affinitypool::spawn_local(move || -> Result<_> {
+			// Create a new transaction
+			let mut txn = db.begin_write()?;
+			// Set the transaction durability
+			let _ = txn.set_durability(if sync {
+				Durability::Immediate
+			} else {
+				Durability::None
+			});
+			// Open the database tables
+			let tab = txn.open_table(TABLE)?;
+			let mut idx = txn.open_table(INDEX)?;
+			// Write the entries of the existing records
+			for v in tab.iter()? {
+				let (key, val) = v?;
+				if let Some(entry) = index.entry(key.value(), &BenchValue::decode(&val.value())?) {
+					idx.insert(entry.as_slice(), ())?;
+				}
+			}
+			drop(idx);
+			drop(tab);
+			txn.commit()?;
+			Ok(())
+		})
+		.await
+	}
+
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		// Writes stop maintaining the index
+		let index = self.indexes.remove(name)?;
+		// Clone the datastore and sync flag
+		let db = self.db.clone();
+		let sync = self.sync;
+		// Execute on the blocking threadpool
+		affinitypool::spawn_local(move || -> Result<_> {
+			// Create a new transaction
+			let mut txn = db.begin_write()?;
+			// Set the transaction durability
+			let _ = txn.set_durability(if sync {
+				Durability::Immediate
+			} else {
+				Durability::None
+			});
+			// Open the index table
+			let mut idx = txn.open_table(INDEX)?;
+			// Delete the entries of the index
+			let end = index.end();
+			idx.retain_in(index.start()..end.as_slice(), |_, _| false)?;
+			drop(idx);
+			txn.commit()?;
+			Ok(())
+		})
+		.await
 	}
 
 	async fn batch_create_u32(
@@ -192,13 +284,16 @@ impl BenchmarkClient for ReDBClient {
 
 impl ReDBClient {
 	async fn create_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
-		// Clone the datastore and sync flag
+		// Clone the datastore, indexes, and sync flag
 		let db = self.db.clone();
+		let indexes = self.indexes.clone();
 		let sync = self.sync;
 		// Execute on the blocking threadpool
 		affinitypool::spawn_local(move || -> Result<_> {
 			// Serialise the value
 			let val = val.encode()?;
+			// Derive any index entries
+			let entries = indexes.entries(key, &val)?;
 			// Create a new transaction
 			let mut txn = db.begin_write()?;
 			// Set the transaction durability
@@ -212,6 +307,13 @@ impl ReDBClient {
 			// Process the data
 			tab.insert(key, val)?;
 			drop(tab);
+			// Write any index entries
+			if !entries.is_empty() {
+				let mut idx = txn.open_table(INDEX)?;
+				for entry in entries {
+					idx.insert(entry.as_slice(), ())?;
+				}
+			}
 			txn.commit()?;
 			Ok(())
 		})
@@ -241,13 +343,16 @@ impl ReDBClient {
 	}
 
 	async fn update_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
-		// Clone the datastore and sync flag
+		// Clone the datastore, indexes, and sync flag
 		let db = self.db.clone();
+		let indexes = self.indexes.clone();
 		let sync = self.sync;
 		// Execute on the blocking threadpool
 		affinitypool::spawn_local(move || -> Result<_> {
 			// Serialise the value
 			let val = val.encode()?;
+			// Derive any index entries
+			let entries = indexes.entries(key, &val)?;
 			// Create a new transaction
 			let mut txn = db.begin_write()?;
 			// Set the transaction durability
@@ -261,6 +366,13 @@ impl ReDBClient {
 			// Process the data
 			tab.insert(key, val)?;
 			drop(tab);
+			// Write any index entries
+			if !entries.is_empty() {
+				let mut idx = txn.open_table(INDEX)?;
+				for entry in entries {
+					idx.insert(entry.as_slice(), ())?;
+				}
+			}
 			txn.commit()?;
 			Ok(())
 		})
@@ -296,8 +408,9 @@ impl ReDBClient {
 		&self,
 		key_vals: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>)>> + Send + 'static,
 	) -> Result<()> {
-		// Clone the datastore and sync flag
+		// Clone the datastore, indexes, and sync flag
 		let db = self.db.clone();
+		let indexes = self.indexes.clone();
 		let sync = self.sync;
 		// Execute on the blocking threadpool
 		affinitypool::spawn_local(move || -> Result<_> {
@@ -312,11 +425,20 @@ impl ReDBClient {
 			// Open the database table
 			let mut tab = txn.open_table(TABLE)?;
 			// Process all the data in batch
+			let mut entries = Vec::new();
 			for result in key_vals {
 				let (key, val) = result?;
+				entries.extend(indexes.entries(&key, &val)?);
 				tab.insert(&key[..], val)?;
 			}
 			drop(tab);
+			// Write any index entries
+			if !entries.is_empty() {
+				let mut idx = txn.open_table(INDEX)?;
+				for entry in entries {
+					idx.insert(entry.as_slice(), ())?;
+				}
+			}
 			txn.commit()?;
 			Ok(())
 		})
@@ -341,10 +463,8 @@ impl ReDBClient {
 				let res: Option<_> = tab.get(&key[..])?;
 				// Check the value exists
 				assert!(res.is_some());
-				// Deserialise the value
-				let val = BenchValue::decode(res.unwrap().value().as_ref())?;
-				// Use the value
-				black_box(val);
+				// Materialise the value
+				materialize::consume(res.unwrap(), |v| BenchValue::decode(v.value().as_ref()))?;
 			}
 			// All ok
 			Ok(())
@@ -356,8 +476,9 @@ impl ReDBClient {
 		&self,
 		key_vals: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>)>> + Send + 'static,
 	) -> Result<()> {
-		// Clone the datastore and sync flag
+		// Clone the datastore, indexes, and sync flag
 		let db = self.db.clone();
+		let indexes = self.indexes.clone();
 		let sync = self.sync;
 		// Execute on the blocking threadpool
 		affinitypool::spawn_local(move || -> Result<_> {
@@ -372,11 +493,20 @@ impl ReDBClient {
 			// Open the database table
 			let mut tab = txn.open_table(TABLE)?;
 			// Process all the data in batch
+			let mut entries = Vec::new();
 			for result in key_vals {
 				let (key, val) = result?;
+				entries.extend(indexes.entries(&key, &val)?);
 				tab.insert(&key[..], val)?;
 			}
 			drop(tab);
+			// Write any index entries
+			if !entries.is_empty() {
+				let mut idx = txn.open_table(INDEX)?;
+				for entry in entries {
+					idx.insert(entry.as_slice(), ())?;
+				}
+			}
 			txn.commit()?;
 			Ok(())
 		})
@@ -413,10 +543,10 @@ impl ReDBClient {
 		.await
 	}
 
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Contional scans are not supported
+	async fn scan_bytes(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Conditional scans filter the records
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			return self.scan_filtered(scan, ctx).await;
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -446,14 +576,14 @@ impl ReDBClient {
 					}
 					Ok(count)
 				}
-				Projection::Full => {
+				Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 					// We use a for loop to iterate over the results, while
 					// calling black_box internally. This is necessary as
 					// an iterator with `filter_map` or `map` is optimised
 					// out by the compiler when calling `count` at the end.
 					let mut count = 0;
 					for v in iter.skip(s).take(l) {
-						black_box(v.unwrap().1.value());
+						materialize::consume(v.unwrap().1.value(), |v| BenchValue::decode(&v))?;
 						count += 1;
 					}
 					Ok(count)
@@ -468,4 +598,53 @@ impl ReDBClient {
 		})
 		.await
 	}
+
+	async fn scan_filtered(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Only conditions on a single integer field are supported
+		let filter = self.indexes.filter(scan, ctx)?;
+		let mut matches = Matches::new(scan)?;
+		let index = self.indexes.find(&filter, ctx);
+		// Clone the datastore
+		let db = self.db.clone();
+		// Execute on the blocking threadpool
+		affinitypool::spawn_local(move || -> Result<_> {
+			// Create a new transaction
+			let txn = db.begin_read()?;
+			// Open the database table
+			let tab = txn.open_table(TABLE)?;
+			match index {
+				// Look up the records of each range of index entries
+				Some(index) => {
+					let idx = txn.open_table(INDEX)?;
+					'ranges: for range in filter.ranges() {
+						let (beg, end) = index.range(*range);
+						for v in idx.range(beg.as_slice()..end.as_slice())? {
+							let (entry, _) = v?;
+							let (value, key) = index.split(entry.value());
+							// Skip the entries left behind by updates and deletes
+							let Some(val) = tab.get(key)? else {
+								continue;
+							};
+							let record = BenchValue::decode(&val.value())?;
+							if index.holds(value, &record) && !matches.push(key, record) {
+								break 'ranges;
+							}
+						}
+					}
+				}
+				// Decode and filter every record
+				None => {
+					for v in tab.iter()? {
+						let (key, val) = v?;
+						let record = BenchValue::decode(&val.value())?;
+						if filter.matches(&record) && !matches.push(key.value(), record) {
+							break;
+						}
+					}
+				}
+			}
+			Ok(matches.count())
+		})
+		.await
+	}
 }
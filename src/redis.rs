@@ -1,21 +1,24 @@
 #![cfg(feature = "redis")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
-use crate::docker::DockerParams;
+use crate::docker::{DockerParams, DockerService, DockerTopology};
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::util::resp::{Multiplexed, RespConnection};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
-use anyhow::{Result, anyhow, bail};
-use futures::StreamExt;
-use redis::aio::MultiplexedConnection;
-use redis::{AsyncCommands, Client, ScanOptions};
+use crate::{Benchmark, KeyType, Scan};
+use anyhow::{Result, bail};
+use redis::AsyncCommands;
 use std::hint::black_box;
 use tokio::sync::Mutex;
 
 pub const DEFAULT: &str = "redis://:root@127.0.0.1:6379/";
 
-pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+/// The ports of the nodes of the `cluster` topology, each a primary for a third of the slots
+const NODES: [u16; 3] = [6379, 6380, 6381];
+
+/// The server arguments, with the host shared between `nodes` servers
+fn server_args(options: &Benchmark, nodes: usize) -> String {
 	// Redis 6+ supports `io-threads` for network I/O parallelism (command
 	// execution itself is still single-threaded). Docs recommend capping at
 	// 8 and leaving room for the main thread. With `appendfsync always`
@@ -24,7 +27,7 @@ pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 	let io_threads = if options.sync {
 		1
 	} else {
-		num_cpus::get().saturating_sub(1).clamp(2, 8)
+		(options.container_cores() / nodes).saturating_sub(1).clamp(2, 8)
 	};
 	// Persistence: AOF on/off + sync flush. When persisted=true we also
 	// disable RDB explicitly so the default snapshot schedule doesn't
@@ -38,51 +41,154 @@ pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 	// rather than evicting; this keeps comparisons deterministic.
 	let memory = match options.optimised {
 		true => {
-			let cache_gb = crate::memory::Config::new().cache_gb.max(1);
+			let cache_gb = (crate::memory::Config::new().cache_gb / nodes as u64).max(1);
 			format!("--maxmemory {cache_gb}gb --maxmemory-policy noeviction")
 		}
 		false => String::new(),
 	};
+	format!("redis-server --requirepass root --io-threads {io_threads} {persistence} {memory}")
+}
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 	DockerParams {
 		image: "redis",
 		pre_args: "-p 127.0.0.1:6379:6379".to_string(),
-		post_args: format!(
-			"redis-server --requirepass root --io-threads {io_threads} \
-			 {persistence} {memory}"
-		),
+		post_args: server_args(options, 1),
 	}
 }
 
+pub(crate) fn topology(
+	params: DockerParams,
+	topology: &str,
+	options: &Benchmark,
+) -> Result<DockerTopology> {
+	if topology != "cluster" {
+		bail!("Redis does not support the `{topology}` topology, expected `cluster`");
+	}
+	// Split the host between the nodes, as they share it
+	let node = |port: u16| {
+		format!(
+			"{} --port {port} --cluster-enabled yes --cluster-config-file nodes-{port}.conf \
+			--masterauth root",
+			server_args(options, NODES.len())
+		)
+	};
+	let nodes = NODES.iter().map(|port| format!("127.0.0.1:{port}")).collect::<Vec<_>>().join(" ");
+	Ok(DockerTopology {
+		main: DockerParams {
+			image: params.image,
+			pre_args: params.pre_args,
+			post_args: node(NODES[0]),
+		},
+		services: ["node2", "node3"]
+			.into_iter()
+			.zip(&NODES[1..])
+			.map(|(name, port)| DockerService {
+				name,
+				params: DockerParams {
+					image: params.image,
+					pre_args: String::new(),
+					post_args: node(*port),
+				},
+				before: false,
+			})
+			.collect(),
+		// Assign the slots once every node is up, then wait for the cluster to agree on them
+		setup: vec![
+			format!(
+				"bash -c 'redis-cli -a root --no-auth-warning cluster info | grep -q cluster_known_nodes:{} || \
+				redis-cli -a root --no-auth-warning --cluster create {nodes} --cluster-yes'",
+				NODES.len()
+			),
+			"bash -c 'redis-cli -a root --no-auth-warning cluster info | grep -q cluster_state:ok'"
+				.to_string(),
+		],
+	})
+}
+
 pub(crate) struct RedisClientProvider {
 	url: String,
+	cluster: bool,
+	pipelined: bool,
 }
 
 impl BenchmarkEngine<RedisClient> for RedisClientProvider {
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
+		// TLS is chosen by the `rediss://` scheme of the endpoint
+		options.security.without_tls_options("Redis")?;
+		// A cluster is reached through its nodes, given as comma-separated endpoints
+		let url = match (&options.endpoint, &options.topology) {
+			(Some(endpoint), _) => endpoint
+				.split(',')
+				.map(|node| options.security.url_with_credentials(node))
+				.collect::<Vec<_>>()
+				.join(","),
+			(None, Some(_)) => {
+				NODES.map(|port| format!("redis://:root@127.0.0.1:{port}/")).join(",")
+			}
+			(None, None) => DEFAULT.to_owned(),
+		};
 		Ok(Self {
-			url: options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned(),
+			cluster: url.contains(','),
+			url,
+			pipelined: options.pipelined,
 		})
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<RedisClient> {
-		let client = Client::open(self.url.as_str())?;
 		Ok(RedisClient {
-			conn_iter: Mutex::new(client.get_multiplexed_async_connection().await?),
-			conn_record: Mutex::new(client.get_multiplexed_async_connection().await?),
+			conn_iter: Mutex::new(self.connect().await?),
+			conn_record: RespConnection::new(self.connect().await?, self.pipelined),
 		})
 	}
 }
 
+impl RedisClientProvider {
+	/// Connects to the single node, or to the cluster, of the endpoint
+	async fn connect(&self) -> Result<Multiplexed> {
+		match self.cluster {
+			true => {
+				Multiplexed::cluster(&self.url.split(',').map(str::trim).collect::<Vec<_>>()).await
+			}
+			false => Multiplexed::node(&self.url).await,
+		}
+	}
+}
+
 pub(crate) struct RedisClient {
-	conn_iter: Mutex<MultiplexedConnection>,
-	conn_record: Mutex<MultiplexedConnection>,
+	conn_iter: Mutex<Multiplexed>,
+	conn_record: RespConnection,
 }
 
 impl BenchmarkClient for RedisClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		self.conn_record.server_version().await
+	}
+
+	fn classify_error(&self, err: &anyhow::Error) -> Option<BenchError> {
+		use redis::ErrorKind;
+		let err = err.chain().find_map(|e| e.downcast_ref::<redis::RedisError>())?;
+		if err.is_timeout() {
+			return Some(BenchError::Timeout);
+		}
+		if err.is_io_error() || err.is_connection_dropped() {
+			return Some(BenchError::Transient);
+		}
+		match err.kind() {
+			// A transaction aborted by a watched key
+			ErrorKind::ExecAbortError => Some(BenchError::Conflict),
+			ErrorKind::TryAgain
+			| ErrorKind::ClusterDown
+			| ErrorKind::MasterDown
+			| ErrorKind::BusyLoadingError => Some(BenchError::Transient),
+			_ => None,
+		}
+	}
+
 	#[allow(dependency_on_unit_never_type_fallback)]
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		let val = val.encode()?;
@@ -139,81 +245,33 @@ impl BenchmarkClient for RedisClient {
 	}
 
 	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+		self.conn_record.scan(&self.conn_iter, scan).await
 	}
 
 	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+		self.conn_record.scan(&self.conn_iter, scan).await
 	}
 
 	async fn batch_create_u32(
 		&self,
 		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
 	) -> Result<()> {
-		// Build the SET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for (k, v) in key_vals {
-			pipe.cmd("SET").arg(k).arg(v.encode()?).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_set(key_vals).await
 	}
 
 	async fn batch_create_string(
 		&self,
 		key_vals: impl Iterator<Item = (String, BenchValue)> + Send,
 	) -> Result<()> {
-		// Build the SET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for (k, v) in key_vals {
-			pipe.cmd("SET").arg(k).arg(v.encode()?).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_set(key_vals).await
 	}
 
 	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
-		// Build the GET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		let mut count = 0usize;
-		for k in keys {
-			pipe.cmd("GET").arg(k);
-			count += 1;
-		}
-		// Execute the pipeline and inspect the responses
-		let vals: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
-		assert_eq!(vals.len(), count);
-		for v in vals {
-			let v = v.ok_or_else(|| anyhow!("missing key"))?;
-			assert!(!v.is_empty());
-			black_box(v);
-		}
-		Ok(())
+		self.conn_record.batch_get(keys).await
 	}
 
 	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
-		// Build the GET pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		let mut count = 0usize;
-		for k in keys {
-			pipe.cmd("GET").arg(k);
-			count += 1;
-		}
-		// Execute the pipeline and inspect the responses
-		let vals: Vec<Option<Vec<u8>>> = pipe.query_async(&mut *conn).await?;
-		assert_eq!(vals.len(), count);
-		for v in vals {
-			let v = v.ok_or_else(|| anyhow!("missing key"))?;
-			assert!(!v.is_empty());
-			black_box(v);
-		}
-		Ok(())
+		self.conn_record.batch_get(keys).await
 	}
 
 	async fn batch_update_u32(
@@ -233,86 +291,10 @@ impl BenchmarkClient for RedisClient {
 	}
 
 	async fn batch_delete_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
-		// Build the DEL pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for k in keys {
-			pipe.cmd("DEL").arg(k).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
+		self.conn_record.batch_del(keys).await
 	}
 
 	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
-		// Build the DEL pipeline
-		let mut conn = self.conn_record.lock().await;
-		let mut pipe = redis::pipe();
-		for k in keys {
-			pipe.cmd("DEL").arg(k).ignore();
-		}
-		// Execute the pipeline
-		pipe.exec_async(&mut *conn).await?;
-		Ok(())
-	}
-}
-
-impl RedisClient {
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Conditional scans are not supported
-		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
-		}
-		// Extract parameters
-		let s = scan.start.unwrap_or(0);
-		let l = scan.limit.unwrap_or(usize::MAX);
-		let p = scan.projection()?;
-		// Get the two connection types
-		let mut conn_iter = self.conn_iter.lock().await;
-		let mut conn_record = self.conn_record.lock().await;
-		// Configure the scan options for improve iteration
-		let opts = ScanOptions::default().with_count(5000);
-		// Create an iterator starting at the beginning
-		let mut iter = conn_iter.scan_options::<String>(opts).await?.skip(s);
-		// Perform the relevant projection scan type
-		match p {
-			Projection::Id => {
-				// We use a for loop to iterate over the results, while
-				// calling black_box internally. This is necessary as
-				// an iterator with `filter_map` or `map` is optimised
-				// out by the compiler when calling `count` at the end.
-				let mut count = 0;
-				for _ in 0..l {
-					if let Some(k) = iter.next().await {
-						black_box(k);
-						count += 1;
-					} else {
-						break;
-					}
-				}
-				Ok(count)
-			}
-			Projection::Full => {
-				// We use a for loop to iterate over the results, while
-				// calling black_box internally. This is necessary as
-				// an iterator with `filter_map` or `map` is optimised
-				// out by the compiler when calling `count` at the end.
-				let mut count = 0;
-				while let Some(k) = iter.next().await {
-					let v: Vec<u8> = conn_record.get(k).await?;
-					black_box(v);
-					count += 1;
-					if count >= l {
-						break;
-					}
-				}
-				Ok(count)
-			}
-			Projection::Count => match scan.limit {
-				// Full count queries are too slow
-				None => bail!(NOT_SUPPORTED_ERROR),
-				Some(l) => Ok(iter.take(l).count().await),
-			},
-		}
+		self.conn_record.batch_del(keys).await
 	}
 }
@@ -0,0 +1,449 @@
+//! `crud-bench report`: merge two or more `result*.json` files into one comparative HTML report.
+//!
+//! Every file becomes one series: throughput is drawn as grouped bars per operation, and the
+//! latency percentiles of each CRUD phase are overlaid as one line per database.
+
+use crate::chart::{PAGE_STYLES, html_escape, scan_chart_bar_height};
+use crate::compare::{PhaseResult, ResultFile};
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::path::Path;
+
+/// Command-line interface for `crud-bench report`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct ReportArgs {
+	/// Result JSON files to merge, one series per file
+	#[arg(required = true, num_args = 2..)]
+	pub(crate) files: Vec<String>,
+
+	/// Path of the generated HTML report
+	#[arg(short, long, default_value = "report.html")]
+	pub(crate) output: String,
+}
+
+/// Which throughput chart a result row is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RowGroup {
+	/// Single-record CRUD phases and their variants.
+	Crud,
+	/// Timed scan legs.
+	Scan,
+	/// Batch throughput cases.
+	Batch,
+}
+
+impl RowGroup {
+	/// Classifies a row label; index build/remove rows are single operations and are not charted.
+	pub(crate) fn of(label: &str) -> Option<Self> {
+		if label.starts_with("[S]can") {
+			Some(Self::Scan)
+		} else if label.starts_with("[B]atch") {
+			Some(Self::Batch)
+		} else if label.starts_with("[I]ndex") || label.starts_with("[R]emoveIndex") {
+			None
+		} else {
+			Some(Self::Crud)
+		}
+	}
+
+	/// Element id and chart title.
+	fn chart(self) -> (&'static str, &'static str) {
+		match self {
+			Self::Crud => ("opsChart", "Operations per second"),
+			Self::Scan => ("scanOpsChart", "Scan operations per second"),
+			Self::Batch => ("batchOpsChart", "Batch operations per second"),
+		}
+	}
+}
+
+/// Throughput normalised by the resources given to the server.
+#[derive(Debug, Clone, Copy)]
+enum Efficiency {
+	/// Operations per second per allocated server core.
+	PerCore,
+	/// Operations per second per GB of resident memory.
+	PerGb,
+	/// Price of one million operations at the recorded hourly price.
+	Cost,
+}
+
+impl Efficiency {
+	/// Element id, chart title, and tooltip unit.
+	fn chart(self) -> (&'static str, &'static str, &'static str) {
+		match self {
+			Self::PerCore => {
+				("opsPerCoreChart", "Operations per second per core", "ops/s per core")
+			}
+			Self::PerGb => ("opsPerGbChart", "Operations per second per GB of RSS", "ops/s per GB"),
+			Self::Cost => ("costChart", "Cost per million operations", "per M ops"),
+		}
+	}
+
+	/// Decimal places shown for values on the chart.
+	fn decimals(self) -> u32 {
+		match self {
+			Self::PerCore | Self::PerGb => 0,
+			Self::Cost => 4,
+		}
+	}
+
+	/// Whether any result file records what this chart needs.
+	fn recorded(self, results: &[ResultFile]) -> bool {
+		match self {
+			Self::PerCore | Self::PerGb => results.iter().any(|r| r.resources.is_some()),
+			Self::Cost => results.iter().any(|r| r.cost_per_hour().is_some()),
+		}
+	}
+
+	/// Normalised throughput of one phase, when the result file records what it needs.
+	fn value(self, result: &ResultFile, phase: &PhaseResult) -> Option<f64> {
+		match self {
+			Self::PerCore => result.resources.as_ref().map(|r| phase.ops / r.cores),
+			Self::PerGb => phase.ops_per_gb(),
+			Self::Cost => phase.cost_per_million_ops(result.cost_per_hour()?),
+		}
+	}
+}
+
+/// Series name per result file: the database name, or the file name when it is not recorded.
+/// Files for the same database are told apart by their file name.
+fn series_names(files: &[String], results: &[ResultFile]) -> Vec<String> {
+	let file_name = |path: &str| {
+		Path::new(path).file_name().and_then(|f| f.to_str()).unwrap_or(path).to_string()
+	};
+	let names: Vec<String> = files
+		.iter()
+		.zip(results)
+		.map(|(path, result)| result.database.clone().unwrap_or_else(|| file_name(path)))
+		.collect();
+	names
+		.iter()
+		.zip(files)
+		.map(|(name, path)| match names.iter().filter(|n| *n == name).count() {
+			1 => name.clone(),
+			_ => format!("{name} ({})", file_name(path)),
+		})
+		.collect()
+}
+
+/// Row labels across all files, in first-seen order.
+fn merged_labels(rows: &[Vec<(String, Option<&PhaseResult>)>]) -> Vec<String> {
+	let mut labels: Vec<String> = Vec::new();
+	for (label, _) in rows.iter().flatten() {
+		if !labels.contains(label) {
+			labels.push(label.clone());
+		}
+	}
+	labels
+}
+
+/// Looks up the result for a row label in one file.
+fn find<'a>(rows: &[(String, Option<&'a PhaseResult>)], label: &str) -> Option<&'a PhaseResult> {
+	rows.iter().find(|(l, _)| l == label).and_then(|(_, r)| *r)
+}
+
+/// Chart data: one grouped bar chart per row group, and one percentile chart per CRUD row.
+fn report_data(names: &[String], results: &[ResultFile]) -> Value {
+	let rows: Vec<_> = results.iter().map(ResultFile::rows).collect();
+	let labels = merged_labels(&rows);
+	// Grouped throughput bars, one series per file
+	let mut groups: Vec<Value> = [RowGroup::Crud, RowGroup::Scan, RowGroup::Batch]
+		.into_iter()
+		.filter_map(|group| {
+			let categories: Vec<&String> =
+				labels.iter().filter(|l| RowGroup::of(l) == Some(group)).collect();
+			if categories.is_empty() {
+				return None;
+			}
+			let series: Vec<Value> = names
+				.iter()
+				.zip(&rows)
+				.map(|(name, rows)| {
+					let data: Vec<Option<f64>> =
+						categories.iter().map(|l| find(rows, l).map(|r| r.ops)).collect();
+					json!({ "name": name, "data": data })
+				})
+				.collect();
+			let (id, title) = group.chart();
+			Some(json!({
+				"id": id,
+				"title": title,
+				"unit": "ops/s",
+				"decimals": 0,
+				"horizontal": group == RowGroup::Scan,
+				"height": scan_chart_bar_height(categories.len() * names.len()),
+				"categories": categories,
+				"series": series,
+			}))
+		})
+		.collect();
+	// Efficiency bars for the CRUD phases, when any file records what they need
+	let categories: Vec<&String> =
+		labels.iter().filter(|l| RowGroup::of(l) == Some(RowGroup::Crud)).collect();
+	let efficiency: Vec<Value> = [Efficiency::PerCore, Efficiency::PerGb, Efficiency::Cost]
+		.into_iter()
+		.filter(|efficiency| efficiency.recorded(results))
+		.map(|efficiency| {
+			let series: Vec<Value> = names
+				.iter()
+				.zip(results.iter().zip(&rows))
+				.map(|(name, (result, rows))| {
+					let data: Vec<Option<f64>> = categories
+						.iter()
+						.map(|l| find(rows, l).and_then(|r| efficiency.value(result, r)))
+						.collect();
+					json!({ "name": name, "data": data })
+				})
+				.collect();
+			let (id, title, unit) = efficiency.chart();
+			json!({
+				"id": id,
+				"title": title,
+				"unit": unit,
+				"decimals": efficiency.decimals(),
+				"horizontal": false,
+				"height": scan_chart_bar_height(categories.len() * names.len()),
+				"categories": categories,
+				"series": series,
+			})
+		})
+		.collect();
+	// Keep the efficiency charts next to the raw CRUD throughput
+	let at = groups.len().min(1);
+	groups.splice(at..at, efficiency);
+	// Overlaid latency percentile lines, one chart per CRUD phase
+	let percentiles: Vec<Value> = labels
+		.iter()
+		.filter(|l| RowGroup::of(l) == Some(RowGroup::Crud))
+		.enumerate()
+		.map(|(i, label)| {
+			let series: Vec<Value> = names
+				.iter()
+				.zip(&rows)
+				.filter_map(|(name, rows)| {
+					let r = find(rows, label)?;
+					let data = [r.min, r.q01, r.q25, r.q50, r.q75, r.q95, r.q99, r.max];
+					Some(json!({ "name": name, "data": data }))
+				})
+				.collect();
+			let title = format!("{label} latency percentiles");
+			json!({ "id": format!("percentileChart{i}"), "title": title, "series": series })
+		})
+		.collect();
+	json!({ "groups": groups, "percentiles": percentiles })
+}
+
+/// Chart containers for the generated chart data.
+fn chart_containers(data: &Value) -> String {
+	let container = |class: &str, chart: &Value| {
+		format!(
+			r#"<div class="chart-container{class}">
+                <div class="chart-title">{}</div>
+                <div id="{}"></div>
+            </div>"#,
+			html_escape(chart["title"].as_str().unwrap_or_default()),
+			chart["id"].as_str().unwrap_or_default(),
+		)
+	};
+	let mut html = Vec::new();
+	for chart in data["groups"].as_array().into_iter().flatten() {
+		html.push(container(" full-width", chart));
+	}
+	for chart in data["percentiles"].as_array().into_iter().flatten() {
+		html.push(container("", chart));
+	}
+	html.join("\n            ")
+}
+
+/// Generate a comparative HTML report for several benchmark results.
+fn generate_report_html(names: &[String], results: &[ResultFile]) -> String {
+	let data = report_data(names, results);
+	let subtitle = names.iter().map(|n| html_escape(n)).collect::<Vec<_>>().join(" · ");
+	format!(
+		r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>CRUD Benchmark Comparison</title>
+    <link rel="preconnect" href="https://fonts.googleapis.com">
+    <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
+    <link href="https://fonts.googleapis.com/css2?family=Inter:opsz,wght@14..32,100..900&family=JetBrains+Mono:wght@100..800&display=swap" rel="stylesheet">
+    <script src="https://cdn.jsdelivr.net/npm/apexcharts@3.45.0/dist/apexcharts.min.js"></script>
+    <style>
+{styles}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="eyebrow">CRUD Benchmark</div>
+        <h1>Benchmark <span class="grad">Comparison</span></h1>
+        <div class="subtitle">{subtitle}</div>
+
+        <div class="chart-grid">
+            {containers}
+        </div>
+    </div>
+
+    <script>
+var REPORT = {data};
+
+// Helper function to format numbers with commas
+function formatNumber(num, decimals) {{
+    var parts = num.toFixed(decimals || 0).split('.');
+    parts[0] = parts[0].replace(/\B(?=(\d{{3}})+(?!\d))/g, ",");
+    return parts.join('.');
+}}
+
+var COLORS = ['#7c5cfc', '#d255fe', '#34d399', '#c471f5', '#651ddd', '#f59e0b', '#38bdf8', '#f472b6'];
+
+var BASE = {{
+    chart: {{
+        fontFamily: '"Inter", system-ui, sans-serif',
+        foreColor: '#9990ab',
+        background: '#16141f',
+        toolbar: {{ show: false }}
+    }},
+    theme: {{ mode: 'dark' }},
+    grid: {{ borderColor: 'rgba(111, 121, 136, 0.2)', strokeDashArray: 4 }},
+    colors: COLORS,
+    dataLabels: {{ enabled: false }},
+    legend: {{ position: 'top', horizontalAlign: 'left', labels: {{ colors: '#e8e4f0' }} }}
+}};
+
+// Grouped throughput bars, one series per result file
+REPORT.groups.forEach(function (group) {{
+    var valueAxis = {{ labels: {{ formatter: function (val) {{ return formatNumber(Number(val), group.decimals); }} }} }};
+    new ApexCharts(document.getElementById(group.id), Object.assign({{}}, BASE, {{
+        series: group.series,
+        chart: Object.assign({{}}, BASE.chart, {{ type: 'bar', height: group.horizontal ? group.height : 380 }}),
+        plotOptions: {{ bar: {{ horizontal: group.horizontal, borderRadius: 3 }} }},
+        xaxis: Object.assign({{ categories: group.categories }}, group.horizontal ? valueAxis : {{}}),
+        yaxis: group.horizontal ? {{ labels: {{ maxWidth: 420 }} }} : valueAxis,
+        tooltip: {{ y: {{ formatter: function (val) {{ return val == null ? '-' : formatNumber(val, group.decimals) + ' ' + group.unit; }} }} }}
+    }})).render();
+}});
+
+// Overlaid latency percentile lines, one series per result file
+REPORT.percentiles.forEach(function (chart) {{
+    new ApexCharts(document.getElementById(chart.id), Object.assign({{}}, BASE, {{
+        series: chart.series,
+        chart: Object.assign({{}}, BASE.chart, {{ type: 'line', height: 350 }}),
+        stroke: {{ width: 3, curve: 'smooth' }},
+        xaxis: {{ categories: ['Min', 'P01', 'P25', 'P50', 'P75', 'P95', 'P99', 'Max'] }},
+        yaxis: {{
+            title: {{ text: 'Latency (microseconds)' }},
+            labels: {{ formatter: function (val) {{ return formatNumber(val); }} }}
+        }},
+        tooltip: {{ shared: true, y: {{ formatter: function (val) {{ return (val / 1000).toFixed(3) + ' ms'; }} }} }}
+    }})).render();
+}});
+    </script>
+</body>
+</html>"##,
+		styles = PAGE_STYLES,
+		subtitle = subtitle,
+		containers = chart_containers(&data),
+		// Keep labels from closing the inline script element
+		data = data.to_string().replace("</", "<\\/"),
+	)
+}
+
+/// Loads the result files and writes the merged HTML report.
+pub(crate) fn run(args: ReportArgs) -> Result<()> {
+	let results =
+		args.files.iter().map(|f| ResultFile::load(f)).collect::<Result<Vec<ResultFile>>>()?;
+	let names = series_names(&args.files, &results);
+	let html = generate_report_html(&names, &results);
+	std::fs::write(&args.output, html)
+		.with_context(|| format!("Failed to write report '{}'", args.output))?;
+	println!("📊 Comparison report saved to: {}", args.output);
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::{ResultFile, report_data, series_names};
+
+	fn result(database: &str, reads: f64) -> ResultFile {
+		serde_json::from_str(&format!(
+			r#"{{
+				"database": "{database}",
+				"creates": {{"ops": 1000.0, "min": 1, "q50": 5, "max": 9}},
+				"reads": {{"ops": {reads}, "min": 1, "q50": 2, "max": 4}},
+				"scans": [{{
+					"id": "limit",
+					"name": "select(id) limit(100)",
+					"samples": 10,
+					"index_build": {{"ops": 1.0}},
+					"index_remove": null,
+					"runs": [{{"workload": {{"kind": "read"}}, "indexed": true, "result": {{"ops": 10.0}}}}]
+				}}],
+				"batches": []
+			}}"#
+		))
+		.unwrap()
+	}
+
+	#[test]
+	fn series_names_disambiguate_duplicates() {
+		let files = vec!["a/result-1.json".to_string(), "b/result-2.json".to_string()];
+		let names = series_names(&files, &[result("Postgres", 1.0), result("Postgres", 2.0)]);
+		assert_eq!(names, vec!["Postgres (result-1.json)", "Postgres (result-2.json)"]);
+		let names = series_names(&files, &[result("Postgres", 1.0), result("MongoDB", 2.0)]);
+		assert_eq!(names, vec!["Postgres", "MongoDB"]);
+	}
+
+	#[test]
+	fn report_groups_series_per_file() {
+		let names = vec!["Postgres".to_string(), "MongoDB".to_string()];
+		let data = report_data(&names, &[result("Postgres", 100.0), result("MongoDB", 200.0)]);
+		// CRUD and scan groups only; index build/remove rows are not charted
+		let groups = data["groups"].as_array().unwrap();
+		assert_eq!(groups.len(), 2);
+		assert_eq!(groups[0]["categories"], serde_json::json!(["[C]reate", "[R]ead"]));
+		assert_eq!(groups[0]["series"][1]["data"], serde_json::json!([1000.0, 200.0]));
+		assert_eq!(groups[1]["categories"].as_array().unwrap().len(), 1);
+		// One percentile chart per CRUD row, one line per file
+		let percentiles = data["percentiles"].as_array().unwrap();
+		assert_eq!(percentiles.len(), 2);
+		assert_eq!(
+			percentiles[1]["series"][0]["data"],
+			serde_json::json!([1, 0, 0, 2, 0, 0, 0, 4])
+		);
+	}
+
+	#[test]
+	fn report_normalises_throughput_by_resources() {
+		let names = vec!["Postgres".to_string(), "MongoDB".to_string()];
+		let mut postgres = result("Postgres", 100.0);
+		postgres.resources =
+			serde_json::from_str(r#"{"cores": 4.0, "memory_limit": null, "limited": true}"#)
+				.unwrap();
+		let data = report_data(&names, &[postgres, result("MongoDB", 200.0)]);
+		// Efficiency charts sit between the CRUD and scan throughput charts
+		let groups = data["groups"].as_array().unwrap();
+		assert_eq!(groups.len(), 4);
+		assert_eq!(groups[1]["id"], "opsPerCoreChart");
+		assert_eq!(groups[1]["series"][0]["data"], serde_json::json!([250.0, 25.0]));
+		// Files without recorded resources have no per-core values
+		assert_eq!(groups[1]["series"][1]["data"], serde_json::json!([null, null]));
+		// No memory samples were recorded, so there are no per-GB values
+		assert_eq!(groups[2]["series"][0]["data"], serde_json::json!([null, null]));
+	}
+
+	#[test]
+	fn report_prices_operations_at_the_hourly_cost() {
+		let names = vec!["Postgres".to_string(), "MongoDB".to_string()];
+		let mut postgres = result("Postgres", 100.0);
+		postgres.metadata = serde_json::from_str(r#"{"cost_per_hour": 3.6}"#).unwrap();
+		let data = report_data(&names, &[postgres, result("MongoDB", 200.0)]);
+		let groups = data["groups"].as_array().unwrap();
+		assert_eq!(groups.len(), 3);
+		assert_eq!(groups[1]["id"], "costChart");
+		// 3.6 per hour at 1000 ops/s is 1.0 per million operations
+		assert_eq!(groups[1]["series"][0]["data"], serde_json::json!([1.0, 10.0]));
+		assert_eq!(groups[1]["series"][1]["data"], serde_json::json!([null, null]));
+	}
+}
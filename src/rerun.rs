@@ -0,0 +1,210 @@
+//! `crud-bench rerun`: replay the workload recorded in a `result*.json` file against another
+//! datastore.
+//!
+//! Every result records its [`Workload`]: the command-line options which shape the workload, and
+//! the benchmark definition with its templates expanded and overrides applied. The rerun writes
+//! them back out as a benchmark definition, with the options as its `[run]` table, and runs the
+//! benchmark from it. The options given after the file choose the datastore, and take precedence
+//! over the recorded ones.
+
+use crate::Args;
+use anyhow::{Context, Result, bail};
+use clap::{ArgMatches, CommandFactory};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Options which choose the datastore and how it is deployed, or where the outputs are written,
+/// rather than the workload which is run against it.
+const TARGET_OPTIONS: [&str; 35] = [
+	"name",
+	"database",
+	"image",
+	"privileged",
+	"docker_platform",
+	"container_cpus",
+	"container_memory",
+	"container_cores",
+	"pin_cores",
+	"numa_node",
+	"endpoint",
+	"username",
+	"password",
+	"tls_ca",
+	"tls_insecure",
+	"no_setup",
+	"keep_data",
+	"topology",
+	"netem",
+	"blocking",
+	"workers",
+	"color",
+	"show_sample",
+	"pid",
+	"store_results",
+	"storage_endpoint",
+	"config",
+	"raw_latencies",
+	"log_file",
+	"cost_per_hour",
+	"profile_server",
+	"emit_phase_markers",
+	"checkpoint",
+	"resume",
+	"shard",
+];
+
+/// Command-line interface for `crud-bench rerun`.
+#[derive(clap::Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct RerunArgs {
+	/// Result JSON file whose workload is replayed
+	pub(crate) file: String,
+
+	/// Benchmark options, including `--database`, which take precedence over the recorded ones
+	#[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+	pub(crate) args: Vec<OsString>,
+}
+
+/// The fully-resolved workload of a run, recorded in its results.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Workload {
+	/// The options which shape the workload, as the `[run]` options of a benchmark definition.
+	pub(crate) run: Map<String, Value>,
+	/// The benchmark definition, with its templates expanded and overrides applied.
+	pub(crate) definition: Value,
+}
+
+/// The subset of a serialised [`crate::result::BenchmarkResult`] needed for a rerun.
+#[derive(Deserialize)]
+struct ResultFile {
+	/// The workload of the run, when the result records it.
+	workload: Option<Workload>,
+}
+
+/// The benchmark definition written for a rerun, removed once the benchmark finishes.
+pub(crate) struct Definition(PathBuf);
+
+impl Drop for Definition {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_file(&self.0);
+	}
+}
+
+/// The options which shape the workload, with their values as given or defaulted, leaving out
+/// the [`TARGET_OPTIONS`] and switches which are off.
+pub(crate) fn run_options(matches: &ArgMatches) -> Map<String, Value> {
+	let mut run = Map::new();
+	for arg in Args::command().get_arguments() {
+		let id = arg.get_id().as_str();
+		if TARGET_OPTIONS.contains(&id) || matches.value_source(id).is_none() {
+			continue;
+		}
+		if !arg.get_action().takes_values() {
+			if matches.get_flag(id) {
+				run.insert(id.to_string(), Value::Bool(true));
+			}
+			continue;
+		}
+		run.insert(id.to_string(), raw_value(matches, id));
+	}
+	run
+}
+
+/// Every option with the value it took, including the defaults and the switches which are off,
+/// recorded in the results so a run can be reproduced from them. The password is left out.
+pub(crate) fn effective_options(matches: &ArgMatches) -> Map<String, Value> {
+	let mut options = Map::new();
+	for arg in Args::command().get_arguments() {
+		let id = arg.get_id().as_str();
+		if id == "password" || matches.value_source(id).is_none() {
+			continue;
+		}
+		let value = match arg.get_action().takes_values() {
+			true => raw_value(matches, id),
+			false => Value::Bool(matches.get_flag(id)),
+		};
+		options.insert(id.to_string(), value);
+	}
+	options
+}
+
+/// The value of an option as given, or an array of them for an option given several values.
+fn raw_value(matches: &ArgMatches, id: &str) -> Value {
+	let mut values: Vec<Value> = matches
+		.get_raw(id)
+		.into_iter()
+		.flatten()
+		.map(|v| Value::String(v.to_string_lossy().into_owned()))
+		.collect();
+	match values.len() {
+		1 => values.remove(0),
+		_ => Value::Array(values),
+	}
+}
+
+impl RerunArgs {
+	/// Writes the recorded workload as a benchmark definition, and returns the benchmark command
+	/// line which runs it, with the options of the definition inserted.
+	pub(crate) fn command_line(self) -> Result<(Definition, Vec<OsString>)> {
+		let text = std::fs::read_to_string(&self.file)
+			.with_context(|| format!("Failed to read result file '{}'", self.file))?;
+		let result: ResultFile = serde_json::from_str(&text)
+			.with_context(|| format!("Failed to parse result file '{}'", self.file))?;
+		let Some(workload) = result.workload else {
+			bail!("The result file '{}' does not record its workload", self.file);
+		};
+		let Value::Object(mut definition) = workload.definition else {
+			bail!("The workload of '{}' must hold a benchmark definition", self.file);
+		};
+		definition.insert("run".to_string(), Value::Object(workload.run));
+		// The definition is YAML, as TOML cannot hold every value of the JSON result
+		let path =
+			std::env::temp_dir().join(format!("crud-bench-rerun-{}.yaml", std::process::id()));
+		std::fs::write(&path, serde_yaml::to_string(&definition)?).with_context(|| {
+			format!("Failed to write benchmark definition '{}'", path.display())
+		})?;
+		let definition = Definition(path);
+		let mut args = vec![
+			OsString::from("crud-bench"),
+			OsString::from("--config"),
+			definition.0.clone().into_os_string(),
+		];
+		args.extend(self.args);
+		Ok((definition, crate::config::command_line_from(args)?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn run_options_leave_out_the_target_and_switches_which_are_off() {
+		let args = ["crud-bench", "-d", "map", "-s", "500", "-r", "--growth-sizes", "600,700"];
+		let matches = Args::command().get_matches_from(args);
+		let run = run_options(&matches);
+		assert_eq!(run["samples"], "500");
+		assert_eq!(run["random"], true);
+		assert_eq!(run["growth_sizes"], serde_json::json!(["600", "700"]));
+		// Defaulted options are recorded, as the defaults may change between versions
+		assert_eq!(run["clients"], "1");
+		assert!(!run.contains_key("database"));
+		assert!(!run.contains_key("sync"));
+		assert!(!run.contains_key("blocking"));
+	}
+
+	#[test]
+	fn effective_options_keep_every_option_but_the_password() {
+		let args =
+			["crud-bench", "-d", "map", "-s", "500", "-e", "localhost", "--password", "s3cret"];
+		let matches = Args::command().get_matches_from(args);
+		let options = effective_options(&matches);
+		assert_eq!(options["database"], "map");
+		assert_eq!(options["samples"], "500");
+		assert_eq!(options["sync"], false);
+		assert!(options.contains_key("blocking"));
+		assert!(!options.contains_key("password"));
+	}
+}
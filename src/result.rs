@@ -1,5 +1,11 @@
 //! Serializable benchmark outcomes: CRUD/scan/batch metrics, terminal tables, CSV, and HTML charts.
 
+use crate::cgroup::{Cgroup, CgroupStats};
+use crate::config::EffectiveConfig;
+use crate::error::{ErrorSummary, ErrorWindow};
+use crate::histograms;
+use crate::profiling::PhaseFlamegraph;
+use crate::rerun::Workload;
 use crate::system::SystemInfo;
 use crate::util::format_duration;
 use crate::value::BenchValue;
@@ -9,8 +15,9 @@ use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Attribute, Cell, CellAlignment, Color, ContentArrangement, Table};
 use csv::Writer;
 use hdrhistogram::Histogram;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::process;
 use std::sync::{Arc, Mutex};
@@ -18,6 +25,7 @@ use std::time::{Duration, Instant};
 use sysinfo::{
 	DiskUsage, LoadAvg, Pid, Process, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System,
 };
+use tokio::runtime::Handle;
 use tokio::task::JoinHandle;
 
 /// Static inputs echoed in JSON results for reproducibility (CLI snapshot).
@@ -37,8 +45,110 @@ pub(crate) struct BenchmarkMetadata {
 	pub(crate) sync: bool,
 	/// Redis-family append-only / persistence toggles.
 	pub(crate) persisted: bool,
+	/// Redis-family commands pipelined across threads, with single-command batches.
+	pub(crate) pipelined: bool,
+	/// SQL CRUD statements prepared once per client, rather than on every call.
+	pub(crate) prepared: bool,
+	/// Integer field scans run on key-value datastores through emulated secondary indexes.
+	pub(crate) emulate_indexes: bool,
+	/// Stringified [`crate::materialize::Materialize`] depth of scanned and batch read records.
+	pub(crate) materialize: String,
 	/// Tuned server settings vs defaults where supported.
 	pub(crate) optimised: bool,
+	/// Hourly instance and storage price, when costs are reported.
+	pub(crate) cost_per_hour: Option<f64>,
+	/// Records bulk loaded beyond the samples before the creates, when preloaded.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) preload: Option<u32>,
+	/// Row count of the table during the scan phases, when it differs from the samples.
+	pub(crate) scan_records: Option<u32>,
+	/// Seed the values were generated from, so another run writes identical payloads.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) seed: Option<u64>,
+	/// Multi-container topology the datastore ran as, when not a single container.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) topology: Option<String>,
+	/// The tc/netem rules the network between the clients and containers was emulated with.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) netem: Option<String>,
+	/// Server flags the Docker container was started with, for datastores which expose them.
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub(crate) server_options: BTreeMap<String, String>,
+	/// Version reported by the datastore server, or the crate version of an embedded engine.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) version: Option<String>,
+	/// Docker image the datastore ran from, when crud-bench started its container.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) image: Option<String>,
+	/// Digest of the Docker image, telling apart builds pushed under the same tag.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) image_digest: Option<String>,
+}
+
+/// Price of one million operations at the given throughput and hourly price.
+pub(crate) fn cost_per_million_ops(ops: f64, cost_per_hour: f64) -> Option<f64> {
+	(ops > 0.0).then(|| cost_per_hour / (ops * 3600.0) * 1_000_000.0)
+}
+
+/// Operations per second for each GB of mean resident memory, falling back to the memory used
+/// at the end of the phase, if memory was sampled.
+pub(crate) fn ops_per_gb(ops: f64, memory_avg: u64, used_memory: u64) -> Option<f64> {
+	let memory = match memory_avg > 0 {
+		true => memory_avg,
+		false => used_memory,
+	};
+	(memory > 0).then(|| ops / (memory as f64 / 1_000_000_000.0))
+}
+
+/// Row label of an update phase synced after every `interval` writes.
+pub(crate) fn synced_update_row_label(interval: u32) -> String {
+	format!("[U]pdate (sync every {interval})")
+}
+
+/// Row label of a bulk deletion of `--delete-strategies`.
+pub(crate) fn delete_strategy_row_label(strategy: &str) -> String {
+	format!("[D]elete ({strategy})")
+}
+
+/// Row label of a soak window ending `elapsed` seconds into the soak.
+pub(crate) fn soak_row_label(elapsed: u64) -> String {
+	let (hours, minutes, seconds) = (elapsed / 3600, elapsed / 60 % 60, elapsed % 60);
+	format!("[S]oak · {hours}:{minutes:02}:{seconds:02}")
+}
+
+/// Row label of a phase repeated at a dataset size grown to `records`.
+pub(crate) fn growth_row_label(records: u32, phase: &str) -> String {
+	format!("[G]rowth · {records} records · {phase}")
+}
+
+/// Row label of a read by the `unique` field of the value template.
+pub(crate) fn secondary_read_row_label(field: &str, phase: &str) -> String {
+	format!("[R]ead · by {field} · {phase}")
+}
+
+/// Row label of a phase linking every record to `fanout` others, or following those links.
+pub(crate) fn relation_row_label(fanout: u32, phase: &str) -> String {
+	format!("[L]ink · {fanout} per record · {phase}")
+}
+
+/// Row label of the writes notifying `subscribers` open live queries.
+pub(crate) fn live_query_row_label(subscribers: u32) -> String {
+	format!("[N]otify · {subscribers} live queries")
+}
+
+/// Row label of a walk over the whole table in pages of `page_size` records.
+pub(crate) fn page_walk_row_label(mode: PageMode, pages: u32, page_size: u32) -> String {
+	format!("[P]age · {mode} · {pages} pages of {page_size}")
+}
+
+/// Row label of a batch case of `samples` batches of `groups` records each.
+pub(crate) fn batch_row_label(name: &str, samples: u32, groups: usize) -> String {
+	format!("[B]atch::{name} ({samples} batches of {groups})")
+}
+
+/// Row label of a time-series phase, appending rows or querying windows of them.
+pub(crate) fn time_series_row_label(name: &str, samples: u32) -> String {
+	format!("[T]ime series::{name} ({samples})")
 }
 
 /// Full benchmark output: timings per phase plus one representative generated [`BenchValue`].
@@ -50,23 +160,346 @@ pub(crate) struct BenchmarkResult {
 	pub(crate) system: Option<SystemInfo>,
 	/// CLI parameters snapshot ([`BenchmarkMetadata`]).
 	pub(crate) metadata: Option<BenchmarkMetadata>,
+	/// The fully-resolved workload, which `crud-bench rerun` replays against another datastore.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) workload: Option<Workload>,
+	/// Every option, the value template, and the scans and batches the run resolved to.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) config: Option<EffectiveConfig>,
+	/// The flamegraph of each phase, when the run was profiled with `PROFILE`.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub(crate) flamegraphs: Vec<PhaseFlamegraph>,
+	/// The flamegraph of the datastore container in each phase, with `--profile-server`.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub(crate) server_flamegraphs: Vec<PhaseFlamegraph>,
 	/// Single-record insert phase.
 	pub(crate) creates: Option<OperationResult>,
 	/// Single-record read phase.
 	pub(crate) reads: Option<OperationResult>,
+	/// Single-record read phase through a restricted role with permission checks.
+	pub(crate) restricted_reads: Option<OperationResult>,
+	/// Reads by the `unique` field, with and without an index, when `--secondary-reads` is set.
+	pub(crate) secondary_reads: Option<SecondaryReads>,
+	/// Linking the records to each other and following the links, when `--relations` is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) relations: Option<Relations>,
+	/// Writes notifying open live queries, when `--live-queries` is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) live_queries: Option<LiveQueries>,
+	/// Writes to the primary polled for on a replica, when `--replication-lag` is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) replication_lag: Option<ReplicationLag>,
+	/// Client vs engine-reported latency of a sampled subset of reads.
+	pub(crate) server_timings: Option<LatencySplit>,
+	/// Server resources used to normalise throughput into efficiency metrics.
+	pub(crate) resources: Option<ServerResources>,
 	/// Single-record update phase.
 	pub(crate) updates: Option<OperationResult>,
+	/// Single-record update phase of one field at a dotted path.
+	pub(crate) path_updates: Option<OperationResult>,
+	/// `(writes_per_sync, histogram_metrics_or_skip)`, one per `--sync-intervals` entry.
+	pub(crate) synced_updates: Vec<(u32, Option<OperationResult>)>,
+	/// The windows of the mixed workload looped for the `--soak` duration, in the order they ran.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub(crate) soak: Vec<SoakWindow>,
 	/// One entry per configured scan id (possibly multiple timed legs inside [`ScanResult::runs`]).
 	pub(crate) scans: Vec<ScanResult>,
+	/// The offset then keyset walks over the whole table, when `--page-size` is set.
+	pub(crate) pagination: Vec<PageWalk>,
+	/// One entry per `--growth-sizes` entry, with the reads and table scans at that size.
+	pub(crate) growth: Vec<GrowthStage>,
 	/// `(batch_case_name, timed_iterations, records_per_batch, histogram_metrics_or_skip)`.
 	pub(crate) batches: Vec<(String, u32, usize, Option<OperationResult>)>,
+	/// `(phase_name, timed_iterations, histogram_metrics_or_skip)`, the append phase then each query.
+	pub(crate) time_series: Vec<(String, u32, Option<OperationResult>)>,
+	/// Row labels of the scans and batches skipped for this database by the benchmark TOML.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub(crate) skipped: Vec<String>,
+	/// The maintenance run between phases with `--maintenance` or `COMPACTION`, in the order it ran.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub(crate) compactions: Vec<Compaction>,
+	/// The backup and restore of the datastore, when `--backup` is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) backup: Option<Backup>,
+	/// The disk footprint after the create and batch create phases, for datastores whose size
+	/// on disk can be measured.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub(crate) footprints: Vec<DiskFootprint>,
 	/// Single-record delete phase.
 	pub(crate) deletes: Option<OperationResult>,
+	/// `(strategy, histogram_metrics_or_skip)`, one per `--delete-strategies` entry.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub(crate) delete_strategies: Vec<(String, Option<OperationResult>)>,
 	/// Example document produced by the value template (for inspection / stored results).
 	#[serde(serialize_with = "serialize_sample")]
 	pub(crate) sample: BenchValue,
 }
 
+/// One run of the datastore compaction hook between phases.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Compaction {
+	/// The phase the compaction ran after.
+	pub(crate) after: String,
+	/// Wall-clock duration of the compaction.
+	pub(crate) elapsed: Duration,
+	/// Bytes on disk before the compaction, for datastores which report them.
+	pub(crate) size_before: Option<u64>,
+	/// Bytes on disk after the compaction, for datastores which report them.
+	pub(crate) size_after: Option<u64>,
+	/// Percentage change in the throughput of a table scan over the compaction, when one ran.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub(crate) throughput_change: Option<f64>,
+}
+
+impl Compaction {
+	/// Bytes the compaction freed, negative when the store grew.
+	pub(crate) fn reclaimed(&self) -> Option<i64> {
+		Some(self.size_before? as i64 - self.size_after? as i64)
+	}
+}
+
+impl Display for Compaction {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Compaction after {}: took {}", self.after, format_duration(self.elapsed))?;
+		if let (Some(before), Some(after), Some(reclaimed)) =
+			(self.size_before, self.size_after, self.reclaimed())
+		{
+			let sign = if reclaimed < 0 {
+				"-"
+			} else {
+				""
+			};
+			write!(
+				f,
+				", reclaimed {sign}{} ({} → {})",
+				ByteSize(reclaimed.unsigned_abs()),
+				ByteSize(before),
+				ByteSize(after)
+			)?;
+		}
+		if let Some(change) = self.throughput_change {
+			write!(f, ", scans {change:+.2}% OPS")?;
+		}
+		Ok(())
+	}
+}
+
+/// A backup of the datastore with its native tool, and its restore into a fresh database.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Backup {
+	/// Wall-clock duration of the backup.
+	pub(crate) backup: Duration,
+	/// Bytes the backup occupies, for datastores which report them.
+	pub(crate) size: Option<u64>,
+	/// Wall-clock duration of the restore, for datastores which can restore.
+	pub(crate) restore: Option<Duration>,
+}
+
+impl Display for Backup {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Backup: took {}", format_duration(self.backup))?;
+		if let Some(size) = self.size {
+			write!(f, ", {}", ByteSize(size))?;
+		}
+		if let Some(restore) = self.restore {
+			write!(f, " · restore took {}", format_duration(restore))?;
+		}
+		Ok(())
+	}
+}
+
+/// The size on disk of the datastore after a phase which loaded records.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct DiskFootprint {
+	/// The phase the size was measured after.
+	pub(crate) after: String,
+	/// Bytes the datastore occupies on disk.
+	pub(crate) size: u64,
+	/// Bytes the datastore wrote to disk during the phase, when they were measured.
+	pub(crate) written: Option<u64>,
+	/// Estimated bytes of the keys and encoded values the phase loaded.
+	pub(crate) logical: u64,
+}
+
+impl DiskFootprint {
+	/// Bytes written to disk for every byte of data loaded.
+	pub(crate) fn write_amplification(&self) -> Option<f64> {
+		match (self.written?, self.logical) {
+			(0, _) | (_, 0) => None,
+			(written, logical) => Some(written as f64 / logical as f64),
+		}
+	}
+}
+
+impl Display for DiskFootprint {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Disk footprint after {}: {} on disk for {} of data",
+			self.after,
+			ByteSize(self.size),
+			ByteSize(self.logical)
+		)?;
+		if let Some(amplification) = self.write_amplification() {
+			write!(f, ", {amplification:.2}× write amplification")?;
+		}
+		Ok(())
+	}
+}
+
+/// Reads by the `unique` field of the value template, before and after it is indexed.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SecondaryReads {
+	/// The field the records are read by.
+	pub(crate) field: String,
+	/// Timed reads in each leg.
+	pub(crate) samples: u32,
+	/// Reads with no index on the field.
+	pub(crate) without_index: Option<OperationResult>,
+	/// Building the index on the field.
+	pub(crate) index_build: Option<OperationResult>,
+	/// Reads through the index on the field.
+	pub(crate) with_index: Option<OperationResult>,
+	/// Dropping the index on the field.
+	pub(crate) index_remove: Option<OperationResult>,
+}
+
+impl SecondaryReads {
+	/// The row labels and results of each leg and index phase, in the order they ran.
+	pub(crate) fn rows(&self) -> [(String, Option<&OperationResult>); 4] {
+		let field = &self.field;
+		[
+			(secondary_read_row_label(field, "without index"), self.without_index.as_ref()),
+			(format!("[I]ndex · {field} · build"), self.index_build.as_ref()),
+			(secondary_read_row_label(field, "with index"), self.with_index.as_ref()),
+			(format!("[R]emoveIndex · {field}"), self.index_remove.as_ref()),
+		]
+	}
+}
+
+/// Records linked to each other, then read by following the links from each record.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Relations {
+	/// The number of records each record is linked to.
+	pub(crate) fanout: u32,
+	/// Timed records in each phase.
+	pub(crate) samples: u32,
+	/// Linking every record to the others.
+	pub(crate) relate: Option<OperationResult>,
+	/// Reading the records linked from every record.
+	pub(crate) traverse: Option<OperationResult>,
+}
+
+impl Relations {
+	/// The row labels and results of each phase, in the order they ran.
+	pub(crate) fn rows(&self) -> [(String, Option<&OperationResult>); 2] {
+		[
+			(relation_row_label(self.fanout, "relate"), self.relate.as_ref()),
+			(relation_row_label(self.fanout, "traverse"), self.traverse.as_ref()),
+		]
+	}
+}
+
+/// How a walk over the whole table finds the start of each page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PageMode {
+	/// Skip the records before each page (`OFFSET`).
+	Offset,
+	/// Start each page after the last id of the previous one (`WHERE id > last`).
+	Keyset,
+}
+
+impl Display for PageMode {
+	fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+		match self {
+			Self::Offset => write!(f, "offset"),
+			Self::Keyset => write!(f, "keyset"),
+		}
+	}
+}
+
+/// One walk over the whole table, a page at a time, through one [`PageMode`].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PageWalk {
+	/// How each page found its start.
+	pub(crate) mode: PageMode,
+	/// Records in each page.
+	pub(crate) page_size: u32,
+	/// Pages fetched to walk the whole table.
+	pub(crate) pages: u32,
+	/// Mean page latency (µs) in each tenth of the walk, first pages first.
+	pub(crate) tenths: Vec<u64>,
+	/// Latency of every page in the walk, or `None` when unsupported.
+	pub(crate) result: Option<OperationResult>,
+}
+
+impl PageWalk {
+	/// Mean page latency (µs) in each tenth of the walk, from per-page latencies in walk order.
+	pub(crate) fn tenths(latencies: &[u64]) -> Vec<u64> {
+		let tenths = latencies.len().min(10);
+		(0..tenths)
+			.map(|i| {
+				let from = i * latencies.len() / tenths;
+				let to = (i + 1) * latencies.len() / tenths;
+				let pages = &latencies[from..to];
+				pages.iter().sum::<u64>() / pages.len() as u64
+			})
+			.collect()
+	}
+
+	/// How many times slower the last tenth of the walk was than the first.
+	pub(crate) fn slowdown(&self) -> Option<f64> {
+		match (self.tenths.first(), self.tenths.last()) {
+			(Some(&first), Some(&last)) if first > 0 => Some(last as f64 / first as f64),
+			_ => None,
+		}
+	}
+
+	/// The row label of this walk.
+	pub(crate) fn row_label(&self) -> String {
+		page_walk_row_label(self.mode, self.pages, self.page_size)
+	}
+}
+
+/// One window of the mixed reads and updates looped by `--soak`.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SoakWindow {
+	/// Seconds from the start of the soak to the end of this window.
+	pub(crate) elapsed: u64,
+	/// Latency of the reads and updates in this window, or `None` when unsupported.
+	pub(crate) result: Option<OperationResult>,
+}
+
+impl SoakWindow {
+	/// The row label of this window.
+	pub(crate) fn row_label(&self) -> String {
+		soak_row_label(self.elapsed)
+	}
+}
+
+/// The reads and table scans repeated once the table holds `records` records.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GrowthStage {
+	/// Records in the table during this stage.
+	pub(crate) records: u32,
+	/// Reads of keys spread evenly across all the records.
+	pub(crate) reads: Option<OperationResult>,
+	/// `(scan_id · scan_name, histogram_metrics_or_skip)`, the table-scan leg of each scan.
+	pub(crate) scans: Vec<(String, Option<OperationResult>)>,
+}
+
+impl GrowthStage {
+	/// The phases of this stage with their names, reads first.
+	pub(crate) fn phases(&self) -> Vec<(String, Option<&OperationResult>)> {
+		let mut phases = vec![("read".to_string(), self.reads.as_ref())];
+		for (name, result) in &self.scans {
+			phases.push((format!("scan · {name}"), result.as_ref()));
+		}
+		phases
+	}
+}
+
 /// Serialise a [`BenchValue`] through its JSON adapter so JSON consumers see the
 /// canonical representation (UUIDs/datetimes/decimals as strings) regardless of
 /// the in-memory variant.
@@ -79,7 +512,7 @@ where
 }
 
 /// Tags each timed scan leg for JSON consumers (`kind` discriminators).
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub(crate) enum ScanWorkload {
 	/// Pure read/query workload.
@@ -88,15 +521,24 @@ pub(crate) enum ScanWorkload {
 	ReadWrite {
 		write_ratio_percent: u32,
 	},
+	/// Pure read workload whose predicate differs per sample, defeating result caches.
+	Perturbed,
+	/// Pure read workload run again after the deletes, over their tombstones or dead tuples.
+	AfterDeletes,
 }
 
+/// Speedup of identical over perturbed scans above which results are mostly cache hits.
+pub(crate) const CACHE_HIT_SPEEDUP: f64 = 2.0;
+
 /// One timed scan leg (read-only or read+writes, with or without a physical index).
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub(crate) struct ScanRun {
 	/// Pure read vs read+writes ([`ScanWorkload`]).
 	pub workload: ScanWorkload,
 	/// Whether this leg used the indexed query path (vs table scan).
 	pub indexed: bool,
+	/// Whether this indexed leg forced the index through the scan's `hint`.
+	pub hinted: bool,
 	/// Latency histogram + resource stats; [`None`] when the backend skipped the leg.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub result: Option<OperationResult>,
@@ -109,16 +551,35 @@ pub(crate) fn writes_ratio_percent(spec: &crate::ScanWithWrites) -> u32 {
 
 /// Table row title for a scan leg (matches `[S]can` markers in stdout tables).
 pub(crate) fn scan_run_row_label(id: &str, name: &str, samples: u32, run: &ScanRun) -> String {
-	let index_slug = if run.indexed {
-		"indexed"
-	} else {
-		"no-index"
-	};
-	let mid = match &run.workload {
+	scan_leg_row_label(id, name, samples, &run.workload, run.indexed, run.hinted)
+}
+
+/// Short slug for the query path of a scan leg.
+fn scan_leg_slug(indexed: bool, hinted: bool) -> &'static str {
+	match (indexed, hinted) {
+		(_, true) => "hinted",
+		(true, false) => "indexed",
+		(false, false) => "no-index",
+	}
+}
+
+/// Table row title built from the leg fields, shared with `compare` when reading result JSON.
+pub(crate) fn scan_leg_row_label(
+	id: &str,
+	name: &str,
+	samples: u32,
+	workload: &ScanWorkload,
+	indexed: bool,
+	hinted: bool,
+) -> String {
+	let index_slug = scan_leg_slug(indexed, hinted);
+	let mid = match workload {
 		ScanWorkload::Read => format!("reads - {index_slug}"),
 		ScanWorkload::ReadWrite {
 			write_ratio_percent: p,
 		} => format!("reads+writes ({p}%) - {index_slug}"),
+		ScanWorkload::Perturbed => format!("reads (perturbed) - {index_slug}"),
+		ScanWorkload::AfterDeletes => format!("reads (after deletes) - {index_slug}"),
 	};
 	format!("[S]can · {id} · {name} - {mid} ({samples})")
 }
@@ -126,21 +587,342 @@ pub(crate) fn scan_run_row_label(id: &str, name: &str, samples: u32, run: &ScanR
 impl ScanRun {
 	/// Short label for charts (query text + leg description).
 	pub(crate) fn chart_label(&self, query: &str) -> String {
-		let index_slug = if self.indexed {
-			"indexed"
-		} else {
-			"no-index"
-		};
+		let index_slug = scan_leg_slug(self.indexed, self.hinted);
 		match &self.workload {
 			ScanWorkload::Read => format!("{query} - reads - {index_slug}"),
 			ScanWorkload::ReadWrite {
 				write_ratio_percent: p,
 			} => format!("{query} - reads+writes ({p}%) - {index_slug}"),
+			ScanWorkload::Perturbed => format!("{query} - reads (perturbed) - {index_slug}"),
+			ScanWorkload::AfterDeletes => {
+				format!("{query} - reads (after deletes) - {index_slug}")
+			}
 		}
 	}
 }
 
-#[derive(Serialize)]
+impl ScanResult {
+	/// The row labels and results of the updates on the idle table and during the index build.
+	pub(crate) fn build_write_rows(&self) -> Vec<(String, &OperationResult)> {
+		let id = &self.id;
+		let mut rows = Vec::new();
+		if let Some(res) = &self.idle_writes {
+			rows.push((format!("[I]ndex · {id} · updates idle"), res));
+		}
+		if let Some(res) = &self.build_writes {
+			rows.push((format!("[I]ndex · {id} · updates during build"), res));
+		}
+		rows
+	}
+
+	/// OPS speedup of each identical read leg over its perturbed counterpart, with the leg.
+	pub(crate) fn cache_speedups(&self) -> impl Iterator<Item = (&ScanRun, f64)> {
+		self.runs.iter().filter(|r| matches!(r.workload, ScanWorkload::Perturbed)).filter_map(
+			|perturbed| {
+				let identical = self.runs.iter().find(|r| {
+					matches!(r.workload, ScanWorkload::Read)
+						&& r.indexed == perturbed.indexed
+						&& !r.hinted
+				})?;
+				let baseline = perturbed.result.as_ref().filter(|p| p.ops() > 0.0)?;
+				Some((identical, identical.result.as_ref()?.ops() / baseline.ops()))
+			},
+		)
+	}
+}
+
+/// CPU and memory allocated to the server, used to normalise throughput across setups.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct ServerResources {
+	/// CPU cores available to the server: the container CPU limit, or every host core.
+	pub(crate) cores: f64,
+	/// Container memory limit (bytes), when one was set.
+	pub(crate) memory_limit: Option<u64>,
+	/// Whether `cores` comes from a container CPU limit rather than the host.
+	pub(crate) limited: bool,
+}
+
+impl ServerResources {
+	/// Uses the container limits when set, falling back to the host core count.
+	pub(crate) fn new(limits: Option<(f64, Option<u64>)>) -> Self {
+		match limits {
+			Some((cores, memory_limit)) if cores > 0.0 => Self {
+				cores,
+				memory_limit,
+				limited: true,
+			},
+			limits => Self {
+				cores: num_cpus::get() as f64,
+				memory_limit: limits.and_then(|(_, memory)| memory),
+				limited: false,
+			},
+		}
+	}
+}
+
+impl Display for ServerResources {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let source = match self.limited {
+			true => "container limit",
+			false => "host",
+		};
+		write!(f, "Server cores: {} ({source})", self.cores)?;
+		if let Some(memory) = self.memory_limit {
+			write!(f, " · memory limit {}", ByteSize(memory))?;
+		}
+		Ok(())
+	}
+}
+
+/// Latency statistics (µs) for one side of a [`LatencySplit`].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LatencySummary {
+	/// Mean latency.
+	mean: f64,
+	/// Median latency.
+	q50: u64,
+	/// 99th percentile latency.
+	q99: u64,
+}
+
+impl From<&Histogram<u64>> for LatencySummary {
+	fn from(histogram: &Histogram<u64>) -> Self {
+		Self {
+			mean: histogram.mean(),
+			q50: histogram.value_at_quantile(0.50),
+			q99: histogram.value_at_quantile(0.99),
+		}
+	}
+}
+
+/// Client-observed vs engine-reported latency of the same sampled reads.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LatencySplit {
+	/// Number of sampled reads.
+	samples: u64,
+	/// Wall-clock latency seen by the client (network, driver, and engine).
+	client: LatencySummary,
+	/// Execution time reported by the engine itself.
+	server: LatencySummary,
+}
+
+impl LatencySplit {
+	/// Summarises the client and server histograms of the sampled reads.
+	pub(crate) fn new(client: &Histogram<u64>, server: &Histogram<u64>) -> Self {
+		Self {
+			samples: client.len(),
+			client: client.into(),
+			server: server.into(),
+		}
+	}
+
+	/// Share of the mean client latency spent inside the engine, in percent.
+	pub(crate) fn server_share(&self) -> f64 {
+		match self.client.mean > 0.0 {
+			true => self.server.mean / self.client.mean * 100.0,
+			false => 0.0,
+		}
+	}
+}
+
+/// Writes notifying open live queries, and how long each notification took to arrive.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LiveQueries {
+	/// Number of live queries open during the writes.
+	pub(crate) subscribers: u32,
+	/// The writes, each notifying every live query.
+	pub(crate) writes: Option<OperationResult>,
+	/// Notifications expected across all the live queries.
+	expected: u64,
+	/// Notifications received before the live queries were closed.
+	delivered: u64,
+	/// Time from each event being written to a live query being notified of it.
+	latency: Option<LatencySummary>,
+}
+
+impl LiveQueries {
+	/// Summarises the notifications received by all the live queries.
+	pub(crate) fn new(
+		subscribers: u32,
+		writes: Option<OperationResult>,
+		expected: u64,
+		latency: &Histogram<u64>,
+	) -> Self {
+		Self {
+			subscribers,
+			writes,
+			expected,
+			delivered: latency.len(),
+			latency: (!latency.is_empty()).then(|| latency.into()),
+		}
+	}
+}
+
+impl Display for LiveQueries {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Live queries ({} open): {} of {} notifications delivered",
+			self.subscribers, self.delivered, self.expected,
+		)?;
+		if let Some(latency) = &self.latency {
+			write!(
+				f,
+				" · mean {:.3} ms, p50 {:.3} ms, p99 {:.3} ms",
+				latency.mean / 1000.0,
+				latency.q50 as f64 / 1000.0,
+				latency.q99 as f64 / 1000.0,
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Writes to the primary, and how long each took to be visible on a replica.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ReplicationLag {
+	/// The writes, each polled for on the replica.
+	pub(crate) writes: Option<OperationResult>,
+	/// Writes expected on the replica.
+	expected: u64,
+	/// Writes seen on the replica before the polling stopped.
+	replicated: u64,
+	/// Time from each write being made to it being seen on the replica.
+	lag: Option<LatencySummary>,
+}
+
+impl ReplicationLag {
+	/// Summarises the writes seen on the replica.
+	pub(crate) fn new(
+		writes: Option<OperationResult>,
+		expected: u64,
+		lag: &Histogram<u64>,
+	) -> Self {
+		Self {
+			writes,
+			expected,
+			replicated: lag.len(),
+			lag: (!lag.is_empty()).then(|| lag.into()),
+		}
+	}
+}
+
+impl Display for ReplicationLag {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Replication lag: {} of {} writes seen on the replica",
+			self.replicated, self.expected,
+		)?;
+		if let Some(lag) = &self.lag {
+			write!(
+				f,
+				" · mean {:.3} ms, p50 {:.3} ms, p99 {:.3} ms",
+				lag.mean / 1000.0,
+				lag.q50 as f64 / 1000.0,
+				lag.q99 as f64 / 1000.0,
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl Display for LatencySplit {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Server timings ({} sampled reads): client mean {:.3} ms, p99 {:.3} ms · server mean {:.3} ms, p99 {:.3} ms · {:.1}% in the engine",
+			self.samples,
+			self.client.mean / 1000.0,
+			self.client.q99 as f64 / 1000.0,
+			self.server.mean / 1000.0,
+			self.server.q99 as f64 / 1000.0,
+			self.server_share(),
+		)
+	}
+}
+
+/// Stability of the ids returned by a scan repeated while no writes occur.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ScanConsistency {
+	/// Number of repeated scans compared.
+	pub(crate) repeats: usize,
+	/// Rows returned by the first scan.
+	pub(crate) rows: usize,
+	/// Most ids returned more than once within a single scan.
+	pub(crate) duplicates: usize,
+	/// Whether a repeated scan returned a different set of ids.
+	pub(crate) unstable_set: bool,
+	/// Whether a repeated scan returned the same ids in a different order.
+	pub(crate) unstable_order: bool,
+	/// Ids also returned by the next page, when the scan has a limit.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) page_overlap: Option<usize>,
+}
+
+impl ScanConsistency {
+	/// Compares the ids of repeated scans with each other, and with the page following them.
+	pub(crate) fn check(scans: &[Vec<String>], next_page: Option<&[String]>) -> Self {
+		let first = scans.first().map(Vec::as_slice).unwrap_or_default();
+		let ids: HashSet<&String> = first.iter().collect();
+		let mut result = Self {
+			repeats: scans.len(),
+			rows: first.len(),
+			duplicates: 0,
+			unstable_set: false,
+			unstable_order: false,
+			page_overlap: next_page.map(|page| page.iter().filter(|id| ids.contains(id)).count()),
+		};
+		for scan in scans {
+			let unique: HashSet<&String> = scan.iter().collect();
+			result.duplicates = result.duplicates.max(scan.len() - unique.len());
+			if unique != ids {
+				result.unstable_set = true;
+			} else if scan.as_slice() != first {
+				result.unstable_order = true;
+			}
+		}
+		result
+	}
+
+	/// Whether every repeated scan returned the same ids, in the same order, without duplicates.
+	pub(crate) fn is_consistent(&self) -> bool {
+		self.duplicates == 0
+			&& !self.unstable_set
+			&& !self.unstable_order
+			&& self.page_overlap.unwrap_or(0) == 0
+	}
+}
+
+impl Display for ScanConsistency {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let mut issues = vec![];
+		if self.duplicates > 0 {
+			issues.push(format!("{} duplicate ids", self.duplicates));
+		}
+		if self.unstable_set {
+			issues.push("unstable result set".to_string());
+		}
+		if self.unstable_order {
+			issues.push("unstable order".to_string());
+		}
+		if let Some(overlap) = self.page_overlap.filter(|o| *o > 0) {
+			issues.push(format!("{overlap} ids repeated on the next page"));
+		}
+		match issues.is_empty() {
+			true => write!(f, "consistent across {} scans of {} rows", self.repeats, self.rows),
+			false => write!(
+				f,
+				"inconsistent across {} scans of {} rows: {}",
+				self.repeats,
+				self.rows,
+				issues.join(", ")
+			),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
 /// Aggregated timings for one logical scan benchmark (`id`) including index ops when applicable.
 pub(crate) struct ScanResult {
 	/// Stable scan identifier from config.
@@ -153,8 +935,17 @@ pub(crate) struct ScanResult {
 	pub(crate) index_build: Option<OperationResult>,
 	/// Index teardown phase.
 	pub(crate) index_remove: Option<OperationResult>,
+	/// Updates on the idle table before the index build, when `--index-build-writes` is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) idle_writes: Option<OperationResult>,
+	/// The same updates run while the index builds, when `--index-build-writes` is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) build_writes: Option<OperationResult>,
 	/// Timed scan legs in benchmark order (baseline → optional write-mix → indexed variants).
 	pub(crate) runs: Vec<ScanRun>,
+	/// Stability of the returned ids, when `--check-scan-consistency` is set.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) consistency: Option<ScanConsistency>,
 }
 
 /// Column titles for the ASCII summary table ([`BenchmarkResult`]'s [`Display`] impl).
@@ -223,14 +1014,97 @@ impl Display for BenchmarkResult {
 		if let Some(res) = &self.reads {
 			table.add_row(res.output("[R]ead"));
 		}
+		// Add the restricted [R]eads results to the output
+		if let Some(res) = &self.restricted_reads {
+			table.add_row(res.output("[R]ead (restricted)"));
+		}
+		// Add the secondary [R]eads results to the output
+		for (name, result) in self.secondary_reads.iter().flat_map(SecondaryReads::rows) {
+			if let Some(res) = result {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
+		// Add the [L]ink results to the output
+		for (name, result) in self.relations.iter().flat_map(Relations::rows) {
+			if let Some(res) = result {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
+		// Add the live query [N]otify results to the output
+		if let Some(live) = &self.live_queries {
+			let name = live_query_row_label(live.subscribers);
+			if let Some(res) = &live.writes {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
+		// Add the replicated [C]reate results to the output
+		if let Some(replication) = &self.replication_lag {
+			let name = "[C]reate (replicated)".to_string();
+			if let Some(res) = &replication.writes {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
 		// Add the [U]pdates results to the output
 		if let Some(res) = &self.updates {
 			table.add_row(res.output("[U]pdate"));
 		}
+		// Add the path [U]pdates results to the output
+		if let Some(res) = &self.path_updates {
+			table.add_row(res.output("[U]pdate (path)"));
+		}
+		// Add the synced [U]pdates results to the output
+		for (interval, result) in &self.synced_updates {
+			let name = synced_update_row_label(*interval);
+			if let Some(res) = &result {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
+		// Add the [S]oak results to the output
+		for window in &self.soak {
+			let name = window.row_label();
+			if let Some(res) = &window.result {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
 		// Add the [D]eletes results to the output
 		if let Some(res) = &self.deletes {
 			table.add_row(res.output("[D]elete"));
 		}
+		// Add the bulk [D]eletes results to the output
+		for (strategy, result) in &self.delete_strategies {
+			let name = delete_strategy_row_label(strategy);
+			if let Some(res) = &result {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
 		for scan in &self.scans {
 			for run in scan.runs.iter().filter(|r| !r.indexed) {
 				let label = scan_run_row_label(&scan.id, &scan.name, scan.samples, run);
@@ -252,6 +1126,9 @@ impl Display for BenchmarkResult {
 					table.add_row(cells);
 				}
 			}
+			for (label, res) in scan.build_write_rows() {
+				table.add_row(res.output(label));
+			}
 			for run in scan.runs.iter().filter(|r| r.indexed) {
 				let label = scan_run_row_label(&scan.id, &scan.name, scan.samples, run);
 				if let Some(res) = &run.result {
@@ -267,14 +1144,46 @@ impl Display for BenchmarkResult {
 				if let Some(res) = &scan.index_remove {
 					table.add_row(res.output(label));
 				} else {
-					let mut cells = vec![label];
+					let mut cells = vec![label];
+					cells.extend(SKIP.iter().map(|s| s.to_string()));
+					table.add_row(cells);
+				}
+			}
+		}
+		for walk in &self.pagination {
+			let name = walk.row_label();
+			if let Some(res) = &walk.result {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
+		for stage in &self.growth {
+			for (phase, result) in stage.phases() {
+				let name = growth_row_label(stage.records, &phase);
+				if let Some(res) = result {
+					table.add_row(res.output(name));
+				} else {
+					let mut cells = vec![name];
 					cells.extend(SKIP.iter().map(|s| s.to_string()));
 					table.add_row(cells);
 				}
 			}
 		}
 		for (name, samples, groups, result) in &self.batches {
-			let name = format!("[B]atch::{name} ({samples} batches of {groups})");
+			let name = batch_row_label(name, *samples, *groups);
+			if let Some(res) = &result {
+				table.add_row(res.output(name));
+			} else {
+				let mut cells = vec![name];
+				cells.extend(SKIP.iter().map(|s| s.to_string()));
+				table.add_row(cells);
+			}
+		}
+		for (name, samples, result) in &self.time_series {
+			let name = time_series_row_label(name, *samples);
 			if let Some(res) = &result {
 				table.add_row(res.output(name));
 			} else {
@@ -296,11 +1205,112 @@ impl Display for BenchmarkResult {
 		let column = table.column_mut(11).expect("The table needs at least 12 columns");
 		column.set_cell_alignment(CellAlignment::Right);
 		// Output the formatted table
-		write!(f, "{table}")
+		write!(f, "{table}")?;
+		// Flag scan legs whose throughput mostly comes from result caching
+		for scan in &self.scans {
+			for (run, speedup) in scan.cache_speedups() {
+				if speedup >= CACHE_HIT_SPEEDUP {
+					let label = scan_run_row_label(&scan.id, &scan.name, scan.samples, run);
+					write!(f, "\n{label}: {speedup:.1}× perturbed OPS, mostly cache hits")?;
+				}
+			}
+		}
+		// Explain the empty rows of the scenarios skipped by the spec overrides
+		for label in &self.skipped {
+			write!(f, "\n{label}: skipped for this database by the benchmark TOML")?;
+		}
+		// Output the duration and reclaimed space of each compaction
+		for compaction in &self.compactions {
+			write!(f, "\n{compaction}")?;
+		}
+		// Output the duration and size of the backup, and the duration of its restore
+		if let Some(backup) = &self.backup {
+			write!(f, "\n{backup}")?;
+		}
+		// Output the size on disk and write amplification after each load
+		for footprint in &self.footprints {
+			write!(f, "\n{footprint}")?;
+		}
+		// Output the stability of the ids returned by each scan
+		for scan in &self.scans {
+			if let Some(consistency) = &scan.consistency {
+				write!(f, "\n[S]can · {} · {}: {consistency}", scan.id, scan.name)?;
+			}
+		}
+		// Output the notification latency of the live queries
+		if let Some(live) = &self.live_queries {
+			write!(f, "\n{live}")?;
+		}
+		// Output the lag of the writes seen on the replica
+		if let Some(replication) = &self.replication_lag {
+			write!(f, "\n{replication}")?;
+		}
+		// Output the client vs server latency split
+		if let Some(split) = &self.server_timings {
+			write!(f, "\n{split}")?;
+		}
+		// Output the throughput efficiency table
+		if let Some(resources) = &self.resources {
+			write!(f, "\n{resources}\n{}", self.efficiency_table(resources))?;
+		}
+		Ok(())
 	}
 }
 
 impl BenchmarkResult {
+	/// Single-record phases with their row labels, in table order.
+	fn crud_phases(&self) -> [(&'static str, Option<&OperationResult>); 6] {
+		[
+			("[C]reate", self.creates.as_ref()),
+			("[R]ead", self.reads.as_ref()),
+			("[R]ead (restricted)", self.restricted_reads.as_ref()),
+			("[U]pdate", self.updates.as_ref()),
+			("[U]pdate (path)", self.path_updates.as_ref()),
+			("[D]elete", self.deletes.as_ref()),
+		]
+	}
+
+	/// Throughput of each single-record phase normalised by cores and resident memory,
+	/// plus the cost per million operations when an hourly price was given.
+	fn efficiency_table(&self, resources: &ServerResources) -> Table {
+		let cost_per_hour = self.metadata.as_ref().and_then(|m| m.cost_per_hour);
+		let mut table = Table::new();
+		table
+			.load_preset(UTF8_FULL)
+			.apply_modifier(UTF8_ROUND_CORNERS)
+			.set_content_arrangement(ContentArrangement::Dynamic);
+		let mut headers = vec!["Test", "OPS", "OPS / core", "OPS / GB RSS"];
+		if cost_per_hour.is_some() {
+			headers.push("Cost / M ops");
+		}
+		table.set_header(
+			headers
+				.into_iter()
+				.map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Blue)),
+		);
+		for (label, res) in self.crud_phases() {
+			if let Some(res) = res {
+				let mut row = vec![
+					label.to_string(),
+					format!("{:.2}", res.ops),
+					format!("{:.2}", res.ops_per_core(resources.cores)),
+					res.ops_per_gb().map(|v| format!("{v:.2}")).unwrap_or_else(|| "-".to_string()),
+				];
+				if let Some(cost_per_hour) = cost_per_hour {
+					let cost = cost_per_million_ops(res.ops, cost_per_hour);
+					row.push(cost.map(|v| format!("{v:.4}")).unwrap_or_else(|| "-".to_string()));
+				}
+				table.add_row(row);
+			}
+		}
+		for column in 1..table.column_count() {
+			if let Some(column) = table.column_mut(column) {
+				column.set_cell_alignment(CellAlignment::Right);
+			}
+		}
+		table
+	}
+
 	/// Writes [`CSV_HEADERS`] and one row per completed phase to `path`.
 	pub(crate) fn to_csv(&self, path: &str) -> Result<(), csv::Error> {
 		let mut w = Writer::from_path(path)?;
@@ -314,14 +1324,97 @@ impl BenchmarkResult {
 		if let Some(res) = &self.reads {
 			w.write_record(res.output_csv("[R]ead"))?;
 		}
+		// Add the restricted [R]eads results to the output
+		if let Some(res) = &self.restricted_reads {
+			w.write_record(res.output_csv("[R]ead (restricted)"))?;
+		}
+		// Add the secondary [R]eads results to the output
+		for (name, result) in self.secondary_reads.iter().flat_map(SecondaryReads::rows) {
+			if let Some(res) = result {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
+		// Add the [L]ink results to the output
+		for (name, result) in self.relations.iter().flat_map(Relations::rows) {
+			if let Some(res) = result {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
+		// Add the live query [N]otify results to the output
+		if let Some(live) = &self.live_queries {
+			let name = live_query_row_label(live.subscribers);
+			if let Some(res) = &live.writes {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
+		// Add the replicated [C]reate results to the output
+		if let Some(replication) = &self.replication_lag {
+			let name = "[C]reate (replicated)".to_string();
+			if let Some(res) = &replication.writes {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
 		// Add the [U]pdates results to the output
 		if let Some(res) = &self.updates {
 			w.write_record(res.output_csv("[U]pdate"))?;
 		}
+		// Add the path [U]pdates results to the output
+		if let Some(res) = &self.path_updates {
+			w.write_record(res.output_csv("[U]pdate (path)"))?;
+		}
+		// Add the synced [U]pdates results to the output
+		for (interval, result) in &self.synced_updates {
+			let name = synced_update_row_label(*interval);
+			if let Some(res) = &result {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
+		// Add the [S]oak results to the output
+		for window in &self.soak {
+			let name = window.row_label();
+			if let Some(res) = &window.result {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
 		// Add the [D]eletes results to the output
 		if let Some(res) = &self.deletes {
 			w.write_record(res.output_csv("[D]elete"))?;
 		}
+		// Add the bulk [D]eletes results to the output
+		for (strategy, result) in &self.delete_strategies {
+			let name = delete_strategy_row_label(strategy);
+			if let Some(res) = &result {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
 		// Add the [S]cans results to the output
 		for scan in &self.scans {
 			for run in scan.runs.iter().filter(|r| !r.indexed) {
@@ -344,6 +1437,9 @@ impl BenchmarkResult {
 					w.write_record(cells)?;
 				}
 			}
+			for (label, res) in scan.build_write_rows() {
+				w.write_record(res.output_csv(label))?;
+			}
 			for run in scan.runs.iter().filter(|r| r.indexed) {
 				let label = scan_run_row_label(&scan.id, &scan.name, scan.samples, run);
 				if let Some(res) = &run.result {
@@ -365,9 +1461,44 @@ impl BenchmarkResult {
 				}
 			}
 		}
+		// Add the [P]agination results to the output
+		for walk in &self.pagination {
+			let name = walk.row_label();
+			if let Some(res) = &walk.result {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
+		// Add the [G]rowth results to the output
+		for stage in &self.growth {
+			for (phase, result) in stage.phases() {
+				let name = growth_row_label(stage.records, &phase);
+				if let Some(res) = result {
+					w.write_record(res.output_csv(name))?;
+				} else {
+					let mut cells = vec![name];
+					cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+					w.write_record(cells)?;
+				}
+			}
+		}
 		// Add the [B]atch results to the output
 		for (name, samples, groups, result) in &self.batches {
-			let name = format!("[B]atch::{name} ({samples} batches of {groups})");
+			let name = batch_row_label(name, *samples, *groups);
+			if let Some(res) = &result {
+				w.write_record(res.output_csv(name))?;
+			} else {
+				let mut cells = vec![name];
+				cells.extend(CSV_SKIP.iter().map(|s| s.to_string()));
+				w.write_record(cells)?;
+			}
+		}
+		// Add the [T]ime series results to the output
+		for (name, samples, result) in &self.time_series {
+			let name = time_series_row_label(name, *samples);
 			if let Some(res) = &result {
 				w.write_record(res.output_csv(name))?;
 			} else {
@@ -402,6 +1533,8 @@ struct StatsCollector {
 	disk_read_samples: Vec<u64>,
 	/// Monotonic disk write deltas since baseline.
 	disk_write_samples: Vec<u64>,
+	/// Tasks waiting in the runtime's global queue.
+	queue_samples: Vec<usize>,
 }
 
 impl StatsCollector {
@@ -412,6 +1545,7 @@ impl StatsCollector {
 			memory_samples: Vec::new(),
 			disk_read_samples: Vec::new(),
 			disk_write_samples: Vec::new(),
+			queue_samples: Vec::new(),
 		}
 	}
 
@@ -423,6 +1557,25 @@ impl StatsCollector {
 		self.disk_write_samples.push(disk_writes);
 	}
 
+	/// Append one sample of the runtime's global queue depth.
+	fn add_queue_depth(&mut self, depth: usize) {
+		self.queue_samples.push(depth);
+	}
+
+	/// Deepest sampled runtime queue.
+	fn queue_max(&self) -> usize {
+		self.queue_samples.iter().copied().max().unwrap_or(0)
+	}
+
+	/// Mean depth of the runtime queue across polled samples.
+	fn queue_average(&self) -> f64 {
+		if self.queue_samples.is_empty() {
+			0.0
+		} else {
+			self.queue_samples.iter().sum::<usize>() as f64 / self.queue_samples.len() as f64
+		}
+	}
+
 	/// Mean CPU across polled samples.
 	fn cpu_average(&self) -> f32 {
 		if self.cpu_samples.is_empty() {
@@ -480,12 +1633,26 @@ impl StatsCollector {
 	}
 }
 
+/// Total time the workers of the current runtime have spent busy rather than parked.
+fn runtime_busy() -> Duration {
+	let metrics = Handle::current().metrics();
+	(0..metrics.num_workers()).map(|w| metrics.worker_total_busy_duration(w)).sum()
+}
+
+/// What [`OperationMetric`] samples the resource usage of.
+enum Monitored {
+	/// Process under test (benchmark worker or explicit `--pid`).
+	Process(Pid),
+	/// The cgroups of the datastore containers, with their counters before the phase.
+	Cgroups(Vec<Cgroup>, CgroupStats),
+}
+
 /// Live [`sysinfo`] handle plus background polling used to build an [`OperationResult`].
 pub(super) struct OperationMetric {
 	/// Shared system state for synchronous refresh calls.
 	system: System,
-	/// Process under test (benchmark worker or explicit `--pid`).
-	pid: Pid,
+	/// Process or containers under test.
+	monitored: Monitored,
 	/// Logical operation count for OPS calculation.
 	samples: u32,
 	/// Wall-clock start for elapsed time.
@@ -498,13 +1665,14 @@ pub(super) struct OperationMetric {
 	stats_collector: Arc<Mutex<StatsCollector>>,
 	/// Tokio task driving [`OperationMetric::background_monitor`].
 	monitor_handle: Option<JoinHandle<()>>,
+	/// Busy time of the runtime workers before the phase.
+	runtime_busy: Duration,
 }
 
 impl OperationMetric {
-	/// Starts periodic polling for the given PID (defaults to current process).
-	pub(super) fn new(pid: Option<u32>, samples: u32) -> Self {
-		// We collect the PID
-		let pid = Pid::from(pid.unwrap_or_else(process::id) as usize);
+	/// Starts periodic polling for the container cgroups, when any are given, or else for the
+	/// given PID (defaults to current process).
+	pub(super) fn new(pid: Option<u32>, cgroups: &[Cgroup], samples: u32) -> Self {
 		let refresh_kind = ProcessRefreshKind::nothing().with_memory().with_cpu().with_disk_usage();
 		let system =
 			System::new_with_specifics(RefreshKind::nothing().with_processes(refresh_kind));
@@ -512,8 +1680,31 @@ impl OperationMetric {
 		// Create stats collector
 		let stats_collector = Arc::new(Mutex::new(StatsCollector::new()));
 
+		// Sample every process of the containers when their cgroups can be read
+		if let Some(initial) = CgroupStats::collect(cgroups) {
+			let monitor_handle = tokio::spawn(Self::cgroup_monitor(
+				cgroups.to_vec(),
+				initial,
+				stats_collector.clone(),
+			));
+			return Self {
+				monitored: Monitored::Cgroups(cgroups.to_vec(), initial),
+				samples,
+				system,
+				start_time: Instant::now(),
+				initial_disk_usage: DiskUsage::default(),
+				refresh_kind,
+				stats_collector,
+				monitor_handle: Some(monitor_handle),
+				runtime_busy: runtime_busy(),
+			};
+		}
+
+		// We collect the PID
+		let pid = Pid::from(pid.unwrap_or_else(process::id) as usize);
+
 		let mut metric = Self {
-			pid,
+			monitored: Monitored::Process(pid),
 			samples,
 			system,
 			start_time: Instant::now(),
@@ -521,6 +1712,7 @@ impl OperationMetric {
 			refresh_kind,
 			stats_collector: stats_collector.clone(),
 			monitor_handle: None,
+			runtime_busy: Duration::ZERO,
 		};
 
 		// We collect the disk usage before the test, so we can subtract it from the count after test
@@ -528,6 +1720,7 @@ impl OperationMetric {
 			metric.initial_disk_usage = process.disk_usage();
 		}
 		metric.start_time = Instant::now();
+		metric.runtime_busy = runtime_busy();
 
 		// Spawn background monitoring task
 		let monitor_handle = tokio::spawn(Self::background_monitor(
@@ -543,12 +1736,82 @@ impl OperationMetric {
 
 	/// Refreshes and returns the watched [`Process`], if still alive.
 	fn collect_process(&mut self) -> Option<&Process> {
+		let Monitored::Process(pid) = self.monitored else {
+			return None;
+		};
 		self.system.refresh_processes_specifics(
-			ProcessesToUpdate::Some(&[self.pid]),
+			ProcessesToUpdate::Some(&[pid]),
 			true,
 			self.refresh_kind,
 		);
-		self.system.process(self.pid)
+		self.system.process(pid)
+	}
+
+	/// Sets the samples of a phase which ran until a deadline rather than for a sample count.
+	pub(super) fn completed(&mut self, samples: u32) {
+		self.samples = samples;
+	}
+
+	/// Final CPU (normalised by core count), resident memory, and disk I/O since the start.
+	fn collect_usage(&mut self) -> (f32, u64, DiskUsage) {
+		if let Monitored::Cgroups(cgroups, initial) = &self.monitored {
+			let Some(stats) = CgroupStats::collect(cgroups) else {
+				return (0.0, 0, DiskUsage::default());
+			};
+			let disk_usage = DiskUsage {
+				total_written_bytes: stats.written_bytes.saturating_sub(initial.written_bytes),
+				written_bytes: 0,
+				total_read_bytes: stats.read_bytes.saturating_sub(initial.read_bytes),
+				read_bytes: 0,
+			};
+			let cpu = stats.cpu_since(initial, self.start_time.elapsed());
+			return (cpu, stats.memory, disk_usage);
+		}
+		let initial_disk_usage = self.initial_disk_usage;
+		let Some(process) = self.collect_process() else {
+			return (0.0, 0, DiskUsage::default());
+		};
+		let mut disk_usage = process.disk_usage();
+		// Subtract the initial disk usage
+		disk_usage.total_written_bytes -= initial_disk_usage.total_written_bytes;
+		disk_usage.total_read_bytes -= initial_disk_usage.total_read_bytes;
+		// Divide the cpu usage by the number of cpus to get a normalized valued
+		let cpu = process.cpu_usage() / num_cpus::get() as f32;
+		(cpu, process.memory(), disk_usage)
+	}
+
+	/// Polls the summed CPU/memory/disk of the cgroups on a fixed interval until they are removed.
+	async fn cgroup_monitor(
+		cgroups: Vec<Cgroup>,
+		initial: CgroupStats,
+		stats_collector: Arc<Mutex<StatsCollector>>,
+	) {
+		let mut interval = tokio::time::interval(Duration::from_millis(250));
+		let mut previous = (initial, Instant::now());
+
+		loop {
+			interval.tick().await;
+
+			// Containers which were stopped end the monitoring
+			let Some(stats) = CgroupStats::collect(&cgroups) else {
+				break;
+			};
+			let now = Instant::now();
+			let cpu = stats.cpu_since(&previous.0, now - previous.1);
+			previous = (stats, now);
+			let depth = Handle::current().metrics().global_queue_depth();
+
+			// Add sample to collector
+			if let Ok(mut collector) = stats_collector.lock() {
+				collector.add_sample(
+					cpu,
+					stats.memory,
+					stats.read_bytes.saturating_sub(initial.read_bytes),
+					stats.written_bytes.saturating_sub(initial.written_bytes),
+				);
+				collector.add_queue_depth(depth);
+			}
+		}
 	}
 
 	/// Polls CPU/memory/disk for `pid` on a fixed interval until the process exits.
@@ -579,10 +1842,12 @@ impl OperationMetric {
 				let disk_writes = disk_usage
 					.total_written_bytes
 					.saturating_sub(initial_disk_usage.total_written_bytes);
+				let depth = Handle::current().metrics().global_queue_depth();
 
 				// Add sample to collector
 				if let Ok(mut collector) = stats_collector.lock() {
 					collector.add_sample(cpu, memory, disk_reads, disk_writes);
+					collector.add_queue_depth(depth);
 				}
 			} else {
 				// Process no longer exists, stop monitoring
@@ -592,7 +1857,7 @@ impl OperationMetric {
 	}
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 /// Histogram-backed latency stats plus resource usage for one benchmark phase.
 pub(crate) struct OperationResult {
 	/// Mean latency (microseconds, HDR histogram centroids).
@@ -621,6 +1886,9 @@ pub(crate) struct OperationResult {
 	elapsed: Duration,
 	/// Number of logical iterations aggregated into `histogram`.
 	samples: u32,
+	/// The complete latency histogram (µs), as base64 of its HdrHistogram V2 deflate encoding.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	histogram: Option<String>,
 	/// Snapshot CPU at end of phase (normalised by core count).
 	cpu_usage: f32,
 	/// Min / max / avg from polled samples when available.
@@ -636,14 +1904,140 @@ pub(crate) struct OperationResult {
 	/// Mean RSS across polls (bytes).
 	memory_avg: u64,
 	/// Delta disk bytes read/written attributed to the process.
+	#[serde(deserialize_with = "DiskUsageDef::deserialize")]
 	disk_usage: DiskUsage,
 	/// Host load averages at end of phase.
+	#[serde(deserialize_with = "LoadAvgDef::deserialize")]
 	load_avg: LoadAvg,
+	/// Failed operations per error category, omitted when none failed.
+	#[serde(default, skip_serializing_if = "ErrorSummary::is_empty")]
+	errors: ErrorSummary,
+	/// The samples which completed and failed in each second, with `--continue-on-error`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	error_rate: Vec<ErrorWindow>,
+	/// Failed attempts which were retried, per error category, omitted when none were.
+	#[serde(default, skip_serializing_if = "ErrorSummary::is_empty")]
+	retries: ErrorSummary,
+	/// Latency of each client and thread, when `--worker-histograms` is set.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	workers: Vec<WorkerLatency>,
+	/// How long the samples waited for a worker after they arrived, with `--arrival-rate`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	queueing: Option<Queueing>,
+	/// How deep the client runtime queue grew, and how busy its workers were.
+	#[serde(default)]
+	backpressure: Backpressure,
+	/// Whether the phase was limited by the clients rather than the datastore.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	client_bound: bool,
+}
+
+/// Share of the phase the client runtime workers can be busy before the clients are saturated.
+const CLIENT_SATURATION: f64 = 0.9;
+
+/// Queue depth and worker utilisation of the client runtime during one phase.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Backpressure {
+	/// Deepest sampled queue of tasks waiting for a runtime worker.
+	pub(crate) queue_max: usize,
+	/// Mean sampled depth of the runtime queue.
+	pub(crate) queue_avg: f64,
+	/// Share of the phase the runtime workers were busy rather than parked (0–1).
+	pub(crate) utilisation: f64,
+}
+
+/// Latency (µs) of the samples run by one task of one client in a phase.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct WorkerLatency {
+	/// The client, numbered from 1.
+	pub(crate) client: u32,
+	/// The task within the client, numbered from 1.
+	pub(crate) thread: u32,
+	/// Number of samples run by this task.
+	samples: u64,
+	/// Mean latency.
+	mean: f64,
+	/// Median latency.
+	q50: u64,
+	/// 99th percentile latency.
+	pub(crate) q99: u64,
+	/// Maximum latency.
+	max: u64,
+	/// The complete latency histogram, encoded as in [`OperationResult`].
+	#[serde(skip_serializing_if = "Option::is_none")]
+	histogram: Option<String>,
+}
+
+impl WorkerLatency {
+	/// Summarises the histogram of the given client and thread.
+	pub(crate) fn new(client: u32, thread: u32, histogram: &Histogram<u64>) -> Self {
+		Self {
+			client,
+			thread,
+			samples: histogram.len(),
+			mean: histogram.mean(),
+			q50: histogram.value_at_quantile(0.50),
+			q99: histogram.value_at_quantile(0.99),
+			max: histogram.max(),
+			histogram: histograms::encode(histogram),
+		}
+	}
+}
+
+/// Time (µs) the samples of an open-loop phase waited for a free worker after they arrived.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct Queueing {
+	/// The arrivals per second the samples were scheduled at.
+	pub(crate) rate: f64,
+	/// Mean time queued.
+	pub(crate) mean: f64,
+	/// Median time queued.
+	pub(crate) q50: u64,
+	/// 99th percentile time queued.
+	pub(crate) q99: u64,
+	/// Longest time queued.
+	pub(crate) max: u64,
+}
+
+impl Queueing {
+	/// Summarises the times queued at the given arrival rate.
+	pub(crate) fn new(rate: f64, histogram: &Histogram<u64>) -> Self {
+		Self {
+			rate,
+			mean: histogram.mean(),
+			q50: histogram.value_at_quantile(0.50),
+			q99: histogram.value_at_quantile(0.99),
+			max: histogram.max(),
+		}
+	}
+}
+
+/// Reads back the [`DiskUsage`] which `sysinfo` only serialises.
+#[derive(Deserialize)]
+#[serde(remote = "DiskUsage")]
+struct DiskUsageDef {
+	total_written_bytes: u64,
+	written_bytes: u64,
+	total_read_bytes: u64,
+	read_bytes: u64,
+}
+
+/// Reads back the [`LoadAvg`] which `sysinfo` only serialises.
+#[derive(Deserialize)]
+#[serde(remote = "LoadAvg")]
+struct LoadAvgDef {
+	one: f64,
+	five: f64,
+	fifteen: f64,
 }
 
 impl OperationResult {
 	/// Finalises histogram + [`OperationMetric`] snapshots into serialisable stats.
-	pub(crate) fn new(mut metric: OperationMetric, histogram: Histogram<u64>) -> Self {
+	pub(crate) fn new(
+		mut metric: OperationMetric,
+		histogram: Histogram<u64>,
+		errors: ErrorSummary,
+	) -> Self {
 		let elapsed = metric.start_time.elapsed();
 
 		// Stop the background monitor and wait for it to complete
@@ -676,17 +2070,8 @@ impl OperationResult {
 			(0.0, 0.0, 0.0, 0, 0, 0, 0, 0)
 		};
 
-		// Get final process stats
-		let (mut cpu_usage, used_memory, mut disk_usage) =
-			if let Some(process) = metric.collect_process() {
-				(process.cpu_usage(), process.memory(), process.disk_usage())
-			} else {
-				(0.0, 0, DiskUsage::default())
-			};
-
-		// Subtract the initial disk usage
-		disk_usage.total_written_bytes -= metric.initial_disk_usage.total_written_bytes;
-		disk_usage.total_read_bytes -= metric.initial_disk_usage.total_read_bytes;
+		// Get final process or container stats
+		let (cpu_usage, used_memory, mut disk_usage) = metric.collect_usage();
 
 		// Use monitored disk I/O if available and greater than final snapshot
 		if final_disk_writes > 0 {
@@ -696,8 +2081,22 @@ impl OperationResult {
 			disk_usage.total_read_bytes = disk_usage.total_read_bytes.max(final_disk_reads);
 		}
 
-		// Divide the cpu usage by the number of cpus to get a normalized valued
-		cpu_usage /= num_cpus::get() as f32;
+		// Measure how deep the client runtime queue grew, and how busy its workers were
+		let (queue_max, queue_avg) = match metric.stats_collector.lock() {
+			Ok(collector) => (collector.queue_max(), collector.queue_average()),
+			Err(_) => (0, 0.0),
+		};
+		let capacity = elapsed.as_secs_f64() * Handle::current().metrics().num_workers() as f64;
+		let busy = runtime_busy().saturating_sub(metric.runtime_busy).as_secs_f64();
+		let backpressure = Backpressure {
+			queue_max,
+			queue_avg,
+			utilisation: if capacity > 0.0 {
+				(busy / capacity).min(1.0)
+			} else {
+				0.0
+			},
+		};
 
 		// Metrics
 		let q75 = histogram.value_at_quantile(0.75);
@@ -706,6 +2105,7 @@ impl OperationResult {
 
 		Self {
 			samples: metric.samples,
+			histogram: histograms::encode(&histogram),
 			mean: histogram.mean(),
 			min: histogram.min(),
 			max: histogram.max(),
@@ -728,8 +2128,67 @@ impl OperationResult {
 			memory_avg,
 			disk_usage,
 			load_avg: System::load_average(),
+			errors,
+			error_rate: Vec::new(),
+			retries: ErrorSummary::default(),
+			workers: Vec::new(),
+			queueing: None,
+			backpressure,
+			client_bound: false,
 		}
 	}
+
+	/// Attaches the latency of each client and thread of the phase.
+	pub(crate) fn with_workers(mut self, workers: Vec<WorkerLatency>) -> Self {
+		self.workers = workers;
+		self
+	}
+
+	/// Attaches the completed and failed samples of each second of the phase.
+	pub(crate) fn with_error_rate(mut self, error_rate: Vec<ErrorWindow>) -> Self {
+		self.error_rate = error_rate;
+		self
+	}
+
+	/// The completed and failed samples of each second of the phase, with `--continue-on-error`.
+	pub(crate) fn error_rate(&self) -> &[ErrorWindow] {
+		&self.error_rate
+	}
+
+	/// Attaches the retries of the phase, per error category.
+	pub(crate) fn with_retries(mut self, retries: ErrorSummary) -> Self {
+		self.retries = retries;
+		self
+	}
+
+	/// The retries of the phase, per error category.
+	pub(crate) fn retries(&self) -> &ErrorSummary {
+		&self.retries
+	}
+
+	/// Attaches how long the samples of an open-loop phase queued for a worker.
+	pub(crate) fn with_queueing(mut self, queueing: Option<Queueing>) -> Self {
+		self.queueing = queueing;
+		self
+	}
+
+	/// How long the samples queued for a worker, with `--arrival-rate`.
+	pub(crate) fn queueing(&self) -> Option<&Queueing> {
+		self.queueing.as_ref()
+	}
+
+	/// The failed operations of the phase, per error category.
+	pub(crate) fn errors(&self) -> &ErrorSummary {
+		&self.errors
+	}
+
+	/// The workers with the highest and lowest p99 latency, when the phase recorded more than one.
+	pub(crate) fn worker_spread(&self) -> Option<(&WorkerLatency, &WorkerLatency)> {
+		let workers = self.workers.iter().filter(|w| w.samples > 0);
+		let slowest = workers.clone().max_by_key(|w| w.q99)?;
+		let fastest = workers.min_by_key(|w| w.q99)?;
+		(self.workers.len() > 1).then_some((slowest, fastest))
+	}
 	/// Output the total time for this operation
 	pub(crate) fn total_time(&self) -> String {
 		format_duration(self.elapsed)
@@ -834,6 +2293,16 @@ impl OperationResult {
 		self.ops
 	}
 
+	/// Operations per second for each allocated server core
+	pub(crate) fn ops_per_core(&self, cores: f64) -> f64 {
+		self.ops / cores.max(f64::MIN_POSITIVE)
+	}
+
+	/// Operations per second for each GB of mean resident memory, if memory was sampled
+	pub(crate) fn ops_per_gb(&self) -> Option<f64> {
+		ops_per_gb(self.ops, self.memory_avg, self.used_memory)
+	}
+
 	/// Get the mean duration
 	pub(crate) fn mean(&self) -> f64 {
 		self.mean
@@ -879,6 +2348,27 @@ impl OperationResult {
 		self.q01
 	}
 
+	/// Flags the phase as client-bound when the client runtime was saturated, or its queue stayed
+	/// deeper than its workers, while the datastore used under half of the CPU available to it
+	/// (`capacity`, as a percentage of every host core), and returns whether it was flagged.
+	pub(crate) fn flag_client_bound(&mut self, capacity: f32) -> bool {
+		let workers = Handle::current().metrics().num_workers() as f64;
+		let saturated = self.backpressure.utilisation >= CLIENT_SATURATION
+			|| self.backpressure.queue_avg >= workers;
+		self.client_bound = saturated && self.cpu_avg < capacity / 2.0;
+		self.client_bound
+	}
+
+	/// Get the queue depth and worker utilisation of the client runtime
+	pub(crate) fn backpressure(&self) -> &Backpressure {
+		&self.backpressure
+	}
+
+	/// Get the mean polled CPU usage
+	pub(crate) fn cpu_avg(&self) -> f32 {
+		self.cpu_avg
+	}
+
 	/// Get the CPU usage
 	pub(crate) fn cpu_usage(&self) -> f32 {
 		self.cpu_usage
@@ -894,3 +2384,70 @@ impl OperationResult {
 		&self.disk_usage
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{DiskFootprint, PageMode, PageWalk, ScanConsistency};
+
+	fn ids(ids: &[&str]) -> Vec<String> {
+		ids.iter().map(|id| id.to_string()).collect()
+	}
+
+	#[test]
+	fn consistent_scans() {
+		let scans = vec![ids(&["1", "2", "3"]), ids(&["1", "2", "3"])];
+		let consistency = ScanConsistency::check(&scans, Some(&ids(&["4", "5", "6"])));
+		assert!(consistency.is_consistent());
+		assert_eq!(consistency.page_overlap, Some(0));
+		assert_eq!(consistency.to_string(), "consistent across 2 scans of 3 rows");
+	}
+
+	#[test]
+	fn inconsistent_scans() {
+		let scans = vec![ids(&["1", "2", "3"]), ids(&["2", "1", "3"]), ids(&["1", "1", "3"])];
+		let consistency = ScanConsistency::check(&scans, Some(&ids(&["3", "4", "5"])));
+		assert!(!consistency.is_consistent());
+		assert_eq!(consistency.duplicates, 1);
+		assert!(consistency.unstable_set);
+		assert!(consistency.unstable_order);
+		assert_eq!(
+			consistency.to_string(),
+			"inconsistent across 3 scans of 3 rows: 1 duplicate ids, unstable result set, unstable order, 1 ids repeated on the next page"
+		);
+	}
+
+	#[test]
+	fn page_walk_tenths() {
+		let latencies: Vec<u64> = (1..=20).collect();
+		let walk = PageWalk {
+			mode: PageMode::Offset,
+			page_size: 10,
+			pages: 20,
+			tenths: PageWalk::tenths(&latencies),
+			result: None,
+		};
+		assert_eq!(walk.tenths, vec![1, 3, 5, 7, 9, 11, 13, 15, 17, 19]);
+		assert_eq!(walk.slowdown(), Some(19.0));
+		assert_eq!(PageWalk::tenths(&[4, 8]), vec![4, 8]);
+		assert_eq!(walk.row_label(), "[P]age · offset · 20 pages of 10");
+	}
+
+	#[test]
+	fn write_amplification_needs_the_bytes_written() {
+		let mut footprint = DiskFootprint {
+			after: "creates".to_string(),
+			size: 3 << 20,
+			written: Some(5 << 20),
+			logical: 2 << 20,
+		};
+		assert_eq!(footprint.write_amplification(), Some(2.5));
+		assert_eq!(
+			footprint.to_string(),
+			"Disk footprint after creates: 3.0 MiB on disk for 2.0 MiB of data, 2.50× write amplification"
+		);
+		footprint.written = Some(0);
+		assert_eq!(footprint.write_amplification(), None);
+		footprint.written = None;
+		assert_eq!(footprint.write_amplification(), None);
+	}
+}
@@ -0,0 +1,131 @@
+//! `crud-bench results`: read back the runs stored in SurrealDB with `--store-results`.
+//!
+//! `crud-bench results query` fetches the newest stored runs, optionally only those of one
+//! database, version, or scenario, or since a date, and prints their phases as a table, or as JSON
+//! for trend dashboards.
+
+use crate::storage::{QueriedRun, RunFilter, StorageClient};
+use anyhow::Result;
+use clap::{Args, Subcommand, ValueEnum};
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, CellAlignment, Color, ContentArrangement, Table};
+
+/// Command-line interface for `crud-bench results`.
+#[derive(Args, Debug)]
+#[command(term_width = 0)]
+pub(crate) struct ResultsArgs {
+	#[command(subcommand)]
+	pub(crate) command: ResultsCommand,
+}
+
+/// What to do with the stored results.
+#[derive(Subcommand, Debug)]
+pub(crate) enum ResultsCommand {
+	/// Fetch the stored runs, newest first
+	Query(QueryArgs),
+}
+
+/// Command-line interface for `crud-bench results query`.
+#[derive(Args, Debug)]
+pub(crate) struct QueryArgs {
+	/// SurrealDB endpoint the results are stored in
+	#[arg(long, env = "CRUD_BENCH_STORAGE_ENDPOINT", default_value = "ws://localhost:8000")]
+	pub(crate) storage_endpoint: String,
+
+	/// Only the runs of this database, by its name in the results
+	#[arg(long)]
+	pub(crate) database: Option<String>,
+
+	/// Only the runs of this datastore version
+	#[arg(long)]
+	pub(crate) version: Option<String>,
+
+	/// Only the runs since this date or datetime, such as 2026-01-31
+	#[arg(long)]
+	pub(crate) since: Option<String>,
+
+	/// Only the phases with this row label, such as "[R]ead"
+	#[arg(long)]
+	pub(crate) scenario: Option<String>,
+
+	/// Maximum number of runs to fetch
+	#[arg(long, default_value = "20")]
+	pub(crate) limit: u32,
+
+	/// Format the runs are printed in
+	#[arg(long, value_enum, default_value_t)]
+	pub(crate) format: QueryFormat,
+}
+
+/// How queried runs are printed.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub(crate) enum QueryFormat {
+	/// One table row per phase of each run.
+	#[default]
+	Table,
+	/// One JSON array of runs, each with its phases.
+	Json,
+}
+
+/// Runs the `crud-bench results` subcommand.
+pub(crate) fn run(args: ResultsArgs) -> Result<()> {
+	match args.command {
+		ResultsCommand::Query(args) => query(args),
+	}
+}
+
+/// Fetches the matching runs and prints them.
+fn query(args: QueryArgs) -> Result<()> {
+	let filter = RunFilter {
+		database: args.database,
+		version: args.version,
+		since: args.since,
+		scenario: args.scenario,
+		limit: args.limit,
+	};
+	let runs = tokio::runtime::Runtime::new()?.block_on(async {
+		let client = StorageClient::connect(&args.storage_endpoint).await?;
+		client.query_runs(&filter).await
+	})?;
+	match args.format {
+		QueryFormat::Table => println!("{}", runs_table(&runs)),
+		QueryFormat::Json => println!("{}", serde_json::to_string_pretty(&runs)?),
+	}
+	Ok(())
+}
+
+/// A table with one row per phase of each run, newest run first.
+fn runs_table(runs: &[QueriedRun]) -> Table {
+	let mut table = Table::new();
+	table
+		.load_preset(UTF8_FULL)
+		.apply_modifier(UTF8_ROUND_CORNERS)
+		.set_content_arrangement(ContentArrangement::Dynamic);
+	let headers =
+		["Date", "Database", "Version", "Test", "OPS", "Mean (µs)", "99th (µs)", "Max (µs)"];
+	table.set_header(
+		headers.into_iter().map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Blue)),
+	);
+	for run in runs {
+		for phase in run.operations.iter().chain(&run.scans) {
+			table.add_row([
+				Cell::new(&run.timestamp),
+				Cell::new(run.database.as_deref().unwrap_or("-")),
+				Cell::new(run.version.as_deref().unwrap_or("-")),
+				Cell::new(&phase.scenario),
+				Cell::new(format!("{:.2}", phase.ops)),
+				Cell::new(format!("{:.2}", phase.mean)),
+				Cell::new(phase.q99),
+				Cell::new(phase.max),
+			]);
+		}
+	}
+	// Right align the numeric columns
+	for i in 4..table.column_count() {
+		if let Some(column) = table.column_mut(i) {
+			column.set_cell_alignment(CellAlignment::Right);
+		}
+	}
+	table
+}
@@ -0,0 +1,194 @@
+//! Retries of operations which fail with a transient error, set by the `[retries]` table of the
+//! benchmark definition.
+//!
+//! By default any failed operation fails its phase. With a retry policy, an operation whose error
+//! falls in one of the retried categories (`transient` and `conflict` by default) is attempted
+//! again after an exponential backoff, up to the maximum number of retries. The policy can be
+//! overridden per operation type, and the retries of each phase are counted per category in its
+//! results, so a datastore which needs them is measured as less reliable rather than crashing.
+
+use crate::Phase;
+use crate::error::BenchError;
+use anyhow::{Result, ensure};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The longest a single backoff grows to, however many retries are allowed.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// The `[retries]` table: a default policy, and overrides for each operation type.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Retries {
+	/// Maximum retries of a failed operation, none by default
+	#[serde(default)]
+	max: u32,
+	/// Milliseconds before the first retry, doubled before each of the next
+	#[serde(default = "default_backoff_ms")]
+	backoff_ms: u64,
+	/// The error categories which are retried
+	#[serde(default = "default_on")]
+	on: Vec<RetryOn>,
+	/// Overrides for the creates, including the preloaded and scan records
+	create: Option<RetryOverride>,
+	/// Overrides for the reads
+	read: Option<RetryOverride>,
+	/// Overrides for the updates
+	update: Option<RetryOverride>,
+	/// Overrides for the scans
+	scan: Option<RetryOverride>,
+	/// Overrides for the deletes
+	delete: Option<RetryOverride>,
+	/// Overrides for the batches
+	batch: Option<RetryOverride>,
+}
+
+/// The settings of the default policy which an operation type replaces.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct RetryOverride {
+	max: Option<u32>,
+	backoff_ms: Option<u64>,
+	on: Option<Vec<RetryOn>>,
+}
+
+/// An error category which can be retried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RetryOn {
+	Transient,
+	Conflict,
+	Timeout,
+}
+
+/// How the operations of one phase are retried.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RetryPolicy {
+	/// Maximum retries of a failed operation
+	max: u32,
+	/// Wait before the first retry
+	backoff: Duration,
+	/// The error categories which are retried
+	on: Vec<BenchError>,
+}
+
+fn default_backoff_ms() -> u64 {
+	10
+}
+
+fn default_on() -> Vec<RetryOn> {
+	vec![RetryOn::Transient, RetryOn::Conflict]
+}
+
+impl Default for Retries {
+	fn default() -> Self {
+		Self {
+			max: 0,
+			backoff_ms: default_backoff_ms(),
+			on: default_on(),
+			create: None,
+			read: None,
+			update: None,
+			scan: None,
+			delete: None,
+			batch: None,
+		}
+	}
+}
+
+impl From<RetryOn> for BenchError {
+	fn from(on: RetryOn) -> Self {
+		match on {
+			RetryOn::Transient => Self::Transient,
+			RetryOn::Conflict => Self::Conflict,
+			RetryOn::Timeout => Self::Timeout,
+		}
+	}
+}
+
+impl Retries {
+	/// Checks every backoff is at least a millisecond.
+	pub(crate) fn validate(&self) -> Result<()> {
+		let overrides =
+			[&self.create, &self.read, &self.update, &self.scan, &self.delete, &self.batch];
+		for backoff in overrides.into_iter().flatten().filter_map(|o| o.backoff_ms) {
+			ensure!(backoff > 0, "The retry backoff must be at least 1ms");
+		}
+		ensure!(self.backoff_ms > 0, "The retry backoff must be at least 1ms");
+		Ok(())
+	}
+
+	/// The policy of an operation type, or no retries for the operations outside them.
+	pub(crate) fn policy(&self, phase: Option<Phase>) -> RetryPolicy {
+		let Some(phase) = phase else {
+			return RetryPolicy::default();
+		};
+		let over = match phase {
+			Phase::Create => &self.create,
+			Phase::Read => &self.read,
+			Phase::Update => &self.update,
+			Phase::Scan => &self.scan,
+			Phase::Delete => &self.delete,
+			Phase::Batch => &self.batch,
+		};
+		let over = over.as_ref();
+		RetryPolicy {
+			max: over.and_then(|o| o.max).unwrap_or(self.max),
+			backoff: Duration::from_millis(
+				over.and_then(|o| o.backoff_ms).unwrap_or(self.backoff_ms),
+			),
+			on: over
+				.and_then(|o| o.on.as_ref())
+				.unwrap_or(&self.on)
+				.iter()
+				.map(|&on| on.into())
+				.collect(),
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// The wait before a failed operation is attempted again, when it is retried. The `attempt`
+	/// counts the retries already made.
+	pub(crate) fn backoff(&self, attempt: u32, category: Option<BenchError>) -> Option<Duration> {
+		let retried = category.is_some_and(|c| self.on.contains(&c));
+		(retried && attempt < self.max)
+			.then(|| self.backoff.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_BACKOFF))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn operation_types_override_the_default_policy() -> Result<()> {
+		let retries: Retries = toml::from_str(
+			r#"
+max = 2
+backoff_ms = 5
+[create]
+max = 4
+on = ["transient"]
+"#,
+		)?;
+		retries.validate()?;
+		let read = retries.policy(Some(Phase::Read));
+		assert_eq!(read.backoff(0, Some(BenchError::Conflict)), Some(Duration::from_millis(5)));
+		assert_eq!(read.backoff(1, Some(BenchError::Transient)), Some(Duration::from_millis(10)));
+		assert_eq!(read.backoff(2, Some(BenchError::Transient)), None);
+		assert_eq!(read.backoff(0, Some(BenchError::NotFound)), None);
+		assert_eq!(read.backoff(0, None), None);
+		let create = retries.policy(Some(Phase::Create));
+		assert_eq!(create.backoff(3, Some(BenchError::Transient)), Some(Duration::from_millis(40)));
+		assert_eq!(create.backoff(0, Some(BenchError::Conflict)), None);
+		assert_eq!(retries.policy(None).backoff(0, Some(BenchError::Transient)), None);
+		Ok(())
+	}
+
+	#[test]
+	fn no_retries_by_default() {
+		let policy = Retries::default().policy(Some(Phase::Create));
+		assert_eq!(policy.backoff(0, Some(BenchError::Transient)), None);
+	}
+}
@@ -1,24 +1,36 @@
 #![cfg(feature = "rocksdb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
 use crate::memory::Config;
+use crate::util::kvindex::{KvIndexes, Matches};
+use crate::util::{crate_version, dir_size};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
 use anyhow::{Result, bail};
-use log::error;
+use rocksdb::checkpoint::Checkpoint;
 use rocksdb::{
 	BlockBasedOptions, BottommostLevelCompaction, Cache, CompactOptions, DBCompactionStyle,
-	DBCompressionType, FlushOptions, IteratorMode, LogLevel, OptimisticTransactionDB,
-	OptimisticTransactionOptions, Options, ReadOptions, WaitForCompactOptions, WriteOptions,
+	DBCompressionType, DEFAULT_COLUMN_FAMILY_NAME, Direction, FlushOptions, IteratorMode, LogLevel,
+	OptimisticTransactionDB, OptimisticTransactionOptions, Options, ReadOptions,
+	WaitForCompactOptions, WriteOptions,
 };
 use std::hint::black_box;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use tracing::error;
 
 const DATABASE_DIR: &str = "rocksdb";
 
+/// The checkpoint which the database is backed up to
+const BACKUP_DIR: &str = "rocksdb-backup";
+
+/// The database which the checkpoint is restored into
+const RESTORED_DIR: &str = "rocksdb-restored";
+
 /// Calculate RocksDB specific memory allocation
 fn calculate_rocksdb_memory() -> u64 {
 	// Load the system memory
@@ -27,20 +39,22 @@ fn calculate_rocksdb_memory() -> u64 {
 	memory.cache_gb * 1024 * 1024 * 1024
 }
 
+/// Index entries are keyed after every record, outside the range the scans iterate
+const INDEX_TAG: &[u8] = &[255];
+
 pub(crate) struct RocksDBClientProvider {
 	db: Arc<OptimisticTransactionDB>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
 impl BenchmarkEngine<RocksDBClient> for RocksDBClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Cleanup the data directory
-		std::fs::remove_dir_all(DATABASE_DIR).ok();
+		// Cleanup the data directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_dir_all(DATABASE_DIR).ok();
+		}
 		// Calculate memory allocation
 		let memory = calculate_rocksdb_memory();
 		// Configure custom options
@@ -155,6 +169,7 @@ impl BenchmarkEngine<RocksDBClient> for RocksDBClientProvider {
 		// Create the store
 		Ok(Self {
 			db,
+			indexes: Arc::new(KvIndexes::new(options.emulate_indexes, INDEX_TAG)),
 			sync: options.sync,
 		})
 	}
@@ -162,6 +177,7 @@ impl BenchmarkEngine<RocksDBClient> for RocksDBClientProvider {
 	async fn create_client(&self) -> Result<RocksDBClient> {
 		Ok(RocksDBClient {
 			db: self.db.clone(),
+			indexes: self.indexes.clone(),
 			sync: self.sync,
 		})
 	}
@@ -169,6 +185,7 @@ impl BenchmarkEngine<RocksDBClient> for RocksDBClientProvider {
 
 pub(crate) struct RocksDBClient {
 	db: Arc<OptimisticTransactionDB>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
@@ -176,6 +193,10 @@ impl BenchmarkClient for RocksDBClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("surrealdb-rocksdb")
+	}
+
 	async fn shutdown(&self) -> Result<()> {
 		// No need to run background jobs
 		self.db.cancel_all_background_work(true);
@@ -209,6 +230,43 @@ impl BenchmarkClient for RocksDBClient {
 		Ok(())
 	}
 
+	fn disk_size(&self) -> Option<u64> {
+		dir_size(DATABASE_DIR)
+	}
+
+	async fn backup(&self) -> Result<Option<u64>> {
+		// A checkpoint hard links the immutable files, and copies the rest
+		std::fs::remove_dir_all(BACKUP_DIR).ok();
+		Checkpoint::new(&*self.db)?.create_checkpoint(BACKUP_DIR)?;
+		Ok(dir_size(BACKUP_DIR))
+	}
+
+	async fn restore(&self) -> Result<()> {
+		// Copy the checkpoint, then open it as a database of its own
+		std::fs::remove_dir_all(RESTORED_DIR).ok();
+		std::fs::create_dir_all(RESTORED_DIR)?;
+		for entry in std::fs::read_dir(BACKUP_DIR)? {
+			let entry = entry?;
+			std::fs::copy(entry.path(), Path::new(RESTORED_DIR).join(entry.file_name()))?;
+		}
+		let db: OptimisticTransactionDB = OptimisticTransactionDB::open_default(RESTORED_DIR)?;
+		drop(db);
+		Ok(())
+	}
+
+	async fn remove_backup(&self) -> Result<()> {
+		std::fs::remove_dir_all(RESTORED_DIR).ok();
+		std::fs::remove_dir_all(BACKUP_DIR).ok();
+		Ok(())
+	}
+
+	async fn sync(&self) -> Result<()> {
+		// Flush and sync the WAL to storage
+		self.db.flush_wal(true)?;
+		// Ok
+		Ok(())
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create_bytes(&key.to_ne_bytes(), val).await
 	}
@@ -241,12 +299,84 @@ impl BenchmarkClient for RocksDBClient {
 		self.delete_bytes(&key.into_bytes()).await
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	// Integer keys are stored in native byte order, so a
+	// range of integers is not a contiguous range of keys
+	async fn delete_range_string(&self, from: String, to: String) -> Result<()> {
+		self.delete_range_bytes(from.as_bytes(), to.as_bytes()).await
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn truncate(&self) -> Result<()> {
+		// The records sort before the index entries, as the scans iterate
+		self.delete_range_bytes(&[0u8], &[255u8]).await
+	}
+
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// Writes maintain the index from now on
+		let index = self.indexes.create(spec, name)?;
+		// Set the transaction options
+		let mut to = OptimisticTransactionOptions::default();
+		to.set_snapshot(true);
+		// Set the write options
+		let mut wo = WriteOptions::default();
+		wo.set_sync(self.sync);
+		// Create a new transaction
+		let txn = self.db.transaction_opt(&wo, &to);
+		// Configure read options
+		let mut ro = ReadOptions::default();
+		ro.set_snapshot(&txn.snapshot());
+		ro.set_readahead_size(2 * 1024 * 1024);
+		ro.set_iterate_lower_bound([0u8]);
+		ro.set_iterate_upper_bound([255u8]);
+		// Collect the entries of the existing records
+		let mut entries = Vec::new();
+		for kv in txn.iterator_opt(IteratorMode::Start, ro) {
+			let (key, val) = kv?;
+			if let Some(entry) = index.entry(&key, &BenchValue::decode(&val)?) {
+				entries.push(entry);
+			}
+		}
+		// Write the entries
+		for entry in entries {
+			txn.put(entry, b"")?;
+		}
+		txn.commit()?;
+		Ok(())
+	}
+
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		// Writes stop maintaining the index
+		let index = self.indexes.remove(name)?;
+		// Set the transaction options
+		let mut to = OptimisticTransactionOptions::default();
+		to.set_snapshot(true);
+		// Set the write options
+		let mut wo = WriteOptions::default();
+		wo.set_sync(self.sync);
+		// Create a new transaction
+		let txn = self.db.transaction_opt(&wo, &to);
+		// Configure read options
+		let mut ro = ReadOptions::default();
+		ro.set_snapshot(&txn.snapshot());
+		ro.set_iterate_upper_bound(index.end());
+		// Collect the entries of the index
+		let mut entries = Vec::new();
+		for kv in txn.iterator_opt(IteratorMode::From(index.start(), Direction::Forward), ro) {
+			entries.push(kv?.0);
+		}
+		// Delete the entries
+		for entry in entries {
+			txn.delete(entry)?;
+		}
+		txn.commit()?;
+		Ok(())
 	}
 
 	async fn batch_create_u32(
@@ -318,6 +448,8 @@ impl RocksDBClient {
 	async fn create_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
 		// Serialise the value
 		let val = val.encode()?;
+		// Derive any index entries
+		let entries = self.indexes.entries(key, &val)?;
 		// Set the transaction options
 		let mut to = OptimisticTransactionOptions::default();
 		to.set_snapshot(true);
@@ -328,6 +460,9 @@ impl RocksDBClient {
 		let txn = self.db.transaction_opt(&wo, &to);
 		// Process the data
 		txn.put(key, val)?;
+		for entry in entries {
+			txn.put(entry, b"")?;
+		}
 		txn.commit()?;
 		Ok(())
 	}
@@ -360,6 +495,8 @@ impl RocksDBClient {
 	async fn update_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
 		// Serialise the value
 		let val = val.encode()?;
+		// Derive any index entries
+		let entries = self.indexes.entries(key, &val)?;
 		// Set the transaction options
 		let mut to = OptimisticTransactionOptions::default();
 		to.set_snapshot(true);
@@ -370,6 +507,9 @@ impl RocksDBClient {
 		let txn = self.db.transaction_opt(&wo, &to);
 		// Process the data
 		txn.put(key, val)?;
+		for entry in entries {
+			txn.put(entry, b"")?;
+		}
 		txn.commit()?;
 		Ok(())
 	}
@@ -389,6 +529,19 @@ impl RocksDBClient {
 		Ok(())
 	}
 
+	async fn delete_range_bytes(&self, from: &[u8], to: &[u8]) -> Result<()> {
+		// Get the column family holding the records
+		let Some(cf) = self.db.cf_handle(DEFAULT_COLUMN_FAMILY_NAME) else {
+			bail!(BenchError::Unsupported);
+		};
+		// Set the write options
+		let mut wo = WriteOptions::default();
+		wo.set_sync(self.sync);
+		// Write a single range tombstone, outside of a transaction
+		self.db.delete_range_cf_opt(cf, from, to, &wo)?;
+		Ok(())
+	}
+
 	async fn batch_create_bytes(
 		&self,
 		key_vals: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>)>>,
@@ -404,6 +557,9 @@ impl RocksDBClient {
 		// Process the data
 		for result in key_vals {
 			let (key, val) = result?;
+			for entry in self.indexes.entries(&key, &val)? {
+				txn.put(entry, b"")?;
+			}
 			txn.put(&key, val)?;
 		}
 		// Commit the batch
@@ -432,10 +588,8 @@ impl RocksDBClient {
 			let res = txn.get_pinned_opt(&key, &ro)?;
 			// Check the value exists
 			assert!(res.is_some());
-			// Deserialise the value
-			let val = BenchValue::decode(res.unwrap().as_ref())?;
-			// Use the value
-			black_box(val);
+			// Materialise the value
+			materialize::consume(res.unwrap(), |v| BenchValue::decode(v.as_ref()))?;
 		}
 		// All ok
 		Ok(())
@@ -456,6 +610,9 @@ impl RocksDBClient {
 		// Process the data
 		for result in key_vals {
 			let (key, val) = result?;
+			for entry in self.indexes.entries(&key, &val)? {
+				txn.put(entry, b"")?;
+			}
 			txn.put(&key, val)?;
 		}
 		// Commit the batch
@@ -481,10 +638,10 @@ impl RocksDBClient {
 		Ok(())
 	}
 
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Contional scans are not supported
+	async fn scan_bytes(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Conditional scans filter the records
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			return self.scan_filtered(scan, ctx).await;
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -523,14 +680,14 @@ impl RocksDBClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
 				// an iterator with `filter_map` or `map` is optimised
 				// out by the compiler when calling `count` at the end.
 				let mut count = 0;
 				for v in iter.skip(s).take(l) {
-					black_box(v.unwrap().1);
+					materialize::consume(v.unwrap().1, |v| BenchValue::decode(&v))?;
 					count += 1;
 				}
 				Ok(count)
@@ -543,4 +700,67 @@ impl RocksDBClient {
 			}
 		}
 	}
+
+	async fn scan_filtered(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Only conditions on a single integer field are supported
+		let filter = self.indexes.filter(scan, ctx)?;
+		let mut matches = Matches::new(scan)?;
+		// Set the transaction options
+		let mut to = OptimisticTransactionOptions::default();
+		to.set_snapshot(true);
+		// Set the write options
+		let mut wo = WriteOptions::default();
+		wo.set_sync(self.sync);
+		// Create a new transaction
+		let txn = self.db.transaction_opt(&wo, &to);
+		match self.indexes.find(&filter, ctx) {
+			// Look up the records of each range of index entries
+			Some(index) => {
+				// Configure read options for the record lookups
+				let mut get = ReadOptions::default();
+				get.set_snapshot(&txn.snapshot());
+				get.set_verify_checksums(false);
+				get.fill_cache(true);
+				'ranges: for range in filter.ranges() {
+					let (beg, end) = index.range(*range);
+					// Configure read options for the index entries
+					let mut ro = ReadOptions::default();
+					ro.set_snapshot(&txn.snapshot());
+					ro.set_iterate_upper_bound(end);
+					for kv in txn.iterator_opt(IteratorMode::From(&beg, Direction::Forward), ro) {
+						let (entry, _) = kv?;
+						let (value, key) = index.split(&entry);
+						// Skip the entries left behind by updates and deletes
+						let Some(val) = txn.get_pinned_opt(key, &get)? else {
+							continue;
+						};
+						let record = BenchValue::decode(val.as_ref())?;
+						if index.holds(value, &record) && !matches.push(key, record) {
+							break 'ranges;
+						}
+					}
+				}
+			}
+			// Decode and filter every record
+			None => {
+				// Configure read options
+				let mut ro = ReadOptions::default();
+				ro.set_snapshot(&txn.snapshot());
+				ro.set_readahead_size(2 * 1024 * 1024);
+				ro.set_iterate_lower_bound([0u8]);
+				ro.set_iterate_upper_bound([255u8]);
+				ro.set_verify_checksums(false);
+				ro.set_async_io(true);
+				ro.fill_cache(true);
+				for kv in txn.iterator_opt(IteratorMode::Start, ro) {
+					let (key, val) = kv?;
+					let record = BenchValue::decode(&val)?;
+					if filter.matches(&record) && !matches.push(&key, record) {
+						break;
+					}
+				}
+			}
+		}
+		Ok(matches.count())
+	}
 }
@@ -1,12 +1,15 @@
 #![cfg(feature = "scylladb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::dialect::AnsiSqlDialect;
-use crate::docker::DockerParams;
+use crate::docker::{DockerParams, DockerService, DockerTopology};
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
+use crate::memory::Config;
+use crate::security::Security;
 use crate::value::BenchValue;
 use crate::valueprovider::{ColumnType, Columns};
-use crate::{Benchmark, KeyType, Projection, Scan};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
 use anyhow::{Result, anyhow, bail};
 use futures::StreamExt;
 use scylla::_macro_internal::SerializeValue;
@@ -14,6 +17,8 @@ use scylla::client::{PoolSize, session::Session, session_builder::SessionBuilder
 use scylla::value::{CqlTimestamp, CqlValue};
 use std::hint::black_box;
 use std::num::NonZeroUsize;
+use std::time::Duration;
+use tokio::time::sleep;
 
 pub const DEFAULT: &str = "127.0.0.1:9042";
 
@@ -32,26 +37,94 @@ pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 	}
 }
 
-pub(crate) struct ScyllaDBClientProvider(KeyType, Columns, String);
+/// The addresses of the nodes of the `cluster` topology, the first being the datastore container
+const NODES: [&str; 3] = ["127.0.0.1", "127.0.0.2", "127.0.0.3"];
+
+/// Runs the datastore as a three node cluster, with every row replicated to each node
+pub(crate) fn topology(
+	params: DockerParams,
+	topology: &str,
+	options: &Benchmark,
+) -> Result<DockerTopology> {
+	if topology != "cluster" {
+		bail!("ScyllaDB does not support the `{topology}` topology, expected `cluster`");
+	}
+	// Split the host between the nodes, as each otherwise claims all of it
+	let smp = (options.container_cores() / NODES.len()).max(1);
+	let memory = (Config::new().cache_gb / NODES.len() as u64).max(1);
+	// Every node joins the cluster through the first node
+	let node = |address: &str| {
+		format!(
+			"{} --seeds {} \
+			--listen-address {address} \
+			--rpc-address {address} \
+			--api-address {address} \
+			--prometheus-address {address} \
+			--smp {smp} \
+			--memory {memory}G \
+			--overprovisioned 1",
+			params.post_args, NODES[0]
+		)
+	};
+	Ok(DockerTopology {
+		main: DockerParams {
+			image: params.image,
+			pre_args: params.pre_args.clone(),
+			post_args: node(NODES[0]),
+		},
+		services: ["node2", "node3"]
+			.into_iter()
+			.zip(NODES.into_iter().skip(1))
+			.map(|(name, address)| DockerService {
+				name,
+				params: DockerParams {
+					image: params.image,
+					pre_args: params.pre_args.clone(),
+					post_args: node(address),
+				},
+				before: false,
+			})
+			.collect(),
+		// Wait for every node to join, as quorum writes fail until they have
+		setup: vec![format!(
+			"bash -c 'for i in $(seq 60); do \
+			[ $(nodetool status | grep -c \"^UN\") -eq {} ] && exit 0; sleep 5; \
+			done; exit 1'",
+			NODES.len()
+		)],
+	})
+}
+
+pub(crate) struct ScyllaDBClientProvider(KeyType, Columns, String, usize, Security);
 
 impl BenchmarkEngine<ScylladbClient> for ScyllaDBClientProvider {
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(kt: KeyType, columns: Columns, options: &Benchmark) -> Result<Self> {
+		options.security.without_tls_options("ScyllaDB")?;
 		let url = options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned();
-		Ok(ScyllaDBClientProvider(kt, columns, url))
+		// Replicate every row to each node of a cluster
+		let replication = match options.topology {
+			Some(_) => NODES.len(),
+			None => 1,
+		};
+		Ok(ScyllaDBClientProvider(kt, columns, url, replication, options.security.clone()))
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<ScylladbClient> {
-		let session = SessionBuilder::new()
+		let mut builder = SessionBuilder::new()
 			.pool_size(PoolSize::PerHost(NonZeroUsize::new(1).unwrap()))
 			.known_node(&self.2)
-			.tcp_nodelay(true)
-			.build()
-			.await?;
+			.tcp_nodelay(true);
+		// The Docker container runs without authentication
+		if self.4.has_credentials() {
+			builder = builder.user(self.4.username("cassandra"), self.4.password("cassandra"));
+		}
+		let session = builder.build().await?;
 		Ok(ScylladbClient {
 			session,
 			kt: self.0,
 			columns: self.1.clone(),
+			replication: self.3,
 		})
 	}
 }
@@ -60,31 +133,44 @@ pub(crate) struct ScylladbClient {
 	session: Session,
 	kt: KeyType,
 	columns: Columns,
+	/// The number of nodes each row is replicated to
+	replication: usize,
 }
 
 impl BenchmarkClient for ScylladbClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		let stm = "SELECT release_version FROM system.local";
+		let rows = self.session.query_unpaged(stm, ()).await?.into_rows_result()?;
+		let (version,): (String,) = rows.single_row().map_err(|e| anyhow!("{e}"))?;
+		Ok(version)
+	}
+
 	async fn startup(&self) -> Result<()> {
 		self.session
 			.query_unpaged(
-				"
+				format!(
+					"
 					CREATE KEYSPACE bench
-					WITH replication = { 'class': 'SimpleStrategy', 'replication_factor' : 1 }
+					WITH replication = {{ 'class': 'SimpleStrategy', 'replication_factor' : {} }}
 					AND durable_writes = true
 				",
+					self.replication
+				),
 				(),
 			)
 			.await?;
 		let id_type = match self.kt {
 			KeyType::Integer => "INT",
-			KeyType::String26 | KeyType::String90 | KeyType::String250 | KeyType::String506 => {
-				"TEXT"
-			}
-			KeyType::Uuid => {
-				todo!()
-			}
+			// The UUID keys are bound as text, like the string keys
+			KeyType::String26
+			| KeyType::String90
+			| KeyType::String250
+			| KeyType::String506
+			| KeyType::Uuid
+			| KeyType::UuidV4 => "TEXT",
 		};
 		let fields: Vec<String> = self
 			.columns
@@ -129,12 +215,43 @@ impl BenchmarkClient for ScylladbClient {
 		self.read(key).await
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan(scan).await
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan(scan, ctx).await
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan(scan).await
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan(scan, ctx).await
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// Secondary indexes cover a single top-level column of every row, and cannot be unique
+		let [field] = spec.fields.as_slice() else {
+			bail!(BenchError::Unsupported);
+		};
+		if field.contains('.')
+			|| spec.index_type.is_some()
+			|| spec.unique.unwrap_or(false)
+			|| spec.filter.is_some()
+		{
+			bail!(BenchError::Unsupported);
+		}
+		// Create the index
+		let stm = format!("CREATE INDEX {name} ON bench.record ({field})");
+		self.session.query_unpaged(stm, ()).await?;
+		// Indexes are backed by a materialized view, which is built in the background
+		let view = format!("{name}_index");
+		let stm = "SELECT view_name FROM system.built_views WHERE keyspace_name = 'bench' AND view_name = ?";
+		while self.session.query_unpaged(stm, (&view,)).await?.into_rows_result()?.rows_num() == 0 {
+			sleep(Duration::from_millis(500)).await;
+		}
+		// All ok
+		Ok(())
+	}
+
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		let stm = format!("DROP INDEX IF EXISTS bench.{name}");
+		self.session.query_unpaged(stm, ()).await?;
+		Ok(())
 	}
 
 	#[allow(dependency_on_unit_never_type_fallback)]
@@ -249,20 +366,25 @@ impl ScylladbClient {
 		Ok(())
 	}
 
-	async fn scan(&self, scan: &Scan) -> Result<usize> {
-		// Ordered scans are not supported
-		if scan.order_by.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+	async fn scan(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Ordered scans, and index hints, are not supported
+		if scan.order_by.is_some() || ctx == ScanContext::WithHint {
+			bail!(BenchError::Unsupported);
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or_default();
 		let l = scan.limit.map(|l| format!("LIMIT {}", l + s)).unwrap_or_default();
 		let c = AnsiSqlDialect::filter_clause(scan)?;
+		// Conditions on columns without a usable index must allow filtering
+		let f = match c.is_empty() {
+			true => "",
+			false => "ALLOW FILTERING",
+		};
 		let p = scan.projection()?;
 		// Perform the relevant projection scan type
 		match p {
 			Projection::Id => {
-				let stm = format!("SELECT id FROM bench.record {c} {l}");
+				let stm = format!("SELECT id FROM bench.record {c} {l} {f}");
 				let mut res = self.session.query_iter(stm, ()).await?.rows_stream()?.skip(s);
 				let mut count = 0;
 				while let Some(v) = res.next().await {
@@ -272,19 +394,23 @@ impl ScylladbClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
-				let stm = format!("SELECT id FROM bench.record {c} {l}");
+			// Objects are stored as JSON text, so nested fields cannot be pruned
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
+				let stm = format!("SELECT JSON * FROM bench.record {c} {l} {f}");
 				let mut res = self.session.query_iter(stm, ()).await?.rows_stream()?.skip(s);
 				let mut count = 0;
 				while let Some(v) = res.next().await {
-					let v: (String,) = v?;
-					black_box(v);
+					let (v,): (String,) = v?;
+					materialize::consume(v, |v| {
+						let json: serde_json::Value = serde_json::from_str(&v)?;
+						Ok(BenchValue::from(&json))
+					})?;
 					count += 1;
 				}
 				Ok(count)
 			}
 			Projection::Count => {
-				let stm = format!("SELECT count(*) FROM bench.record {c} {l}");
+				let stm = format!("SELECT count(*) FROM bench.record {c} {l} {f}");
 				let mut res = self.session.query_iter(stm, ()).await?.rows_stream()?.skip(s);
 				let count: (String,) = res.next().await.unwrap()?;
 				let count: usize = count.0.parse()?;
@@ -0,0 +1,143 @@
+//! Profiling of the datastore container with `perf`, enabled with `--profile-server`.
+//!
+//! `perf record` is attached to the main process of the container for the length of each phase,
+//! and each recording is rendered with the `flamegraph` tool (`cargo install flamegraph`, or the
+//! `FLAMEGRAPH_BIN` environment variable) into a flamegraph such as `server-flamegraph-02-read.svg`.
+//! The on-CPU profile samples the stacks running on the CPU, while the off-CPU profile records the
+//! stacks the server threads were switched out at, showing where they block on locks or I/O.
+
+use crate::docker::Container;
+use crate::profiling::{PhaseFlamegraph, slug};
+use anyhow::{Context, Result, bail, ensure};
+use clap::ValueEnum;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// What `perf` records of the datastore container, with `--profile-server`.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ServerProfile {
+	/// Sample the stacks running on the CPU
+	OnCpu,
+	/// Record the stacks the threads block at, from the scheduler switch events
+	OffCpu,
+}
+
+/// Attaches `perf` to the datastore container for each phase.
+pub(crate) struct ServerProfiler {
+	/// What is recorded
+	profile: ServerProfile,
+	/// The host pid of the main process of the container
+	pid: u32,
+	/// The number of recordings started, which names their files
+	recordings: AtomicU32,
+	/// The flamegraphs rendered for the phases so far, in the order they ran
+	flamegraphs: Mutex<Vec<PhaseFlamegraph>>,
+}
+
+/// A `perf record` running for the length of a phase.
+pub(crate) struct ServerRecording {
+	/// The `perf record` process
+	child: Child,
+	/// The file the samples are written to
+	data: String,
+}
+
+impl ServerProfiler {
+	/// Finds the process of the container, and checks `perf` can be run.
+	pub(crate) fn attach(profile: ServerProfile, container: Option<&Container>) -> Result<Self> {
+		let Some(container) = container else {
+			bail!("The `--profile-server` option needs a container started by crud-bench");
+		};
+		let pid = container.pid().context("Failed to find the process of the container")?;
+		let perf = Command::new("perf").arg("--version").output();
+		ensure!(
+			perf.is_ok_and(|o| o.status.success()),
+			"The `--profile-server` option needs `perf` to be installed"
+		);
+		Ok(Self {
+			profile,
+			pid,
+			recordings: AtomicU32::new(0),
+			flamegraphs: Mutex::new(Vec::new()),
+		})
+	}
+
+	/// Starts recording the container, warning when `perf` cannot be started.
+	pub(crate) fn start(&self) -> Option<ServerRecording> {
+		// Phases which run alongside another, such as the updates during an index build, are
+		// recorded at the same time
+		let number = self.recordings.fetch_add(1, Ordering::Relaxed) + 1;
+		let data = format!("server-perf-{number:02}.data");
+		let mut command = Command::new("perf");
+		command.arg("record").arg("-g").args(["-p", &self.pid.to_string(), "-o", &data]);
+		match self.profile {
+			ServerProfile::OnCpu => command.args(["-F", "99"]),
+			ServerProfile::OffCpu => command.args(["-e", "sched:sched_switch"]),
+		};
+		match command.stdout(Stdio::null()).stderr(Stdio::null()).spawn() {
+			Ok(child) => Some(ServerRecording {
+				child,
+				data,
+			}),
+			Err(e) => {
+				eprintln!("⚠️ Failed to start perf: {e}");
+				None
+			}
+		}
+	}
+
+	/// Stops the recording of a phase, and renders its flamegraph.
+	pub(crate) fn finish(&self, recording: ServerRecording, phase: &str) {
+		if let Err(e) = self.render(recording, phase) {
+			eprintln!("⚠️ Failed to write the server flamegraph of {phase}: {e:#}");
+		}
+	}
+
+	/// The flamegraphs rendered for the phases of the benchmark.
+	pub(crate) fn flamegraphs(&self) -> Vec<PhaseFlamegraph> {
+		self.flamegraphs.lock().map(|f| f.clone()).unwrap_or_default()
+	}
+
+	fn render(&self, mut recording: ServerRecording, phase: &str) -> Result<()> {
+		recording.stop()?;
+		let mut flamegraphs =
+			self.flamegraphs.lock().map_err(|_| anyhow::anyhow!("poisoned lock"))?;
+		let path = format!("server-flamegraph-{:02}-{}.svg", flamegraphs.len() + 1, slug(phase));
+		let bin = std::env::var("FLAMEGRAPH_BIN").unwrap_or_else(|_| "flamegraph".to_string());
+		let output = Command::new(&bin)
+			.args(["--perfdata", &recording.data, "-o", &path])
+			.output()
+			.with_context(|| {
+				format!("Failed to run `{bin}`, install it with `cargo install flamegraph`")
+			})?;
+		ensure!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr).trim());
+		flamegraphs.push(PhaseFlamegraph {
+			phase: phase.to_string(),
+			path,
+		});
+		Ok(())
+	}
+}
+
+impl ServerRecording {
+	/// Stops perf as Ctrl-C would, so it flushes the samples to the file.
+	fn stop(&mut self) -> Result<()> {
+		if self.child.try_wait()?.is_none() {
+			// SAFETY: the pid is of the perf process, which has not been waited on yet
+			unsafe {
+				libc::kill(self.child.id() as libc::pid_t, libc::SIGINT);
+			}
+			self.child.wait()?;
+		}
+		Ok(())
+	}
+}
+
+impl Drop for ServerRecording {
+	fn drop(&mut self) {
+		// A phase which failed or was skipped leaves no recording behind
+		let _ = self.stop();
+		let _ = std::fs::remove_file(&self.data);
+	}
+}
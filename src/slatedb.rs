@@ -1,8 +1,10 @@
 #![cfg(feature = "slatedb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
 use crate::memory::Config;
+use crate::util::crate_version;
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
 use crate::{Benchmark, KeyType, Projection, Scan};
@@ -33,14 +35,12 @@ pub(crate) struct SlateDBClientProvider {
 }
 
 impl BenchmarkEngine<SlateDBClient> for SlateDBClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_kt: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Cleanup the data directory
-		std::fs::remove_dir_all(DATABASE_DIR).ok();
+		// Cleanup the data directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_dir_all(DATABASE_DIR).ok();
+		}
 		// Create the database directories
 		std::fs::create_dir_all(DATA_DIR)?;
 		std::fs::create_dir_all(WAL_DIR)?;
@@ -112,6 +112,10 @@ impl BenchmarkClient for SlateDBClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("slatedb")
+	}
+
 	async fn shutdown(&self) -> Result<()> {
 		// Close the database
 		self.db.close().await?;
@@ -301,10 +305,8 @@ impl SlateDBClient {
 			let res = txn.get(&key).await?;
 			// Check the value exists
 			assert!(res.is_some());
-			// Deserialise the value
-			let val = BenchValue::decode(res.unwrap().as_ref())?;
-			// Use the value
-			black_box(val);
+			// Materialise the value
+			materialize::consume(res.unwrap(), |v| BenchValue::decode(v.as_ref()))?;
 		}
 		// All ok
 		Ok(())
@@ -341,7 +343,7 @@ impl SlateDBClient {
 	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
 		// Conditional scans are not supported
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -376,14 +378,14 @@ impl SlateDBClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// We use a while loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
 				// otherwise the loop is optimised out by the compiler
 				// when calling `count` at the end.
 				let mut count = 0;
 				while let Ok(Some(item)) = iter.next().await {
-					black_box(item.value);
+					materialize::consume(item.value, |v| BenchValue::decode(&v))?;
 					count += 1;
 					if count >= l {
 						break;
@@ -0,0 +1,59 @@
+//! Soak runs: a mixed read and update workload looped for hours rather than a burst of samples.
+//!
+//! With `--soak`, the reads and updates alternate over the created records in windows of
+//! `--soak-interval` minutes until the soak duration has elapsed. Each window is one phase, so
+//! its throughput, latency, memory, and disk I/O are recorded separately, and the windows
+//! completed so far are written to `<result>-soak.json` and `<result>-soak.html` after every
+//! window, so memory growth and throughput decay can be followed while the soak runs.
+
+use crate::chart;
+use crate::result::SoakWindow;
+use anyhow::Result;
+use std::time::Duration;
+
+/// Parses a soak duration given in seconds, minutes, hours, or days, such as `90s`, `30m`, or `6h`.
+pub(crate) fn parse_duration(value: &str) -> Result<Duration, String> {
+	let value = value.trim();
+	let (number, unit) =
+		value.split_at(value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len()));
+	let number: u64 = number.parse().map_err(|_| format!("Invalid soak duration `{value}`"))?;
+	let seconds = match unit {
+		"s" => 1,
+		"m" => 60,
+		"h" => 3600,
+		"d" => 86400,
+		_ => return Err(format!("The soak duration `{value}` must end in `s`, `m`, `h`, or `d`")),
+	};
+	match number.saturating_mul(seconds) {
+		0 => Err("The soak duration must be greater than zero".to_string()),
+		secs => Ok(Duration::from_secs(secs)),
+	}
+}
+
+/// Writes the soak windows completed so far as `<stem>.json`, and charts them in `<stem>.html`.
+pub(crate) fn flush(stem: &str, windows: &[SoakWindow]) -> Result<()> {
+	std::fs::write(format!("{stem}.json"), serde_json::to_string_pretty(windows)?)?;
+	std::fs::write(format!("{stem}.html"), chart::generate_soak_html(windows))?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_each_unit() {
+		assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+		assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+		assert_eq!(parse_duration("6h"), Ok(Duration::from_secs(21600)));
+		assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(86400)));
+	}
+
+	#[test]
+	fn rejects_missing_units_and_zero() {
+		assert!(parse_duration("90").is_err());
+		assert!(parse_duration("h").is_err());
+		assert!(parse_duration("0m").is_err());
+		assert!(parse_duration("6w").is_err());
+	}
+}
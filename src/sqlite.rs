@@ -1,9 +1,12 @@
 #![cfg(feature = "sqlite")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::dialect::{AnsiSqlDialect, Dialect, SqliteDialect};
-use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::engine::{BenchmarkClient, BenchmarkEngine, PageStart, ScanContext};
+use crate::error::BenchError;
+use crate::keyprovider::GeneratedKey;
+use crate::materialize::{self, Materialize};
 use crate::memory::Config;
+use crate::util::dir_size;
 use crate::util::sql::bench_to_sqlite_param;
 use crate::value::BenchValue;
 use crate::valueprovider::{ColumnType, Columns};
@@ -15,7 +18,6 @@ use std::borrow::Cow;
 use std::cmp::max;
 use std::hint::black_box;
 use std::sync::Arc;
-use std::time::Duration;
 use tokio_rusqlite::types::ToSqlOutput;
 use tokio_rusqlite::types::Value;
 use tokio_rusqlite::{Connection, rusqlite};
@@ -23,8 +25,37 @@ use uuid::Uuid;
 
 const DATABASE_DIR: &str = "sqlite";
 
+/// The table holding the records with keys generated by the datastore
+const GENERATED_TABLE: &str = "record_generated";
+
 const MIN_CACHE_SIZE: u64 = 512 * 1024 * 1024;
 
+/// The environment variable choosing how clients connect (default: `shared`)
+const CONNECTIONS_VAR: &str = "CRUD_BENCH_SQLITE_CONNECTIONS";
+
+/// How long a connection waits for the lock held by another before failing
+const BUSY_TIMEOUT_MS: u64 = 10_000;
+
+/// How the clients connect to the database
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Connections {
+	/// One connection, holding an exclusive lock, shared by every client, so that
+	/// every operation is serialised
+	Shared,
+	/// A connection per client in WAL mode, so that readers run concurrently with
+	/// each other and with the single writer, which the others wait for
+	Client,
+}
+
+/// Reads how the clients connect from [`CONNECTIONS_VAR`]
+fn connections() -> Result<Connections> {
+	match std::env::var(CONNECTIONS_VAR).as_deref() {
+		Err(_) | Ok("shared") => Ok(Connections::Shared),
+		Ok("client") => Ok(Connections::Client),
+		Ok(v) => bail!("{CONNECTIONS_VAR} must be `shared` or `client`, not `{v}`"),
+	}
+}
+
 // We can't just return `tokio_rusqlite::Row` because it's not Send/Sync
 type Row = Vec<(String, Value)>;
 
@@ -69,17 +100,17 @@ pub(crate) struct SqliteClientProvider {
 	columns: Columns,
 	sync: bool,
 	optimised: bool,
+	connections: Connections,
+	clients: u32,
 }
 
 impl BenchmarkEngine<SqliteClient> for SqliteClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(kt: KeyType, columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Remove the database directory
-		std::fs::remove_dir_all(DATABASE_DIR).ok();
+		// Remove the database directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_dir_all(DATABASE_DIR).ok();
+		}
 		// Recreate the database directory
 		std::fs::create_dir(DATABASE_DIR)?;
 		// Switch to the new directory
@@ -93,17 +124,27 @@ impl BenchmarkEngine<SqliteClient> for SqliteClientProvider {
 			columns,
 			sync: options.sync,
 			optimised: options.optimised,
+			connections: connections()?,
+			clients: options.clients,
 		})
 	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<SqliteClient> {
-		Ok(SqliteClient {
+		let mut client = SqliteClient {
 			conn: self.conn.clone(),
 			kt: self.kt,
 			columns: self.columns.clone(),
 			sync: self.sync,
 			optimised: self.optimised,
-		})
+			connections: self.connections,
+			clients: self.clients,
+		};
+		// Open a connection of its own, with the same settings as the first
+		if self.connections == Connections::Client {
+			client.conn = Arc::new(Connection::open(format!("{DATABASE_DIR}/db")).await?);
+			client.execute_batch(Cow::Owned(client.connection_pragmas())).await?;
+		}
+		Ok(client)
 	}
 }
 
@@ -113,6 +154,8 @@ pub(crate) struct SqliteClient {
 	columns: Columns,
 	sync: bool,
 	optimised: bool,
+	connections: Connections,
+	clients: u32,
 }
 
 impl BenchmarkClient for SqliteClient {
@@ -126,37 +169,19 @@ impl BenchmarkClient for SqliteClient {
 		Ok(())
 	}
 
+	fn disk_size(&self) -> Option<u64> {
+		dir_size(DATABASE_DIR)
+	}
+
 	async fn startup(&self) -> Result<()> {
-		// Calculate the size of the page cache (in pages of 16 KiB).
-		let cache_pages = calculate_sqlite_memory() / 16384;
-		// synchronous mode:
-		//   - sync=true  → FULL  (fsync on every commit; full durability)
-		//   - sync=false → OFF   (no fsync, fastest; matches the default sync=false
-		//     intent of other adapters that disable fsync entirely)
-		let synchronous = if self.sync {
-			"FULL"
-		} else {
-			"OFF"
-		};
-		// mmap_size (optimised only): 1 GiB memory-mapped reads. Off by default
-		// because some hosts (small VMs, restricted containers) reject large mmaps.
-		let mmap_size: u64 = if self.optimised {
-			1024 * 1024 * 1024
-		} else {
-			0
-		};
-		// temp_store=MEMORY keeps temp B-trees in RAM (used by ORDER BY / GROUP BY).
+		// The journal mode and page size apply to the database, so are set once
 		let stmt = format!(
 			"
 			PRAGMA journal_mode = WAL;
-			PRAGMA synchronous = {synchronous};
 			PRAGMA page_size = 16384;
-			PRAGMA cache_size = {cache_pages};
-			PRAGMA temp_store = MEMORY;
-			PRAGMA mmap_size = {mmap_size};
-			PRAGMA locking_mode = EXCLUSIVE;
-			PRAGMA wal_autocheckpoint = 10000;
-		"
+			{}
+		",
+			self.connection_pragmas()
 		);
 		self.execute_batch(Cow::Owned(stmt)).await?;
 		let id_type = match self.kt {
@@ -165,31 +190,9 @@ impl BenchmarkClient for SqliteClient {
 			KeyType::String90 => "VARCHAR(90)",
 			KeyType::String250 => "VARCHAR(250)",
 			KeyType::String506 => "VARCHAR(506)",
-			KeyType::Uuid => {
-				todo!()
-			}
+			KeyType::Uuid | KeyType::UuidV4 => "VARCHAR(36)",
 		};
-		let fields = self
-			.columns
-			.0
-			.iter()
-			.map(|(n, t)| {
-				let n = AnsiSqlDialect::escape_field(n.clone());
-				match t {
-					ColumnType::String => format!("{n} TEXT NOT NULL"),
-					ColumnType::Integer => format!("{n} INTEGER NOT NULL"),
-					ColumnType::Object => format!("{n} JSON NOT NULL"),
-					ColumnType::Array => format!("{n} JSON NOT NULL"),
-					ColumnType::Float => format!("{n} REAL NOT NULL"),
-					ColumnType::DateTime => format!("{n} TIMESTAMP NOT NULL"),
-					ColumnType::Uuid => format!("{n} UUID NOT NULL"),
-					ColumnType::Decimal => format!("{n} TEXT NOT NULL"),
-					ColumnType::Bool => format!("{n} BOOL NOT NULL"),
-					ColumnType::Bytes => format!("{n} BLOB NOT NULL"),
-				}
-			})
-			.collect::<Vec<String>>()
-			.join(",");
+		let fields = self.column_definitions();
 		let stmt = format!(
 			"
 		    DROP TABLE IF EXISTS record;
@@ -200,6 +203,19 @@ impl BenchmarkClient for SqliteClient {
 		Ok(())
 	}
 
+	async fn setup_generated_keys(&self) -> Result<()> {
+		// An INTEGER PRIMARY KEY aliases the rowid, so is assigned on insert
+		let fields = self.column_definitions();
+		let stmt = format!(
+			"
+		    DROP TABLE IF EXISTS {GENERATED_TABLE};
+		    CREATE TABLE {GENERATED_TABLE} ( id INTEGER PRIMARY KEY, {fields});
+		"
+		);
+		self.execute_batch(Cow::Owned(stmt)).await?;
+		Ok(())
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create(key.into(), val).await
 	}
@@ -216,6 +232,18 @@ impl BenchmarkClient for SqliteClient {
 		self.read(key.into()).await
 	}
 
+	async fn read_secondary(&self, field: &str, value: String) -> Result<usize> {
+		let field = AnsiSqlDialect::escape_field(field.to_string());
+		let stm = format!("SELECT * FROM record WHERE {field} = $1");
+		let res = self.query(Cow::Owned(stm), Some(value.into())).await?;
+		let mut count = 0;
+		for row in res {
+			black_box(self.consume(row));
+			count += 1;
+		}
+		Ok(count)
+	}
+
 	async fn update_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.update(key.into(), val).await
 	}
@@ -232,6 +260,19 @@ impl BenchmarkClient for SqliteClient {
 		self.delete(key.into()).await
 	}
 
+	async fn delete_range_u32(&self, from: u32, to: u32) -> Result<()> {
+		self.delete_range(from.into(), to.into()).await
+	}
+
+	async fn delete_range_string(&self, from: String, to: String) -> Result<()> {
+		self.delete_range(from.into(), to.into()).await
+	}
+
+	async fn truncate(&self) -> Result<()> {
+		// SQLite has no TRUNCATE, but drops the pages of the table for a DELETE without a WHERE
+		self.execute_batch(Cow::Borrowed("DELETE FROM record")).await
+	}
+
 	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
 		// Get the unique flag
 		let unique = if spec.unique.unwrap_or(false) {
@@ -242,13 +283,16 @@ impl BenchmarkClient for SqliteClient {
 		.to_string();
 		// Get the fields
 		let fields = SqliteDialect::btree_index_key_list(&self.columns, spec);
+		// Get the partial index predicate
+		let filter = SqliteDialect::index_filter_clause(spec)?;
 		// Check if an index type is specified
 		let stmt = match &spec.index_type {
-			Some(kind) if kind == "fulltext" => {
-				bail!(NOT_SUPPORTED_ERROR)
+			// SQLite only builds B-tree indexes
+			Some(_) => {
+				bail!(BenchError::Unsupported)
 			}
-			_ => {
-				format!("CREATE {unique} INDEX {name} ON record ({fields})")
+			None => {
+				format!("CREATE {unique} INDEX {name} ON record ({fields}) {filter}")
 			}
 		};
 		// Create the index
@@ -263,6 +307,67 @@ impl BenchmarkClient for SqliteClient {
 		Ok(())
 	}
 
+	async fn version(&self) -> Result<String> {
+		let version = self
+			.conn
+			.call(|conn| -> rusqlite::Result<String> {
+				conn.query_row("SELECT sqlite_version()", [], |row| row.get(0))
+			})
+			.await?;
+		Ok(format!("sqlite {version}"))
+	}
+
+	async fn compact(&self) -> Result<()> {
+		// Rebuild the database file without its free pages, then refresh the statistics
+		self.execute_batch(Cow::Borrowed("VACUUM; ANALYZE;")).await?;
+		Ok(())
+	}
+
+	async fn snapshot_table(&self) -> Result<()> {
+		self.conn
+			.call(|conn| -> rusqlite::Result<()> {
+				// Reuse the table definition, so the snapshot keeps its primary key
+				let create: String = conn.query_row(
+					"SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'record'",
+					[],
+					|row| row.get(0),
+				)?;
+				let create = create.replacen("CREATE TABLE record", "CREATE TABLE record_snapshot", 1);
+				conn.execute_batch(&format!(
+					"DROP TABLE IF EXISTS record_snapshot; {create}; INSERT INTO record_snapshot SELECT * FROM record;"
+				))
+			})
+			.await?;
+		Ok(())
+	}
+
+	async fn restore_table(&self) -> Result<()> {
+		let stmt = "DROP TABLE record; ALTER TABLE record_snapshot RENAME TO record;";
+		self.execute_batch(Cow::Borrowed(stmt)).await?;
+		Ok(())
+	}
+
+	fn classify_error(&self, err: &anyhow::Error) -> Option<BenchError> {
+		use rusqlite::ErrorCode;
+		err.chain().find_map(|e| {
+			let closed = e.downcast_ref::<tokio_rusqlite::Error>();
+			if let Some(tokio_rusqlite::Error::ConnectionClosed) = closed {
+				return Some(BenchError::Transient);
+			}
+			match e.downcast_ref::<rusqlite::Error>()? {
+				rusqlite::Error::QueryReturnedNoRows => Some(BenchError::NotFound),
+				rusqlite::Error::SqliteFailure(e, _) => match e.code {
+					ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => {
+						Some(BenchError::Conflict)
+					}
+					ErrorCode::ConstraintViolation => Some(BenchError::ConstraintViolation),
+					_ => None,
+				},
+				_ => None,
+			}
+		})
+	}
+
 	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
 		self.scan(scan, ctx).await
 	}
@@ -271,6 +376,50 @@ impl BenchmarkClient for SqliteClient {
 		self.scan(scan, ctx).await
 	}
 
+	async fn scan_ids(&self, scan: &Scan) -> Result<Vec<String>> {
+		// SQLite doesn't yet support full-text indexes
+		if let Some(index) = &scan.with_index
+			&& let Some(kind) = &index.index_type
+			&& kind == "fulltext"
+		{
+			bail!(BenchError::Unsupported);
+		}
+		// Extract parameters
+		let s = scan.start.map(|s| format!("OFFSET {s}")).unwrap_or_default();
+		let l = scan.limit.map(|s| format!("LIMIT {s}")).unwrap_or_default();
+		let c = SqliteDialect::filter_clause(scan)?;
+		let o = AnsiSqlDialect::order_by_clause(scan)?;
+		// Fetch the ids as text, whatever the key type
+		let stm = format!("SELECT CAST(id AS TEXT) AS id FROM record {c} {o} {l} {s}");
+		let res = self.query(Cow::Owned(stm), None).await?;
+		res.into_iter()
+			.map(|row| match row.into_iter().next() {
+				Some((_, Value::Text(id))) => Ok(id),
+				v => bail!("Unexpected response type `{v:?}`"),
+			})
+			.collect()
+	}
+
+	async fn scan_page_u32(&self, start: PageStart<u32>, limit: usize) -> Result<Option<u32>> {
+		match self.scan_page(start.map(Into::into), limit).await? {
+			Some(Value::Integer(id)) => Ok(Some(id as u32)),
+			None => Ok(None),
+			v => bail!("Unexpected id type `{v:?}`"),
+		}
+	}
+
+	async fn scan_page_string(
+		&self,
+		start: PageStart<String>,
+		limit: usize,
+	) -> Result<Option<String>> {
+		match self.scan_page(start.map(Into::into), limit).await? {
+			Some(Value::Text(id)) => Ok(Some(id)),
+			None => Ok(None),
+			v => bail!("Unexpected id type `{v:?}`"),
+		}
+	}
+
 	async fn batch_create_u32(
 		&self,
 		key_vals: impl Iterator<Item = (u32, BenchValue)> + Send,
@@ -286,11 +435,11 @@ impl BenchmarkClient for SqliteClient {
 	}
 
 	async fn batch_read_u32(&self, keys: impl Iterator<Item = u32> + Send) -> Result<()> {
-		self.batch_read(keys.collect()).await
+		self.batch_read("record", keys.collect()).await
 	}
 
 	async fn batch_read_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
-		self.batch_read(keys.collect()).await
+		self.batch_read("record", keys.collect()).await
 	}
 
 	async fn batch_update_u32(
@@ -314,9 +463,117 @@ impl BenchmarkClient for SqliteClient {
 	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
 		self.batch_delete(keys.collect()).await
 	}
+
+	async fn batch_create_generated(&self, vals: Vec<BenchValue>) -> Result<Vec<GeneratedKey>> {
+		// Clone the connection
+		let conn = self.conn.clone();
+		// Fetch the columns to insert
+		let column_defs = self.columns.clone();
+		// Execute the batch insert on the connection
+		conn.call(move |conn| -> rusqlite::Result<Vec<GeneratedKey>> {
+			// Store the records to insert
+			let mut inserts = Vec::with_capacity(vals.len());
+			// Store all parameter values as owned types
+			let mut all_params: Vec<Box<dyn tokio_rusqlite::types::ToSql>> = Vec::new();
+			// Iterate over the values, leaving the id to the datastore
+			for val in vals {
+				let mut row = Vec::with_capacity(column_defs.0.len());
+				if let BenchValue::Object(obj) = val {
+					for (column, column_type) in &column_defs.0 {
+						if let Some(value) = obj.iter().find(|(k, _)| k == column).map(|(_, v)| v) {
+							let param = bench_to_sqlite_param(column_type, value)
+								.expect("Failed to convert BenchValue to SQL parameter");
+							all_params.push(param);
+							row.push(format!("${}", all_params.len()));
+						} else {
+							panic!("Missing value for column {column}");
+						}
+					}
+				}
+				inserts.push(format!("({})", row.join(", ")));
+			}
+			// Fetch the columns to insert
+			let columns = column_defs
+				.0
+				.iter()
+				.map(|(name, _)| AnsiSqlDialect::escape_field(name.clone()))
+				.collect::<Vec<String>>()
+				.join(", ");
+			// Build the INSERT statement, returning the assigned ids
+			let stmt = format!(
+				"INSERT INTO {GENERATED_TABLE} ({columns}) VALUES {} RETURNING id",
+				inserts.join(", ")
+			);
+			let params: Vec<&dyn tokio_rusqlite::types::ToSql> =
+				all_params.iter().map(|p| p.as_ref()).collect();
+			let mut prepared_stmt = conn.prepare(&stmt)?;
+			let keys = prepared_stmt
+				.query_map(params.as_slice(), |row| row.get(0).map(GeneratedKey::Integer))?
+				.collect::<rusqlite::Result<Vec<_>>>()?;
+			// Check the number of rows inserted
+			assert_eq!(keys.len(), inserts.len());
+			Ok(keys)
+		})
+		.await
+		.map_err(Into::into)
+	}
+
+	async fn batch_read_generated(&self, keys: Vec<GeneratedKey>) -> Result<()> {
+		let ids = keys
+			.iter()
+			.map(|k| match k {
+				GeneratedKey::Integer(id) => Ok(*id),
+				GeneratedKey::String(_) => Err(anyhow!("Unexpected generated key {k:?}")),
+			})
+			.collect::<Result<Vec<i64>>>()?;
+		self.batch_read(GENERATED_TABLE, ids).await
+	}
 }
 
 impl SqliteClient {
+	/// The settings of each connection, applied as it is opened
+	fn connection_pragmas(&self) -> String {
+		// Calculate the size of the page cache (in pages of 16 KiB),
+		// split between the connections of the clients when separate
+		let cache_pages = match self.connections {
+			Connections::Shared => calculate_sqlite_memory() / 16384,
+			Connections::Client => calculate_sqlite_memory() / 16384 / self.clients.max(1) as u64,
+		};
+		// synchronous mode:
+		//   - sync=true  → FULL  (fsync on every commit; full durability)
+		//   - sync=false → OFF   (no fsync, fastest; matches the default sync=false
+		//     intent of other adapters that disable fsync entirely)
+		let synchronous = if self.sync {
+			"FULL"
+		} else {
+			"OFF"
+		};
+		// mmap_size (optimised only): 1 GiB memory-mapped reads. Off by default
+		// because some hosts (small VMs, restricted containers) reject large mmaps.
+		let mmap_size: u64 = if self.optimised {
+			1024 * 1024 * 1024
+		} else {
+			0
+		};
+		// A shared connection holds the lock for good, while separate
+		// connections wait for each other to release it
+		let locking = match self.connections {
+			Connections::Shared => "PRAGMA locking_mode = EXCLUSIVE;".to_string(),
+			Connections::Client => format!("PRAGMA busy_timeout = {BUSY_TIMEOUT_MS};"),
+		};
+		// temp_store=MEMORY keeps temp B-trees in RAM (used by ORDER BY / GROUP BY).
+		format!(
+			"
+			PRAGMA synchronous = {synchronous};
+			PRAGMA cache_size = {cache_pages};
+			PRAGMA temp_store = MEMORY;
+			PRAGMA mmap_size = {mmap_size};
+			{locking}
+			PRAGMA wal_autocheckpoint = 10000;
+		"
+		)
+	}
+
 	async fn execute_batch(&self, query: Cow<'static, str>) -> Result<()> {
 		self.conn.call(move |conn| conn.execute_batch(query.as_ref())).await?;
 		Ok(())
@@ -377,6 +634,30 @@ impl SqliteClient {
 			.map_err(Into::into)
 	}
 
+	/// The definitions of the value columns, shared by the record tables.
+	fn column_definitions(&self) -> String {
+		self.columns
+			.0
+			.iter()
+			.map(|(n, t)| {
+				let n = AnsiSqlDialect::escape_field(n.clone());
+				match t {
+					ColumnType::String => format!("{n} TEXT NOT NULL"),
+					ColumnType::Integer => format!("{n} INTEGER NOT NULL"),
+					ColumnType::Object => format!("{n} JSON NOT NULL"),
+					ColumnType::Array => format!("{n} JSON NOT NULL"),
+					ColumnType::Float => format!("{n} REAL NOT NULL"),
+					ColumnType::DateTime => format!("{n} TIMESTAMP NOT NULL"),
+					ColumnType::Uuid => format!("{n} UUID NOT NULL"),
+					ColumnType::Decimal => format!("{n} TEXT NOT NULL"),
+					ColumnType::Bool => format!("{n} BOOL NOT NULL"),
+					ColumnType::Bytes => format!("{n} BLOB NOT NULL"),
+				}
+			})
+			.collect::<Vec<String>>()
+			.join(",")
+	}
+
 	fn consume(&self, row: Row) -> BenchValue {
 		let mut val: Vec<(String, BenchValue)> = Vec::with_capacity(row.len());
 		for (key, value) in row {
@@ -415,6 +696,31 @@ impl SqliteClient {
 		Ok(())
 	}
 
+	async fn scan_page(
+		&self,
+		start: PageStart<ToSqlOutput<'static>>,
+		limit: usize,
+	) -> Result<Option<Value>> {
+		let (stm, key) = match start {
+			PageStart::Offset(s) => {
+				(format!("SELECT * FROM record ORDER BY id LIMIT {limit} OFFSET {s}"), None)
+			}
+			PageStart::After(None) => {
+				(format!("SELECT * FROM record ORDER BY id LIMIT {limit}"), None)
+			}
+			PageStart::After(Some(key)) => {
+				(format!("SELECT * FROM record WHERE id > $1 ORDER BY id LIMIT {limit}"), Some(key))
+			}
+		};
+		let res = self.query(Cow::Owned(stm), key).await?;
+		let last = res.last().and_then(|row| row.iter().find(|(n, _)| n == "id"));
+		let last = last.map(|(_, id)| id.clone());
+		for row in res {
+			materialize::consume(row, |row| Ok(self.consume(row)))?;
+		}
+		Ok(last)
+	}
+
 	async fn read(&self, key: ToSqlOutput<'static>) -> Result<BenchValue> {
 		let stm = "SELECT * FROM record WHERE id=$1";
 		let mut res = self.query(Cow::Borrowed(stm), Some(key)).await?;
@@ -459,24 +765,35 @@ impl SqliteClient {
 		Ok(())
 	}
 
-	async fn scan(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
+	async fn delete_range(
+		&self,
+		from: ToSqlOutput<'static>,
+		to: ToSqlOutput<'static>,
+	) -> Result<()> {
+		let stm = "DELETE FROM record WHERE id >= $1 AND id < $2";
+		self.execute_params(Cow::Borrowed(stm), vec![Box::new(from), Box::new(to)]).await?;
+		Ok(())
+	}
+
+	async fn scan(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
 		// SQLite doesn't yet support full-text indexes
 		if let Some(index) = &scan.with_index
 			&& let Some(kind) = &index.index_type
 			&& kind == "fulltext"
 		{
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		// Extract parameters
 		let s = scan.start.map(|s| format!("OFFSET {s}")).unwrap_or_default();
 		let l = scan.limit.map(|s| format!("LIMIT {s}")).unwrap_or_default();
 		let c = SqliteDialect::filter_clause(scan)?;
 		let o = AnsiSqlDialect::order_by_clause(scan)?;
+		let h = SqliteDialect::hint_clause(scan, ctx)?;
 		let p = scan.projection()?;
 		// Perform the relevant projection scan type
 		match p {
 			Projection::Id => {
-				let stm = format!("SELECT id FROM record {c} {o} {l} {s}");
+				let stm = format!("SELECT id FROM record {h} {c} {o} {l} {s}");
 				let res = self.query(Cow::Owned(stm), None).await?;
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
@@ -490,7 +807,41 @@ impl SqliteClient {
 				Ok(count)
 			}
 			Projection::Full => {
-				let stm = format!("SELECT * FROM record {c} {o} {l} {s}");
+				let stm = format!("SELECT * FROM record {h} {c} {o} {l} {s}");
+				let res = self.query(Cow::Owned(stm), None).await?;
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					materialize::consume(v, |v| Ok(self.consume(v)))?;
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Field(path) => {
+				let f = SqliteDialect::field_path(&path);
+				let stm = format!("SELECT id, {f} AS field FROM record {h} {c} {o} {l} {s}");
+				let res = self.query(Cow::Owned(stm), None).await?;
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for v in res {
+					black_box(self.consume(v));
+					count += 1;
+				}
+				Ok(count)
+			}
+			Projection::Fields(paths) => {
+				let f = paths
+					.iter()
+					.map(|p| SqliteDialect::field_path(p))
+					.collect::<Vec<_>>()
+					.join(", ");
+				let stm = format!("SELECT id, {f} FROM record {h} {c} {o} {l} {s}");
 				let res = self.query(Cow::Owned(stm), None).await?;
 				// We use a for loop to iterate over the results, while
 				// calling black_box internally. This is necessary as
@@ -504,7 +855,7 @@ impl SqliteClient {
 				Ok(count)
 			}
 			Projection::Count => {
-				let stm = format!("SELECT COUNT(*) FROM (SELECT id FROM record {c} {l} {s})");
+				let stm = format!("SELECT COUNT(*) FROM (SELECT id FROM record {h} {c} {l} {s})");
 				let res = self.query(Cow::Owned(stm), None).await?;
 				let Value::Integer(count) = res.first().unwrap().first().unwrap().1 else {
 					panic!("Unexpected response type `{res:?}`");
@@ -577,7 +928,7 @@ impl SqliteClient {
 		.map_err(Into::into)
 	}
 
-	async fn batch_read<T>(&self, keys: Vec<T>) -> Result<()>
+	async fn batch_read<T>(&self, table: &'static str, keys: Vec<T>) -> Result<()>
 	where
 		T: Into<ToSqlOutput<'static>> + Send + 'static,
 	{
@@ -585,6 +936,8 @@ impl SqliteClient {
 		let conn = self.conn.clone();
 		// Fetch the columns to read
 		let column_defs = self.columns.clone();
+		// Fetch the depth to materialise the rows to
+		let mode = materialize::mode();
 		// Execute the batch read on the connection
 		conn.call(move |conn| -> rusqlite::Result<()> {
 			// Build the IN clause with positional parameters
@@ -595,7 +948,7 @@ impl SqliteClient {
 				all_params.push(Box::new(key.into()));
 			}
 			// Build and execute the SELECT statement
-			let stmt = format!("SELECT * FROM record WHERE id IN ({ids})");
+			let stmt = format!("SELECT * FROM {table} WHERE id IN ({ids})");
 			let params: Vec<&dyn tokio_rusqlite::types::ToSql> =
 				all_params.iter().map(|p| p.as_ref()).collect();
 			// Prepare the statement
@@ -604,19 +957,25 @@ impl SqliteClient {
 			// Iterate over the rows
 			let mut count = 0;
 			while let Some(row) = rows.next()? {
-				// Consume the row
-				let names = row.as_ref().column_names();
-				let mut map = Vec::with_capacity(names.len());
-				for (i, name) in names.into_iter().enumerate() {
-					map.push((name.to_owned(), row.get(i)?));
-				}
-				// Build the typed bench value
-				let mut val: Vec<(String, BenchValue)> = Vec::with_capacity(map.len());
-				for (key, value) in map {
-					let bv = sqlite_cell_to_bench(&column_defs, &key, value);
-					val.push((key, bv));
+				// Read the cells of the row, unless only counting
+				if mode != Materialize::Count {
+					let names = row.as_ref().column_names();
+					let mut map: Vec<(String, Value)> = Vec::with_capacity(names.len());
+					for (i, name) in names.into_iter().enumerate() {
+						map.push((name.to_owned(), row.get(i)?));
+					}
+					if mode == Materialize::Bytes {
+						black_box(map);
+					} else {
+						// Build the typed bench value
+						let mut val: Vec<(String, BenchValue)> = Vec::with_capacity(map.len());
+						for (key, value) in map {
+							let bv = sqlite_cell_to_bench(&column_defs, &key, value);
+							val.push((key, bv));
+						}
+						black_box(BenchValue::Object(val));
+					}
 				}
-				black_box(BenchValue::Object(val));
 				count += 1;
 			}
 			// Check the number of rows read
@@ -1,10 +1,244 @@
+//! Results stored in SurrealDB with `--store-results`, and read back by `crud-bench results`.
+//!
+//! Every run is stored twice: as the complete result document in `result`, and normalized into
+//! `run` (the run parameters and datastore version), `system` (one record per host shape, shared
+//! by its runs), and one `operation` or `scan` record per timed phase. The normalized tables are
+//! indexed by database, version, and date, so trend dashboards can query them directly.
+
+use crate::compare::ResultFile;
+use crate::export::category;
 use crate::result::BenchmarkResult;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use surrealdb::Surreal;
 use surrealdb::engine::remote::ws::Client;
 use surrealdb::engine::remote::ws::Ws;
 use surrealdb::opt::auth::Root;
 
+/// The version of the normalized schema, recorded on every run.
+const SCHEMA_VERSION: u32 = 2;
+
+/// Defines the tables, fields, and indexes of the stored results.
+const SCHEMA: &str = r#"
+	DEFINE TABLE IF NOT EXISTS result SCHEMAFULL;
+	DEFINE FIELD IF NOT EXISTS database ON result TYPE option<string>;
+	DEFINE FIELD IF NOT EXISTS system_info ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS benchmark_metadata ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS workload ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS config ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS creates ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS reads ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS restricted_reads ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS secondary_reads ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS relations ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS live_queries ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS replication_lag ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS server_timings ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS resources ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS updates ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS path_updates ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS synced_updates ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS deletes ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS delete_strategies ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS scans ON result TYPE array;
+	DEFINE FIELD IF NOT EXISTS pagination ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS growth ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS batches ON result TYPE array;
+	DEFINE FIELD IF NOT EXISTS time_series ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS skipped ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS compactions ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS backup ON result TYPE option<object>;
+	DEFINE FIELD IF NOT EXISTS footprints ON result TYPE option<array>;
+	DEFINE FIELD IF NOT EXISTS sample ON result TYPE object;
+	DEFINE FIELD IF NOT EXISTS timestamp ON result TYPE datetime DEFAULT time::now();
+	DEFINE INDEX IF NOT EXISTS idx_database ON result FIELDS database;
+	DEFINE INDEX IF NOT EXISTS idx_timestamp ON result FIELDS timestamp;
+	DEFINE TABLE IF NOT EXISTS system SCHEMAFULL;
+	DEFINE FIELD IF NOT EXISTS os_name ON system TYPE string;
+	DEFINE FIELD IF NOT EXISTS cpu_arch ON system TYPE string;
+	DEFINE FIELD IF NOT EXISTS cpu_cores ON system TYPE int;
+	DEFINE FIELD IF NOT EXISTS cpu_physical_cores ON system TYPE int;
+	DEFINE FIELD IF NOT EXISTS total_memory ON system TYPE int;
+	DEFINE TABLE IF NOT EXISTS run SCHEMAFULL;
+	DEFINE FIELD IF NOT EXISTS schema_version ON run TYPE int;
+	DEFINE FIELD IF NOT EXISTS result ON run TYPE record<result>;
+	DEFINE FIELD IF NOT EXISTS system ON run TYPE option<record<system>>;
+	DEFINE FIELD IF NOT EXISTS database ON run TYPE option<string>;
+	DEFINE FIELD IF NOT EXISTS version ON run TYPE option<string>;
+	DEFINE FIELD IF NOT EXISTS samples ON run TYPE option<int>;
+	DEFINE FIELD IF NOT EXISTS clients ON run TYPE option<int>;
+	DEFINE FIELD IF NOT EXISTS threads ON run TYPE option<int>;
+	DEFINE FIELD IF NOT EXISTS key_type ON run TYPE option<string>;
+	DEFINE FIELD IF NOT EXISTS random ON run TYPE option<bool>;
+	DEFINE FIELD IF NOT EXISTS timestamp ON run TYPE datetime DEFAULT time::now();
+	DEFINE INDEX IF NOT EXISTS idx_run_database ON run FIELDS database, timestamp;
+	DEFINE INDEX IF NOT EXISTS idx_run_version ON run FIELDS version, timestamp;
+	DEFINE INDEX IF NOT EXISTS idx_run_timestamp ON run FIELDS timestamp;
+	DEFINE TABLE IF NOT EXISTS operation SCHEMAFULL;
+	DEFINE TABLE IF NOT EXISTS scan SCHEMAFULL;
+	FOR $table IN ['operation', 'scan'] {
+		DEFINE FIELD IF NOT EXISTS run ON type::table($table) TYPE record<run>;
+		DEFINE FIELD IF NOT EXISTS scenario ON type::table($table) TYPE string;
+		DEFINE FIELD IF NOT EXISTS category ON type::table($table) TYPE string;
+		DEFINE FIELD IF NOT EXISTS ops ON type::table($table) TYPE float;
+		DEFINE FIELD IF NOT EXISTS mean ON type::table($table) TYPE float;
+		DEFINE FIELD IF NOT EXISTS min ON type::table($table) TYPE int;
+		DEFINE FIELD IF NOT EXISTS q50 ON type::table($table) TYPE int;
+		DEFINE FIELD IF NOT EXISTS q95 ON type::table($table) TYPE int;
+		DEFINE FIELD IF NOT EXISTS q99 ON type::table($table) TYPE int;
+		DEFINE FIELD IF NOT EXISTS max ON type::table($table) TYPE int;
+	};
+	DEFINE INDEX IF NOT EXISTS idx_operation_run ON operation FIELDS run;
+	DEFINE INDEX IF NOT EXISTS idx_operation_scenario ON operation FIELDS scenario;
+	DEFINE INDEX IF NOT EXISTS idx_scan_run ON scan FIELDS run;
+	DEFINE INDEX IF NOT EXISTS idx_scan_scenario ON scan FIELDS scenario;
+"#;
+
+/// Stores the result document, then the run, its host, and its phases linked to it.
+const STORE: &str = r#"
+	BEGIN TRANSACTION;
+	LET $stored = (CREATE ONLY result CONTENT $result RETURN id).id;
+	LET $host = IF $system {
+		(UPSERT ONLY type::record('system', [$system.os_name, $system.cpu_arch, $system.cpu_cores, $system.total_memory])
+			CONTENT $system RETURN id).id
+	};
+	LET $entry = (CREATE ONLY run SET
+		schema_version = $run.schema_version,
+		result = $stored,
+		system = $host,
+		database = $run.database,
+		version = $run.version,
+		samples = $run.samples,
+		clients = $run.clients,
+		threads = $run.threads,
+		key_type = $run.key_type,
+		random = $run.random
+	RETURN id).id;
+	INSERT INTO operation (SELECT *, $entry AS run FROM $operations);
+	INSERT INTO scan (SELECT *, $entry AS run FROM $scans);
+	COMMIT TRANSACTION;
+"#;
+
+/// The parameters of a stored run, leaving out those which were not recorded.
+#[derive(Debug, Serialize)]
+struct StoredRun<'a> {
+	schema_version: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	database: Option<&'a str>,
+	/// The datastore version, when it was detected.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	version: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	samples: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	clients: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	threads: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	key_type: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	random: Option<bool>,
+}
+
+/// One timed phase of a stored run, as stored and as queried.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct StoredPhase {
+	/// Row label, as in the CLI and CSV tables.
+	pub(crate) scenario: String,
+	/// One of `crud`, `scan`, `index`, or `batch`.
+	pub(crate) category: String,
+	/// Operations per second.
+	pub(crate) ops: f64,
+	pub(crate) mean: f64,
+	pub(crate) min: u64,
+	pub(crate) q50: u64,
+	pub(crate) q95: u64,
+	pub(crate) q99: u64,
+	pub(crate) max: u64,
+}
+
+/// A stored run with its phases, as returned by [`StorageClient::query_runs`].
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct QueriedRun {
+	pub(crate) id: String,
+	pub(crate) timestamp: String,
+	pub(crate) database: Option<String>,
+	pub(crate) version: Option<String>,
+	pub(crate) samples: Option<u32>,
+	pub(crate) clients: Option<u32>,
+	pub(crate) threads: Option<u32>,
+	pub(crate) key_type: Option<String>,
+	pub(crate) os_name: Option<String>,
+	pub(crate) cpu_arch: Option<String>,
+	pub(crate) cpu_cores: Option<usize>,
+	pub(crate) operations: Vec<StoredPhase>,
+	pub(crate) scans: Vec<StoredPhase>,
+}
+
+/// Which stored runs to return, newest first.
+#[derive(Debug, Default)]
+pub(crate) struct RunFilter {
+	pub(crate) database: Option<String>,
+	pub(crate) version: Option<String>,
+	/// The earliest date or datetime of the runs, such as `2026-01-31`
+	pub(crate) since: Option<String>,
+	/// Only the phases with this row label
+	pub(crate) scenario: Option<String>,
+	pub(crate) limit: u32,
+}
+
+impl RunFilter {
+	/// The `SELECT` returning the matching runs with their phases.
+	fn query(&self) -> String {
+		let mut conditions = Vec::new();
+		if self.database.is_some() {
+			conditions.push("database = $database");
+		}
+		if self.version.is_some() {
+			conditions.push("version = $version");
+		}
+		if self.since.is_some() {
+			conditions.push("timestamp >= <datetime> $since");
+		}
+		let filter = match conditions.is_empty() {
+			true => String::new(),
+			false => format!("WHERE {}", conditions.join(" AND ")),
+		};
+		let phases = match self.scenario.is_some() {
+			true => "WHERE run = $parent.id AND scenario = $scenario",
+			false => "WHERE run = $parent.id",
+		};
+		format!(
+			"SELECT <string> id AS id, <string> timestamp AS timestamp, database, version, samples,
+				clients, threads, key_type, system.os_name AS os_name, system.cpu_arch AS cpu_arch,
+				system.cpu_cores AS cpu_cores,
+				(SELECT scenario, category, ops, mean, min, q50, q95, q99, max FROM operation {phases}) AS operations,
+				(SELECT scenario, category, ops, mean, min, q50, q95, q99, max FROM scan {phases}) AS scans
+			FROM run {filter} ORDER BY timestamp DESC LIMIT $limit"
+		)
+	}
+}
+
+/// Splits the timed phases of a result into its operations and its scans.
+fn stored_phases(result: &ResultFile) -> (Vec<StoredPhase>, Vec<StoredPhase>) {
+	result
+		.rows()
+		.into_iter()
+		.filter_map(|(scenario, phase)| phase.map(|p| (scenario, p)))
+		.map(|(scenario, p)| StoredPhase {
+			category: category(&scenario).to_string(),
+			scenario,
+			ops: p.ops,
+			mean: p.mean,
+			min: p.min,
+			q50: p.q50,
+			q95: p.q95,
+			q99: p.q99,
+			max: p.max,
+		})
+		.partition(|p| !matches!(p.category.as_str(), "scan" | "index"))
+}
+
 pub struct StorageClient {
 	db: Surreal<Client>,
 }
@@ -26,33 +260,96 @@ impl StorageClient {
 		})
 	}
 
+	/// Creates the schema if it does not exist, then stores the result and its normalized rows.
 	pub async fn store_result(&self, result: &BenchmarkResult) -> Result<()> {
-		// Create the schema if it doesn't exist
-		self.db
-			.query(
-				r#"
-				DEFINE TABLE IF NOT EXISTS result SCHEMAFULL;
-				DEFINE FIELD IF NOT EXISTS database ON result TYPE option<string>;
-				DEFINE FIELD IF NOT EXISTS system_info ON result TYPE option<object>;
-				DEFINE FIELD IF NOT EXISTS benchmark_metadata ON result TYPE option<object>;
-				DEFINE FIELD IF NOT EXISTS creates ON result TYPE option<object>;
-				DEFINE FIELD IF NOT EXISTS reads ON result TYPE option<object>;
-				DEFINE FIELD IF NOT EXISTS updates ON result TYPE option<object>;
-				DEFINE FIELD IF NOT EXISTS deletes ON result TYPE option<object>;
-				DEFINE FIELD IF NOT EXISTS scans ON result TYPE array;
-				DEFINE FIELD IF NOT EXISTS batches ON result TYPE array;
-				DEFINE FIELD IF NOT EXISTS sample ON result TYPE object;
-				DEFINE FIELD IF NOT EXISTS timestamp ON result TYPE datetime DEFAULT time::now();
-				DEFINE INDEX IF NOT EXISTS idx_database ON result FIELDS database;
-				DEFINE INDEX IF NOT EXISTS idx_timestamp ON result FIELDS timestamp;
-			"#,
-			)
-			.await?;
+		self.db.query(SCHEMA).await?.check()?;
 		// Convert to serde_json::Value for insertion
-		let result = serde_json::to_value(result)?;
-		// Insert the result using a query
-		self.db.query("CREATE result CONTENT $result").bind(("result", result)).await?.check()?;
+		let value = serde_json::to_value(result)?;
+		// Read the phases back as row labels, as `crud-bench export` does
+		let file: ResultFile = serde_json::from_value(value.clone())?;
+		let (operations, scans) = stored_phases(&file);
+		let metadata = file.metadata.as_ref();
+		let run = StoredRun {
+			schema_version: SCHEMA_VERSION,
+			database: file.database.as_deref(),
+			version: metadata.and_then(|m| m.version.as_deref()),
+			samples: metadata.map(|m| m.samples),
+			clients: metadata.map(|m| m.clients),
+			threads: metadata.map(|m| m.threads),
+			key_type: metadata.map(|m| m.key_type.as_str()),
+			random: metadata.map(|m| m.random),
+		};
+		let system = serde_json::to_value(&file.system)?;
+		self.db
+			.query(STORE)
+			.bind(("result", value))
+			.bind(("system", system))
+			.bind(("run", serde_json::to_value(run)?))
+			.bind(("operations", serde_json::to_value(operations)?))
+			.bind(("scans", serde_json::to_value(scans)?))
+			.await?
+			.check()?;
 		// All ok
 		Ok(())
 	}
+
+	/// The stored runs matching the filter, newest first, with their phases.
+	pub async fn query_runs(&self, filter: &RunFilter) -> Result<Vec<QueriedRun>> {
+		let runs = self
+			.db
+			.query(filter.query())
+			.bind(("database", filter.database.clone()))
+			.bind(("version", filter.version.clone()))
+			.bind(("since", filter.since.clone()))
+			.bind(("scenario", filter.scenario.clone()))
+			.bind(("limit", filter.limit))
+			.await?
+			.check()?
+			.take::<surrealdb::types::Value>(0)?;
+		Ok(serde_json::from_value(runs.into_json_value())?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn phases_are_split_into_operations_and_scans() {
+		let result: ResultFile = serde_json::from_str(
+			r#"{
+				"creates": {"ops": 1000.0, "q99": 12},
+				"updates": null,
+				"scans": [{
+					"id": "limit",
+					"name": "select(id) limit(100)",
+					"samples": 10,
+					"index_build": {"ops": 1.0},
+					"index_remove": null,
+					"runs": [{"workload": {"kind": "read"}, "indexed": false, "result": {"ops": 50.0}}]
+				}],
+				"batches": [["batch_test", 10, 5, {"ops": 50.0}]]
+			}"#,
+		)
+		.unwrap();
+		let (operations, scans) = stored_phases(&result);
+		let labels = |p: &[StoredPhase]| p.iter().map(|p| p.scenario.clone()).collect::<Vec<_>>();
+		assert_eq!(labels(&operations), ["[C]reate", "[B]atch::batch_test (10 batches of 5)"]);
+		assert_eq!(scans.len(), 1);
+		assert_eq!(scans[0].category, "scan");
+		assert_eq!(operations[0].q99, 12);
+	}
+
+	#[test]
+	fn filters_only_bind_what_is_set() {
+		let filter = RunFilter {
+			database: Some("surrealdb".to_string()),
+			limit: 5,
+			..Default::default()
+		};
+		let query = filter.query();
+		assert!(query.contains("FROM run WHERE database = $database ORDER BY"));
+		assert!(!query.contains("$version"));
+		assert!(!query.contains("$scenario"));
+	}
 }
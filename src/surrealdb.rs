@@ -1,27 +1,31 @@
 #![cfg(feature = "surrealdb")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::dialect::SurrealDBDialect;
-use crate::docker::DockerParams;
-use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::docker::{DockerParams, DockerService, DockerTopology};
+use crate::engine::{BenchmarkClient, BenchmarkEngine, LiveStream, PageStart, ScanContext};
+use crate::error::BenchError;
+use crate::keyprovider::GeneratedKey;
+use crate::materialize;
 use crate::memory::Config as MemoryConfig;
+use crate::timeseries::{TimeSeriesRow, TimeWindow};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
 use crate::{Benchmark, Index, KeyType, Projection, Scan};
-use anyhow::{Result, bail};
-use log::{error, warn};
+use anyhow::{Result, anyhow, bail};
+use futures::StreamExt;
 use std::env;
 use std::hint::black_box;
 use std::time::Duration;
-use surrealdb::Surreal;
 use surrealdb::engine::any::{Any, connect};
-use surrealdb::opt::auth::Root;
+use surrealdb::opt::auth::{Record, Root};
 use surrealdb::opt::{Config, Resource};
 use surrealdb::types::{
 	Array, Bytes as SurrealBytes, Datetime, Number, Object, RecordId, RecordIdKey, SurrealValue,
 	ToSql, Uuid as SurrealUuid, Value,
 };
+use surrealdb::{Notification, Surreal};
 use tokio::time::{sleep, timeout};
+use tracing::{error, warn};
 
 /// Convert a [`BenchValue`] to a native [`surrealdb::types::Value`]. UUID,
 /// datetime, decimal, and bytes go through directly without a JSON detour.
@@ -84,8 +88,35 @@ fn surreal_to_bench_value(v: Value) -> BenchValue {
 }
 
 const DEFAULT: &str = "ws://127.0.0.1:8000";
+
+/// The endpoint of the Docker container when connecting over HTTP
+const DEFAULT_HTTP: &str = "http://127.0.0.1:8000";
+
+/// The endpoint of the second node of the `tikv` topology
+const REPLICA: &str = "ws://127.0.0.1:8001";
 const TABLE: &str = "record";
 
+/// The table holding the records with keys generated by the datastore
+const GENERATED_TABLE: &str = "record_generated";
+
+/// The table holding the rows appended by the time-series workload
+const TIME_SERIES_TABLE: &str = "metric";
+
+/// The table holding the events written while the live queries are open
+const LIVE_TABLE: &str = "live_event";
+
+/// The relation table holding the edges between the records
+const EDGE_TABLE: &str = "edge";
+
+/// The table holding the writes whose replication is measured
+const REPLICATED_TABLE: &str = "replicated";
+
+/// The file which the database is exported to
+const BACKUP_FILE: &str = "surrealdb-backup.surql";
+
+/// The database which the export is imported into
+const RESTORED_DATABASE: &str = "restored";
+
 /// Wraps a SurrealDB [`types::Value`](surrealdb::types::Value);
 /// [`BenchValue`] is produced only via [`From`]/[`Into`].
 pub(crate) struct Row(pub Value);
@@ -96,8 +127,21 @@ impl From<Row> for BenchValue {
 	}
 }
 
+/// Materialise the records of a query response, returning the number of records.
+fn consume_records(res: Value) -> Result<usize> {
+	let Value::Array(arr) = res else {
+		panic!("Unexpected response type");
+	};
+	let records = Vec::from(arr);
+	let count = records.len();
+	for v in records {
+		materialize::consume(v, |v| Ok(surreal_to_bench_value(v)))?;
+	}
+	Ok(count)
+}
+
 /// Wrap a SurrealDB result error so the failing SurrealQL surfaces in two
-/// places: a `log::error!` line emitted immediately (visible in the bench's
+/// places: a `tracing::error!` line emitted immediately (visible in the bench's
 /// stderr stream while it is still running), and the `anyhow::Error` chain
 /// returned to the caller (which `main` prints with the `{:#}` formatter so
 /// the chain — including the `query: ...` context — is shown end-to-end).
@@ -230,6 +274,75 @@ pub(super) fn surrealdb_password() -> String {
 	env::var("SURREALDB_PASS").unwrap_or_else(|_| String::from("root"))
 }
 
+/// Runs the datastore on a TiKV cluster, with its placement driver, as the storage layer
+pub(crate) fn topology(
+	params: DockerParams,
+	topology: &str,
+	options: &Benchmark,
+) -> Result<DockerTopology> {
+	if topology != "tikv" {
+		bail!("SurrealDB does not support the `{topology}` topology, expected `tikv`");
+	}
+	if options.endpoint.is_some() {
+		bail!("The `tikv` topology replaces the storage backend chosen with `--endpoint`");
+	}
+	// Get credentials from environment variables or use defaults
+	let username = surrealdb_username();
+	let password = surrealdb_password();
+	Ok(DockerTopology {
+		main: DockerParams {
+			image: params.image,
+			pre_args: "--ulimit nofile=65536:65536 -p 8000:8000 --user root".to_string(),
+			post_args: format!("start --user {username} --pass {password} tikv://127.0.0.1:2379"),
+		},
+		services: vec![
+			DockerService {
+				name: "pd",
+				params: DockerParams {
+					image: "pingcap/pd",
+					pre_args: "--ulimit nofile=65536:65536".to_string(),
+					post_args: "--name=pd \
+						--data-dir=/data/pd \
+						--client-urls=http://127.0.0.1:2379 \
+						--peer-urls=http://127.0.0.1:2380 \
+						--advertise-client-urls=http://127.0.0.1:2379 \
+						--advertise-peer-urls=http://127.0.0.1:2380"
+						.to_string(),
+				},
+				before: true,
+			},
+			DockerService {
+				name: "tikv",
+				params: DockerParams {
+					image: "pingcap/tikv",
+					// TiKV refuses to start with fewer than 82920 open files
+					pre_args: "--ulimit nofile=1048576:1048576".to_string(),
+					post_args: "--addr=127.0.0.1:20160 \
+						--advertise-addr=127.0.0.1:20160 \
+						--status-addr=127.0.0.1:20180 \
+						--data-dir=/data/tikv \
+						--pd=127.0.0.1:2379"
+						.to_string(),
+				},
+				before: true,
+			},
+			// A second node over the same TiKV cluster, which the replication lag is read from
+			DockerService {
+				name: "node2",
+				params: DockerParams {
+					image: params.image,
+					pre_args: "--ulimit nofile=65536:65536 --user root".to_string(),
+					post_args: format!(
+						"start --user {username} --pass {password} --bind 0.0.0.0:8001 tikv://127.0.0.1:2379"
+					),
+				},
+				before: false,
+			},
+		],
+		setup: vec![],
+	})
+}
+
 pub(crate) fn docker(options: &Benchmark) -> DockerParams {
 	// Parse the endpoint or use the default
 	let backend =
@@ -295,6 +408,8 @@ pub(crate) struct SurrealDBClientProvider {
 	client: Option<Surreal<Any>>,
 	endpoint: String,
 	root: Root,
+	/// The endpoint of the second node of the `tikv` topology
+	replica: Option<&'static str>,
 }
 
 pub(super) async fn initialise_db(endpoint: &str, root: Root) -> Result<Surreal<Any>> {
@@ -310,14 +425,56 @@ pub(super) async fn initialise_db(endpoint: &str, root: Root) -> Result<Surreal<
 	Ok(db)
 }
 
+/// The record access method used for restricted reads
+const RESTRICTED_ACCESS: &str = "reader";
+
+/// The record user name used for restricted reads
+const RESTRICTED_USER: &str = "bench";
+
+/// Sign-in parameters for the restricted record user
+#[derive(Debug, SurrealValue)]
+#[surreal(crate = "surrealdb::types")]
+struct Credentials {
+	name: String,
+}
+
+/// Connects and signs in as the record user created by
+/// [`BenchmarkClient::setup_restricted_reads`], so that
+/// table permissions are checked on every query.
+pub(super) async fn initialise_restricted_db(endpoint: &str) -> Result<Surreal<Any>> {
+	// Connect to the database
+	let db = connect(endpoint).await?;
+	// Signin as the restricted record user
+	db.signin(Record {
+		namespace: "test".to_string(),
+		database: "test".to_string(),
+		access: RESTRICTED_ACCESS.to_string(),
+		params: Credentials {
+			name: RESTRICTED_USER.to_string(),
+		},
+	})
+	.await?;
+	// Select a specific namespace / database
+	db.use_ns("test").use_db("test").await?;
+	// Return the client
+	Ok(db)
+}
+
 impl BenchmarkEngine<SurrealDBClient> for SurrealDBClientProvider {
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
 		// Parse the benchmark engine endpoint
 		let mode = parse_endpoint(options.endpoint.as_deref())?;
+		// TLS is chosen by the `wss://` and `https://` schemes of the endpoint
+		options.security.without_tls_options("SurrealDB")?;
 		// Define root user details from environment variables or use defaults
-		let username = surrealdb_username();
-		let password = surrealdb_password();
+		let mut username = surrealdb_username();
+		let mut password = surrealdb_password();
+		// A remote server may be signed in to as another user
+		if matches!(mode, Endpoint::Remote(_)) {
+			username = options.security.username(&username);
+			password = options.security.password(&password);
+		}
 		let root = Root {
 			username,
 			password,
@@ -348,8 +505,17 @@ impl BenchmarkEngine<SurrealDBClient> for SurrealDBClientProvider {
 			endpoint,
 			root,
 			client,
+			replica: options.topology.is_some().then_some(REPLICA),
 		})
 	}
+	/// Checks the server reports itself healthy, as its `/health` endpoint does, before the
+	/// clients sign in. An embedded datastore is ready once it has been opened.
+	async fn ready(&self) -> Result<()> {
+		if self.client.is_none() {
+			connect(self.endpoint.as_str()).await?.health().await?;
+		}
+		Ok(())
+	}
 	/// Creates a new client for this benchmarking engine
 	async fn create_client(&self) -> Result<SurrealDBClient> {
 		let client = match &self.client {
@@ -358,6 +524,79 @@ impl BenchmarkEngine<SurrealDBClient> for SurrealDBClientProvider {
 		};
 		Ok(SurrealDBClient::new(client))
 	}
+	/// Creates a client signed in as the restricted record user
+	async fn create_restricted_client(&self) -> Result<SurrealDBClient> {
+		// Embedded clients share a single connection, so signing
+		// in as the record user would affect every other client
+		if self.client.is_some() {
+			bail!(BenchError::Unsupported);
+		}
+		let client = initialise_restricted_db(&self.endpoint).await?;
+		Ok(SurrealDBClient::new(client))
+	}
+	/// Creates a client connected to the second node of the `tikv` topology
+	async fn create_replica_client(&self) -> Result<SurrealDBClient> {
+		let Some(endpoint) = self.replica else {
+			bail!(BenchError::Unsupported);
+		};
+		let client = initialise_db(endpoint, self.root.clone()).await?;
+		Ok(SurrealDBClient::new(client))
+	}
+	/// Opens a live query on the events table
+	async fn subscribe(&self) -> Result<LiveStream> {
+		let client = self.create_client().await?;
+		let sql = format!("LIVE SELECT * FROM {LIVE_TABLE}");
+		let mut res = client.db.query(&sql).await.map_err(log_sql_err(&sql))?;
+		let stream = res.stream::<Notification<Value>>(0).map_err(log_sql_err(&sql))?;
+		Ok(Box::pin(stream.map(|notification| {
+			let data = notification?.data;
+			match data["sent"] {
+				Value::Number(Number::Int(sent)) => Ok(sent as u64),
+				_ => bail!("Unexpected live query notification: {data:?}"),
+			}
+		})))
+	}
+}
+
+/// Connects to a SurrealDB server over its HTTP endpoint rather than a WebSocket, so the
+/// protocol overhead can be measured on an identical workload.
+pub(crate) struct SurrealDBHttpClientProvider(SurrealDBClientProvider);
+
+impl BenchmarkEngine<SurrealDBClient> for SurrealDBHttpClientProvider {
+	/// Initiates a new datastore benchmarking engine
+	async fn setup(kt: KeyType, columns: Columns, options: &Benchmark) -> Result<Self> {
+		// Only a server can be connected to over HTTP
+		let docker = match parse_endpoint(options.endpoint.as_deref())? {
+			Endpoint::Docker(_) => true,
+			Endpoint::Remote(url) if url.starts_with("http://") || url.starts_with("https://") => {
+				false
+			}
+			_ => {
+				bail!("The surrealdb-http endpoint must be a server, or an http:// or https:// URL")
+			}
+		};
+		let mut provider = SurrealDBClientProvider::setup(kt, columns, options).await?;
+		if docker {
+			provider.endpoint = DEFAULT_HTTP.to_string();
+		}
+		Ok(Self(provider))
+	}
+	/// Checks the server reports itself healthy
+	async fn ready(&self) -> Result<()> {
+		self.0.ready().await
+	}
+	/// Creates a new client for this benchmarking engine
+	async fn create_client(&self) -> Result<SurrealDBClient> {
+		self.0.create_client().await
+	}
+	/// Creates a client signed in as the restricted record user
+	async fn create_restricted_client(&self) -> Result<SurrealDBClient> {
+		self.0.create_restricted_client().await
+	}
+	/// Creates a client connected to the second node of the `tikv` topology
+	async fn create_replica_client(&self) -> Result<SurrealDBClient> {
+		self.0.create_replica_client().await
+	}
 }
 
 pub(crate) struct SurrealDBClient {
@@ -400,6 +639,116 @@ impl BenchmarkClient for SurrealDBClient {
 		Ok(())
 	}
 
+	async fn setup_generated_keys(&self) -> Result<()> {
+		// Define the table up front, for the same reason as in startup
+		let sql = format!(
+			"
+			REMOVE TABLE IF EXISTS {GENERATED_TABLE};
+			DEFINE TABLE {GENERATED_TABLE};
+			"
+		);
+		self.db.query(&sql).await.map_err(log_sql_err(&sql))?.check().map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn setup_time_series(&self) -> Result<()> {
+		// Define the table up front, for the same reason as in startup
+		let sql = format!(
+			"
+			REMOVE TABLE IF EXISTS {TIME_SERIES_TABLE};
+			DEFINE TABLE {TIME_SERIES_TABLE};
+			DEFINE INDEX {TIME_SERIES_TABLE}_ts ON {TIME_SERIES_TABLE} FIELDS ts;
+			"
+		);
+		self.db.query(&sql).await.map_err(log_sql_err(&sql))?.check().map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn setup_live_queries(&self) -> Result<()> {
+		// Define the table up front, for the same reason as in startup
+		let sql = format!(
+			"
+			REMOVE TABLE IF EXISTS {LIVE_TABLE};
+			DEFINE TABLE {LIVE_TABLE};
+			"
+		);
+		self.db.query(&sql).await.map_err(log_sql_err(&sql))?.check().map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn setup_replication_lag(&self) -> Result<()> {
+		// Define the table up front, for the same reason as in startup
+		let sql = format!(
+			"
+			REMOVE TABLE IF EXISTS {REPLICATED_TABLE};
+			DEFINE TABLE {REPLICATED_TABLE};
+			"
+		);
+		self.db.query(&sql).await.map_err(log_sql_err(&sql))?.check().map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn read_replicated(&self, from: u32) -> Result<Vec<(u32, u64)>> {
+		let sql =
+			format!("SELECT record::id(id) AS id, sent FROM {REPLICATED_TABLE} WHERE id >= $from");
+		let res: surrealdb::types::Value = self
+			.db
+			.query(&sql)
+			.bind(("from", Value::RecordId(RecordId::new(REPLICATED_TABLE, from as i64))))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.take(0)
+			.map_err(log_sql_err(&sql))?;
+		let Some(rows) = res.as_array() else {
+			bail!("Unexpected response type");
+		};
+		rows.iter()
+			.map(|row| match (&row["id"], &row["sent"]) {
+				(Value::Number(Number::Int(id)), Value::Number(Number::Int(sent))) => {
+					Ok((*id as u32, *sent as u64))
+				}
+				_ => bail!("Unexpected replicated write: {row:?}"),
+			})
+			.collect()
+	}
+
+	async fn setup_relations(&self) -> Result<()> {
+		// A relation table only holds edges, whose ends
+		// are records of the records table on both sides
+		let sql = format!(
+			"
+			REMOVE TABLE IF EXISTS {EDGE_TABLE};
+			DEFINE TABLE {EDGE_TABLE} TYPE RELATION IN {TABLE} OUT {TABLE};
+			"
+		);
+		self.db.query(&sql).await.map_err(log_sql_err(&sql))?.check().map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn setup_restricted_reads(&self) -> Result<()> {
+		// Record users are subject to table permissions, whereas
+		// the root user bypasses them. The select permission
+		// admits every row for the reader, so the difference to
+		// the unrestricted reads is the cost of the check itself.
+		let sql = format!(
+			"
+			DEFINE TABLE OVERWRITE {RESTRICTED_ACCESS} PERMISSIONS FOR select WHERE id = $auth.id;
+			UPSERT {RESTRICTED_ACCESS}:{RESTRICTED_USER} SET name = '{RESTRICTED_USER}';
+			DEFINE ACCESS OVERWRITE {RESTRICTED_ACCESS} ON DATABASE TYPE RECORD
+				SIGNIN (SELECT * FROM {RESTRICTED_ACCESS} WHERE name = $name);
+			DEFINE TABLE OVERWRITE record PERMISSIONS
+				FOR select WHERE $auth.id = {RESTRICTED_ACCESS}:{RESTRICTED_USER}
+				FOR create, update, delete NONE;
+			"
+		);
+		self.db.query(&sql).await.map_err(log_sql_err(&sql))?.check().map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn version(&self) -> Result<String> {
+		Ok(self.db.version().await?.to_string())
+	}
+
 	async fn compact(&self) -> Result<()> {
 		// Issue a system compaction request
 		let sql = "ALTER SYSTEM COMPACT";
@@ -418,6 +767,26 @@ impl BenchmarkClient for SurrealDBClient {
 		}
 	}
 
+	async fn backup(&self) -> Result<Option<u64>> {
+		self.db.export(BACKUP_FILE).await?;
+		Ok(std::fs::metadata(BACKUP_FILE).ok().map(|m| m.len()))
+	}
+
+	async fn restore(&self) -> Result<()> {
+		// The clients share the connection, so it returns to the benchmarked database
+		self.db.use_db(RESTORED_DATABASE).await?;
+		let res = self.db.import(BACKUP_FILE).await;
+		self.db.use_db("test").await?;
+		Ok(res?)
+	}
+
+	async fn remove_backup(&self) -> Result<()> {
+		let sql = format!("REMOVE DATABASE IF EXISTS {RESTORED_DATABASE}");
+		self.db.query(&sql).await.map_err(log_sql_err(&sql))?.check().map_err(log_sql_err(&sql))?;
+		std::fs::remove_file(BACKUP_FILE).ok();
+		Ok(())
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create(key as i64, val).await
 	}
@@ -434,6 +803,67 @@ impl BenchmarkClient for SurrealDBClient {
 		self.read(key).await
 	}
 
+	async fn read_secondary(&self, field: &str, value: String) -> Result<usize> {
+		let sql = format!("SELECT * FROM record WHERE {field} = $value");
+		let res: surrealdb::types::Value = self
+			.db
+			.query(&sql)
+			.bind(("value", Value::String(value)))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.take(0)
+			.map_err(log_sql_err(&sql))?;
+		// Check the response
+		let Some(arr) = res.as_array() else {
+			panic!("Unexpected response type");
+		};
+		Ok(black_box(arr).len())
+	}
+
+	async fn read_server_time_u32(&self, key: u32) -> Result<Duration> {
+		self.read_server_time(key as i64).await
+	}
+
+	async fn read_server_time_string(&self, key: String) -> Result<Duration> {
+		self.read_server_time(key).await
+	}
+
+	async fn relate_u32(&self, key: u32, targets: Vec<u32>) -> Result<()> {
+		self.relate(key as i64, targets.into_iter().map(|t| t as i64)).await
+	}
+
+	async fn relate_string(&self, key: String, targets: Vec<String>) -> Result<()> {
+		self.relate(key, targets.into_iter()).await
+	}
+
+	async fn traverse_u32(&self, key: u32) -> Result<usize> {
+		self.traverse(key as i64).await
+	}
+
+	async fn traverse_string(&self, key: String) -> Result<usize> {
+		self.traverse(key).await
+	}
+
+	async fn scan_page_u32(&self, start: PageStart<u32>, limit: usize) -> Result<Option<u32>> {
+		match self.scan_page(start.map(|k| RecordIdKey::from(k as i64)), limit).await? {
+			Some(BenchValue::Int(id)) => Ok(Some(id as u32)),
+			None => Ok(None),
+			Some(id) => bail!("Unexpected id type `{id:?}`"),
+		}
+	}
+
+	async fn scan_page_string(
+		&self,
+		start: PageStart<String>,
+		limit: usize,
+	) -> Result<Option<String>> {
+		match self.scan_page(start.map(RecordIdKey::from), limit).await? {
+			Some(BenchValue::String(id)) => Ok(Some(id)),
+			None => Ok(None),
+			Some(id) => bail!("Unexpected id type `{id:?}`"),
+		}
+	}
+
 	async fn update_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.update(key as i64, val).await
 	}
@@ -442,6 +872,14 @@ impl BenchmarkClient for SurrealDBClient {
 		self.update(key, val).await
 	}
 
+	async fn update_path_u32(&self, key: u32, path: &str, val: BenchValue) -> Result<()> {
+		self.update_path(key as i64, path, val).await
+	}
+
+	async fn update_path_string(&self, key: String, path: &str, val: BenchValue) -> Result<()> {
+		self.update_path(key, path, val).await
+	}
+
 	async fn delete_u32(&self, key: u32) -> Result<()> {
 		self.delete(key as i64).await
 	}
@@ -450,6 +888,20 @@ impl BenchmarkClient for SurrealDBClient {
 		self.delete(key).await
 	}
 
+	async fn delete_range_u32(&self, from: u32, to: u32) -> Result<()> {
+		self.delete_range(from as i64, to as i64).await
+	}
+
+	async fn delete_range_string(&self, from: String, to: String) -> Result<()> {
+		self.delete_range(from, to).await
+	}
+
+	async fn truncate(&self) -> Result<()> {
+		let sql = "DELETE record RETURN NONE";
+		self.db.query(sql).await.map_err(log_sql_err(sql))?.check().map_err(log_sql_err(sql))?;
+		Ok(())
+	}
+
 	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
 		// Get the unique flag
 		let unique = if spec.unique.unwrap_or(false) {
@@ -458,6 +910,10 @@ impl BenchmarkClient for SurrealDBClient {
 			""
 		}
 		.to_string();
+		// SurrealDB indexes always cover every record of the table
+		if spec.filter.is_some() {
+			bail!(BenchError::Unsupported);
+		}
 		// Get the fields
 		let fields = spec.fields.join(", ");
 		// Check if an index type is specified
@@ -478,6 +934,8 @@ impl BenchmarkClient for SurrealDBClient {
 					"DEFINE INDEX {name} ON TABLE record FIELDS {fields} FULLTEXT ANALYZER {name} BM25 CONCURRENTLY"
 				)
 			}
+			// Postgres access methods have no SurrealDB equivalent
+			Some(_) if spec.method().is_some() => bail!(BenchError::Unsupported),
 			_ => {
 				format!("DEFINE INDEX {name} ON TABLE record FIELDS {fields} {unique} CONCURRENTLY")
 			}
@@ -641,6 +1099,133 @@ impl BenchmarkClient for SurrealDBClient {
 	async fn batch_delete_string(&self, keys: impl Iterator<Item = String> + Send) -> Result<()> {
 		self.batch_delete(keys).await
 	}
+
+	async fn append_time_series(&self, row: TimeSeriesRow) -> Result<()> {
+		let content = bench_to_surreal_value(BenchValue::Object(vec![
+			("ts".into(), BenchValue::DateTime(row.ts)),
+			("sensor".into(), BenchValue::String(row.sensor)),
+			("value".into(), BenchValue::Float(row.value)),
+		]));
+		let sql = format!("CREATE {TIME_SERIES_TABLE} CONTENT $content RETURN NONE");
+		self.db
+			.query(&sql)
+			.bind(("content", content))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.check()
+			.map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn query_time_series(&self, window: TimeWindow) -> Result<usize> {
+		let sql = match window.bucket {
+			None => format!(
+				"SELECT ts, sensor, value FROM {TIME_SERIES_TABLE} WHERE ts >= $from AND ts < $to"
+			),
+			// Flooring a datetime aligns the buckets to the epoch
+			Some(bucket) => format!(
+				"SELECT time::floor(ts, {bucket}s) AS bucket, math::mean(value) AS avg, \
+				math::min(value) AS min, math::max(value) AS max FROM {TIME_SERIES_TABLE} \
+				WHERE ts >= $from AND ts < $to GROUP BY bucket"
+			),
+		};
+		let res: surrealdb::types::Value = self
+			.db
+			.query(&sql)
+			.bind(("from", Value::Datetime(Datetime::from(window.from))))
+			.bind(("to", Value::Datetime(Datetime::from(window.to))))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.take(0)
+			.map_err(log_sql_err(&sql))?;
+		// Check the response
+		let Some(arr) = res.as_array() else {
+			panic!("Unexpected response type");
+		};
+		Ok(black_box(arr).len())
+	}
+
+	async fn append_replicated(&self, id: u32, sent: u64) -> Result<()> {
+		let sql = "CREATE $id SET sent = $sent RETURN NONE";
+		self.db
+			.query(sql)
+			.bind(("id", Value::RecordId(RecordId::new(REPLICATED_TABLE, id as i64))))
+			.bind(("sent", Value::Number(Number::Int(sent as i64))))
+			.await
+			.map_err(log_sql_err(sql))?
+			.check()
+			.map_err(log_sql_err(sql))?;
+		Ok(())
+	}
+
+	async fn append_live_event(&self, seq: u32, sent: u64) -> Result<()> {
+		let sql = format!("CREATE {LIVE_TABLE} SET seq = $seq, sent = $sent RETURN NONE");
+		self.db
+			.query(&sql)
+			.bind(("seq", Value::Number(Number::Int(seq as i64))))
+			.bind(("sent", Value::Number(Number::Int(sent as i64))))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.check()
+			.map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn batch_create_generated(&self, vals: Vec<BenchValue>) -> Result<Vec<GeneratedKey>> {
+		// Construct the records, leaving the ids to the datastore
+		let rows: Vec<Value> = vals.into_iter().map(bench_to_surreal_value).collect();
+		let rows_len = rows.len();
+		// Construct the SQL query, returning the generated ids
+		let sql = format!("INSERT INTO {GENERATED_TABLE} $rows RETURN VALUE record::id(id)");
+		// Execute the SQL query
+		let res: surrealdb::types::Value = self
+			.db
+			.query(&sql)
+			.bind(("rows", Value::Array(Array::from(rows))))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.take(0)
+			.map_err(log_sql_err(&sql))?;
+		// Check the response
+		let Some(arr) = res.as_array() else {
+			panic!("Unexpected response type");
+		};
+		assert_eq!(arr.len(), rows_len);
+		arr.iter()
+			.map(|id| match id {
+				Value::String(id) => Ok(GeneratedKey::String(id.clone())),
+				id => Err(anyhow!("Unexpected generated key {id:?}")),
+			})
+			.collect()
+	}
+
+	async fn batch_read_generated(&self, keys: Vec<GeneratedKey>) -> Result<()> {
+		// Construct the Record IDs
+		let ids = keys
+			.into_iter()
+			.map(|k| match k {
+				GeneratedKey::String(id) => Ok(Value::RecordId(RecordId::new(GENERATED_TABLE, id))),
+				k => Err(anyhow!("Unexpected generated key {k:?}")),
+			})
+			.collect::<Result<Vec<Value>>>()?;
+		// Store the total Record IDs
+		let ids_len = ids.len();
+		// Construct the SQL query
+		let sql = "SELECT * FROM $ids";
+		// Execute the SQL query
+		let res: surrealdb::types::Value = self
+			.db
+			.query(sql)
+			.bind(("ids", Value::Array(Array::from(ids))))
+			.await
+			.map_err(log_sql_err(sql))?
+			.take(0)
+			.map_err(log_sql_err(sql))?;
+		// Check the response
+		assert_eq!(consume_records(res)?, ids_len);
+		// All ok
+		Ok(())
+	}
 }
 
 impl SurrealDBClient {
@@ -665,6 +1250,47 @@ impl SurrealDBClient {
 		Ok(())
 	}
 
+	async fn scan_page(
+		&self,
+		start: PageStart<RecordIdKey>,
+		limit: usize,
+	) -> Result<Option<BenchValue>> {
+		// Return the key of each record alongside it, to start the next keyset page after
+		let (sql, after) = match start {
+			PageStart::Offset(s) => (
+				format!(
+					"SELECT *, record::id(id) AS key FROM record ORDER BY id LIMIT {limit} START {s}"
+				),
+				Value::None,
+			),
+			PageStart::After(None) => (
+				format!("SELECT *, record::id(id) AS key FROM record ORDER BY id LIMIT {limit}"),
+				Value::None,
+			),
+			PageStart::After(Some(key)) => (
+				format!(
+					"SELECT *, record::id(id) AS key FROM record WHERE id > $after ORDER BY id LIMIT {limit}"
+				),
+				Value::RecordId(RecordId::new(TABLE, key)),
+			),
+		};
+		let res: surrealdb::types::Value = self
+			.db
+			.query(&sql)
+			.bind(("after", after))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.take(0)
+			.map_err(log_sql_err(&sql))?;
+		// Check the response
+		let Some(arr) = res.as_array() else {
+			panic!("Unexpected response type");
+		};
+		let last = arr.iter().last().cloned().map(surreal_to_bench_value);
+		consume_records(res)?;
+		Ok(last.and_then(|row| row.get_field("key").cloned()))
+	}
+
 	async fn read<T>(&self, key: T) -> Result<Row>
 	where
 		T: Into<RecordIdKey>,
@@ -674,6 +1300,62 @@ impl SurrealDBClient {
 		Ok(black_box(Row(v)))
 	}
 
+	async fn read_server_time<T>(&self, key: T) -> Result<Duration>
+	where
+		T: Into<RecordIdKey>,
+	{
+		// Time the select inside the engine, so only the statement execution is measured
+		let sql =
+			"LET $t = time::now(); SELECT * FROM $id; RETURN duration::micros(time::now() - $t);";
+		let res = self
+			.db
+			.query(sql)
+			.bind(("id", Value::RecordId(RecordId::new(TABLE, key))))
+			.await
+			.map_err(log_sql_err(sql))?
+			.take::<surrealdb::types::Value>(2)
+			.map_err(log_sql_err(sql))?;
+		match res {
+			Value::Number(Number::Int(micros)) => Ok(Duration::from_micros(micros as u64)),
+			v => bail!("Unexpected server timing result: {v:?}"),
+		}
+	}
+
+	async fn relate<T>(&self, key: T, targets: impl Iterator<Item = T>) -> Result<()>
+	where
+		T: Into<RecordIdKey>,
+	{
+		// A single statement creates an edge to every target
+		let sql = format!("RELATE $from->{EDGE_TABLE}->$to RETURN NONE");
+		let to: Vec<Value> = targets.map(|t| Value::RecordId(RecordId::new(TABLE, t))).collect();
+		self.db
+			.query(&sql)
+			.bind(("from", Value::RecordId(RecordId::new(TABLE, key))))
+			.bind(("to", Value::Array(Array::from(to))))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.check()
+			.map_err(log_sql_err(&sql))?;
+		Ok(())
+	}
+
+	async fn traverse<T>(&self, key: T) -> Result<usize>
+	where
+		T: Into<RecordIdKey>,
+	{
+		// Follow the outgoing edges, and fetch the records at their ends
+		let sql = format!("SELECT * FROM $from->{EDGE_TABLE}->{TABLE}");
+		let res: surrealdb::types::Value = self
+			.db
+			.query(&sql)
+			.bind(("from", Value::RecordId(RecordId::new(TABLE, key))))
+			.await
+			.map_err(log_sql_err(&sql))?
+			.take(0)
+			.map_err(log_sql_err(&sql))?;
+		consume_records(res)
+	}
+
 	async fn update<T>(&self, key: T, val: BenchValue) -> Result<()>
 	where
 		T: Into<RecordIdKey>,
@@ -695,6 +1377,27 @@ impl SurrealDBClient {
 		Ok(())
 	}
 
+	async fn update_path<T>(&self, key: T, path: &str, val: BenchValue) -> Result<()>
+	where
+		T: Into<RecordIdKey>,
+	{
+		let sql = format!("UPDATE $id SET {path} = $content RETURN NULL");
+		let content = bench_to_surreal_value(val);
+		let res = self
+			.db
+			.query(&sql)
+			.bind(Bindings {
+				id: Value::RecordId(RecordId::new(TABLE, key)),
+				content,
+			})
+			.await
+			.map_err(log_sql_err(&sql))?
+			.take::<surrealdb::types::Value>(0)
+			.map_err(log_sql_err(&sql))?;
+		assert!(!res.is_none());
+		Ok(())
+	}
+
 	async fn delete<T>(&self, key: T) -> Result<()>
 	where
 		T: Into<RecordIdKey>,
@@ -712,6 +1415,22 @@ impl SurrealDBClient {
 		Ok(())
 	}
 
+	async fn delete_range<T>(&self, from: T, to: T) -> Result<()>
+	where
+		T: Into<RecordIdKey>,
+	{
+		let sql = "DELETE record WHERE id >= $from AND id < $to RETURN NONE";
+		self.db
+			.query(sql)
+			.bind(("from", Value::RecordId(RecordId::new(TABLE, from))))
+			.bind(("to", Value::RecordId(RecordId::new(TABLE, to))))
+			.await
+			.map_err(log_sql_err(sql))?
+			.check()
+			.map_err(log_sql_err(sql))?;
+		Ok(())
+	}
+
 	async fn scan(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
 		// SurrealDB requires a full-text index to use the @@ operator
 		if ctx == ScanContext::WithoutIndex
@@ -719,18 +1438,19 @@ impl SurrealDBClient {
 			&& let Some(kind) = &index.index_type
 			&& kind == "fulltext"
 		{
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		// Extract parameters
 		let s = scan.start.map(|s| format!("START {s}")).unwrap_or_default();
 		let l = scan.limit.map(|s| format!("LIMIT {s}")).unwrap_or_default();
 		let c = SurrealDBDialect::filter_clause(scan)?;
 		let o = SurrealDBDialect::order_by_clause(scan)?;
+		let h = SurrealDBDialect::hint_clause(scan, ctx)?;
 		let p = scan.projection()?;
 		// Perform the relevant projection scan type
 		match p {
 			Projection::Id => {
-				let sql = format!("SELECT id FROM record {c} {o} {s} {l}");
+				let sql = format!("SELECT id FROM record {h} {c} {o} {s} {l}");
 				let res: surrealdb::types::Value = self
 					.db
 					.query(&sql)
@@ -744,7 +1464,33 @@ impl SurrealDBClient {
 				Ok(arr.len())
 			}
 			Projection::Full => {
-				let sql = format!("SELECT * FROM record {c} {o} {s} {l}");
+				let sql = format!("SELECT * FROM record {h} {c} {o} {s} {l}");
+				let res: surrealdb::types::Value = self
+					.db
+					.query(&sql)
+					.await
+					.map_err(log_sql_err(&sql))?
+					.take(0)
+					.map_err(log_sql_err(&sql))?;
+				consume_records(res)
+			}
+			Projection::Field(path) => {
+				let sql = format!("SELECT id, {path} FROM record {h} {c} {o} {s} {l}");
+				let res: surrealdb::types::Value = self
+					.db
+					.query(&sql)
+					.await
+					.map_err(log_sql_err(&sql))?
+					.take(0)
+					.map_err(log_sql_err(&sql))?;
+				let Some(arr) = res.as_array() else {
+					panic!("Unexpected response type");
+				};
+				Ok(arr.len())
+			}
+			Projection::Fields(paths) => {
+				let f = paths.join(", ");
+				let sql = format!("SELECT id, {f} FROM record {h} {c} {o} {s} {l}");
 				let res: surrealdb::types::Value = self
 					.db
 					.query(&sql)
@@ -758,10 +1504,10 @@ impl SurrealDBClient {
 				Ok(arr.len())
 			}
 			Projection::Count => {
-				let sql = if s.is_empty() && l.is_empty() {
+				let sql = if s.is_empty() && l.is_empty() && h.is_empty() {
 					format!("SELECT count() FROM record {c} GROUP ALL")
 				} else {
-					format!("SELECT count() FROM (SELECT 1 FROM record {c} {s} {l}) GROUP ALL")
+					format!("SELECT count() FROM (SELECT 1 FROM record {h} {c} {s} {l}) GROUP ALL")
 				};
 				let res: Option<usize> = self
 					.db
@@ -827,10 +1573,7 @@ impl SurrealDBClient {
 			.take(0)
 			.map_err(log_sql_err(sql))?;
 		// Check the response
-		let Some(arr) = res.as_array() else {
-			panic!("Unexpected response type");
-		};
-		assert_eq!(arr.len(), ids_len);
+		assert_eq!(consume_records(res)?, ids_len);
 		// All ok
 		Ok(())
 	}
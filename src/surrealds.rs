@@ -30,7 +30,10 @@
 //! across all configured SurrealDB instances.
 
 use crate::engine::BenchmarkEngine;
-use crate::surrealdb::{SurrealDBClient, initialise_db, surrealdb_password, surrealdb_username};
+use crate::surrealdb::{
+	SurrealDBClient, initialise_db, initialise_restricted_db, surrealdb_password,
+	surrealdb_username,
+};
 use crate::valueprovider::Columns;
 use crate::{Benchmark, KeyType};
 use anyhow::{Result, bail};
@@ -125,12 +128,14 @@ impl BenchmarkEngine<SurrealDBClient> for SurrealDBClientsProvider {
 		// Get the custom endpoint configuration from options, or use the default
 		// three-instance local setup if no endpoint is specified
 		let endpoint = options.endpoint.as_deref().unwrap_or(DEFAULT);
+		// TLS is chosen by the `wss://` and `https://` schemes of the endpoints
+		options.security.without_tls_options("SurrealDB")?;
 
 		// Define root user authentication credentials from environment variables or use defaults
 		// All configured SurrealDB instances must accept these credentials
 		let root = Root {
-			username: surrealdb_username(),
-			password: surrealdb_password(),
+			username: options.security.username(&surrealdb_username()),
+			password: options.security.password(&surrealdb_password()),
 		};
 
 		// Parse and validate endpoints from the semicolon-separated string
@@ -210,4 +215,15 @@ impl BenchmarkEngine<SurrealDBClient> for SurrealDBClientsProvider {
 		// Wrap the connected client in a SurrealDBClient for the benchmark interface
 		Ok(SurrealDBClient::new(client))
 	}
+
+	/// Returns a `SurrealDBClient` signed in as the restricted record user, using
+	/// the same round-robin endpoint selection as [`Self::create_client`].
+	async fn create_restricted_client(&self) -> Result<SurrealDBClient> {
+		// Select the next endpoint in the rotation
+		let next = self.round_robin.fetch_add(1, Ordering::Relaxed);
+		let endpoint = &self.endpoints[next % self.endpoints.len()];
+		// Connect and sign in as the restricted record user
+		let client = initialise_restricted_db(endpoint).await?;
+		Ok(SurrealDBClient::new(client))
+	}
 }
@@ -1,16 +1,18 @@
 #![cfg(feature = "surrealkv")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
 use crate::memory::Config;
+use crate::util::kvindex::{KvIndexes, Matches};
+use crate::util::{crate_version, dir_size};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
-use crate::{Benchmark, KeyType, Projection, Scan};
+use crate::{Benchmark, Index, KeyType, Projection, Scan};
 use anyhow::{Result, bail};
 use std::hint::black_box;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
 use surrealkv::Durability;
 use surrealkv::LSMIterator;
 use surrealkv::Mode::{ReadOnly, ReadWrite};
@@ -21,6 +23,9 @@ const DATABASE_DIR: &str = "surrealkv";
 
 const BLOCK_SIZE: usize = 64 * 1024;
 
+/// Index entries are keyed after every record, outside the range the scans iterate
+const INDEX_TAG: &[u8] = &[255];
+
 /// Calculate SurrealKV specific memory allocation
 fn calculate_surrealkv_memory() -> u64 {
 	// Load the system memory
@@ -31,18 +36,17 @@ fn calculate_surrealkv_memory() -> u64 {
 
 pub(crate) struct SurrealKVClientProvider {
 	store: Arc<Tree>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
 impl BenchmarkEngine<SurrealKVClient> for SurrealKVClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
-		// Cleanup the data directory
-		std::fs::remove_dir_all(DATABASE_DIR).ok();
+		// Cleanup the data directory, unless the dataset of an earlier run is reused
+		if !options.no_setup {
+			std::fs::remove_dir_all(DATABASE_DIR).ok();
+		}
 		// Calculate memory allocation
 		let block_cache_bytes = calculate_surrealkv_memory();
 		// Configure custom options
@@ -64,6 +68,7 @@ impl BenchmarkEngine<SurrealKVClient> for SurrealKVClientProvider {
 		// Create the store
 		Ok(Self {
 			store: Arc::new(store),
+			indexes: Arc::new(KvIndexes::new(options.emulate_indexes, INDEX_TAG)),
 			sync: options.sync,
 		})
 	}
@@ -71,6 +76,7 @@ impl BenchmarkEngine<SurrealKVClient> for SurrealKVClientProvider {
 	async fn create_client(&self) -> Result<SurrealKVClient> {
 		Ok(SurrealKVClient {
 			db: self.store.clone(),
+			indexes: self.indexes.clone(),
 			sync: self.sync,
 		})
 	}
@@ -78,6 +84,7 @@ impl BenchmarkEngine<SurrealKVClient> for SurrealKVClientProvider {
 
 pub(crate) struct SurrealKVClient {
 	db: Arc<Tree>,
+	indexes: Arc<KvIndexes>,
 	sync: bool,
 }
 
@@ -85,6 +92,10 @@ impl BenchmarkClient for SurrealKVClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("surrealkv")
+	}
+
 	async fn shutdown(&self) -> Result<()> {
 		// Close the database
 		self.db.close().await?;
@@ -94,6 +105,18 @@ impl BenchmarkClient for SurrealKVClient {
 		Ok(())
 	}
 
+	async fn compact(&self) -> Result<()> {
+		// The public API exposes no compaction, so only
+		// sync the write-ahead log of every committed write
+		self.db.flush_wal(true)?;
+		// Ok
+		Ok(())
+	}
+
+	fn disk_size(&self) -> Option<u64> {
+		dir_size(DATABASE_DIR)
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create_bytes(&key.to_ne_bytes(), val).await
 	}
@@ -126,12 +149,76 @@ impl BenchmarkClient for SurrealKVClient {
 		self.delete_bytes(&key.into_bytes()).await
 	}
 
-	async fn scan_u32(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_u32(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
 	}
 
-	async fn scan_string(&self, scan: &Scan, _ctx: ScanContext) -> Result<usize> {
-		self.scan_bytes(scan).await
+	async fn scan_string(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		self.scan_bytes(scan, ctx).await
+	}
+
+	async fn build_index(&self, spec: &Index, name: &str) -> Result<()> {
+		// Writes maintain the index from now on
+		let index = self.indexes.create(spec, name)?;
+		// Collect the entries of the existing records
+		let mut entries = Vec::new();
+		{
+			let txn = self.db.begin_with_mode(ReadOnly)?;
+			let mut iter = txn.range([0u8].as_slice(), [255u8].as_slice())?;
+			iter.seek_first()?;
+			while iter.valid() {
+				let record = BenchValue::decode(iter.value()?.as_ref())?;
+				if let Some(entry) = index.entry(iter.key().user_key(), &record) {
+					entries.push(entry);
+				}
+				iter.next()?;
+			}
+		}
+		// Create a new transaction
+		let mut txn = self.db.begin_with_mode(ReadWrite)?;
+		// Set the transaction durability
+		txn.set_durability(if self.sync {
+			Durability::Immediate
+		} else {
+			Durability::Eventual
+		});
+		// Write the entries
+		for entry in entries {
+			txn.set(&entry, &[])?;
+		}
+		txn.commit().await?;
+		Ok(())
+	}
+
+	async fn drop_index(&self, name: &str) -> Result<()> {
+		// Writes stop maintaining the index
+		let index = self.indexes.remove(name)?;
+		// Collect the entries of the index
+		let mut entries = Vec::new();
+		{
+			let txn = self.db.begin_with_mode(ReadOnly)?;
+			let end = index.end();
+			let mut iter = txn.range(index.start(), end.as_slice())?;
+			iter.seek_first()?;
+			while iter.valid() {
+				entries.push(iter.key().user_key().to_vec());
+				iter.next()?;
+			}
+		}
+		// Create a new transaction
+		let mut txn = self.db.begin_with_mode(ReadWrite)?;
+		// Set the transaction durability
+		txn.set_durability(if self.sync {
+			Durability::Immediate
+		} else {
+			Durability::Eventual
+		});
+		// Delete the entries
+		for entry in entries {
+			txn.delete(&entry)?;
+		}
+		txn.commit().await?;
+		Ok(())
 	}
 
 	async fn batch_create_u32(
@@ -203,6 +290,8 @@ impl SurrealKVClient {
 	async fn create_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
 		// Serialise the value
 		let val = val.encode()?;
+		// Derive any index entries
+		let entries = self.indexes.entries(key, &val)?;
 		// Create a new transaction
 		let mut txn = self.db.begin_with_mode(ReadWrite)?;
 		// Set the transaction durability
@@ -213,6 +302,9 @@ impl SurrealKVClient {
 		});
 		// Process the data
 		txn.set(key, &val)?;
+		for entry in entries {
+			txn.set(&entry, &[])?;
+		}
 		txn.commit().await?;
 		Ok(())
 	}
@@ -233,6 +325,8 @@ impl SurrealKVClient {
 	async fn update_bytes(&self, key: &[u8], val: BenchValue) -> Result<()> {
 		// Serialise the value
 		let val = val.encode()?;
+		// Derive any index entries
+		let entries = self.indexes.entries(key, &val)?;
 		// Create a new transaction
 		let mut txn = self.db.begin_with_mode(ReadWrite)?;
 		// Set the transaction durability
@@ -243,6 +337,9 @@ impl SurrealKVClient {
 		});
 		// Process the data
 		txn.set(key, &val)?;
+		for entry in entries {
+			txn.set(&entry, &[])?;
+		}
 		txn.commit().await?;
 		Ok(())
 	}
@@ -277,6 +374,9 @@ impl SurrealKVClient {
 		// Process the data
 		for result in key_vals {
 			let (key, val) = result?;
+			for entry in self.indexes.entries(&key, &val)? {
+				txn.set(&entry, &[])?;
+			}
 			txn.set(&key, &val)?;
 		}
 		// Commit the batch
@@ -293,10 +393,8 @@ impl SurrealKVClient {
 			let res = txn.get(&key)?;
 			// Check the value exists
 			assert!(res.is_some());
-			// Deserialise the value
-			let val = BenchValue::decode(res.unwrap().as_ref())?;
-			// Use the value
-			black_box(val);
+			// Materialise the value
+			materialize::consume(res.unwrap(), |v| BenchValue::decode(v.as_ref()))?;
 		}
 		// All ok
 		Ok(())
@@ -317,6 +415,9 @@ impl SurrealKVClient {
 		// Process the data
 		for result in key_vals {
 			let (key, val) = result?;
+			for entry in self.indexes.entries(&key, &val)? {
+				txn.set(&entry, &[])?;
+			}
 			txn.set(&key, &val)?;
 		}
 		// Commit the batch
@@ -342,10 +443,10 @@ impl SurrealKVClient {
 		Ok(())
 	}
 
-	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
-		// Contional scans are not supported
+	async fn scan_bytes(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Conditional scans filter the records
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			return self.scan_filtered(scan, ctx).await;
 		}
 		// Extract parameters
 		let s = scan.start.unwrap_or(0);
@@ -380,7 +481,7 @@ impl SurrealKVClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// Create a cursor-based iterator over the key range
 				let mut iter = txn.range(beg, end)?;
 				iter.seek_first()?;
@@ -397,14 +498,14 @@ impl SurrealKVClient {
 				// out by the compiler when calling `count` at the end.
 				let mut count = 0;
 				while iter.valid() && count < l {
-					black_box(iter.value()?);
+					materialize::consume(iter.value()?, |v| BenchValue::decode(&v))?;
 					count += 1;
 					iter.next()?;
 				}
 				Ok(count)
 			}
 			Projection::Count => match scan.limit {
-				Some(_) => bail!(NOT_SUPPORTED_ERROR),
+				Some(_) => bail!(BenchError::Unsupported),
 				None => {
 					// Iterate over all entries to count them
 					let mut iter = txn.range(beg, end)?;
@@ -419,4 +520,46 @@ impl SurrealKVClient {
 			},
 		}
 	}
+
+	async fn scan_filtered(&self, scan: &Scan, ctx: ScanContext) -> Result<usize> {
+		// Only conditions on a single integer field are supported
+		let filter = self.indexes.filter(scan, ctx)?;
+		let mut matches = Matches::new(scan)?;
+		// Create a new transaction
+		let txn = self.db.begin_with_mode(ReadOnly)?;
+		match self.indexes.find(&filter, ctx) {
+			// Look up the records of each range of index entries
+			Some(index) => {
+				'ranges: for range in filter.ranges() {
+					let (beg, end) = index.range(*range);
+					let mut iter = txn.range(beg.as_slice(), end.as_slice())?;
+					iter.seek_first()?;
+					while iter.valid() {
+						let (value, key) = index.split(iter.key().user_key());
+						// Skip the entries left behind by updates and deletes
+						if let Some(val) = txn.get(key)? {
+							let record = BenchValue::decode(val.as_ref())?;
+							if index.holds(value, &record) && !matches.push(key, record) {
+								break 'ranges;
+							}
+						}
+						iter.next()?;
+					}
+				}
+			}
+			// Decode and filter every record
+			None => {
+				let mut iter = txn.range([0u8].as_slice(), [255u8].as_slice())?;
+				iter.seek_first()?;
+				while iter.valid() {
+					let record = BenchValue::decode(iter.value()?.as_ref())?;
+					if filter.matches(&record) && !matches.push(iter.key().user_key(), record) {
+						break;
+					}
+					iter.next()?;
+				}
+			}
+		}
+		Ok(matches.count())
+	}
 }
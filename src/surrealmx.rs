@@ -1,14 +1,15 @@
 #![cfg(feature = "surrealmx")]
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, BenchmarkEngine, ScanContext};
+use crate::error::BenchError;
+use crate::materialize;
+use crate::util::{crate_version, dir_size};
 use crate::value::BenchValue;
 use crate::valueprovider::Columns;
 use crate::{Benchmark, KeyType, Projection, Scan};
 use anyhow::{Result, bail};
 use std::hint::black_box;
 use std::sync::Arc;
-use std::time::Duration;
 use surrealmx::Database;
 use surrealmx::{AolMode, FsyncMode, SnapshotMode};
 use surrealmx::{DatabaseOptions, PersistenceOptions};
@@ -18,10 +19,6 @@ const DATABASE_DIR: &str = "surrealmx";
 pub(crate) struct SurrealMXClientProvider(Arc<Database>);
 
 impl BenchmarkEngine<SurrealMXClient> for SurrealMXClientProvider {
-	/// The number of seconds to wait before connecting
-	fn wait_timeout(&self) -> Option<Duration> {
-		None
-	}
 	/// Initiates a new datastore benchmarking engine
 	async fn setup(_: KeyType, _columns: Columns, options: &Benchmark) -> Result<Self> {
 		// Check if persistence is enabled
@@ -63,6 +60,24 @@ impl BenchmarkClient for SurrealMXClient {
 	// The return type when reading a row
 	type ReadRow = BenchValue;
 
+	async fn version(&self) -> Result<String> {
+		crate_version("surrealmx")
+	}
+
+	async fn compact(&self) -> Result<()> {
+		// Remove the versions which no transaction can read any more
+		self.db.run_gc();
+		// Remove the keys whose latest version is a delete
+		self.db.run_cleanup();
+		// Ok
+		Ok(())
+	}
+
+	fn disk_size(&self) -> Option<u64> {
+		// Only the append-only log is on disk, when persisted
+		dir_size(DATABASE_DIR)
+	}
+
 	async fn create_u32(&self, key: u32, val: BenchValue) -> Result<()> {
 		self.create_bytes(&key.to_ne_bytes(), val).await
 	}
@@ -238,10 +253,8 @@ impl SurrealMXClient {
 			let res = txn.get(key)?;
 			// Check the value exists
 			assert!(res.is_some());
-			// Deserialise the value
-			let val = BenchValue::decode(res.unwrap().as_ref())?;
-			// Use the value
-			black_box(val);
+			// Materialise the value
+			materialize::consume(res.unwrap(), |v| BenchValue::decode(v.as_ref()))?;
 		}
 		// All ok
 		Ok(())
@@ -278,7 +291,7 @@ impl SurrealMXClient {
 	async fn scan_bytes(&self, scan: &Scan) -> Result<usize> {
 		// Contional scans are not supported
 		if scan.condition.is_some() {
-			bail!(NOT_SUPPORTED_ERROR);
+			bail!(BenchError::Unsupported);
 		}
 		// Extract parameters
 		let p = scan.projection()?;
@@ -304,7 +317,7 @@ impl SurrealMXClient {
 				}
 				Ok(count)
 			}
-			Projection::Full => {
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
 				// Scan the desired range of keys
 				let iter = txn.scan(beg..end, scan.start, scan.limit)?;
 				// Create an iterator starting at the beginning
@@ -315,7 +328,7 @@ impl SurrealMXClient {
 				// out by the compiler when calling `count` at the end.
 				let mut count = 0;
 				for v in iter {
-					black_box(v.1);
+					materialize::consume(v.1, |v| BenchValue::decode(&v))?;
 					count += 1;
 				}
 				Ok(count)
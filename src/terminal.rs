@@ -116,6 +116,15 @@ impl BenchUi {
 		}
 	}
 
+	/// Prominent warning line (bold yellow when colour is on).
+	pub(crate) fn println_warning(self, line: &str) {
+		if self.use_color {
+			println!("\x1b[1;33m⚠ {line}\x1b[0m");
+		} else {
+			println!("WARNING: {line}");
+		}
+	}
+
 	/// Progress bar for `total` global iterations; message is a short label.
 	pub(crate) fn progress_bar(self, total: u64, short_label: &str) -> Option<Arc<ProgressBar>> {
 		if !self.use_progress_bar() || total == 0 {
@@ -0,0 +1,43 @@
+#![cfg(feature = "tidb")]
+
+use crate::dialect::MySqlDialect;
+use crate::docker::DockerParams;
+use crate::mysql_common::{Flavour, MysqlCommonClientProvider};
+use crate::valueprovider::Columns;
+use crate::{Benchmark, Index};
+
+pub const DEFAULT: &str = "mysql://root@127.0.0.1:4000/test";
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	// A single tidb-server runs on its embedded unistore storage
+	// engine, standing in for a TiKV cluster, so there is no
+	// separate placement driver or storage node to start
+	let mut post_args = vec!["--store=unistore".to_string(), "--path=/var/lib/tidb".to_string()];
+	if options.optimised {
+		// Allow as many concurrently executing sessions as
+		// connections, and keep the log out of the hot path
+		post_args.push("--token-limit=1024".to_string());
+		post_args.push("-L=error".to_string());
+	}
+	// Return Docker parameters
+	DockerParams {
+		image: "pingcap/tidb",
+		pre_args: "--ulimit nofile=65536:65536 -p 127.0.0.1:4000:4000".to_string(),
+		post_args: post_args.join(" "),
+	}
+}
+
+/// TiDB, which speaks the MySQL protocol, and reports the first
+/// key generated by a multi-row insert, but has no full-text indexes.
+pub(crate) struct Tidb;
+
+impl Flavour for Tidb {
+	const DEFAULT: &'static str = DEFAULT;
+	const INSERT_RETURNING: bool = false;
+	const FULLTEXT_INDEXES: bool = false;
+	fn btree_index_key_list(columns: &Columns, spec: &Index) -> String {
+		MySqlDialect::btree_index_key_list(columns, spec)
+	}
+}
+
+pub(crate) type TidbClientProvider = MysqlCommonClientProvider<Tidb>;
@@ -0,0 +1,114 @@
+//! Per-phase operation timeouts derived from the latency of the first samples.
+//!
+//! Every phase starts with the `--operation-timeout` ceiling. Once its warmup samples have
+//! completed, the timeout tightens to `--timeout-multiplier` times their p99 latency, so a
+//! genuinely hung operation fails quickly, while slow but healthy operations keep a budget
+//! proportional to how long they actually take.
+
+use crate::util::format_duration;
+use hdrhistogram::Histogram;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Maximum number of samples observed before the timeout is derived.
+const WARMUP_SAMPLES: u32 = 100;
+
+/// Lower bound on a derived timeout, absorbing pauses (GC, compaction, fsync) between samples.
+const MINIMUM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout shared by all the workers of one benchmark phase.
+pub(crate) struct AdaptiveTimeout {
+	/// Fixed upper bound, and the timeout used during the warmup.
+	ceiling: Duration,
+	/// Multiple of the warmup p99 latency, or `None` when disabled.
+	multiplier: Option<u32>,
+	/// Number of samples observed before the timeout is derived.
+	warmup: u32,
+	/// Latencies of the warmup samples, in microseconds.
+	observed: Mutex<Histogram<u64>>,
+	/// The derived timeout, alongside the warmup p99 it was derived from.
+	derived: OnceLock<(Duration, Duration)>,
+}
+
+impl AdaptiveTimeout {
+	/// Creates the timeout for a phase of `samples` operations.
+	pub(crate) fn new(ceiling: Duration, multiplier: u32, samples: u32) -> Self {
+		Self {
+			ceiling,
+			multiplier: (multiplier > 0).then_some(multiplier),
+			// Leave the bulk of a short phase under the ceiling
+			warmup: (samples / 10).clamp(1, WARMUP_SAMPLES),
+			observed: Mutex::new(Histogram::new(3).expect("valid histogram precision")),
+			derived: OnceLock::new(),
+		}
+	}
+
+	/// The timeout for the next operation.
+	pub(crate) fn current(&self) -> Duration {
+		self.derived.get().map_or(self.ceiling, |(timeout, _)| *timeout)
+	}
+
+	/// Records a completed sample, deriving the timeout once the warmup is complete.
+	pub(crate) fn observe(&self, latency: Duration) {
+		let Some(multiplier) = self.multiplier else {
+			return;
+		};
+		if self.derived.get().is_some() {
+			return;
+		}
+		let mut observed = self.observed.lock().unwrap_or_else(|e| e.into_inner());
+		let _ = observed.record(latency.as_micros() as u64);
+		if observed.len() >= self.warmup as u64 {
+			let p99 = Duration::from_micros(observed.value_at_quantile(0.99));
+			// The ceiling wins when it is set below the minimum
+			let timeout = (p99 * multiplier).max(MINIMUM_TIMEOUT).min(self.ceiling);
+			let _ = self.derived.set((timeout, p99));
+		}
+	}
+
+	/// Describes a `limit` returned by [`Self::current`], for timeout errors.
+	pub(crate) fn describe(&self, limit: Duration) -> String {
+		match (self.derived.get(), self.multiplier) {
+			(Some((timeout, p99)), Some(multiplier)) if *timeout == limit => format!(
+				"{} ({multiplier}× the warmup p99 of {})",
+				format_duration(limit),
+				format_duration(*p99)
+			),
+			_ => format_duration(limit),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn timeout_tightens_after_warmup() {
+		let timeout = AdaptiveTimeout::new(Duration::from_secs(1800), 100, 50);
+		// A 50-sample phase warms up over its first 5 samples
+		for _ in 0..4 {
+			timeout.observe(Duration::from_secs(1));
+		}
+		assert_eq!(timeout.current(), Duration::from_secs(1800));
+		timeout.observe(Duration::from_secs(1));
+		let current = timeout.current();
+		assert!(current >= Duration::from_secs(99) && current <= Duration::from_secs(101));
+		// Later samples no longer move the timeout
+		timeout.observe(Duration::from_secs(60));
+		assert_eq!(timeout.current(), current);
+	}
+
+	#[test]
+	fn timeout_is_bounded_and_can_be_disabled() {
+		let fast = AdaptiveTimeout::new(Duration::from_secs(1800), 100, 10);
+		fast.observe(Duration::from_micros(50));
+		assert_eq!(fast.current(), MINIMUM_TIMEOUT);
+		let slow = AdaptiveTimeout::new(Duration::from_secs(600), 100, 10);
+		slow.observe(Duration::from_secs(30));
+		assert_eq!(slow.current(), Duration::from_secs(600));
+		let disabled = AdaptiveTimeout::new(Duration::from_secs(1800), 0, 10);
+		disabled.observe(Duration::from_micros(50));
+		assert_eq!(disabled.current(), Duration::from_secs(1800));
+	}
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "timescaledb")]
+
+use crate::docker::DockerParams;
+use crate::engine::{BenchmarkEngine, LiveStream};
+use crate::postgres::{PostgresClient, PostgresClientProvider};
+use crate::valueprovider::Columns;
+use crate::{Benchmark, KeyType};
+use anyhow::Result;
+
+pub const DEFAULT: &str = "host=127.0.0.1 user=postgres password=postgres";
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	// The TimescaleDB image preloads the extension into a
+	// stock Postgres server, so the Postgres tuning applies
+	DockerParams {
+		image: "timescale/timescaledb:latest-pg17",
+		..crate::postgres::docker(options)
+	}
+}
+
+/// TimescaleDB is a Postgres extension, so the Postgres client is
+/// reused, storing the time series in a hypertable partitioned by time.
+pub(crate) struct TimescaleDBClientProvider(PostgresClientProvider);
+
+impl BenchmarkEngine<PostgresClient> for TimescaleDBClientProvider {
+	/// Initiates a new datastore benchmarking engine
+	async fn setup(kt: KeyType, columns: Columns, options: &Benchmark) -> Result<Self> {
+		// Get the custom endpoint if specified
+		let url = options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned();
+		// Create the client provider
+		Ok(Self(
+			PostgresClientProvider::new(kt, columns, url, options.prepared)
+				.with_security(options.security.clone())
+				.with_hypertables(),
+		))
+	}
+	/// Checks the server runs queries
+	async fn ready(&self) -> Result<()> {
+		self.0.ready().await
+	}
+	/// Creates a new client for this benchmarking engine
+	async fn create_client(&self) -> Result<PostgresClient> {
+		self.0.create_client().await
+	}
+	/// Creates a client connected as the restricted role
+	async fn create_restricted_client(&self) -> Result<PostgresClient> {
+		self.0.create_restricted_client().await
+	}
+	/// Listens on the channel notified of every event
+	async fn subscribe(&self) -> Result<LiveStream> {
+		self.0.subscribe().await
+	}
+}
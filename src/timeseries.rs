@@ -0,0 +1,200 @@
+//! Time-series workload (`[time_series]` in the benchmark TOML).
+//!
+//! Appends timestamped rows, then queries them by time window, either returning the
+//! rows in the window or downsampling them into fixed-width buckets.
+
+use crate::keyprovider::{IntegerKeyProvider, UnorderedInteger};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The timestamp of the first appended row, 2024-01-01T00:00:00Z
+const START_SECS: i64 = 1_704_067_200;
+
+/// The number of sensors the appended rows are spread across
+const SENSORS: u32 = 16;
+
+/// Appended rows and timed queries over them, from the `[time_series]` table.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct TimeSeries {
+	/// Milliseconds between the timestamps of consecutive rows
+	#[serde(default = "default_interval")]
+	pub(crate) interval: u64,
+	/// Number of rows to append; when unset, uses `--samples`
+	pub(crate) rows: Option<u32>,
+	/// Queries timed after all rows are appended
+	#[serde(default)]
+	pub(crate) queries: Vec<TimeSeriesQuery>,
+}
+
+fn default_interval() -> u64 {
+	1000
+}
+
+/// One timed query over the appended rows.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct TimeSeriesQuery {
+	/// Name shown in the results
+	pub(crate) name: String,
+	/// Seconds of data covered by each queried window
+	pub(crate) window: u64,
+	/// Seconds per bucket when downsampling; omit to return the rows in the window
+	pub(crate) bucket: Option<u64>,
+	/// Number of windows to query; when unset, uses `--samples`
+	pub(crate) samples: Option<u32>,
+}
+
+/// A single appended row.
+#[derive(Debug, Clone)]
+pub(crate) struct TimeSeriesRow {
+	pub(crate) ts: DateTime<Utc>,
+	pub(crate) sensor: String,
+	pub(crate) value: f64,
+}
+
+/// The half-open window `[from, to)` of a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TimeWindow {
+	pub(crate) from: DateTime<Utc>,
+	pub(crate) to: DateTime<Utc>,
+	/// Seconds per bucket, aligned to the Unix epoch, when downsampling
+	pub(crate) bucket: Option<u64>,
+}
+
+impl TimeSeries {
+	/// The row appended as sample `n`.
+	pub(crate) fn row(&self, n: u32) -> TimeSeriesRow {
+		TimeSeriesRow {
+			ts: timestamp(self.offset_ms(n as u64)),
+			sensor: format!("sensor-{}", n % SENSORS),
+			value: (UnorderedInteger::default().key(n) % 10_000) as f64 / 100.0,
+		}
+	}
+
+	/// The window queried as sample `n`, out of `rows` appended rows.
+	///
+	/// Windows start on a multiple of the bucket, or of the window when
+	/// not downsampling, and are spread pseudo-randomly over the rows.
+	pub(crate) fn window(&self, query: &TimeSeriesQuery, rows: u32, n: u32) -> TimeWindow {
+		let window_ms = query.window * 1000;
+		let step_ms = query.bucket.unwrap_or(query.window).max(1) * 1000;
+		let span_ms = self.offset_ms(rows as u64);
+		let starts = span_ms.saturating_sub(window_ms) / step_ms + 1;
+		let from_ms = (UnorderedInteger::default().key(n) as u64 % starts) * step_ms;
+		TimeWindow {
+			from: timestamp(from_ms),
+			to: timestamp(from_ms + window_ms),
+			bucket: query.bucket,
+		}
+	}
+
+	/// The number of results a query over `window` returns, out of `rows` appended
+	/// rows: the rows within the window, or the buckets holding at least one row.
+	pub(crate) fn expected(&self, window: &TimeWindow, rows: u32) -> usize {
+		let from_ms = (window.from - timestamp(0)).num_milliseconds() as u64;
+		let to_ms = (window.to - timestamp(0)).num_milliseconds() as u64;
+		match window.bucket {
+			None => self.rows_between(from_ms, to_ms, rows) as usize,
+			Some(bucket) => {
+				// Buckets are aligned to the epoch, which the first row is aligned to
+				let bucket_ms = bucket.max(1) * 1000;
+				let base_ms = START_SECS as u64 * 1000;
+				let first = (base_ms + from_ms) / bucket_ms;
+				let last = (base_ms + to_ms).div_ceil(bucket_ms);
+				(first..last)
+					.filter(|b| {
+						let lo = (b * bucket_ms).max(base_ms + from_ms) - base_ms;
+						let hi = ((b + 1) * bucket_ms).min(base_ms + to_ms) - base_ms;
+						self.rows_between(lo, hi, rows) > 0
+					})
+					.count()
+			}
+		}
+	}
+
+	/// The number of rows with an offset in `[from_ms, to_ms)`.
+	fn rows_between(&self, from_ms: u64, to_ms: u64, rows: u32) -> u64 {
+		let interval = self.interval.max(1);
+		let lo = from_ms.div_ceil(interval).min(rows as u64);
+		let hi = to_ms.div_ceil(interval).min(rows as u64);
+		hi.saturating_sub(lo)
+	}
+
+	/// Milliseconds between the first row and row `n`.
+	fn offset_ms(&self, n: u64) -> u64 {
+		n * self.interval
+	}
+}
+
+/// The timestamp `offset_ms` milliseconds after the first row.
+fn timestamp(offset_ms: u64) -> DateTime<Utc> {
+	DateTime::from_timestamp(START_SECS, 0).unwrap_or_default()
+		+ TimeDelta::milliseconds(offset_ms as i64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn series(interval: u64) -> TimeSeries {
+		TimeSeries {
+			interval,
+			rows: None,
+			queries: vec![],
+		}
+	}
+
+	fn query(window: u64, bucket: Option<u64>) -> TimeSeriesQuery {
+		TimeSeriesQuery {
+			name: "q".to_string(),
+			window,
+			bucket,
+			samples: None,
+		}
+	}
+
+	#[test]
+	fn windows_stay_within_the_rows() {
+		let ts = series(1000);
+		let q = query(60, None);
+		for n in 0..100 {
+			let w = ts.window(&q, 3600, n);
+			assert_eq!(w.to - w.from, TimeDelta::seconds(60));
+			assert!(w.from >= ts.row(0).ts);
+			assert!(w.to <= ts.row(3599).ts + TimeDelta::seconds(1));
+			assert_eq!(ts.expected(&w, 3600), 60);
+		}
+	}
+
+	#[test]
+	fn expected_counts_rows_and_buckets() {
+		let ts = series(250);
+		// Four rows a second, over a one minute window
+		let w = ts.window(&query(60, None), 10_000, 0);
+		assert_eq!(ts.expected(&w, 10_000), 240);
+		// Downsampled into ten second buckets
+		let w = ts.window(&query(60, Some(10)), 10_000, 0);
+		assert_eq!(ts.expected(&w, 10_000), 6);
+		// A window longer than the rows only sees the appended rows
+		let w = ts.window(&query(3600, Some(60)), 400, 7);
+		assert_eq!(w.from, ts.row(0).ts);
+		assert_eq!(ts.expected(&w, 400), 2);
+		let rows = TimeWindow {
+			bucket: None,
+			..w
+		};
+		assert_eq!(ts.expected(&rows, 400), 400);
+	}
+
+	#[test]
+	fn sparse_rows_leave_buckets_empty() {
+		// One row every 25 seconds, so only four of the ten buckets hold a row
+		let ts = series(25_000);
+		let w = ts.window(&query(100, Some(10)), 1000, 0);
+		let rows = TimeWindow {
+			bucket: None,
+			..w
+		};
+		assert_eq!(ts.expected(&rows, 1000), 4);
+		assert_eq!(ts.expected(&w, 1000), 4);
+	}
+}
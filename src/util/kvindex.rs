@@ -0,0 +1,356 @@
+//! Secondary indexes on integer fields, emulated with composite keys for the embedded key-value
+//! datastores.
+//!
+//! With `--emulate-indexes`, scans whose generic `sql` condition compares a single integer field
+//! against constants (`=`, `<`, `<=`, `>`, `>=`, `AND`, `OR`, and `IN`) run against the key-value
+//! datastores. Without an index every record is decoded and filtered, and with one the records are
+//! found through the entries of the index. Each entry key holds the index name, the field value in
+//! an order-preserving encoding, and the record key, so every value range of a condition is one
+//! contiguous range of entries.
+//!
+//! Creates and updates add the entry of the new field value in the same transaction, but updates
+//! and deletes leave the entries of old values in place, so every entry is checked against the
+//! record it points to when read. Counts through an index therefore read the records too.
+
+use crate::engine::ScanContext;
+use crate::error::BenchError;
+use crate::value::BenchValue;
+use crate::{Index, Projection, Scan};
+use anyhow::{Result, bail};
+use std::hint::black_box;
+use std::sync::RwLock;
+
+/// A condition on one integer field, as the sorted, disjoint, inclusive ranges of values it matches.
+#[derive(Debug, PartialEq)]
+pub(crate) struct IntegerFilter {
+	field: String,
+	ranges: Vec<(i64, i64)>,
+}
+
+impl IntegerFilter {
+	/// Parses a generic SQL condition, or returns `None` when it is not on a single integer field
+	fn parse(sql: &str) -> Option<Self> {
+		// A list of values is a disjunction of single values
+		if let Some((field, list)) = sql.split_once(" IN ") {
+			let list = list.trim().strip_prefix('(')?.strip_suffix(')')?;
+			let ranges = list
+				.split(',')
+				.map(|v| v.trim().parse().ok().map(|v| (v, v)))
+				.collect::<Option<Vec<_>>>()?;
+			return Some(Self::new(identifier(field)?, ranges));
+		}
+		// Otherwise a disjunction of conjunctions of comparisons
+		let mut field = None;
+		let mut ranges = Vec::new();
+		for term in sql.split(" OR ") {
+			let (mut lo, mut hi) = (i64::MIN, i64::MAX);
+			for cmp in term.split(" AND ") {
+				let (f, op, v) = comparison(cmp)?;
+				if *field.get_or_insert_with(|| f.clone()) != f {
+					return None;
+				}
+				match op {
+					"=" => (lo, hi) = (lo.max(v), hi.min(v)),
+					">=" => lo = lo.max(v),
+					">" => lo = lo.max(v.checked_add(1)?),
+					"<=" => hi = hi.min(v),
+					"<" => hi = hi.min(v.checked_sub(1)?),
+					_ => return None,
+				}
+			}
+			ranges.push((lo, hi));
+		}
+		Some(Self::new(field?, ranges))
+	}
+
+	/// Sorts the ranges, dropping empty ones and merging those which overlap or touch
+	fn new(field: String, mut ranges: Vec<(i64, i64)>) -> Self {
+		ranges.retain(|(lo, hi)| lo <= hi);
+		ranges.sort_unstable();
+		let mut merged: Vec<(i64, i64)> = Vec::with_capacity(ranges.len());
+		for (lo, hi) in ranges {
+			match merged.last_mut() {
+				Some(last) if lo <= last.1.saturating_add(1) => last.1 = last.1.max(hi),
+				_ => merged.push((lo, hi)),
+			}
+		}
+		Self {
+			field,
+			ranges: merged,
+		}
+	}
+
+	/// The ranges of values the condition matches, in ascending order
+	pub(crate) fn ranges(&self) -> &[(i64, i64)] {
+		&self.ranges
+	}
+
+	/// Whether a record satisfies the condition
+	pub(crate) fn matches(&self, record: &BenchValue) -> bool {
+		match record.get_path(&self.field).and_then(integer) {
+			Some(v) => self.ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&v)),
+			None => false,
+		}
+	}
+}
+
+/// Splits a comparison into its field, operator, and integer constant
+fn comparison(sql: &str) -> Option<(String, &'static str, i64)> {
+	for op in [">=", "<=", "=", ">", "<"] {
+		if let Some((field, value)) = sql.split_once(op) {
+			return Some((identifier(field)?, op, value.trim().parse().ok()?));
+		}
+	}
+	None
+}
+
+/// A field path, made of identifiers separated by dots
+fn identifier(sql: &str) -> Option<String> {
+	let sql = sql.trim();
+	let valid = !sql.is_empty()
+		&& sql.split('.').all(|part| {
+			part.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+				&& part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+		});
+	valid.then(|| sql.to_string())
+}
+
+/// The value of an integer field, including whole floats
+fn integer(value: &BenchValue) -> Option<i64> {
+	match value {
+		BenchValue::Int(i) => Some(*i),
+		BenchValue::UInt(u) => i64::try_from(*u).ok(),
+		BenchValue::Float(f) if f.fract() == 0.0 => Some(*f as i64),
+		_ => None,
+	}
+}
+
+/// Encodes a value so that the byte order of encodings matches the numeric order of values
+fn sortable(value: i64) -> [u8; 8] {
+	((value as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+/// A secondary index on one integer field.
+#[derive(Clone, Debug)]
+pub(crate) struct KvIndex {
+	name: String,
+	field: String,
+	prefix: Vec<u8>,
+}
+
+impl KvIndex {
+	/// The first key of the entries of the index
+	pub(crate) fn start(&self) -> &[u8] {
+		&self.prefix
+	}
+
+	/// The key after the last entry of the index
+	pub(crate) fn end(&self) -> Vec<u8> {
+		let mut end = self.prefix.clone();
+		// The prefix ends with a zero separator, so this is the next prefix
+		*end.last_mut().unwrap() = 1;
+		end
+	}
+
+	/// The entry of a record with key `key`, when it has an integer value for the field
+	pub(crate) fn entry(&self, key: &[u8], record: &BenchValue) -> Option<Vec<u8>> {
+		let value = record.get_path(&self.field).and_then(integer)?;
+		let mut entry = Vec::with_capacity(self.prefix.len() + 8 + key.len());
+		entry.extend_from_slice(&self.prefix);
+		entry.extend_from_slice(&sortable(value));
+		entry.extend_from_slice(key);
+		Some(entry)
+	}
+
+	/// The first entry key of an inclusive value range, and the key after its last entry
+	pub(crate) fn range(&self, (lo, hi): (i64, i64)) -> (Vec<u8>, Vec<u8>) {
+		let start = [&self.prefix[..], &sortable(lo)].concat();
+		let end = match hi.checked_add(1) {
+			Some(next) => [&self.prefix[..], &sortable(next)].concat(),
+			None => self.end(),
+		};
+		(start, end)
+	}
+
+	/// Splits an entry key into the value it was written for and the key of its record
+	pub(crate) fn split<'a>(&self, entry: &'a [u8]) -> (i64, &'a [u8]) {
+		let (value, key) = entry[self.prefix.len()..].split_at(8);
+		let value = u64::from_be_bytes(value.try_into().unwrap()) ^ (1 << 63);
+		(value as i64, key)
+	}
+
+	/// Whether a record still holds the value an entry was written for
+	pub(crate) fn holds(&self, value: i64, record: &BenchValue) -> bool {
+		record.get_path(&self.field).and_then(integer) == Some(value)
+	}
+}
+
+/// The secondary indexes of a datastore, shared by all of its clients.
+pub(crate) struct KvIndexes {
+	/// Whether `--emulate-indexes` is set
+	enabled: bool,
+	/// Prepended to every entry key, to place the entries outside the records
+	tag: &'static [u8],
+	/// The indexes which writes maintain
+	indexes: RwLock<Vec<KvIndex>>,
+}
+
+impl KvIndexes {
+	/// Indexes whose entry keys start with `tag`, when `enabled`
+	pub(crate) fn new(enabled: bool, tag: &'static [u8]) -> Self {
+		Self {
+			enabled,
+			tag,
+			indexes: RwLock::new(Vec::new()),
+		}
+	}
+
+	/// The filter of a conditional scan, failing as unsupported for any other condition,
+	/// and for the hinted leg, as there is no planner to force an index on
+	pub(crate) fn filter(&self, scan: &Scan, ctx: ScanContext) -> Result<IntegerFilter> {
+		if !self.enabled || scan.order_by.is_some() || ctx == ScanContext::WithHint {
+			bail!(BenchError::Unsupported);
+		}
+		match scan.condition.as_ref().and_then(|c| c.sql.as_deref()).and_then(IntegerFilter::parse)
+		{
+			Some(filter) => Ok(filter),
+			None => bail!(BenchError::Unsupported),
+		}
+	}
+
+	/// Adds index `name`, which writes maintain from now on, failing as unsupported for
+	/// anything but a plain index on one field
+	pub(crate) fn create(&self, spec: &Index, name: &str) -> Result<KvIndex> {
+		if !self.enabled
+			|| spec.fields.len() != 1
+			|| spec.index_type.is_some()
+			|| spec.unique == Some(true)
+			|| spec.filter.is_some()
+		{
+			bail!(BenchError::Unsupported);
+		}
+		let index = KvIndex {
+			name: name.to_string(),
+			field: spec.fields[0].clone(),
+			prefix: [self.tag, name.as_bytes(), &[0]].concat(),
+		};
+		self.indexes.write().unwrap_or_else(|e| e.into_inner()).push(index.clone());
+		Ok(index)
+	}
+
+	/// Removes index `name`, returning it so its entries can be deleted
+	pub(crate) fn remove(&self, name: &str) -> Result<KvIndex> {
+		let mut indexes = self.indexes.write().unwrap_or_else(|e| e.into_inner());
+		match indexes.iter().position(|i| i.name == name) {
+			Some(pos) => Ok(indexes.remove(pos)),
+			None => bail!(BenchError::Unsupported),
+		}
+	}
+
+	/// The index the indexed leg of a scan reads through, when one is on the field of its filter
+	pub(crate) fn find(&self, filter: &IntegerFilter, ctx: ScanContext) -> Option<KvIndex> {
+		if ctx != ScanContext::WithIndex {
+			return None;
+		}
+		let indexes = self.indexes.read().unwrap_or_else(|e| e.into_inner());
+		indexes.iter().find(|i| i.field == filter.field).cloned()
+	}
+
+	/// The entries to write alongside a record with key `key` and encoded value `val`
+	pub(crate) fn entries(&self, key: &[u8], val: &[u8]) -> Result<Vec<Vec<u8>>> {
+		let indexes = self.indexes.read().unwrap_or_else(|e| e.into_inner());
+		// Records are only decoded while there are indexes to maintain
+		if indexes.is_empty() {
+			return Ok(Vec::new());
+		}
+		let record = BenchValue::decode(val)?;
+		Ok(indexes.iter().filter_map(|i| i.entry(key, &record)).collect())
+	}
+}
+
+/// The records returned by a conditional scan, after its start and up to its limit.
+pub(crate) struct Matches {
+	start: usize,
+	limit: usize,
+	projection: Projection,
+	skipped: usize,
+	count: usize,
+}
+
+impl Matches {
+	pub(crate) fn new(scan: &Scan) -> Result<Self> {
+		Ok(Self {
+			start: scan.start.unwrap_or(0),
+			limit: scan.limit.unwrap_or(usize::MAX),
+			projection: scan.projection()?,
+			skipped: 0,
+			count: 0,
+		})
+	}
+
+	/// Adds a record satisfying the condition, returning whether the scan needs more
+	pub(crate) fn push(&mut self, key: &[u8], record: BenchValue) -> bool {
+		if self.count >= self.limit {
+			return false;
+		}
+		if self.skipped < self.start {
+			self.skipped += 1;
+			return true;
+		}
+		match self.projection {
+			Projection::Id => {
+				black_box(key);
+			}
+			Projection::Count => (),
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
+				black_box(record);
+			}
+		}
+		self.count += 1;
+		self.count < self.limit
+	}
+
+	/// The number of records the scan returned
+	pub(crate) fn count(&self) -> usize {
+		self.count
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn integer_conditions_parse_into_ranges() {
+		let parse = |sql| IntegerFilter::parse(sql).map(|f| (f.field, f.ranges));
+		assert_eq!(parse("number = 21"), Some(("number".into(), vec![(21, 21)])));
+		assert_eq!(parse("number = 22 OR number = 21"), Some(("number".into(), vec![(21, 22)])));
+		assert_eq!(parse("number >= 18 AND number <= 21"), Some(("number".into(), vec![(18, 21)])));
+		assert_eq!(parse("number > 18 AND number < 21"), Some(("number".into(), vec![(19, 20)])));
+		assert_eq!(
+			parse("number IN (18, 21, 30)"),
+			Some(("number".into(), vec![(18, 18), (21, 21), (30, 30)]))
+		);
+		assert_eq!(parse("number = 21 AND status = 'published'"), None);
+		assert_eq!(parse("number = 21 OR age = 42"), None);
+		assert_eq!(parse("number <= 50 AND (tier = 0 OR tier = 3)"), None);
+	}
+
+	#[test]
+	fn entries_sort_by_value_and_point_at_their_record() {
+		let index = KvIndex {
+			name: "i".into(),
+			field: "number".into(),
+			prefix: b"i\0".to_vec(),
+		};
+		let record = |n: i64| BenchValue::from(json!({ "number": n }));
+		let low = index.entry(b"a", &record(-5)).unwrap();
+		let high = index.entry(b"b", &record(7)).unwrap();
+		assert!(index.start() <= &low[..] && low < high && high < index.end());
+		let (start, end) = index.range((7, 7));
+		assert!(start <= high && high < end && low < start);
+		assert_eq!(index.split(&high), (7, &b"b"[..]));
+		assert!(index.holds(7, &record(7)) && !index.holds(7, &record(8)));
+	}
+}
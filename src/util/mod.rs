@@ -1,9 +1,71 @@
 //! Shared helpers used across datastore backends.
 
+use anyhow::{Result, bail};
+#[cfg(any(
+	feature = "fjall",
+	feature = "lmdb",
+	feature = "mdbx",
+	feature = "rocksdb",
+	feature = "sqlite",
+	feature = "surrealkv",
+	feature = "surrealmx"
+))]
+use std::path::Path;
 use std::time::Duration;
 
+#[cfg(any(
+	feature = "fjall",
+	feature = "lmdb",
+	feature = "mdbx",
+	feature = "redb",
+	feature = "rocksdb",
+	feature = "surrealkv"
+))]
+pub(crate) mod kvindex;
+#[cfg(any(feature = "dragonfly", feature = "keydb", feature = "redis"))]
+pub(crate) mod resp;
 pub(crate) mod sql;
 
+/// Total size in bytes of the files under a directory, or `None` when it does not exist.
+#[cfg(any(
+	feature = "fjall",
+	feature = "lmdb",
+	feature = "mdbx",
+	feature = "rocksdb",
+	feature = "sqlite",
+	feature = "surrealkv",
+	feature = "surrealmx"
+))]
+pub(crate) fn dir_size(path: impl AsRef<Path>) -> Option<u64> {
+	let entries = std::fs::read_dir(path).ok()?;
+	let size = entries
+		.flatten()
+		.map(|entry| match entry.metadata() {
+			Ok(meta) if meta.is_dir() => dir_size(entry.path()).unwrap_or(0),
+			Ok(meta) => meta.len(),
+			Err(_) => 0,
+		})
+		.sum();
+	Some(size)
+}
+
+/// The lock file the benchmark was built with, recording the versions of the engine crates.
+const CARGO_LOCK: &str = include_str!("../../Cargo.lock");
+
+/// The version of a crate the benchmark was built with, such as an embedded engine, as `name x.y.z`.
+pub(crate) fn crate_version(name: &str) -> Result<String> {
+	let package = format!("name = \"{name}\"");
+	let mut lines = CARGO_LOCK.lines();
+	while let Some(line) = lines.next() {
+		if line == package
+			&& let Some(version) = lines.next().and_then(|l| l.strip_prefix("version = \""))
+		{
+			return Ok(format!("{name} {}", version.trim_end_matches('"')));
+		}
+	}
+	bail!("The crate {name} is not in the lock file")
+}
+
 pub(crate) fn format_duration(duration: Duration) -> String {
 	let secs = duration.as_secs();
 	if secs >= 86400 {
@@ -0,0 +1,312 @@
+//! Shared record connection and batch commands for the Redis-family datastores.
+//!
+//! By default each client runs one command at a time, so `-t` threads queue for the connection
+//! and every command pays a full round trip. With `--pipelined` the threads share the multiplexed
+//! connection, which pipelines their commands on the wire, and batches are sent as a single
+//! `MSET`, `MGET`, or `DEL` instead of a pipeline of one command per key.
+//!
+//! Connections to a Redis Cluster route each command to the node owning its hash slot, and
+//! split multi-key commands by slot, so the adapters send the same commands in either case.
+//!
+//! Scans walk the keyspace with `SCAN`, which visits the keys in hash order rather than key
+//! order, so `start` skips that many keys of the walk and `limit` stops it after that many
+//! more. Full scans fetch the values of each page of keys with one `MGET`, and counting every
+//! record reads `DBSIZE` instead of walking the keyspace.
+
+use crate::error::BenchError;
+use crate::materialize;
+use crate::value::BenchValue;
+use crate::{Projection, Scan};
+use anyhow::{Result, anyhow, bail};
+use futures::StreamExt;
+use redis::aio::{ConnectionLike, MultiplexedConnection};
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::{AsyncCommands, Client, Cmd, Pipeline, RedisFuture, ScanOptions, ToRedisArgs, Value};
+use std::hint::black_box;
+use std::ops::{Deref, DerefMut};
+use tokio::sync::{Mutex, MutexGuard};
+
+/// The number of keys each `SCAN` call returns, and each `MGET` of a full scan fetches
+const SCAN_PAGE: usize = 5000;
+
+/// A multiplexed connection to a single node, or to every node of a cluster.
+#[derive(Clone)]
+pub(crate) enum Multiplexed {
+	/// A connection to a single server
+	Node(MultiplexedConnection),
+	/// A connection to a cluster, routing each command by its hash slot
+	Cluster(ClusterConnection),
+}
+
+impl Multiplexed {
+	/// Connects to a single node at `url`
+	pub(crate) async fn node(url: &str) -> Result<Self> {
+		let client = Client::open(url)?;
+		Ok(Self::Node(client.get_multiplexed_async_connection().await?))
+	}
+
+	/// Connects to the cluster which the `urls` are seed nodes of
+	pub(crate) async fn cluster(urls: &[&str]) -> Result<Self> {
+		let client = ClusterClient::new(urls.to_vec())?;
+		Ok(Self::Cluster(client.get_async_connection().await?))
+	}
+
+	/// Whether the keys are spread over the nodes of a cluster
+	pub(crate) fn is_cluster(&self) -> bool {
+		matches!(self, Self::Cluster(_))
+	}
+}
+
+impl ConnectionLike for Multiplexed {
+	fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+		match self {
+			Self::Node(conn) => conn.req_packed_command(cmd),
+			Self::Cluster(conn) => conn.req_packed_command(cmd),
+		}
+	}
+
+	fn req_packed_commands<'a>(
+		&'a mut self,
+		cmd: &'a Pipeline,
+		offset: usize,
+		count: usize,
+	) -> RedisFuture<'a, Vec<Value>> {
+		match self {
+			Self::Node(conn) => conn.req_packed_commands(cmd, offset, count),
+			Self::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+		}
+	}
+
+	fn get_db(&self) -> i64 {
+		match self {
+			Self::Node(conn) => conn.get_db(),
+			Self::Cluster(conn) => conn.get_db(),
+		}
+	}
+}
+
+/// The connection a client sends its record commands over.
+pub(crate) struct RespConnection {
+	conn: Mutex<Multiplexed>,
+	pipelined: bool,
+}
+
+/// A connection held by one thread, either exclusively or as a pipelined handle.
+pub(crate) enum RespGuard<'a> {
+	/// Held until dropped, so other threads wait for the command to complete
+	Exclusive(MutexGuard<'a, Multiplexed>),
+	/// A handle to the shared connection, whose commands are pipelined with other threads
+	Pipelined(Multiplexed),
+}
+
+impl Deref for RespGuard<'_> {
+	type Target = Multiplexed;
+	fn deref(&self) -> &Self::Target {
+		match self {
+			Self::Exclusive(conn) => conn,
+			Self::Pipelined(conn) => conn,
+		}
+	}
+}
+
+impl DerefMut for RespGuard<'_> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		match self {
+			Self::Exclusive(conn) => conn,
+			Self::Pipelined(conn) => conn,
+		}
+	}
+}
+
+impl RespConnection {
+	/// Wraps a connection, pipelining the commands of concurrent threads when `pipelined`
+	pub(crate) fn new(conn: Multiplexed, pipelined: bool) -> Self {
+		Self {
+			conn: Mutex::new(conn),
+			pipelined,
+		}
+	}
+
+	/// Takes the connection for the next command
+	pub(crate) async fn lock(&self) -> RespGuard<'_> {
+		match self.pipelined {
+			true => RespGuard::Pipelined(self.conn.lock().await.clone()),
+			false => RespGuard::Exclusive(self.conn.lock().await),
+		}
+	}
+
+	/// The version from `INFO server`, preferring Dragonfly's own over its Redis compatibility
+	pub(crate) async fn server_version(&self) -> Result<String> {
+		let info: String =
+			redis::cmd("INFO").arg("server").query_async(&mut *self.lock().await).await?;
+		let field = |name: &str| {
+			info.lines().find_map(|line| line.strip_prefix(name)).map(|v| v.trim().to_string())
+		};
+		field("dragonfly_version:")
+			.or_else(|| field("redis_version:"))
+			.ok_or_else(|| anyhow!("The server did not report its version"))
+	}
+
+	/// Writes a batch of values, overwriting any existing ones
+	pub(crate) async fn batch_set<K: ToRedisArgs>(
+		&self,
+		key_vals: impl Iterator<Item = (K, BenchValue)>,
+	) -> Result<()> {
+		let mut conn = self.lock().await;
+		let mut pipe = redis::pipe();
+		match self.pipelined {
+			true => {
+				// Build a single MSET command
+				pipe.cmd("MSET");
+				for (k, v) in key_vals {
+					pipe.arg(k).arg(v.encode()?);
+				}
+				pipe.ignore();
+			}
+			false => {
+				// Build the SET pipeline
+				for (k, v) in key_vals {
+					pipe.cmd("SET").arg(k).arg(v.encode()?).ignore();
+				}
+			}
+		}
+		// Execute the pipeline
+		pipe.exec_async(&mut *conn).await?;
+		Ok(())
+	}
+
+	/// Reads a batch of values, failing if any is missing
+	pub(crate) async fn batch_get<K: ToRedisArgs>(
+		&self,
+		keys: impl Iterator<Item = K>,
+	) -> Result<()> {
+		let mut conn = self.lock().await;
+		let keys: Vec<K> = keys.collect();
+		let vals: Vec<Option<Vec<u8>>> = match self.pipelined {
+			// Send a single MGET command
+			true => redis::cmd("MGET").arg(&keys).query_async(&mut *conn).await?,
+			// Build and execute the GET pipeline
+			false => {
+				let mut pipe = redis::pipe();
+				for k in &keys {
+					pipe.cmd("GET").arg(k);
+				}
+				pipe.query_async(&mut *conn).await?
+			}
+		};
+		// Inspect the responses
+		assert_eq!(vals.len(), keys.len());
+		for v in vals {
+			let v = v.ok_or_else(|| anyhow!("missing key"))?;
+			assert!(!v.is_empty());
+			materialize::consume(v, |v| BenchValue::decode(&v))?;
+		}
+		Ok(())
+	}
+
+	/// Deletes a batch of values
+	pub(crate) async fn batch_del<K: ToRedisArgs>(
+		&self,
+		keys: impl Iterator<Item = K>,
+	) -> Result<()> {
+		let mut conn = self.lock().await;
+		let mut pipe = redis::pipe();
+		match self.pipelined {
+			// Build a single DEL command
+			true => {
+				pipe.cmd("DEL");
+				for k in keys {
+					pipe.arg(k);
+				}
+				pipe.ignore();
+			}
+			// Build the DEL pipeline
+			false => {
+				for k in keys {
+					pipe.cmd("DEL").arg(k).ignore();
+				}
+			}
+		}
+		// Execute the pipeline
+		pipe.exec_async(&mut *conn).await?;
+		Ok(())
+	}
+
+	/// Scans the records, walking the keyspace on `conn_iter` and reading the values on this
+	/// connection, returning the number of records the projection returned
+	pub(crate) async fn scan(&self, conn_iter: &Mutex<Multiplexed>, scan: &Scan) -> Result<usize> {
+		// Conditional and ordered scans are not supported
+		if scan.condition.is_some() || scan.order_by.is_some() {
+			bail!(BenchError::Unsupported);
+		}
+		// Extract parameters
+		let s = scan.start.unwrap_or(0);
+		let l = scan.limit.unwrap_or(usize::MAX);
+		let p = scan.projection()?;
+		// Get the iteration connection
+		let mut conn_iter = conn_iter.lock().await;
+		// Count every record from the size of the keyspace, summed over
+		// the nodes of a cluster, instead of walking the keys
+		if matches!(p, Projection::Count) && scan.limit.is_none() {
+			let size: usize = redis::cmd("DBSIZE").query_async(&mut *conn_iter).await?;
+			return Ok(size.saturating_sub(s));
+		}
+		// SCAN only iterates the keys of the node it is routed to
+		if conn_iter.is_cluster() {
+			bail!(BenchError::Unsupported);
+		}
+		// Configure the scan options for improve iteration
+		let opts = ScanOptions::default().with_count(SCAN_PAGE);
+		// Create an iterator starting at the beginning
+		let mut iter = conn_iter.scan_options::<String>(opts).await?.skip(s);
+		// Perform the relevant projection scan type
+		match p {
+			Projection::Id => {
+				// We use a for loop to iterate over the results, while
+				// calling black_box internally. This is necessary as
+				// an iterator with `filter_map` or `map` is optimised
+				// out by the compiler when calling `count` at the end.
+				let mut count = 0;
+				for _ in 0..l {
+					if let Some(k) = iter.next().await {
+						black_box(k);
+						count += 1;
+					} else {
+						break;
+					}
+				}
+				Ok(count)
+			}
+			Projection::Full | Projection::Field(_) | Projection::Fields(_) => {
+				let mut count = 0;
+				let mut walked = 0;
+				let mut keys = Vec::with_capacity(SCAN_PAGE);
+				loop {
+					// Collect the next page of keys
+					while walked < l && keys.len() < SCAN_PAGE {
+						match iter.next().await {
+							Some(k) => keys.push(k),
+							None => break,
+						}
+						walked += 1;
+					}
+					if keys.is_empty() {
+						break;
+					}
+					// Fetch the values of the page at once
+					let vals: Vec<Option<Vec<u8>>> =
+						redis::cmd("MGET").arg(&keys).query_async(&mut *self.lock().await).await?;
+					// Keys deleted since the walk passed them are not returned
+					for v in vals.into_iter().flatten() {
+						materialize::consume(v, |v| BenchValue::decode(&v))?;
+						count += 1;
+					}
+					keys.clear();
+				}
+				Ok(count)
+			}
+			Projection::Count => Ok(iter.take(l).count().await),
+		}
+	}
+}
@@ -88,7 +88,7 @@ pub(crate) fn bench_to_sqlite_param(
 /// Bind a [`BenchValue`] to a `mysql_async::Value`, validated against the
 /// destination [`ColumnType`]. Decimals are passed as strings (MySQL/MariaDB
 /// accept implicit cast to `DECIMAL`); UUIDs as their canonical 36-char form.
-#[cfg(any(feature = "mysql", feature = "mariadb"))]
+#[cfg(any(feature = "mysql", feature = "mariadb", feature = "tidb"))]
 pub(crate) fn bench_to_mysql_value(
 	column_type: &ColumnType,
 	v: &BenchValue,
@@ -107,6 +107,11 @@ impl BenchValue {
 		}
 	}
 
+	/// Nested field accessor for a dotted path such as `geography.code`.
+	pub(crate) fn get_path(&self, path: &str) -> Option<&BenchValue> {
+		path.split('.').try_fold(self, |v, key| v.get_field(key))
+	}
+
 	/// Reject any leading top-level value that is not an object payload.
 	pub(crate) fn into_object(self) -> Result<Vec<(String, BenchValue)>> {
 		match self {
@@ -237,6 +242,18 @@ mod tests {
 		assert_eq!(original, back);
 	}
 
+	#[test]
+	fn get_path_follows_nested_objects() {
+		let bv = BenchValue::from(&json!({
+			"name": "x",
+			"geography": { "code": "GBR" },
+		}));
+		assert_eq!(bv.get_path("name"), Some(&BenchValue::String("x".into())));
+		assert_eq!(bv.get_path("geography.code"), Some(&BenchValue::String("GBR".into())));
+		assert_eq!(bv.get_path("geography.missing"), None);
+		assert_eq!(bv.get_path("name.code"), None);
+	}
+
 	#[test]
 	fn json_emits_canonical_strings_for_native_types() {
 		let u = Uuid::nil();
@@ -1,8 +1,9 @@
+use crate::keyprovider::{IntegerKeyProvider, UnorderedInteger};
 use crate::value::BenchValue;
 use anyhow::{Result, anyhow, bail};
 use chrono::{TimeZone, Utc};
-use log::debug;
 use rand::RngExt as RandGen;
+use rand::SeedableRng;
 use rand::prelude::SmallRng;
 use rust_decimal::Decimal;
 use serde_json::{Map, Number, Value};
@@ -10,7 +11,8 @@ use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::ops::Range;
 use std::str::FromStr;
-use uuid::Uuid;
+use tracing::debug;
+use uuid::Builder;
 
 /// Generates synthetic [`BenchValue`] payloads from a JSON template authored in
 /// `bench.toml`. The template is parsed once into a [`ValueGenerator`] tree and
@@ -20,6 +22,8 @@ pub(crate) struct ValueProvider {
 	generator: ValueGenerator,
 	rng: SmallRng,
 	columns: Columns,
+	/// Derives the value of each sample from this seed, so it can be generated again
+	seed: Option<u64>,
 }
 
 impl ValueProvider {
@@ -37,9 +41,37 @@ impl ValueProvider {
 			generator,
 			columns,
 			rng: rand::make_rng(),
+			seed: None,
 		})
 	}
 
+	/// Derive the payload of each sample from `seed`, so that
+	/// [`Self::generate_value_for`] returns the same value every time,
+	/// and [`Self::generate_value`] the same sequence in every run.
+	pub(crate) fn seeded(mut self, seed: u64) -> Self {
+		self.seed = Some(seed);
+		self.rng = SmallRng::seed_from_u64(seed);
+		self
+	}
+
+	/// A copy whose seeded payloads differ from those of every other
+	/// `round`, such as the values written by the updates after the creates.
+	pub(crate) fn round(&self, round: u64) -> Self {
+		let mut vp = self.clone();
+		vp.seed = self.seed.map(|seed| mix(seed ^ mix(round)));
+		vp
+	}
+
+	/// A copy for the `worker` of a phase, whose seeded [`Self::generate_value`]
+	/// sequence differs from that of every other worker, rather than repeating it.
+	pub(crate) fn worker(&self, worker: u64) -> Self {
+		let mut vp = self.clone();
+		if let Some(seed) = self.seed {
+			vp.rng = SmallRng::seed_from_u64(mix(seed.wrapping_add(worker)));
+		}
+		vp
+	}
+
 	/// Returns the schema's columns in their declared order.
 	pub(crate) fn columns(&self) -> Columns {
 		self.columns.clone()
@@ -47,16 +79,59 @@ impl ValueProvider {
 
 	/// Produce a single randomised [`BenchValue`] payload.
 	pub(crate) fn generate_value(&mut self) -> BenchValue {
-		self.generator.generate(&mut self.rng)
+		self.generator.generate(&mut self.rng, None)
+	}
+
+	/// Produce a randomised [`BenchValue`] payload for the record of sample `n`,
+	/// whose `unique` fields hold [`unique_value`] of `n`. When seeded, the
+	/// payload is the same every time it is generated for `n`.
+	pub(crate) fn generate_value_for(&mut self, n: u32) -> BenchValue {
+		match self.seed {
+			Some(seed) => {
+				let mut rng = SmallRng::seed_from_u64(mix(seed ^ n as u64));
+				self.generator.generate(&mut rng, Some(n))
+			}
+			None => self.generator.generate(&mut self.rng, Some(n)),
+		}
+	}
+
+	/// The first top-level field declared as `unique` in the template.
+	pub(crate) fn unique_field(&self) -> Option<&str> {
+		match &self.generator {
+			ValueGenerator::Object(o) => {
+				o.iter().find(|(_, v)| matches!(v, ValueGenerator::Unique)).map(|(k, _)| k.as_str())
+			}
+			_ => None,
+		}
 	}
 }
 
+/// Scrambles the bits of a seed (SplitMix64), so neighbouring samples get unrelated payloads.
+fn mix(mut x: u64) -> u64 {
+	x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+	x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	x ^ (x >> 31)
+}
+
+/// The value of the `unique` fields in the record of sample `n`.
+///
+/// Samples are permuted so that neighbouring records do not hold
+/// neighbouring values, which would favour range-partitioned indexes.
+pub(crate) fn unique_value(n: u32) -> String {
+	format!("u{:010}", UnorderedInteger::default().key(n))
+}
+
 impl Clone for ValueProvider {
 	fn clone(&self) -> Self {
 		Self {
 			generator: self.generator.clone(),
-			rng: rand::make_rng(),
+			rng: match self.seed {
+				Some(seed) => SmallRng::seed_from_u64(seed),
+				None => rand::make_rng(),
+			},
 			columns: self.columns.clone(),
+			seed: self.seed,
 		}
 	}
 }
@@ -71,6 +146,8 @@ enum ValueGenerator {
 	Float,
 	DateTime,
 	Uuid,
+	/// A string held by exactly one record, derived from its sample
+	Unique,
 	Decimal,
 	Bytes(Length<usize>),
 	// We use i32 for better compatibility across DBs
@@ -79,6 +156,10 @@ enum ValueGenerator {
 	FloatRange(Range<f32>),
 	DecimalRange(Range<f64>),
 	StringEnum(Vec<String>),
+	/// Hot strings with their percentage of the values, then the rest chosen uniformly
+	StringEnumSkew(Vec<(String, f64)>, Vec<String>),
+	/// Hot integers with their percentage of the values, then the rest chosen from the range
+	IntegerSkew(Vec<(i32, f64)>, Range<i32>),
 	IntegerEnum(Vec<Number>),
 	FloatEnum(Vec<Number>),
 	DecimalEnum(Vec<Decimal>),
@@ -207,6 +288,31 @@ impl ValueGenerator {
 			}
 		} else if let Some(i) = s.strip_prefix("bytes:") {
 			Self::Bytes(Length::new(i)?)
+		} else if let Some(s) = s.strip_prefix("string_enum_skew:") {
+			// Parse format: "string_enum_skew:hot=90,warm=5,cold,colder"
+			let (hot, rest) = skew_weights(s)?;
+			if rest.is_empty() && hot.iter().map(|(_, p)| p).sum::<f64>() < 100.0 {
+				bail!("Skew weights must total 100% without unweighted values: {s}");
+			}
+			let hot = hot.into_iter().map(|(v, p)| (v.to_string(), p)).collect();
+			Self::StringEnumSkew(hot, rest.into_iter().map(|v| v.to_string()).collect())
+		} else if let Some(s) = s.strip_prefix("int_skew:") {
+			// Parse format: "int_skew:1..5000;21=90,22=5"
+			let Some((range, weights)) = s.split_once(';') else {
+				bail!("Skewed integers require a range and weights separated by semicolon: {s}");
+			};
+			let Length::Range(range) = Length::new(range)? else {
+				bail!("Expected a range but got: {range}");
+			};
+			let (hot, rest) = skew_weights(weights)?;
+			if !rest.is_empty() {
+				bail!("Every skewed integer needs a weight: {weights}");
+			}
+			let mut values = Vec::with_capacity(hot.len());
+			for (v, p) in hot {
+				values.push((v.parse::<i32>()?, p));
+			}
+			Self::IntegerSkew(values, range)
 		} else if let Some(s) = s.strip_prefix("string_enum:") {
 			let labels = s.split(",").map(|s| s.to_string()).collect();
 			Self::StringEnum(labels)
@@ -246,6 +352,8 @@ impl ValueGenerator {
 			Self::DateTime
 		} else if s.eq("uuid") {
 			Self::Uuid
+		} else if s.eq("unique") {
+			Self::Unique
 		} else {
 			bail!("Unsupported type: {s}");
 		};
@@ -274,7 +382,7 @@ impl ValueGenerator {
 		Ok(Self::Object(map))
 	}
 
-	fn generate(&self, rng: &mut SmallRng) -> BenchValue {
+	fn generate(&self, rng: &mut SmallRng, n: Option<u32>) -> BenchValue {
 		match self {
 			ValueGenerator::Bool => {
 				let v = RandGen::random_bool(&mut *rng, 0.5);
@@ -318,7 +426,17 @@ impl ValueGenerator {
 					.unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap());
 				BenchValue::DateTime(dt)
 			}
-			ValueGenerator::Uuid => BenchValue::Uuid(Uuid::new_v4()),
+			ValueGenerator::Uuid => {
+				// Drawn from the generator, so seeded payloads hold the same UUIDs
+				let bytes = RandGen::random::<[u8; 16]>(&mut *rng);
+				BenchValue::Uuid(Builder::from_random_bytes(bytes).into_uuid())
+			}
+			ValueGenerator::Unique => match n {
+				Some(n) => BenchValue::String(unique_value(n)),
+				// Values without a sample, such as those with generated keys,
+				// are random, and of the same shape as the derived values
+				None => BenchValue::String(format!("r{}", string(rng, 10))),
+			},
 			ValueGenerator::Decimal => {
 				// Generate a 4-fractional-digit decimal in [0, 1_000_000) so
 				// the value fits comfortably in `NUMERIC(38, 10)` and similar.
@@ -350,6 +468,20 @@ impl ValueGenerator {
 				let i = rng.random_range(0..a.len());
 				BenchValue::String(a[i].to_string())
 			}
+			ValueGenerator::StringEnumSkew(hot, rest) => {
+				let v = match pick_hot(rng, hot) {
+					Some(v) => v.clone(),
+					None => rest[rng.random_range(0..rest.len())].clone(),
+				};
+				BenchValue::String(v)
+			}
+			ValueGenerator::IntegerSkew(hot, r) => {
+				let v = match pick_hot(rng, hot) {
+					Some(v) => *v,
+					None => rng.random_range(r.start..r.end),
+				};
+				BenchValue::Int(v as i64)
+			}
 			ValueGenerator::IntegerEnum(a) => {
 				let i = rng.random_range(0..a.len());
 				let n = &a[i];
@@ -372,14 +504,14 @@ impl ValueGenerator {
 			ValueGenerator::Array(a) => {
 				let mut vec = Vec::with_capacity(a.len());
 				for v in a {
-					vec.push(v.generate(rng));
+					vec.push(v.generate(rng, n));
 				}
 				BenchValue::Array(vec)
 			}
 			ValueGenerator::Object(o) => {
 				let mut vec = Vec::with_capacity(o.len());
 				for (k, v) in o {
-					vec.push((k.clone(), v.generate(rng)));
+					vec.push((k.clone(), v.generate(rng, n)));
 				}
 				BenchValue::Object(vec)
 			}
@@ -387,6 +519,48 @@ impl ValueGenerator {
 	}
 }
 
+/// Values with their percentage of the generated values.
+type Weighted<'a> = Vec<(&'a str, f64)>;
+
+/// Splits `value=percent` entries, separated by commas, into the weighted
+/// values and the unweighted values, which share the remaining percentage.
+fn skew_weights(s: &str) -> Result<(Weighted<'_>, Vec<&str>)> {
+	let mut hot = Vec::new();
+	let mut rest = Vec::new();
+	for entry in s.split(',') {
+		match entry.split_once('=') {
+			Some((v, p)) => {
+				let p = p.trim().trim_end_matches('%').parse::<f64>()?;
+				if !(0.0..=100.0).contains(&p) {
+					bail!("Skew weights are percentages, but got: {p}");
+				}
+				hot.push((v.trim(), p));
+			}
+			None => rest.push(entry.trim()),
+		}
+	}
+	if hot.is_empty() {
+		bail!("Skewed values need at least one weighted value: {s}");
+	}
+	if hot.iter().map(|(_, p)| p).sum::<f64>() > 100.0 {
+		bail!("Skew weights must not total more than 100%: {s}");
+	}
+	Ok((hot, rest))
+}
+
+/// Picks one of the hot values by its percentage, or none for the remaining share.
+fn pick_hot<'a, T>(rng: &mut SmallRng, hot: &'a [(T, f64)]) -> Option<&'a T> {
+	let r = rng.random_range(0.0..100.0);
+	let mut total = 0.0;
+	for (v, p) in hot {
+		total += p;
+		if r < total {
+			return Some(v);
+		}
+	}
+	None
+}
+
 #[derive(Clone, Debug)]
 enum Length<Idx>
 where
@@ -473,11 +647,14 @@ impl ColumnType {
 			ValueGenerator::Object(_) => ColumnType::Object,
 			ValueGenerator::Array(_) => ColumnType::Array,
 			ValueGenerator::StringEnum(_)
+			| ValueGenerator::StringEnumSkew(_, _)
 			| ValueGenerator::String(_)
 			| ValueGenerator::Text(_)
-			| ValueGenerator::Words(_, _) => ColumnType::String,
+			| ValueGenerator::Words(_, _)
+			| ValueGenerator::Unique => ColumnType::String,
 			ValueGenerator::Integer
 			| ValueGenerator::IntegerRange(_)
+			| ValueGenerator::IntegerSkew(_, _)
 			| ValueGenerator::IntegerEnum(_) => ColumnType::Integer,
 			ValueGenerator::Float
 			| ValueGenerator::FloatRange(_)
@@ -513,4 +690,85 @@ mod test {
 		assert_ne!(v1a, v2a);
 		assert_ne!(v1b, v2b);
 	}
+
+	#[test]
+	fn unique_fields_are_derived_from_the_sample() {
+		let mut vp = ValueProvider::new(r#"{ "int": "int", "sk": "unique" }"#).unwrap();
+		assert_eq!(vp.unique_field(), Some("sk"));
+		let a = vp.generate_value_for(7);
+		let b = vp.generate_value_for(7);
+		assert_ne!(a.get_field("int"), b.get_field("int"));
+		assert_eq!(a.get_field("sk"), Some(&BenchValue::String(unique_value(7))));
+		assert_eq!(a.get_field("sk"), b.get_field("sk"));
+		let values: std::collections::HashSet<_> = (0..10_000).map(unique_value).collect();
+		assert_eq!(values.len(), 10_000);
+		assert_eq!(ValueProvider::new(r#"{ "int": "int" }"#).unwrap().unique_field(), None);
+	}
+
+	#[test]
+	fn seeded_values_are_generated_again_per_sample() {
+		let template = r#"{ "int": "int", "id": "uuid", "text": "text:50", "sk": "unique" }"#;
+		let mut vp = ValueProvider::new(template).unwrap().seeded(42);
+		let a = vp.generate_value_for(7);
+		assert_eq!(a, vp.clone().generate_value_for(7));
+		assert_ne!(a, vp.generate_value_for(8));
+		let mut updates = vp.round(1);
+		assert_ne!(a, updates.generate_value_for(7));
+		assert_eq!(updates.generate_value_for(7), vp.round(1).generate_value_for(7));
+	}
+
+	#[test]
+	fn seeded_runs_generate_identical_payloads() {
+		let template = r#"{ "text": "text:50", "words": "words:20;a,b,c", "bytes": "bytes:8" }"#;
+		let run = |seed| {
+			let mut vp = ValueProvider::new(template).unwrap().seeded(seed);
+			let values = [vp.generate_value(), vp.generate_value(), vp.generate_value_for(3)];
+			values.map(|v| v.encode().unwrap())
+		};
+		assert_eq!(run(7), run(7));
+		assert_ne!(run(7), run(8));
+	}
+
+	#[test]
+	fn seeded_workers_generate_distinct_payloads() {
+		let template = r#"{ "text": "text:50", "int": "int" }"#;
+		let vp = ValueProvider::new(template).unwrap().seeded(7);
+		let run = |worker| {
+			let mut vp = vp.worker(worker);
+			[vp.generate_value(), vp.generate_value()].map(|v| v.encode().unwrap())
+		};
+		assert_eq!(run(1), run(1));
+		assert_ne!(run(1), run(2));
+		// The value of each sample is the same whichever worker generates it
+		assert_eq!(vp.worker(1).generate_value_for(3), vp.worker(2).generate_value_for(3));
+	}
+
+	#[test]
+	fn skewed_values_follow_their_weights() {
+		let template = r#"{ "n": "int_skew:1..5000;21=90", "s": "string_enum_skew:hot=60,a,b" }"#;
+		let mut vp = ValueProvider::new(template).unwrap();
+		let samples = 10_000;
+		let (mut n, mut s) = (0, 0);
+		for _ in 0..samples {
+			let v = vp.generate_value();
+			if v.get_field("n") == Some(&BenchValue::Int(21)) {
+				n += 1;
+			}
+			if v.get_field("s") == Some(&BenchValue::String("hot".to_string())) {
+				s += 1;
+			}
+		}
+		// Allow for sampling noise, and the other values landing on the hot integer
+		assert!((8_800..=9_200).contains(&n), "{n} of {samples} integers were hot");
+		assert!((5_700..=6_300).contains(&s), "{s} of {samples} strings were hot");
+	}
+
+	#[test]
+	fn skewed_weights_are_validated() {
+		assert!(ValueProvider::new(r#"{ "n": "int_skew:1..10;1=60,2=50" }"#).is_err());
+		assert!(ValueProvider::new(r#"{ "n": "int_skew:1..10;1=60,2" }"#).is_err());
+		assert!(ValueProvider::new(r#"{ "n": "int_skew:1..10" }"#).is_err());
+		assert!(ValueProvider::new(r#"{ "s": "string_enum_skew:a=60" }"#).is_err());
+		assert!(ValueProvider::new(r#"{ "s": "string_enum_skew:a=60,b=40" }"#).is_ok());
+	}
 }
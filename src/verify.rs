@@ -0,0 +1,155 @@
+//! Compares the records read back by `--verify` against the values written to them.
+//!
+//! Datastores round-trip values through their own types, so the comparison is lenient about
+//! representation: numbers are compared by value, decimals, UUIDs and datetimes may come back
+//! as strings, bytes as arrays, and fields which were not written (such as the id) are ignored.
+
+use crate::value::BenchValue;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+
+/// Relative difference below which two numbers are treated as equal.
+const TOLERANCE: f64 = 1e-6;
+
+/// Describes the first difference between the `expected` value and the `actual` value read
+/// back, or returns `None` when the read value holds everything which was written.
+pub(crate) fn mismatch(expected: &BenchValue, actual: &BenchValue) -> Option<String> {
+	diff("", expected, actual)
+}
+
+/// Compares two values at a dotted `path` into the record.
+fn diff(path: &str, expected: &BenchValue, actual: &BenchValue) -> Option<String> {
+	let same = match (expected, actual) {
+		(BenchValue::Object(fields), _) => {
+			if !matches!(actual, BenchValue::Object(_)) {
+				return Some(difference(path, expected, actual));
+			}
+			for (key, value) in fields {
+				let path = match path {
+					"" => key.clone(),
+					_ => format!("{path}.{key}"),
+				};
+				match actual.get_field(key) {
+					Some(found) => {
+						if let Some(d) = diff(&path, value, found) {
+							return Some(d);
+						}
+					}
+					None => return Some(format!("`{path}` is missing")),
+				}
+			}
+			true
+		}
+		(BenchValue::Array(items), BenchValue::Array(found)) => {
+			if items.len() != found.len() {
+				return Some(difference(path, expected, actual));
+			}
+			for (i, (item, found)) in items.iter().zip(found).enumerate() {
+				if let Some(d) = diff(&format!("{path}[{i}]"), item, found) {
+					return Some(d);
+				}
+			}
+			true
+		}
+		(BenchValue::Bytes(bytes), BenchValue::Array(found)) => {
+			bytes.len() == found.len()
+				&& bytes.iter().zip(found).all(|(b, f)| number(f) == Some(*b as f64))
+		}
+		(BenchValue::Bool(b), BenchValue::Bool(f)) => b == f,
+		(BenchValue::String(s), BenchValue::String(f)) => s == f,
+		(BenchValue::Uuid(u), _) => match actual {
+			BenchValue::Uuid(f) => u == f,
+			BenchValue::String(f) => u.to_string().eq_ignore_ascii_case(f),
+			_ => false,
+		},
+		(BenchValue::DateTime(dt), _) => datetime(actual).is_some_and(|f| f == *dt),
+		(
+			BenchValue::Int(_)
+			| BenchValue::UInt(_)
+			| BenchValue::Float(_)
+			| BenchValue::Decimal(_),
+			_,
+		) => match (number(expected), number(actual)) {
+			(Some(e), Some(f)) => (e - f).abs() <= TOLERANCE * e.abs().max(f.abs()).max(1.0),
+			_ => false,
+		},
+		_ => expected == actual,
+	};
+	match same {
+		true => None,
+		false => Some(difference(path, expected, actual)),
+	}
+}
+
+/// The message for a value which differs from the one written at `path`.
+fn difference(path: &str, expected: &BenchValue, actual: &BenchValue) -> String {
+	let path = match path {
+		"" => "the record",
+		path => path,
+	};
+	format!("`{path}` holds {} instead of {}", actual.to_json(), expected.to_json())
+}
+
+/// A numeric value, including decimals returned as strings.
+fn number(value: &BenchValue) -> Option<f64> {
+	match value {
+		BenchValue::Int(i) => Some(*i as f64),
+		BenchValue::UInt(u) => Some(*u as f64),
+		BenchValue::Float(f) => Some(*f),
+		BenchValue::Decimal(d) => d.to_f64(),
+		BenchValue::String(s) => s.parse().ok(),
+		_ => None,
+	}
+}
+
+/// A datetime value, including RFC 3339 datetimes returned as strings.
+fn datetime(value: &BenchValue) -> Option<DateTime<Utc>> {
+	match value {
+		BenchValue::DateTime(dt) => Some(*dt),
+		BenchValue::String(s) => {
+			DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.with_timezone(&Utc))
+		}
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn representations_of_the_same_value_match() {
+		let expected = BenchValue::Object(vec![
+			("n".into(), BenchValue::Int(42)),
+			("f".into(), BenchValue::Float(0.5)),
+			("d".into(), BenchValue::Decimal("12.3400".parse().unwrap())),
+			("u".into(), BenchValue::Uuid(uuid::Uuid::nil())),
+			("b".into(), BenchValue::Bytes(vec![1, 2])),
+			("tags".into(), BenchValue::Array(vec![BenchValue::String("a".into())])),
+		]);
+		let actual = BenchValue::from(json!({
+			"id": 7,
+			"n": 42.0,
+			"f": 0.5,
+			"d": "12.34",
+			"u": "00000000-0000-0000-0000-000000000000",
+			"b": [1, 2],
+			"tags": ["a"],
+		}));
+		assert_eq!(mismatch(&expected, &actual), None);
+	}
+
+	#[test]
+	fn differences_name_the_field() {
+		let expected = BenchValue::from(json!({ "geo": { "code": "GBR" }, "n": 1 }));
+		let changed = BenchValue::from(json!({ "geo": { "code": "USA" }, "n": 1 }));
+		assert_eq!(
+			mismatch(&expected, &changed).as_deref(),
+			Some(r#"`geo.code` holds "USA" instead of "GBR""#)
+		);
+		let missing = BenchValue::from(json!({ "geo": { "code": "GBR" } }));
+		assert_eq!(mismatch(&expected, &missing).as_deref(), Some("`n` is missing"));
+		assert!(mismatch(&expected, &BenchValue::Null).is_some());
+	}
+}
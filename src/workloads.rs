@@ -2,8 +2,8 @@
 //! swap indexed column values between two rows so multiset statistics for equality predicates stay
 //! stable while indexes are invalidated.
 
-use crate::benchmark::NOT_SUPPORTED_ERROR;
 use crate::engine::{BenchmarkClient, ScanContext};
+use crate::error::BenchError;
 use crate::keyprovider::{IntegerKeyProvider, KeyProvider, StringKeyProvider};
 use crate::value::BenchValue;
 use crate::{Scan, ScanWithWrites, ScanWritesOperation};
@@ -65,6 +65,16 @@ pub(crate) async fn run_scan_with_writes<C: BenchmarkClient>(
 			let kb = p.key(sample.wrapping_mul(31).wrapping_add(samples / 2) % samples);
 			compensating_swap_string(client, ka, kb, scan).await
 		}
+		KeyProvider::OrderedUuid(p) => {
+			let ka = p.key(sample.wrapping_mul(17) % samples);
+			let kb = p.key(sample.wrapping_mul(31).wrapping_add(samples / 2) % samples);
+			compensating_swap_string(client, ka, kb, scan).await
+		}
+		KeyProvider::UnorderedUuid(p) => {
+			let ka = p.key(sample.wrapping_mul(17) % samples);
+			let kb = p.key(sample.wrapping_mul(31).wrapping_add(samples / 2) % samples);
+			compensating_swap_string(client, ka, kb, scan).await
+		}
 	}
 }
 
@@ -95,7 +105,7 @@ async fn compensating_swap_u32<C: BenchmarkClient>(
 	let va_res = client.read_u32(ka).await;
 	let vb_res = client.read_u32(kb).await;
 	let (mut va, mut vb): (BenchValue, BenchValue) = match (va_res, vb_res) {
-		(Err(ea), _) | (_, Err(ea)) if ea.to_string().contains(NOT_SUPPORTED_ERROR) => {
+		(Err(ea), _) | (_, Err(ea)) if BenchError::is_unsupported(&ea) => {
 			return Ok(());
 		}
 		(Err(e), _) => return Err(e),
@@ -128,7 +138,7 @@ async fn compensating_swap_string<C: BenchmarkClient>(
 	let va_res = client.read_string(ka.clone()).await;
 	let vb_res = client.read_string(kb.clone()).await;
 	let (mut va, mut vb): (BenchValue, BenchValue) = match (va_res, vb_res) {
-		(Err(ea), _) | (_, Err(ea)) if ea.to_string().contains(NOT_SUPPORTED_ERROR) => {
+		(Err(ea), _) | (_, Err(ea)) if BenchError::is_unsupported(&ea) => {
 			return Ok(());
 		}
 		(Err(e), _) => return Err(e),
@@ -0,0 +1,82 @@
+#![cfg(feature = "yugabytedb")]
+
+use crate::docker::DockerParams;
+use crate::engine::BenchmarkEngine;
+use crate::memory::Config;
+use crate::postgres::{PostgresClient, PostgresClientProvider};
+use crate::valueprovider::Columns;
+use crate::{Benchmark, KeyType};
+use anyhow::Result;
+
+pub const DEFAULT: &str =
+	"host=127.0.0.1 port=5433 user=yugabyte password=yugabyte dbname=yugabyte";
+
+/// Calculate YugabyteDB specific memory allocation
+fn calculate_yugabytedb_memory() -> u64 {
+	// Load the system memory
+	let memory = Config::new();
+	// Use ~50% of recommended cache allocation for the
+	// DocDB block cache, matching the Postgres buffers
+	(memory.cache_gb / 2).max(1) * 1024 * 1024 * 1024
+}
+
+pub(crate) fn docker(options: &Benchmark) -> DockerParams {
+	// The WAL is only fsynced on every write with `durable_wal_write`,
+	// otherwise writes are flushed to disk in the background
+	let mut tserver_flags = vec![
+		"ysql_max_connections=1024".to_string(),
+		format!("durable_wal_write={}", options.sync),
+	];
+	let mut master_flags = vec![];
+	if options.optimised {
+		// Size the block cache explicitly, store each row as a single
+		// packed value, and keep tablets from splitting mid-benchmark
+		let block_cache_bytes = calculate_yugabytedb_memory();
+		tserver_flags.push(format!("db_block_cache_size_bytes={block_cache_bytes}"));
+		tserver_flags.push("ysql_enable_packed_row=true".to_string());
+		master_flags.push("enable_automatic_tablet_splitting=false".to_string());
+	}
+	// Gflags are passed to yugabyted as comma-separated lists
+	let master_flags = match master_flags.is_empty() {
+		true => String::new(),
+		false => format!("--master_flags={}", master_flags.join(",")),
+	};
+	// Return Docker parameters
+	DockerParams {
+		image: "yugabytedb/yugabyte",
+		pre_args: "--ulimit nofile=1048576:1048576 -p 127.0.0.1:5433:5433".to_string(),
+		post_args: format!(
+			"bin/yugabyted start --background=false --tserver_flags={} {master_flags}",
+			tserver_flags.join(",")
+		),
+	}
+}
+
+/// YugabyteDB speaks the Postgres wire protocol (YSQL), so the
+/// Postgres client is reused, connecting to the YSQL endpoint.
+pub(crate) struct YugabyteDBClientProvider(PostgresClientProvider);
+
+impl BenchmarkEngine<PostgresClient> for YugabyteDBClientProvider {
+	/// Initiates a new datastore benchmarking engine
+	async fn setup(kt: KeyType, columns: Columns, options: &Benchmark) -> Result<Self> {
+		// Get the custom endpoint if specified
+		let url = options.endpoint.as_deref().unwrap_or(DEFAULT).to_owned();
+		// Create the client provider
+		Ok(Self(
+			PostgresClientProvider::new(kt, columns, url, options.prepared)
+				.with_security(options.security.clone()),
+		))
+	}
+	/// Checks YSQL runs queries, once the master and tablet server have both started
+	async fn ready(&self) -> Result<()> {
+		self.0.ready().await
+	}
+	/// Creates a new client for this benchmarking engine
+	async fn create_client(&self) -> Result<PostgresClient> {
+		self.0.create_client().await
+	}
+	/// Creates a client connected as the restricted role
+	async fn create_restricted_client(&self) -> Result<PostgresClient> {
+		self.0.create_restricted_client().await
+	}
+}